@@ -396,13 +396,13 @@ pub struct HllState {
 }
 
 impl HllState {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             registers: vec![0u8; HLL_REGISTERS],
         }
     }
 
-    fn add_hash(&mut self, h: u64) {
+    pub(crate) fn add_hash(&mut self, h: u64) {
         let idx = (h as usize) & (HLL_REGISTERS - 1);
         // Right-shift the bucket-index bits out so the LZ count is over the
         // remaining (64 - p)-bit substream. Because the top `p` bits of the
@@ -418,7 +418,7 @@ impl HllState {
         }
     }
 
-    fn merge(&mut self, other: &Self) {
+    pub(crate) fn merge(&mut self, other: &Self) {
         for (a, b) in self.registers.iter_mut().zip(other.registers.iter()) {
             if *b > *a {
                 *a = *b;
@@ -719,7 +719,10 @@ fn record_value_for_field(record: &Value, field: &str) -> Option<Value> {
     }
 }
 
-fn record_value_for_field_ref<'a>(record: &'a Value, field: &str) -> Option<&'a Value> {
+pub(crate) fn record_value_for_field_ref<'a>(
+    record: &'a Value,
+    field: &str,
+) -> Option<&'a Value> {
     if field.starts_with('/') {
         record.pointer(field)
     } else {
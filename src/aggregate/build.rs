@@ -50,9 +50,10 @@ where
     A: Aggregator,
     F: Fn() -> A + Send + Sync,
 {
+    let weighted_inputs = file_sizes(inputs);
     let pb_build = if progress {
-        Some(make_count_progress(
-            inputs.len() as u64,
+        Some(make_weighted_count_progress(
+            &weighted_inputs,
             "Aggregate: build shards",
         ))
     } else {
@@ -62,7 +63,8 @@ where
     let outcomes: Vec<ShardBuildResult> = inputs
         .par_iter()
         .zip(shard_paths.par_iter())
-        .map(|(input, out_shard)| {
+        .zip(weighted_inputs.par_iter())
+        .map(|((input, out_shard), (_, input_size))| {
             let mut agg = make_agg();
             let mut line_no = 0_u64;
             let mut ingested = 0_u64;
@@ -157,7 +159,7 @@ where
             };
 
             if let Some(pb) = &pb_build {
-                pb.inc(1);
+                pb.inc(*input_size);
             }
             outcome
         })
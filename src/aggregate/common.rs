@@ -0,0 +1,208 @@
+//! Ready-made [`Aggregator`]s for common single-field metrics.
+//!
+//! Each one is configured with a field — a bare top-level key (`score`) or a
+//! JSON pointer starting with `/` (`/media/oembed/provider_name`), as in
+//! [`ExprAggregator`](crate::ExprAggregator) — so run them through
+//! [`RedditETL::aggregate_jsonls_parallel_collect_with`](crate::RedditETL::aggregate_jsonls_parallel_collect_with)
+//! with a constructor closure:
+//!
+//! ```ignore
+//! let (by_sub, _report) = etl.aggregate_jsonls_parallel_collect_with(
+//!     parts,
+//!     shards_dir,
+//!     || CountByField::new("subreddit"),
+//! )?;
+//! ```
+//!
+//! The `Default` instance has no field. It is the merge identity: it ingests
+//! nothing and adopts its peer's field on merge, the same convention
+//! `ExprAggregator` uses. It is not a valid shard state, though, so
+//! `aggregate_jsonls_parallel::<CountByField>` (which builds shards via
+//! `Default`) fails up front instead of publishing an empty result. Records
+//! without the field (or with `null`) are skipped and tallied in
+//! `records_missing`.
+
+use super::Aggregator;
+use crate::agg_expr::record_value_for_field_ref;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Shard states must name a field: the fieldless `Default` would silently
+/// ingest nothing.
+fn require_field(aggregator: &str, field: &str) -> Result<()> {
+    if field.is_empty() {
+        anyhow::bail!(
+            "{aggregator} has no field; build shard states with aggregate_jsonls_parallel_collect_with(.., || {aggregator}::new(\"<field>\")) instead of Default"
+        );
+    }
+    Ok(())
+}
+
+/// The field's value in `record` as a number, accepting numeric strings
+/// (`"42"`) the way dump exports sometimes encode scores.
+fn numeric_field(record: &Value, field: &str) -> Option<f64> {
+    match record_value_for_field_ref(record, field)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Sum of a numeric field over every record that has one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SumField {
+    pub field: String,
+    pub sum: f64,
+    /// Records whose field was numeric and added to `sum`.
+    pub records: u64,
+    /// Records without a numeric value for the field.
+    pub records_missing: u64,
+}
+
+impl SumField {
+    pub fn new(field: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Aggregator for SumField {
+    fn check_shard_state(&self) -> Result<()> {
+        require_field("SumField", &self.field)
+    }
+
+    fn ingest(&mut self, record: &Value) {
+        if self.field.is_empty() {
+            return;
+        }
+        match numeric_field(record, &self.field) {
+            Some(x) => {
+                self.sum += x;
+                self.records += 1;
+            }
+            None => self.records_missing += 1,
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        if self.field.is_empty() {
+            self.field = other.field;
+        }
+        self.sum += other.sum;
+        self.records += other.records;
+        self.records_missing += other.records_missing;
+    }
+}
+
+/// Histogram of a field's values: `value -> records`. Strings count as-is
+/// (so `subreddit` keys keep their source casing); numbers and booleans count
+/// under their JSON text.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountByField {
+    pub field: String,
+    pub counts: BTreeMap<String, u64>,
+    /// Records without the field, or with `null` there.
+    pub records_missing: u64,
+}
+
+impl CountByField {
+    pub fn new(field: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Aggregator for CountByField {
+    fn check_shard_state(&self) -> Result<()> {
+        require_field("CountByField", &self.field)
+    }
+
+    fn ingest(&mut self, record: &Value) {
+        if self.field.is_empty() {
+            return;
+        }
+        let key = match record_value_for_field_ref(record, &self.field) {
+            None | Some(Value::Null) => {
+                self.records_missing += 1;
+                return;
+            }
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        };
+        *self.counts.entry(key).or_insert(0) += 1;
+    }
+
+    fn merge(&mut self, other: Self) {
+        if self.field.is_empty() {
+            self.field = other.field;
+        }
+        for (key, n) in other.counts {
+            *self.counts.entry(key).or_insert(0) += n;
+        }
+        self.records_missing += other.records_missing;
+    }
+}
+
+/// Minimum and maximum of a numeric field. Both stay `None` until a record
+/// with a numeric value arrives.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinMaxField {
+    pub field: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Records whose field was numeric.
+    pub records: u64,
+    /// Records without a numeric value for the field.
+    pub records_missing: u64,
+}
+
+impl MinMaxField {
+    pub fn new(field: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Aggregator for MinMaxField {
+    fn check_shard_state(&self) -> Result<()> {
+        require_field("MinMaxField", &self.field)
+    }
+
+    fn ingest(&mut self, record: &Value) {
+        if self.field.is_empty() {
+            return;
+        }
+        match numeric_field(record, &self.field) {
+            Some(x) => {
+                self.min = Some(self.min.map_or(x, |m| m.min(x)));
+                self.max = Some(self.max.map_or(x, |m| m.max(x)));
+                self.records += 1;
+            }
+            None => self.records_missing += 1,
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        if self.field.is_empty() {
+            self.field = other.field;
+        }
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.records += other.records;
+        self.records_missing += other.records_missing;
+    }
+}
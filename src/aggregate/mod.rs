@@ -1,5 +1,8 @@
 //! Generic parallel aggregation support over JSONL inputs with progress.
-//! Implement `Aggregator` for your aggregation state and call `aggregate_jsonls_parallel`.
+//! Implement `Aggregator` for your aggregation state and call `aggregate_jsonls_parallel`,
+//! or start from the single-field aggregators in [`common`].
+
+pub mod common;
 
 include!("types.rs");
 include!("paths.rs");
@@ -23,28 +23,29 @@ impl RedditETL {
         shards_dir: &Path,
         final_out: &Path,
         pretty: bool,
-    ) -> Result<AggregateBuildReport> {
+    ) -> Result<AggregateBuildReport, RetlError> {
+        let _run_settings = self.opts.enter_run_settings();
         let manifest_start = RunManifestStart::now();
         let input_count = inputs.len();
         let manifest_inputs = inputs.clone();
         let (total, report) = self.aggregate_jsonls_parallel_collect::<A>(inputs, shards_dir)?;
         if input_count > 0 && report.merged_shards == 0 && report.problem_count() > 0 {
-            anyhow::bail!(
+            return Err(anyhow::anyhow!(
                 "aggregate failed: {} of {} input(s) failed or were partial; 0 shard(s) merged",
                 report.problem_count(),
                 input_count
-            );
+            ).into());
         }
         // Strict mode: any fatal input fails the whole run before publishing,
         // so a mistyped input path in a 12-file batch can't silently produce a
         // successful 11/12 result. Each fatal input was already named via
         // `tracing::warn!` during shard build.
         if self.opts.aggregate_strict && report.fatal_count() > 0 {
-            anyhow::bail!(
+            return Err(anyhow::anyhow!(
                 "aggregate strict mode: {} of {} input(s) failed during shard build; no output published (clear --strict / aggregate_strict to merge the surviving shards)",
                 report.fatal_count(),
                 input_count
-            );
+            ).into());
         }
 
         // Atomically publish the final output through `<out-parent>/_staging`
@@ -103,7 +104,7 @@ impl RedditETL {
         &self,
         inputs: Vec<PathBuf>,
         shards_dir: &Path,
-    ) -> Result<(A, AggregateBuildReport)> {
+    ) -> Result<(A, AggregateBuildReport), RetlError> {
         self.aggregate_jsonls_parallel_collect_with::<A, _>(inputs, shards_dir, A::default)
     }
 
@@ -118,7 +119,7 @@ impl RedditETL {
         inputs: Vec<PathBuf>,
         shards_dir: &Path,
         make_agg: F,
-    ) -> Result<(A, AggregateBuildReport)>
+    ) -> Result<(A, AggregateBuildReport), RetlError>
     where
         A: Aggregator,
         F: Fn() -> A + Send + Sync,
@@ -139,11 +140,13 @@ impl RedditETL {
         shards_dir: &Path,
         make_agg: F,
         partial_policy: AggregatePartialReadPolicy,
-    ) -> Result<(A, AggregateBuildReport)>
+    ) -> Result<(A, AggregateBuildReport), RetlError>
     where
         A: Aggregator,
         F: Fn() -> A + Send + Sync,
     {
+        let _run_settings = self.opts.enter_run_settings();
+        make_agg().check_shard_state()?;
         crate::util::create_dir_all_with_default_backoff(shards_dir)
             .with_context(|| format!("creating shards_dir {}", shards_dir.display()))?;
         let run_token = aggregate_run_token();
@@ -1,14 +1,16 @@
 use crate::atomic_write::{ensure_staging_dir, write_at_path_atomic, write_jsonl_atomic};
+use crate::error::RetlError;
 use crate::ndjson::for_each_jsonl_line_cfg;
 use crate::pipeline::RedditETL;
-use crate::progress::make_count_progress;
+use crate::progress::{
+    file_sizes, make_count_progress, make_weighted_count_progress, ProgressBar,
+};
 use crate::run_manifest::{
     discover_upstream_manifests_from_inputs, file_identities, maybe_write_run_manifest,
     ManifestDestination, RunManifestInput, RunManifestStart,
 };
 use crate::util::with_thread_pool;
 use anyhow::{Context, Result};
-use indicatif::ProgressBar;
 use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -39,6 +41,15 @@ static AGGREGATE_RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
 pub trait Aggregator: Send + Default + Serialize + DeserializeOwned {
     fn ingest(&mut self, record: &Value);
     fn merge(&mut self, other: Self);
+
+    /// Reject a per-input shard state that cannot ingest anything, such as a
+    /// field-configured aggregator built via `Default` instead of its
+    /// constructor. Checked once on a fresh `make_agg()` state before any
+    /// input is read, so a misconfigured run fails instead of publishing an
+    /// empty result. The default accepts every state.
+    fn check_shard_state(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Per-input aggregate issue returned to library callers and printed by the
@@ -7,6 +7,7 @@ use zstd::stream::write::Encoder as ZstdEncoder;
 
 use super::{unique_inprogress_path, STAGING_DIR_NAME};
 use crate::util::replace_file_atomic_backoff;
+use crate::zstd_jsonl::ZstdDictionary;
 
 #[cfg(test)]
 use super::testing::notify_stage_path_for_tests;
@@ -204,10 +205,15 @@ where
 /// atomic rename is skipped, so a zero-record partition never appears at the
 /// published path. Pass `|_| true` to always publish. Returns the body's
 /// result either way.
+///
+/// With `dictionary` set the frame is compressed against that trained
+/// dictionary and records its id; readers need the same dictionary installed
+/// (see [`set_zstd_dictionary`](crate::set_zstd_dictionary)) to decode it.
 pub fn write_zst_atomic_if<T, F, P>(
     staging_dir: &Path,
     final_dest: &Path,
     level: i32,
+    dictionary: Option<&ZstdDictionary>,
     write_buf_bytes: usize,
     should_publish: P,
     body: F,
@@ -225,8 +231,16 @@ where
             // Build the encoder over a mutable reference to the staged
             // BufWriter so we can finish() it before stage_and_execute
             // flushes/renames.
-            let mut enc =
-                ZstdEncoder::new(writer.by_ref(), level).context("zstd encoder init")?;
+            let mut enc = match dictionary {
+                Some(dict) => ZstdEncoder::with_dictionary(writer.by_ref(), level, dict.as_bytes())
+                    .with_context(|| {
+                        format!(
+                            "zstd encoder init with dictionary {}",
+                            dict.path().display()
+                        )
+                    })?,
+                None => ZstdEncoder::new(writer.by_ref(), level).context("zstd encoder init")?,
+            };
             enc.include_checksum(true)
                 .context("enable zstd content checksum")?;
 
@@ -238,6 +252,60 @@ where
     )
 }
 
+/// Atomically write a `.bz2` file, publishing the staged file onto
+/// `final_dest` only when `should_publish` returns `true` for the body's
+/// result. Mirrors [`write_zst_atomic_if`]: the bzip2 stream is explicitly
+/// finished before the atomic rename so a published file always carries its
+/// end-of-stream marker, and an unpublished one is discarded in staging.
+///
+/// **`bzip2` feature required.** Without it this returns a build-flag error.
+pub fn write_bz2_atomic_if<T, F, P>(
+    staging_dir: &Path,
+    final_dest: &Path,
+    level: u32,
+    write_buf_bytes: usize,
+    should_publish: P,
+    body: F,
+) -> Result<T>
+where
+    F: FnOnce(&mut dyn Write) -> Result<T>,
+    P: FnOnce(&T) -> bool,
+{
+    #[cfg(feature = "bzip2")]
+    {
+        stage_and_execute(
+            staging_dir,
+            final_dest,
+            write_buf_bytes,
+            should_publish,
+            |writer| {
+                let mut enc = bzip2::write::BzEncoder::new(
+                    writer.by_ref(),
+                    bzip2::Compression::new(level),
+                );
+                let result = body(&mut enc)?;
+                enc.finish().context("finish bzip2 stream")?;
+                Ok(result)
+            },
+        )
+    }
+    #[cfg(not(feature = "bzip2"))]
+    {
+        let _ = (
+            staging_dir,
+            final_dest,
+            level,
+            write_buf_bytes,
+            should_publish,
+            body,
+        );
+        Err(anyhow::anyhow!(
+            "retl: bzip2 output was requested but this binary was built without the `bzip2` cargo feature. \
+             Rebuild with `cargo build --release --features bzip2`."
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,9 +366,16 @@ mod tests {
 
         // The encoder still closes a real (empty-payload) frame on the staged
         // file; declining to publish must discard it rather than rename it on.
-        let written =
-            write_zst_atomic_if(&staging, &dest, 3, 64 * 1024, |&n: &u64| n > 0, |_w| Ok(0u64))
-                .unwrap();
+        let written = write_zst_atomic_if(
+            &staging,
+            &dest,
+            3,
+            None,
+            64 * 1024,
+            |&n: &u64| n > 0,
+            |_w| Ok(0u64),
+        )
+        .unwrap();
 
         assert_eq!(written, 0);
         assert!(
@@ -124,6 +124,11 @@ pub(crate) struct QueryOpts {
     #[arg(long = "ids-file", value_name = "PATH")]
     pub(crate) ids_files: Vec<PathBuf>,
 
+    /// Subreddit deny-list entry (repeatable). Normalized like `--subreddit`. Applies with or
+    /// without `--subreddit`; a subreddit given to both is excluded. Blank values are rejected.
+    #[arg(long = "exclude-subreddit", value_name = "NAME")]
+    pub(crate) exclude_subreddits: Vec<String>,
+
     /// Author allow-list entry (repeatable). `--author-in` is an alias. Match is
     /// case-insensitive (record author lowercased before comparison). Blank values rejected.
     #[arg(long = "author", visible_alias = "author-in", value_name = "NAME")]
@@ -210,12 +215,13 @@ pub(crate) struct QueryOpts {
     /// are AND-joined. Operators (LHS is the pointer, RHS is a value/regex):
     ///
     ///   exists:/path        — true iff `/path` resolves to any value (including null).
+    ///   missing:/path       — true iff `/path` does not resolve (complement of exists:).
     ///   /path=value         — true iff `/path` equals `value`. Missing field → false.
     ///   /path!=value        — true iff `/path` resolves AND is unequal. Missing → false.
     ///   /path>N, >=N, <N, <=N — numeric, on finite numbers only. Missing → false.
     ///   /path~=REGEX        — regex match. Pointer must be a string. Missing → false.
     ///
-    /// IMPORTANT: every operator except `exists:` evaluates to false when the field is
+    /// IMPORTANT: every operator except `exists:` / `missing:` evaluates to false when the field is
     /// absent — records lacking the field are dropped, not included. To keep records
     /// that may or may not have the field, gate the predicate with `exists:` first
     /// or move the check to `--source rs` only. Values use JSON scalars when possible
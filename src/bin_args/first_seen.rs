@@ -21,4 +21,8 @@ pub(crate) struct FirstSeenArgs {
     /// `--work-dir` when the query/config/corpus fingerprint still matches.
     #[arg(long)]
     pub(crate) resume: bool,
+    /// Also write the subreddit and id of each author's earliest record:
+    /// `<author>\t<earliest_created_utc>\t<subreddit>\t<id>`.
+    #[arg(long)]
+    pub(crate) detailed: bool,
 }
@@ -86,6 +86,7 @@ fn cli_corpus_snapshot(common: &CommonOpts) -> Result<CorpusSnapshot> {
 fn cli_query_value(common: &CommonOpts, query: &QueryOpts) -> Value {
     serde_json::json!({
         "subreddits": normalize_cli_values(&common.subreddits, true),
+        "subreddits_out": normalize_cli_values(&query.exclude_subreddits, true),
         "ids": &query.ids,
         "ids_files": query
             .ids_files
@@ -40,7 +40,7 @@ pub(crate) fn run_dedupe_to(args: DedupeArgs, w: &mut dyn Write) -> Result<()> {
             Ok(stats) => stats,
             Err(e) => {
                 let _ = retl::remove_with_short_backoff(&tmp_path);
-                return Err(e);
+                return Err(e.into());
             }
         };
         let copy_result = (|| -> Result<()> {
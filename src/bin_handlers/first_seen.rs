@@ -16,14 +16,20 @@ pub(crate) fn run_first_seen(args: FirstSeenArgs) -> Result<()> {
     if let Some(limit) = args.limit {
         scan = scan.limit(limit);
     }
+    let detailed = args.detailed;
+    let build = move |p: &Path| {
+        if detailed {
+            scan.build_first_seen_detailed_to_tsv(p)
+        } else {
+            scan.build_first_seen_index_to_tsv(p)
+        }
+    };
     // `--out -` streams the TSV to stdout, matching `count --mode author`.
     if to_stdout {
         let work_dir = args.common.work_dir.clone();
-        stream_path_output_to_stdout(&work_dir, "first_seen", "first_seen.tsv", |p| {
-            scan.build_first_seen_index_to_tsv(p)
-        })?;
+        stream_path_output_to_stdout(&work_dir, "first_seen", "first_seen.tsv", build)?;
     } else {
-        scan.build_first_seen_index_to_tsv(&args.out)?;
+        build(args.out.as_path())?;
     }
     emit_partial_read_report(&partial_reporter)?;
     Ok(())
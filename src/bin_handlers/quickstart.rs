@@ -67,10 +67,10 @@ fn prepare_quickstart_sample_corpus(out_dir: &Path) -> Result<PathBuf> {
         .filter(|line| !line.trim().is_empty())
     {
         let value: Value = serde_json::from_str(line).context("parse sample JSONL line")?;
-        if value.get("body").is_some() {
-            comments.push(line.to_owned());
-        } else if value.get("title").is_some() {
-            submissions.push(line.to_owned());
+        match retl::record_kind(&value) {
+            Some(retl::FileKind::Comment) => comments.push(line.to_owned()),
+            Some(retl::FileKind::Submission) => submissions.push(line.to_owned()),
+            None => {}
         }
     }
 
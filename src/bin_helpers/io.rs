@@ -1,12 +1,13 @@
 
 /// Run an operation that writes to a file path, then stream the resulting
 /// file to stdout and remove it. Used to honor `--out -` for APIs that only
-/// know how to write to a `Path`.
-pub(crate) fn stream_path_output_to_stdout(
+/// know how to write to a `Path`. `write_output` may return a library
+/// [`retl::RetlError`] or a plain `anyhow::Error`.
+pub(crate) fn stream_path_output_to_stdout<E: Into<anyhow::Error>>(
     work_dir: &Path,
     temp_prefix: &str,
     file_stem: &str,
-    write_output: impl FnOnce(&Path) -> Result<()>,
+    write_output: impl FnOnce(&Path) -> std::result::Result<(), E>,
 ) -> Result<()> {
     let lib_tmp = work_dir.join("lib_tmp");
     retl::create_dir_all_with_default_backoff(&lib_tmp)
@@ -23,7 +24,7 @@ pub(crate) fn stream_path_output_to_stdout(
 
     if let Err(e) = result {
         let _ = retl::remove_with_short_backoff(&tmp_path);
-        return Err(e);
+        return Err(e.into());
     }
 
     let copy_result = (|| -> Result<()> {
@@ -46,7 +47,7 @@ pub(crate) fn stream_path_output_to_stdout(
 pub(crate) fn stream_extract_to_stdout(
     work_dir: &Path,
     file_stem: &str,
-    extract: impl FnOnce(&Path) -> Result<()>,
+    extract: impl FnOnce(&Path) -> std::result::Result<(), retl::RetlError>,
 ) -> Result<()> {
     stream_path_output_to_stdout(work_dir, "export", file_stem, extract)
 }
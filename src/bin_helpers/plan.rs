@@ -17,6 +17,9 @@ macro_rules! plan {
             }
             scan = scan.ids_in(id_selectors.iter().map(String::as_str));
         }
+        if !query.exclude_subreddits.is_empty() {
+            scan = scan.subreddits_out(query.exclude_subreddits.iter().map(String::as_str));
+        }
         if !query.authors.is_empty() {
             scan = scan.authors_in(query.authors.iter().map(String::as_str));
         }
@@ -9,6 +9,10 @@ pub(crate) fn parse_json_predicate(raw: &str) -> Result<JsonPointerPredicate> {
         validate_cli_json_pointer(pointer, raw)?;
         return Ok(JsonPointerPredicate::exists(pointer.to_string()));
     }
+    if let Some(pointer) = raw.strip_prefix("missing:") {
+        validate_cli_json_pointer(pointer, raw)?;
+        return Ok(JsonPointerPredicate::missing(pointer.to_string()));
+    }
 
     let (pointer, op, value) = split_json_predicate(raw)?;
     validate_cli_json_pointer(pointer, raw)?;
@@ -89,7 +93,7 @@ fn split_json_predicate(raw: &str) -> Result<(&str, &'static str, &str)> {
     }
 
     anyhow::bail!(
-        "bad --json predicate {raw:?}: missing or unsupported operator; expected exists:/path, /path=value, /path!=value, /path>value, /path>=value, /path<value, /path<=value, or /path~=REGEX"
+        "bad --json predicate {raw:?}: missing or unsupported operator; expected exists:/path, missing:/path, /path=value, /path!=value, /path>value, /path>=value, /path<value, /path<=value, or /path~=REGEX"
     )
 }
 
@@ -13,7 +13,7 @@ use std::sync::{
     Arc,
 };
 
-use crate::config::clamp_shard_count;
+use crate::config::{clamp_shard_count, RunSettings};
 use crate::dedupe::BYTES_PER_MB;
 use crate::key_extractor::KeyExtractor;
 use crate::mem::{available_memory_fraction, is_low_memory};
@@ -332,8 +332,10 @@ where
     let chan_cap = cfg.inflight_groups.max(1);
     let (tx, rx) = crossbeam_channel::bounded::<(String, Vec<String>)>(chan_cap);
 
+    let run_settings = RunSettings::current();
     std::thread::scope(|s| -> Result<()> {
         let consumer_handle = s.spawn(move || -> Result<()> {
+            let _run_settings = RunSettings::enter(run_settings);
             while let Ok((k, v)) = rx.recv() {
                 on_group(&k, v)?;
             }
@@ -1,8 +1,88 @@
 //! Concurrency helper: limit the number of monthly files processed in parallel.
+//!
+//! ## File descriptors
+//!
+//! Every in-flight monthly file holds a few descriptors (the `.zst` input, a
+//! staged output, a checkpoint or spool part), on top of whatever the
+//! operation opened up front — a sharded writer keeps all `shard_count`
+//! shard files open for the whole scan, so the default 256 shards alone use a
+//! quarter of a typical 1024 soft limit. [`for_each_file_limited`] therefore
+//! caps `file_concurrency` so that the descriptors already open plus
+//! [`FDS_PER_FILE_JOB`] per in-flight file stay within the open-file budget
+//! (see [`set_open_file_budget`]; on Linux it defaults to the soft
+//! `RLIMIT_NOFILE`). Anything that still hits `EMFILE`/`ENFILE` is retried by
+//! the `*_with_backoff` helpers while other files finish. If a run still
+//! fails, lower `shard_count` or raise `ulimit -n`.
 
 use anyhow::Result;
 use parking_lot::{Condvar, Mutex};
 use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// Descriptors budgeted per in-flight monthly file: the input, the staged
+/// output, and room for a checkpoint/spool part plus one transient open.
+pub const FDS_PER_FILE_JOB: usize = 4;
+
+/// Cap set by [`set_open_file_budget`]; `0` means "detect".
+static OPEN_FILE_BUDGET: AtomicUsize = AtomicUsize::new(0);
+
+/// Cap the number of files `for_each_file_limited` lets operations hold open
+/// at once. `None` restores detection from the soft `RLIMIT_NOFILE` (Linux
+/// only; elsewhere no cap applies). Process-wide; takes effect on the next
+/// `for_each_file_limited` call. A `Some(0)` budget is treated as one.
+pub fn set_open_file_budget(budget: Option<usize>) {
+    OPEN_FILE_BUDGET.store(budget.map_or(0, |b| b.max(1)), Ordering::Relaxed);
+}
+
+/// The open-file budget in effect: the value from [`set_open_file_budget`],
+/// else the detected soft `RLIMIT_NOFILE`, else `None`.
+pub fn open_file_budget() -> Option<usize> {
+    match OPEN_FILE_BUDGET.load(Ordering::Relaxed) {
+        0 => detected_fd_limit(),
+        b => Some(b),
+    }
+}
+
+/// Soft `RLIMIT_NOFILE` from `/proc/self/limits`, read once.
+fn detected_fd_limit() -> Option<usize> {
+    static LIMIT: OnceLock<Option<usize>> = OnceLock::new();
+    *LIMIT.get_or_init(|| {
+        let limits = std::fs::read_to_string("/proc/self/limits").ok()?;
+        let line = limits.lines().find(|l| l.starts_with("Max open files"))?;
+        line["Max open files".len()..]
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    })
+}
+
+/// Descriptors this process holds right now (Linux only; `0` elsewhere).
+fn open_fd_count() -> usize {
+    std::fs::read_dir("/proc/self/fd").map_or(0, |dir| dir.count())
+}
+
+/// `limit` lowered so the files it lets run fit in the open-file budget next
+/// to the descriptors already open. Never below one.
+fn fd_limited_concurrency(limit: usize) -> usize {
+    let Some(budget) = open_file_budget() else {
+        return limit;
+    };
+    let headroom = budget.saturating_sub(open_fd_count());
+    let fits = (headroom / FDS_PER_FILE_JOB).max(1);
+    if fits < limit {
+        tracing::warn!(
+            file_concurrency = limit,
+            effective = fits,
+            open_file_budget = budget,
+            "lowering file concurrency to stay within the open-file budget; \
+             raise `ulimit -n` or lower shard_count to run more files at once"
+        );
+        return fits;
+    }
+    limit
+}
 
 /// Counting semaphore built on parking_lot primitives. Used to gate how many
 /// rayon workers may be inside `f` at once without forcing them to wait at
@@ -50,6 +130,9 @@ impl Drop for Permit<'_> {
 
 /// Limit parallelism across monthly files: at most `limit` decoders in flight.
 ///
+/// `limit` is further lowered to fit the open-file budget; see the
+/// [module docs](self) for how it relates to `shard_count` and `ulimit -n`.
+///
 /// Uses a rayon parallel iterator gated by a counting semaphore so workers
 /// pick up the next file as soon as a slot frees. The previous chunk-then-
 /// par_iter version had workers idle at every chunk boundary waiting for the
@@ -59,6 +142,7 @@ where
     T: Sync,
     F: Sync + Fn(&T) -> Result<()>,
 {
+    let limit = fd_limited_concurrency(limit);
     if limit <= 1 {
         for job in files {
             f(job)?;
@@ -71,3 +155,39 @@ where
         f(job)
     })
 }
+
+/// [`for_each_file_limited`] that starts files strictly in slice order and
+/// passes each file's index to `f`.
+///
+/// `f` may block until earlier files are consumed (as an ordered writer's
+/// reorder window does) without deadlocking: every earlier file was already
+/// claimed by a running worker. After the first error no further file is
+/// started.
+pub(crate) fn for_each_file_in_order<T, F>(files: &[T], limit: usize, f: F) -> Result<()>
+where
+    T: Sync,
+    F: Sync + Fn(usize, &T) -> Result<()>,
+{
+    let limit = fd_limited_concurrency(limit).min(files.len());
+    if limit <= 1 {
+        for (idx, job) in files.iter().enumerate() {
+            f(idx, job)?;
+        }
+        return Ok(());
+    }
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    (0..limit).into_par_iter().try_for_each(|_| -> Result<()> {
+        while !failed.load(Ordering::Relaxed) {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            let Some(job) = files.get(idx) else {
+                break;
+            };
+            if let Err(e) = f(idx, job) {
+                failed.store(true, Ordering::Relaxed);
+                return Err(e);
+            }
+        }
+        Ok(())
+    })
+}
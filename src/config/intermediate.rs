@@ -0,0 +1,18 @@
+/// On-disk encoding for scratch intermediates: dedupe run files and
+/// [`ShardedKVWriter`](crate::ShardedKVWriter) shards.
+///
+/// Only the scratch encoding changes; merge/reduce semantics and final
+/// outputs are identical either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntermediateFormat {
+    /// Newline-delimited text (`run_NNNN.ndjson`, `key\tvalue` shard lines).
+    /// Human-readable, and the historical default.
+    #[default]
+    Text,
+    /// Length-prefixed binary records with varint lengths. KV shards store
+    /// values as zigzag varints and skip empty payloads, so they are usually
+    /// smaller than `key\tvalue` text. Dedupe runs stay about the size of
+    /// text but store each key once per group, so the merge phase does not
+    /// re-parse JSON to find it.
+    Binary,
+}
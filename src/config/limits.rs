@@ -1,8 +1,12 @@
 use crate::date::YearMonth;
+use crate::integrity::CorruptAction;
 use crate::mem::AdaptiveMemCfg;
 use crate::parents::ParentPayloadSpec;
+use crate::paths::DuplicateMonthPolicy;
+use crate::zstd_jsonl::ZstdDictionary;
 use parking_lot::Mutex;
 use serde::Serialize;
+use std::cell::RefCell;
 use std::error::Error;
 use std::fmt;
 use std::path::{Path, PathBuf};
@@ -11,7 +15,15 @@ use std::sync::Arc;
 /// Structured error returned when ETL option builders contain invalid settings.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ConfigBuildError {
-    InvalidDateRange { start: YearMonth, end: YearMonth },
+    InvalidDateRange {
+        start: YearMonth,
+        end: YearMonth,
+    },
+    /// [`ETLOptions::with_zstd_dictionary`] could not load the dictionary.
+    InvalidZstdDictionary {
+        path: PathBuf,
+        message: String,
+    },
 }
 
 impl fmt::Display for ConfigBuildError {
@@ -20,6 +32,9 @@ impl fmt::Display for ConfigBuildError {
             ConfigBuildError::InvalidDateRange { start, end } => {
                 write!(f, "invalid date range: start {start} is after end {end}")
             }
+            ConfigBuildError::InvalidZstdDictionary { path, message } => {
+                write!(f, "invalid zstd dictionary {}: {message}", path.display())
+            }
         }
     }
 }
@@ -1,9 +1,13 @@
 include!("limits.rs");
 include!("sources.rs");
+include!("intermediate.rs");
 include!("partial_read.rs");
+include!("reject_sink.rs");
 include!("options/types.rs");
 include!("options/defaults.rs");
 include!("options/builders_core.rs");
 include!("options/builders_output.rs");
 include!("options/inflight.rs");
+include!("options/tuning.rs");
+include!("run_settings.rs");
 include!("tests.rs");
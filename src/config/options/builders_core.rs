@@ -7,6 +7,114 @@ impl ETLOptions {
         self
     }
 
+    /// Append another corpus root; see [`ETLOptions::extra_base_dirs`].
+    pub fn with_extra_base_dir(mut self, base_dir: impl AsRef<Path>) -> Self {
+        self.extra_base_dirs.push(base_dir.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn with_duplicate_month_policy(mut self, policy: DuplicateMonthPolicy) -> Self {
+        self.duplicate_months = policy;
+        self
+    }
+
+    /// `(comments_dir, submissions_dir)` for every corpus root, primary first.
+    pub(crate) fn corpus_roots(&self) -> Vec<(PathBuf, PathBuf)> {
+        std::iter::once((self.comments_dir.clone(), self.submissions_dir.clone()))
+            .chain(
+                self.extra_base_dirs
+                    .iter()
+                    .map(|root| (root.join("comments"), root.join("submissions"))),
+            )
+            .collect()
+    }
+
+    /// Process exactly `files`; see [`ETLOptions::files`].
+    pub fn with_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = Some(files);
+        self
+    }
+
+    /// Jobs for [`ETLOptions::files`], comments then submissions, each in
+    /// month order; `None` when the corpus roots should be discovered.
+    pub(crate) fn explicit_file_jobs(
+        &self,
+    ) -> Result<Option<Vec<crate::paths::FileJob>>, crate::paths::PlanningError> {
+        let Some(files) = &self.files else {
+            return Ok(None);
+        };
+        let discovered = crate::paths::discover_files_checked(files, self.duplicate_months)?;
+        let (start, end) = match self.last_n_months {
+            Some(_) => self.planned_range(&discovered, Sources::Both),
+            None => (None, None),
+        };
+        let mut jobs = crate::paths::plan_files(&discovered, Sources::Both, start, end);
+        self.retain_last_n_months(&mut jobs);
+        Ok(Some(jobs))
+    }
+
+    /// Stitch single-file outputs month by month (each month's RC part, then
+    /// its RS part) under `ordered_output` or `interleaved_sources`.
+    pub(crate) fn stitch_month_major(&self) -> bool {
+        self.ordered_output || self.interleaved_sources
+    }
+
+    /// `(start, end)` for planning over `discovered`: the configured range,
+    /// or with [`ETLOptions::last_n_months`] the newest months present for
+    /// `sources`. Holes in the corpus do not use up part of `n`.
+    ///
+    /// The window is counted per source; for [`Sources::Both`] this returns
+    /// the span covering both windows, and [`Self::retain_last_n_months`]
+    /// trims each source's planned files back to its own window.
+    pub(crate) fn planned_range(
+        &self,
+        discovered: &crate::paths::Discovered,
+        sources: Sources,
+    ) -> (Option<YearMonth>, Option<YearMonth>) {
+        let Some(n) = self.last_n_months else {
+            return (self.start, self.end);
+        };
+        let window = |months: &std::collections::BTreeMap<YearMonth, PathBuf>| {
+            let mut newest = months.keys().rev().take(n.max(1));
+            let end = *newest.next()?;
+            Some((newest.next_back().copied().unwrap_or(end), end))
+        };
+        let windows: Vec<(YearMonth, YearMonth)> = match sources {
+            Sources::Comments => window(&discovered.comments).into_iter().collect(),
+            Sources::Submissions => window(&discovered.submissions).into_iter().collect(),
+            Sources::Both => window(&discovered.comments)
+                .into_iter()
+                .chain(window(&discovered.submissions))
+                .collect(),
+        };
+        let start = windows.iter().map(|&(start, _)| start).min();
+        let end = windows.iter().map(|&(_, end)| end).max();
+        (start, end)
+    }
+
+    /// With [`ETLOptions::last_n_months`], drop planned files older than the
+    /// newest `n` months of their own source. Needed under
+    /// [`Sources::Both`], where [`Self::planned_range`] spans both windows
+    /// and a source whose dumps lag the other would otherwise pick up extra
+    /// months.
+    pub(crate) fn retain_last_n_months(&self, jobs: &mut Vec<crate::paths::FileJob>) {
+        let Some(n) = self.last_n_months else {
+            return;
+        };
+        let mut cutoffs = std::collections::HashMap::new();
+        for kind in [crate::paths::FileKind::Comment, crate::paths::FileKind::Submission] {
+            let months: std::collections::BTreeSet<YearMonth> = jobs
+                .iter()
+                .filter(|job| job.kind == kind)
+                .map(|job| job.ym)
+                .collect();
+            if let Some(&cutoff) = months.iter().rev().take(n.max(1)).next_back() {
+                cutoffs.insert(kind, cutoff);
+            }
+        }
+        jobs.retain(|job| cutoffs.get(&job.kind).is_none_or(|&cutoff| job.ym >= cutoff));
+    }
+
     #[deprecated(note = "use RedditETL::scan().subreddits([...]) instead")]
     pub fn with_subreddit(mut self, sub: impl AsRef<str>) -> Self {
         let mut s = sub.as_ref().trim().to_lowercase();
@@ -28,15 +136,34 @@ impl ETLOptions {
     pub fn with_date_range(mut self, start: Option<YearMonth>, end: Option<YearMonth>) -> Self {
         self.start = start;
         self.end = end;
-        self.build_error = match (start, end) {
+        self.last_n_months = None;
+        let range_error = match (start, end) {
             (Some(s), Some(e)) if s > e => {
                 Some(ConfigBuildError::InvalidDateRange { start: s, end: e })
             }
             _ => None,
         };
+        // Replace only a date-range error; other deferred errors (an
+        // unloadable zstd dictionary) must survive a later valid range.
+        let stale_range_error = matches!(
+            self.build_error,
+            Some(ConfigBuildError::InvalidDateRange { .. })
+        );
+        if range_error.is_some() || stale_range_error {
+            self.build_error = range_error;
+        }
         self
     }
 
+    /// Process the newest `n` months present; see
+    /// [`ETLOptions::last_n_months`]. Replaces any date range, and a later
+    /// [`with_date_range`](Self::with_date_range) replaces it in turn.
+    pub fn with_last_n_months(self, n: usize) -> Self {
+        let mut opts = self.with_date_range(None, None);
+        opts.last_n_months = Some(n);
+        opts
+    }
+
     /// Surface any deferred [`ConfigBuildError`] recorded by a builder setter.
     ///
     /// `with_date_range` stores an invalid (`start > end`) range as a deferred
@@ -53,11 +180,49 @@ impl ETLOptions {
         Ok(())
     }
 
+    /// Number of on-disk dedup shards. Also overrides the size-scaled shard
+    /// count `collect_parent_ids_from_jsonls` would otherwise pick.
     pub fn with_shard_count(mut self, shards: usize) -> Self {
-        self.shard_count = clamp_shard_count(shards, "ETLOptions::with_shard_count");
+        self.shard_count = Some(clamp_shard_count(shards, "ETLOptions::with_shard_count"));
+        self
+    }
+
+    /// [`ETLOptions::shard_count`], or [`MAX_SHARDS`] when unset. Clamped
+    /// again because the field can be assigned directly.
+    pub(crate) fn resolved_shard_count(&self) -> usize {
+        self.shard_count.map_or(MAX_SHARDS, |shards| {
+            clamp_shard_count(shards, "ETLOptions::shard_count")
+        })
+    }
+
+    /// See [`ETLOptions::shard_flush_bytes`].
+    pub fn with_shard_flush_bytes(mut self, bytes: u64) -> Self {
+        self.shard_flush_bytes = bytes;
         self
     }
 
+    /// See [`ETLOptions::in_memory_shards`].
+    pub fn with_in_memory_shards(mut self, on: bool) -> Self {
+        self.in_memory_shards = on;
+        self
+    }
+
+    /// See [`ETLOptions::in_memory_shard_spill_bytes`].
+    pub fn with_in_memory_shard_spill_bytes(mut self, bytes: u64) -> Self {
+        self.in_memory_shard_spill_bytes = bytes;
+        self
+    }
+
+    /// See [`ETLOptions::intermediate_format`].
+    pub fn with_intermediate_format(mut self, format: IntermediateFormat) -> Self {
+        self.intermediate_format = format;
+        self
+    }
+
+    /// Keep only these fields in exported records. Plain names select
+    /// top-level keys; entries starting with `/` are JSON pointers written
+    /// under their segments joined with `.` (`/media/oembed/title` ->
+    /// `media.oembed.title`), or under `<name>` with a trailing ` as <name>`.
     pub fn with_whitelist_fields<I, S>(mut self, fields: I) -> Self
     where
         I: IntoIterator<Item = S>,
@@ -85,6 +250,12 @@ impl ETLOptions {
         self
     }
 
+    /// See [`ETLOptions::whitelist_source_order`].
+    pub fn with_whitelist_source_order(mut self, yes: bool) -> Self {
+        self.whitelist_source_order = yes;
+        self
+    }
+
     pub fn with_strict_key(mut self, yes: bool) -> Self {
         self.strict_key = yes;
         self
@@ -111,11 +282,35 @@ impl ETLOptions {
         self
     }
 
+    /// See [`ETLOptions::ordered_output`].
+    pub fn with_ordered_output(mut self, yes: bool) -> Self {
+        self.ordered_output = yes;
+        self
+    }
+
+    /// See [`ETLOptions::interleaved_sources`].
+    pub fn with_interleaved_sources(mut self, yes: bool) -> Self {
+        self.interleaved_sources = yes;
+        self
+    }
+
     pub fn with_file_concurrency(mut self, n: usize) -> Self {
         self.file_concurrency = clamp_file_concurrency(n, "ETLOptions::with_file_concurrency");
         self
     }
 
+    /// See [`ETLOptions::integrity_file_timeout`].
+    pub fn with_integrity_file_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.integrity_file_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`ETLOptions::integrity_on_corrupt`].
+    pub fn with_integrity_on_corrupt(mut self, action: CorruptAction) -> Self {
+        self.integrity_on_corrupt = action;
+        self
+    }
+
     pub fn with_progress(mut self, yes: bool) -> Self {
         self.progress = yes;
         self
@@ -126,6 +321,12 @@ impl ETLOptions {
         self
     }
 
+    /// See [`ETLOptions::progress_stage`].
+    pub fn with_progress_stage(mut self, stage: crate::progress::ProgressStage) -> Self {
+        self.progress_stage = Some(stage);
+        self
+    }
+
     // IO buffers tuning
     //
     // Requests are clamped into `[MIN_IO_BUFFER, MAX_IO_BUFFER]` by
@@ -146,4 +347,47 @@ impl ETLOptions {
         self.write_buffer_bytes = clamp_io_buffer(write_bytes, "ETLOptions::with_io_buffers");
         self
     }
+
+    /// See [`ETLOptions::write_batch_size`]; `0` is treated as `1`.
+    pub fn with_write_batch_size(mut self, records: usize) -> Self {
+        self.write_batch_size = records.max(1);
+        self
+    }
+
+    /// Retry transient file-op errors up to `tries` times, sleeping
+    /// `base_delay_ms * attempt` between attempts. More tries ride out a
+    /// flaky NAS; `tries = 1` fails fast in CI. `tries = 0` means one
+    /// attempt.
+    ///
+    /// Applies to runs with these options only: the process-wide
+    /// [`set_io_retries`](crate::set_io_retries) budget is left alone, and
+    /// runs executing at the same time each keep their own value. A run that
+    /// leaves this (or `max_line_bytes`, `fsync`, or the zstd dictionary) at
+    /// its default uses the process-wide setting.
+    pub fn with_io_retries(mut self, tries: usize, base_delay_ms: u64) -> Self {
+        let tries = tries.max(1);
+        self.io_retries = (tries, base_delay_ms);
+        self
+    }
+
+    /// Cap a single decoded corpus line at `bytes` (minimum 1). A corrupt
+    /// stream with no newline for that long is treated like a zstd decode
+    /// error: fatal by default, skipped with the rest of the file under
+    /// `allow_partial`. Scoped to runs like [`ETLOptions::with_io_retries`]
+    /// (the process-wide cap is [`set_max_line_bytes`](crate::set_max_line_bytes)).
+    pub fn with_max_line_bytes(mut self, bytes: usize) -> Self {
+        let bytes = bytes.max(1);
+        self.max_line_bytes = bytes;
+        self
+    }
+
+    /// `sync_all` each output before its atomic promotion (and its directory
+    /// after, on Unix), so an output a call reported as written survives a
+    /// crash right after. Costs a disk flush per published file; off by
+    /// default. Scoped to runs like [`ETLOptions::with_io_retries`] (the
+    /// process-wide switch is [`set_fsync_outputs`](crate::set_fsync_outputs)).
+    pub fn with_fsync(mut self, yes: bool) -> Self {
+        self.fsync = yes;
+        self
+    }
 }
@@ -5,6 +5,44 @@ impl ETLOptions {
         self
     }
 
+    /// Write JSON records with object keys sorted recursively (see
+    /// [`ETLOptions::canonicalize_json`]). Applies to JSONL-family outputs;
+    /// CSV/TSV columns already follow the requested field order.
+    pub fn with_canonicalize_json(mut self, yes: bool) -> Self {
+        self.canonicalize_json = yes;
+        self
+    }
+
+    /// Annotate written JSON records with their source file and line (see
+    /// [`ETLOptions::add_provenance`]). Applies to JSONL-family outputs.
+    pub fn with_add_provenance(mut self, yes: bool) -> Self {
+        self.add_provenance = yes;
+        self
+    }
+
+    /// Validate every written line as full JSON (see
+    /// [`ETLOptions::strict_json`]). Lines that fail go to the reject sink
+    /// when one is set and fail the file otherwise, like any malformed line.
+    /// Costs a full parse per matching record.
+    pub fn with_strict_json(mut self, yes: bool) -> Self {
+        self.strict_json = yes;
+        self
+    }
+
+    /// Stop writing once about `bytes` of output have been written (see
+    /// [`ETLOptions::max_output_bytes`]). The cap is soft: each concurrent
+    /// worker may finish the record it is writing past it.
+    pub fn with_max_output_bytes(mut self, bytes: u64) -> Self {
+        self.max_output_bytes = Some(bytes);
+        self
+    }
+
+    /// See [`ETLOptions::keep_empty_outputs`].
+    pub fn with_keep_empty_outputs(mut self, yes: bool) -> Self {
+        self.keep_empty_outputs = yes;
+        self
+    }
+
     /// Set the zstd compression level used when writing partitioned `.zst`
     /// outputs. zstd's accepted range is 1..=22; values outside that band are
     /// clamped. Default: 7 (good ratio, ~5x faster than 19 on real workloads).
@@ -13,6 +51,32 @@ impl ETLOptions {
         self
     }
 
+    /// Compress `.zst` outputs (partitioned exports and attach-parents parts)
+    /// against the trained dictionary at `path`, which pays off on corpora of
+    /// many tiny monthly files. Runs with these options also read with it, in
+    /// place of the process-wide
+    /// [`set_zstd_dictionary`](crate::set_zstd_dictionary) one, so
+    /// dictionary-compressed inputs decode. Reading such files back needs the
+    /// same dictionary. A missing or untrained file is
+    /// recorded as a deferred [`ConfigBuildError`] surfaced by
+    /// [`ETLOptions::check_config`].
+    pub fn with_zstd_dictionary(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        match ZstdDictionary::load(path) {
+            Ok(dict) => {
+                self.zstd_dictionary = Some(dict);
+            }
+            Err(e) => {
+                self.zstd_dictionary = None;
+                self.build_error = Some(ConfigBuildError::InvalidZstdDictionary {
+                    path: path.to_path_buf(),
+                    message: format!("{e:#}"),
+                });
+            }
+        }
+        self
+    }
+
     /// Rows per Parquet row group when writing `.parquet` outputs. Larger
     /// groups improve scan performance and compression ratio but raise the
     /// writer's per-group memory peak. Values < 1 are clamped to 1. Default:
@@ -75,6 +139,24 @@ impl ETLOptions {
         self
     }
 
+    /// See [`ETLOptions::parent_cache_caps`]. Zero is raised to one.
+    pub fn with_parent_cache_caps(mut self, comments: usize, submissions: usize) -> Self {
+        self.parent_cache_caps = Some((comments.max(1), submissions.max(1)));
+        self
+    }
+
+    /// See [`ETLOptions::split_unresolved_parents`].
+    pub fn with_split_unresolved_parents(mut self, yes: bool) -> Self {
+        self.split_unresolved_parents = yes;
+        self
+    }
+
+    /// See [`ETLOptions::parent_reply_links`].
+    pub fn with_parent_reply_links(mut self, yes: bool) -> Self {
+        self.parent_reply_links = yes;
+        self
+    }
+
     /// Opt in to lossy corpus scans/exports that skip corrupt zstd monthly
     /// files instead of failing the operation. Skipped paths are collected in
     /// [`PartialReadReporter`] and incomplete months are not committed to
@@ -88,4 +170,14 @@ impl ETLOptions {
         self.allow_partial = yes;
         self
     }
+
+    /// Record corpus lines that fail to parse as JSON in a JSONL file at
+    /// `path` (raw line, error, source path and line number) and keep
+    /// scanning, instead of aborting the month. Applies to the streaming
+    /// exports (extract, spool, partitioned, direct parent attach). See
+    /// [`RejectSink`].
+    pub fn with_reject_sink(mut self, path: impl Into<PathBuf>) -> Self {
+        self.reject_sink = Some(RejectSink::new(path));
+        self
+    }
 }
@@ -7,6 +7,10 @@ pub(crate) const DEFAULT_INFLIGHT_BYTES: usize = 256 * 1024 * 1024;
 pub(crate) const DEFAULT_INFLIGHT_GROUPS: usize = 8;
 pub const DEFAULT_PARQUET_ROW_GROUP_SIZE: usize = 128 * 1024;
 pub const DEFAULT_PARQUET_COMPRESSION: &str = "zstd:3";
+/// Bytes appended across a sharded writer's shards between periodic flushes.
+pub const DEFAULT_SHARD_FLUSH_BYTES: u64 = 16 * 1024 * 1024;
+/// Bytes in-memory username shards may hold before the largest spill to disk.
+pub const DEFAULT_IN_MEMORY_SHARD_SPILL_BYTES: u64 = 1024 * 1024 * 1024;
 
 impl Default for ETLOptions {
     fn default() -> Self {
@@ -16,27 +20,52 @@ impl Default for ETLOptions {
             comments_dir: base.join("comments"),
             submissions_dir: base.join("submissions"),
             base_dir: base,
+            extra_base_dirs: Vec::new(),
+            duplicate_months: DuplicateMonthPolicy::Error,
+            files: None,
             subreddit: None,
             sources: Sources::Both,
             start: None,
             end: None,
-            shard_count: MAX_SHARDS,
+            last_n_months: None,
+            shard_count: None,
+            shard_flush_bytes: DEFAULT_SHARD_FLUSH_BYTES,
+            in_memory_shards: false,
+            in_memory_shard_spill_bytes: DEFAULT_IN_MEMORY_SHARD_SPILL_BYTES,
+            intermediate_format: IntermediateFormat::Text,
             whitelist_fields: None,
             strict_whitelist: false,
+            whitelist_source_order: false,
             strict_key: false,
             aggregate_strict: false,
             parallelism: None,
             work_dir: None,
             file_concurrency: DEFAULT_FILE_CONCURRENCY, // safe default to prevent OOM on big .zst windows
+            ordered_output: false,
+            interleaved_sources: false,
             progress: true,
             progress_label: None,
+            progress_stage: None,
 
             read_buffer_bytes: DEFAULT_READ_BUFFER_BYTES,
             write_buffer_bytes: DEFAULT_WRITE_BUFFER_BYTES,
+            write_batch_size: 1,
+            io_retries: (
+                crate::util::DEFAULT_BACKOFF_TRIES,
+                crate::util::DEFAULT_BACKOFF_DELAY_MS,
+            ),
+            max_line_bytes: crate::ndjson::DEFAULT_MAX_LINE_BYTES,
+            fsync: false,
 
             human_readable_timestamps: false,
+            canonicalize_json: false,
+            add_provenance: false,
+            strict_json: false,
+            max_output_bytes: None,
+            keep_empty_outputs: false,
 
             zst_level: DEFAULT_ZST_LEVEL,
+            zstd_dictionary: None,
             parquet_row_group_size: DEFAULT_PARQUET_ROW_GROUP_SIZE,
             parquet_compression: DEFAULT_PARQUET_COMPRESSION.to_string(),
 
@@ -45,9 +74,15 @@ impl Default for ETLOptions {
             adaptive_mem: AdaptiveMemCfg::default(),
             resume: false,
             parent_payload_spec: ParentPayloadSpec::default(),
+            parent_cache_caps: None,
+            split_unresolved_parents: false,
+            parent_reply_links: false,
             emit_manifest: true,
             allow_partial: false,
             partial_read_reporter: PartialReadReporter::default(),
+            reject_sink: None,
+            integrity_file_timeout: None,
+            integrity_on_corrupt: CorruptAction::Report,
             build_error: None,
         }
     }
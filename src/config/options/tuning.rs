@@ -0,0 +1,114 @@
+/// Coherent set of IO and memory knobs applied in one call by
+/// [`ETLOptions::with_tuning`] / [`RedditETL::tuning`](crate::RedditETL::tuning).
+///
+/// Start from a preset ([`low_memory`](Self::low_memory),
+/// [`balanced`](Self::balanced), [`high_throughput`](Self::high_throughput))
+/// and adjust fields as needed; individual builder calls made after
+/// `tuning` still override single knobs. Settings that change output bytes
+/// (`zst_level`, Parquet options) are not part of a profile.
+#[derive(Clone, Debug)]
+pub struct TuningProfile {
+    /// See [`ETLOptions::with_io_read_buffer`].
+    pub read_buffer_bytes: usize,
+    /// See [`ETLOptions::with_io_write_buffer`].
+    pub write_buffer_bytes: usize,
+    /// See [`ETLOptions::with_file_concurrency`].
+    pub file_concurrency: usize,
+    /// Explicit shard count, or `None` to keep the default (and the
+    /// corpus-size auto-sizing of id scratch shards).
+    pub shard_count: Option<usize>,
+    /// See [`ETLOptions::shard_flush_bytes`].
+    pub shard_flush_bytes: u64,
+    /// See [`ETLOptions::inflight_bytes`].
+    pub inflight_bytes: usize,
+    /// See [`ETLOptions::inflight_groups`].
+    pub inflight_groups: usize,
+    /// See [`ETLOptions::with_adaptive_mem`].
+    pub adaptive_mem: AdaptiveMemCfg,
+    /// See [`ETLOptions::write_batch_size`].
+    pub write_batch_size: usize,
+}
+
+impl TuningProfile {
+    /// Small buffers, one file at a time, a 64 MiB inflight budget, and
+    /// adaptive throttling that backs off early and reacts quickly. For
+    /// laptops and shared machines where RSS matters more than speed.
+    pub fn low_memory() -> Self {
+        Self {
+            read_buffer_bytes: 64 * 1024,
+            write_buffer_bytes: 64 * 1024,
+            file_concurrency: 1,
+            shard_count: None,
+            shard_flush_bytes: 4 * 1024 * 1024,
+            inflight_bytes: 64 * 1024 * 1024,
+            inflight_groups: 1,
+            adaptive_mem: AdaptiveMemCfg {
+                soft_low_frac: 0.30,
+                high_frac: 0.95,
+                adapt_cooldown_ms: 200,
+            },
+            write_batch_size: 1,
+        }
+    }
+
+    /// The [`ETLOptions::default`] values.
+    pub fn balanced() -> Self {
+        Self {
+            read_buffer_bytes: DEFAULT_READ_BUFFER_BYTES,
+            write_buffer_bytes: DEFAULT_WRITE_BUFFER_BYTES,
+            file_concurrency: DEFAULT_FILE_CONCURRENCY,
+            shard_count: None,
+            shard_flush_bytes: DEFAULT_SHARD_FLUSH_BYTES,
+            inflight_bytes: DEFAULT_INFLIGHT_BYTES,
+            inflight_groups: DEFAULT_INFLIGHT_GROUPS,
+            adaptive_mem: AdaptiveMemCfg::default(),
+            write_batch_size: 1,
+        }
+    }
+
+    /// Large buffers, four files in flight, a 1 GiB inflight budget, batched
+    /// sink writes, and adaptive throttling that only backs off when memory
+    /// is nearly exhausted. For dedicated machines with RAM to spare.
+    pub fn high_throughput() -> Self {
+        Self {
+            read_buffer_bytes: 1024 * 1024,
+            write_buffer_bytes: 1024 * 1024,
+            file_concurrency: 4,
+            shard_count: None,
+            shard_flush_bytes: 64 * 1024 * 1024,
+            inflight_bytes: 1024 * 1024 * 1024,
+            inflight_groups: DEFAULT_INFLIGHT_GROUPS,
+            adaptive_mem: AdaptiveMemCfg {
+                soft_low_frac: 0.10,
+                high_frac: 0.75,
+                adapt_cooldown_ms: 800,
+            },
+            write_batch_size: 64,
+        }
+    }
+}
+
+impl Default for TuningProfile {
+    fn default() -> Self {
+        Self::balanced()
+    }
+}
+
+impl ETLOptions {
+    /// Apply every knob in `profile`, through the same clamps as the
+    /// individual builders. Later builder calls override single knobs.
+    pub fn with_tuning(self, profile: TuningProfile) -> Self {
+        let opts = self
+            .with_io_buffers(profile.read_buffer_bytes, profile.write_buffer_bytes)
+            .with_file_concurrency(profile.file_concurrency)
+            .with_shard_flush_bytes(profile.shard_flush_bytes)
+            .with_inflight_bytes(profile.inflight_bytes)
+            .with_inflight_groups(profile.inflight_groups)
+            .with_adaptive_mem(profile.adaptive_mem)
+            .with_write_batch_size(profile.write_batch_size);
+        match profile.shard_count {
+            Some(shards) => opts.with_shard_count(shards),
+            None => opts,
+        }
+    }
+}
@@ -6,13 +6,60 @@ pub struct ETLOptions {
     pub base_dir: PathBuf,
     pub comments_dir: PathBuf,
     pub submissions_dir: PathBuf,
+    /// Additional corpus roots, each with its own `comments/` and
+    /// `submissions/`, discovered after `base_dir` and merged into one plan.
+    /// Scratch space and other base-relative defaults still use `base_dir`.
+    pub extra_base_dirs: Vec<PathBuf>,
+    /// How discovery resolves a month present under more than one root.
+    /// Defaults to [`DuplicateMonthPolicy::Error`].
+    pub duplicate_months: DuplicateMonthPolicy,
+    /// Explicit monthly files to process instead of discovering the corpus
+    /// roots. Each file's source and month come from its name
+    /// (`RC_YYYY-MM.zst` / `RS_YYYY-MM.zst`, or `.jsonl`), and the date
+    /// range and `sources` no longer select files. Default `None`.
+    pub files: Option<Vec<PathBuf>>,
     pub subreddit: Option<String>, // normalized lowercase, no "r/"; deprecated single-subreddit default
     pub sources: Sources,
     pub start: Option<YearMonth>, // inclusive
     pub end: Option<YearMonth>,   // inclusive
-    pub shard_count: usize,       // number of on-disk dedup shards, clamped to MAX_SHARDS
+    /// Number of on-disk dedup shards, clamped to [`MAX_SHARDS`]. Default
+    /// `None`: [`MAX_SHARDS`], except that parent-id collection and record
+    /// dedupe size their fan-out from input bytes.
+    pub shard_count: Option<usize>,
+    /// Process only the newest `n` months present (among `files` when set),
+    /// resolved when a run plans its files; replaces `start`/`end`. `0` is
+    /// treated as 1. Default `None`.
+    pub last_n_months: Option<usize>,
+    /// Flush every shard of a sharded scratch writer once this many bytes
+    /// have been appended across them, bounding what a crash loses; `0`
+    /// leaves flushing to full buffers, memory pressure, and the end of the
+    /// run. Default [`DEFAULT_SHARD_FLUSH_BYTES`] (16 MiB).
+    pub shard_flush_bytes: u64,
+    /// Keep username shards (`usernames`, `distinct_authors`,
+    /// `distinct_subreddits`) in memory instead of scratch files, spilling
+    /// the largest to the work dir once they hold more than
+    /// `in_memory_shard_spill_bytes` or memory runs low. Results are the
+    /// same either way. Default `false`.
+    pub in_memory_shards: bool,
+    /// Spill limit for `in_memory_shards`. Default
+    /// [`DEFAULT_IN_MEMORY_SHARD_SPILL_BYTES`] (1 GiB).
+    pub in_memory_shard_spill_bytes: u64,
+    /// Encoding for dedupe run files and KV shard scratch. Default
+    /// [`IntermediateFormat::Text`]; [`IntermediateFormat::Binary`] trades
+    /// readable scratch for smaller KV shards and dedupe merges that do not
+    /// re-parse JSON.
+    pub intermediate_format: IntermediateFormat,
     pub whitelist_fields: Option<Vec<String>>,
     pub strict_whitelist: bool, // fail instead of warn when whitelisted keys match nothing
+    /// Keep whitelisted fields in the record's own key order on every line.
+    /// The streaming projection already copies fields in source order, but a
+    /// line it cannot tokenize falls back to a full parse whose output keys
+    /// come out sorted. Enable for byte-stable exports whose key order must
+    /// follow the corpus. Also holds on the full-parse path taken for
+    /// `add_provenance`, `transform` and the other record rewrites, whose
+    /// added keys follow the whitelisted ones; `canonicalize_json` sorts
+    /// every key and takes precedence. Default `false`.
+    pub whitelist_source_order: bool,
     pub strict_key: bool,       // fail dedupe when matching records lack the requested key
     /// Fail the whole aggregate run when any input is fatal (open error,
     /// malformed JSON, shard write failure). Default `false` keeps the
@@ -24,19 +71,85 @@ pub struct ETLOptions {
     pub parallelism: Option<usize>, // Some(N) to set rayon threads (clamped), None to use default
     pub work_dir: Option<PathBuf>, // if None, create in base_dir/.reddit_etl_work/
     pub file_concurrency: usize, // limit monthly files processed concurrently, clamped to MAX_FILE_CONCURRENCY
+    /// Deliver records to callback/stream consumers (`for_each_record`,
+    /// `records_channel`, ...) in chronological `(month, RC before RS)`
+    /// order. Costs throughput: those operations then decode one file at a
+    /// time regardless of `file_concurrency`. Stitched single-file outputs
+    /// (`extract_to_jsonl`, `_json`, `_csv`, `_tsv`, `_parquet`) are written
+    /// in the same order, as under `interleaved_sources`; without either
+    /// option their parts go all RC months before all RS months.
+    pub ordered_output: bool,
+    /// Plan files month-major (`RC_2006-01, RS_2006-01, RC_2006-02, ...`)
+    /// instead of all RC months followed by all RS months, so a month's
+    /// comments and submissions are processed next to each other. Stitched
+    /// single-file outputs (`extract_to_jsonl`, `_json`, `_csv`, `_tsv`,
+    /// `_parquet`) follow the same month-major order.
+    pub interleaved_sources: bool,
     pub progress: bool,          // show progress bar
     pub progress_label: Option<String>, // optional label for progress bar
+    /// Draw this run's progress bar as one stage of a shared
+    /// [`PipelineProgress`](crate::PipelineProgress) stack. Default `None`.
+    pub progress_stage: Option<crate::progress::ProgressStage>,
 
     // IO tuning
     pub read_buffer_bytes: usize,  // BufReader capacity
     pub write_buffer_bytes: usize, // BufWriter capacity
+    /// Records [`ScanPlan::extract_to_writer`](crate::ScanPlan::extract_to_writer)
+    /// (and its framed variant) gathers before each `write_all` to its sink. Default `1` (one write
+    /// per record); raise it for sockets and pipes, where every write costs
+    /// a syscall.
+    pub write_batch_size: usize,
+    /// `(tries, base_delay_ms)` for the Windows-friendly file-op retries.
+    /// Default `(16, 50)`. A non-default budget applies to this run in place
+    /// of the process-wide [`set_io_retries`](crate::set_io_retries) one.
+    pub io_retries: (usize, u64),
+    /// Longest corpus line accepted, in bytes; a longer newline-free run is
+    /// handled as corruption under the partial-read policy. Default
+    /// [`DEFAULT_MAX_LINE_BYTES`](crate::DEFAULT_MAX_LINE_BYTES). Applied per
+    /// run like `io_retries`.
+    pub max_line_bytes: usize,
+    /// Sync every published output to disk before it is promoted. Default
+    /// `false`. Applied per run like `io_retries`.
+    pub fsync: bool,
 
     // output formatting
     pub human_readable_timestamps: bool, // convert unix timestamps to RFC3339 strings
+    /// Re-serialize every written JSON record with object keys sorted
+    /// recursively, so equal records are byte-identical regardless of source
+    /// key order. Forces the full-parse write path. Default `false`.
+    pub canonicalize_json: bool,
+    /// Add `_src_file` (`RC_YYYY-MM`/`RS_YYYY-MM`) and `_src_line` (1-based
+    /// line within that file) to every written JSON record. Forces the
+    /// full-parse write path. Default `false`.
+    pub add_provenance: bool,
+    /// Fully parse every matching line before copying it, even on paths
+    /// that would otherwise write it after only the `MinimalRecord` parse,
+    /// so a line that is not strictly valid JSON (e.g. a lone surrogate
+    /// escape in a field `MinimalRecord` skips) is treated as malformed
+    /// instead of written. Default `false`.
+    pub strict_json: bool,
+    /// Soft cap on uncompressed bytes a run writes to its JSONL/CSV outputs.
+    /// Once reached, workers stop claiming records and the output is
+    /// finalized normally. Concurrent workers may each overshoot by the
+    /// record they are writing. A resumed run starts from the bytes its
+    /// committed months wrote, recorded in `_progress.json`. Default `None`
+    /// (no cap).
+    pub max_output_bytes: Option<u64>,
+    /// Publish a partition for every planned month in
+    /// [`ScanPlan::export_partitioned`](crate::ScanPlan::export_partitioned),
+    /// writing an empty one when nothing matched, instead of skipping it.
+    /// Lets downstream tools tell "processed, no matches" from a missing
+    /// month. Default `false`.
+    pub keep_empty_outputs: bool,
 
     // zstd compression level used by partitioned ZST writers
     pub zst_level: i32,
 
+    /// Trained zstd dictionary used by `.zst` output writers and, for runs
+    /// with these options, for reading dictionary-compressed inputs. Set through
+    /// [`ETLOptions::with_zstd_dictionary`]. Default `None`.
+    pub zstd_dictionary: Option<ZstdDictionary>,
+
     /// Rows per Parquet row group when writing `.parquet` outputs (single-file
     /// `extract_to_parquet` and per-partition writers). Larger groups improve
     /// scan performance and compression ratio but raise the writer's per-group
@@ -109,6 +222,24 @@ pub struct ETLOptions {
     /// submissions).
     pub parent_payload_spec: ParentPayloadSpec,
 
+    /// Parent-shard cache sizes `(comments, submissions)`: how many resolved
+    /// monthly shards each attach worker keeps loaded, also scaling the shared
+    /// id-set cache in `resolve_parent_maps`. `None` keeps the built-in sizes.
+    /// The free-memory tier caps both, so larger values only take effect when
+    /// RAM allows. Set via [`ETLOptions::with_parent_cache_caps`].
+    pub parent_cache_caps: Option<(usize, usize)>,
+
+    /// Route records whose parent could not be resolved to
+    /// `<out_dir>/unresolved/<name>` instead of the main attach output.
+    /// Default false. Set via [`ETLOptions::with_split_unresolved_parents`].
+    pub split_unresolved_parents: bool,
+
+    /// Have `resolve_parent_maps` record each resolved comment parent's own
+    /// `parent_id` (`<RC shard>.parent-links.json`) and load those links, as
+    /// [`ScanPlan::annotate_depth`](crate::ScanPlan::annotate_depth) needs.
+    /// Default false. Set via [`ETLOptions::with_parent_reply_links`].
+    pub parent_reply_links: bool,
+
     /// Emit user-facing provenance manifests next to file/directory outputs.
     /// Enabled by default; disable via [`ETLOptions::with_run_manifest`] or the
     /// CLI's `--no-manifest` when absolute local paths are too sensitive for a
@@ -121,6 +252,19 @@ pub struct ETLOptions {
     /// in [`Self::partial_read_reporter`], and never committed to resume manifests.
     pub allow_partial: bool,
     pub partial_read_reporter: PartialReadReporter,
+    /// Where malformed corpus lines go instead of aborting the month; see
+    /// [`ETLOptions::with_reject_sink`]. `None` (default) keeps them fatal.
+    pub reject_sink: Option<RejectSink>,
+    /// Wall-clock budget per file for
+    /// [`RedditETL::check_corpus_integrity`](crate::RedditETL::check_corpus_integrity).
+    /// A file still decoding when it runs out is reported with the error
+    /// `"timeout"` instead of holding up the run. `None` (default) waits for
+    /// every file.
+    pub integrity_file_timeout: Option<std::time::Duration>,
+    /// What [`RedditETL::check_corpus_integrity`](crate::RedditETL::check_corpus_integrity)
+    /// does with each file that fails its check. Default
+    /// [`CorruptAction::Report`] leaves files in place.
+    pub integrity_on_corrupt: CorruptAction,
 
     #[doc(hidden)]
     pub build_error: Option<ConfigBuildError>,
@@ -0,0 +1,369 @@
+/// Shared destination for corpus lines that fail to parse as JSON.
+///
+/// Set with [`ETLOptions::with_reject_sink`]. Instead of aborting the month,
+/// each malformed line is recorded in the sink's file as one JSON object
+/// `{"path", "line_number", "error", "line"}` and the scan continues.
+///
+/// The handle is cloned by `Arc` like [`PartialReadReporter`]. Each consuming
+/// operation starts the sink once before it plans or counts anything. An
+/// input file's rows are staged while it is read and appended to the reject
+/// file once it has been read to the end; the reject file exists only while
+/// it holds rows. A `resume` run keeps the rows of the months it skips, and a
+/// month it reads again replaces its rows instead of adding them twice. Only
+/// that replacement rewrites the file; a second pass over an input in the
+/// same run (the `source_ratio` pre-count, a dedupe emit pass) that rejects
+/// the same rows leaves it alone.
+#[derive(Clone, Debug)]
+pub struct RejectSink {
+    path: PathBuf,
+    inner: Arc<Mutex<RejectFile>>,
+}
+
+#[derive(Debug, Default)]
+struct RejectFile {
+    /// Input files whose rows an earlier run left in the reject file.
+    /// Reading one again rewrites the file without them.
+    carried: std::collections::HashSet<PathBuf>,
+    /// Input files this run published rows for, with those rows' digest.
+    appended: std::collections::HashMap<PathBuf, RowsDigest>,
+    /// This run's rows per input file, staged until the input is fully read.
+    staged: std::collections::HashMap<PathBuf, StagedRejects>,
+}
+
+/// Row count and hash of one input's rows, to tell whether a later pass over
+/// the input rejected exactly the rows already published for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RowsDigest {
+    rows: u64,
+    hash: u64,
+}
+
+#[derive(Debug)]
+struct StagedRejects {
+    path: PathBuf,
+    file: std::io::BufWriter<std::fs::File>,
+    rows: u64,
+    hasher: std::collections::hash_map::DefaultHasher,
+}
+
+impl StagedRejects {
+    fn digest(&self) -> RowsDigest {
+        use std::hash::Hasher;
+
+        RowsDigest {
+            rows: self.rows,
+            hash: self.hasher.finish(),
+        }
+    }
+
+    /// Flush the staged rows to disk and return the staged file's path.
+    fn into_path(self) -> anyhow::Result<PathBuf> {
+        use anyhow::Context;
+
+        self.file
+            .into_inner()
+            .map_err(|e| e.into_error())
+            .with_context(|| format!("flush staged reject file {}", self.path.display()))?;
+        Ok(self.path)
+    }
+}
+
+impl RejectFile {
+    fn discard_staged(&mut self) {
+        for (_, staged) in self.staged.drain() {
+            drop(staged.file);
+            let _ = std::fs::remove_file(&staged.path);
+        }
+    }
+}
+
+impl Drop for RejectFile {
+    fn drop(&mut self) {
+        self.discard_staged();
+    }
+}
+
+#[derive(Serialize)]
+struct RejectedLine<'a> {
+    path: &'a Path,
+    line_number: u64,
+    error: String,
+    line: &'a str,
+}
+
+/// The only field `begin_run` and rewrites need from a published row.
+#[derive(serde::Deserialize)]
+struct RejectedSource {
+    path: PathBuf,
+}
+
+impl RejectSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            inner: Arc::new(Mutex::new(RejectFile::default())),
+        }
+    }
+
+    /// File the rejected lines are written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn dir(&self) -> &Path {
+        self.path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+    }
+
+    /// Stage one rejected `line` (1-based `line_number` in `source`). It is
+    /// published with the rest of `source`'s rows by [`Self::finish_source`].
+    pub(crate) fn record(
+        &self,
+        source: &Path,
+        line_number: u64,
+        line: &str,
+        error: impl fmt::Display,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        use std::collections::hash_map::Entry;
+        use std::io::Write;
+
+        let mut row = serde_json::to_vec(&RejectedLine {
+            path: source,
+            line_number,
+            error: error.to_string(),
+            line,
+        })?;
+        row.push(b'\n');
+
+        let mut inner = self.inner.lock();
+        let staged = match inner.staged.entry(source.to_path_buf()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let staging_dir = crate::atomic_write::ensure_staging_dir(self.dir())?;
+                let path = crate::atomic_write::unique_inprogress_path(&staging_dir, &self.path)?;
+                let file = crate::util::create_with_default_backoff(&path)
+                    .with_context(|| format!("create staged reject file {}", path.display()))?;
+                entry.insert(StagedRejects {
+                    path,
+                    file: std::io::BufWriter::new(file),
+                    rows: 0,
+                    hasher: Default::default(),
+                })
+            }
+        };
+        std::hash::Hasher::write(&mut staged.hasher, &row);
+        staged.rows += 1;
+        staged
+            .file
+            .write_all(&row)
+            .with_context(|| format!("write staged reject file {}", staged.path.display()))
+    }
+
+    /// Publish the rows staged for `source` now that it has been read to the
+    /// end, replacing any rows the reject file already holds for it.
+    ///
+    /// New rows are appended. The file is rewritten only when `source`
+    /// already has rows in it that differ from the staged ones; a repeat pass
+    /// that staged the same rows as the one published this run is dropped.
+    /// With `fsync` set, appended rows are synced to disk before returning.
+    pub(crate) fn finish_source(&self, source: &Path, fsync: bool) -> anyhow::Result<()> {
+        let mut inner = self.inner.lock();
+        let staged = inner.staged.remove(source);
+        let digest = staged.as_ref().map(StagedRejects::digest);
+        let replace = if inner.carried.remove(source) {
+            true
+        } else {
+            match inner.appended.get(source) {
+                None => false,
+                Some(&published) if Some(published) == digest => {
+                    if let Some(staged) = staged {
+                        drop(staged.file);
+                        let _ = std::fs::remove_file(&staged.path);
+                    }
+                    return Ok(());
+                }
+                Some(_) => true,
+            }
+        };
+        if replace {
+            inner.appended.remove(source);
+            let keep_others = !inner.carried.is_empty() || !inner.appended.is_empty();
+            self.replace_rows(source, staged, keep_others)?;
+        } else if let Some(staged) = staged {
+            self.append_rows(staged, fsync)?;
+        }
+        if let Some(digest) = digest {
+            inner.appended.insert(source.to_path_buf(), digest);
+        }
+        Ok(())
+    }
+
+    /// Append `staged` to the reject file. The staged file is removed either
+    /// way. A crash part-way leaves a torn last row, which the next `resume`
+    /// run's [`Self::begin_run`] trims.
+    fn append_rows(&self, staged: StagedRejects, fsync: bool) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let staged_path = staged.path.clone();
+        let result = (|| -> anyhow::Result<()> {
+            let path = staged.into_path()?;
+            let mut rows = crate::util::open_with_default_backoff(&path)
+                .with_context(|| format!("open staged reject file {}", path.display()))?;
+            let mut out = crate::util::open_append_with_default_backoff(&self.path)
+                .with_context(|| format!("open reject file {}", self.path.display()))?;
+            std::io::copy(&mut rows, &mut out)
+                .with_context(|| format!("append to reject file {}", self.path.display()))?;
+            if fsync {
+                out.sync_all()
+                    .with_context(|| format!("sync reject file {}", self.path.display()))?;
+            }
+            Ok(())
+        })();
+        let _ = std::fs::remove_file(staged_path);
+        result
+    }
+
+    /// Atomically rewrite the reject file as its rows for other inputs (when
+    /// `keep_others`) followed by `staged`, or remove it when neither is left.
+    /// The staged file is removed either way.
+    fn replace_rows(
+        &self,
+        source: &Path,
+        staged: Option<StagedRejects>,
+        keep_others: bool,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let staged_path = staged.as_ref().map(|s| s.path.clone());
+        let result = (|| -> anyhow::Result<()> {
+            if let Some(staged) = staged {
+                staged.into_path()?;
+            } else if !keep_others {
+                return match std::fs::remove_file(&self.path) {
+                    Ok(()) => Ok(()),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                    Err(e) => Err(e)
+                        .with_context(|| format!("remove reject file {}", self.path.display())),
+                };
+            }
+            crate::atomic_write::write_at_path_atomic(&self.path, 64 * 1024, |w| {
+                if keep_others {
+                    self.for_each_published_row(|row_source, row| {
+                        if row_source != source {
+                            w.write_all(row.as_bytes())?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                if let Some(path) = &staged_path {
+                    let mut rows = crate::util::open_with_default_backoff(path)
+                        .with_context(|| format!("open staged reject file {}", path.display()))?;
+                    std::io::copy(&mut rows, w)?;
+                }
+                Ok(())
+            })
+        })();
+        if let Some(path) = staged_path {
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+
+    /// Call `f` with the input path and raw text of each published row, and
+    /// return the length in bytes of those rows. A last row without its
+    /// newline is a torn append and is skipped.
+    fn for_each_published_row(
+        &self,
+        mut f: impl FnMut(&Path, &str) -> anyhow::Result<()>,
+    ) -> anyhow::Result<u64> {
+        use anyhow::Context;
+        use std::io::BufRead;
+
+        let file = match crate::util::open_with_default_backoff(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e).with_context(|| format!("open reject file {}", self.path.display()))
+            }
+        };
+        let mut reader = std::io::BufReader::new(file);
+        let mut row = String::new();
+        let mut line_number = 0_u64;
+        let mut complete_bytes = 0_u64;
+        loop {
+            row.clear();
+            let read = reader
+                .read_line(&mut row)
+                .with_context(|| format!("read reject file {}", self.path.display()))?;
+            if read == 0 || !row.ends_with('\n') {
+                return Ok(complete_bytes);
+            }
+            complete_bytes += read as u64;
+            line_number += 1;
+            let parsed: RejectedSource = serde_json::from_str(&row).with_context(|| {
+                format!(
+                    "malformed row in reject file {} at line {line_number}",
+                    self.path.display()
+                )
+            })?;
+            f(&parsed.path, &row)?;
+        }
+    }
+
+    /// Drop staged rows and remove any reject file left by an earlier run.
+    /// Called at the start of each consuming operation that is not resuming.
+    pub fn reset(&self) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let mut inner = self.inner.lock();
+        inner.discard_staged();
+        inner.carried.clear();
+        inner.appended.clear();
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                Err(e).with_context(|| format!("remove stale reject file {}", self.path.display()))
+            }
+        }
+    }
+
+    /// Start a consuming operation: [`Self::reset`], or under `resume` keep
+    /// the earlier run's rows so only the inputs read again replace theirs.
+    /// A torn last row from an append the earlier run did not finish is
+    /// trimmed.
+    pub(crate) fn begin_run(&self, resume: bool) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        if !resume {
+            return self.reset();
+        }
+        let mut inner = self.inner.lock();
+        inner.discard_staged();
+        inner.appended.clear();
+        let mut carried = std::collections::HashSet::new();
+        let complete_bytes = self.for_each_published_row(|source, _row| {
+            carried.insert(source.to_path_buf());
+            Ok(())
+        })?;
+        let on_disk = match std::fs::metadata(&self.path) {
+            Ok(meta) => meta.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => {
+                return Err(e).with_context(|| format!("stat reject file {}", self.path.display()))
+            }
+        };
+        if on_disk > complete_bytes {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(&self.path)
+                .and_then(|f| f.set_len(complete_bytes))
+                .with_context(|| format!("trim torn row from {}", self.path.display()))?;
+        }
+        inner.carried = carried;
+        Ok(())
+    }
+}
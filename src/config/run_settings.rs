@@ -0,0 +1,100 @@
+// Per-run IO settings. Builders only record `io_retries`, `max_line_bytes`,
+// `fsync` and `zstd_dictionary` on `ETLOptions`, and no run writes them into
+// the process-wide `set_*` defaults, so overlapping runs never see each
+// other's values. Stream jobs and line streams take them per call
+// (`StreamJobCtx`, `LineStreamOpts`). The shared IO helpers with no per-call
+// parameter (the `*_with_default_backoff` wrappers, atomic publishes) read
+// the current thread's `RunSettings`, which a run enters on its own thread
+// and `with_thread_pool` / the run's scoped threads hand to their workers.
+
+/// The IO settings a run overrides. `None` keeps the process-wide default.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct RunSettings {
+    pub(crate) io_retries: Option<(usize, u64)>,
+    pub(crate) max_line_bytes: Option<usize>,
+    pub(crate) fsync: Option<bool>,
+    pub(crate) zstd_dictionary: Option<ZstdDictionary>,
+}
+
+thread_local! {
+    /// Settings of the run executing on this thread; see [`RunSettings::enter`].
+    static CURRENT_RUN_SETTINGS: RefCell<Option<Arc<RunSettings>>> = const { RefCell::new(None) };
+}
+
+impl RunSettings {
+    /// The fields of `opts` that differ from [`ETLOptions::default`], so a
+    /// run that never touched a knob keeps whatever
+    /// [`set_io_retries`](crate::set_io_retries) and friends installed.
+    pub(crate) fn from_options(opts: &ETLOptions) -> Self {
+        let defaults = ETLOptions::default();
+        Self {
+            io_retries: (opts.io_retries != defaults.io_retries).then_some(opts.io_retries),
+            max_line_bytes: (opts.max_line_bytes != defaults.max_line_bytes)
+                .then_some(opts.max_line_bytes),
+            fsync: (opts.fsync != defaults.fsync).then_some(opts.fsync),
+            zstd_dictionary: opts.zstd_dictionary.clone(),
+        }
+    }
+
+    /// The settings of the run executing on this thread, if it overrides any.
+    pub(crate) fn current() -> Option<Arc<RunSettings>> {
+        CURRENT_RUN_SETTINGS
+            .try_with(|current| current.borrow().clone())
+            .ok()
+            .flatten()
+    }
+
+    /// `field` of the current run's settings; `None` outside a run or when
+    /// the run left that knob at its default.
+    pub(crate) fn read<T>(field: impl FnOnce(&RunSettings) -> Option<T>) -> Option<T> {
+        Self::current().as_deref().and_then(field)
+    }
+
+    /// Make `settings` this thread's run settings until the returned scope
+    /// drops, restoring the previous ones. `None` masks an enclosing run's
+    /// settings, so a run nested in another's callback uses its own.
+    pub(crate) fn enter(settings: Option<Arc<RunSettings>>) -> RunSettingsScope {
+        let previous = CURRENT_RUN_SETTINGS
+            .try_with(|current| current.replace(settings))
+            .ok()
+            .flatten();
+        RunSettingsScope { previous }
+    }
+
+    /// Make `settings` this thread's run settings for good. Only for threads
+    /// that run nothing but this run's work, like the workers of a pool it
+    /// built (see [`with_thread_pool`](crate::util::with_thread_pool)).
+    pub(crate) fn adopt(settings: Arc<RunSettings>) {
+        let _ = CURRENT_RUN_SETTINGS.try_with(|current| *current.borrow_mut() = Some(settings));
+    }
+}
+
+/// Keeps one run's IO settings current on the thread that entered them; see
+/// [`RunSettings::enter`]. Drop it on that thread.
+#[must_use = "the settings are left when the scope drops"]
+pub(crate) struct RunSettingsScope {
+    previous: Option<Arc<RunSettings>>,
+}
+
+impl Drop for RunSettingsScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        // `try_with` fails only during thread teardown; nothing to restore then.
+        let _ = CURRENT_RUN_SETTINGS.try_with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+impl ETLOptions {
+    /// This run's non-default IO settings, or `None` when it overrides none.
+    pub(crate) fn run_settings(&self) -> Option<Arc<RunSettings>> {
+        let settings = RunSettings::from_options(self);
+        (settings != RunSettings::default()).then(|| Arc::new(settings))
+    }
+
+    /// Make this run's `io_retries`, `max_line_bytes`, `fsync` and
+    /// `zstd_dictionary` current on this thread for as long as the returned
+    /// scope lives. Other threads, and so other runs, are unaffected.
+    pub(crate) fn enter_run_settings(&self) -> RunSettingsScope {
+        RunSettings::enter(self.run_settings())
+    }
+}
@@ -79,7 +79,7 @@ mod tests {
             .parallelism(usize::MAX)
             .file_concurrency(usize::MAX);
 
-        assert_eq!(etl.opts.shard_count, MAX_SHARDS);
+        assert_eq!(etl.opts.shard_count, Some(MAX_SHARDS));
         assert_eq!(etl.opts.parallelism, Some(max_parallelism_limit()));
         assert_eq!(etl.opts.file_concurrency, MAX_FILE_CONCURRENCY);
     }
@@ -133,7 +133,7 @@ mod tests {
             .with_parallelism(0)
             .with_file_concurrency(0);
 
-        assert_eq!(opts.shard_count, 1);
+        assert_eq!(opts.shard_count, Some(1));
         assert_eq!(opts.parallelism, Some(1));
         assert_eq!(opts.file_concurrency, 1);
     }
@@ -181,4 +181,117 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn unloadable_zstd_dictionary_is_a_deferred_config_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let not_a_dict = dir.path().join("plain.bin");
+        std::fs::write(&not_a_dict, b"just some bytes, no dictionary header").unwrap();
+
+        // A later valid date range must not clear the dictionary error.
+        let opts = ETLOptions::default()
+            .with_zstd_dictionary(&not_a_dict)
+            .with_date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 3)));
+        assert!(opts.zstd_dictionary.is_none());
+        let err = opts
+            .check_config()
+            .expect_err("an untrained dictionary file should surface a ConfigBuildError");
+        match err.downcast_ref::<ConfigBuildError>() {
+            Some(ConfigBuildError::InvalidZstdDictionary { path, message }) => {
+                assert_eq!(path, &not_a_dict);
+                assert!(message.contains("not a trained zstd dictionary"), "{message}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn run_settings_are_scoped_to_the_entering_thread() {
+        let baseline = crate::util::io_retries();
+        let run = ETLOptions::default().with_io_retries(baseline.0 + 1, baseline.1);
+        assert!(ETLOptions::default().run_settings().is_none());
+
+        let scope = run.enter_run_settings();
+        assert_eq!(crate::util::run_io_retries(), run.io_retries);
+        assert_eq!(crate::util::io_retries(), baseline, "the default is untouched");
+        let elsewhere = std::thread::spawn(crate::util::run_io_retries)
+            .join()
+            .unwrap();
+        assert_eq!(elsewhere, baseline, "other threads keep the default");
+
+        // A run at defaults started from inside this one uses the defaults.
+        let nested = ETLOptions::default().enter_run_settings();
+        assert_eq!(crate::util::run_io_retries(), baseline);
+        drop(nested);
+        assert_eq!(crate::util::run_io_retries(), run.io_retries);
+        drop(scope);
+        assert_eq!(crate::util::run_io_retries(), baseline);
+    }
+
+    #[test]
+    fn thread_pools_carry_run_settings_to_their_workers() {
+        use rayon::prelude::*;
+
+        let baseline = crate::util::io_retries();
+        let run = ETLOptions::default().with_io_retries(baseline.0 + 2, baseline.1);
+        let _scope = run.enter_run_settings();
+        for parallelism in [None, Some(2)] {
+            let seen: Vec<_> = crate::util::with_thread_pool(parallelism, || {
+                (0..64)
+                    .into_par_iter()
+                    .map(|_| crate::util::run_io_retries())
+                    .collect()
+            });
+            assert!(seen.iter().all(|&budget| budget == run.io_retries), "{seen:?}");
+        }
+    }
+
+    #[test]
+    fn tuning_presets_set_every_knob_and_allow_later_overrides() {
+        let low = ETLOptions::default().with_tuning(TuningProfile::low_memory());
+        assert_eq!(
+            (low.read_buffer_bytes, low.write_buffer_bytes),
+            (64 * 1024, 64 * 1024)
+        );
+        assert_eq!(low.file_concurrency, 1);
+        assert_eq!(low.shard_flush_bytes, 4 * 1024 * 1024);
+        assert_eq!(
+            (low.inflight_bytes, low.inflight_groups),
+            (64 * 1024 * 1024, 1)
+        );
+        assert_eq!(low.adaptive_mem.soft_low_frac, 0.30);
+        assert_eq!(low.write_batch_size, 1);
+        assert_eq!(low.shard_count, None);
+
+        let defaults = ETLOptions::default();
+        let balanced = ETLOptions::default().with_tuning(TuningProfile::balanced());
+        assert_eq!(balanced.read_buffer_bytes, defaults.read_buffer_bytes);
+        assert_eq!(balanced.write_buffer_bytes, defaults.write_buffer_bytes);
+        assert_eq!(balanced.file_concurrency, defaults.file_concurrency);
+        assert_eq!(balanced.shard_flush_bytes, defaults.shard_flush_bytes);
+        assert_eq!(balanced.inflight_bytes, defaults.inflight_bytes);
+        assert_eq!(balanced.inflight_groups, defaults.inflight_groups);
+        assert_eq!(
+            balanced.adaptive_mem.high_frac,
+            defaults.adaptive_mem.high_frac
+        );
+        assert_eq!(balanced.write_batch_size, defaults.write_batch_size);
+
+        let high = ETLOptions::default()
+            .with_tuning(TuningProfile::high_throughput())
+            .with_file_concurrency(2);
+        assert_eq!(
+            (high.read_buffer_bytes, high.write_buffer_bytes),
+            (1024 * 1024, 1024 * 1024)
+        );
+        assert_eq!(high.file_concurrency, 2, "a later builder call wins");
+        assert_eq!(high.inflight_bytes, 1024 * 1024 * 1024);
+        assert_eq!(high.write_batch_size, 64);
+
+        let sharded = ETLOptions::default().with_tuning(TuningProfile {
+            shard_count: Some(32),
+            ..TuningProfile::low_memory()
+        });
+        assert_eq!(sharded.shard_count, Some(32));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::config::ETLOptions;
+use crate::config::{ETLOptions, IntermediateFormat};
 use crate::mem::AdaptiveMemCfg;
 
 pub(crate) const BYTES_PER_MB: usize = 1024 * 1024;
@@ -29,6 +29,9 @@ pub struct DedupeCfg {
     /// is bounded by this value (one map being filled + one map awaiting
     /// disk write). 0 disables the cap and falls back to `max_buf_mb` only.
     pub inflight_bytes: usize,
+    /// Encoding of the `run_*` scratch files. Binary runs store each line's
+    /// key next to it, so the merge phase skips key extraction.
+    pub run_format: IntermediateFormat,
 }
 impl Default for DedupeCfg {
     fn default() -> Self {
@@ -42,6 +45,7 @@ impl Default for DedupeCfg {
             // peak inflight = ~2 * (inflight_bytes / 2) = 256 MiB regardless of
             // available_memory_fraction sampling.
             inflight_bytes: 256 * BYTES_PER_MB,
+            run_format: IntermediateFormat::Text,
         }
     }
 }
@@ -53,6 +57,7 @@ impl From<&ETLOptions> for DedupeCfg {
             read_buf_bytes: opts.read_buffer_bytes,
             write_buf_bytes: opts.write_buffer_bytes,
             inflight_bytes: opts.inflight_bytes,
+            run_format: opts.intermediate_format,
             ..Self::default()
         }
     }
@@ -1,5 +1,6 @@
 use super::cfg::DedupeCfg;
 use super::note_key_extraction_failed;
+use super::runs::BINARY_RUN_EXTENSION;
 use crate::atomic_write::write_at_path_atomic;
 use crate::key_extractor::KeyExtractor;
 use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
@@ -39,12 +40,25 @@ impl PartialEq for HeapItem {
     }
 }
 
+/// Position inside a binary run: the key of the group being read and how
+/// many of its lines remain.
+#[derive(Default)]
+struct BinaryRunCursor {
+    key: String,
+    remaining: u64,
+}
+
 // Read until `reader` yields a line with an extractable key, stripping
 // CRLF and building a HeapItem for it. Returns `Ok(None)` only at EOF.
 // Lines without a key are counted and skipped; `read_bytes` and `pb` are
 // updated on every successful read so progress accounting stays
 // consistent across the call sites below.
 //
+// Binary runs (`binary` is `Some`) carry the key already extracted in
+// phase 1 as a frame ahead of each key's lines, so no key extraction happens
+// here and every record yields a HeapItem. The cursor remembers the current
+// key and how many of its lines are still unread.
+//
 // `line_buf` is a single reusable buffer shared across every call: each
 // read fills it in place (`read_line_capped` reuses the backing storage),
 // so skipped no-key lines cost no allocation at all. When a keyed line is
@@ -62,7 +76,48 @@ fn advance_reader(
     pb: &ProgressScope,
     key_extractions_failed: Option<&AtomicU64>,
     line_buf: &mut String,
+    binary: Option<&mut BinaryRunCursor>,
 ) -> Result<Option<HeapItem>> {
+    if let Some(cursor) = binary {
+        let context = || {
+            format!(
+                "read binary dedupe run {} at record {}",
+                run_path.display(),
+                *line_number + 1
+            )
+        };
+        let mut n = 0_u64;
+        while cursor.remaining == 0 {
+            if !crate::frames::read_str_frame(reader, &mut cursor.key, run_path)
+                .with_context(context)?
+            {
+                return Ok(None);
+            }
+            cursor.remaining =
+                crate::frames::read_required_varint(reader, run_path).with_context(context)?;
+            n += cursor.key.len() as u64 + 2;
+        }
+        if !crate::frames::read_str_frame(reader, line_buf, run_path).with_context(context)? {
+            anyhow::bail!(
+                "binary dedupe run {} ends inside the lines of key {:?} at record {}",
+                run_path.display(),
+                cursor.key,
+                *line_number + 1
+            );
+        }
+        cursor.remaining -= 1;
+        n += line_buf.len() as u64 + 2;
+        *read_bytes += n;
+        *line_number += 1;
+        pb.inc_bytes(n);
+        let cap = line_buf.capacity();
+        let line = std::mem::replace(line_buf, String::with_capacity(cap));
+        return Ok(Some(HeapItem {
+            key: cursor.key.clone(),
+            run_idx,
+            line,
+        }));
+    }
     loop {
         let n = read_line_capped(reader, line_buf, DEFAULT_MAX_LINE_BYTES, run_path).with_context(
             || {
@@ -101,6 +156,10 @@ fn advance_reader(
 /// from all runs and call the user-provided `merge_same_key` callback to write **one**
 /// output NDJSON line for that key.
 ///
+/// Runs named `*.bin` are read as binary runs (see
+/// [`IntermediateFormat`](crate::IntermediateFormat)); any other run is read
+/// as NDJSON, so the result does not depend on which format built the runs.
+///
 /// The input `run_*` files are scratch and are **always** removed before this
 /// function returns — on success, on a propagated merge error, and on a panic
 /// — so a later dedupe re-run cannot pick up stale runs. The caller still owns
@@ -156,6 +215,14 @@ pub(crate) fn merge_runs_sorted_with_key_stats(
             .with_context(|| format!("open {}", p.display()))?;
         readers.push((BufReader::with_capacity(cfg.read_buf_bytes, f), 0, 0));
     }
+    let mut binary: Vec<Option<BinaryRunCursor>> = runs
+        .iter()
+        .map(|p| {
+            p.extension()
+                .is_some_and(|e| e == BINARY_RUN_EXTENSION)
+                .then(BinaryRunCursor::default)
+        })
+        .collect();
 
     write_at_path_atomic(
         output,
@@ -180,6 +247,7 @@ pub(crate) fn merge_runs_sorted_with_key_stats(
                     &pb,
                     key_extractions_failed,
                     &mut line_buf,
+                    binary[i].as_mut(),
                 )? {
                     heap.push(item);
                 }
@@ -206,6 +274,7 @@ pub(crate) fn merge_runs_sorted_with_key_stats(
                         &pb,
                         key_extractions_failed,
                         &mut line_buf,
+                        binary[top.run_idx].as_mut(),
                     )? {
                         heap.push(item);
                     }
@@ -228,6 +297,7 @@ pub(crate) fn merge_runs_sorted_with_key_stats(
                         &pb,
                         key_extractions_failed,
                         &mut line_buf,
+                        binary[run_idx].as_mut(),
                     )? {
                         heap.push(item);
                     }
@@ -1,5 +1,6 @@
 use super::cfg::{DedupeCfg, BUILD_RUNS_CHANNEL_CAP, BYTES_PER_MB};
 use super::note_key_extraction_failed;
+use crate::config::{IntermediateFormat, RunSettings};
 use crate::key_extractor::KeyExtractor;
 use crate::mem::{available_memory_fraction, is_low_memory, AdaptiveMemCfg};
 use crate::ndjson::{NdjsonReader, NdjsonWriter};
@@ -8,6 +9,7 @@ use crate::util::smoothstep_memory_fraction;
 use crate::zstd_jsonl::malformed_json_error;
 use anyhow::{Context, Result};
 use std::fs;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicU64;
 use std::time::{Duration, Instant};
@@ -20,6 +22,12 @@ type RunMap = ahash::AHashMap<String, Vec<String>>;
 /// grouped by key (keys are written in sorted order within each run).
 /// Returns the run file paths.
 ///
+/// With `cfg.run_format` = [`IntermediateFormat::Binary`] each run is
+/// `run_<n>.bin`: per key, a key frame and a line count, then one frame per
+/// line (see [`IntermediateFormat`]).
+/// [`merge_runs_sorted`](super::merge_runs_sorted) picks the decoding per
+/// file from that extension.
+///
 /// Producer/consumer split: the line-reader runs on the calling thread and
 /// hands completed maps to a disk-writer thread via a bounded crossbeam
 /// channel (capacity 1). Per-flush map size is capped at
@@ -62,13 +70,17 @@ pub(crate) fn build_runs_sorted_with_key_stats(
 
     let runs_dir_buf = runs_dir.to_path_buf();
     let write_buf_bytes = cfg.write_buf_bytes;
+    let run_format = cfg.run_format;
+
+    let run_settings = RunSettings::current();
 
     let run_paths: Vec<PathBuf> = std::thread::scope(|s| -> Result<Vec<PathBuf>> {
         let writer_handle = s.spawn(move || -> Result<Vec<(usize, PathBuf)>> {
+            let _run_settings = RunSettings::enter(run_settings);
             let mut written: Vec<(usize, PathBuf)> = Vec::new();
             while let Ok((idx, mut m)) = rx.recv() {
-                let run_path = runs_dir_buf.join(format!("run_{:04}.ndjson", idx));
-                write_run_sorted(&run_path, &mut m, write_buf_bytes)?;
+                let run_path = runs_dir_buf.join(run_file_name(idx, run_format));
+                write_run_sorted(&run_path, &mut m, write_buf_bytes, run_format)?;
                 written.push((idx, run_path));
             }
             Ok(written)
@@ -196,10 +208,44 @@ fn run_producer(
     Ok(())
 }
 
-fn write_run_sorted(run_path: &Path, buf_map: &mut RunMap, write_buf: usize) -> Result<()> {
+/// Extension of binary run files; text runs keep `.ndjson`.
+pub(super) const BINARY_RUN_EXTENSION: &str = "bin";
+
+fn run_file_name(idx: usize, format: IntermediateFormat) -> String {
+    match format {
+        IntermediateFormat::Text => format!("run_{idx:04}.ndjson"),
+        IntermediateFormat::Binary => format!("run_{idx:04}.{BINARY_RUN_EXTENSION}"),
+    }
+}
+
+fn write_run_sorted(
+    run_path: &Path,
+    buf_map: &mut RunMap,
+    write_buf: usize,
+    format: IntermediateFormat,
+) -> Result<()> {
     let mut keys: Vec<String> = buf_map.keys().cloned().collect();
     keys.sort_unstable();
 
+    if format == IntermediateFormat::Binary {
+        let f = crate::util::create_with_default_backoff(run_path)
+            .with_context(|| format!("create {}", run_path.display()))?;
+        let mut w = BufWriter::with_capacity(write_buf.max(8 * 1024), f);
+        for k in keys {
+            if let Some(lines) = buf_map.remove(&k) {
+                // One key frame and line count per key, then its lines.
+                crate::frames::write_frame(&mut w, k.as_bytes())?;
+                crate::frames::write_varint(&mut w, lines.len() as u64)?;
+                for s in lines {
+                    crate::frames::write_frame(&mut w, s.as_bytes())?;
+                }
+            }
+        }
+        w.flush()
+            .with_context(|| format!("flush {}", run_path.display()))?;
+        return Ok(());
+    }
+
     let mut w = NdjsonWriter::create(run_path, write_buf)
         .with_context(|| format!("create {}", run_path.display()))?;
 
@@ -0,0 +1,163 @@
+//! [`RetlError`], the error type returned by the operations on `RedditETL`
+//! and `ScanPlan`.
+//!
+//! Internally RETL works with `anyhow::Error` so context chains stay intact;
+//! each operation classifies its failure through `From<anyhow::Error>` as it
+//! returns. Callers branch on the variant, read the original error through
+//! [`RetlError::inner`] (e.g. to `downcast_ref` a [`ConfigBuildError`]), or
+//! keep using `?` into `anyhow::Result`.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::ConfigBuildError;
+use crate::corpus_manifest::CorpusManifestError;
+use crate::paths::PlanningError;
+use crate::pipeline_exec::PartialScanError;
+use crate::query::QueryBuildError;
+use crate::zstd_jsonl::{MalformedJsonError, ZstdDecodeContext};
+
+/// Broad failure category of a RETL operation. Every variant keeps the
+/// original error, so `{:#}` still renders the full context chain.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RetlError {
+    /// Invalid options or query, or a corpus layout / date range that plans
+    /// no files ([`ConfigBuildError`], [`QueryBuildError`], [`PlanningError`],
+    /// [`CorpusManifestError`]).
+    Config(anyhow::Error),
+    /// Filesystem or other I/O failure not tied to decoding a corpus file.
+    Io(anyhow::Error),
+    /// A corpus or spool file could not be decoded: a corrupt zstd frame or a
+    /// malformed JSONL line. `path` is the offending file.
+    Decode {
+        path: PathBuf,
+        source: anyhow::Error,
+    },
+    /// A caller callback stopped the operation by returning [`Cancelled`].
+    Cancelled(anyhow::Error),
+    /// Anything else.
+    Other(anyhow::Error),
+}
+
+impl RetlError {
+    /// The underlying error with its full context chain.
+    pub fn inner(&self) -> &anyhow::Error {
+        match self {
+            RetlError::Config(e)
+            | RetlError::Io(e)
+            | RetlError::Cancelled(e)
+            | RetlError::Other(e)
+            | RetlError::Decode { source: e, .. } => e,
+        }
+    }
+
+    /// Unwrap the underlying error, e.g. to add context and propagate it
+    /// through `anyhow` without nesting the classification.
+    pub fn into_inner(self) -> anyhow::Error {
+        match self {
+            RetlError::Config(e)
+            | RetlError::Io(e)
+            | RetlError::Cancelled(e)
+            | RetlError::Other(e)
+            | RetlError::Decode { source: e, .. } => e,
+        }
+    }
+
+    /// Path of the file that failed to decode, for [`RetlError::Decode`].
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            RetlError::Decode { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for RetlError {
+    fn from(err: anyhow::Error) -> Self {
+        if err.chain().any(|cause| cause.is::<Cancelled>()) {
+            return RetlError::Cancelled(err);
+        }
+        let is_config = err.chain().any(|cause| {
+            cause.is::<ConfigBuildError>()
+                || cause.is::<QueryBuildError>()
+                || cause.is::<PlanningError>()
+                || cause.is::<CorpusManifestError>()
+        });
+        if is_config {
+            return RetlError::Config(err);
+        }
+        if let Some(path) = decode_path(&err) {
+            return RetlError::Decode { path, source: err };
+        }
+        if err.chain().any(|cause| cause.is::<std::io::Error>()) {
+            return RetlError::Io(err);
+        }
+        RetlError::Other(err)
+    }
+}
+
+impl From<ConfigBuildError> for RetlError {
+    fn from(err: ConfigBuildError) -> Self {
+        RetlError::Config(err.into())
+    }
+}
+
+impl From<QueryBuildError> for RetlError {
+    fn from(err: QueryBuildError) -> Self {
+        RetlError::Config(err.into())
+    }
+}
+
+impl From<PlanningError> for RetlError {
+    fn from(err: PlanningError) -> Self {
+        RetlError::Config(err.into())
+    }
+}
+
+/// Context layers (`ZstdDecodeContext`) are only reachable through
+/// `downcast_ref`, not `chain()`, so check both.
+fn decode_path(err: &anyhow::Error) -> Option<PathBuf> {
+    if let Some(ctx) = err.downcast_ref::<ZstdDecodeContext>() {
+        return Some(ctx.path.clone());
+    }
+    err.chain().find_map(|cause| {
+        if let Some(e) = cause.downcast_ref::<MalformedJsonError>() {
+            Some(e.path.clone())
+        } else {
+            cause
+                .downcast_ref::<PartialScanError>()
+                .map(|e| e.path.clone())
+        }
+    })
+}
+
+impl fmt::Display for RetlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.inner(), f)
+    }
+}
+
+/// `Display` already shows the inner error's own message, so `source` starts
+/// at its cause; wrapping a `RetlError` in another `anyhow::Error` then
+/// renders each message once.
+impl std::error::Error for RetlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let inner: &(dyn std::error::Error + 'static) = self.inner().as_ref();
+        inner.source()
+    }
+}
+
+/// Stop signal for caller callbacks: return `Err(Cancelled.into())` from
+/// e.g. `ScanPlan::for_each_record` to end the operation early. The operation
+/// then fails with [`RetlError::Cancelled`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("operation cancelled by the caller")
+    }
+}
+
+impl std::error::Error for Cancelled {}
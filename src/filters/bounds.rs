@@ -1,4 +1,5 @@
 use crate::date::YearMonth;
+use crate::query::TimeField;
 use crate::zstd_jsonl::MinimalRecord;
 use time::{Date, OffsetDateTime};
 
@@ -31,15 +32,22 @@ pub fn bounds_tuple(start: Option<YearMonth>, end: Option<YearMonth>) -> Option<
 }
 
 pub fn within_bounds(min: &MinimalRecord, bounds: Option<DateBounds>) -> bool {
+    within_bounds_on(min, bounds, TimeField::Created)
+}
+
+/// [`within_bounds`] against the timestamp selected by `field` instead of
+/// `created_utc`. Records lacking that timestamp are rejected when bounds are
+/// active.
+pub fn within_bounds_on(min: &MinimalRecord, bounds: Option<DateBounds>, field: TimeField) -> bool {
     let Some(bounds) = bounds else {
         return true;
     };
 
-    let Some(ts) = min.created_utc else {
+    let Some(ts) = min.timestamp(field) else {
         return false;
     };
 
-    // A `created_utc` outside the representable `OffsetDateTime` range (e.g. an
+    // A timestamp outside the representable `OffsetDateTime` range (e.g. an
     // absurd magnitude that still coerces to a valid i64) cannot be proven to
     // fall inside the requested window. Reject it as a non-match rather than
     // folding it to 1970, mirroring the TimestampBounds fast path.
@@ -121,6 +129,28 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn within_bounds_on_reads_the_selected_time_field() {
+        // Created 2006-01, edited 2012-03, retrieved 2015-07.
+        let r = parse_minimal(
+            r#"{"created_utc":1136073600,"edited":1331078400,"retrieved_on":1436486400}"#,
+        )
+        .expect("valid minimal record");
+        let bounds = bounds_tuple(Some(ym(2015, 1)), Some(ym(2015, 12)));
+        assert!(!within_bounds(&r, bounds));
+        assert!(!within_bounds_on(&r, bounds, TimeField::Edited));
+        assert!(within_bounds_on(&r, bounds, TimeField::RetrievedOn));
+
+        // A boolean `edited` has no timestamp and fails any active bound.
+        let unedited = parse_minimal(r#"{"created_utc":1136073600,"edited":false}"#).unwrap();
+        assert_eq!(unedited.edited, None);
+        assert!(!within_bounds_on(
+            &unedited,
+            bounds_tuple(None, Some(ym(2020, 12))),
+            TimeField::Edited
+        ));
+    }
+
     #[test]
     fn ym_from_epoch_checked_rejects_absurd_timestamp() {
         assert!(ym_from_epoch_checked(99_999_999_999_999).is_none());
@@ -1,5 +1,5 @@
 use crate::paths::FileKind;
-use crate::query::QuerySpec;
+use crate::query::{QuerySpec, RecordFilter};
 use serde_json::Value;
 
 use super::ci::list_contains_ci;
@@ -21,4 +21,5 @@ pub fn matches_full(val: &Value, kind: FileKind, q: &QuerySpec) -> bool {
     q.json_predicates
         .iter()
         .all(|predicate| predicate.matches(val))
+        && q.filter.as_ref().is_none_or(|RecordFilter(f)| f(val))
 }
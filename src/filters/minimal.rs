@@ -4,7 +4,8 @@ use crate::zstd_jsonl::MinimalRecord;
 
 use super::ci::list_contains_ci;
 use super::text::{
-    keyword_all_matches_record, keyword_any_matches_record, record_text_regex_matches,
+    keyword_all_matches_record, keyword_any_matches_record, record_body_excluded,
+    record_text_regex_matches,
 };
 use super::url::record_contains_url;
 
@@ -31,6 +32,15 @@ pub fn matches_minimal(
     if !q.id_filter_matches(record_kind, min.id.as_deref()) {
         return false;
     }
+    if q.top_level_comments_only
+        && kind == FileKind::Comment
+        && !min
+            .parent_id
+            .as_deref()
+            .is_some_and(|p| p.starts_with("t3_"))
+    {
+        return false;
+    }
 
     if let Some(targets) = targets_opt {
         match min.subreddit.as_deref() {
@@ -40,6 +50,23 @@ pub fn matches_minimal(
     } else if min.subreddit.is_none() {
         return false;
     }
+    // Deny-list is checked after the allow-list so it wins when both name
+    // the same subreddit.
+    if let (Some(deny), Some(s)) = (&q.subreddits_out, min.subreddit.as_deref()) {
+        if list_contains_ci(deny, s) {
+            return false;
+        }
+    }
+    if let (Some(sample), Some(s)) = (q.subreddit_sample, min.subreddit.as_deref()) {
+        if !sample.keeps(s) {
+            return false;
+        }
+    }
+    if let Some(ratio) = q.source_ratio {
+        if !ratio.keeps(record_kind, min.id.as_deref()) {
+            return false;
+        }
+    }
 
     if let Some(a) = min.author.as_deref() {
         if q.filter_pseudo_users
@@ -54,7 +81,11 @@ pub fn matches_minimal(
                 return false;
             }
         }
-        if let Some(ref allow) = q.authors_in {
+        if let Some(set) = q.authors_in_set() {
+            if !set.contains(a) {
+                return false;
+            }
+        } else if let Some(ref allow) = q.authors_in {
             if !list_contains_ci(allow, a) {
                 return false;
             }
@@ -82,9 +113,9 @@ pub fn matches_minimal(
     }
 
     if q.timestamp_bounds.is_active() {
-        match min.created_utc {
+        match min.timestamp(q.time_field) {
             Some(ts) if q.timestamp_bounds.contains(ts) => {}
-            // Exact timestamp filters reject created_utc that is missing or
+            // Exact timestamp filters reject a time field that is missing or
             // not a number (string- and float-encoded numbers are coerced).
             _ => return false,
         }
@@ -126,6 +157,9 @@ pub fn matches_minimal(
             return false;
         }
     }
+    if (q.exclude_bot_bodies || !q.body_regex_exclude.is_empty()) && record_body_excluded(min, q) {
+        return false;
+    }
     if q.contains_url == Some(true) && !record_contains_url(min) {
         return false;
     }
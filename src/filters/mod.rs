@@ -11,7 +11,7 @@ mod url;
 
 use crate::zstd_jsonl::MinimalRecord;
 
-pub use self::bounds::{bounds_tuple, within_bounds, ym_from_epoch, DateBounds};
+pub use self::bounds::{bounds_tuple, within_bounds, within_bounds_on, ym_from_epoch, DateBounds};
 pub use self::full::matches_full;
 pub use self::minimal::{matches_minimal, matches_subreddit_basic};
 pub use self::targets::resolve_target_subs_from;
@@ -1,3 +1,4 @@
+use crate::query::QuerySpec;
 use crate::zstd_jsonl::MinimalRecord;
 
 use super::any_text_field_matches;
@@ -99,3 +100,22 @@ pub(super) fn keyword_all_matches_record(
 pub(super) fn record_text_regex_matches(min: &MinimalRecord, re: &regex::Regex) -> bool {
     any_text_field_matches(min, |s| re.is_match(s))
 }
+
+/// Default bot-footer patterns, compiled once per process.
+fn bot_body_set() -> &'static regex::RegexSet {
+    static SET: std::sync::OnceLock<regex::RegexSet> = std::sync::OnceLock::new();
+    SET.get_or_init(|| {
+        regex::RegexSet::new(crate::util::default_bot_body_patterns())
+            .expect("default bot body patterns are valid")
+    })
+}
+
+/// Whether `body` or `selftext` hits a body exclusion: the default bot
+/// footers when `exclude_bot_bodies` is set, or any `body_regex_exclude`.
+pub(super) fn record_body_excluded(min: &MinimalRecord, q: &QuerySpec) -> bool {
+    let mut excluded = |s: &str| {
+        (q.exclude_bot_bodies && bot_body_set().is_match(s))
+            || q.body_regex_exclude.iter().any(|re| re.is_match(s))
+    };
+    min.body.as_deref().is_some_and(&mut excluded) || min.selftext.as_deref().is_some_and(excluded)
+}
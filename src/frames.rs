@@ -0,0 +1,396 @@
+//! Length-prefixed binary framing for scratch intermediates.
+//!
+//! A frame is an unsigned LEB128 varint byte length followed by that many
+//! bytes, so short fields cost one length byte. Dedupe runs and KV shards use
+//! frames instead of text lines when
+//! [`IntermediateFormat::Binary`](crate::IntermediateFormat::Binary) is
+//! selected, and a reader gets each field back without scanning for
+//! delimiters or re-parsing JSON. That encoding is private to scratch files.
+//!
+//! The one public frame format is the record stream of
+//! [`ScanPlan::extract_framed_to_writer`](crate::ScanPlan::extract_framed_to_writer):
+//! a big-endian prefix, the network byte order length-prefixed protocols
+//! expect, with records capped at [`DEFAULT_MAX_LINE_BYTES`] so that
+//! [`read_framed`] can read back everything the writer sends.
+
+use crate::ndjson::DEFAULT_MAX_LINE_BYTES;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Largest varint this module writes: a `u64` needs at most 10 LEB128 bytes.
+const MAX_VARINT_BYTES: usize = 10;
+
+/// A LEB128-encoded `u64`, for callers that assemble a record from slices
+/// (see `LineShardWriters::write_parts`).
+pub(crate) struct Varint {
+    buf: [u8; MAX_VARINT_BYTES],
+    len: usize,
+}
+
+impl Varint {
+    pub(crate) fn new(mut value: u64) -> Self {
+        let mut buf = [0u8; MAX_VARINT_BYTES];
+        let mut len = 0;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                buf[len] = byte;
+                len += 1;
+                return Self { buf, len };
+            }
+            buf[len] = byte | 0x80;
+            len += 1;
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+/// Map an `i64` onto a `u64` so values near zero, negative or not, get short
+/// varints.
+pub(crate) fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of [`zigzag`].
+pub(crate) fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Append one frame holding `bytes`.
+pub(crate) fn write_frame<W: Write + ?Sized>(w: &mut W, bytes: &[u8]) -> io::Result<()> {
+    w.write_all(frame_len_prefix(bytes)?.as_bytes())?;
+    w.write_all(bytes)
+}
+
+/// Append one varint, e.g. a zigzag-encoded value or a record count.
+pub(crate) fn write_varint<W: Write + ?Sized>(w: &mut W, value: u64) -> io::Result<()> {
+    w.write_all(Varint::new(value).as_bytes())
+}
+
+/// Length prefix for a frame of `bytes`.
+pub(crate) fn frame_len_prefix(bytes: &[u8]) -> io::Result<Varint> {
+    frame_len(bytes).map(Varint::new)
+}
+
+/// Byte length of a frame of `bytes`. Lengths stay within `u32` like the
+/// big-endian record frames.
+pub(crate) fn frame_len(bytes: &[u8]) -> io::Result<u64> {
+    u32::try_from(bytes.len()).map(u64::from).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "frame of {} bytes exceeds the u32 length prefix",
+                bytes.len()
+            ),
+        )
+    })
+}
+
+/// Append a big-endian length-prefixed frame holding `bytes` to `out`.
+/// Records above [`DEFAULT_MAX_LINE_BYTES`], which [`read_framed`] would
+/// reject, are `io::ErrorKind::InvalidInput` even when a run raises
+/// `max_line_bytes`.
+pub(crate) fn write_be_frame(out: &mut Vec<u8>, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|&len| len as usize <= DEFAULT_MAX_LINE_BYTES)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "record of {} bytes is above the {DEFAULT_MAX_LINE_BYTES}-byte frame cap \
+                     read_framed accepts",
+                    bytes.len()
+                ),
+            )
+        })?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Read the next record written by
+/// [`ScanPlan::extract_framed_to_writer`](crate::ScanPlan::extract_framed_to_writer):
+/// a 4-byte big-endian length, then that many bytes of JSON.
+///
+/// Returns `Ok(None)` at a clean end of input. Input that ends inside a
+/// frame is `io::ErrorKind::UnexpectedEof`; a length above
+/// [`DEFAULT_MAX_LINE_BYTES`] or a body that is not JSON is
+/// `io::ErrorKind::InvalidData`.
+pub fn read_framed<R: io::Read>(r: &mut R) -> io::Result<Option<serde_json::Value>> {
+    let mut len = [0u8; 4];
+    if !read_exact_or_eof(r, &mut len)? {
+        return Ok(None);
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > DEFAULT_MAX_LINE_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("record frame claims {len} bytes, above the {DEFAULT_MAX_LINE_BYTES}-byte cap"),
+        ));
+    }
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body).map_err(|e| {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            io::Error::new(e.kind(), "input ends inside a record frame")
+        } else {
+            e
+        }
+    })?;
+    serde_json::from_slice(&body).map(Some).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("record frame is not JSON: {e}"),
+        )
+    })
+}
+
+/// Read the next frame into `buf`, replacing its contents.
+///
+/// Returns `Ok(false)` at a clean end of input (no bytes before the next
+/// length prefix). A file that ends inside a frame is
+/// `io::ErrorKind::UnexpectedEof`, and a length above
+/// [`DEFAULT_MAX_LINE_BYTES`] is `io::ErrorKind::InvalidData`, so a
+/// truncated or foreign file fails loudly instead of allocating whatever a
+/// garbage prefix claims.
+pub(crate) fn read_frame<R: BufRead>(
+    r: &mut R,
+    buf: &mut Vec<u8>,
+    path: &Path,
+) -> io::Result<bool> {
+    let Some(len) = read_varint(r, path)? else {
+        return Ok(false);
+    };
+    read_frame_body(r, len, buf, path)?;
+    Ok(true)
+}
+
+/// Read the `len` bytes of a frame whose length prefix the caller already
+/// decoded, applying the same cap as [`read_frame`].
+pub(crate) fn read_frame_body<R: BufRead>(
+    r: &mut R,
+    len: u64,
+    buf: &mut Vec<u8>,
+    path: &Path,
+) -> io::Result<()> {
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|&len| len <= DEFAULT_MAX_LINE_BYTES)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "binary frame in {} claims {len} bytes, above the {DEFAULT_MAX_LINE_BYTES}-byte cap; \
+                     the file is corrupt or not in the binary intermediate format",
+                    path.display()
+                ),
+            )
+        })?;
+    buf.clear();
+    buf.resize(len, 0);
+    r.read_exact(buf).map_err(|e| truncated(e, path))
+}
+
+/// [`read_frame`] for frames that hold UTF-8 text, reusing `out`'s
+/// allocation the same way `read_line_capped` does.
+pub(crate) fn read_str_frame<R: BufRead>(
+    r: &mut R,
+    out: &mut String,
+    path: &Path,
+) -> io::Result<bool> {
+    let mut bytes = std::mem::take(out).into_bytes();
+    if !read_frame(r, &mut bytes, path)? {
+        bytes.clear();
+        // An empty Vec is valid UTF-8; this only hands the allocation back.
+        *out = String::from_utf8(bytes).unwrap_or_default();
+        return Ok(false);
+    }
+    *out = utf8_frame(bytes, path)?;
+    Ok(true)
+}
+
+/// [`read_frame_body`] for UTF-8 text.
+pub(crate) fn read_str_frame_body<R: BufRead>(
+    r: &mut R,
+    len: u64,
+    out: &mut String,
+    path: &Path,
+) -> io::Result<()> {
+    let mut bytes = std::mem::take(out).into_bytes();
+    read_frame_body(r, len, &mut bytes, path)?;
+    *out = utf8_frame(bytes, path)?;
+    Ok(())
+}
+
+fn utf8_frame(bytes: Vec<u8>, path: &Path) -> io::Result<String> {
+    String::from_utf8(bytes).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("binary frame in {} is not valid UTF-8: {e}", path.display()),
+        )
+    })
+}
+
+/// Read one LEB128 varint, or `Ok(None)` if the input is already at EOF.
+/// EOF inside the varint is `io::ErrorKind::UnexpectedEof`, and a varint
+/// longer than a `u64` is `io::ErrorKind::InvalidData`.
+pub(crate) fn read_varint<R: BufRead>(r: &mut R, path: &Path) -> io::Result<Option<u64>> {
+    let mut value = 0u64;
+    for i in 0..MAX_VARINT_BYTES {
+        let mut byte = [0u8; 1];
+        if !read_exact_or_eof(r, &mut byte)? {
+            if i == 0 {
+                return Ok(None);
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "input ends inside a binary varint",
+            ));
+        }
+        let bits = u64::from(byte[0] & 0x7f);
+        if i == MAX_VARINT_BYTES - 1 && bits > 1 {
+            break;
+        }
+        value |= bits << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "binary varint in {} overflows a u64; the file is corrupt or not in the \
+             binary intermediate format",
+            path.display()
+        ),
+    ))
+}
+
+/// A varint that must follow a field already read: EOF here is truncation.
+pub(crate) fn read_required_varint<R: BufRead>(r: &mut R, path: &Path) -> io::Result<u64> {
+    read_varint(r, path)?.ok_or_else(|| truncated(io::ErrorKind::UnexpectedEof.into(), path))
+}
+
+/// Fill `out` completely, or return `Ok(false)` if the input is already at
+/// EOF. EOF after a partial read is an error.
+fn read_exact_or_eof<R: io::Read>(r: &mut R, out: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < out.len() {
+        match r.read(&mut out[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "input ends inside a record frame length prefix",
+                ))
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+fn truncated(e: io::Error, path: &Path) -> io::Error {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "binary intermediate {} is truncated mid-record",
+                path.display()
+            ),
+        )
+    } else {
+        e
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn frames_round_trip_and_stop_cleanly_at_eof() {
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, b"alice").unwrap();
+        write_frame(&mut bytes, b"").unwrap();
+        write_frame(&mut bytes, "tab\tnew\nline".as_bytes()).unwrap();
+
+        let path = Path::new("mem");
+        let mut r = Cursor::new(bytes);
+        let mut s = String::new();
+        assert!(read_str_frame(&mut r, &mut s, path).unwrap());
+        assert_eq!(s, "alice");
+        assert!(read_str_frame(&mut r, &mut s, path).unwrap());
+        assert_eq!(s, "");
+        assert!(read_str_frame(&mut r, &mut s, path).unwrap());
+        assert_eq!(s, "tab\tnew\nline");
+        assert!(!read_str_frame(&mut r, &mut s, path).unwrap());
+    }
+
+    #[test]
+    fn truncated_and_oversized_frames_are_errors() {
+        let path = Path::new("mem");
+        let mut buf = Vec::new();
+
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, b"alice").unwrap();
+        bytes.truncate(bytes.len() - 2);
+        let err = read_frame(&mut Cursor::new(bytes), &mut buf, path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let err = read_frame(&mut Cursor::new(vec![0x80u8]), &mut buf, path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let huge = Varint::new(u64::from(u32::MAX));
+        let err =
+            read_frame(&mut Cursor::new(huge.as_bytes().to_vec()), &mut buf, path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = read_varint(&mut Cursor::new(vec![0xffu8; 11]), path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn varints_round_trip_and_short_values_take_one_byte() {
+        let path = Path::new("mem");
+        for value in [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            let encoded = Varint::new(value);
+            let mut r = Cursor::new(encoded.as_bytes().to_vec());
+            assert_eq!(read_varint(&mut r, path).unwrap(), Some(value));
+            assert_eq!(read_varint(&mut r, path).unwrap(), None);
+        }
+        assert_eq!(Varint::new(127).as_bytes().len(), 1);
+        assert_eq!(Varint::new(u64::MAX).as_bytes().len(), MAX_VARINT_BYTES);
+        for value in [0, 1, -1, 63, -64, i64::MAX, i64::MIN] {
+            assert_eq!(unzigzag(zigzag(value)), value);
+        }
+        assert_eq!(Varint::new(zigzag(-64)).as_bytes().len(), 1);
+    }
+
+    #[test]
+    fn big_endian_frames_round_trip_and_share_the_read_cap() {
+        let mut bytes = Vec::new();
+        write_be_frame(&mut bytes, br#"{"id":"a"}"#).unwrap();
+        let mut r = Cursor::new(bytes);
+        assert_eq!(
+            read_framed(&mut r).unwrap(),
+            Some(serde_json::json!({"id": "a"}))
+        );
+        assert_eq!(read_framed(&mut r).unwrap(), None);
+
+        let over = (DEFAULT_MAX_LINE_BYTES as u32 + 1).to_be_bytes().to_vec();
+        let err = read_framed(&mut Cursor::new(over)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err =
+            write_be_frame(&mut Vec::new(), &vec![b' '; DEFAULT_MAX_LINE_BYTES + 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -1,22 +1,81 @@
 use crate::concurrency::for_each_file_limited;
+use crate::config::RunSettings;
+use crate::error::RetlError;
+use crate::ndjson::read_line_capped;
 use crate::paths::{
-    discover_sources_checked, log_missing_month_warnings, plan_files_checked, FileJob,
+    discover_roots_checked, log_missing_month_warnings, plan_files_checked, FileJob,
 };
 use crate::progress::make_count_progress;
 use crate::util::with_thread_pool;
+use crate::zstd_jsonl::{is_plain_jsonl, open_zstd_decoder};
 use crate::RedditETL;
-use anyhow::{Context, Result};
-use std::io::{self, Read};
+use anyhow::{anyhow, Context, Result};
+use std::cell::Cell;
+use std::io::{self, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::Mutex;
-use zstd::stream::read::Decoder;
+use std::time::Duration;
 
-/// Prevents "Frame requires too much memory" on large Reddit dumps.
-const ZSTD_WINDOW_LOG_MAX: u32 = 31;
 const ZERO_SAMPLE_BYTES_ERROR: &str =
     "--sample-bytes must be > 0; use --mode full for complete validation";
 
+/// Failure message recorded for a file that overran
+/// [`ETLOptions::integrity_file_timeout`](crate::ETLOptions::integrity_file_timeout).
+pub const INTEGRITY_TIMEOUT_ERROR: &str = "timeout";
+
+/// `Read` adapter over the raw file that fails once `cancel` is set, so a
+/// decode abandoned by its deadline stops at the next chunk instead of
+/// running to EOF. It sets `source_failed` whenever it returns an error, which
+/// tells a failed read of the file apart from content that failed to decode.
+struct CancellableRead<'a, R> {
+    inner: R,
+    cancel: &'a AtomicBool,
+    source_failed: &'a Cell<bool>,
+}
+
+impl<R: Read> Read for CancellableRead<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let res = if self.cancel.load(Ordering::Acquire) {
+            Err(io::Error::other("integrity check cancelled"))
+        } else {
+            self.inner.read(buf)
+        };
+        if res
+            .as_ref()
+            .is_err_and(|e| e.kind() != io::ErrorKind::Interrupted)
+        {
+            self.source_failed.set(true);
+        }
+        res
+    }
+}
+
+/// A file whose bytes were read but did not decode: a zstd frame or checksum
+/// error, or a plain line over the cap or not UTF-8. Only failures carrying
+/// this marker are handed to a [`CorruptAction`]; open, permission and other
+/// I/O errors leave the file alone. `Display` forwards the decode error.
+#[derive(Debug)]
+struct CorruptContentError(io::Error);
+
+impl std::fmt::Display for CorruptContentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for CorruptContentError {}
+
+/// Classify an error from decoding a file read through a [`CancellableRead`].
+fn decode_error(e: io::Error, source_failed: &Cell<bool>) -> anyhow::Error {
+    if source_failed.get() {
+        e.into()
+    } else {
+        CorruptContentError(e).into()
+    }
+}
+
 /// Whether a [`quick_validate_zst`] sample covered the whole file or only a
 /// decompressed prefix.
 ///
@@ -47,15 +106,28 @@ pub enum QuickOutcome {
 /// therefore validated as thoroughly as [`validate_zst_full`] would. Use
 /// [`validate_zst_full`] directly when you need that guarantee unconditionally.
 pub fn quick_validate_zst(path: &Path, max_decompressed_bytes: u64) -> Result<QuickOutcome> {
+    quick_validate_zst_cancellable(path, max_decompressed_bytes, &AtomicBool::new(false))
+}
+
+fn quick_validate_zst_cancellable(
+    path: &Path,
+    max_decompressed_bytes: u64,
+    cancel: &AtomicBool,
+) -> Result<QuickOutcome> {
     if max_decompressed_bytes == 0 {
         anyhow::bail!(ZERO_SAMPLE_BYTES_ERROR);
     }
 
-    let file = crate::util::open_with_default_backoff(path)?;
-    let mut decoder = Decoder::new(file)?;
-    decoder.window_log_max(ZSTD_WINDOW_LOG_MAX)?;
+    let source_failed = Cell::new(false);
+    let file = CancellableRead {
+        inner: crate::util::open_with_default_backoff(path)?,
+        cancel,
+        source_failed: &source_failed,
+    };
+    let decoder = open_zstd_decoder(file, path)?;
     let mut limited = decoder.take(max_decompressed_bytes);
-    let decoded = io::copy(&mut limited, &mut io::sink())?;
+    let decoded =
+        io::copy(&mut limited, &mut io::sink()).map_err(|e| decode_error(e, &source_failed))?;
     if decoded < max_decompressed_bytes {
         // The decompressed stream hit EOF before the sample budget — the
         // whole frame (and its trailing checksum) was decoded and verified.
@@ -66,7 +138,10 @@ pub fn quick_validate_zst(path: &Path, max_decompressed_bytes: u64) -> Result<Qu
     // checksum verified by the EOF read) from one that still has data — and
     // therefore unverified trailing content — past the sampled prefix.
     let mut decoder = limited.into_inner();
-    if decoder.read(&mut [0u8; 1])? == 0 {
+    let probed = decoder
+        .read(&mut [0u8; 1])
+        .map_err(|e| decode_error(e, &source_failed))?;
+    if probed == 0 {
         Ok(QuickOutcome::FullyDecoded)
     } else {
         Ok(QuickOutcome::PrefixOnly)
@@ -83,11 +158,60 @@ pub fn quick_validate_zst(path: &Path, max_decompressed_bytes: u64) -> Result<Qu
 /// is needed. (Bit-flips inside compressed payloads typically also fail
 /// earlier with a frame/entropy decode error.)
 pub fn validate_zst_full(path: &Path) -> Result<()> {
-    let file = crate::util::open_with_default_backoff(path)?;
-    let mut decoder = Decoder::new(file)?;
-    decoder.window_log_max(ZSTD_WINDOW_LOG_MAX)?;
-    io::copy(&mut decoder, &mut io::sink())?;
-    Ok(())
+    validate_zst_full_stats(path).map(|_| ())
+}
+
+/// `Write` sink that counts decoded bytes and newline-terminated lines.
+#[derive(Default)]
+struct LineCountSink {
+    bytes: u64,
+    lines: u64,
+    ends_with_newline: bool,
+}
+
+impl Write for LineCountSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(&last) = buf.last() {
+            self.bytes += buf.len() as u64;
+            self.lines += buf.iter().filter(|&&b| b == b'\n').count() as u64;
+            self.ends_with_newline = last == b'\n';
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// FULL check that also inventories the file: decodes to EOF exactly like
+/// [`validate_zst_full`] and returns `(decoded_bytes, lines)`. A final line
+/// without a trailing newline still counts as a line.
+pub fn validate_zst_full_stats(path: &Path) -> Result<(u64, u64)> {
+    validate_zst_full_stats_cancellable(path, &AtomicBool::new(false))
+}
+
+fn validate_zst_full_stats_cancellable(path: &Path, cancel: &AtomicBool) -> Result<(u64, u64)> {
+    let source_failed = Cell::new(false);
+    let file = CancellableRead {
+        inner: crate::util::open_with_default_backoff(path)?,
+        cancel,
+        source_failed: &source_failed,
+    };
+    let mut decoder = open_zstd_decoder(file, path)?;
+    let mut sink = LineCountSink::default();
+    io::copy(&mut decoder, &mut sink).map_err(|e| decode_error(e, &source_failed))?;
+    let trailing = u64::from(sink.bytes > 0 && !sink.ends_with_newline);
+    Ok((sink.bytes, sink.lines + trailing))
+}
+
+/// Decoded size of one file that passed an [`IntegrityMode::Full`] check.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityFileStats {
+    /// Decompressed bytes in the file.
+    pub decoded_bytes: u64,
+    /// Lines (records) in the file.
+    pub lines: u64,
 }
 
 /// Mode for integrity checks.
@@ -110,15 +234,223 @@ fn validate_integrity_mode(mode: IntegrityMode) -> Result<()> {
     Ok(())
 }
 
-fn validate_integrity_job(job: &FileJob, mode: IntegrityMode) -> Result<()> {
+/// Read a plain `.jsonl` month line by line, failing on a line longer than
+/// `max_line_bytes` or one that is not UTF-8. `sample_bytes` stops the read
+/// after that many bytes, like quick mode's decoded prefix. Returns
+/// `(bytes, lines)` read.
+fn validate_plain_jsonl_cancellable(
+    path: &Path,
+    sample_bytes: Option<u64>,
+    max_line_bytes: usize,
+    cancel: &AtomicBool,
+) -> Result<(u64, u64)> {
+    let source_failed = Cell::new(false);
+    let mut reader = BufReader::new(CancellableRead {
+        inner: crate::util::open_with_default_backoff(path)?,
+        cancel,
+        source_failed: &source_failed,
+    });
+    let mut line = String::new();
+    let (mut bytes, mut lines) = (0_u64, 0_u64);
+    while sample_bytes.is_none_or(|cap| bytes < cap) {
+        let n = read_line_capped(&mut reader, &mut line, max_line_bytes, path)
+            .map_err(|e| decode_error(e, &source_failed))?;
+        if n == 0 {
+            break;
+        }
+        bytes += n as u64;
+        lines += 1;
+    }
+    Ok((bytes, lines))
+}
+
+fn validate_integrity_job(
+    job: &FileJob,
+    mode: IntegrityMode,
+    max_line_bytes: usize,
+    cancel: &AtomicBool,
+) -> Result<Option<IntegrityFileStats>> {
+    if is_plain_jsonl(&job.path) {
+        let sample_bytes = match mode {
+            IntegrityMode::Quick { sample_bytes } => Some(sample_bytes),
+            IntegrityMode::Full => None,
+        };
+        let (decoded_bytes, lines) =
+            validate_plain_jsonl_cancellable(&job.path, sample_bytes, max_line_bytes, cancel)?;
+        return Ok(sample_bytes.is_none().then_some(IntegrityFileStats {
+            decoded_bytes,
+            lines,
+        }));
+    }
     match mode {
         // Quick mode's prefix-vs-full distinction is surfaced by
         // [`quick_validate_zst`] for direct callers; the corpus runner only
         // cares whether the file decoded without error.
         IntegrityMode::Quick { sample_bytes } => {
-            quick_validate_zst(&job.path, sample_bytes).map(|_| ())
+            quick_validate_zst_cancellable(&job.path, sample_bytes, cancel).map(|_| None)
+        }
+        IntegrityMode::Full => {
+            let (decoded_bytes, lines) = validate_zst_full_stats_cancellable(&job.path, cancel)?;
+            Ok(Some(IntegrityFileStats {
+                decoded_bytes,
+                lines,
+            }))
         }
-        IntegrityMode::Full => validate_zst_full(&job.path),
+    }
+}
+
+/// Run `validate_job` for one file, giving up after `timeout`.
+///
+/// The validation runs on a scoped worker thread that reports back over a
+/// channel. When `recv_timeout` expires the worker's cancel flag is set, its
+/// decoder fails on the next read, and the scope joins it before returning,
+/// so an abandoned decode never outlives the run. The file is then reported
+/// as [`INTEGRITY_TIMEOUT_ERROR`].
+fn validate_job_with_deadline<V>(
+    job: &FileJob,
+    mode: IntegrityMode,
+    timeout: Option<Duration>,
+    validate_job: &V,
+) -> Result<Option<IntegrityFileStats>>
+where
+    V: Fn(&FileJob, IntegrityMode, &AtomicBool) -> Result<Option<IntegrityFileStats>> + Sync,
+{
+    let cancel = AtomicBool::new(false);
+    let Some(timeout) = timeout else {
+        return validate_job(job, mode, &cancel);
+    };
+    let run_settings = RunSettings::current();
+    std::thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        let cancel = &cancel;
+        scope.spawn(move || {
+            let _run_settings = RunSettings::enter(run_settings);
+            // The receiver is gone only after a timeout; the result no
+            // longer matters then.
+            let _ = tx.send(validate_job(job, mode, cancel));
+        });
+        match rx.recv_timeout(timeout) {
+            Ok(res) => res,
+            Err(RecvTimeoutError::Timeout) => {
+                cancel.store(true, Ordering::Release);
+                Err(anyhow!(INTEGRITY_TIMEOUT_ERROR))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(anyhow!(
+                "integrity worker for {} exited without a result",
+                job.path.display()
+            )),
+        }
+    })
+}
+
+/// What a corpus integrity run does with a file that fails its check, set
+/// with [`RedditETL::integrity_on_corrupt`](crate::RedditETL::integrity_on_corrupt).
+///
+/// Only files whose bytes were read but failed to decode (a zstd frame or
+/// checksum error, or a plain `.jsonl` line over the cap or not UTF-8) are
+/// acted on. Open, permission and other I/O errors, and
+/// [`INTEGRITY_TIMEOUT_ERROR`], are only reported: they are not evidence of a
+/// corrupt file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorruptAction {
+    /// Report the failure and leave the file in place (default).
+    #[default]
+    Report,
+    /// Move the file into this directory under its own file name. The
+    /// directory is created as needed; an existing file of the same name is
+    /// never overwritten.
+    Quarantine(PathBuf),
+    /// Delete the file.
+    Delete,
+}
+
+/// What a [`CorruptAction`] did to one failed file, listed in
+/// [`IntegrityReport::corrupt_actions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorruptOutcome {
+    /// Moved to this path.
+    Quarantined(PathBuf),
+    /// Removed from disk.
+    Deleted,
+    /// The move or delete failed with this error; the file is still in
+    /// place.
+    Failed(String),
+}
+
+/// Apply `action` to the corrupt file at `path`. `None` for
+/// [`CorruptAction::Report`].
+fn apply_corrupt_action(path: &Path, action: &CorruptAction) -> Option<CorruptOutcome> {
+    let result = match action {
+        CorruptAction::Report => return None,
+        CorruptAction::Quarantine(dir) => {
+            quarantine_file(path, dir).map(CorruptOutcome::Quarantined)
+        }
+        CorruptAction::Delete => {
+            crate::util::remove_with_default_backoff(path).map(|()| CorruptOutcome::Deleted)
+        }
+    };
+    Some(result.unwrap_or_else(|e| {
+        tracing::warn!(path=%path.display(), error=%e, "Could not act on corrupt file; left in place");
+        CorruptOutcome::Failed(format!("{e:#}"))
+    }))
+}
+
+/// Move `path` to `dir/<file name>` without replacing a file already there:
+/// a hard link where the filesystem allows one. Across devices the file is
+/// copied to a private `.inprogress` name in `dir`, synced, and only then
+/// linked to its final name, so a crash mid-copy never leaves a truncated
+/// file under the quarantine name. The link fails if the destination exists,
+/// so the check and the move cannot race. The original is removed last.
+fn quarantine_file(path: &Path, dir: &Path) -> Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{} has no file name", path.display()))?;
+    crate::util::create_dir_all_with_default_backoff(dir)
+        .with_context(|| format!("create quarantine dir {}", dir.display()))?;
+    let dest = dir.join(name);
+    match std::fs::hard_link(path, &dest) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            return Err(quarantine_exists(&dest));
+        }
+        Err(_) => {
+            let staged = crate::atomic_write::unique_inprogress_path(dir, &dest)?;
+            let published = copy_synced(path, &staged)
+                .with_context(|| format!("copy {} to {}", path.display(), staged.display()))
+                .and_then(|()| publish_no_clobber(&staged, &dest));
+            let _ = crate::util::remove_with_default_backoff(&staged);
+            published?;
+        }
+    }
+    crate::util::remove_with_default_backoff(path)
+        .with_context(|| format!("remove {} after quarantining it", path.display()))?;
+    Ok(dest)
+}
+
+fn quarantine_exists(dest: &Path) -> anyhow::Error {
+    anyhow!("{} already exists in quarantine", dest.display())
+}
+
+/// Copy `src` into the new file `staged` and sync it to disk.
+fn copy_synced(src: &Path, staged: &Path) -> io::Result<()> {
+    let mut out = crate::util::create_new_with_default_backoff(staged)?;
+    let mut src = crate::util::open_with_default_backoff(src)?;
+    io::copy(&mut src, &mut out)?;
+    out.sync_all()
+}
+
+/// Give the synced copy `staged` its final name `dest`, failing if `dest`
+/// exists. Filesystems without hard links fall back to a rename after an
+/// existence check; the caller removes `staged` either way.
+fn publish_no_clobber(staged: &Path, dest: &Path) -> Result<()> {
+    match std::fs::hard_link(staged, dest) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(quarantine_exists(dest)),
+        Err(_) if dest.symlink_metadata().is_ok() => Err(quarantine_exists(dest)),
+        Err(_) => std::fs::rename(staged, dest)
+            .with_context(|| format!("rename {} to {}", staged.display(), dest.display())),
     }
 }
 
@@ -150,6 +482,14 @@ pub struct IntegrityReport {
     /// Failures observed beyond [`MAX_RETAINED_FAILURES`] and dropped from
     /// `failures` to keep memory bounded. `0` when nothing was dropped.
     pub dropped: usize,
+    /// Decoded size of every file that passed, sorted by path. Only
+    /// [`IntegrityMode::Full`] decodes whole files, so Quick runs leave this
+    /// empty.
+    pub file_stats: Vec<(PathBuf, IntegrityFileStats)>,
+    /// What [`CorruptAction::Quarantine`] or [`CorruptAction::Delete`] did
+    /// to each failed file, sorted by path. Empty under the default
+    /// [`CorruptAction::Report`].
+    pub corrupt_actions: Vec<(PathBuf, CorruptOutcome)>,
 }
 
 impl IntegrityReport {
@@ -165,19 +505,35 @@ impl IntegrityReport {
     }
 }
 
-fn run_integrity_checks<F, V>(
-    files: &[FileJob],
+/// Per-run settings for [`run_integrity_checks`].
+#[derive(Clone, Copy)]
+struct IntegrityRunCtx<'a> {
     mode: IntegrityMode,
     file_concurrency: usize,
     parallelism: Option<usize>,
+    file_timeout: Option<Duration>,
+    on_corrupt: &'a CorruptAction,
     progress: bool,
+}
+
+fn run_integrity_checks<F, V>(
+    files: &[FileJob],
+    ctx: &IntegrityRunCtx<'_>,
     on_failure: &F,
     validate_job: &V,
 ) -> Result<IntegrityReport>
 where
     F: Fn(&Path, &str) -> Result<()> + Send + Sync,
-    V: Fn(&FileJob, IntegrityMode) -> Result<()> + Send + Sync,
+    V: Fn(&FileJob, IntegrityMode, &AtomicBool) -> Result<Option<IntegrityFileStats>> + Send + Sync,
 {
+    let IntegrityRunCtx {
+        mode,
+        file_concurrency,
+        parallelism,
+        file_timeout,
+        on_corrupt,
+        progress,
+    } = *ctx;
     validate_integrity_mode(mode)?;
 
     let label = match mode {
@@ -194,14 +550,20 @@ where
     // all-corrupt corpora; `total_failures` counts every failure regardless of
     // the cap so the heartbeat and the returned `failure_count` stay accurate.
     let failures = Mutex::new(Vec::<(PathBuf, String)>::new());
+    let file_stats = Mutex::new(Vec::<(PathBuf, IntegrityFileStats)>::new());
+    let corrupt_actions = Mutex::new(Vec::<(PathBuf, CorruptOutcome)>::new());
     let total_failures = AtomicUsize::new(0);
     let heartbeat_reported = AtomicUsize::new(0);
 
     let fanout = with_thread_pool(parallelism, || {
         for_each_file_limited(files, file_concurrency, |job| -> Result<()> {
-            let res = validate_job(job, mode);
+            let res = validate_job_with_deadline(job, mode, file_timeout, validate_job);
+            if let Ok(Some(stats)) = res {
+                file_stats.lock().unwrap().push((job.path.clone(), stats));
+            }
             if let Err(e) = res {
                 let path = &job.path;
+                let corrupt = e.downcast_ref::<CorruptContentError>().is_some();
                 let err = e.to_string();
                 total_failures.fetch_add(1, Ordering::Relaxed);
                 {
@@ -215,6 +577,14 @@ where
                 on_failure(path, &err).with_context(|| {
                     format!("streaming integrity failure for {}", path.display())
                 })?;
+                if corrupt {
+                    if let Some(outcome) = apply_corrupt_action(path, on_corrupt) {
+                        corrupt_actions
+                            .lock()
+                            .unwrap()
+                            .push((path.clone(), outcome));
+                    }
+                }
             }
 
             if let Some(pb) = &pb {
@@ -252,12 +622,22 @@ where
     let dropped = total_failures
         .load(Ordering::Relaxed)
         .saturating_sub(failures.len());
-    Ok(IntegrityReport { failures, dropped })
+    let mut file_stats = file_stats.into_inner().unwrap();
+    file_stats.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut corrupt_actions = corrupt_actions.into_inner().unwrap();
+    corrupt_actions.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(IntegrityReport {
+        failures,
+        dropped,
+        file_stats,
+        corrupt_actions,
+    })
 }
 
 impl RedditETL {
     /// Check all monthly `.zst` files (RC/RS depending on `sources`) within the date range
-    /// configured on this `RedditETL` instance. Returns an [`IntegrityReport`]
+    /// configured on this `RedditETL` instance. Plain `.jsonl` months are read
+    /// line by line and fail on a line over `max_line_bytes` or invalid UTF-8. Returns an [`IntegrityReport`]
     /// describing the files that failed the selected integrity check.
     ///
     /// - Set `.sources()` (Comments / Submissions / Both) and `.date_range()` on the builder
@@ -268,6 +648,12 @@ impl RedditETL {
     ///   entire stream.
     /// - Parallelism is controlled by `.parallelism(n)`, while `.file_concurrency(n)`
     ///   bounds the number of zstd decoders in flight.
+    /// - `.integrity_file_timeout(d)` caps the wall-clock time spent on each
+    ///   file; a file that overruns it fails with [`INTEGRITY_TIMEOUT_ERROR`]
+    ///   and the run moves on.
+    /// - `.integrity_on_corrupt(action)` quarantines or deletes each file that
+    ///   fails, in the same pass; [`IntegrityReport::corrupt_actions`] lists
+    ///   what was done.
     ///
     /// The report's `failures` list is capped at [`MAX_RETAINED_FAILURES`] so an
     /// all-corrupt corpus cannot grow memory without bound; consult
@@ -275,7 +661,7 @@ impl RedditETL {
     /// method only returns failures after the run completes — use
     /// [`RedditETL::check_corpus_integrity_with_failure_sink`] to observe failures
     /// incrementally.
-    pub fn check_corpus_integrity(self, mode: IntegrityMode) -> Result<IntegrityReport> {
+    pub fn check_corpus_integrity(self, mode: IntegrityMode) -> Result<IntegrityReport, RetlError> {
         self.check_corpus_integrity_with_failure_sink(mode, |_path, _err| Ok(()))
     }
 
@@ -296,45 +682,55 @@ impl RedditETL {
         self,
         mode: IntegrityMode,
         on_failure: F,
-    ) -> Result<IntegrityReport>
+    ) -> Result<IntegrityReport, RetlError>
     where
         F: Fn(&Path, &str) -> Result<()> + Send + Sync,
     {
         validate_integrity_mode(mode)?;
+        let _run_settings = self.opts.enter_run_settings();
         // Surface a deferred ConfigBuildError (e.g. a backwards date range from
         // `with_date_range`) before planning, so `integrity` fails fast with
         // the "invalid date range" message instead of "planned zero files".
         self.opts.check_config()?;
 
-        let discovered = discover_sources_checked(
-            &self.opts.comments_dir,
-            &self.opts.submissions_dir,
-            self.opts.sources,
-        )?;
-        let files = plan_files_checked(
-            &discovered,
-            &self.opts.comments_dir,
-            &self.opts.submissions_dir,
-            self.opts.sources,
-            self.opts.start,
-            self.opts.end,
-        )?;
-        log_missing_month_warnings(
-            &discovered,
-            self.opts.sources,
-            self.opts.start,
-            self.opts.end,
-        );
+        let files = match self.opts.explicit_file_jobs()? {
+            Some(files) => files,
+            None => {
+                let discovered = discover_roots_checked(
+                    &self.opts.corpus_roots(),
+                    self.opts.sources,
+                    self.opts.duplicate_months,
+                )?;
+                let (start, end) = self.opts.planned_range(&discovered, self.opts.sources);
+                let mut files = plan_files_checked(
+                    &discovered,
+                    &self.opts.comments_dir,
+                    &self.opts.submissions_dir,
+                    self.opts.sources,
+                    start,
+                    end,
+                )?;
+                log_missing_month_warnings(&discovered, self.opts.sources, start, end);
+                self.opts.retain_last_n_months(&mut files);
+                files
+            }
+        };
 
-        run_integrity_checks(
+        Ok(run_integrity_checks(
             &files,
-            mode,
-            self.opts.file_concurrency,
-            self.opts.parallelism,
-            self.opts.progress,
+            &IntegrityRunCtx {
+                mode,
+                file_concurrency: self.opts.file_concurrency,
+                parallelism: self.opts.parallelism,
+                file_timeout: self.opts.integrity_file_timeout,
+                on_corrupt: &self.opts.integrity_on_corrupt,
+                progress: self.opts.progress,
+            },
             &on_failure,
-            &validate_integrity_job,
-        )
+            &|job, mode, cancel| {
+                validate_integrity_job(job, mode, self.opts.max_line_bytes, cancel)
+            },
+        )?)
     }
 }
 
@@ -366,17 +762,21 @@ mod tests {
 
         run_integrity_checks(
             &jobs,
-            IntegrityMode::Full,
-            3,
-            Some(8),
-            false,
+            &IntegrityRunCtx {
+                mode: IntegrityMode::Full,
+                file_concurrency: 3,
+                parallelism: Some(8),
+                file_timeout: None,
+                on_corrupt: &CorruptAction::Report,
+                progress: false,
+            },
             &|_path, _err| Ok(()),
-            &|_job, _mode| {
+            &|_job, _mode, _cancel| {
                 let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
                 peak.fetch_max(now, Ordering::SeqCst);
                 std::thread::sleep(Duration::from_millis(30));
                 in_flight.fetch_sub(1, Ordering::SeqCst);
-                Ok(())
+                Ok(None)
             },
         )
         .unwrap();
@@ -404,10 +804,14 @@ mod tests {
             let bad_path_for_validator = bad_path.clone();
             let res = run_integrity_checks(
                 &jobs,
-                IntegrityMode::Full,
-                2,
-                Some(2),
-                false,
+                &IntegrityRunCtx {
+                    mode: IntegrityMode::Full,
+                    file_concurrency: 2,
+                    parallelism: Some(2),
+                    file_timeout: None,
+                    on_corrupt: &CorruptAction::Report,
+                    progress: false,
+                },
                 &|path, err| {
                     tx.lock()
                         .unwrap()
@@ -415,14 +819,14 @@ mod tests {
                         .unwrap();
                     Ok(())
                 },
-                &|job, _mode| {
+                &|job, _mode, _cancel| {
                     if job.path == bad_path_for_validator {
                         return Err(anyhow!("boom"));
                     }
                     while !release_in_thread.load(Ordering::Acquire) {
                         std::thread::sleep(Duration::from_millis(5));
                     }
-                    Ok(())
+                    Ok(None)
                 },
             );
             done_in_thread.store(true, Ordering::Release);
@@ -459,15 +863,19 @@ mod tests {
 
         let report = run_integrity_checks(
             &jobs,
-            IntegrityMode::Full,
-            4,
-            Some(4),
-            false,
+            &IntegrityRunCtx {
+                mode: IntegrityMode::Full,
+                file_concurrency: 4,
+                parallelism: Some(4),
+                file_timeout: None,
+                on_corrupt: &CorruptAction::Report,
+                progress: false,
+            },
             &|_path, _err| {
                 streamed.fetch_add(1, Ordering::Relaxed);
                 Ok(())
             },
-            &|_job, _mode| Err(anyhow!("every file is corrupt")),
+            &|_job, _mode, _cancel| Err(anyhow!("every file is corrupt")),
         )
         .unwrap();
 
@@ -479,4 +887,138 @@ mod tests {
         assert_eq!(report.failures.len(), MAX_RETAINED_FAILURES);
         assert_eq!(report.dropped, file_count - MAX_RETAINED_FAILURES);
     }
+
+    /// A validation that overruns `file_timeout` is reported as a timeout
+    /// failure while the other file still passes, and the worker has seen the
+    /// cancel flag and exited by the time the run returns.
+    #[test]
+    fn file_timeout_flags_slow_file_and_joins_its_worker() {
+        let jobs = fake_jobs(2);
+        let slow_path = jobs[0].path.clone();
+        let worker_exited = AtomicBool::new(false);
+
+        let report = run_integrity_checks(
+            &jobs,
+            &IntegrityRunCtx {
+                mode: IntegrityMode::Full,
+                file_concurrency: 2,
+                parallelism: Some(2),
+                file_timeout: Some(Duration::from_millis(50)),
+                on_corrupt: &CorruptAction::Report,
+                progress: false,
+            },
+            &|_path, _err| Ok(()),
+            &|job, _mode, cancel| {
+                if job.path == slow_path {
+                    while !cancel.load(Ordering::Acquire) {
+                        std::thread::sleep(Duration::from_millis(5));
+                    }
+                    worker_exited.store(true, Ordering::Release);
+                    return Err(anyhow!("cancelled"));
+                }
+                Ok(Some(IntegrityFileStats::default()))
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            report.failures,
+            vec![(slow_path, INTEGRITY_TIMEOUT_ERROR.to_string())]
+        );
+        assert_eq!(report.file_stats.len(), 1);
+        assert!(worker_exited.load(Ordering::Acquire));
+    }
+
+    /// Only decode failures reach the corrupt action: a file that could not
+    /// be read is reported but never deleted.
+    #[test]
+    fn corrupt_action_skips_io_failures_and_acts_on_decode_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let jobs: Vec<FileJob> = ["RC_2006-01.zst", "RC_2006-02.zst"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, b"x").unwrap();
+                FileJob {
+                    kind: FileKind::Comment,
+                    ym: YearMonth::new(2006, i as u8 + 1),
+                    path,
+                }
+            })
+            .collect();
+        let unreadable = jobs[0].path.clone();
+
+        let report = run_integrity_checks(
+            &jobs,
+            &IntegrityRunCtx {
+                mode: IntegrityMode::Full,
+                file_concurrency: 1,
+                parallelism: Some(1),
+                file_timeout: None,
+                on_corrupt: &CorruptAction::Delete,
+                progress: false,
+            },
+            &|_path, _err| Ok(()),
+            &|job, _mode, _cancel| {
+                let e = io::Error::from(io::ErrorKind::PermissionDenied);
+                if job.path == unreadable {
+                    Err(e.into())
+                } else {
+                    Err(CorruptContentError(e).into())
+                }
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.failure_count(), 2);
+        assert_eq!(
+            report.corrupt_actions,
+            vec![(jobs[1].path.clone(), CorruptOutcome::Deleted)]
+        );
+        assert!(unreadable.is_file());
+        assert!(!jobs[1].path.exists());
+    }
+
+    #[test]
+    fn quarantine_never_replaces_an_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = dir.path().join("quarantine");
+        std::fs::create_dir_all(&quarantine).unwrap();
+        let path = dir.path().join("RC_2006-01.zst");
+        std::fs::write(&path, b"new").unwrap();
+        std::fs::write(quarantine.join("RC_2006-01.zst"), b"old").unwrap();
+
+        let outcome =
+            apply_corrupt_action(&path, &CorruptAction::Quarantine(quarantine.clone())).unwrap();
+
+        assert!(matches!(outcome, CorruptOutcome::Failed(_)), "{outcome:?}");
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        assert_eq!(
+            std::fs::read(quarantine.join("RC_2006-01.zst")).unwrap(),
+            b"old"
+        );
+    }
+
+    #[test]
+    fn quarantine_copy_fallback_never_replaces_an_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("RC_2006-02.zst");
+        std::fs::write(&src, b"corrupt bytes").unwrap();
+        let quarantine = dir.path().join("quarantine");
+        std::fs::create_dir_all(&quarantine).unwrap();
+        let dest = quarantine.join("RC_2006-02.zst");
+
+        let staged = crate::atomic_write::unique_inprogress_path(&quarantine, &dest).unwrap();
+        copy_synced(&src, &staged).unwrap();
+        publish_no_clobber(&staged, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"corrupt bytes");
+
+        std::fs::write(&src, b"other bytes").unwrap();
+        let staged = crate::atomic_write::unique_inprogress_path(&quarantine, &dest).unwrap();
+        copy_synced(&src, &staged).unwrap();
+        let err = publish_no_clobber(&staged, &dest).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err:#}");
+        assert_eq!(std::fs::read(&dest).unwrap(), b"corrupt bytes");
+    }
 }
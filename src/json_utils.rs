@@ -1,5 +1,7 @@
 use serde_json::Value;
 
+use crate::paths::FileKind;
+
 /// Extract lowercased author from a JSON value.
 pub fn author_lower(v: &Value) -> Option<String> {
     v.get("author")
@@ -14,18 +16,47 @@ pub fn subreddit_lower(v: &Value) -> Option<String> {
         .map(|s| s.to_lowercase())
 }
 
-/// Heuristic: a record is a comment if it has a `body` and a `parent_id`.
+/// Classify a raw Reddit record as a comment or submission from its own
+/// fields, strongest signal first:
+///
+/// 1. the `name` fullname prefix (`t1_` comment, `t3_` submission);
+/// 2. `link_id`, which only comments carry;
+/// 3. a `t1_`/`t3_`-prefixed `parent_id`, likewise comment-only;
+/// 4. submission-only fields (`title`, `selftext`, `is_self`, `num_comments`).
+///
+/// `body` alone is not evidence either way. Returns `None` when no signal is
+/// present (e.g. a record whitelisted down to `author`/`subreddit`).
+pub fn record_kind(v: &Value) -> Option<FileKind> {
+    let obj = v.as_object()?;
+    match obj.get("name").and_then(Value::as_str) {
+        Some(name) if name.starts_with("t1_") => return Some(FileKind::Comment),
+        Some(name) if name.starts_with("t3_") => return Some(FileKind::Submission),
+        _ => {}
+    }
+    if obj.get("link_id").is_some_and(|x| !x.is_null()) || has_prefixed_parent_id(v) {
+        return Some(FileKind::Comment);
+    }
+    ["title", "selftext", "is_self", "num_comments"]
+        .iter()
+        .any(|key| obj.get(*key).is_some_and(|x| !x.is_null()))
+        .then_some(FileKind::Submission)
+}
+
+/// `true` when [`record_kind`] classifies `v` as a comment.
 pub fn is_comment_record(v: &Value) -> bool {
-    v.get("body").is_some() && v.get("parent_id").is_some()
+    record_kind(v) == Some(FileKind::Comment)
 }
 
-/// Parent-attach matching is intentionally more permissive than the historical
-/// public [`is_comment_record`] helper: whitelist-stripped comments may omit
-/// `body`, but a Reddit comment can still be identified by a canonical
-/// `parent_id` prefix.
-pub(crate) fn is_comment_record_for_parent_attach(v: &Value) -> bool {
+fn has_prefixed_parent_id(v: &Value) -> bool {
     v.get("parent_id")
         .and_then(|x| x.as_str())
-        .map(|parent_id| parent_id.starts_with("t1_") || parent_id.starts_with("t3_"))
-        .unwrap_or(false)
+        .is_some_and(|parent_id| parent_id.starts_with("t1_") || parent_id.starts_with("t3_"))
+}
+
+/// Parent attach needs a comment whose `parent_id` it can resolve: the record
+/// must classify as a comment (so a submission with a stray `parent_id` is
+/// passed through untouched) and carry a `t1_`/`t3_`-prefixed `parent_id`.
+/// Whitelist-stripped comments without `body` still qualify.
+pub(crate) fn is_comment_record_for_parent_attach(v: &Value) -> bool {
+    has_prefixed_parent_id(v) && is_comment_record(v)
 }
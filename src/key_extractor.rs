@@ -1,3 +1,4 @@
+use crate::query::normalize_author;
 use crate::zstd_jsonl::parse_minimal;
 use anyhow::Result;
 use serde_json::Value;
@@ -11,7 +12,8 @@ use std::sync::Arc;
 ///  - `key_from_line(&str)`
 ///
 /// Notes:
-///  - `author_lowercase_fast` / `subreddit_lowercase_fast` prefer `MinimalRecord`.
+///  - `author_lowercase_fast` / `subreddit_lowercase_fast` prefer `MinimalRecord`;
+///    author keys also drop a `u/`, `/u/` or `@` prefix (`normalize_author`).
 ///  - `json_pointer("/user")` works for arbitrary JSON (serde parse), and
 ///    coerces pointed-to scalar values to text.
 pub enum KeyExtractor {
@@ -21,6 +23,17 @@ pub enum KeyExtractor {
     ByValue(Arc<dyn Fn(&Value) -> Option<String> + Send + Sync>),
 }
 
+impl std::fmt::Debug for KeyExtractor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyExtractor::AuthorLowerFast => f.write_str("AuthorLowerFast"),
+            KeyExtractor::SubredditLowerFast => f.write_str("SubredditLowerFast"),
+            KeyExtractor::JsonPointer(ptr) => f.debug_tuple("JsonPointer").field(ptr).finish(),
+            KeyExtractor::ByValue(_) => f.write_str("ByValue(<custom>)"),
+        }
+    }
+}
+
 impl KeyExtractor {
     pub fn author_lowercase_fast() -> Self { Self::AuthorLowerFast }
     pub fn subreddit_lowercase_fast() -> Self { Self::SubredditLowerFast }
@@ -32,7 +45,7 @@ impl KeyExtractor {
     /// Extract the key from a full `serde_json::Value`.
     pub fn key_from_value(&self, v: &Value) -> Option<String> {
         match self {
-            KeyExtractor::AuthorLowerFast => v.get("author").and_then(|x| x.as_str()).map(|s| s.to_lowercase()),
+            KeyExtractor::AuthorLowerFast => v.get("author").and_then(|x| x.as_str()).map(normalize_author),
             KeyExtractor::SubredditLowerFast => v.get("subreddit").and_then(|x| x.as_str()).map(|s| s.to_lowercase()),
             KeyExtractor::JsonPointer(ptr) => v.pointer(ptr).and_then(json_pointer_value_to_key),
             KeyExtractor::ByValue(f) => f(v),
@@ -48,10 +61,10 @@ impl KeyExtractor {
     pub fn key_from_line(&self, line: &str) -> Result<Option<String>> {
         match self {
             KeyExtractor::AuthorLowerFast => match parse_minimal(line) {
-                Ok(rec) => Ok(rec.author.map(|s| s.to_lowercase())),
+                Ok(rec) => Ok(rec.author.as_deref().map(normalize_author)),
                 Err(_) => {
                     let v: Value = serde_json::from_str(line)?;
-                    Ok(v.get("author").and_then(|x| x.as_str()).map(|s| s.to_lowercase()))
+                    Ok(v.get("author").and_then(|x| x.as_str()).map(normalize_author))
                 }
             },
             KeyExtractor::SubredditLowerFast => match parse_minimal(line) {
@@ -115,6 +128,21 @@ mod tests {
         assert_eq!(key.key_from_line(r#"{"other":42}"#).unwrap(), None);
     }
 
+    #[test]
+    fn author_key_strips_user_prefix() {
+        let key = KeyExtractor::author_lowercase_fast();
+        assert_eq!(
+            key.key_from_line(r#"{"author":"u/Alice"}"#)
+                .unwrap()
+                .as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            key.key_from_value(&json!({ "author": "/u/Alice" })).as_deref(),
+            Some("alice")
+        );
+    }
+
     #[test]
     fn malformed_json_is_distinct_from_missing_key() {
         let key = KeyExtractor::author_lowercase_fast();
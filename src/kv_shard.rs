@@ -1,27 +1,32 @@
-use crate::config::clamp_shard_count;
+use crate::config::{clamp_shard_count, IntermediateFormat};
 use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
 use crate::shard_common;
 use crate::util::unique_scratch_dir;
 use ahash::RandomState;
 use anyhow::{Context, Result};
 use rayon::prelude::*;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::{BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
 
-/// Sharded key->i64 writer for large-scale reductions (sum/min, etc.).
+/// Sharded key->i64 writer for large-scale reductions (sum/min/max, etc.).
 ///
-/// # Key constraints
+/// # Key escaping
 ///
 /// Each `(key, value)` pair is persisted as a single TAB-delimited,
 /// newline-terminated line (`<key>\t<value>\n`) and parsed back with
-/// `split_once('\t')`. Keys must therefore be free of the bytes that delimit
-/// that format: a key containing a tab would be truncated at the first tab
-/// (the remainder mis-parsed as the value), and a key containing a newline /
-/// carriage-return would split into two corrupt lines. [`write_kv`] rejects
-/// such keys with an error instead of silently corrupting the shard; callers
-/// keying by arbitrary text (JSON-pointer string values, flair, titles)
-/// should escape or hash those keys before writing.
+/// `split_once('\t')`. [`write_kv`] therefore escapes the bytes that delimit
+/// that format before writing: backslash becomes `\\`, tab `\t`, newline
+/// `\n`, and carriage-return `\r` (see [`escape_kv_key`]). The escaping is
+/// one-to-one, so reducers group escaped keys exactly as they would the raw
+/// ones, and the reduced TSV keeps the escaped form so every row still splits
+/// on its first tab. Keys without those bytes are written unchanged; use
+/// [`unescape_kv_key`] to recover the original text from an output row.
+///
+/// With [`IntermediateFormat::Binary`] the shards hold length-prefixed
+/// records instead; keys are escaped the same way, so the reduced outputs
+/// are the same TSV either way.
 ///
 /// [`write_kv`]: Self::write_kv
 pub struct ShardedKVWriter {
@@ -30,6 +35,7 @@ pub struct ShardedKVWriter {
     shards: shard_common::LineShardWriters,
     count: usize,
     state: RandomState,
+    format: IntermediateFormat,
 }
 
 impl ShardedKVWriter {
@@ -52,9 +58,26 @@ impl ShardedKVWriter {
             shards,
             count,
             state,
+            format: IntermediateFormat::Text,
         })
     }
 
+    /// Encode shard scratch as `format`. Call before the first write: the
+    /// reducers decode every shard with the format set here.
+    pub fn with_intermediate_format(mut self, format: IntermediateFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Flush every shard once `bytes` have been appended across them since
+    /// the last flush (`0` flushes only when buffers fill, under memory
+    /// pressure, and at the end). Defaults to
+    /// [`DEFAULT_SHARD_FLUSH_BYTES`](crate::DEFAULT_SHARD_FLUSH_BYTES).
+    pub fn with_flush_every_bytes(mut self, bytes: u64) -> Self {
+        self.shards.set_flush_every_bytes(bytes);
+        self
+    }
+
     pub fn scratch_root(&self) -> &Path {
         &self.run_root
     }
@@ -66,29 +89,77 @@ impl ShardedKVWriter {
 
     /// Append a `(key, value)` pair to the shard the key hashes to.
     ///
-    /// Returns an error (without writing anything) when `key` contains a tab,
-    /// newline, or carriage-return — those bytes delimit the on-disk
-    /// TAB-delimited line format, so writing them would silently corrupt the
-    /// shard on read-back. See the [type-level docs](Self) for the rationale.
+    /// Tabs, newlines, carriage-returns, and backslashes in `key` are escaped
+    /// first; see the [type-level docs](Self).
     pub fn write_kv(&self, key: &str, val: i64) -> Result<()> {
-        if let Some(pos) = key
-            .bytes()
-            .position(|b| b == b'\t' || b == b'\n' || b == b'\r')
-        {
-            let bad = key.as_bytes()[pos];
+        self.write_record(&escape_kv_key(key), val, "")
+    }
+
+    /// Append a `(key, value, payload)` row for
+    /// [`reduce_argmin`](Self::reduce_argmin).
+    ///
+    /// `payload` is the trailing field, so it may contain tabs (e.g. several
+    /// TAB-joined columns) but not newline or carriage-return. The key is
+    /// escaped like [`write_kv`](Self::write_kv); the payload is not.
+    pub fn write_kv_payload(&self, key: &str, val: i64, payload: &str) -> Result<()> {
+        if let Some(pos) = payload.bytes().position(|b| b == b'\n' || b == b'\r') {
+            let bad = payload.as_bytes()[pos];
             anyhow::bail!(
-                "ShardedKVWriter::write_kv: key contains a disallowed delimiter byte \
-                 {bad:#04x} at offset {pos}; keys are stored in a TAB-delimited line \
-                 format and must not contain tab, newline, or carriage-return"
+                "ShardedKVWriter::write_kv_payload: payload contains a disallowed line \
+                 terminator {bad:#04x} at offset {pos}"
             );
         }
+        self.write_record(&escape_kv_key(key), val, payload)
+    }
+
+    /// Append one escaped row; an empty `payload` is omitted from text rows.
+    fn write_record(&self, key: &str, val: i64, payload: &str) -> Result<()> {
         let idx = self.shard_index(key);
-        let mut w = self.shards[idx].lock();
-        w.write_all(key.as_bytes())?;
-        w.write_all(b"\t")?;
-        w.write_all(val.to_string().as_bytes())?;
-        w.write_all(b"\n")?;
-        Ok(())
+        match self.format {
+            IntermediateFormat::Text => {
+                let val = val.to_string();
+                if payload.is_empty() {
+                    self.shards
+                        .write_parts(idx, &[key.as_bytes(), b"\t", val.as_bytes(), b"\n"])
+                } else {
+                    self.shards.write_parts(
+                        idx,
+                        &[
+                            key.as_bytes(),
+                            b"\t",
+                            val.as_bytes(),
+                            b"\t",
+                            payload.as_bytes(),
+                            b"\n",
+                        ],
+                    )
+                }
+            }
+            IntermediateFormat::Binary => {
+                // The key's length prefix carries a has-payload bit, so the
+                // common payload-free row is just `prefix, key, value`.
+                let key_len = crate::frames::frame_len(key.as_bytes())?;
+                let prefix =
+                    crate::frames::Varint::new(key_len << 1 | u64::from(!payload.is_empty()));
+                let val = crate::frames::Varint::new(crate::frames::zigzag(val));
+                if payload.is_empty() {
+                    self.shards
+                        .write_parts(idx, &[prefix.as_bytes(), key.as_bytes(), val.as_bytes()])
+                } else {
+                    let payload_len = crate::frames::frame_len_prefix(payload.as_bytes())?;
+                    self.shards.write_parts(
+                        idx,
+                        &[
+                            prefix.as_bytes(),
+                            key.as_bytes(),
+                            val.as_bytes(),
+                            payload_len.as_bytes(),
+                            payload.as_bytes(),
+                        ],
+                    )
+                }
+            }
+        }
     }
 
     pub fn flush_all(&self) -> Result<()> {
@@ -133,6 +204,38 @@ impl ShardedKVWriter {
         self.reduce(prefix, Reducer::Min, "kv_min")
     }
 
+    /// Reduce all shards by keeping the maximum value seen per key. Like
+    /// [`reduce_min`](Self::reduce_min), every observed `i64` (including
+    /// [`i64::MIN`]) is a legal value.
+    pub fn reduce_max(self, prefix: &str) -> Result<Vec<PathBuf>> {
+        let (outs, _scratch_root) = self.reduce_max_with_scratch(prefix)?;
+        Ok(outs)
+    }
+
+    /// Like [`reduce_max`](Self::reduce_max) but also returns the scratch
+    /// directory root so the caller can clean it up.
+    pub fn reduce_max_with_scratch(self, prefix: &str) -> Result<(Vec<PathBuf>, PathBuf)> {
+        self.reduce(prefix, Reducer::Max, "kv_max")
+    }
+
+    /// Reduce all shards by keeping, per key, the minimum value together with
+    /// the payload written alongside it (see
+    /// [`write_kv_payload`](Self::write_kv_payload)). Output rows are
+    /// `key\tvalue\tpayload`.
+    ///
+    /// Ties on the minimum keep the lexicographically smallest payload, so the
+    /// result does not depend on the order concurrent writers appended rows.
+    pub fn reduce_argmin(self, prefix: &str) -> Result<Vec<PathBuf>> {
+        let (outs, _scratch_root) = self.reduce_argmin_with_scratch(prefix)?;
+        Ok(outs)
+    }
+
+    /// Like [`reduce_argmin`](Self::reduce_argmin) but also returns the
+    /// scratch directory root so the caller can clean it up.
+    pub fn reduce_argmin_with_scratch(self, prefix: &str) -> Result<(Vec<PathBuf>, PathBuf)> {
+        self.reduce(prefix, Reducer::ArgMin, "kv_argmin")
+    }
+
     fn reduce(
         self,
         prefix: &str,
@@ -149,6 +252,7 @@ impl ShardedKVWriter {
             shards,
             count,
             state: _,
+            format,
         } = self;
         drop(shards);
 
@@ -170,7 +274,7 @@ impl ShardedKVWriter {
                         .to_string_lossy()
                         .replace(".tmp", ".tsv"),
                 );
-                reduce_shard(p, &out, reducer)?;
+                reduce_shard(p, &out, reducer, format)?;
                 Ok(out)
             })
             .collect::<Result<Vec<_>>>()?;
@@ -188,91 +292,217 @@ enum Reducer {
     /// so any `i64` (including `i64::MAX`) is a legal observed value and keys
     /// never observed never appear in output.
     Min,
+    /// Per-key maximum; sentinel-free like `Min`.
+    Max,
+    /// Per-key minimum that also keeps the payload of the winning row.
+    /// Ties keep the smaller payload so the output is order-independent.
+    ArgMin,
+}
+
+/// Escape `key` for a TAB-delimited KV row: `\` → `\\`, tab → `\t`,
+/// newline → `\n`, carriage-return → `\r`. Borrows when nothing needs
+/// escaping, which is the common case.
+pub fn escape_kv_key(key: &str) -> Cow<'_, str> {
+    if !key
+        .bytes()
+        .any(|b| matches!(b, b'\\' | b'\t' | b'\n' | b'\r'))
+    {
+        return Cow::Borrowed(key);
+    }
+    let mut out = String::with_capacity(key.len() + 8);
+    for c in key.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Inverse of [`escape_kv_key`], for reading keys back out of a reduced KV
+/// TSV (e.g. [`ScanPlan::author_counts_to_tsv`](crate::ScanPlan::author_counts_to_tsv)).
+/// An unrecognized escape or a trailing lone backslash is kept literally.
+pub fn unescape_kv_key(key: &str) -> Cow<'_, str> {
+    if !key.contains('\\') {
+        return Cow::Borrowed(key);
+    }
+    let mut out = String::with_capacity(key.len());
+    let mut chars = key.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    Cow::Owned(out)
 }
 
-fn reduce_shard(input: &Path, output: &Path, reducer: Reducer) -> Result<()> {
-    let mut acc: HashMap<String, i64> = HashMap::with_capacity(64_000);
+fn reduce_shard(
+    input: &Path,
+    output: &Path,
+    reducer: Reducer,
+    format: IntermediateFormat,
+) -> Result<()> {
+    // The payload slot stays empty for `Sum`/`Min`/`Max`.
+    let mut acc: HashMap<String, (i64, String)> = HashMap::with_capacity(64_000);
     let mut sum_overflow_warned = false;
+    let mut observe = |k: &str, val: i64, payload: &str, line_no: usize| match reducer {
+        Reducer::Sum => {
+            let (e, _) = acc.entry(k.to_string()).or_insert((0i64, String::new()));
+            match e.checked_add(val) {
+                Some(s) => *e = s,
+                None => {
+                    // Saturate to keep the run going; emit one warning per
+                    // shard so the user sees the overflow without log spam.
+                    let saturated = if val >= 0 { i64::MAX } else { i64::MIN };
+                    if !sum_overflow_warned {
+                        tracing::warn!(
+                            path = %input.display(),
+                            line = line_no,
+                            key = %k,
+                            accumulator = *e,
+                            value = val,
+                            saturated_to = saturated,
+                            "kv_shard Sum overflow: saturating; further overflows in this shard suppressed"
+                        );
+                        sum_overflow_warned = true;
+                    }
+                    *e = saturated;
+                }
+            }
+        }
+        Reducer::Min => {
+            // Sentinel-free: seed with the first observed value so `i64::MAX`
+            // is a legal observation and 'never seen' is unambiguous (the
+            // key simply never enters the map).
+            acc.entry(k.to_string())
+                .and_modify(|(cur, _)| {
+                    if val < *cur {
+                        *cur = val;
+                    }
+                })
+                .or_insert((val, String::new()));
+        }
+        Reducer::Max => {
+            acc.entry(k.to_string())
+                .and_modify(|(cur, _)| {
+                    if val > *cur {
+                        *cur = val;
+                    }
+                })
+                .or_insert((val, String::new()));
+        }
+        Reducer::ArgMin => {
+            acc.entry(k.to_string())
+                .and_modify(|(cur, cur_payload)| {
+                    if val < *cur || (val == *cur && payload < cur_payload.as_str()) {
+                        *cur = val;
+                        cur_payload.clear();
+                        cur_payload.push_str(payload);
+                    }
+                })
+                .or_insert_with(|| (val, payload.to_string()));
+        }
+    };
     let mut r = BufReader::new(
         crate::util::open_with_default_backoff(input)
             .with_context(|| format!("open {}", input.display()))?,
     );
-    let mut line = String::with_capacity(16 * 1024);
-    let mut line_no = 0usize;
-    loop {
-        let n = read_line_capped(&mut r, &mut line, DEFAULT_MAX_LINE_BYTES, input)
-            .with_context(|| format!("read {} at line {}", input.display(), line_no + 1))?;
-        if n == 0 {
-            break;
-        }
-        line_no += 1;
-        if line.is_empty() {
-            continue;
-        }
-        let (k, v) = line.split_once('\t').ok_or_else(|| {
-            anyhow::anyhow!(
-                "malformed K-V shard line in {} at line {}: missing tab separator",
-                input.display(),
-                line_no
-            )
-        })?;
-        let val = v.parse::<i64>().with_context(|| {
-            format!(
-                "malformed K-V shard line in {} at line {}: value is not an i64",
-                input.display(),
-                line_no
-            )
-        })?;
-        match reducer {
-            Reducer::Sum => {
-                let e = acc.entry(k.to_string()).or_insert(0i64);
-                match e.checked_add(val) {
-                    Some(s) => *e = s,
-                    None => {
-                        // Saturate to keep the run going; emit one warning per
-                        // shard so the user sees the overflow without log spam.
-                        let saturated = if val >= 0 { i64::MAX } else { i64::MIN };
-                        if !sum_overflow_warned {
-                            tracing::warn!(
-                                path = %input.display(),
-                                line = line_no,
-                                key = %k,
-                                accumulator = *e,
-                                value = val,
-                                saturated_to = saturated,
-                                "kv_shard Sum overflow: saturating; further overflows in this shard suppressed"
-                            );
-                            sum_overflow_warned = true;
-                        }
-                        *e = saturated;
-                    }
+    match format {
+        IntermediateFormat::Text => {
+            let mut line = String::with_capacity(16 * 1024);
+            let mut line_no = 0usize;
+            loop {
+                let n = read_line_capped(&mut r, &mut line, DEFAULT_MAX_LINE_BYTES, input)
+                    .with_context(|| format!("read {} at line {}", input.display(), line_no + 1))?;
+                if n == 0 {
+                    break;
+                }
+                line_no += 1;
+                if line.is_empty() {
+                    continue;
                 }
+                let (k, v) = line.split_once('\t').ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "malformed K-V shard line in {} at line {}: missing tab separator",
+                        input.display(),
+                        line_no
+                    )
+                })?;
+                let (v, payload) = match reducer {
+                    Reducer::ArgMin => v.split_once('\t').unwrap_or((v, "")),
+                    Reducer::Sum | Reducer::Min | Reducer::Max => (v, ""),
+                };
+                let val = v.parse::<i64>().with_context(|| {
+                    format!(
+                        "malformed K-V shard line in {} at line {}: value is not an i64",
+                        input.display(),
+                        line_no
+                    )
+                })?;
+                observe(k, val, payload, line_no);
             }
-            Reducer::Min => {
-                // Sentinel-free: seed with the first observed value so `i64::MAX`
-                // is a legal observation and 'never seen' is unambiguous (the
-                // key simply never enters the map).
-                acc.entry(k.to_string())
-                    .and_modify(|cur| {
-                        if val < *cur {
-                            *cur = val;
-                        }
-                    })
-                    .or_insert(val);
+        }
+        IntermediateFormat::Binary => {
+            // Records are `varint(key_len << 1 | has_payload), key, zigzag
+            // varint value[, payload frame]` (see `write_record`).
+            let mut key = String::new();
+            let mut payload = String::new();
+            let mut record_no = 0usize;
+            loop {
+                let context = || format!("read {} at record {}", input.display(), record_no + 1);
+                let Some(prefix) =
+                    crate::frames::read_varint(&mut r, input).with_context(context)?
+                else {
+                    break;
+                };
+                crate::frames::read_str_frame_body(&mut r, prefix >> 1, &mut key, input)
+                    .with_context(context)?;
+                let val = crate::frames::unzigzag(
+                    crate::frames::read_required_varint(&mut r, input).with_context(context)?,
+                );
+                if prefix & 1 == 1 {
+                    let len =
+                        crate::frames::read_required_varint(&mut r, input).with_context(context)?;
+                    crate::frames::read_str_frame_body(&mut r, len, &mut payload, input)
+                        .with_context(context)?;
+                } else {
+                    payload.clear();
+                }
+                record_no += 1;
+                observe(&key, val, &payload, record_no);
             }
         }
     }
-    let mut rows: Vec<(String, i64)> = acc.into_iter().collect();
+    let mut rows: Vec<(String, (i64, String))> = acc.into_iter().collect();
     rows.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
 
     let mut w = BufWriter::new(
         crate::util::create_with_default_backoff(output)
             .with_context(|| format!("create {}", output.display()))?,
     );
-    for (k, v) in rows {
+    for (k, (v, payload)) in rows {
         w.write_all(k.as_bytes())?;
         w.write_all(b"\t")?;
         w.write_all(v.to_string().as_bytes())?;
+        if matches!(reducer, Reducer::ArgMin) {
+            w.write_all(b"\t")?;
+            w.write_all(payload.as_bytes())?;
+        }
         w.write_all(b"\n")?;
     }
     w.flush()?;
@@ -295,33 +525,107 @@ mod tests {
     }
 
     #[test]
-    fn write_kv_rejects_keys_with_delimiter_bytes() {
-        let tmp = tempfile::tempdir().expect("tempdir");
-        let writer = ShardedKVWriter::create(tmp.path(), "delim", 4).expect("create");
-
-        for bad in ["ta\tb", "new\nline", "carriage\rreturn"] {
-            let err = writer
-                .write_kv(bad, 1)
-                .expect_err("delimiter byte in key must be rejected");
-            let msg = format!("{err:#}");
-            assert!(
-                msg.contains("disallowed delimiter byte"),
-                "unexpected error for key {bad:?}: {msg}"
-            );
+    fn write_kv_escapes_keys_with_delimiter_bytes() {
+        for format in [IntermediateFormat::Text, IntermediateFormat::Binary] {
+            let tmp = tempfile::tempdir().expect("tempdir");
+            let writer = ShardedKVWriter::create(tmp.path(), "delim", 4)
+                .expect("create")
+                .with_intermediate_format(format);
+
+            let keys = [
+                "ta\tb",
+                "new\nline",
+                "carriage\rreturn",
+                "back\\t",
+                "clean_key",
+            ];
+            for key in keys {
+                writer.write_kv(key, 1).expect("write");
+                writer.write_kv(key, 2).expect("write");
+            }
+            // Escaping is one-to-one: a literal `\t` never merges with a tab.
+            writer.write_kv("ta\\tb", 10).expect("write");
+
+            let outs = writer.reduce_sum("delim").expect("reduce");
+            let mut total = HashMap::new();
+            for out in &outs {
+                for (k, v) in read_kv_tsv(out) {
+                    total.insert(unescape_kv_key(&k).into_owned(), v);
+                }
+            }
+            for key in keys {
+                assert_eq!(total.get(key).copied(), Some(3), "{key:?} ({format:?})");
+            }
+            assert_eq!(total.get("ta\\tb").copied(), Some(10));
+            assert_eq!(total.len(), keys.len() + 1);
         }
+    }
 
-        // A clean key still writes fine and round-trips through reduce.
-        writer.write_kv("clean_key", 7).expect("clean key writes");
-        let outs = writer.reduce_sum("delim").expect("reduce");
-        let mut total = HashMap::new();
-        for out in &outs {
-            for (k, v) in read_kv_tsv(out) {
-                total.insert(k, v);
+    #[test]
+    fn binary_shards_are_smaller_than_text_and_reduce_the_same() {
+        fn tree_bytes(dir: &Path) -> u64 {
+            std::fs::read_dir(dir)
+                .expect("read scratch")
+                .map(|e| {
+                    let path = e.expect("entry").path();
+                    if path.is_dir() {
+                        tree_bytes(&path)
+                    } else {
+                        path.metadata().expect("metadata").len()
+                    }
+                })
+                .sum()
+        }
+        let shard_bytes = |writer: &ShardedKVWriter| -> u64 {
+            writer.flush_all().expect("flush");
+            tree_bytes(writer.scratch_root())
+        };
+        let mut sizes = Vec::new();
+        let mut reduced = Vec::new();
+        for format in [IntermediateFormat::Text, IntermediateFormat::Binary] {
+            let tmp = tempfile::tempdir().expect("tempdir");
+            let writer = ShardedKVWriter::create(tmp.path(), "size", 2)
+                .expect("create")
+                .with_intermediate_format(format);
+            for i in 0..200_i64 {
+                let key = format!("user_{}", i % 50);
+                writer
+                    .write_kv_payload(&key, 1_136_073_600 - i, &format!("t3_{i}\tr/x"))
+                    .expect("write");
+                writer.write_kv(&key, -i).expect("write");
+            }
+            sizes.push(shard_bytes(&writer));
+            let mut rows = Vec::new();
+            for out in writer.reduce_argmin("size").expect("reduce") {
+                rows.extend(
+                    std::fs::read_to_string(out)
+                        .expect("read")
+                        .lines()
+                        .map(String::from),
+                );
             }
+            rows.sort();
+            reduced.push(rows);
+        }
+        assert!(
+            sizes[1] < sizes[0],
+            "binary {} vs text {}",
+            sizes[1],
+            sizes[0]
+        );
+        assert_eq!(reduced[0], reduced[1]);
+        assert_eq!(reduced[0].len(), 50);
+    }
+
+    #[test]
+    fn kv_key_escaping_round_trips() {
+        for key in ["plain", "", "a\tb\nc\rd", "\\", "x\\ty", "trail\\"] {
+            let escaped = escape_kv_key(key);
+            assert!(!escaped.contains(['\t', '\n', '\r']), "{escaped:?}");
+            assert_eq!(unescape_kv_key(&escaped), key);
         }
-        assert_eq!(total.get("clean_key").copied(), Some(7));
-        // The rejected keys never made it into any shard.
-        assert_eq!(total.len(), 1);
+        assert!(matches!(escape_kv_key("plain"), Cow::Borrowed(_)));
+        assert_eq!(unescape_kv_key("odd\\q"), "odd\\q");
     }
 
     #[test]
@@ -331,7 +635,8 @@ mod tests {
         let output = tmp.path().join("kv_0000.tsv");
         std::fs::write(&input, b"alice\t1\nmalformed\n").expect("write input");
 
-        let err = reduce_shard(&input, &output, Reducer::Sum).expect_err("malformed shard fails");
+        let err = reduce_shard(&input, &output, Reducer::Sum, IntermediateFormat::Text)
+            .expect_err("malformed shard fails");
         let msg = format!("{err:#}");
 
         assert!(
@@ -352,7 +657,8 @@ mod tests {
         let mut f = std::fs::File::create(&input).expect("create input");
         writeln!(f, "alice\tbogus").expect("write input");
 
-        let err = reduce_shard(&input, &output, Reducer::Sum).expect_err("malformed shard fails");
+        let err = reduce_shard(&input, &output, Reducer::Sum, IntermediateFormat::Text)
+            .expect_err("malformed shard fails");
         let msg = format!("{err:#}");
 
         assert!(
@@ -393,7 +699,8 @@ mod tests {
         writeln!(f, "bob\t42").expect("write");
         drop(f);
 
-        reduce_shard(&input, &output, Reducer::Sum).expect("sum should saturate, not panic");
+        reduce_shard(&input, &output, Reducer::Sum, IntermediateFormat::Text)
+            .expect("sum should saturate, not panic");
         let rows = read_kv_tsv(&output);
         assert_eq!(rows.get("alice").copied(), Some(i64::MAX));
         assert_eq!(rows.get("bob").copied(), Some(42));
@@ -410,7 +717,8 @@ mod tests {
         writeln!(f, "alice\t-1").expect("write");
         drop(f);
 
-        reduce_shard(&input, &output, Reducer::Sum).expect("sum should saturate, not panic");
+        reduce_shard(&input, &output, Reducer::Sum, IntermediateFormat::Text)
+            .expect("sum should saturate, not panic");
         let rows = read_kv_tsv(&output);
         assert_eq!(rows.get("alice").copied(), Some(i64::MIN));
     }
@@ -433,7 +741,8 @@ mod tests {
         writeln!(f, "mixed\t{}", i64::MAX).expect("write");
         drop(f);
 
-        reduce_shard(&input, &output, Reducer::Min).expect("min should succeed");
+        reduce_shard(&input, &output, Reducer::Min, IntermediateFormat::Text)
+            .expect("min should succeed");
         let rows = read_kv_tsv(&output);
         assert_eq!(rows.get("lonely_max").copied(), Some(i64::MAX));
         assert_eq!(rows.get("pair_max").copied(), Some(i64::MAX));
@@ -457,9 +766,53 @@ mod tests {
         writeln!(f, "b\t-100").expect("write");
         drop(f);
 
-        reduce_shard(&input, &output, Reducer::Min).expect("min should succeed");
+        reduce_shard(&input, &output, Reducer::Min, IntermediateFormat::Text)
+            .expect("min should succeed");
         let rows = read_kv_tsv(&output);
         assert_eq!(rows.get("a").copied(), Some(10));
         assert_eq!(rows.get("b").copied(), Some(-100));
     }
+
+    #[test]
+    fn reduce_shard_max_picks_largest_value() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let input = tmp.path().join("kv_0000.tmp");
+        let output = tmp.path().join("kv_0000.tsv");
+
+        let mut f = std::fs::File::create(&input).expect("create input");
+        writeln!(f, "a\t10").expect("write");
+        writeln!(f, "a\t30").expect("write");
+        writeln!(f, "a\t20").expect("write");
+        writeln!(f, "b\t-100").expect("write");
+        writeln!(f, "b\t-5").expect("write");
+        writeln!(f, "lonely_min\t{}", i64::MIN).expect("write");
+        drop(f);
+
+        reduce_shard(&input, &output, Reducer::Max, IntermediateFormat::Text)
+            .expect("max should succeed");
+        let rows = read_kv_tsv(&output);
+        assert_eq!(rows.get("a").copied(), Some(30));
+        assert_eq!(rows.get("b").copied(), Some(-5));
+        assert_eq!(rows.get("lonely_min").copied(), Some(i64::MIN));
+    }
+
+    #[test]
+    fn reduce_shard_argmin_keeps_payload_of_smallest_value() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let input = tmp.path().join("kv_0000.tmp");
+        let output = tmp.path().join("kv_0000.tsv");
+
+        let mut f = std::fs::File::create(&input).expect("create input");
+        writeln!(f, "a\t30\tlate\tx3").expect("write");
+        writeln!(f, "a\t10\tearly\tx1").expect("write");
+        writeln!(f, "a\t20\tmiddle\tx2").expect("write");
+        writeln!(f, "b\t5\tzeta").expect("write");
+        writeln!(f, "b\t5\talpha").expect("write");
+        drop(f);
+
+        reduce_shard(&input, &output, Reducer::ArgMin, IntermediateFormat::Text)
+            .expect("argmin should succeed");
+        let text = std::fs::read_to_string(&output).expect("read output");
+        assert_eq!(text, "a\t10\tearly\tx1\nb\t5\talpha\n");
+    }
 }
@@ -22,7 +22,8 @@
 //!      `inflight_bytes` (default 256 MiB; cap on producer→consumer
 //!      backpressure), `inflight_groups` (bucketing channel depth),
 //!      `adaptive_mem` thresholds, `resume`, `allow_partial`, IO buffer
-//!      sizes, `zst_level`.
+//!      sizes, `zst_level`, and an optional trained `zstd_dictionary`
+//!      ([`train_zstd_dictionary`] builds one from sampled records).
 //!    - [`YearMonth`] / `iter_year_months` — inclusive month range cursors.
 //!    - [`ScanPlan`] / [`QuerySpec`] — the query builder returned by
 //!      [`RedditETL::scan`], plus subreddit / record-ID / author / regex /
@@ -45,8 +46,21 @@
 //!    - `discover_sources_checked` / `plan_files_checked` (doc(hidden)) walk
 //!      the corpus and emit [`FileJob`]s honoring the configured sources and
 //!      date bounds while surfacing directory/filename diagnostics.
+//!    - `RedditETL::plan` / `ScanPlan::plan` — dry run returning the same
+//!      planned files as [`PlannedFile`]s with compressed sizes;
+//!      `plan_stats` totals them as [`PlanStats`], with `matched_files == 0`
+//!      rather than an error when no file falls in the date range.
+//!    - `ScanPlan::estimate_output` samples the head of each planned file and
+//!      projects matched records and bytes as an [`OutputEstimate`].
+//!    - `RedditETL::corpus_bounds` — earliest/latest month and month count per
+//!      source as [`CorpusBounds`], ignoring the configured date range;
+//!      `RedditETL::last_n_months` limits a run to the newest `n` months,
+//!      resolved when it plans its files.
+//!    - `RedditETL::for_each_planned_file` — public per-file hook: runs a
+//!      callback on each planned [`FileJob`] under `file_concurrency`.
 //!    - [`for_each_file_limited`] drives the per-file fan-out under a scoped
-//!      Rayon pool.
+//!      Rayon pool, kept within the open-file budget
+//!      ([`set_open_file_budget`]).
 //!
 //! 3. **Decode & filter (hot loop)**
 //!    - [`MinimalRecord`] + [`parse_minimal`] — line-level fast-path schema.
@@ -55,20 +69,33 @@
 //!    - The slow path (whitelist/full parse) is taken only when
 //!      `QuerySpec::requires_full_parse()` returns true; otherwise the
 //!      minimal struct alone decides keep/drop.
+//!    - [`Record`] + `ScanPlan::for_each_record` — callback access to each
+//!      matched line: fast-path accessors for common fields, full parse only
+//!      on the first arbitrary-field `get`. `ScanPlan::for_each_group`
+//!      groups matched lines by a [`KeyExtractor`] key (possibly in partial
+//!      groups, like [`process_bucket_streaming`]).
+//!      `ScanPlan::for_each_batch` hands parsed records over in fixed-size
+//!      batches for vectorized consumers.
 //!    - [`for_each_line_cfg`] / [`quick_validate_zst`] / [`validate_zst_full`]
 //!      — zstd readers configured with `window_log_max(31)` so frames written
 //!      at the spec's max window size decode without "Frame requires too much
 //!      memory." Corpus scans are strict by default; opt into
 //!      `allow_partial` only when lossy skipped-file reporting is acceptable.
+//!      [`for_each_line_reader`] decodes from any `Read` (stdin, an
+//!      in-memory buffer) instead of a path.
 //!
 //! 4. **Emit**
 //!    - `stream_job` drives the per-file scan + write; [`apply_human_timestamps`]
 //!      rewrites unix epochs to
 //!      RFC3339 when enabled.
 //!    - [`ShardedWriter`] (per-month spool) and [`ShardedKVWriter`]
-//!      (per-author shards) handle on-disk fan-out.
+//!      (per-author shards) handle on-disk fan-out. KV keys are escaped
+//!      (`\t`, `\n`, `\r`, `\\`) so reduced TSVs stay one row per key;
+//!      [`unescape_kv_key`] reverses it.
 //!    - `ScanPlan::export_partitioned` (called after `RedditETL::scan`)
 //!      writes RC/RS partitions as JSONL or ZST.
+//!      `ScanPlan::export_partitioned_by` partitions by a [`KeyExtractor`]
+//!      key instead.
 //!    - [`NdjsonReader`] / [`NdjsonWriter`] are the line-buffered helpers
 //!      used by stitch and dedupe.
 //!
@@ -80,7 +107,9 @@
 //!      `KeyExtractor::key_from_line` uses [`MinimalRecord`] for the common
 //!      `author`/`subreddit` keys; pointer/custom keys fall back to a full
 //!      `serde_json::Value` parse.
-//!    - [`Aggregator`] computes per-author / per-month rollups.
+//!    - [`Aggregator`] computes per-author / per-month rollups;
+//!      [`SumField`] / [`CountByField`] / [`MinMaxField`] cover common
+//!      single-field metrics without a custom impl.
 //!    - [`ParentIds`] / [`ParentMaps`] resolve parent content for the
 //!      parents-pipeline.
 //!
@@ -108,24 +137,45 @@
 //!      decoders, also re-exported for direct use; `quick_validate_zst`
 //!      returns a [`QuickOutcome`] flagging whether a small file was decoded
 //!      in full.
+//!    - Full mode also fills [`IntegrityReport::file_stats`] with each passing
+//!      file's decoded bytes and line count ([`validate_zst_full_stats`]), so
+//!      an integrity run doubles as a corpus-size inventory.
+//!    - `RedditETL::integrity_file_timeout` bounds each file's check; a file
+//!      that overruns it is reported as [`INTEGRITY_TIMEOUT_ERROR`].
+//!    - `RedditETL::integrity_on_corrupt` with [`CorruptAction::Quarantine`]
+//!      or [`CorruptAction::Delete`] moves or removes files that failed to
+//!      decode in the same pass; [`IntegrityReport::corrupt_actions`] records each outcome.
 //!
 //! ## Cross-cutting helpers
 //!
 //! - [`open_with_backoff`] / [`create_with_backoff`] /
 //!   [`create_dir_all_with_backoff`] / [`read_dir_with_backoff`] /
 //!   [`remove_with_backoff`] / [`replace_file_atomic_backoff`] —
-//!   Windows-friendly retry/backoff over transient sharing/AV errors.
+//!   Windows-friendly retry/backoff over transient sharing/AV errors;
+//!   [`set_io_retries`] tunes the budget of the `*_with_default_backoff`
+//!   variants process-wide, and [`is_retriable_io_error`] exposes their
+//!   classifier for custom file loops. [`set_fsync_outputs`] makes every
+//!   atomic publish sync to disk first.
 //! - [`with_thread_pool`] — scoped Rayon pool (preferred over
 //!   `build_global`).
 //! - [`init_tracing_for_binary`] — *binary-only* tracing init; library code
 //!   must not call it.
 //! - [`set_global_multiprogress`] / [`make_count_progress`] /
-//!   [`make_progress_bar_labeled`] / [`ProgressScope`] — indicatif glue.
+//!   [`make_weighted_count_progress`] /
+//!   [`make_progress_bar_labeled`] / [`ProgressScope`] — indicatif glue;
+//!   [`PipelineProgress`] stacks multi-stage runs under one overall bar.
+//!   [`ProgressBar`] / [`MultiProgress`] are the `indicatif` types, or
+//!   draw-free shims when the default `progress` feature is disabled.
 //! - [`PartitionWriters`] — standalone user-keyed NDJSON fan-out helper that
 //!   writes `<stem>_part_NNNN.ndjson`; it is not the RC/RS JSONL/ZST
 //!   partitioned export path.
 //! - [`available_memory_fraction`] / [`is_low_memory`] — adaptive throttling
-//!   knobs for binaries.
+//!   knobs for binaries; [`available_memory_bytes`] / [`total_memory_bytes`]
+//!   give the same cached reading in bytes, and [`set_memory_budget_bytes`]
+//!   pins them to a fixed budget (e.g. a container limit).
+//! - [`RetlError`] — returned by the `RedditETL` / `ScanPlan` operations;
+//!   classifies a failure as config / I/O / decode (with the file path) /
+//!   cancelled / other. A callback returns [`Cancelled`] to stop a scan.
 //!
 //! See `CLAUDE.md` at the repository root for invariants (atomic-write
 //! contract, backpressure model, taskboard rules) and bench/fuzz commands.
@@ -133,9 +183,11 @@
 mod config;
 mod corpus_manifest;
 mod date;
+mod error;
 mod kv_shard;
 mod paths;
 mod query;
+mod record;
 mod shard;
 mod shard_common;
 mod username_stream;
@@ -160,9 +212,11 @@ mod integrity;
 mod parents;
 mod parents_ids;
 mod partition;
+mod quantile;
 
 mod bucketing;
 mod dedupe;
+mod frames;
 mod json_utils;
 mod json_whitelist;
 mod key_extractor;
@@ -176,33 +230,41 @@ pub use crate::obs::{
 };
 
 pub use crate::config::{
-    max_parallelism_limit, ConfigBuildError, ETLOptions, PartialReadReport, PartialReadReporter,
-    SkippedFile, Sources, DEFAULT_PARQUET_COMPRESSION, DEFAULT_PARQUET_ROW_GROUP_SIZE,
-    MAX_FILE_CONCURRENCY, MAX_IO_BUFFER, MAX_RAYON_THREADS, MAX_SHARDS, MIN_IO_BUFFER,
+    max_parallelism_limit, ConfigBuildError, ETLOptions, IntermediateFormat, PartialReadReport,
+    PartialReadReporter, RejectSink, SkippedFile, Sources, TuningProfile,
+    DEFAULT_IN_MEMORY_SHARD_SPILL_BYTES, DEFAULT_PARQUET_COMPRESSION,
+    DEFAULT_PARQUET_ROW_GROUP_SIZE, DEFAULT_SHARD_FLUSH_BYTES, MAX_FILE_CONCURRENCY, MAX_IO_BUFFER,
+    MAX_RAYON_THREADS, MAX_SHARDS, MIN_IO_BUFFER,
 };
 pub use crate::corpus_manifest::{
     CorpusAvailability, CorpusLocalStatus, CorpusManifest, CorpusManifestError, CorpusManifestFile,
     CorpusPlanItem, CorpusSource, CorpusSourceManifest, CorpusUnavailableRange,
 };
 pub use crate::date::YearMonth;
+pub use crate::error::{Cancelled, RetlError};
+pub use crate::paths::{DuplicateMonthPolicy, FileJob, FileKind};
 pub use crate::pipeline::{RedditETL, ScanPlan};
 pub use crate::pipeline_exec::{
-    convert_jsonl_to_csv, convert_jsonl_to_tsv, DedupeKeySummary, ExportFormat,
-    TabularExportOptions,
+    convert_jsonl_to_csv, convert_jsonl_to_tsv, CorpusBounds, DedupeKeySummary, ExportFormat,
+    OutputEstimate, PlanStats, PlannedFile, SourceBounds, TabularExportOptions,
+    MAX_OPEN_KEY_PARTITIONS,
 };
 pub use crate::query::{
-    read_record_ids_file, JsonPointerPredicate, NumericComparison, QueryBuildError, QuerySpec,
-    TimestampBounds,
+    normalize_all, normalize_author, normalize_str, read_record_ids_file, EditedMode,
+    JsonPointerPredicate, NumericComparison, QueryBuildError, QuerySpec, SubredditSample,
+    TimeField, TimestampBounds,
 };
 pub use crate::run_manifest::{
     discover_upstream_manifests_from_inputs, file_identities, file_identity,
     manifest_path_for_directory, manifest_path_for_file, path_to_stable_string,
     upstream_manifest_for_directory, write_run_manifest, CorpusSnapshot, FileIdentity, GeneratedBy,
-    ManifestDestination, OutputSnapshot, ResumeSnapshot, RunManifest, RunManifestInput,
-    RunManifestStart, UpstreamManifest, DIR_MANIFEST_NAME, FILE_MANIFEST_SUFFIX,
+    ManifestDestination, OutputFileEntry, OutputSnapshot, ResumeSnapshot, RunManifest,
+    RunManifestInput, RunManifestStart, UpstreamManifest, DIR_MANIFEST_NAME, FILE_MANIFEST_SUFFIX,
 };
+pub use crate::record::Record;
 pub use crate::shard::UsernameStream;
 
+pub use crate::aggregate::common::{CountByField, MinMaxField, SumField};
 pub use crate::aggregate::{
     AggregateBuildReport, AggregateInputIssue, AggregatePartialReadPolicy, Aggregator,
 };
@@ -212,6 +274,7 @@ pub use crate::agg_expr::{
 };
 pub use crate::parents::{
     ParentAttachStats, ParentIds, ParentMaps, ParentPayload, ParentPayloadSpec,
+    DEFAULT_MAX_REPLY_DEPTH,
 };
 
 #[doc(hidden)]
@@ -219,16 +282,21 @@ pub use crate::aggregate::{merge_aggregator_shards_parallel, merge_aggregator_sh
 
 // Expose multiprogress and progress helpers.
 pub use crate::progress::{
-    make_count_progress, make_progress_bar_labeled, set_global_multiprogress,
-    total_compressed_size, ProgressScope,
+    file_sizes, make_count_progress, make_progress_bar_labeled, make_weighted_count_progress,
+    set_global_multiprogress, total_compressed_size, MultiProgress, PipelineProgress, ProgressBar,
+    ProgressScope, ProgressStage,
 };
 
 // Expose memory helpers for adaptive throttling from the binary.
 pub use crate::mem::{
-    available_memory_fraction, is_low_memory, maybe_throttle_low_memory, sysinfo_ok,
-    FALLBACK_FRAC_ENV,
+    available_memory_bytes, available_memory_fraction, is_low_memory,
+    maybe_throttle_low_memory, memory_budget_bytes, set_memory_budget_bytes, sysinfo_ok,
+    total_memory_bytes, FALLBACK_FRAC_ENV,
 };
 
+// Process-wide cap on files held open at once by the per-file fan-out.
+pub use crate::concurrency::{open_file_budget, set_open_file_budget, FDS_PER_FILE_JOB};
+
 // Test-only injection point so integration tests can drive the cooperative
 // throttles in dedupe/bucketing/zstd_jsonl. Strictly gated; production
 // builds (no `test-utils`) don't see this symbol.
@@ -245,8 +313,17 @@ pub use crate::mem::set_available_memory_fraction_for_tests;
 pub use crate::util::{cap_backoff_budget_for_test, TestBackoffBudgetGuard};
 
 // Expose integrity checker mode + report, and (optionally) direct zstd validators.
-pub use crate::integrity::{IntegrityMode, IntegrityReport, MAX_RETAINED_FAILURES};
-pub use crate::zstd_jsonl::{quick_validate_zst, validate_zst_full, QuickOutcome};
+pub use crate::integrity::{
+    CorruptAction, CorruptOutcome, IntegrityFileStats, IntegrityMode, IntegrityReport,
+    INTEGRITY_TIMEOUT_ERROR, MAX_RETAINED_FAILURES,
+};
+pub use crate::zstd_jsonl::{
+    quick_validate_zst, validate_zst_full, validate_zst_full_stats, QuickOutcome,
+};
+pub use crate::zstd_jsonl::{
+    max_line_bytes, set_max_line_bytes, set_zstd_dictionary, train_zstd_dictionary,
+    zstd_dictionary, ZstdDictionary, DEFAULT_ZSTD_DICTIONARY_BYTES,
+};
 
 //export partition writers (lambda-capable)
 pub use crate::partition::{PartitionFinalizeError, PartitionWriters, MAX_PARTITIONS};
@@ -257,13 +334,15 @@ pub use crate::atomic_write::{write_at_path_atomic, write_text_atomic, STAGING_D
 
 //export robust file ops from util so binaries can import from crate root.
 pub use crate::util::{
-    create_dir_all_with_backoff, create_dir_all_with_default_backoff, create_new_with_backoff,
-    create_new_with_default_backoff, create_with_backoff, create_with_default_backoff,
+    concat_jsonl_to_zst, create_dir_all_with_backoff, create_dir_all_with_default_backoff,
+    create_new_with_backoff, create_new_with_default_backoff, create_with_backoff,
+    create_with_default_backoff, fsync_outputs, io_retries, is_retriable_io_error,
     open_with_backoff, open_with_default_backoff, output_parent, read_dir_with_backoff,
     read_dir_with_default_backoff, remove_dir_all_with_backoff,
     remove_dir_all_with_default_backoff, remove_dir_all_with_short_backoff, remove_with_backoff,
     remove_with_default_backoff, remove_with_short_backoff, replace_file_atomic_backoff,
-    DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES, SHORT_BACKOFF_TRIES,
+    set_fsync_outputs, set_io_retries, DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES,
+    SHORT_BACKOFF_TRIES,
 };
 
 // Scoped rayon pool + opt-in tracing init for binaries.
@@ -274,9 +353,10 @@ pub use crate::bucketing::{
     bucketize_shards, bucketize_shards_with_key_stats, partition_stage1,
     partition_stage1_with_key_stats, process_bucket_streaming, BucketingCfg,
 };
-pub use crate::json_utils::{author_lower, is_comment_record, subreddit_lower};
+pub use crate::json_utils::{author_lower, is_comment_record, record_kind, subreddit_lower};
 
 // export NDJSON helpers
+pub use crate::frames::read_framed;
 pub use crate::ndjson::{read_line_capped, NdjsonReader, NdjsonWriter, DEFAULT_MAX_LINE_BYTES};
 
 // export streaming whitelist tokenizer for tests/benches
@@ -297,19 +377,24 @@ pub use crate::concurrency::for_each_file_limited;
 #[doc(hidden)]
 pub use crate::date::iter_year_months;
 #[doc(hidden)]
-pub use crate::filters::{bounds_tuple, resolve_target_subs_from, within_bounds, DateBounds};
+pub use crate::filters::{
+    bounds_tuple, resolve_target_subs_from, within_bounds, within_bounds_on, DateBounds,
+};
+pub use crate::kv_shard::{escape_kv_key, unescape_kv_key};
 #[doc(hidden)]
 pub use crate::kv_shard::ShardedKVWriter;
 #[doc(hidden)]
 pub use crate::paths::{
     discover_all, discover_all_checked, discover_sources_checked, format_year_month_ranges,
     log_missing_month_warnings, missing_month_diagnostics, plan_files, plan_files_checked,
-    Discovered, FileJob, FileKind, MissingMonthDiagnostic, PlanningError, SourceStatus,
+    Discovered, MissingMonthDiagnostic, PlanningError, SourceStatus,
 };
 #[doc(hidden)]
+pub use crate::paths::discover_roots_checked;
+#[doc(hidden)]
 pub use crate::shard::ShardedWriter;
 #[doc(hidden)]
-pub use crate::zstd_jsonl::{parse_minimal, MinimalRecord};
+pub use crate::zstd_jsonl::{for_each_line_reader, parse_minimal, MinimalRecord};
 
 // Bench-only re-exports of hot inner-loop functions. Used by `benches/inner_loops.rs`
 // (criterion harness) to defend ahash/byte-rewrite perf changes against regressions.
@@ -2,7 +2,7 @@ use parking_lot::Mutex;
 use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use sysinfo::System;
+use sysinfo::{Pid, ProcessesToUpdate, System};
 
 /// Cached, low-overhead memory watcher.
 /// - Refreshes at most every `REFRESH_EVERY`.
@@ -33,6 +33,15 @@ static FRAC_PPM: AtomicU64 = AtomicU64::new(1_000_000);
 /// Millis-since-`EPOCH` at which `FRAC_PPM` was last updated.
 static LAST_CHECK_MS: AtomicU64 = AtomicU64::new(0);
 
+/// Raw `available_memory()` / `total_memory()` bytes from the last refresh.
+/// `0` until the first refresh, and whenever sysinfo reports `total == 0`.
+static AVAIL_BYTES: AtomicU64 = AtomicU64::new(0);
+static TOTAL_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Fixed memory budget in bytes set by [`set_memory_budget_bytes`]; `0`
+/// means "unset, use system RAM".
+static BUDGET_BYTES: AtomicU64 = AtomicU64::new(0);
+
 /// Mutex serializing the underlying `sysinfo` refresh. The hot read path
 /// never touches this.
 static REFRESH_LOCK: OnceLock<Mutex<System>> = OnceLock::new();
@@ -94,11 +103,57 @@ fn refresh_lock() -> &'static Mutex<System> {
     REFRESH_LOCK.get_or_init(|| Mutex::new(System::new()))
 }
 
+/// Pin the memory watcher to a fixed budget instead of system RAM, or
+/// restore system RAM with `None`.
+///
+/// Containers often see the host's RAM through `sysinfo` rather than their
+/// cgroup limit, so the cooperative throttles would wait for host memory
+/// pressure that never comes before the container is OOM-killed. With a
+/// budget set, [`available_memory_fraction`], [`available_memory_bytes`] and
+/// [`total_memory_bytes`] measure against `budget` instead.
+///
+/// Usage is estimated as this process's resident set size, sampled through
+/// `sysinfo` on the same refresh cadence as the system reading:
+/// `available = budget - rss`, further capped by the system's own available
+/// memory so a genuinely short host still throttles. RSS does not count other
+/// processes sharing the cgroup or reclaimable page cache, so leave headroom
+/// below the hard limit. If the process's RSS cannot be read, readings fall
+/// back to system RAM. A `Some(0)` budget is treated as one byte.
+///
+/// Takes effect on the next read; the cached reading is invalidated.
+pub fn set_memory_budget_bytes(budget: Option<u64>) {
+    BUDGET_BYTES.store(budget.map_or(0, |b| b.max(1)), Ordering::Relaxed);
+    LAST_CHECK_MS.store(0, Ordering::Relaxed);
+}
+
+/// The budget set by [`set_memory_budget_bytes`], if any.
+pub fn memory_budget_bytes() -> Option<u64> {
+    match BUDGET_BYTES.load(Ordering::Relaxed) {
+        0 => None,
+        b => Some(b),
+    }
+}
+
+/// `(budget, available)` under a [`set_memory_budget_bytes`] budget, or
+/// `None` when no budget is set or this process's RSS cannot be read.
+fn budget_reading(sys: &mut System) -> Option<(u64, u64)> {
+    let budget = memory_budget_bytes()?;
+    let pid = Pid::from_u32(std::process::id());
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    let rss = sys.process(pid)?.memory();
+    let mut available = budget.saturating_sub(rss);
+    if sys.total_memory() > 0 {
+        available = available.min(sys.available_memory());
+    }
+    Some((budget, available))
+}
+
 /// Returns a recent estimate of available memory fraction (`0.0..=1.0`).
 ///
 /// Backed by a cached `sysinfo::System::refresh_memory()` reading. The hot
 /// path is two relaxed atomic loads — no mutex, no syscall — so this is
-/// cheap enough to call inside tight loops.
+/// cheap enough to call inside tight loops. With a
+/// [`set_memory_budget_bytes`] budget, the fraction is of that budget.
 ///
 /// # Fallback when sysinfo is unusable
 ///
@@ -127,11 +182,19 @@ fn refresh_lock() -> &'static Mutex<System> {
 /// resume; `sysinfo_ok()` stays `false` so the prior failure is still
 /// visible.
 pub fn available_memory_fraction() -> f64 {
+    refresh_cached_memory(false)
+        .unwrap_or_else(|| FRAC_PPM.load(Ordering::Relaxed) as f64 / PPM)
+}
+
+/// Refresh the cached readings when they are older than `REFRESH_EVERY` (or
+/// unconditionally with `force`). Returns the new fraction when this call did
+/// the refresh, `None` when the cache was still fresh.
+fn refresh_cached_memory(force: bool) -> Option<f64> {
     // Hot path: two relaxed loads. No mutex, no syscall.
     let now = now_ms();
     let last = LAST_CHECK_MS.load(Ordering::Relaxed);
-    if now.saturating_sub(last) < REFRESH_EVERY_MS {
-        return FRAC_PPM.load(Ordering::Relaxed) as f64 / PPM;
+    if !force && now.saturating_sub(last) < REFRESH_EVERY_MS {
+        return None;
     }
 
     // Slow path: serialize the sysinfo refresh. Re-check under the lock so
@@ -139,12 +202,14 @@ pub fn available_memory_fraction() -> f64 {
     let mut sys = refresh_lock().lock();
     let now = now_ms();
     let last = LAST_CHECK_MS.load(Ordering::Relaxed);
-    if now.saturating_sub(last) < REFRESH_EVERY_MS {
-        return FRAC_PPM.load(Ordering::Relaxed) as f64 / PPM;
+    if !force && now.saturating_sub(last) < REFRESH_EVERY_MS {
+        return None;
     }
     sys.refresh_memory();
-    let total_raw = sys.total_memory();
-    let avail_raw = sys.available_memory();
+    let (total_raw, avail_raw) = budget_reading(&mut sys)
+        .unwrap_or_else(|| (sys.total_memory(), sys.available_memory()));
+    TOTAL_BYTES.store(total_raw, Ordering::Relaxed);
+    AVAIL_BYTES.store(avail_raw.min(total_raw), Ordering::Relaxed);
     let frac = if total_raw > 0 {
         ((avail_raw as f64) / (total_raw as f64)).clamp(0.0, 1.0)
     } else {
@@ -165,7 +230,29 @@ pub fn available_memory_fraction() -> f64 {
     };
     FRAC_PPM.store((frac * PPM) as u64, Ordering::Relaxed);
     LAST_CHECK_MS.store(now, Ordering::Relaxed);
-    frac
+    Some(frac)
+}
+
+/// Cached byte reading, refreshed on the same cadence as
+/// [`available_memory_fraction`]. Forces a refresh when no reading has been
+/// taken yet (e.g. the test setter stamped the cache before any refresh).
+fn cached_memory_bytes(slot: &AtomicU64) -> u64 {
+    refresh_cached_memory(TOTAL_BYTES.load(Ordering::Relaxed) == 0 && sysinfo_ok());
+    slot.load(Ordering::Relaxed)
+}
+
+/// Recent available system memory in bytes, from the same cached `sysinfo`
+/// reading as [`available_memory_fraction`]. Never exceeds
+/// [`total_memory_bytes`]. Returns `0` when sysinfo is unusable (see
+/// [`sysinfo_ok`]); unlike the fraction, there is no env-var fallback.
+pub fn available_memory_bytes() -> u64 {
+    cached_memory_bytes(&AVAIL_BYTES)
+}
+
+/// Total system memory in bytes, from the same cached `sysinfo` reading as
+/// [`available_memory_fraction`]. Returns `0` when sysinfo is unusable.
+pub fn total_memory_bytes() -> u64 {
+    cached_memory_bytes(&TOTAL_BYTES)
 }
 
 /// Returns true if the cached available-memory fraction is below `threshold` (e.g., 0.10 for 10%).
@@ -1,7 +1,7 @@
 use crate::util::replace_file_atomic_backoff;
 use anyhow::{Context, Result};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::io::{self, BufRead, BufWriter, Write};
 use std::path::{Path, PathBuf};
 
 /// Default upper bound (16 MiB) on a single NDJSON line.
@@ -15,14 +15,15 @@ use std::path::{Path, PathBuf};
 pub const DEFAULT_MAX_LINE_BYTES: usize = 16 * 1024 * 1024;
 
 /// Minimal NDJSON reader with buffering and empty-line trimming.
-/// Uses robust open-with-backoff for Windows-friendliness.
+/// Uses robust open-with-backoff for Windows-friendliness, and reads
+/// `.gz`/`.zst` files decompressed (chosen by extension).
 ///
 /// Each line is bounded by `max_line_bytes` (default
 /// [`DEFAULT_MAX_LINE_BYTES`]). Exceeding the cap surfaces as
 /// `io::ErrorKind::InvalidData` rather than allowing unbounded buffer
 /// growth.
 pub struct NdjsonReader {
-    rdr: BufReader<File>,
+    rdr: Box<dyn BufRead + Send>,
     path: PathBuf,
     max_line_bytes: usize,
 }
@@ -36,9 +37,8 @@ impl NdjsonReader {
     /// knows the input may contain unusually large records and wants to
     /// raise (or tighten) the default 16 MiB ceiling.
     pub fn open_with_max(path: &Path, buf_bytes: usize, max_line_bytes: usize) -> io::Result<Self> {
-        let f = crate::util::open_with_default_backoff(path)?;
         Ok(Self {
-            rdr: BufReader::with_capacity(buf_bytes.max(8 * 1024), f),
+            rdr: crate::util::open_maybe_compressed(path, buf_bytes.max(8 * 1024))?,
             path: path.to_path_buf(),
             max_line_bytes,
         })
@@ -71,23 +71,37 @@ impl NdjsonReader {
 /// payload to this type to tell a record-level problem apart from a genuine
 /// zstd-frame decode error. Without the marker, an invalid-UTF-8 line was
 /// misclassified as a tolerated corrupt-frame skip under
-/// `PartialReadPolicy::AllowPartial`; with it, both the cap and the UTF-8
-/// violation stay fatal regardless of partial-read policy. `Display` forwards
-/// the human-readable message verbatim, so `io::Error::to_string()` is
-/// unchanged from a plain `io::Error::new(InvalidData, msg)`.
+/// `PartialReadPolicy::AllowPartial`; with it, the UTF-8 violation stays
+/// fatal regardless of partial-read policy. A cap violation sets
+/// [`InvalidLineError::is_over_cap`]: the zstd streamer treats a newline-free
+/// run that long as stream corruption and applies the partial-read policy.
+/// `Display` forwards the human-readable message verbatim, so
+/// `io::Error::to_string()` is unchanged from a plain
+/// `io::Error::new(InvalidData, msg)`.
 #[derive(Debug)]
-pub(crate) struct InvalidLineError(String);
+pub(crate) struct InvalidLineError {
+    message: String,
+    over_cap: bool,
+}
 
 impl InvalidLineError {
     /// Wrap `msg` as an `InvalidData` `io::Error` carrying this marker.
-    fn io_error(msg: String) -> io::Error {
-        io::Error::new(io::ErrorKind::InvalidData, InvalidLineError(msg))
+    fn io_error(message: String, over_cap: bool) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            InvalidLineError { message, over_cap },
+        )
+    }
+
+    /// Whether the line exceeded the byte cap (rather than being invalid UTF-8).
+    pub(crate) fn is_over_cap(&self) -> bool {
+        self.over_cap
     }
 }
 
 impl std::fmt::Display for InvalidLineError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        f.write_str(&self.message)
     }
 }
 
@@ -130,14 +144,15 @@ pub fn read_line_capped<R: BufRead>(
             None => (available.len(), false),
         };
         if bytes.len().saturating_add(take) > max_bytes {
-            return Err(InvalidLineError::io_error(format!(
+            let message = format!(
                 "JSONL line in {} exceeds max_line_bytes={} (read {} so far; next chunk would push to {}). \
                  Bump NdjsonReader::with_max_line_bytes or raise the caller's per-line cap to accept larger records.",
                 path.display(),
                 max_bytes,
                 bytes.len(),
                 bytes.len().saturating_add(take)
-            )));
+            );
+            return Err(InvalidLineError::io_error(message, true));
         }
         bytes.extend_from_slice(&available[..take]);
         reader.consume(take);
@@ -146,20 +161,18 @@ pub fn read_line_capped<R: BufRead>(
         }
     }
     if bytes.is_empty() {
-        // `buf` already holds the empty `String` left by `mem::take`.
+        // Hand the (empty) allocation back so EOF does not cost the caller
+        // its reused buffer.
+        *buf = String::from_utf8(bytes).unwrap_or_default();
         return Ok(0);
     }
     let raw_len = bytes.len();
     // Invalid UTF-8 is a record-level fault, not zstd-frame corruption: mark it
-    // with `InvalidLineError` (same as the cap violation above) so the zstd
-    // streamer classifies it as a fatal `InvalidLine` rather than a tolerated
-    // decode skip.
+    // with `InvalidLineError` so the zstd streamer classifies it as a fatal
+    // `InvalidLine` rather than a tolerated decode skip.
     *buf = String::from_utf8(bytes).map_err(|e| {
-        InvalidLineError::io_error(format!(
-            "JSONL line in {} is not valid UTF-8: {}",
-            path.display(),
-            e
-        ))
+        let message = format!("JSONL line in {} is not valid UTF-8: {}", path.display(), e);
+        InvalidLineError::io_error(message, false)
     })?;
     if buf.ends_with('\n') {
         buf.pop();
@@ -213,7 +226,7 @@ impl NdjsonWriter {
     }
 }
 
-/// Stream a plain JSONL file line-by-line, calling `on_line` with each raw line
+/// Stream a JSONL file line-by-line, calling `on_line` with each raw line
 /// (trailing `\r?\n` stripped, empty lines included).
 ///
 /// Mirrors `zstd_jsonl::for_each_line_cfg` swallow-and-warn semantics for
@@ -224,8 +237,9 @@ impl NdjsonWriter {
 /// clean read, letting callers (e.g. aggregator shard build) decide whether
 /// to drop or merge partial input.
 ///
-/// File-open errors and `on_line` errors are propagated to the caller; only
-/// per-line I/O errors are swallowed and surfaced in the returned `Option`.
+/// `.gz` and `.zst` inputs are decompressed by extension. File-open errors
+/// and `on_line` errors are propagated to the caller; only per-line I/O
+/// errors are swallowed and surfaced in the returned `Option`.
 /// Each line is bounded by [`DEFAULT_MAX_LINE_BYTES`]; a line that exceeds
 /// the cap is treated as a tolerated mid-file read error so a single
 /// oversized record cannot OOM the worker.
@@ -234,8 +248,7 @@ pub fn for_each_jsonl_line_cfg(
     read_buf_bytes: usize,
     mut on_line: impl FnMut(&str) -> Result<()>,
 ) -> Result<Option<io::Error>> {
-    let f = crate::util::open_with_default_backoff(path)?;
-    let mut reader = BufReader::with_capacity(read_buf_bytes.max(8 * 1024), f);
+    let mut reader = crate::util::open_maybe_compressed(path, read_buf_bytes.max(8 * 1024))?;
     let mut buf = String::with_capacity(16 * 1024);
     let mut read_error = None;
     loop {
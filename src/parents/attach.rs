@@ -49,16 +49,22 @@ fn owned_attach_output_base_name(name: &str) -> Option<String> {
     is_owned_spool_part_name(base).then(|| base.to_string())
 }
 
+/// File name of the attached output for `input`: the input's own name, minus
+/// a `.gz`/`.zst` suffix since attached output is always plain JSONL.
+fn attach_output_name(input: &Path) -> Result<String> {
+    let name = input.file_name().ok_or_else(|| {
+        anyhow::anyhow!(
+            "attach_parents input path has no file name: {}",
+            input.display()
+        )
+    })?;
+    Ok(crate::util::strip_compression_suffix(&name.to_string_lossy()).to_string())
+}
+
 fn attach_output_basenames(inputs: &[(usize, PathBuf)]) -> Result<BTreeSet<String>> {
     let mut names = BTreeSet::new();
     for (_idx, input) in inputs {
-        let name = input.file_name().ok_or_else(|| {
-            anyhow::anyhow!(
-                "attach_parents input path has no file name: {}",
-                input.display()
-            )
-        })?;
-        names.insert(name.to_string_lossy().to_string());
+        names.insert(attach_output_name(input)?);
     }
     Ok(names)
 }
@@ -66,8 +72,9 @@ fn attach_output_basenames(inputs: &[(usize, PathBuf)]) -> Result<BTreeSet<Strin
 /// Reject input sets in which two paths share a file name.
 ///
 /// `attach_parents_jsonls_parallel_with_stats` derives every output path as
-/// `out_dir.join(<input file name>)`, so two inputs from different directories
-/// with the same basename map to one output. The atomic publish would then let
+/// `out_dir.join(<input file name>)` (see `attach_output_name`), so two inputs
+/// from different directories with the same basename map to one output, as do
+/// `x.jsonl` and `x.jsonl.gz`. The atomic publish would then let
 /// the last writer win — one input's attached output silently lost, `attached`
 /// returning duplicate paths, and `prune_stale_attach_outputs` /
 /// `attach_output_basenames` collapsing the pair. The CLI never reaches this
@@ -76,16 +83,7 @@ fn attach_output_basenames(inputs: &[(usize, PathBuf)]) -> Result<BTreeSet<Strin
 fn ensure_unique_attach_basenames(inputs: &[(usize, PathBuf)]) -> Result<()> {
     let mut seen: HashMap<String, PathBuf> = HashMap::new();
     for (_idx, input) in inputs {
-        let name = input
-            .file_name()
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "attach_parents input path has no file name: {}",
-                    input.display()
-                )
-            })?
-            .to_string_lossy()
-            .to_string();
+        let name = attach_output_name(input)?;
         if let Some(prev) = seen.insert(name.clone(), input.clone()) {
             anyhow::bail!(
                 "attach_parents inputs collide on basename `{name}`: `{}` and `{}` would both \
@@ -139,6 +137,20 @@ struct AttachFileCtx<'a> {
     submission_shards: Option<&'a HashMap<YearMonth, PathBuf>>,
 }
 
+fn attach_file_ctx<'a>(
+    parents: &'a ParentMaps,
+    empty_parent_payloads: &'a HashMap<String, ParentPayload>,
+) -> AttachFileCtx<'a> {
+    AttachFileCtx {
+        legacy_payload: parents.payload_spec.is_legacy_default(),
+        parents_c_eager: &parents.comments,
+        parents_s_eager: &parents.submissions,
+        empty_parent_payloads,
+        comment_shards: parents.comment_shards.as_ref(),
+        submission_shards: parents.submission_shards.as_ref(),
+    }
+}
+
 /// Per-worker FIFO caches keyed by parent kind / payload mode. See
 /// `WorkerShardCache` for why eviction is plain FIFO with no bump-on-hit.
 struct AttachWorkerCaches {
@@ -149,14 +161,14 @@ struct AttachWorkerCaches {
 }
 
 impl AttachWorkerCaches {
-    fn new() -> Self {
+    /// `caps` is `ETLOptions::parent_cache_caps`; see `shard_cache_caps`.
+    fn new(caps: Option<(usize, usize)>) -> Self {
+        let (comment_cap, submission_cap) = shard_cache_caps(caps, available_memory_fraction());
         Self {
-            legacy_comments: WorkerShardCache::<String>::new(COMMENT_SHARD_CACHE_CAP),
-            legacy_submissions: WorkerShardCache::<(String, String)>::new(
-                SUBMISSION_SHARD_CACHE_CAP,
-            ),
-            full_comments: WorkerShardCache::<ParentPayload>::new(COMMENT_SHARD_CACHE_CAP),
-            full_submissions: WorkerShardCache::<ParentPayload>::new(SUBMISSION_SHARD_CACHE_CAP),
+            legacy_comments: WorkerShardCache::<String>::new(comment_cap),
+            legacy_submissions: WorkerShardCache::<(String, String)>::new(submission_cap),
+            full_comments: WorkerShardCache::<ParentPayload>::new(comment_cap),
+            full_submissions: WorkerShardCache::<ParentPayload>::new(submission_cap),
         }
     }
 }
@@ -238,10 +250,14 @@ fn resolve_parent_into_value(
 /// diagnostics, splice resolved parents onto comment-shaped records, and
 /// write each (possibly augmented) record to `w`. Returns the file's stats
 /// and diagnostics; `files_scanned` is always 1.
-fn attach_parents_for_one_file<W: std::io::Write + ?Sized>(
+/// Attach parents to every record of `in_path`. With `unresolved` set,
+/// records whose parent could not be resolved go there instead of `w`.
+fn attach_parents_for_one_file(
     in_path: &Path,
-    w: &mut W,
+    w: &mut dyn std::io::Write,
+    mut unresolved: Option<&mut dyn std::io::Write>,
     ctx: &AttachFileCtx<'_>,
+    cache_caps: Option<(usize, usize)>,
     is_rc_spool_part: bool,
 ) -> Result<(ParentAttachStats, ParentAttachDiagnostics)> {
     let mut file_stats = ParentAttachStats::default();
@@ -249,10 +265,9 @@ fn attach_parents_for_one_file<W: std::io::Write + ?Sized>(
         files_scanned: 1,
         ..Default::default()
     };
-    let mut caches = AttachWorkerCaches::new();
+    let mut caches = AttachWorkerCaches::new(cache_caps);
 
-    let f = crate::util::open_with_default_backoff(in_path)?;
-    let mut r = BufReader::new(f);
+    let mut r = crate::util::open_maybe_compressed(in_path, 8 * 1024)?;
     let mut line_buf = String::new();
     let mut line_no: u64 = 0;
 
@@ -280,72 +295,100 @@ fn attach_parents_for_one_file<W: std::io::Write + ?Sized>(
             )
         })?;
 
-        let has_body = v.get("body").is_some();
-        let parent_id_value = v.get("parent_id");
-        let has_parent_id = parent_id_value.is_some();
-        // A `parent_id` is usable only if it is a string prefixed `t1_`/`t3_`;
-        // anything else (unprefixed, numeric, null) is skipped by
-        // `is_comment_record_for_parent_attach` without being tallied.
-        let has_prefixed_parent_id = parent_id_value
-            .and_then(|x| x.as_str())
-            .is_some_and(|s| s.starts_with("t1_") || s.starts_with("t3_"));
-        let has_link_id = v.get("link_id").is_some();
-        diagnostics.parsed_records += 1;
-        if is_rc_spool_part {
-            diagnostics.rc_records += 1;
-        }
-        if has_body {
-            diagnostics.records_with_body += 1;
-        }
-        if has_parent_id {
-            diagnostics.records_with_parent_id += 1;
-        }
-        if has_parent_id && !has_prefixed_parent_id {
-            diagnostics.records_with_unprefixed_parent_id += 1;
-        }
-        if has_link_id {
-            diagnostics.records_with_link_id += 1;
-        }
+        let unresolved_before = file_stats.unresolved;
+        attach_parent_to_record(
+            &mut v,
+            ctx,
+            &mut caches,
+            &mut file_stats,
+            &mut diagnostics,
+            is_rc_spool_part,
+        )?;
 
-        if is_comment_record_for_parent_attach(&v) {
-            diagnostics.comment_shaped_records += 1;
-            // Own-month is derived from the consuming record's `created_utc`;
-            // used to pick the most-likely parent shard before falling back to
-            // other months.
-            let own_ym = v
-                .get("created_utc")
-                .and_then(|x| x.as_i64())
-                .map(ym_from_epoch);
-            let parent_id = v
-                .get("parent_id")
-                .and_then(|x| x.as_str())
-                .map(|s| s.to_string());
-            if let Some(parent_id) = parent_id {
-                let resolved = resolve_parent_into_value(&parent_id, own_ym, ctx, &mut caches)?;
-                if let Some(payload) = resolved {
-                    if let Some(map) = v.as_object_mut() {
-                        map.insert("parent".into(), Value::Object(payload));
-                    }
-                    file_stats.resolved += 1;
-                } else {
-                    file_stats.unresolved += 1;
+        let out: &mut dyn std::io::Write = match unresolved.as_deref_mut() {
+            Some(u) if file_stats.unresolved > unresolved_before => u,
+            _ => &mut *w,
+        };
+        serde_json::to_writer(&mut *out, &v)?;
+        out.write_all(b"\n")?;
+    }
+
+    Ok((file_stats, diagnostics))
+}
+
+/// Tally diagnostics for one parsed record and, when it is comment-shaped,
+/// splice its resolved parent under `"parent"`. Shared by the spool-part
+/// reader above and `ParentAttachWriter`, so both attach paths produce
+/// identical records and counts.
+fn attach_parent_to_record(
+    v: &mut Value,
+    ctx: &AttachFileCtx<'_>,
+    caches: &mut AttachWorkerCaches,
+    file_stats: &mut ParentAttachStats,
+    diagnostics: &mut ParentAttachDiagnostics,
+    is_rc_spool_part: bool,
+) -> Result<()> {
+    let has_body = v.get("body").is_some();
+    let parent_id_value = v.get("parent_id");
+    let has_parent_id = parent_id_value.is_some();
+    // A `parent_id` is usable only if it is a string prefixed `t1_`/`t3_`;
+    // anything else (unprefixed, numeric, null) is skipped by
+    // `is_comment_record_for_parent_attach` without being tallied.
+    let has_prefixed_parent_id = parent_id_value
+        .and_then(|x| x.as_str())
+        .is_some_and(|s| s.starts_with("t1_") || s.starts_with("t3_"));
+    let has_link_id = v.get("link_id").is_some();
+    diagnostics.parsed_records += 1;
+    if is_rc_spool_part {
+        diagnostics.rc_records += 1;
+    }
+    if has_body {
+        diagnostics.records_with_body += 1;
+    }
+    if has_parent_id {
+        diagnostics.records_with_parent_id += 1;
+    }
+    if has_parent_id && !has_prefixed_parent_id {
+        diagnostics.records_with_unprefixed_parent_id += 1;
+    }
+    if has_link_id {
+        diagnostics.records_with_link_id += 1;
+    }
+
+    if is_comment_record_for_parent_attach(v) {
+        diagnostics.comment_shaped_records += 1;
+        // Own-month is derived from the consuming record's `created_utc`;
+        // used to pick the most-likely parent shard before falling back to
+        // other months.
+        let own_ym = v
+            .get("created_utc")
+            .and_then(|x| x.as_i64())
+            .map(ym_from_epoch);
+        let parent_id = v
+            .get("parent_id")
+            .and_then(|x| x.as_str())
+            .map(|s| s.to_string());
+        if let Some(parent_id) = parent_id {
+            let resolved = resolve_parent_into_value(&parent_id, own_ym, ctx, caches)?;
+            if let Some(payload) = resolved {
+                if let Some(map) = v.as_object_mut() {
+                    map.insert("parent".into(), Value::Object(payload));
                 }
+                file_stats.resolved += 1;
+            } else {
+                file_stats.unresolved += 1;
             }
-        } else if has_parent_id {
-            // The record carries a `parent_id` field, but its value is not a
-            // `t1_`/`t3_`-prefixed string, so it can never be resolved. Count
-            // it as `unresolved` rather than dropping it from the tally:
-            // `ParentAttachStats::total()` must account for every record that
-            // claims a parent, or a spool full of malformed parent_ids reports
-            // a misleadingly clean `total()`. See `warn_if_malformed_parent_ids`.
-            file_stats.unresolved += 1;
         }
-
-        serde_json::to_writer(&mut *w, &v)?;
-        w.write_all(b"\n")?;
+    } else if has_parent_id {
+        // The record carries a `parent_id` field, but its value is not a
+        // `t1_`/`t3_`-prefixed string, so it can never be resolved. Count
+        // it as `unresolved` rather than dropping it from the tally:
+        // `ParentAttachStats::total()` must account for every record that
+        // claims a parent, or a spool full of malformed parent_ids reports
+        // a misleadingly clean `total()`. See `warn_if_malformed_parent_ids`.
+        file_stats.unresolved += 1;
     }
-
-    Ok((file_stats, diagnostics))
+    Ok(())
 }
 
 /// Build the `RunManifestInput` emitted by
@@ -392,6 +435,7 @@ fn build_attach_manifest(
         "parallelism": opts.parallelism,
         "file_concurrency": opts.file_concurrency,
         "emit_manifest": opts.emit_manifest,
+        "split_unresolved_parents": opts.split_unresolved_parents,
     });
     manifest.inputs = file_identities(manifest_inputs);
     manifest.output_format = "jsonl-directory".to_string();
@@ -6,7 +6,7 @@ impl RedditETL {
         out_dir: &Path,
         parents: &ParentMaps,
         resume: bool,
-    ) -> Result<Vec<PathBuf>> {
+    ) -> Result<Vec<PathBuf>, RetlError> {
         Ok(self
             .attach_parents_jsonls_parallel_with_stats(inputs, out_dir, parents, resume)?
             .0)
@@ -18,7 +18,8 @@ impl RedditETL {
         out_dir: &Path,
         parents: &ParentMaps,
         resume: bool,
-    ) -> Result<(Vec<PathBuf>, ParentAttachStats)> {
+    ) -> Result<(Vec<PathBuf>, ParentAttachStats), RetlError> {
+        let _run_settings = self.opts.enter_run_settings();
         with_thread_pool(self.opts.parallelism, || {
             let manifest_start = RunManifestStart::now();
             let manifest_inputs = inputs.clone();
@@ -33,28 +34,41 @@ impl RedditETL {
             let keep_basenames = attach_output_basenames(&indexed_inputs)?;
             prune_stale_attach_outputs(out_dir, &keep_basenames)?;
 
+            let split_unresolved = self.opts.split_unresolved_parents;
+            let unresolved_dirs = if split_unresolved {
+                let dir = out_dir.join(UNRESOLVED_ATTACH_DIR);
+                crate::util::create_dir_all_with_default_backoff(&dir).with_context(|| {
+                    format!("create unresolved-parent output dir {}", dir.display())
+                })?;
+                let staging = ensure_staging_dir(&dir)?;
+                sweep_stale_inprogress(&dir, true)?;
+                prune_stale_attach_outputs(&dir, &keep_basenames)?;
+                Some((dir, staging))
+            } else {
+                None
+            };
+
             let label = self
                 .opts
                 .progress_label
                 .as_deref()
                 .unwrap_or("Attaching parents");
+            let weighted_inputs = file_sizes(&manifest_inputs);
             let pb = if self.opts.progress {
-                Some(make_count_progress(indexed_inputs.len() as u64, label))
+                Some(make_stage_weighted_count_progress(
+                    &weighted_inputs,
+                    label,
+                    self.opts.progress_stage.as_ref(),
+                ))
             } else {
                 None
             };
 
             let empty_parent_payloads: HashMap<String, ParentPayload> = HashMap::new();
-            let file_ctx = AttachFileCtx {
-                legacy_payload: parents.payload_spec.is_legacy_default(),
-                parents_c_eager: &parents.comments,
-                parents_s_eager: &parents.submissions,
-                empty_parent_payloads: &empty_parent_payloads,
-                comment_shards: parents.comment_shards.as_ref(),
-                submission_shards: parents.submission_shards.as_ref(),
-            };
+            let file_ctx = attach_file_ctx(parents, &empty_parent_payloads);
             let parent_cache_fingerprint = attach_parent_cache_fingerprint(parents);
-            let resolution_range = attach_resolution_range(self.opts.start, self.opts.end);
+            let (start, end) = self.planned_date_range()?;
+            let resolution_range = attach_resolution_range(start, end);
 
             diagnose_initial_attach_shape(&indexed_inputs, self.opts.read_buffer_bytes)?;
             let attached = std::sync::Mutex::new(vec![None; indexed_inputs.len()]);
@@ -63,30 +77,32 @@ impl RedditETL {
                 &indexed_inputs,
                 self.opts.file_concurrency,
                 |(idx, in_path)| -> Result<()> {
-                    let name = in_path
-                        .file_name()
-                        .ok_or_else(|| {
-                            anyhow::anyhow!(
-                                "attach_parents input path has no file name: {}",
-                                in_path.display()
-                            )
-                        })?
-                        .to_string_lossy()
-                        .to_string();
-                    let out_path = out_dir.join(name);
-                    let inprogress_exists = attach_inprogress_exists(&staging_dir, &out_path)?;
+                    let name = attach_output_name(in_path)?;
+                    let out_path = out_dir.join(&name);
+                    let unresolved_path = unresolved_dirs.as_ref().map(|(dir, _)| dir.join(&name));
+                    let mut inprogress_exists = attach_inprogress_exists(&staging_dir, &out_path)?;
+                    if let (Some((_, staging)), Some(path)) = (&unresolved_dirs, &unresolved_path) {
+                        inprogress_exists |= attach_inprogress_exists(staging, path)?;
+                    }
                     let sidecar_path = attach_fingerprint_path(&out_path);
-                    let fingerprint = build_attach_fingerprint(
-                        in_path,
-                        &parent_cache_fingerprint,
-                        &resolution_range,
-                    );
+                    let fingerprint = AttachFingerprint {
+                        split_unresolved,
+                        ..build_attach_fingerprint(
+                            in_path,
+                            &parent_cache_fingerprint,
+                            &resolution_range,
+                        )
+                    };
+                    let mut published: Vec<&Path> = vec![&out_path];
+                    published.extend(unresolved_path.as_deref());
 
-                    if resume && out_path.exists() && !inprogress_exists {
+                    if resume && published.iter().all(|p| p.exists()) && !inprogress_exists {
                         if attach_fingerprint_matches(&sidecar_path, &fingerprint) {
-                            if validate_jsonl_file(&out_path) {
+                            if published.iter().all(|p| validate_jsonl_file(p))
+                                && attach_record_counts_match(in_path, &published)
+                            {
                                 if let Some(pb) = &pb {
-                                    pb.inc(1);
+                                    pb.inc(weighted_inputs[*idx].1);
                                 }
                                 let diagnostics = ParentAttachDiagnostics {
                                     resume_skipped_files: 1,
@@ -96,7 +112,7 @@ impl RedditETL {
                                     Some((out_path, ParentAttachStats::default(), diagnostics));
                                 return Ok(());
                             }
-                            tracing::warn!(path=%out_path.display(), "resume: existing attached JSONL is unreadable/corrupt, rebuilding");
+                            tracing::warn!(path=%out_path.display(), "resume: existing attached JSONL is unreadable/corrupt or its record count does not match the input, rebuilding");
                         } else {
                             tracing::debug!(path=%out_path.display(), sidecar=%sidecar_path.display(), "resume: attached JSONL fingerprint missing or stale, rebuilding");
                         }
@@ -107,7 +123,34 @@ impl RedditETL {
                         &staging_dir,
                         &out_path,
                         self.opts.write_buffer_bytes,
-                        |w| attach_parents_for_one_file(in_path, w, &file_ctx, is_rc_spool_part),
+                        |w| match (&unresolved_dirs, &unresolved_path) {
+                            // The unresolved output publishes first; the
+                            // main output and its sidecar, which resume
+                            // checks, only follow once both are complete.
+                            (Some((_, staging)), Some(path)) => write_jsonl_atomic(
+                                staging,
+                                path,
+                                self.opts.write_buffer_bytes,
+                                |u| {
+                                    attach_parents_for_one_file(
+                                        in_path,
+                                        w,
+                                        Some(u),
+                                        &file_ctx,
+                                        self.opts.parent_cache_caps,
+                                        is_rc_spool_part,
+                                    )
+                                },
+                            ),
+                            _ => attach_parents_for_one_file(
+                                in_path,
+                                w,
+                                None,
+                                &file_ctx,
+                                self.opts.parent_cache_caps,
+                                is_rc_spool_part,
+                            ),
+                        },
                     )?;
 
                     write_attach_fingerprint_atomic(
@@ -118,7 +161,7 @@ impl RedditETL {
                     )?;
 
                     if let Some(pb) = &pb {
-                        pb.inc(1);
+                        pb.inc(weighted_inputs[*idx].1);
                     }
                     attached.lock().unwrap()[*idx] = Some((out_path, file_stats, diagnostics));
                     Ok(())
@@ -0,0 +1,218 @@
+// Streaming parent attachment for `ScanPlan::attach_parents_zst`: records
+// written by the scan's per-month stream pass through `ParentAttachWriter`,
+// which splices parents with the same per-record logic as the spool-part path.
+
+/// Per-output stats and diagnostics from one `ParentAttachWriter`, summed
+/// by `finish_parent_attach_reports` once every month has been written.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ParentAttachFileReport {
+    stats: ParentAttachStats,
+    diagnostics: ParentAttachDiagnostics,
+}
+
+impl ParentAttachFileReport {
+    /// Report for an output kept by the resume fast-path.
+    pub(crate) fn resume_skipped() -> Self {
+        Self {
+            diagnostics: ParentAttachDiagnostics {
+                resume_skipped_files: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn records_written(&self) -> u64 {
+        self.diagnostics.parsed_records
+    }
+}
+
+/// Sum per-output reports and emit the same end-of-run warnings as
+/// `attach_parents_jsonls_parallel_with_stats`.
+pub(crate) fn finish_parent_attach_reports(
+    reports: impl IntoIterator<Item = ParentAttachFileReport>,
+) -> ParentAttachStats {
+    let mut stats = ParentAttachStats::default();
+    let mut diagnostics = ParentAttachDiagnostics::default();
+    for report in reports {
+        stats.add(report.stats);
+        diagnostics.add(report.diagnostics);
+    }
+    warn_if_no_comment_shaped_records(diagnostics);
+    warn_if_malformed_parent_ids(diagnostics);
+    stats
+}
+
+/// `Write` adapter that parses each newline-terminated JSON record written to
+/// it, attaches its parent, and forwards the re-serialized record to `inner`.
+/// Holds its own worker shard caches, so one writer serves one output file.
+pub(crate) struct ParentAttachWriter<'a> {
+    inner: &'a mut dyn std::io::Write,
+    parents: &'a ParentMaps,
+    empty_parent_payloads: HashMap<String, ParentPayload>,
+    caches: AttachWorkerCaches,
+    depth: Option<ReplyDepthWalker>,
+    is_comment_file: bool,
+    pending: Vec<u8>,
+    report: ParentAttachFileReport,
+}
+
+impl<'a> ParentAttachWriter<'a> {
+    pub(crate) fn new(
+        inner: &'a mut dyn std::io::Write,
+        parents: &'a ParentMaps,
+        cache_caps: Option<(usize, usize)>,
+        is_comment_file: bool,
+    ) -> Self {
+        Self {
+            inner,
+            parents,
+            empty_parent_payloads: HashMap::new(),
+            caches: AttachWorkerCaches::new(cache_caps),
+            depth: None,
+            is_comment_file,
+            pending: Vec::new(),
+            report: ParentAttachFileReport {
+                diagnostics: ParentAttachDiagnostics {
+                    files_scanned: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Also annotate each record with its reply `depth`, following at most
+    /// `max_depth` parent links per record.
+    pub(crate) fn with_reply_depth(
+        mut self,
+        max_depth: usize,
+        cache_caps: Option<(usize, usize)>,
+    ) -> Self {
+        self.depth = Some(ReplyDepthWalker::new(self.parents, max_depth, cache_caps));
+        self
+    }
+
+    /// Attach any unterminated trailing record and return the file's report.
+    pub(crate) fn finish(mut self) -> Result<ParentAttachFileReport> {
+        self.attach_pending()?;
+        if let Some(depth) = &self.depth {
+            depth.finish();
+        }
+        Ok(self.report)
+    }
+
+    fn attach_pending(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let mut v: Value = serde_json::from_slice(&self.pending).with_context(|| {
+            format!(
+                "malformed JSON in streamed parent attach record {}",
+                self.report.diagnostics.parsed_records + 1
+            )
+        })?;
+        let ctx = attach_file_ctx(self.parents, &self.empty_parent_payloads);
+        attach_parent_to_record(
+            &mut v,
+            &ctx,
+            &mut self.caches,
+            &mut self.report.stats,
+            &mut self.report.diagnostics,
+            self.is_comment_file,
+        )?;
+        if let Some(depth) = &mut self.depth {
+            depth.annotate(self.parents, &mut v, self.is_comment_file)?;
+        }
+        serde_json::to_writer(&mut *self.inner, &v)?;
+        self.inner.write_all(b"\n")?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl std::io::Write for ParentAttachWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut rest = buf;
+        while let Some(pos) = rest.iter().position(|&b| b == b'\n') {
+            self.pending.extend_from_slice(&rest[..pos]);
+            rest = &rest[pos + 1..];
+            self.attach_pending().map_err(std::io::Error::other)?;
+        }
+        self.pending.extend_from_slice(rest);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn direct_attach_fingerprint(
+    input: &Path,
+    parents: &ParentMaps,
+    start: Option<YearMonth>,
+    end: Option<YearMonth>,
+    scan: &str,
+) -> AttachFingerprint {
+    AttachFingerprint {
+        scan: Some(scan.to_string()),
+        ..build_attach_fingerprint(
+            input,
+            &attach_parent_cache_fingerprint(parents),
+            &attach_resolution_range(start, end),
+        )
+    }
+}
+
+/// Resume check for one direct-attach output: it must be published, not
+/// mid-write in staging, carry a sidecar matching `input`, `parents`, the
+/// resolution range and `scan`, and still decode end to end.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn direct_attach_output_is_current(
+    staging_dir: &Path,
+    out_path: &Path,
+    input: &Path,
+    parents: &ParentMaps,
+    start: Option<YearMonth>,
+    end: Option<YearMonth>,
+    scan: &str,
+    compressed: bool,
+) -> Result<bool> {
+    if !out_path.exists() || attach_inprogress_exists(staging_dir, out_path)? {
+        return Ok(false);
+    }
+    let fingerprint = direct_attach_fingerprint(input, parents, start, end, scan);
+    if !attach_fingerprint_matches(&attach_fingerprint_path(out_path), &fingerprint) {
+        return Ok(false);
+    }
+    let readable = if compressed {
+        crate::integrity::validate_zst_full(out_path).is_ok()
+    } else {
+        validate_jsonl_file(out_path)
+    };
+    if !readable {
+        tracing::warn!(path=%out_path.display(), "resume: existing attached output is unreadable/corrupt, rebuilding");
+    }
+    Ok(readable)
+}
+
+/// Publish the resume sidecar for a freshly written direct-attach output.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_direct_attach_fingerprint(
+    staging_dir: &Path,
+    out_path: &Path,
+    input: &Path,
+    parents: &ParentMaps,
+    start: Option<YearMonth>,
+    end: Option<YearMonth>,
+    scan: &str,
+    write_buf_bytes: usize,
+) -> Result<()> {
+    write_attach_fingerprint_atomic(
+        staging_dir,
+        &attach_fingerprint_path(out_path),
+        &direct_attach_fingerprint(input, parents, start, end, scan),
+        write_buf_bytes,
+    )
+}
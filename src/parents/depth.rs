@@ -0,0 +1,138 @@
+// Reply-depth annotation for `ScanPlan::annotate_depth`: walks each comment's
+// `parent_id` chain through the resolver's comment parent links.
+
+/// Default cap on the number of parent links one depth walk follows. Real
+/// Reddit threads are far shallower; the cap only stops walks through
+/// corrupt, cyclic link data.
+pub const DEFAULT_MAX_REPLY_DEPTH: usize = 1000;
+
+/// Companion file the resolver writes next to a comment shard, mapping each
+/// resolved comment id to that comment's own `parent_id`.
+fn parent_links_path(comment_shard: &Path) -> PathBuf {
+    let mut links = comment_shard.as_os_str().to_os_string();
+    links.push(PARENT_LINKS_SUFFIX);
+    PathBuf::from(links)
+}
+
+/// Whether `parents` carries any comment parent links to walk: eagerly
+/// loaded ones, or a link file next to one of its comment shards. Maps with
+/// no comment shards have nothing to check.
+pub(crate) fn has_reply_links(parents: &ParentMaps) -> bool {
+    if !parents.comment_parent_links.is_empty() {
+        return true;
+    }
+    match &parents.comment_shards {
+        Some(shards) if !shards.is_empty() => shards
+            .values()
+            .any(|shard| parent_links_path(shard).is_file()),
+        _ => true,
+    }
+}
+
+/// Per-output depth walker. Like the attach caches, one walker serves one
+/// writer, so its FIFO link-shard cache needs no locking.
+pub(crate) struct ReplyDepthWalker {
+    max_depth: usize,
+    link_shards: Option<HashMap<YearMonth, PathBuf>>,
+    cache: WorkerShardCache<String>,
+    capped: u64,
+}
+
+impl ReplyDepthWalker {
+    pub(crate) fn new(
+        parents: &ParentMaps,
+        max_depth: usize,
+        cache_caps: Option<(usize, usize)>,
+    ) -> Self {
+        // Hand-built maps may point at shards without link files; those
+        // months resolve only through `comment_parent_links`.
+        let link_shards = parents.comment_shards.as_ref().map(|shards| {
+            shards
+                .iter()
+                .map(|(ym, shard)| (*ym, parent_links_path(shard)))
+                .filter(|(_, links)| links.is_file())
+                .collect()
+        });
+        let (comment_cap, _) = shard_cache_caps(cache_caps, available_memory_fraction());
+        Self {
+            max_depth: max_depth.max(1),
+            link_shards,
+            cache: WorkerShardCache::new(comment_cap),
+            capped: 0,
+        }
+    }
+
+    /// Hops from the submission to a comment whose parent is `parent_id`:
+    /// 1 for a top-level comment, 2 for a reply to it, and so on. `None`
+    /// when a link in the chain is unresolved or the walk hits the cap.
+    fn depth(
+        &mut self,
+        parents: &ParentMaps,
+        parent_id: &str,
+        own_ym: Option<YearMonth>,
+    ) -> Result<Option<u64>> {
+        let mut current = parent_id.to_string();
+        let mut depth = 1usize;
+        loop {
+            if current.starts_with("t3_") {
+                return Ok(Some(depth as u64));
+            }
+            let Some(comment_id) = current.strip_prefix("t1_") else {
+                return Ok(None);
+            };
+            if depth >= self.max_depth {
+                self.capped += 1;
+                return Ok(None);
+            }
+            let Some(next) = load_shard_value(
+                &parents.comment_parent_links,
+                self.link_shards.as_ref(),
+                &mut self.cache,
+                comment_id,
+                own_ym,
+            )?
+            else {
+                return Ok(None);
+            };
+            current = next;
+            depth += 1;
+        }
+    }
+
+    /// Insert `depth` into one record: 0 for a submission, the walked depth
+    /// for a comment, or `null` when the chain cannot be followed.
+    pub(crate) fn annotate(
+        &mut self,
+        parents: &ParentMaps,
+        v: &mut Value,
+        is_comment_file: bool,
+    ) -> Result<()> {
+        let depth = match v.get("parent_id").and_then(Value::as_str) {
+            Some(parent_id) => {
+                let own_ym = v
+                    .get("created_utc")
+                    .and_then(|x| x.as_i64())
+                    .map(ym_from_epoch);
+                self.depth(parents, parent_id, own_ym)?
+            }
+            None if !is_comment_file => Some(0),
+            None => None,
+        };
+        if let Some(map) = v.as_object_mut() {
+            map.insert("depth".into(), depth.map_or(Value::Null, Value::from));
+        }
+        Ok(())
+    }
+
+    /// Warn once per output if any walk stopped at the cap.
+    pub(crate) fn finish(&self) {
+        if self.capped > 0 {
+            tracing::warn!(
+                capped_records = self.capped,
+                max_depth = self.max_depth as u64,
+                "reply-depth walk reached the cap for {} record(s); their `depth` is null (cyclic or corrupt parent links?)",
+                self.capped,
+            );
+        }
+    }
+}
@@ -10,28 +10,22 @@ impl RedditETL {
         prefixed_ids: I,
         out: &Path,
         parents: &ParentMaps,
-    ) -> Result<ParentAttachStats>
+    ) -> Result<ParentAttachStats, RetlError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
+        let _run_settings = self.opts.enter_run_settings();
         let parent_dir = output_parent(out);
         crate::util::create_dir_all_with_default_backoff(parent_dir)
             .with_context(|| format!("create direct parent output dir {}", parent_dir.display()))?;
         sweep_stale_inprogress(parent_dir, true)?;
 
         let empty_parent_payloads: HashMap<String, ParentPayload> = HashMap::new();
-        let ctx = AttachFileCtx {
-            legacy_payload: parents.payload_spec.is_legacy_default(),
-            parents_c_eager: &parents.comments,
-            parents_s_eager: &parents.submissions,
-            empty_parent_payloads: &empty_parent_payloads,
-            comment_shards: parents.comment_shards.as_ref(),
-            submission_shards: parents.submission_shards.as_ref(),
-        };
-        let mut caches = AttachWorkerCaches::new();
+        let ctx = attach_file_ctx(parents, &empty_parent_payloads);
+        let mut caches = AttachWorkerCaches::new(self.opts.parent_cache_caps);
 
-        write_at_path_atomic(
+        Ok(write_at_path_atomic(
             out,
             self.opts.write_buffer_bytes,
             |w| -> Result<ParentAttachStats> {
@@ -55,6 +49,6 @@ impl RedditETL {
                 }
                 Ok(stats)
             },
-        )
+        )?)
     }
 }
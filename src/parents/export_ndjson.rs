@@ -0,0 +1,198 @@
+/// One NDJSON row for a resolved parent: RETL's `kind`/`id` metadata, the
+/// parent's `author`, and the configured payload fields.
+fn parent_ndjson_row(
+    id: &str,
+    kind: FileKind,
+    min: &MinimalRecord,
+    line: &str,
+    spec: &ParentPayloadSpec,
+) -> Result<ParentPayload> {
+    let mut row = parent_payload_from_line(line, spec)?;
+    if spec.is_legacy_default() && kind == FileKind::Submission {
+        // Match the legacy shard format, which stores missing submission
+        // text as empty strings.
+        for field in ["title", "selftext"] {
+            row.entry(field).or_insert_with(|| Value::String(String::new()));
+        }
+    }
+    row.entry("author")
+        .or_insert_with(|| min.author.clone().map_or(Value::Null, Value::String));
+    let kind = match kind {
+        FileKind::Comment => "comment",
+        FileKind::Submission => "submission",
+    };
+    row.insert("kind".into(), Value::String(kind.into()));
+    row.insert("id".into(), Value::String(id.to_string()));
+    Ok(row)
+}
+
+/// NDJSON rows for every parent in `ids` found in one corpus file.
+fn parent_ndjson_for_file(
+    job: &FileJob,
+    ids: &ParentIds,
+    idset_cache: &SharedIdsetCache,
+    read_buf: usize,
+    pb: Option<&ProgressBar>,
+    spec: &ParentPayloadSpec,
+) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    scan_needed_parents(job, ids, idset_cache, read_buf, pb, |id, min, line, line_number| {
+        let row = parent_ndjson_row(id, job.kind, min, line, spec)
+            .map_err(|e| malformed_json_error(&job.path, line_number, e))?;
+        serde_json::to_writer(&mut buf, &row)?;
+        buf.push(b'\n');
+        Ok(())
+    })?;
+    Ok(buf)
+}
+
+/// Reorder window for [`RedditETL::export_parent_maps_ndjson`]: a month
+/// starts only once it is fewer than `size` months past the next month to
+/// write, so at most `size` finished months wait in memory for a slow
+/// earlier one.
+struct ReorderWindow {
+    size: usize,
+    /// Next month to write, and whether the writer stopped.
+    state: parking_lot::Mutex<(usize, bool)>,
+    cv: parking_lot::Condvar,
+}
+
+impl ReorderWindow {
+    fn new(size: usize) -> Self {
+        Self {
+            size: size.max(1),
+            state: parking_lot::Mutex::new((0, false)),
+            cv: parking_lot::Condvar::new(),
+        }
+    }
+
+    /// Block until month `idx` fits in the window. False once closed.
+    fn wait_turn(&self, idx: usize) -> bool {
+        let mut state = self.state.lock();
+        while !state.1 && idx >= state.0 + self.size {
+            self.cv.wait(&mut state);
+        }
+        !state.1
+    }
+
+    fn advance(&self, next: usize) {
+        self.state.lock().0 = next;
+        self.cv.notify_all();
+    }
+
+    /// Release every waiter; used when either side stops early.
+    fn close(&self) {
+        self.state.lock().1 = true;
+        self.cv.notify_all();
+    }
+}
+
+impl RedditETL {
+    /// Resolve the parents in `ids` and write them to `out_path` as flat
+    /// NDJSON, one `{id, kind, author, ...payload}` line per resolved parent,
+    /// for joining in Spark/DuckDB and the like.
+    ///
+    /// Resolves over the same window and with the same matching as
+    /// [`resolve_parent_maps`](Self::resolve_parent_maps), but writes no
+    /// shard cache. Payload fields follow `parent_payload_spec` (`body` for
+    /// comments, `title`/`selftext` for submissions by default); `kind` is
+    /// `"comment"` or `"submission"` and `id` is unprefixed, as in attached
+    /// `parent` objects. Lines follow corpus planning order (month by month);
+    /// the file is published atomically. Returns the number of lines written.
+    pub fn export_parent_maps_ndjson(&self, ids: &ParentIds, out_path: &Path) -> Result<u64, RetlError> {
+        self.opts.check_config()?;
+        let _run_settings = self.opts.enter_run_settings();
+        let files = self.plan_parent_resolver_files("export_parent_maps_ndjson")?;
+        let payload_spec = &self.opts.parent_payload_spec;
+        let read_buf = self.opts.read_buffer_bytes;
+
+        let pb = if self.opts.progress {
+            Some(make_stage_progress_bar(
+                total_compressed_size(&files),
+                self.opts.progress_label.as_deref(),
+                self.opts.progress_stage.as_ref(),
+            ))
+        } else {
+            None
+        };
+        let pb_ref = pb.as_ref();
+        let idset_cache = SharedIdsetCache::new(idset_cache_cap(
+            self.opts.parent_cache_caps,
+            available_memory_fraction(),
+        ));
+
+        // Months are scanned concurrently on the pool while this thread owns
+        // the (non-`Send`) output writer, stitching finished months back into
+        // planning order within a bounded reorder window.
+        let in_flight = self.opts.file_concurrency.max(1) * 2;
+        let window = ReorderWindow::new(in_flight);
+        let (tx, rx) = crossbeam_channel::bounded::<(usize, Vec<u8>)>(in_flight);
+        let written = write_at_path_atomic(out_path, self.opts.write_buffer_bytes, |w| {
+            std::thread::scope(|s| -> Result<u64> {
+                let window = &window;
+                let producer = s.spawn(move || {
+                    let _run_settings = self.opts.enter_run_settings();
+                    with_thread_pool(self.opts.parallelism, || {
+                        crate::concurrency::for_each_file_in_order(
+                            &files,
+                            self.opts.file_concurrency,
+                            |idx, job| -> Result<()> {
+                                let stopped =
+                                    || anyhow::anyhow!("parent NDJSON writer stopped early");
+                                if !window.wait_turn(idx) {
+                                    return Err(stopped());
+                                }
+                                let sent = parent_ndjson_for_file(
+                                    job,
+                                    ids,
+                                    &idset_cache,
+                                    read_buf,
+                                    pb_ref,
+                                    payload_spec,
+                                )
+                                .and_then(|buf| tx.send((idx, buf)).map_err(|_| stopped()));
+                                if sent.is_err() {
+                                    // Later months wait on this one; let them fail too.
+                                    window.close();
+                                }
+                                sent
+                            },
+                        )
+                    })
+                });
+
+                let mut pending: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+                let mut next = 0usize;
+                let mut lines = 0u64;
+                let consumed = (|| -> Result<()> {
+                    while let Ok((idx, buf)) = rx.recv() {
+                        pending.insert(idx, buf);
+                        while let Some(buf) = pending.remove(&next) {
+                            lines += buf.iter().filter(|&&b| b == b'\n').count() as u64;
+                            w.write_all(&buf)?;
+                            next += 1;
+                            window.advance(next);
+                        }
+                    }
+                    Ok(())
+                })();
+                // Unblock a producer stuck on `send` or on the window before
+                // joining it.
+                window.close();
+                drop(rx);
+                let produced = producer.join().expect("parent NDJSON producer panicked");
+                // A failed write makes the producer fail with "stopped early";
+                // report the write error itself.
+                consumed?;
+                produced?;
+                Ok(lines)
+            })
+        })
+        .with_context(|| format!("write parent NDJSON {}", out_path.display()))?;
+
+        if let Some(pb) = pb {
+            pb.finish_with_message("done");
+        }
+        Ok(written)
+    }
+}
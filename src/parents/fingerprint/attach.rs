@@ -88,6 +88,8 @@ fn build_attach_fingerprint(
         input: attach_file_identity(input),
         resolution_range: resolution_range.clone(),
         parent_cache: parent_cache.clone(),
+        scan: None,
+        split_unresolved: false,
     }
 }
 
@@ -6,6 +6,16 @@ struct AttachFingerprint {
     input: AttachFileIdentity,
     resolution_range: AttachResolutionRange,
     parent_cache: AttachParentCacheFingerprint,
+    /// Query/config fingerprint for outputs attached straight from the corpus
+    /// by `ScanPlan::attach_parents_zst`. Absent (and not serialized) for
+    /// spool-part inputs, so existing sidecars still match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scan: Option<String>,
+    /// Whether unresolved-parent records were split into
+    /// `<out_dir>/unresolved/`. Not serialized when false, so sidecars from
+    /// unsplit runs still match.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    split_unresolved: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
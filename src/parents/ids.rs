@@ -185,6 +185,10 @@ pub struct ParentMaps {
     pub comments: HashMap<String, String>,
     /// Backwards-compatible eager submission cache (`id -> (title, selftext)`).
     pub submissions: HashMap<String, (String, String)>,
+    /// Eager `comment id -> parent_id` links for resolved comment parents,
+    /// used by reply-depth annotation. Months not loaded here fall back to
+    /// the per-shard link files next to `comment_shards`.
+    pub comment_parent_links: HashMap<String, String>,
     pub comment_shards: Option<HashMap<YearMonth, PathBuf>>,
     pub submission_shards: Option<HashMap<YearMonth, PathBuf>>,
     pub payload_spec: ParentPayloadSpec,
@@ -195,6 +199,7 @@ impl Default for ParentMaps {
         Self {
             comments: HashMap::new(),
             submissions: HashMap::new(),
+            comment_parent_links: HashMap::new(),
             comment_shards: None,
             submission_shards: None,
             payload_spec: ParentPayloadSpec::default(),
@@ -8,6 +8,9 @@ include!("attach.rs");
 include!("fingerprint/mod.rs");
 include!("resolver_tail.rs");
 include!("resolve.rs");
+include!("export_ndjson.rs");
 include!("direct.rs");
 include!("attach_pipeline.rs");
+include!("attach_stream.rs");
+include!("depth.rs");
 include!("tests.rs");
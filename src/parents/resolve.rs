@@ -1,17 +1,52 @@
 
 impl RedditETL {
+    /// Corpus files (both sources, every corpus root) in the resolver
+    /// window, warning about months missing inside it, or exactly the
+    /// explicit `files` list when one is set. `operation` names the caller in
+    /// errors.
+    fn plan_parent_resolver_files(&self, operation: &str) -> Result<Vec<FileJob>> {
+        if let Some(files) = self.opts.explicit_file_jobs()? {
+            return Ok(files);
+        }
+        let discovered = discover_roots_checked(
+            &self.opts.corpus_roots(),
+            crate::config::Sources::Both,
+            self.opts.duplicate_months,
+        )?;
+        let (start, end) = self.opts.planned_range(&discovered, self.opts.sources);
+        let files = plan_files_checked(
+            &discovered,
+            &self.opts.comments_dir,
+            &self.opts.submissions_dir,
+            crate::config::Sources::Both,
+            start,
+            end,
+        )
+        .with_context(|| {
+            format!("{operation} planned zero corpus files for resolver range {start:?}..={end:?}")
+        })?;
+        // Report corpus holes inside the resolution window. A child whose
+        // `parent_id` lives in a missing month resolves as `unresolved`;
+        // without this the only signal is the generic unresolved-rate
+        // warning, whose "use a larger --window-months" remedy is
+        // misleading when the real cause is a gap inside the window.
+        warn_resolver_window_gaps(&discovered, start, end);
+        Ok(files)
+    }
+
     pub fn resolve_parent_maps(
         &self,
         ids: &ParentIds,
         cache_dir: &Path,
         resume: bool,
-    ) -> Result<ParentMaps> {
+    ) -> Result<ParentMaps, RetlError> {
         // Surface a deferred ConfigBuildError (e.g. a backwards date range from
         // `with_date_range`) before planning, so the parents resolver fails
         // fast with the "invalid date range" message instead of the confusing
         // "planned zero corpus files" context below.
         self.opts.check_config()?;
-        with_thread_pool(self.opts.parallelism, || {
+        let _run_settings = self.opts.enter_run_settings();
+        Ok(with_thread_pool(self.opts.parallelism, || -> Result<ParentMaps> {
             let comments_out = cache_dir.join("comments");
             let submissions_out = cache_dir.join("submissions");
             crate::util::create_dir_all_with_default_backoff(&comments_out).with_context(|| {
@@ -34,38 +69,18 @@ impl RedditETL {
             sweep_stale_inprogress(&comments_out, true)?;
             sweep_stale_inprogress(&submissions_out, true)?;
 
-            let discovered =
-                discover_all_checked(&self.opts.comments_dir, &self.opts.submissions_dir)?;
-            let files = plan_files_checked(
-                &discovered,
-                &self.opts.comments_dir,
-                &self.opts.submissions_dir,
-                crate::config::Sources::Both,
-                self.opts.start,
-                self.opts.end,
-            )
-            .with_context(|| {
-                format!(
-                    "resolve_parent_maps planned zero corpus files for resolver range {:?}..={:?}",
-                    self.opts.start, self.opts.end
-                )
-            })?;
-            // Report corpus holes inside the resolution window. A child whose
-            // `parent_id` lives in a missing month resolves as `unresolved`;
-            // without this the only signal is the generic unresolved-rate
-            // warning, whose "use a larger --window-months" remedy is
-            // misleading when the real cause is a gap inside the window.
-            warn_resolver_window_gaps(&discovered, self.opts.start, self.opts.end);
-
+            let files = self.plan_parent_resolver_files("resolve_parent_maps")?;
             let parent_ids_fp = parent_ids_fingerprint(ids)?;
-            let resolution_range = attach_resolution_range(self.opts.start, self.opts.end);
+            let (start, end) = self.planned_date_range()?;
+            let resolution_range = attach_resolution_range(start, end);
             let payload_spec = self.opts.parent_payload_spec.clone();
 
             let total_bytes = total_compressed_size(&files);
             let pb = if self.opts.progress {
-                Some(make_progress_bar_labeled(
+                Some(make_stage_progress_bar(
                     total_bytes,
                     self.opts.progress_label.as_deref(),
+                    self.opts.progress_stage.as_ref(),
                 ))
             } else {
                 None
@@ -80,9 +95,7 @@ impl RedditETL {
                 &comments_out,
                 &submissions_out,
                 resume,
-                self.opts.read_buffer_bytes,
-                self.opts.write_buffer_bytes,
-                self.opts.file_concurrency,
+                &self.opts,
                 pb.as_ref(),
             )?;
 
@@ -171,13 +184,34 @@ impl RedditETL {
                 }
             }
 
+            // Parent links are small (two ids per resolved comment parent), so
+            // they load under any payload spec; the same truncation fallback
+            // to per-shard reads applies.
+            let mut comment_parent_links: HashMap<String, String> = HashMap::new();
+            if eager_ok && self.opts.parent_reply_links {
+                let mut link_paths: Vec<PathBuf> = comment_shards
+                    .values()
+                    .map(|p| parent_links_path(p))
+                    .collect();
+                link_paths.sort();
+                for p in &link_paths {
+                    let f = crate::util::open_with_default_backoff(p)?;
+                    let m: HashMap<String, String> = serde_json::from_reader(BufReader::new(f))?;
+                    comment_parent_links.extend(m);
+                    if is_low_memory(0.10) {
+                        break;
+                    }
+                }
+            }
+
             Ok(ParentMaps {
                 comments: comments_map,
                 submissions: submissions_map,
+                comment_parent_links,
                 comment_shards: Some(comment_shards),
                 submission_shards: Some(submission_shards),
                 payload_spec,
             })
-        })
+        })?)
     }
 }
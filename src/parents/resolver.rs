@@ -10,6 +10,38 @@ fn idset_cache_cap_for_free_memory(free: f64) -> usize {
     }
 }
 
+/// Shared id-set cache cap for `resolve_parent_maps`. Configured
+/// `parent_cache_caps` scale the tier cap by their ratio to the default worker
+/// caps, never above the tier cap itself.
+fn idset_cache_cap(caps: Option<(usize, usize)>, free: f64) -> usize {
+    let tier_cap = idset_cache_cap_for_free_memory(free);
+    let Some((comments, submissions)) = caps else {
+        return tier_cap;
+    };
+    let default_total = COMMENT_SHARD_CACHE_CAP + SUBMISSION_SHARD_CACHE_CAP;
+    let scaled = tier_cap.saturating_mul(comments.saturating_add(submissions)) / default_total;
+    scaled.clamp(1, tier_cap)
+}
+
+/// Per-worker attach cache caps `(comments, submissions)`: the defaults, or
+/// configured `parent_cache_caps` clamped to the free-memory tier's ceiling.
+fn shard_cache_caps(caps: Option<(usize, usize)>, free: f64) -> (usize, usize) {
+    let Some((comments, submissions)) = caps else {
+        return (COMMENT_SHARD_CACHE_CAP, SUBMISSION_SHARD_CACHE_CAP);
+    };
+    let (comment_max, submission_max) = if free > IDSET_CACHE_HIGH_FREE_THRESHOLD {
+        (SHARD_CACHE_HIGH_MAX_CAP, SHARD_CACHE_HIGH_MAX_CAP)
+    } else if free > IDSET_CACHE_MEDIUM_FREE_THRESHOLD {
+        (SHARD_CACHE_MEDIUM_MAX_CAP, SHARD_CACHE_MEDIUM_MAX_CAP)
+    } else {
+        (COMMENT_SHARD_CACHE_CAP, SUBMISSION_SHARD_CACHE_CAP)
+    };
+    (
+        comments.clamp(1, comment_max),
+        submissions.clamp(1, submission_max),
+    )
+}
+
 fn parent_payload_from_line(line: &str, spec: &ParentPayloadSpec) -> Result<ParentPayload> {
     let v: Value = serde_json::from_str(line)?;
     let Some(obj) = v.as_object() else {
@@ -29,6 +61,67 @@ fn parent_payload_from_line(line: &str, spec: &ParentPayloadSpec) -> Result<Pare
     Ok(payload)
 }
 
+/// Scan one corpus file and call `on_parent(id, min, line, line_number)` for
+/// every record whose id is in `ids` (t1 ids for comment files, t3 ids for
+/// submission files). Shared by the map-shard resolver and the NDJSON export
+/// so both resolve exactly the same parents.
+fn scan_needed_parents<F>(
+    job: &FileJob,
+    ids: &ParentIds,
+    idset_cache: &SharedIdsetCache,
+    read_buf: usize,
+    pb: Option<&ProgressBar>,
+    mut on_parent: F,
+) -> Result<()>
+where
+    F: FnMut(&str, &MinimalRecord, &str, u64) -> Result<()>,
+{
+    let (mem, sharded) = match job.kind {
+        FileKind::Comment => (ids.t1_ids_mem.as_ref(), ids.t1_ids_sharded.as_ref()),
+        FileKind::Submission => (ids.t3_ids_mem.as_ref(), ids.t3_ids_sharded.as_ref()),
+    };
+    let id_needed = |id: &str| -> Result<bool> {
+        if mem.map(|ids| ids.contains(id)).unwrap_or(false) {
+            return Ok(true);
+        }
+        if let Some(sh) = sharded {
+            let p = sh.path_for(sh.idx(id));
+            return Ok(idset_cache.get_or_load(&p)?.contains(id));
+        }
+        Ok(false)
+    };
+
+    let mut line_number = 0u64;
+    let completed = for_each_line_with_progress_cfg_no_throttle_status(
+        &job.path,
+        read_buf,
+        |d| {
+            if let Some(pb) = pb {
+                pb.inc(d);
+            }
+        },
+        |line| {
+            line_number += 1;
+            let min =
+                parse_minimal(line).map_err(|e| malformed_json_error(&job.path, line_number, e))?;
+            if let Some(id) = min.id.as_deref() {
+                if id_needed(id)? {
+                    on_parent(id, &min, line, line_number)?;
+                }
+            }
+            Ok(())
+        },
+    )
+    .with_context(|| format!("scan {}", job.path.display()))?;
+    if !completed {
+        return Err(anyhow::anyhow!(
+            "incomplete zstd decode while resolving parent map from {}",
+            job.path.display()
+        ));
+    }
+    Ok(())
+}
+
 fn build_id_shard_index(
     files: &[FileJob],
     ids: &ParentIds,
@@ -38,11 +131,11 @@ fn build_id_shard_index(
     comments_out: &Path,
     submissions_out: &Path,
     resume: bool,
-    read_buf: usize,
-    write_buf: usize,
-    file_concurrency: usize,
-    pb: Option<&indicatif::ProgressBar>,
+    opts: &crate::config::ETLOptions,
+    pb: Option<&ProgressBar>,
 ) -> Result<(HashMap<YearMonth, PathBuf>, HashMap<YearMonth, PathBuf>)> {
+    let reply_links = opts.parent_reply_links;
+    let (read_buf, write_buf) = (opts.read_buffer_bytes, opts.write_buffer_bytes);
     // Shard-keyed indexes (one entry per processed monthly shard, NOT per id).
     // ~10^6x memory reduction vs. the prior per-id index at corpus scale.
     let comment_shards = parking_lot::Mutex::new(HashMap::<YearMonth, PathBuf>::new());
@@ -50,17 +143,18 @@ fn build_id_shard_index(
 
     // Process-wide id-shard cache: each (t1|t3) ids_NNNN.txt shard is read
     // and parsed at most once globally instead of once per rayon worker.
-    let idset_cap = idset_cache_cap_for_free_memory(available_memory_fraction());
+    let idset_cap = idset_cache_cap(opts.parent_cache_caps, available_memory_fraction());
     let idset_cache: Arc<SharedIdsetCache> = Arc::new(SharedIdsetCache::new(idset_cap));
 
     // Limit file concurrency to reduce RAM spikes while resolving.
-    crate::concurrency::for_each_file_limited(files, file_concurrency, |job| -> Result<()> {
+    crate::concurrency::for_each_file_limited(files, opts.file_concurrency, |job| -> Result<()> {
         let (out_dir, prefix) = match job.kind {
             FileKind::Comment => (comments_out, "RC"),
             FileKind::Submission => (submissions_out, "RS"),
         };
         let out = out_dir.join(format!("{}_{}.json", prefix, job.ym));
         let sidecar_path = resolver_fingerprint_path(&out);
+        let links_path = parent_links_path(&out);
         let fingerprint =
             build_resolver_fingerprint(job, parent_ids_fp, resolution_range, payload_spec);
 
@@ -99,6 +193,15 @@ fn build_id_shard_index(
                 }
                 Err(_) => false,
             };
+            // With `reply_links`, comment shards carry a companion link file
+            // for reply-depth walks; shards cached without one are rebuilt.
+            let valid_json = valid_json
+                && (job.kind == FileKind::Submission
+                    || !reply_links
+                    || crate::util::open_with_default_backoff(&links_path).is_ok_and(|f| {
+                        serde_json::from_reader::<_, HashMap<String, String>>(BufReader::new(f))
+                            .is_ok()
+                    }));
             if valid_json && resolver_fingerprint_matches(&sidecar_path, &fingerprint) {
                 record_shard(out.clone());
                 if let Some(pb) = pb {
@@ -112,101 +215,53 @@ fn build_id_shard_index(
             }
         }
 
-        let ensure_contains = |shard_path: &Path, id: &str| -> Result<bool> {
-            let set = idset_cache.get_or_load(shard_path)?;
-            Ok(set.contains(id))
-        };
-        let id_needed = |mem: Option<&AHashSet<String>>,
-                         sharded: Option<&IdShards>,
-                         id: &str|
-         -> Result<bool> {
-            if mem.map(|ids| ids.contains(id)).unwrap_or(false) {
-                return Ok(true);
-            }
-            if let Some(sh) = sharded {
-                let idx = sh.idx(id);
-                let p = sh.path_for(idx);
-                return ensure_contains(&p, id);
-            }
-            Ok(false)
-        };
-
         let legacy_payload = payload_spec.is_legacy_default();
         let mut out_map_c: HashMap<String, String> = HashMap::new();
         let mut out_map_s: HashMap<String, (String, String)> = HashMap::new();
         let mut out_payload_c: HashMap<String, ParentPayload> = HashMap::new();
         let mut out_payload_s: HashMap<String, ParentPayload> = HashMap::new();
+        let mut out_links_c: HashMap<String, String> = HashMap::new();
 
-        let mut line_number = 0u64;
-        let completed = for_each_line_with_progress_cfg_no_throttle_status(
-            &job.path,
-            read_buf,
-            |d| {
-                if let Some(pb) = pb {
-                    pb.inc(d);
+        scan_needed_parents(job, ids, &idset_cache, read_buf, pb, |id, min, line, line_number| {
+            if reply_links && job.kind == FileKind::Comment {
+                if let Some(parent_id) = min.parent_id.as_deref() {
+                    out_links_c.insert(id.to_string(), parent_id.to_string());
                 }
-            },
-            |line| {
-                line_number += 1;
-                let min = parse_minimal(line)
-                    .map_err(|e| malformed_json_error(&job.path, line_number, e))?;
-                if let Some(id) = min.id.as_deref() {
-                    match job.kind {
-                        FileKind::Comment => {
-                            let needed = id_needed(
-                                ids.t1_ids_mem.as_ref(),
-                                ids.t1_ids_sharded.as_ref(),
-                                id,
-                            )?;
-
-                            if needed {
-                                if legacy_payload {
-                                    if let Some(body) = min.body.as_deref() {
-                                        out_map_c.insert(id.to_string(), body.to_string());
-                                    }
-                                } else {
-                                    let payload = parent_payload_from_line(line, payload_spec)
-                                        .map_err(|e| {
-                                            malformed_json_error(&job.path, line_number, e)
-                                        })?;
-                                    out_payload_c.insert(id.to_string(), payload);
-                                }
-                            }
-                        }
-                        FileKind::Submission => {
-                            let needed = id_needed(
-                                ids.t3_ids_mem.as_ref(),
-                                ids.t3_ids_sharded.as_ref(),
-                                id,
-                            )?;
-
-                            if needed {
-                                if legacy_payload {
-                                    let title =
-                                        min.title.as_deref().unwrap_or_default().to_string();
-                                    let selftext =
-                                        min.selftext.as_deref().unwrap_or_default().to_string();
-                                    out_map_s.insert(id.to_string(), (title, selftext));
-                                } else {
-                                    let payload = parent_payload_from_line(line, payload_spec)
-                                        .map_err(|e| {
-                                            malformed_json_error(&job.path, line_number, e)
-                                        })?;
-                                    out_payload_s.insert(id.to_string(), payload);
-                                }
-                            }
-                        }
+            }
+            match (legacy_payload, job.kind) {
+                (true, FileKind::Comment) => {
+                    if let Some(body) = min.body.as_deref() {
+                        out_map_c.insert(id.to_string(), body.to_string());
                     }
                 }
+                (true, FileKind::Submission) => {
+                    let title = min.title.as_deref().unwrap_or_default().to_string();
+                    let selftext = min.selftext.as_deref().unwrap_or_default().to_string();
+                    out_map_s.insert(id.to_string(), (title, selftext));
+                }
+                (false, kind) => {
+                    let payload = parent_payload_from_line(line, payload_spec)
+                        .map_err(|e| malformed_json_error(&job.path, line_number, e))?;
+                    match kind {
+                        FileKind::Comment => out_payload_c.insert(id.to_string(), payload),
+                        FileKind::Submission => out_payload_s.insert(id.to_string(), payload),
+                    };
+                }
+            }
+            Ok(())
+        })?;
+
+        // Links publish before the shard so a shard that passes the resume
+        // check above always has its link file. Without `reply_links` a link
+        // file left by an earlier run no longer matches the new shard.
+        if job.kind == FileKind::Comment && reply_links {
+            write_at_path_atomic(&links_path, write_buf, |w| -> Result<()> {
+                serde_json::to_writer(w, &out_links_c)?;
                 Ok(())
-            },
-        )
-        .with_context(|| format!("scan {}", job.path.display()))?;
-        if !completed {
-            return Err(anyhow::anyhow!(
-                "incomplete zstd decode while resolving parent map from {}",
-                job.path.display()
-            ));
+            })
+            .with_context(|| format!("write parent links {}", links_path.display()))?;
+        } else if job.kind == FileKind::Comment {
+            crate::util::remove_with_short_backoff(&links_path)?;
         }
 
         // Atomic write: stage under `<out_dir>/_staging/<basename>.retl-<pid>-<nonce>.inprogress`,
@@ -236,7 +291,7 @@ fn build_id_shard_index(
 }
 
 /// Remove resolver-cache shard JSONs (and their `.parents-resolve.json`
-/// sidecars) whose month is not in `shards` — i.e. not in the currently
+/// sidecars and `.parent-links.json` files) whose month is not in `shards` — i.e. not in the currently
 /// planned `files` set.
 ///
 /// Mirrors `prune_stale_attach_outputs`. `build_id_shard_index` only ever
@@ -268,9 +323,12 @@ fn prune_stale_resolver_shards(
         let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
             continue;
         };
-        // A cache entry is either the shard JSON itself or its resolver
-        // fingerprint sidecar; reduce both to the shard basename.
-        let base = name.strip_suffix(RESOLVER_SIDECAR_SUFFIX).unwrap_or(name);
+        // A cache entry is the shard JSON itself, its resolver fingerprint
+        // sidecar, or its parent-link file; reduce all to the shard basename.
+        let base = name
+            .strip_suffix(RESOLVER_SIDECAR_SUFFIX)
+            .or_else(|| name.strip_suffix(PARENT_LINKS_SUFFIX))
+            .unwrap_or(name);
         // Only touch files we own: `<prefix>_<YYYY-MM>.json`. This skips
         // `_staging/`, `.inprogress` leftovers, and anything unrelated.
         let is_owned_shard = base
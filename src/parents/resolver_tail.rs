@@ -21,6 +21,43 @@ fn validate_jsonl_file(path: &Path) -> bool {
     }
 }
 
+/// Records in a JSONL file, counted the way the attach reader does: one per
+/// non-empty line. `None` when the file cannot be read.
+fn count_jsonl_records(path: &Path) -> Option<u64> {
+    let mut r = crate::util::open_maybe_compressed(path, 8 * 1024).ok()?;
+    let mut buf = String::new();
+    let mut records = 0u64;
+    loop {
+        match read_line_capped(&mut r, &mut buf, DEFAULT_MAX_LINE_BYTES, path) {
+            Ok(0) => return Some(records),
+            Ok(_) => {
+                if !buf.is_empty() {
+                    records += 1;
+                }
+            }
+            Err(_) => return None,
+        }
+    }
+}
+
+/// Attach writes one output line per non-empty input line (split across
+/// `out_paths` when unresolved records are routed separately), so published
+/// outputs holding fewer (or more) records than their input are truncated or
+/// foreign files, even when every line they do hold parses.
+fn attach_record_counts_match(in_path: &Path, out_paths: &[&Path]) -> bool {
+    let Some(input) = count_jsonl_records(in_path) else {
+        return false;
+    };
+    let mut output = 0u64;
+    for out_path in out_paths {
+        match count_jsonl_records(out_path) {
+            Some(n) => output += n,
+            None => return false,
+        }
+    }
+    input == output
+}
+
 fn looks_like_rc_spool_path(path: &Path) -> bool {
     path.file_name()
         .and_then(|name| name.to_str())
@@ -33,9 +70,8 @@ fn diagnose_initial_attach_shape(inputs: &[(usize, PathBuf)], read_buf: usize) -
         return Ok(());
     };
 
-    let f = crate::util::open_with_default_backoff(first_input)
+    let mut r = crate::util::open_maybe_compressed(first_input, read_buf)
         .with_context(|| format!("open initial parent attach input {}", first_input.display()))?;
-    let mut r = BufReader::with_capacity(read_buf, f);
     let mut buf = String::with_capacity(64 * 1024);
     let mut line_number = 0u64;
     let mut shape = ParentAttachInitialShape::default();
@@ -85,6 +85,8 @@ mod tests {
                     digest: format!("submissions-{tag}"),
                 },
             },
+            scan: None,
+            split_unresolved: false,
         }
     }
 
@@ -302,6 +304,7 @@ mod tests {
         let empty_eager = ParentMaps {
             comments: HashMap::new(),
             submissions: HashMap::new(),
+            comment_parent_links: HashMap::new(),
             comment_shards: Some(shards.clone()),
             submission_shards: Some(shards.clone()),
             payload_spec: Default::default(),
@@ -309,6 +312,7 @@ mod tests {
         let full_eager = ParentMaps {
             comments: full_comments,
             submissions: full_submissions,
+            comment_parent_links: HashMap::new(),
             comment_shards: Some(shards.clone()),
             submission_shards: Some(shards),
             payload_spec: Default::default(),
@@ -328,6 +332,7 @@ mod tests {
         let parents = ParentMaps {
             comments: HashMap::new(),
             submissions: HashMap::new(),
+            comment_parent_links: HashMap::new(),
             comment_shards: None,
             submission_shards: None,
             payload_spec: Default::default(),
@@ -344,4 +349,58 @@ mod tests {
             "expected Err for input path with no file_name(), got Ok"
         );
     }
+
+    #[test]
+    fn configured_parent_cache_caps_are_bounded_by_free_memory_tier() {
+        assert_eq!(
+            shard_cache_caps(None, 0.9),
+            (COMMENT_SHARD_CACHE_CAP, SUBMISSION_SHARD_CACHE_CAP)
+        );
+        assert_eq!(shard_cache_caps(Some((32, 24)), 0.9), (32, 24));
+        assert_eq!(
+            shard_cache_caps(Some((1000, 1000)), 0.9),
+            (SHARD_CACHE_HIGH_MAX_CAP, SHARD_CACHE_HIGH_MAX_CAP)
+        );
+        assert_eq!(
+            shard_cache_caps(Some((1000, 1000)), 0.3),
+            (SHARD_CACHE_MEDIUM_MAX_CAP, SHARD_CACHE_MEDIUM_MAX_CAP)
+        );
+        assert_eq!(
+            shard_cache_caps(Some((1000, 1000)), 0.1),
+            (COMMENT_SHARD_CACHE_CAP, SUBMISSION_SHARD_CACHE_CAP)
+        );
+        assert_eq!(shard_cache_caps(Some((2, 1)), 0.1), (2, 1));
+
+        assert_eq!(idset_cache_cap(None, 0.9), IDSET_CACHE_HIGH_CAP);
+        assert_eq!(idset_cache_cap(Some((1000, 1000)), 0.9), IDSET_CACHE_HIGH_CAP);
+        assert_eq!(idset_cache_cap(Some((1000, 1000)), 0.1), IDSET_CACHE_LOW_CAP);
+        let default_total = COMMENT_SHARD_CACHE_CAP + SUBMISSION_SHARD_CACHE_CAP;
+        assert_eq!(
+            idset_cache_cap(Some((1, 1)), 0.9),
+            IDSET_CACHE_HIGH_CAP * 2 / default_total
+        );
+    }
+
+    #[test]
+    fn reorder_window_holds_months_past_the_window_until_earlier_ones_are_written() {
+        let window = Arc::new(ReorderWindow::new(2));
+        assert!(window.wait_turn(0));
+        assert!(window.wait_turn(1));
+
+        let waiter = {
+            let window = Arc::clone(&window);
+            thread::spawn(move || window.wait_turn(2))
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished(), "month 2 must wait for month 0");
+        window.advance(1);
+        assert!(waiter.join().unwrap());
+
+        let waiter = {
+            let window = Arc::clone(&window);
+            thread::spawn(move || window.wait_turn(5))
+        };
+        window.close();
+        assert!(!waiter.join().unwrap(), "a closed window releases waiters");
+    }
 }
@@ -3,17 +3,21 @@ use crate::atomic_write::{
     write_jsonl_atomic, INPROGRESS_EXT,
 };
 use crate::date::YearMonth;
+use crate::error::RetlError;
 use crate::filters::ym_from_epoch;
 use crate::json_utils::is_comment_record_for_parent_attach;
 use crate::mem::{available_memory_fraction, is_low_memory};
 use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
 use crate::parents_ids::{IdShards, SharedIdsetCache, WorkerShardCache};
 use crate::paths::{
-    discover_all_checked, format_year_month_ranges, missing_month_diagnostics, plan_files_checked,
+    discover_roots_checked, format_year_month_ranges, missing_month_diagnostics, plan_files_checked,
     Discovered, FileJob, FileKind,
 };
 use crate::pipeline::RedditETL;
-use crate::progress::{make_count_progress, make_progress_bar_labeled, total_compressed_size};
+use crate::progress::{
+    file_sizes, make_stage_progress_bar, make_stage_weighted_count_progress, total_compressed_size,
+    ProgressBar,
+};
 use crate::run_manifest::{
     discover_upstream_manifests_from_inputs, file_identities, maybe_write_run_manifest,
     path_to_stable_string, ManifestDestination, RunManifestInput, RunManifestStart,
@@ -24,6 +28,7 @@ use crate::util::{
 };
 use crate::zstd_jsonl::{
     for_each_line_with_progress_cfg_no_throttle_status, malformed_json_error, parse_minimal,
+    MinimalRecord,
 };
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -41,6 +46,10 @@ use ahash::AHashSet;
 /// `attach_parents_jsonls_parallel`.
 const COMMENT_SHARD_CACHE_CAP: usize = 8;
 const SUBMISSION_SHARD_CACHE_CAP: usize = 6;
+/// Ceilings on `ETLOptions::parent_cache_caps` by free-memory tier (same
+/// thresholds as the id-set cache); in the low tier the defaults above win.
+const SHARD_CACHE_HIGH_MAX_CAP: usize = 64;
+const SHARD_CACHE_MEDIUM_MAX_CAP: usize = 16;
 const IDSET_CACHE_HIGH_FREE_THRESHOLD: f64 = 0.50;
 const IDSET_CACHE_MEDIUM_FREE_THRESHOLD: f64 = 0.20;
 const IDSET_CACHE_HIGH_CAP: usize = 512;
@@ -54,6 +63,10 @@ const RESOLVER_FORMAT_VERSION: u32 = 1;
 const LEGACY_PARENT_PAYLOAD_FORMAT_VERSION: u32 = 1;
 const STRUCTURED_PARENT_PAYLOAD_FORMAT_VERSION: u32 = 2;
 const RESOLVER_SIDECAR_SUFFIX: &str = ".parents-resolve.json";
+const PARENT_LINKS_SUFFIX: &str = ".parent-links.json";
+/// Subdirectory of the attach output dir that receives unresolved-parent
+/// records under `ETLOptions::split_unresolved_parents`.
+const UNRESOLVED_ATTACH_DIR: &str = "unresolved";
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct ParentAttachStats {
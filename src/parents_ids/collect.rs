@@ -1,4 +1,3 @@
-
 /// Scale parent-id shard fan-out to the total input size.
 ///
 /// `IdShardWriter::create` eagerly creates one `<kind>_ids_NNNN.tmp` scratch
@@ -9,14 +8,14 @@
 /// dominates runtime. One shard per 32 MiB of input, clamped to
 /// `[1, MAX_SHARDS]`, keeps large corpora at full fan-out while a small input
 /// gets only a handful of shards.
-fn parent_id_shard_count(total_bytes: u64) -> usize {
+pub(crate) fn parent_id_shard_count(total_bytes: u64) -> usize {
     const BYTES_PER_SHARD: u64 = 32 * 1024 * 1024;
     let scaled = total_bytes / BYTES_PER_SHARD + 1;
     (scaled as usize).clamp(1, MAX_SHARDS)
 }
 
 impl RedditETL {
-    pub fn collect_parent_ids_from_jsonls<I>(&self, jsonl_paths: I) -> Result<ParentIds>
+    pub fn collect_parent_ids_from_jsonls<I>(&self, jsonl_paths: I) -> Result<ParentIds, RetlError>
     where
         I: IntoIterator<Item = PathBuf>,
     {
@@ -25,6 +24,7 @@ impl RedditETL {
             return Ok(ParentIds::new());
         }
 
+        let _run_settings = self.opts.enter_run_settings();
         with_thread_pool(self.opts.parallelism, || {
             let work_dir = self.ensure_work_dir()?;
             let scratch_root = IdScratchRoot::create(&work_dir)?;
@@ -35,15 +35,23 @@ impl RedditETL {
                 .sum();
 
             // Size shard fan-out to the input rather than always opening
-            // `2 * MAX_SHARDS` scratch files — see `parent_id_shard_count`.
-            let shard_count = parent_id_shard_count(total_bytes);
-            let t1_writer = IdShardWriter::create(scratch_root.clone(), "t1", shard_count)?;
-            let t3_writer = IdShardWriter::create(scratch_root, "t3", shard_count)?;
+            // `2 * MAX_SHARDS` scratch files — see `parent_id_shard_count` —
+            // unless the caller picked a count with `shard_count(..)`.
+            let shard_count = self
+                .opts
+                .shard_count
+                .unwrap_or_else(|| parent_id_shard_count(total_bytes));
+            let flush_bytes = self.opts.shard_flush_bytes;
+            let t1_writer = IdShardWriter::create(scratch_root.clone(), "t1", shard_count)?
+                .with_flush_every_bytes(flush_bytes);
+            let t3_writer = IdShardWriter::create(scratch_root, "t3", shard_count)?
+                .with_flush_every_bytes(flush_bytes);
 
             let pb = if self.opts.progress {
-                Some(make_progress_bar_labeled(
+                Some(make_stage_progress_bar(
                     total_bytes,
                     self.opts.progress_label.as_deref(),
+                    self.opts.progress_stage.as_ref(),
                 ))
             } else {
                 None
@@ -68,66 +76,70 @@ impl RedditETL {
             // bounds in-flight readers on this stage exactly as it does on the
             // resolve and attach stages; an unbounded `par_iter` here would
             // silently ignore the knob the run manifest records as applied.
-            crate::concurrency::for_each_file_limited(&paths, self.opts.file_concurrency, |p| -> Result<()> {
-                let f = crate::util::open_with_default_backoff(p)
-                    .with_context(|| format!("open parent-id spool input {}", p.display()))?;
-                let mut r = BufReader::with_capacity(read_buf, f);
-                let mut buf = String::with_capacity(64 * 1024);
-                let mut line_number = 0u64;
-                loop {
-                    let n = read_line_capped(&mut r, &mut buf, DEFAULT_MAX_LINE_BYTES, p)
-                        .with_context(|| {
-                            format!(
-                                "read parent-id spool input {} near line {}",
-                                p.display(),
-                                line_number + 1
-                            )
-                        })?;
-                    if n == 0 {
-                        break;
-                    }
-                    line_number += 1;
-                    if buf.is_empty() {
-                        if let Some(pb) = &pb {
-                            pb.inc(n as u64);
+            crate::concurrency::for_each_file_limited(
+                &paths,
+                self.opts.file_concurrency,
+                |p| -> Result<()> {
+                    let f = crate::util::open_with_default_backoff(p)
+                        .with_context(|| format!("open parent-id spool input {}", p.display()))?;
+                    let mut r = BufReader::with_capacity(read_buf, f);
+                    let mut buf = String::with_capacity(64 * 1024);
+                    let mut line_number = 0u64;
+                    loop {
+                        let n = read_line_capped(&mut r, &mut buf, DEFAULT_MAX_LINE_BYTES, p)
+                            .with_context(|| {
+                                format!(
+                                    "read parent-id spool input {} near line {}",
+                                    p.display(),
+                                    line_number + 1
+                                )
+                            })?;
+                        if n == 0 {
+                            break;
+                        }
+                        line_number += 1;
+                        if buf.is_empty() {
+                            if let Some(pb) = &pb {
+                                pb.inc(n as u64);
+                            }
+                            continue;
                         }
-                        continue;
-                    }
 
-                    let v: Value = serde_json::from_str(&buf)
-                        .map_err(|e| malformed_json_error(p, line_number, e))?;
-                    let mut record_has_ref = false;
-                    let mut record_has_usable_id = false;
-                    if let Some(parent_id) = v.get("parent_id").and_then(|x| x.as_str()) {
-                        record_has_ref = true;
-                        if let Some(rest) = parent_id.strip_prefix("t1_") {
-                            t1_writer.write(rest)?;
-                            record_has_usable_id = true;
-                        } else if let Some(rest) = parent_id.strip_prefix("t3_") {
-                            t3_writer.write(rest)?;
-                            record_has_usable_id = true;
+                        let v: Value = serde_json::from_str(&buf)
+                            .map_err(|e| malformed_json_error(p, line_number, e))?;
+                        let mut record_has_ref = false;
+                        let mut record_has_usable_id = false;
+                        if let Some(parent_id) = v.get("parent_id").and_then(|x| x.as_str()) {
+                            record_has_ref = true;
+                            if let Some(rest) = parent_id.strip_prefix("t1_") {
+                                t1_writer.write(rest)?;
+                                record_has_usable_id = true;
+                            } else if let Some(rest) = parent_id.strip_prefix("t3_") {
+                                t3_writer.write(rest)?;
+                                record_has_usable_id = true;
+                            }
                         }
-                    }
-                    if let Some(link_id) = v.get("link_id").and_then(|x| x.as_str()) {
-                        record_has_ref = true;
-                        if let Some(rest) = link_id.strip_prefix("t3_") {
-                            t3_writer.write(rest)?;
-                            record_has_usable_id = true;
+                        if let Some(link_id) = v.get("link_id").and_then(|x| x.as_str()) {
+                            record_has_ref = true;
+                            if let Some(rest) = link_id.strip_prefix("t3_") {
+                                t3_writer.write(rest)?;
+                                record_has_usable_id = true;
+                            }
                         }
-                    }
-                    if record_has_ref {
-                        records_with_ref.fetch_add(1, Ordering::Relaxed);
-                        if !record_has_usable_id {
-                            records_with_unprefixed_ref.fetch_add(1, Ordering::Relaxed);
+                        if record_has_ref {
+                            records_with_ref.fetch_add(1, Ordering::Relaxed);
+                            if !record_has_usable_id {
+                                records_with_unprefixed_ref.fetch_add(1, Ordering::Relaxed);
+                            }
                         }
-                    }
 
-                    if let Some(pb) = &pb {
-                        pb.inc(n as u64);
+                        if let Some(pb) = &pb {
+                            pb.inc(n as u64);
+                        }
                     }
-                }
-                Ok(())
-            })?;
+                    Ok(())
+                },
+            )?;
 
             let t1_shards = t1_writer.dedup()?;
             let t3_shards = t3_writer.dedup()?;
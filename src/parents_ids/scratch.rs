@@ -1,8 +1,9 @@
 use crate::config::{clamp_shard_count, MAX_SHARDS};
+use crate::error::RetlError;
 use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
 use crate::parents::ParentIds;
 use crate::pipeline::RedditETL;
-use crate::progress::make_progress_bar_labeled;
+use crate::progress::make_stage_progress_bar;
 use crate::shard_common;
 use crate::util::with_thread_pool;
 use crate::zstd_jsonl::malformed_json_error;
@@ -12,7 +13,7 @@ use rayon::prelude::*;
 use serde_json::Value;
 use std::collections::{HashMap, VecDeque};
 use std::fs;
-use std::io::{self, BufReader, Write};
+use std::io::{self, BufReader};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -25,7 +26,7 @@ pub(crate) struct IdScratchRoot {
 }
 
 impl IdScratchRoot {
-    fn create(work_dir: &Path) -> Result<Arc<Self>> {
+    pub(crate) fn create(work_dir: &Path) -> Result<Arc<Self>> {
         let parent = work_dir.join("parent_ids");
         crate::util::create_dir_all_with_default_backoff(&parent)
             .with_context(|| format!("create parent-id scratch parent {}", parent.display()))?;
@@ -56,7 +57,7 @@ impl IdScratchRoot {
         ))
     }
 
-    fn path(&self) -> &Path {
+    pub(crate) fn path(&self) -> &Path {
         &self.path
     }
 }
@@ -33,6 +33,11 @@ impl IdShardWriter {
             writers,
         })
     }
+    /// See [`ShardedWriter::with_flush_every_bytes`](crate::ShardedWriter::with_flush_every_bytes).
+    pub(crate) fn with_flush_every_bytes(mut self, bytes: u64) -> Self {
+        self.writers.set_flush_every_bytes(bytes);
+        self
+    }
     #[inline]
     fn idx(&self, id: &str) -> usize {
         shard_common::shard_index(&self.rs, id, self.count)
@@ -40,10 +45,7 @@ impl IdShardWriter {
     #[inline]
     pub(crate) fn write(&self, id: &str) -> Result<()> {
         let i = self.idx(id);
-        let mut w = self.writers[i].lock();
-        w.write_all(id.as_bytes())?;
-        w.write_all(b"\n")?;
-        Ok(())
+        self.writers.write_parts(i, &[id.as_bytes(), b"\n"])
     }
     fn flush(&self) -> Result<()> {
         shard_common::flush_line_shard_writers(&self.writers)
@@ -63,6 +63,25 @@ impl fmt::Display for PlanningError {
                     .unwrap_or_else(|| "<corpus-end>".to_string());
                 write!(f, "requested date range {req_start}..={req_end} matched no files for source selection {sources:?}; corpus available range is {available_start}..={available_end}")
             }
+            PlanningError::DuplicateMonth {
+                kind,
+                ym,
+                first,
+                second,
+            } => {
+                write!(
+                    f,
+                    "{} month {ym} is present in more than one corpus root: {} and {}; remove one or use DuplicateMonthPolicy::LaterWins",
+                    kind.long_label(),
+                    first.display(),
+                    second.display()
+                )
+            }
+            PlanningError::UnrecognizedFile { path } => write!(
+                f,
+                "input file {} is not named RC_YYYY-MM or RS_YYYY-MM with a .zst or .jsonl extension",
+                path.display()
+            ),
         }
     }
 }
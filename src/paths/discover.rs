@@ -1,4 +1,4 @@
-use super::{Discovered, FileKind, PlanningError};
+use super::{Discovered, DuplicateMonthPolicy, FileKind, PlanningError};
 use crate::config::Sources;
 use crate::date::YearMonth;
 use regex::Regex;
@@ -7,14 +7,45 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use walkdir::WalkDir;
 
+/// Monthly corpus file names: the `.zst` dumps, or plain `.jsonl` as written
+/// by a JSONL partitioned export, so an export can be re-queried as a corpus.
+fn monthly_file_regex(kind: FileKind) -> Regex {
+    match kind {
+        FileKind::Comment => Regex::new(r"^RC_(\d{4})-(\d{2})\.(?:zst|jsonl)$").unwrap(),
+        FileKind::Submission => Regex::new(r"^RS_(\d{4})-(\d{2})\.(?:zst|jsonl)$").unwrap(),
+    }
+}
+
+/// Add a discovered month, keeping the `.zst` dump when a directory holds
+/// both it and a decompressed `.jsonl` copy of the same month.
+fn insert_discovered_month(
+    map: &mut BTreeMap<YearMonth, PathBuf>,
+    ym: YearMonth,
+    path: PathBuf,
+    kind: FileKind,
+) {
+    let keep_existing = map
+        .get(&ym)
+        .is_some_and(|existing| !crate::zstd_jsonl::is_plain_jsonl(existing));
+    if let Some(existing) = map.get(&ym) {
+        tracing::warn!(
+            source = kind.long_label(),
+            month = %ym,
+            first = %existing.display(),
+            second = %path.display(),
+            "month found as both .zst and .jsonl; reading the .zst"
+        );
+    }
+    if !keep_existing {
+        map.insert(ym, path);
+    }
+}
+
 fn discover_month_map_checked(
     dir: &Path,
     kind: FileKind,
 ) -> Result<BTreeMap<YearMonth, PathBuf>, PlanningError> {
-    let re = match kind {
-        FileKind::Comment => Regex::new(r"^RC_(\d{4})-(\d{2})\.zst$").unwrap(),
-        FileKind::Submission => Regex::new(r"^RS_(\d{4})-(\d{2})\.zst$").unwrap(),
-    };
+    let re = monthly_file_regex(kind);
     let mut map = BTreeMap::new();
     if !dir.exists() {
         return Ok(map);
@@ -36,9 +67,7 @@ fn discover_month_map_checked(
             if let Some(caps) = re.captures(name) {
                 let ym_raw = format!("{}-{}", &caps[1], &caps[2]);
                 match YearMonth::from_str(&ym_raw) {
-                    Ok(ym) => {
-                        map.insert(ym, ent.path().to_path_buf());
-                    }
+                    Ok(ym) => insert_discovered_month(&mut map, ym, ent.path().to_path_buf(), kind),
                     Err(error) => {
                         tracing::warn!(
                             path = %ent.path().display(),
@@ -101,6 +130,100 @@ pub fn discover_sources_checked(
     })
 }
 
+/// Discover several corpus roots, given as `(comments_dir, submissions_dir)`
+/// pairs, and merge them into one [`Discovered`]. Roots are visited in order;
+/// a month found under two roots is resolved by `policy`. The same file
+/// reached through two identical roots is not a conflict.
+pub fn discover_roots_checked(
+    roots: &[(PathBuf, PathBuf)],
+    sources: Sources,
+    policy: DuplicateMonthPolicy,
+) -> Result<Discovered, PlanningError> {
+    let mut merged = Discovered {
+        comments: BTreeMap::new(),
+        submissions: BTreeMap::new(),
+    };
+    for (comments_dir, submissions_dir) in roots {
+        let found = discover_sources_checked(comments_dir, submissions_dir, sources)?;
+        merge_month_map(
+            &mut merged.comments,
+            found.comments,
+            FileKind::Comment,
+            policy,
+        )?;
+        merge_month_map(
+            &mut merged.submissions,
+            found.submissions,
+            FileKind::Submission,
+            policy,
+        )?;
+    }
+    Ok(merged)
+}
+
+/// Group an explicit list of monthly files (see `ETLOptions::files`) into a
+/// [`Discovered`], taking each file's kind and month from its name. A name
+/// that is not a valid `RC_YYYY-MM` or `RS_YYYY-MM` `.zst`/`.jsonl` file
+/// fails planning;
+/// two files for the same month are resolved by `policy`.
+pub fn discover_files_checked(
+    paths: &[PathBuf],
+    policy: DuplicateMonthPolicy,
+) -> Result<Discovered, PlanningError> {
+    let res = [
+        (FileKind::Comment, monthly_file_regex(FileKind::Comment)),
+        (
+            FileKind::Submission,
+            monthly_file_regex(FileKind::Submission),
+        ),
+    ];
+    let mut merged = Discovered {
+        comments: BTreeMap::new(),
+        submissions: BTreeMap::new(),
+    };
+    for path in paths {
+        let unrecognized = || PlanningError::UnrecognizedFile { path: path.clone() };
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(unrecognized)?;
+        let (kind, caps) = res
+            .iter()
+            .find_map(|(kind, re)| re.captures(name).map(|caps| (*kind, caps)))
+            .ok_or_else(unrecognized)?;
+        let ym = YearMonth::from_str(&format!("{}-{}", &caps[1], &caps[2]))
+            .map_err(|_| unrecognized())?;
+        let into = match kind {
+            FileKind::Comment => &mut merged.comments,
+            FileKind::Submission => &mut merged.submissions,
+        };
+        merge_month_map(into, BTreeMap::from([(ym, path.clone())]), kind, policy)?;
+    }
+    Ok(merged)
+}
+
+fn merge_month_map(
+    into: &mut BTreeMap<YearMonth, PathBuf>,
+    from: BTreeMap<YearMonth, PathBuf>,
+    kind: FileKind,
+    policy: DuplicateMonthPolicy,
+) -> Result<(), PlanningError> {
+    for (ym, path) in from {
+        if let Some(first) = into.get(&ym) {
+            if *first != path && policy == DuplicateMonthPolicy::Error {
+                return Err(PlanningError::DuplicateMonth {
+                    kind,
+                    ym,
+                    first: first.clone(),
+                    second: path,
+                });
+            }
+        }
+        into.insert(ym, path);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +262,75 @@ mod tests {
         }));
         assert!(discovered.submissions.is_empty());
     }
+
+    #[test]
+    fn plain_jsonl_months_are_discovered_but_zst_wins_a_tie() {
+        let tmp = tempfile::tempdir().unwrap();
+        let comments = tmp.path().join("comments");
+        let submissions = tmp.path().join("submissions");
+        fs::create_dir(&comments).unwrap();
+        fs::create_dir(&submissions).unwrap();
+        fs::write(comments.join("RC_2024-01.jsonl"), b"").unwrap();
+        fs::write(comments.join("RC_2024-01.zst"), b"").unwrap();
+        fs::write(submissions.join("RS_2024-02.jsonl"), b"").unwrap();
+
+        let discovered = discover_all_checked(&comments, &submissions).unwrap();
+        assert_eq!(
+            discovered.comments.get(&YearMonth::new(2024, 1)),
+            Some(&comments.join("RC_2024-01.zst"))
+        );
+        assert_eq!(
+            discovered.submissions.get(&YearMonth::new(2024, 2)),
+            Some(&submissions.join("RS_2024-02.jsonl"))
+        );
+    }
+
+    #[test]
+    fn root_merge_detects_duplicate_months_unless_later_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut roots = Vec::new();
+        for name in ["a", "b"] {
+            let comments = tmp.path().join(name).join("comments");
+            fs::create_dir_all(&comments).unwrap();
+            fs::write(comments.join("RC_2024-01.zst"), b"").unwrap();
+            roots.push((comments, tmp.path().join(name).join("submissions")));
+        }
+
+        let err =
+            discover_roots_checked(&roots, Sources::Both, DuplicateMonthPolicy::Error).unwrap_err();
+        assert!(matches!(err, PlanningError::DuplicateMonth { .. }), "{err}");
+
+        let merged =
+            discover_roots_checked(&roots, Sources::Both, DuplicateMonthPolicy::LaterWins).unwrap();
+        assert_eq!(
+            merged.comments.get(&YearMonth::new(2024, 1)),
+            Some(&roots[1].0.join("RC_2024-01.zst"))
+        );
+    }
+
+    #[test]
+    fn explicit_files_are_classified_by_name() {
+        let files = vec![
+            PathBuf::from("/a/RS_2024-03.zst"),
+            PathBuf::from("/b/RC_2023-11.zst"),
+        ];
+        let discovered = discover_files_checked(&files, DuplicateMonthPolicy::Error).unwrap();
+        assert_eq!(
+            discovered.comments.get(&YearMonth::new(2023, 11)),
+            Some(&files[1])
+        );
+        assert_eq!(
+            discovered.submissions.get(&YearMonth::new(2024, 3)),
+            Some(&files[0])
+        );
+
+        for bad in ["/a/RC_2024-13.zst", "/a/comments_2024-01.zst"] {
+            let err = discover_files_checked(&[PathBuf::from(bad)], DuplicateMonthPolicy::Error)
+                .unwrap_err();
+            assert!(
+                matches!(err, PlanningError::UnrecognizedFile { .. }),
+                "{err}"
+            );
+        }
+    }
 }
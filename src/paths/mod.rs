@@ -6,8 +6,12 @@ mod types;
 pub use diagnostics::{
     format_year_month_ranges, log_missing_month_warnings, missing_month_diagnostics,
 };
-pub use discover::{discover_all, discover_all_checked, discover_sources_checked};
+pub use discover::{
+    discover_all, discover_all_checked, discover_files_checked, discover_roots_checked,
+    discover_sources_checked,
+};
 pub use plan::{plan_files, plan_files_checked};
 pub use types::{
-    Discovered, FileJob, FileKind, MissingMonthDiagnostic, PlanningError, SourceStatus,
+    Discovered, DuplicateMonthPolicy, FileJob, FileKind, MissingMonthDiagnostic, PlanningError,
+    SourceStatus,
 };
@@ -19,9 +19,12 @@ impl FileKind {
     }
 }
 
+/// One monthly corpus file selected for a run, as passed to
+/// [`RedditETL::for_each_planned_file`](crate::RedditETL::for_each_planned_file).
 #[derive(Clone, Debug)]
 pub struct FileJob {
     pub kind: FileKind,
+    /// Month named in the file name (`RC_YYYY-MM.zst`).
     pub ym: YearMonth,
     pub path: PathBuf,
 }
@@ -34,6 +37,17 @@ pub struct Discovered {
     pub submissions: BTreeMap<YearMonth, PathBuf>,
 }
 
+/// What discovery does when two corpus roots both hold the same month of the
+/// same source (see `ETLOptions::extra_base_dirs`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateMonthPolicy {
+    /// Fail planning with [`PlanningError::DuplicateMonth`].
+    #[default]
+    Error,
+    /// Keep the file from the root listed last.
+    LaterWins,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PlanningError {
     DiscoveryFailed {
@@ -52,6 +66,16 @@ pub enum PlanningError {
         available_start: YearMonth,
         available_end: YearMonth,
     },
+    DuplicateMonth {
+        kind: FileKind,
+        ym: YearMonth,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    /// An explicit input file whose name is not a valid monthly file name.
+    UnrecognizedFile {
+        path: PathBuf,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
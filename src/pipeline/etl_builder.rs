@@ -1,15 +1,19 @@
-use crate::config::{ETLOptions, Sources};
+use crate::config::{ETLOptions, IntermediateFormat, Sources, TuningProfile};
 use crate::date::YearMonth;
+use crate::error::RetlError;
+use crate::integrity::CorruptAction;
+use crate::key_extractor::KeyExtractor;
 use crate::mem::AdaptiveMemCfg;
-use crate::parents::ParentPayloadSpec;
+use crate::parents::{ParentPayloadSpec, DEFAULT_MAX_REPLY_DEPTH};
+use crate::paths::DuplicateMonthPolicy;
 use crate::query::{
-    normalize_str, read_record_ids_file, JsonPointerPredicate, NumericComparison, QueryBuildError,
-    QuerySpec, RecordIdKind, TimestampBounds,
+    read_record_ids_file, EditedMode, IntoAuthorRegex, JsonPointerPredicate, NumericComparison,
+    QueryBuildError, QuerySpec, RecordIdKind, RecordTransform, SourceRatio, TimeField,
 };
 use crate::util::{default_bot_authors, try_merge_extra_exclusions};
 use anyhow::Result;
-use regex::Regex;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct RedditETL {
@@ -34,6 +38,29 @@ impl RedditETL {
         self.opts = self.opts.with_base_dir(base);
         self
     }
+    /// Add another corpus root alongside `base_dir` (e.g. a second drive
+    /// holding later months). Roots are discovered in the order given and
+    /// merged; see [`RedditETL::duplicate_month_policy`] for overlaps.
+    pub fn add_base_dir(mut self, base: impl AsRef<std::path::Path>) -> Self {
+        self.opts = self.opts.with_extra_base_dir(base);
+        self
+    }
+    /// Choose whether a month present under two roots is an error (the
+    /// default) or resolved in favor of the root added last.
+    pub fn duplicate_month_policy(mut self, policy: DuplicateMonthPolicy) -> Self {
+        self.opts = self.opts.with_duplicate_month_policy(policy);
+        self
+    }
+    /// Process exactly these monthly files instead of discovering
+    /// `base_dir`, e.g. a few months re-downloaded after corruption. Each
+    /// file's source and month are read from its name, which must be
+    /// `RC_YYYY-MM.zst` or `RS_YYYY-MM.zst` (`.jsonl` for uncompressed
+    /// exports). The date range and `sources` no
+    /// longer pick files, though a date range still filters records.
+    pub fn files(mut self, files: Vec<PathBuf>) -> Self {
+        self.opts = self.opts.with_files(files);
+        self
+    }
     #[deprecated(
         note = "use RedditETL::scan().subreddits([...]) instead; ETLOptions::subreddit is a single-value default"
     )]
@@ -52,6 +79,33 @@ impl RedditETL {
         self.opts = self.opts.with_date_range(start, end);
         self
     }
+    /// Process `start` and every later month, keeping any end bound already
+    /// set. The open end resolves to the last month on disk.
+    pub fn since(self, start: YearMonth) -> Self {
+        let end = self.opts.end;
+        self.date_range(Some(start), end)
+    }
+    /// Process every month up to and including `end`, keeping any start
+    /// bound already set. The open start resolves to the first month on disk.
+    pub fn until(self, end: YearMonth) -> Self {
+        let start = self.opts.start;
+        self.date_range(start, Some(end))
+    }
+    /// Process January through December of `year`; shorthand for
+    /// `date_range(Some(YearMonth::new(year, 1)), Some(YearMonth::new(year, 12)))`.
+    pub fn year(self, year: u16) -> Self {
+        self.years(year..=year)
+    }
+    /// Process January of the first year through December of the last, e.g.
+    /// `years(2014..=2016)`. An empty range is rejected like an inverted
+    /// `date_range`.
+    pub fn years(self, years: std::ops::RangeInclusive<u16>) -> Self {
+        let (first, last) = years.into_inner();
+        self.date_range(
+            Some(YearMonth::new(first, 1)),
+            Some(YearMonth::new(last, 12)),
+        )
+    }
     pub fn whitelist_fields<I, S>(mut self, fields: I) -> Self
     where
         I: IntoIterator<Item = S>,
@@ -64,6 +118,12 @@ impl RedditETL {
         self.opts = self.opts.with_strict_whitelist(yes);
         self
     }
+    /// Emit whitelisted fields in each record's source key order, even on
+    /// lines that take the full-parse fallback.
+    pub fn whitelist_source_order(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_whitelist_source_order(yes);
+        self
+    }
     pub fn strict_key(mut self, yes: bool) -> Self {
         self.opts = self.opts.with_strict_key(yes);
         self
@@ -89,10 +149,53 @@ impl RedditETL {
         self.opts = self.opts.with_shard_count(count);
         self
     }
+    /// See [`ETLOptions::shard_flush_bytes`].
+    pub fn shard_flush_bytes(mut self, bytes: u64) -> Self {
+        self.opts = self.opts.with_shard_flush_bytes(bytes);
+        self
+    }
+    /// See [`ETLOptions::in_memory_shards`].
+    pub fn in_memory_shards(mut self, on: bool) -> Self {
+        self.opts = self.opts.with_in_memory_shards(on);
+        self
+    }
+    /// See [`ETLOptions::in_memory_shard_spill_bytes`].
+    pub fn in_memory_shard_spill_bytes(mut self, bytes: u64) -> Self {
+        self.opts = self.opts.with_in_memory_shard_spill_bytes(bytes);
+        self
+    }
+    /// See [`ETLOptions::intermediate_format`].
+    pub fn intermediate_format(mut self, format: IntermediateFormat) -> Self {
+        self.opts = self.opts.with_intermediate_format(format);
+        self
+    }
     pub fn file_concurrency(mut self, n: usize) -> Self {
         self.opts = self.opts.with_file_concurrency(n);
         self
     }
+    /// See [`ETLOptions::integrity_file_timeout`].
+    pub fn integrity_file_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.opts = self.opts.with_integrity_file_timeout(timeout);
+        self
+    }
+    /// See [`ETLOptions::integrity_on_corrupt`].
+    pub fn integrity_on_corrupt(mut self, action: CorruptAction) -> Self {
+        self.opts = self.opts.with_integrity_on_corrupt(action);
+        self
+    }
+    /// Deliver callback/stream records, and stitch single-file outputs, in
+    /// chronological order. See [`ETLOptions::ordered_output`] for the
+    /// throughput tradeoff.
+    pub fn ordered_output(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_ordered_output(yes);
+        self
+    }
+    /// Plan and stitch RC and RS files month by month; see
+    /// [`ETLOptions::interleaved_sources`].
+    pub fn interleaved_sources(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_interleaved_sources(yes);
+        self
+    }
     pub fn progress(mut self, yes: bool) -> Self {
         self.opts = self.opts.with_progress(yes);
         self
@@ -101,6 +204,12 @@ impl RedditETL {
         self.opts = self.opts.with_progress_label(label);
         self
     }
+    /// Draw this call's progress bar as `stage` of a [`PipelineProgress`]
+    /// stack; see [`PipelineProgress::run_stage`].
+    pub fn progress_stage(mut self, stage: crate::progress::ProgressStage) -> Self {
+        self.opts = self.opts.with_progress_stage(stage);
+        self
+    }
     pub fn io_read_buffer(mut self, bytes: usize) -> Self {
         self.opts = self.opts.with_io_read_buffer(bytes);
         self
@@ -113,14 +222,65 @@ impl RedditETL {
         self.opts = self.opts.with_io_buffers(read_bytes, write_bytes);
         self
     }
+    /// See [`ETLOptions::write_batch_size`].
+    pub fn write_batch_size(mut self, records: usize) -> Self {
+        self.opts = self.opts.with_write_batch_size(records);
+        self
+    }
+    /// See [`ETLOptions::with_io_retries`].
+    pub fn io_retries(mut self, tries: usize, base_delay_ms: u64) -> Self {
+        self.opts = self.opts.with_io_retries(tries, base_delay_ms);
+        self
+    }
+    /// See [`ETLOptions::with_max_line_bytes`].
+    pub fn max_line_bytes(mut self, bytes: usize) -> Self {
+        self.opts = self.opts.with_max_line_bytes(bytes);
+        self
+    }
+    /// See [`ETLOptions::with_fsync`].
+    pub fn fsync(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_fsync(yes);
+        self
+    }
     pub fn timestamps_human_readable(mut self, yes: bool) -> Self {
         self.opts = self.opts.with_human_timestamps(yes);
         self
     }
+    /// See [`ETLOptions::with_canonicalize_json`].
+    pub fn canonicalize_json(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_canonicalize_json(yes);
+        self
+    }
+    /// See [`ETLOptions::with_add_provenance`].
+    pub fn add_provenance(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_add_provenance(yes);
+        self
+    }
+    /// See [`ETLOptions::with_strict_json`].
+    pub fn strict_json(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_strict_json(yes);
+        self
+    }
+    /// See [`ETLOptions::with_max_output_bytes`].
+    pub fn max_output_bytes(mut self, bytes: u64) -> Self {
+        self.opts = self.opts.with_max_output_bytes(bytes);
+        self
+    }
+    /// See [`ETLOptions::keep_empty_outputs`].
+    pub fn keep_empty_outputs(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_keep_empty_outputs(yes);
+        self
+    }
     pub fn zst_level(mut self, level: i32) -> Self {
         self.opts = self.opts.with_zst_level(level);
         self
     }
+    /// See [`ETLOptions::with_zstd_dictionary`]; runs also install the
+    /// dictionary for readers.
+    pub fn zstd_dictionary(mut self, path: impl AsRef<Path>) -> Self {
+        self.opts = self.opts.with_zstd_dictionary(path);
+        self
+    }
     /// Rows per Parquet row group (`extract_to_parquet` / partitioned Parquet).
     /// See [`ETLOptions::parquet_row_group_size`].
     pub fn parquet_row_group_size(mut self, rows: usize) -> Self {
@@ -181,6 +341,12 @@ impl RedditETL {
         self.opts = self.opts.disable_inflight_cap();
         self
     }
+    /// Set the IO and memory knobs from one [`TuningProfile`]; see
+    /// [`ETLOptions::with_tuning`]. Call before any single-knob overrides.
+    pub fn tuning(mut self, profile: TuningProfile) -> Self {
+        self.opts = self.opts.with_tuning(profile);
+        self
+    }
     /// Override the adaptive-memory policy used by bucketing/dedupe producers.
     pub fn adaptive_mem(mut self, cfg: AdaptiveMemCfg) -> Self {
         self.opts = self.opts.with_adaptive_mem(cfg);
@@ -223,6 +389,32 @@ impl RedditETL {
         self
     }
 
+    /// Keep up to `comments` / `submissions` resolved parent shards per attach
+    /// worker. A performance knob only; see [`ETLOptions::parent_cache_caps`].
+    pub fn parent_cache_caps(mut self, comments: usize, submissions: usize) -> Self {
+        self.opts = self.opts.with_parent_cache_caps(comments, submissions);
+        self
+    }
+
+    /// Make `attach_parents_jsonls_parallel` write records whose parent is
+    /// unresolved to `<out_dir>/unresolved/<name>`, keeping only resolved
+    /// (and parentless) records in the main output. The split counts are the
+    /// `resolved` / `unresolved` fields of the returned
+    /// [`ParentAttachStats`](crate::ParentAttachStats).
+    pub fn split_unresolved_parents(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_split_unresolved_parents(yes);
+        self
+    }
+
+    /// Record comment parent links while resolving, so
+    /// [`ScanPlan::annotate_depth`](crate::ScanPlan::annotate_depth) can walk
+    /// reply chains through the returned maps. See
+    /// [`ETLOptions::parent_reply_links`].
+    pub fn parent_reply_links(mut self, yes: bool) -> Self {
+        self.opts = self.opts.with_parent_reply_links(yes);
+        self
+    }
+
     /// Replace the full parent-payload specification used by parents helpers.
     pub fn parent_payload_spec(mut self, spec: ParentPayloadSpec) -> Self {
         self.opts = self.opts.with_parent_payload_spec(spec);
@@ -238,6 +430,14 @@ impl RedditETL {
         self
     }
 
+    /// Write malformed corpus lines to a JSONL reject file at `path` and
+    /// keep going instead of aborting the month. See
+    /// [`ETLOptions::with_reject_sink`].
+    pub fn reject_sink(mut self, path: impl Into<PathBuf>) -> Self {
+        self.opts = self.opts.with_reject_sink(path);
+        self
+    }
+
     /// Return a handle that snapshots tolerated partial zstd reads recorded by
     /// this builder. Clone it before starting a consuming operation.
     ///
@@ -253,12 +453,9 @@ impl RedditETL {
     pub fn scan(self) -> ScanPlan {
         ScanPlan {
             etl: self,
-            query: QuerySpec {
-                filter_pseudo_users: true,
-                ..Default::default()
-            }
-            .normalize(),
+            query: QuerySpec::new(),
             limit: None,
+            reply_depth_cap: None,
         }
     }
 
@@ -1,4 +1,14 @@
 impl ScanPlan {
+    /// Replace this plan's filters with `query`, e.g. one assembled once with
+    /// the [`QuerySpec`] builders and reused across several `RedditETL`s
+    /// (different date ranges, corpora, or options).
+    ///
+    /// The query is normalized on adoption and validated, like any other
+    /// filters, by [`ScanPlan::build`].
+    pub fn with_query(mut self, query: QuerySpec) -> Self {
+        self.query = query.normalize();
+        self
+    }
     pub fn build(mut self) -> std::result::Result<Self, QueryBuildError> {
         if self
             .etl
@@ -7,28 +7,11 @@ pub(crate) fn log_domain_filter_comment_drop(query: &QuerySpec, sources: Sources
 }
 
 impl ScanPlan {
-    /// Common implementation for setters that map an iterator of strings into
-    /// an `Option<Vec<String>>` field on the [`QuerySpec`], then renormalize.
-    /// `set_field` writes the collected list onto the chosen field; `norm` is
-    /// applied per-element before collection (e.g. `normalize_str`, lowercase).
-    fn set_string_list<I, S>(
-        mut self,
-        set_field: impl FnOnce(&mut QuerySpec, Vec<String>),
-        iter: I,
-        norm: fn(&str) -> String,
-    ) -> Self
-    where
-        I: IntoIterator<Item = S>,
-        S: AsRef<str>,
-    {
-        let v: Vec<String> = iter.into_iter().map(|s| norm(s.as_ref())).collect();
-        set_field(&mut self.query, v);
-        self.query = self.query.normalize();
+    /// Apply one of the [`QuerySpec`] builder methods to this plan's query.
+    /// The filter setters on `ScanPlan` all delegate through here so the two
+    /// builder surfaces cannot drift apart.
+    fn map_query(mut self, f: impl FnOnce(QuerySpec) -> QuerySpec) -> Self {
+        self.query = f(self.query);
         self
     }
 }
-
-#[inline]
-fn lowercase_str(s: &str) -> String {
-    s.to_lowercase()
-}
@@ -54,19 +54,12 @@ impl ScanPlan {
         self.ids_in(iter)
     }
     /// Alias for [`ScanPlan::ids`]; like `ids`, repeated calls accumulate.
-    pub fn ids_in<I, S>(mut self, iter: I) -> Self
+    pub fn ids_in<I, S>(self, iter: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        // Append onto the unqualified bucket and re-normalize: `normalize`
-        // re-buckets every ID list, so previously supplied `t1_`/`t3_`
-        // selectors (already split into `comment_ids_in`/`submission_ids_in`)
-        // keep their source constraint instead of being wiped.
-        let new = iter.into_iter().map(|s| s.as_ref().to_string());
-        self.query.ids_in.get_or_insert_with(Vec::new).extend(new);
-        self.query = self.query.normalize();
-        self
+        self.map_query(|q| q.ids_in(iter))
     }
     /// Load record ID selectors from a newline-delimited file and apply them as
     /// an [`ids`](ScanPlan::ids) filter.
@@ -75,8 +68,15 @@ impl ScanPlan {
     /// ignored. Inline comments are not stripped. The returned `Result` covers
     /// file I/O and UTF-8/line-length errors; blank or duplicate IDs are still
     /// reported by [`ScanPlan::build`] before scanning starts.
-    pub fn ids_file(self, path: impl AsRef<Path>) -> Result<Self> {
+    pub fn ids_file(self, path: impl AsRef<Path>) -> Result<Self, RetlError> {
         let ids = read_record_ids_file(path.as_ref())?;
         Ok(self.ids_in(ids))
     }
+    /// When `yes`, keep only top-level comments: those whose `parent_id` is a
+    /// submission fullname (`t3_...`) rather than another comment (`t1_...`).
+    /// Comments with no `parent_id` are dropped. Submissions are always kept,
+    /// so pair with `Sources::Comments` for comments alone.
+    pub fn top_level_comments_only(self, yes: bool) -> Self {
+        self.map_query(|q| q.top_level_comments_only(yes))
+    }
 }
@@ -1,21 +1,39 @@
 impl ScanPlan {
     /// Add an arbitrary full-record JSON Pointer predicate.
-    pub fn json_predicate(mut self, predicate: JsonPointerPredicate) -> Self {
-        self.query.json_predicates.push(predicate);
-        self
+    pub fn json_predicate(self, predicate: JsonPointerPredicate) -> Self {
+        self.map_query(|q| q.json_predicate(predicate))
     }
     /// Add multiple arbitrary full-record JSON Pointer predicates.
-    pub fn json_predicates<I>(mut self, predicates: I) -> Self
+    pub fn json_predicates<I>(self, predicates: I) -> Self
     where
         I: IntoIterator<Item = JsonPointerPredicate>,
     {
-        self.query.json_predicates.extend(predicates);
-        self
+        self.map_query(|q| q.json_predicates(predicates))
+    }
+    /// Keep only records for which `f` returns true, for conditions no
+    /// builder covers (e.g. a `score` to `num_comments` ratio). It runs on the
+    /// parsed record after the built-in filters, so every record in the
+    /// planned files is fully parsed, and applies to counts and exports
+    /// alike. Setting it again replaces the earlier closure.
+    ///
+    /// A closure cannot be fingerprinted, so resume only knows whether a
+    /// filter is set: after changing what `f` keeps, rerun without resume.
+    pub fn filter(self, f: Arc<dyn Fn(&serde_json::Value) -> bool + Send + Sync>) -> Self {
+        self.map_query(|q| q.filter(f))
     }
     /// Keep records where `pointer` exists, including JSON `null` values.
     pub fn json_exists(self, pointer: impl Into<String>) -> Self {
         self.json_predicate(JsonPointerPredicate::exists(pointer))
     }
+    /// Keep records that carry the field at `pointer`. Alias for
+    /// [`ScanPlan::json_exists`]; a present JSON `null` counts as present.
+    pub fn has_field(self, pointer: impl Into<String>) -> Self {
+        self.json_exists(pointer)
+    }
+    /// Keep records where `pointer` does not resolve to any value.
+    pub fn missing_field(self, pointer: impl Into<String>) -> Self {
+        self.json_predicate(JsonPointerPredicate::missing(pointer))
+    }
     /// Keep records where `pointer` equals a scalar JSON value.
     pub fn json_eq(self, pointer: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
         self.json_predicate(JsonPointerPredicate::equals(pointer, value))
@@ -7,14 +7,131 @@ impl ScanPlan {
         self.limit = Some(n);
         self
     }
-    pub fn include_pseudo_users(mut self) -> Self {
-        self.query.filter_pseudo_users = false;
+    /// Stop writing once about `bytes` of uncompressed output have been
+    /// written, then finalize the output as usual. Like [`ScanPlan::limit`]
+    /// the cap is soft: each concurrent worker may finish the record it is
+    /// writing past it. See
+    /// [`ETLOptions::max_output_bytes`](crate::ETLOptions::max_output_bytes).
+    pub fn max_output_bytes(mut self, bytes: u64) -> Self {
+        self.etl = self.etl.max_output_bytes(bytes);
         self
     }
+    /// Annotate each record written by [`ScanPlan::attach_parents_zst`] with
+    /// its reply `depth`: 0 for a submission, 1 for a top-level comment, 2
+    /// for a reply to it, and so on.
+    ///
+    /// Depth is walked through the resolved parent maps, which must be
+    /// resolved with
+    /// [`RedditETL::parent_reply_links`](crate::RedditETL::parent_reply_links)
+    /// so they record each resolved comment parent's own `parent_id`. It is
+    /// only known when every comment up the chain was resolved; otherwise
+    /// `depth` is `null`. Walks stop after [`DEFAULT_MAX_REPLY_DEPTH`] links (see
+    /// [`ScanPlan::annotate_depth_with_max`]). Other operations ignore this.
+    pub fn annotate_depth(self) -> Self {
+        self.annotate_depth_with_max(DEFAULT_MAX_REPLY_DEPTH)
+    }
+    /// Like [`ScanPlan::annotate_depth`], but follow at most `max_depth`
+    /// parent links per record (minimum 1). Deeper or cyclic chains get a
+    /// `null` depth and a warning.
+    pub fn annotate_depth_with_max(mut self, max_depth: usize) -> Self {
+        self.reply_depth_cap = Some(max_depth.max(1));
+        self
+    }
+    /// Drop records whose `key` (e.g. `KeyExtractor::json_pointer("/id")`)
+    /// an earlier record already had, so overlapping corpus slices export one
+    /// copy per key. Applies to [`ScanPlan::extract_to_jsonl`],
+    /// [`ScanPlan::extract_to_json`], [`ScanPlan::extract_to_parquet`],
+    /// [`ScanPlan::extract_spool_monthly`], [`ScanPlan::export_partitioned`]
+    /// and [`ScanPlan::export_partitioned_by`]; other operations ignore it.
+    ///
+    /// Output workers run concurrently, so "already emitted" cannot be
+    /// decided on the fly. The run makes two passes instead: the first reads
+    /// every planned file and shards each matching record's key and position
+    /// to disk under `work_dir`, so memory stays bounded by one shard rather
+    /// than the whole key set; the second writes all but the earliest copy of
+    /// each key (by month, then comments before submissions, then line).
+    /// Expect roughly twice the read time. Records without a key are kept.
+    pub fn dedupe_records_by(mut self, key: KeyExtractor) -> Self {
+        self.query.dedupe_records = Some(Arc::new(key));
+        self
+    }
+    /// Run `f` on every record just before it is written, after the
+    /// whitelist, timestamp and provenance transforms, to drop fields
+    /// conditionally, derive values or redact text. Filters still see the
+    /// original record. Every record takes the full-parse write path, and
+    /// CSV/TSV extracts select their columns from the transformed record.
+    ///
+    /// A closure cannot be fingerprinted, so resume only knows whether a
+    /// transform is set: after changing what `f` does, rerun without resume.
+    pub fn transform(mut self, f: Arc<dyn Fn(&mut serde_json::Value) + Send + Sync>) -> Self {
+        self.query.transform = Some(RecordTransform(f));
+        self
+    }
+    /// Give the written `edited` field one type for schemas that cannot
+    /// take Reddit's mix of `false` and timestamps; see [`EditedMode`].
+    /// Any mode other than [`EditedMode::Leave`] sends every record down the
+    /// full-parse write path. The rewrite runs before
+    /// [`RedditETL::timestamps_human_readable`] and [`ScanPlan::transform`],
+    /// and CSV/TSV extracts write the rewritten value; filters still see the
+    /// original value.
+    pub fn normalize_edited(mut self, mode: EditedMode) -> Self {
+        self.query.normalize_edited = mode;
+        self
+    }
+    /// Write the `subreddit` field lowercased (`AskScience` becomes
+    /// `askscience`), the form subreddit filters and per-subreddit counts
+    /// already use, so one subreddit is spelled one way across the output.
+    /// Off by default: records keep the casing found in the dump. Turning it
+    /// on sends every record down the full-parse write path, and CSV/TSV
+    /// extracts write the lowercased name too; filters still see the
+    /// original value.
+    pub fn canonicalize_subreddit_case(mut self, on: bool) -> Self {
+        self.query.canonicalize_subreddit_case = on;
+        self
+    }
+    /// Thin the output toward `comments : submissions` matched records, for
+    /// balanced datasets under `Sources::Both` where comments vastly
+    /// outnumber submissions.
+    ///
+    /// Before the run, one extra pass counts the records each kind matches;
+    /// the more plentiful kind is then sampled down and the scarcer kept
+    /// whole. Sampling hashes each record's `id`, so the same corpus and
+    /// query keep the same records every time, and the output ratio is only
+    /// approximate (close for large counts). A thinned kind drops records
+    /// without an `id`. `(1, 0)` or `(0, 1)` keeps one kind only; `(0, 0)`
+    /// is rejected by [`ScanPlan::build`]. `.limit()` applies after the
+    /// sampling.
+    pub fn source_ratio(mut self, comments: u32, submissions: u32) -> Self {
+        self.query.source_ratio = Some(SourceRatio::new(comments, submissions));
+        self
+    }
+    pub fn include_pseudo_users(self) -> Self {
+        self.map_query(QuerySpec::include_pseudo_users)
+    }
     #[deprecated(note = "use include_pseudo_users()")]
     pub fn allow_pseudo_users(self) -> Self {
         self.include_pseudo_users()
     }
+    /// See [`RedditETL::year`].
+    pub fn year(mut self, year: u16) -> Self {
+        self.etl = self.etl.year(year);
+        self
+    }
+    /// See [`RedditETL::years`].
+    pub fn years(mut self, years: std::ops::RangeInclusive<u16>) -> Self {
+        self.etl = self.etl.years(years);
+        self
+    }
+    /// See [`RedditETL::since`].
+    pub fn since(mut self, start: YearMonth) -> Self {
+        self.etl = self.etl.since(start);
+        self
+    }
+    /// See [`RedditETL::until`].
+    pub fn until(mut self, end: YearMonth) -> Self {
+        self.etl = self.etl.until(end);
+        self
+    }
     pub fn whitelist_fields<I, S>(mut self, fields: I) -> Self
     where
         I: IntoIterator<Item = S>,
@@ -28,6 +145,23 @@ impl ScanPlan {
         self.etl = self.etl.strict_whitelist(yes);
         self
     }
+    /// Write records with object keys sorted recursively, so two records with
+    /// the same content but different key order produce identical bytes.
+    /// Every record takes the full-parse write path; see
+    /// [`ETLOptions::canonicalize_json`](crate::ETLOptions::canonicalize_json).
+    pub fn canonicalize_json(mut self) -> Self {
+        self.etl = self.etl.canonicalize_json(true);
+        self
+    }
+    /// Add `_src_file` (e.g. `RC_2006-01`) and `_src_line` (1-based line in
+    /// that file) to every written record, so any output line can be traced
+    /// back to the dump it came from. Every record takes the full-parse write
+    /// path; see
+    /// [`ETLOptions::add_provenance`](crate::ETLOptions::add_provenance).
+    pub fn add_provenance(mut self) -> Self {
+        self.etl = self.etl.add_provenance(true);
+        self
+    }
     pub fn strict_key(mut self, yes: bool) -> Self {
         self.etl = self.etl.strict_key(yes);
         self
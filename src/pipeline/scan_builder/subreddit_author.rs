@@ -1,42 +1,65 @@
 impl ScanPlan {
-    pub fn subreddit(mut self, s: impl AsRef<str>) -> Self {
-        self.query.subreddits = Some(vec![normalize_str(s.as_ref())]);
-        self
+    pub fn subreddit(self, s: impl AsRef<str>) -> Self {
+        self.map_query(|q| q.subreddit(s))
     }
     pub fn subreddits<I, S>(self, iter: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.subreddits = Some(v), iter, normalize_str)
+        self.map_query(|q| q.subreddits(iter))
     }
-    pub fn author(mut self, author: impl AsRef<str>) -> Self {
-        self.query.authors_in = Some(vec![normalize_str(author.as_ref())]);
-        self.query = self.query.normalize();
-        self
+    /// Reject records from these subreddits (normalized like
+    /// [`ScanPlan::subreddits`]). Works with or without an allow-list; a
+    /// subreddit named in both is excluded.
+    pub fn subreddits_out<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.map_query(|q| q.subreddits_out(iter))
+    }
+    /// Alias for subreddits_out: exclude the provided subreddits (normalized).
+    pub fn exclude_subreddits<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.subreddits_out(iter)
+    }
+    /// Keep every record from a deterministic `fraction` of subreddits and
+    /// none from the rest, instead of sampling individual records.
+    ///
+    /// Subreddits are chosen by hashing the (case-insensitive) name with
+    /// `seed`, so the same arguments select the same communities on every run.
+    /// Composes with the allow/deny lists, which are applied first. A
+    /// `fraction` outside `0.0..=1.0` is rejected by [`ScanPlan::build`].
+    pub fn sample_subreddits(self, fraction: f64, seed: u64) -> Self {
+        self.map_query(|q| q.sample_subreddits(fraction, seed))
+    }
+    pub fn author(self, author: impl AsRef<str>) -> Self {
+        self.map_query(|q| q.author(author))
     }
     pub fn authors<I, S>(self, iter: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.authors_in = Some(v), iter, normalize_str)
+        self.map_query(|q| q.authors(iter))
     }
     pub fn authors_in<I, S>(self, iter: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.authors_in = Some(v), iter, normalize_str)
+        self.map_query(|q| q.authors_in(iter))
     }
-    pub fn authors_out<I, S>(mut self, iter: I) -> Self
+    pub fn authors_out<I, S>(self, iter: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self = self.set_string_list(|q, v| q.authors_out = Some(v), iter, normalize_str);
-        self.query.authors_out_explicit = true;
-        self
+        self.map_query(|q| q.authors_out(iter))
     }
     /// Alias for authors_out: exclude the provided authors (normalized).
     pub fn exclude_authors<I, S>(self, iter: I) -> Self
@@ -51,21 +74,10 @@ impl ScanPlan {
     /// This composes with [`ScanPlan::authors_out`] / [`ScanPlan::exclude_authors`]
     /// regardless of call order; the actual merge happens in [`ScanPlan::build`]
     /// so explicit deny-list entries are never overwritten by the defaults.
-    pub fn exclude_common_bots(mut self) -> Self {
-        self.query.exclude_common_bots = true;
-        self
+    pub fn exclude_common_bots(self) -> Self {
+        self.map_query(QuerySpec::exclude_common_bots)
     }
-    pub fn author_regex<R: IntoAuthorRegex>(mut self, re: R) -> Self {
-        match re.into_author_regex() {
-            AuthorRegexInput::Compiled(re) => {
-                self.query.author_regex_pattern = Some(re.as_str().to_string());
-                self.query.author_regex = Some(re);
-            }
-            AuthorRegexInput::Pattern(pattern) => {
-                self.query.author_regex_pattern = Some(pattern);
-                self.query.author_regex = None;
-            }
-        }
-        self
+    pub fn author_regex<R: IntoAuthorRegex>(self, re: R) -> Self {
+        self.map_query(|q| q.author_regex(re))
     }
 }
@@ -5,7 +5,7 @@ impl ScanPlan {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.keywords_any = Some(v), iter, lowercase_str)
+        self.map_query(|q| q.keywords_any(iter))
     }
     /// Keep records only when every keyword appears across `body`, `selftext`, and `title`.
     pub fn keywords_all<I, S>(self, iter: I) -> Self
@@ -13,7 +13,7 @@ impl ScanPlan {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.keywords_all = Some(v), iter, lowercase_str)
+        self.map_query(|q| q.keywords_all(iter))
     }
     /// Reject records where any keyword appears in `body`, `selftext`, or `title`.
     pub fn exclude_keywords<I, S>(self, iter: I) -> Self
@@ -21,16 +21,27 @@ impl ScanPlan {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.keywords_exclude = Some(v), iter, lowercase_str)
+        self.map_query(|q| q.exclude_keywords(iter))
     }
     /// Keep records where `pattern` matches `body`, `selftext`, or `title`.
     ///
     /// The pattern uses Rust `regex` syntax and is compiled by [`ScanPlan::build`],
     /// so malformed patterns return [`QueryBuildError`] before scanning starts.
-    pub fn text_regex(mut self, pattern: impl Into<String>) -> Self {
-        self.query.text_regex_pattern = Some(pattern.into());
-        self.query.text_regex = None;
-        self
+    pub fn text_regex(self, pattern: impl Into<String>) -> Self {
+        self.map_query(|q| q.text_regex(pattern))
+    }
+    /// Reject comments and posts whose `body` or `selftext` carries a common
+    /// bot footer such as "I am a bot, and this action was performed
+    /// automatically", catching bots that vary their username. Complements
+    /// [`ScanPlan::exclude_common_bots`]. The default patterns are
+    /// conservative; add your own with [`ScanPlan::exclude_body_regex`].
+    pub fn exclude_bot_bodies(self) -> Self {
+        self.map_query(QuerySpec::exclude_bot_bodies)
+    }
+    /// Reject records whose `body` or `selftext` matches `re`. Repeated calls
+    /// add patterns.
+    pub fn exclude_body_regex(self, re: regex::Regex) -> Self {
+        self.map_query(|q| q.exclude_body_regex(re))
     }
     /// Restrict to submissions whose top-level `domain` field matches one of
     /// the provided domains (case-insensitive).
@@ -43,21 +54,19 @@ impl ScanPlan {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.set_string_list(|q, v| q.domains_in = Some(v), iter, lowercase_str)
+        self.map_query(|q| q.domains_in(iter))
     }
     /// Keep only records that contain an HTTP(S) URL when `yes` is true.
     ///
     /// Passing `false` clears/disables the positive URL filter. Use
     /// [`ScanPlan::no_url`] for the negative URL predicate.
-    pub fn contains_url(mut self, yes: bool) -> Self {
-        self.query.contains_url = yes.then_some(true);
-        self
+    pub fn contains_url(self, yes: bool) -> Self {
+        self.map_query(|q| q.contains_url(yes))
     }
     /// Keep only records without an HTTP(S) URL in text and without an outbound
     /// link-submission URL.
-    pub fn no_url(mut self) -> Self {
-        self.query.no_url = true;
-        self
+    pub fn no_url(self) -> Self {
+        self.map_query(QuerySpec::no_url)
     }
     /// Alias for [`ScanPlan::no_url`].
     pub fn without_url(self) -> Self {
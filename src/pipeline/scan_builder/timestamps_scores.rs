@@ -1,11 +1,9 @@
 impl ScanPlan {
-    pub fn min_score(mut self, v: i64) -> Self {
-        self.query.min_score = Some(v);
-        self
+    pub fn min_score(self, v: i64) -> Self {
+        self.map_query(|q| q.min_score(v))
     }
-    pub fn max_score(mut self, v: i64) -> Self {
-        self.query.max_score = Some(v);
-        self
+    pub fn max_score(self, v: i64) -> Self {
+        self.map_query(|q| q.max_score(v))
     }
     /// Set an inclusive lower bound for the top-level `created_utc` Unix timestamp.
     ///
@@ -13,9 +11,8 @@ impl ScanPlan {
     /// full JSON parse. Records whose `created_utc` is missing or non-numeric
     /// are rejected when any timestamp bound is active; string- and
     /// float-encoded numbers (`"1700000000"`, `1.7e9`) are coerced.
-    pub fn created_utc_gte(mut self, ts: i64) -> Self {
-        self.query.timestamp_bounds.created_utc_gte = Some(ts);
-        self
+    pub fn created_utc_gte(self, ts: i64) -> Self {
+        self.map_query(|q| q.created_utc_gte(ts))
     }
     /// Set an exclusive upper bound for the top-level `created_utc` Unix timestamp.
     ///
@@ -23,18 +20,16 @@ impl ScanPlan {
     /// full JSON parse. Records whose `created_utc` is missing or non-numeric
     /// are rejected when any timestamp bound is active; string- and
     /// float-encoded numbers (`"1700000000"`, `1.7e9`) are coerced.
-    pub fn created_utc_lt(mut self, ts: i64) -> Self {
-        self.query.timestamp_bounds.created_utc_lt = Some(ts);
-        self
+    pub fn created_utc_lt(self, ts: i64) -> Self {
+        self.map_query(|q| q.created_utc_lt(ts))
     }
     /// Set exact `created_utc` bounds (`>= created_utc_gte`, `< created_utc_lt`).
     pub fn timestamp_bounds(
-        mut self,
+        self,
         created_utc_gte: Option<i64>,
         created_utc_lt: Option<i64>,
     ) -> Self {
-        self.query.timestamp_bounds = TimestampBounds::new(created_utc_gte, created_utc_lt);
-        self
+        self.map_query(|q| q.timestamp_bounds(created_utc_gte, created_utc_lt))
     }
     /// Alias for [`ScanPlan::created_utc_gte`].
     pub fn after(self, ts: i64) -> Self {
@@ -44,4 +39,14 @@ impl ScanPlan {
     pub fn before(self, ts: i64) -> Self {
         self.created_utc_lt(ts)
     }
+    /// Choose which record timestamp the time filters read: the
+    /// `created_utc_*` bounds, the `start`/`end` month gate, and
+    /// [`ScanPlan::count_by_month`]. Defaults to [`TimeField::Created`].
+    ///
+    /// Monthly files are still selected by creation month, so `start`/`end`
+    /// also limit which files are read; see [`TimeField`] for how timestamp
+    /// bounds on other fields plan files.
+    pub fn time_field(self, field: TimeField) -> Self {
+        self.map_query(|q| q.time_field(field))
+    }
 }
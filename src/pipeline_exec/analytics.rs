@@ -1,11 +1,18 @@
-// `ScanPlan` analytics outputs: month histograms, author counts, and the
-// first-seen index TSV. These three live together because they share the
-// same `scan_records` / checkpoint replay shape and emit small summary
-// outputs rather than full-record exports.
+// `ScanPlan` analytics outputs: month histograms, distinct authors per
+// month, distinct subreddits, the field inventory, per-author count/karma/max-score TSVs, and
+// the first-seen index TSV.
+// These live together because they all consume matches through
+// `ScanPlan::for_each_matched` (a `scan_records` pass, or a checkpoint replay
+// under `resume`) and emit small summary outputs rather than full-record
+// exports.
 
 impl ScanPlan {
     /// Count matched records per calendar month.
     ///
+    /// Months are taken from the query's [`TimeField`](crate::TimeField)
+    /// (`created_utc` unless [`ScanPlan::time_field`] says otherwise); records
+    /// without that timestamp are not counted.
+    ///
     /// **Run-manifest exemption (deliberate).** Unlike its analytics siblings
     /// [`ScanPlan::author_counts_to_tsv`] and
     /// [`ScanPlan::build_first_seen_index_to_tsv`], this method does **not**
@@ -22,111 +29,322 @@ impl ScanPlan {
     ///   which emits a loud end-of-run `tracing::warn!` summarizing any month
     ///   skipped by `--allow-partial` — so a `--resume --allow-partial` count
     ///   that silently dropped a month is still visible to a watcher.
-    pub fn count_by_month(self) -> Result<BTreeMap<YearMonth, u64>> {
-        let plan = self.build()?;
+    pub fn count_by_month(self) -> Result<BTreeMap<YearMonth, u64>, RetlError> {
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let parallelism = plan.etl.opts.parallelism;
+        let time_field = plan.query.time_field;
         with_thread_pool(parallelism, || {
             let total = Mutex::new(BTreeMap::<YearMonth, u64>::new());
-            if plan.etl.opts.resume {
-                let checkpoint = materialize_scan_checkpoint(
-                    &plan.etl,
-                    &plan.query,
-                    /*show_progress=*/ true,
-                    plan.limit,
-                )?;
-                for_each_checkpoint_record(
-                    &checkpoint.parts,
-                    plan.etl.opts.read_buffer_bytes,
-                    |min, _line| {
-                        if let Some(ts) = min.created_utc {
-                            let ym = ym_from_epoch(ts);
-                            *total.lock().unwrap().entry(ym).or_insert(0) += 1;
-                        }
-                        Ok(())
-                    },
-                )?;
-            } else {
-                scan_records(
-                    &plan.etl,
-                    &plan.query,
-                    /*show_progress=*/ true,
-                    plan.limit,
-                    |min, _kind, _line| {
-                        if let Some(ts) = min.created_utc {
-                            let ym = ym_from_epoch(ts);
-                            *total.lock().unwrap().entry(ym).or_insert(0) += 1;
-                        }
-                        Ok(())
-                    },
-                )?;
-            }
+            plan.for_each_matched(|min, _line| {
+                if let Some(ts) = min.timestamp(time_field) {
+                    let ym = ym_from_epoch(ts);
+                    *total.lock().unwrap().entry(ym).or_insert(0) += 1;
+                }
+                Ok(())
+            })?;
             Ok(total.into_inner().unwrap())
         })
     }
 
-    pub fn author_counts_to_tsv(self, out_path: &Path) -> Result<()> {
-        let plan = self.build()?;
+    /// Count distinct authors per calendar month ("monthly active users").
+    ///
+    /// Months come from the query's [`TimeField`](crate::TimeField) as in
+    /// [`ScanPlan::count_by_month`]; authors are compared after
+    /// [`normalize_author`](crate::normalize_author) (so `u/Alice` and `alice`
+    /// are one author) and empty authors are skipped. Exact: each
+    /// `(month, author)` pair is written to disk shards keyed by author, every
+    /// shard is deduplicated, and the surviving pairs are counted per month,
+    /// so memory stays bounded by one shard. See
+    /// [`ScanPlan::distinct_authors_by_month_approx`] for a single-pass
+    /// estimate. Shares `count_by_month`'s run-manifest exemption.
+    pub fn distinct_authors_by_month(self) -> Result<BTreeMap<YearMonth, u64>, RetlError> {
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let parallelism = plan.etl.opts.parallelism;
+        let time_field = plan.query.time_field;
+        Ok(with_thread_pool(parallelism, || {
+            let work_dir = plan.etl.ensure_work_dir()?;
+            let scratch = crate::util::unique_scratch_dir(&work_dir, "distinct_authors", "shards");
+            crate::util::create_dir_all_with_default_backoff(&scratch).with_context(|| {
+                format!("creating distinct-author work dir {}", scratch.display())
+            })?;
+
+            let result = (|| -> Result<BTreeMap<YearMonth, u64>> {
+                let opts = &plan.etl.opts;
+                let shard_count = opts.resolved_shard_count();
+                let mut shards = if opts.in_memory_shards {
+                    create_memory_line_shards(
+                        &scratch,
+                        shard_count,
+                        distinct_author_shard_file_name,
+                        opts.in_memory_shard_spill_bytes,
+                    )
+                } else {
+                    create_line_shard_writers(
+                        &scratch,
+                        shard_count,
+                        distinct_author_shard_file_name,
+                        "distinct-author shard",
+                    )?
+                };
+                shards.set_flush_every_bytes(opts.shard_flush_bytes);
+                let state = seeded_state("distinct_authors");
+                plan.for_each_matched(|min, _line| {
+                    let Some((ym, author)) = month_author(min, time_field) else {
+                        return Ok(());
+                    };
+                    shards.write_parts(
+                        shard_index(&state, &author, shard_count),
+                        &[ym.to_string().as_bytes(), b"\t", author.as_bytes(), b"\n"],
+                    )
+                })?;
+
+                // An author's pairs all land in one shard, so per-shard month
+                // counts of the deduped pairs simply add up.
+                let per_shard = shards
+                    .finish()?
+                    .par_iter()
+                    .map(|contents| count_month_author_pairs(&unique_shard_line_vec(contents)?))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut total = BTreeMap::<YearMonth, u64>::new();
+                for counts in per_shard {
+                    for (ym, n) in counts {
+                        *total.entry(ym).or_insert(0) += n;
+                    }
+                }
+                Ok(total)
+            })();
+            cleanup_scratch_dir(&scratch, "distinct_authors");
+            result
+        })?)
+    }
+
+    /// Approximate [`ScanPlan::distinct_authors_by_month`]: one
+    /// [`HllState`](crate::HllState) HyperLogLog sketch per month instead of
+    /// on-disk shards. A single pass with no scratch files and 4 KiB of
+    /// memory per month; each count is an estimate with ~1.6 % relative
+    /// error (small months are counted almost exactly).
+    pub fn distinct_authors_by_month_approx(self) -> Result<BTreeMap<YearMonth, u64>, RetlError> {
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let parallelism = plan.etl.opts.parallelism;
+        let time_field = plan.query.time_field;
+        with_thread_pool(parallelism, || {
+            let sketches = PerThread::new(BTreeMap::<YearMonth, HllState>::new);
+            let hasher = seeded_state("usernames");
+            let observe = |min: &MinimalRecord| -> Result<()> {
+                if let Some((ym, a)) = month_author(min, time_field) {
+                    let h = hasher.hash_one(&a);
+                    sketches
+                        .with(|months| months.entry(ym).or_insert_with(HllState::new).add_hash(h));
+                }
+                Ok(())
+            };
+            plan.for_each_matched(|min, _line| observe(min))?;
+            let mut merged = BTreeMap::<YearMonth, HllState>::new();
+            for months in sketches.into_inner() {
+                for (ym, hll) in months {
+                    merged
+                        .entry(ym)
+                        .and_modify(|m| m.merge(&hll))
+                        .or_insert(hll);
+                }
+            }
+            Ok(merged
+                .into_iter()
+                .map(|(ym, hll)| (ym, hll.estimate()))
+                .collect())
+        })
+    }
+
+    /// The distinct subreddits of the matched records, lowercased and sorted:
+    /// the subreddit analog of [`ScanPlan::usernames`], e.g. to see which
+    /// communities a broad keyword scan touched. Names are deduplicated
+    /// through on-disk shards, so only the final list is held in memory.
+    /// Shares `count_by_month`'s run-manifest exemption.
+    pub fn distinct_subreddits(self) -> Result<Vec<String>, RetlError> {
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let parallelism = plan.etl.opts.parallelism;
+        Ok(with_thread_pool(parallelism, || {
+            let work_dir = plan.etl.ensure_work_dir()?;
+            let shard_writer = name_shard_writer(&plan.etl.opts, &work_dir, "distinct_subreddits")?;
+            let scratch_root = shard_writer.scratch_root().to_path_buf();
+
+            let scanned =
+                plan.for_each_matched(|min, _line| match min.subreddit.as_deref().map(str::trim) {
+                    Some(sub) if !sub.is_empty() => shard_writer.write(&sub.to_lowercase()),
+                    _ => Ok(()),
+                });
+            if let Err(e) = scanned {
+                cleanup_scratch_dir(&scratch_root, "distinct_subreddits");
+                return Err(e);
+            }
+            // Removes the scratch dir itself, on success and failure alike.
+            shard_writer.into_sorted_vec_parallel()
+        })?)
+    }
+
+    /// Approximate score quantiles of the matched records, one per entry of
+    /// `qs` (each in `[0.0, 1.0]`, e.g. `&[0.5, 0.9, 0.99]` for median, p90
+    /// and p99), rounded to the nearest integer score. Records without a
+    /// numeric `score` are skipped.
+    ///
+    /// Each scan worker feeds a t-digest and the digests are merged at the
+    /// end, so memory stays at a few KiB however many records match. The
+    /// error is in rank: an estimate is typically within ~0.5 % of the
+    /// requested rank near the median and much closer at the tails, while
+    /// `0.0` and `1.0` return the exact minimum and maximum. Errors when no
+    /// matched record has a score.
+    pub fn score_quantiles(self, qs: &[f64]) -> Result<Vec<i64>, RetlError> {
+        if let Some(q) = qs.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+            return Err(anyhow!("score_quantiles: quantile {q} must be in [0.0, 1.0]").into());
+        }
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let parallelism = plan.etl.opts.parallelism;
+        Ok(with_thread_pool(parallelism, || {
+            let digests = PerThread::new(|| TDigest::new(QUANTILE_COMPRESSION));
+            let observe = |min: &MinimalRecord| -> Result<()> {
+                if let Some(score) = min.score {
+                    digests.with(|digest| digest.add(score as f64));
+                }
+                Ok(())
+            };
+            plan.for_each_matched(|min, _line| observe(min))?;
+
+            let mut merged = TDigest::new(QUANTILE_COMPRESSION);
+            for digest in digests.into_inner() {
+                merged.merge(digest);
+            }
+            if merged.is_empty() {
+                anyhow::bail!("score_quantiles: no matched record has a numeric score");
+            }
+            Ok(qs
+                .iter()
+                .map(|&q| merged.quantile(q).map_or(0, |v| v.round() as i64))
+                .collect())
+        })?)
+    }
+
+    /// Count how many matched records carry each top-level key, across
+    /// comments and submissions alike, to document an unfamiliar corpus slice
+    /// empirically. Every matched record is fully parsed; see
+    /// [`ScanPlan::field_inventory_sampled`] to bound that cost.
+    pub fn field_inventory(self) -> Result<BTreeMap<String, u64>, RetlError> {
+        self.field_inventory_sampled(1.0)
+    }
+
+    /// Like [`ScanPlan::field_inventory`], but only parses a deterministic
+    /// `sample_fraction` (in `(0.0, 1.0]`) of the matched records, chosen by a
+    /// hash of each record's line so reruns inventory the same records.
+    /// Counts are over the sampled records, not scaled up.
+    pub fn field_inventory_sampled(
+        self,
+        sample_fraction: f64,
+    ) -> Result<BTreeMap<String, u64>, RetlError> {
+        if !(sample_fraction > 0.0 && sample_fraction <= 1.0) {
+            return Err(anyhow!(
+                "field_inventory sample_fraction ({sample_fraction}) must be in (0.0, 1.0]"
+            )
+            .into());
+        }
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let parallelism = plan.etl.opts.parallelism;
+        with_thread_pool(parallelism, || {
+            let counts = PerThread::new(BTreeMap::<String, u64>::new);
+            let observe = |line: &str| -> Result<()> {
+                if !line_in_sample(line, sample_fraction) {
+                    return Ok(());
+                }
+                let keys: HashMap<String, serde::de::IgnoredAny> = serde_json::from_str(line)
+                    .context("field_inventory: matched record is not a JSON object")?;
+                counts.with(|counts| {
+                    for key in keys.into_keys() {
+                        *counts.entry(key).or_insert(0) += 1;
+                    }
+                });
+                Ok(())
+            };
+            plan.for_each_matched(|_min, line| observe(line))?;
+            let mut total = BTreeMap::<String, u64>::new();
+            for counts in counts.into_inner() {
+                for (key, n) in counts {
+                    *total.entry(key).or_insert(0) += n;
+                }
+            }
+            Ok(total)
+        })
+    }
+
+    /// Write `author\tcount` rows: matched records per author. Authors here
+    /// and in the other `author_*_to_tsv` outputs are keyed by
+    /// [`normalize_author`](crate::normalize_author), as in
+    /// [`ScanPlan::distinct_authors_by_month`].
+    pub fn author_counts_to_tsv(self, out_path: &Path) -> Result<(), RetlError> {
+        Ok(self.author_metric_to_tsv(out_path, AuthorMetric::Count)?)
+    }
+
+    /// Write `author\tkarma` rows: the sum of `score` over each author's
+    /// matched records. Sums can be negative; records without a numeric
+    /// `score` are skipped. Totals saturate at the `i64` bounds (see
+    /// [`ShardedKVWriter::reduce_sum`]).
+    pub fn author_karma_to_tsv(self, out_path: &Path) -> Result<(), RetlError> {
+        Ok(self.author_metric_to_tsv(out_path, AuthorMetric::ScoreSum)?)
+    }
+
+    /// Write `author\tmax_score` rows: each author's highest single-record
+    /// `score` among matched records. Records without a numeric `score` are
+    /// skipped, so authors with no scored record are absent.
+    pub fn author_max_score_to_tsv(self, out_path: &Path) -> Result<(), RetlError> {
+        Ok(self.author_metric_to_tsv(out_path, AuthorMetric::ScoreMax)?)
+    }
+
+    fn author_metric_to_tsv(self, out_path: &Path, metric: AuthorMetric) -> Result<()> {
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let parallelism = plan.etl.opts.parallelism;
+        let prefix = metric.scratch_prefix();
         with_thread_pool(parallelism, || {
             let manifest_start = RunManifestStart::now();
             let files = plan_pipeline_files(&plan.etl, Some(&plan.query))?;
             let work_dir = plan.etl.ensure_work_dir()?;
-            let kv =
-                ShardedKVWriter::create(&work_dir, "author_counts", plan.etl.opts.shard_count)?;
+            let shards = plan.etl.opts.resolved_shard_count();
+            let kv = ShardedKVWriter::create(&work_dir, prefix, shards)?
+                .with_flush_every_bytes(plan.etl.opts.shard_flush_bytes)
+                .with_intermediate_format(plan.etl.opts.intermediate_format);
             let scratch_root = kv.scratch_root().to_path_buf();
 
             let result = (|| -> Result<()> {
                 let matched_records = AtomicU64::new(0);
-                if plan.etl.opts.resume {
-                    let checkpoint = materialize_scan_checkpoint(
-                        &plan.etl,
-                        &plan.query,
-                        /*show_progress=*/ true,
-                        plan.limit,
-                    )?;
-                    for_each_checkpoint_record(
-                        &checkpoint.parts,
-                        plan.etl.opts.read_buffer_bytes,
-                        |min, _line| {
-                            if let Some(a) = min.author.as_deref() {
-                                let a = a.trim();
-                                if a.is_empty() {
-                                    return Ok(());
-                                }
-                                matched_records.fetch_add(1, Ordering::Relaxed);
-                                kv.write_kv(a, 1)?;
-                            }
-                            Ok(())
-                        },
-                    )?;
-                } else {
-                    scan_records(
-                        &plan.etl,
-                        &plan.query,
-                        /*show_progress=*/ true,
-                        plan.limit,
-                        |min, _kind, _line| {
-                            if let Some(a) = min.author.as_deref() {
-                                let a = a.trim();
-                                if a.is_empty() {
-                                    return Ok(());
-                                }
-                                matched_records.fetch_add(1, Ordering::Relaxed);
-                                kv.write_kv(a, 1)?;
-                            }
-                            Ok(())
-                        },
-                    )?;
-                }
+                let observe = |min: &MinimalRecord| -> Result<()> {
+                    let Some(a) = min.author.as_deref().map(normalize_author) else {
+                        return Ok(());
+                    };
+                    if a.is_empty() {
+                        return Ok(());
+                    }
+                    let Some(value) = metric.value(min) else {
+                        return Ok(());
+                    };
+                    matched_records.fetch_add(1, Ordering::Relaxed);
+                    kv.write_kv(&a, value)
+                };
+                plan.for_each_matched(|min, _line| observe(min))?;
 
-                let (shards, _scratch_root) = kv.reduce_sum_with_scratch("author_counts")?;
+                let (shards, _scratch_root) = match metric {
+                    AuthorMetric::Count | AuthorMetric::ScoreSum => {
+                        kv.reduce_sum_with_scratch(prefix)?
+                    }
+                    AuthorMetric::ScoreMax => kv.reduce_max_with_scratch(prefix)?,
+                };
                 concat_tsvs(&shards, out_path, plan.etl.opts.write_buffer_bytes)?;
                 let output_rows = count_text_lines(out_path)?;
                 let manifest = scan_manifest_input(
                     manifest_start,
-                    "scan.author_counts_to_tsv",
+                    metric.operation(),
                     "tsv",
                     &plan.etl,
                     &plan.query,
@@ -147,84 +365,130 @@ impl ScanPlan {
                 )?;
                 Ok(())
             })();
-            cleanup_scratch_dir(&scratch_root, "author_counts");
+            cleanup_scratch_dir(&scratch_root, prefix);
             result
         })
     }
 
-    pub fn build_first_seen_index_to_tsv(self, out_path: &Path) -> Result<()> {
-        let plan = self.build()?;
+    pub fn build_first_seen_index_to_tsv(self, out_path: &Path) -> Result<(), RetlError> {
+        Ok(self.first_seen_to_tsv(out_path, false, None)?)
+    }
+
+    /// Fold this scan into an existing first-seen index instead of
+    /// rebuilding it: `existing_tsv` is a prior
+    /// [`build_first_seen_index_to_tsv`](Self::build_first_seen_index_to_tsv)
+    /// output (`author\tearliest_created_utc`), and `out_tsv` receives every
+    /// author from either side with the smaller timestamp, reduced with the
+    /// same min semantics as the full build. Point the scan at just the new
+    /// months (e.g. `.date_range(...)` on the new range) to keep the index
+    /// current without rescanning history.
+    ///
+    /// `out_tsv` may be `existing_tsv`: the index is read before the output
+    /// is atomically replaced. Rows that are not `author\tinteger` (such as
+    /// a detailed index) are rejected.
+    pub fn update_first_seen_index(
+        self,
+        existing_tsv: &Path,
+        out_tsv: &Path,
+    ) -> Result<(), RetlError> {
+        Ok(self.first_seen_to_tsv(out_tsv, false, Some(existing_tsv))?)
+    }
+
+    /// Like [`build_first_seen_index_to_tsv`](Self::build_first_seen_index_to_tsv),
+    /// but each row also names where the author was first seen:
+    /// `author\tearliest_created_utc\tsubreddit\tid`. The subreddit and id come
+    /// from the same record as the timestamp; either is empty when that record
+    /// lacks it. Records sharing the earliest timestamp resolve to the smallest
+    /// `subreddit\tid`, so the output is stable across runs.
+    pub fn build_first_seen_detailed_to_tsv(self, out_path: &Path) -> Result<(), RetlError> {
+        Ok(self.first_seen_to_tsv(out_path, true, None)?)
+    }
+
+    fn first_seen_to_tsv(
+        self,
+        out_path: &Path,
+        detailed: bool,
+        existing: Option<&Path>,
+    ) -> Result<()> {
+        let operation = match (detailed, existing) {
+            (true, _) => "scan.build_first_seen_detailed_to_tsv",
+            (false, Some(_)) => "scan.update_first_seen_index",
+            (false, None) => "scan.build_first_seen_index_to_tsv",
+        };
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let parallelism = plan.etl.opts.parallelism;
         with_thread_pool(parallelism, || {
             let manifest_start = RunManifestStart::now();
             let files = plan_pipeline_files(&plan.etl, Some(&plan.query))?;
             let work_dir = plan.etl.ensure_work_dir()?;
-            let kv = ShardedKVWriter::create(&work_dir, "first_seen", plan.etl.opts.shard_count)?;
+            let shards = plan.etl.opts.resolved_shard_count();
+            let kv = ShardedKVWriter::create(&work_dir, "first_seen", shards)?
+                .with_flush_every_bytes(plan.etl.opts.shard_flush_bytes)
+                .with_intermediate_format(plan.etl.opts.intermediate_format);
             let scratch_root = kv.scratch_root().to_path_buf();
 
             let result = (|| -> Result<()> {
+                // Seed the shards with the prior index first, so a bad index
+                // fails before the scan.
+                let existing_rows = match existing {
+                    Some(path) => seed_first_seen_index(&kv, path)?,
+                    None => 0,
+                };
                 let matched_records = AtomicU64::new(0);
-                if plan.etl.opts.resume {
-                    let checkpoint = materialize_scan_checkpoint(
-                        &plan.etl,
-                        &plan.query,
-                        /*show_progress=*/ true,
-                        plan.limit,
-                    )?;
-                    for_each_checkpoint_record(
-                        &checkpoint.parts,
-                        plan.etl.opts.read_buffer_bytes,
-                        |min, _line| {
-                            if let (Some(a), Some(ts)) = (min.author.as_deref(), min.created_utc) {
-                                let a = a.trim();
-                                if a.is_empty() {
-                                    return Ok(());
-                                }
-                                matched_records.fetch_add(1, Ordering::Relaxed);
-                                kv.write_kv(a, ts)?;
-                            }
-                            Ok(())
-                        },
-                    )?;
-                } else {
-                    scan_records(
-                        &plan.etl,
-                        &plan.query,
-                        /*show_progress=*/ true,
-                        plan.limit,
-                        |min, _kind, _line| {
-                            if let (Some(a), Some(ts)) = (min.author.as_deref(), min.created_utc) {
-                                let a = a.trim();
-                                if a.is_empty() {
-                                    return Ok(());
-                                }
-                                matched_records.fetch_add(1, Ordering::Relaxed);
-                                kv.write_kv(a, ts)?;
-                            }
-                            Ok(())
-                        },
-                    )?;
-                }
+                let observe = |min: &MinimalRecord| -> Result<()> {
+                    let (Some(a), Some(ts)) = (min.author.as_deref(), min.created_utc) else {
+                        return Ok(());
+                    };
+                    let a = a.trim();
+                    if a.is_empty() {
+                        return Ok(());
+                    }
+                    matched_records.fetch_add(1, Ordering::Relaxed);
+                    if detailed {
+                        let payload = format!(
+                            "{}\t{}",
+                            first_seen_field(min.subreddit.as_deref()),
+                            first_seen_field(min.id.as_deref())
+                        );
+                        kv.write_kv_payload(a, ts, &payload)
+                    } else {
+                        kv.write_kv(a, ts)
+                    }
+                };
+                plan.for_each_matched(|min, _line| observe(min))?;
 
-                let (shards, _scratch_root) = kv.reduce_min_with_scratch("first_seen")?;
+                let (shards, _scratch_root) = if detailed {
+                    kv.reduce_argmin_with_scratch("first_seen")?
+                } else {
+                    kv.reduce_min_with_scratch("first_seen")?
+                };
                 concat_tsvs(&shards, out_path, plan.etl.opts.write_buffer_bytes)?;
                 let output_rows = count_text_lines(out_path)?;
+                let mut counts = vec![
+                    ("matched_records", matched_records.load(Ordering::Relaxed)),
+                    ("output_rows", output_rows),
+                ];
+                if existing.is_some() {
+                    counts.push(("existing_rows", existing_rows));
+                }
                 let manifest = scan_manifest_input(
                     manifest_start,
-                    "scan.build_first_seen_index_to_tsv",
+                    operation,
                     "tsv",
                     &plan.etl,
                     &plan.query,
                     &files,
                     plan.limit,
-                    manifest_counts(&[
-                        ("matched_records", matched_records.load(Ordering::Relaxed)),
-                        ("output_rows", output_rows),
-                    ]),
+                    manifest_counts(&counts),
                     None,
                     None,
-                    serde_json::json!({}),
+                    match existing {
+                        Some(path) => serde_json::json!({
+                            "existing_index": crate::run_manifest::path_to_stable_string(path),
+                        }),
+                        None => serde_json::json!({}),
+                    },
                 );
                 maybe_write_run_manifest(
                     plan.etl.opts.emit_manifest,
@@ -238,3 +502,135 @@ impl ScanPlan {
         })
     }
 }
+
+/// Write every `author\tts` row of a prior first-seen index into `kv`,
+/// returning the row count. Authors are stored escaped, so they are
+/// unescaped here and re-escaped identically by `write_kv`.
+fn seed_first_seen_index(kv: &ShardedKVWriter, path: &Path) -> Result<u64> {
+    use std::io::BufRead;
+
+    let file = crate::util::open_with_default_backoff(path)
+        .with_context(|| format!("open existing first-seen index {}", path.display()))?;
+    let mut rows = 0u64;
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line =
+            line.with_context(|| format!("read existing first-seen index {}", path.display()))?;
+        if line.is_empty() {
+            continue;
+        }
+        let (author, ts) = line
+            .split_once('\t')
+            .and_then(|(author, ts)| Some((author, ts.parse::<i64>().ok()?)))
+            .ok_or_else(|| {
+                anyhow!(
+                    "{}:{}: expected an `author\\ttimestamp` first-seen row, got {line:?}",
+                    path.display(),
+                    idx + 1
+                )
+            })?;
+        kv.write_kv(&crate::kv_shard::unescape_kv_key(author), ts)?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+/// A first-seen TSV column value: TAB/CR/LF would split the row, so they
+/// become spaces.
+fn first_seen_field(value: Option<&str>) -> String {
+    value.unwrap_or("").replace(['\t', '\n', '\r'], " ")
+}
+
+/// The `(month, normalized author)` a record contributes to the distinct-author
+/// counts, or `None` when it has no timestamp or no non-empty author.
+fn month_author(min: &MinimalRecord, time_field: TimeField) -> Option<(YearMonth, String)> {
+    let ts = min.timestamp(time_field)?;
+    let a = normalize_author(min.author.as_deref()?);
+    (!a.is_empty()).then(|| (ym_from_epoch(ts), a))
+}
+
+fn distinct_author_shard_file_name(idx: usize) -> String {
+    format!("distinct_authors_{idx:04}.tmp")
+}
+
+/// Count the distinct `YYYY-MM\tauthor` lines of one shard per month.
+fn count_month_author_pairs(pairs: &[String]) -> Result<BTreeMap<YearMonth, u64>> {
+    let mut counts = BTreeMap::<YearMonth, u64>::new();
+    for pair in pairs {
+        let ym = pair
+            .split_once('\t')
+            .and_then(|(ym, _)| ym.parse::<YearMonth>().ok())
+            .ok_or_else(|| anyhow!("malformed distinct-author pair {pair:?}"))?;
+        *counts.entry(ym).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// One accumulator per scan pool thread (plus one for callers outside the
+/// pool), so the per-record lock stays uncontended; the caller merges the
+/// slots once the scan ends.
+struct PerThread<T> {
+    slots: Vec<Mutex<T>>,
+}
+
+impl<T> PerThread<T> {
+    fn new(init: impl Fn() -> T) -> Self {
+        let slots = (0..rayon::current_num_threads() + 1)
+            .map(|_| Mutex::new(init()))
+            .collect();
+        Self { slots }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let n = self.slots.len();
+        let slot = rayon::current_thread_index().map_or(n - 1, |i| i % n);
+        f(&mut self.slots[slot].lock().unwrap())
+    }
+
+    fn into_inner(self) -> impl Iterator<Item = T> {
+        self.slots.into_iter().map(|m| m.into_inner().unwrap())
+    }
+}
+
+/// Whether `line` falls in a `fraction` sample of the hash range.
+fn line_in_sample(line: &str, fraction: f64) -> bool {
+    if fraction >= 1.0 {
+        return true;
+    }
+    crate::util::seeded_fraction_hash(b"", line.as_bytes()) < (fraction * u64::MAX as f64) as u64
+}
+
+/// Per-author value reduced by the `author_*_to_tsv` family.
+#[derive(Clone, Copy)]
+enum AuthorMetric {
+    /// `1` per record, summed.
+    Count,
+    /// `score`, summed.
+    ScoreSum,
+    /// `score`, maximum.
+    ScoreMax,
+}
+
+impl AuthorMetric {
+    fn scratch_prefix(self) -> &'static str {
+        match self {
+            AuthorMetric::Count => "author_counts",
+            AuthorMetric::ScoreSum => "author_karma",
+            AuthorMetric::ScoreMax => "author_max_score",
+        }
+    }
+
+    fn operation(self) -> &'static str {
+        match self {
+            AuthorMetric::Count => "scan.author_counts_to_tsv",
+            AuthorMetric::ScoreSum => "scan.author_karma_to_tsv",
+            AuthorMetric::ScoreMax => "scan.author_max_score_to_tsv",
+        }
+    }
+
+    fn value(self, min: &MinimalRecord) -> Option<i64> {
+        match self {
+            AuthorMetric::Count => Some(1),
+            AuthorMetric::ScoreSum | AuthorMetric::ScoreMax => min.score,
+        }
+    }
+}
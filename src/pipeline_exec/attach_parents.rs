@@ -0,0 +1,311 @@
+// Direct parent attachment: `ScanPlan::attach_parents_zst` streams matching
+// corpus records through `ParentAttachWriter` instead of spooling them first.
+// `ScanPlan::extract_with_parents` chains the four spooled stages instead.
+
+impl ScanPlan {
+    /// Attach resolved parents to matching records straight from the `.zst`
+    /// corpus, writing one `part_RC_YYYY-MM` / `part_RS_YYYY-MM` file per
+    /// planned month into `out_dir`.
+    ///
+    /// Equivalent to [`extract_spool_monthly`](Self::extract_spool_monthly)
+    /// followed by [`RedditETL::attach_parents_jsonls_parallel`], without the
+    /// intermediate spool: records take the same filter/whitelist/timestamp
+    /// path the spool writes, then get the same `parent` object. `format`
+    /// selects `.jsonl` ([`ExportFormat::Jsonl`]) or checksummed `.jsonl.zst`
+    /// ([`ExportFormat::Zst`], at `zst_level`) parts; other formats are
+    /// rejected. `parents` comes from
+    /// [`RedditETL::resolve_parent_maps`] as usual.
+    ///
+    /// With `resume`, a month whose published part and
+    /// `.parents-attach.json` sidecar still match the corpus file, the query,
+    /// and the parent cache is kept as-is. `.limit()` cannot be combined with
+    /// `resume`, since kept months would not count toward the limit.
+    ///
+    /// Returns the published part paths (sorted) and the summed attach stats.
+    pub fn attach_parents_zst(
+        self,
+        out_dir: &Path,
+        parents: &crate::parents::ParentMaps,
+        format: ExportFormat,
+        resume: bool,
+    ) -> Result<(Vec<PathBuf>, crate::parents::ParentAttachStats), RetlError> {
+        let (ext, operation, compressed) = match format {
+            ExportFormat::Jsonl => ("jsonl", "attach-parents-jsonl", false),
+            ExportFormat::Zst => ("jsonl.zst", "attach-parents-zst", true),
+            other => return Err(anyhow!(
+                "attach_parents_zst writes JSONL or zst parts; {other:?} is not supported"
+            ).into()),
+        };
+        let plan = self.build_for_scan()?;
+        // Depth changes every comment record, so it is part of the resume
+        // identity of each published part.
+        let operation = match plan.reply_depth_cap {
+            Some(max_depth) => format!("{operation}+depth-{max_depth}"),
+            None => operation.to_string(),
+        };
+        if resume && plan.limit.is_some() {
+            return Err(anyhow!(
+                "attach_parents_zst cannot combine .limit() with resume: months kept from a \
+                 prior run would not count toward the limit"
+            ).into());
+        }
+        if plan.reply_depth_cap.is_some() && !crate::parents::has_reply_links(parents) {
+            return Err(anyhow!(
+                "annotate_depth needs comment parent links: resolve the parent maps with \
+                 .parent_reply_links(true)"
+            ).into());
+        }
+        log_pseudo_user_filter(&plan.query);
+        validate_export_whitelist(&plan.etl)?;
+
+        with_thread_pool(plan.etl.opts.parallelism, || {
+            let manifest_start = RunManifestStart::now();
+            crate::util::create_dir_all_with_default_backoff(out_dir).with_context(|| {
+                format!("create parent attach output dir {}", out_dir.display())
+            })?;
+            let staging_dir = ensure_staging_dir(out_dir)?;
+            sweep_stale_inprogress(out_dir, true)?;
+
+            let targets =
+                resolve_target_subs_from(&plan.etl.opts.subreddit, &plan.query.subreddits);
+            let files = plan_pipeline_files(&plan.etl, Some(&plan.query))?;
+            warn_if_unfiltered_undated_query(&plan.etl, &plan.query, &files);
+
+            let pb = if plan.etl.opts.progress {
+                Some(make_stage_progress_bar(
+                    total_compressed_size(&files),
+                    plan.etl.opts.progress_label.as_deref(),
+                    plan.etl.opts.progress_stage.as_ref(),
+                ))
+            } else {
+                None
+            };
+
+            let whitelist = plan.etl.opts.whitelist_fields.clone();
+            let whitelist_tracker = whitelist.as_ref().map(|fields| {
+                Arc::new(WhitelistMatchTracker::new(
+                    plan.etl.opts.strict_whitelist,
+                    fields.iter().cloned(),
+                ))
+            });
+            let record_limit = record_limit_from(plan.limit, plan.etl.opts.max_output_bytes);
+            let stream_ctx = StreamJobCtx {
+                targets: targets.as_ref(),
+                whitelist: &whitelist,
+                pb: pb.as_ref(),
+                whitelist_tracker: whitelist_tracker.as_deref(),
+                record_limit: record_limit.as_deref(),
+                ..StreamJobCtx::from_options(&plan.etl.opts, &plan.query)
+            };
+            let (start, end) = plan.etl.planned_date_range()?;
+            let write_buf = plan.etl.opts.write_buffer_bytes;
+            let zst_level = plan.etl.opts.zst_level;
+
+            let indexed: Vec<(usize, &FileJob)> = files.iter().enumerate().collect();
+            let outputs = Mutex::new(vec![None; files.len()]);
+
+            crate::concurrency::for_each_file_limited(
+                &indexed,
+                plan.etl.opts.file_concurrency,
+                |(idx, job)| -> Result<()> {
+                    let prefix = match job.kind {
+                        FileKind::Comment => FILE_PREFIX_RC,
+                        FileKind::Submission => FILE_PREFIX_RS,
+                    };
+                    let out_path = out_dir.join(format!("{prefix}_{}.{ext}", job.ym));
+                    let scan = build_resume_fingerprint(
+                        &plan.etl,
+                        &plan.query,
+                        &operation,
+                        plan.limit,
+                        std::slice::from_ref(*job),
+                    )?;
+
+                    if resume
+                        && crate::parents::direct_attach_output_is_current(
+                            &staging_dir,
+                            &out_path,
+                            &job.path,
+                            parents,
+                            start,
+                            end,
+                            &scan,
+                            compressed,
+                        )?
+                    {
+                        if let Some(pb) = &pb {
+                            pb.inc(fs::metadata(&job.path).map(|m| m.len()).unwrap_or(0));
+                        }
+                        outputs.lock().unwrap()[*idx] = Some((
+                            out_path,
+                            crate::parents::ParentAttachFileReport::resume_skipped(),
+                        ));
+                        return Ok(());
+                    }
+
+                    let body = |w: &mut dyn Write| -> Result<crate::parents::ParentAttachFileReport> {
+                        let mut attach_w = crate::parents::ParentAttachWriter::new(
+                            w,
+                            parents,
+                            plan.etl.opts.parent_cache_caps,
+                            job.kind == FileKind::Comment,
+                        );
+                        if let Some(max_depth) = plan.reply_depth_cap {
+                            attach_w = attach_w
+                                .with_reply_depth(max_depth, plan.etl.opts.parent_cache_caps);
+                        }
+                        let result =
+                            stream_job_with_partial_policy(job, &mut attach_w, &stream_ctx)?;
+                        complete_stream_job(job, result)?;
+                        attach_w.finish()
+                    };
+                    let written = if compressed {
+                        write_zst_atomic_if(
+                            &staging_dir,
+                            &out_path,
+                            zst_level,
+                            plan.etl.opts.zstd_dictionary.as_ref(),
+                            write_buf,
+                            |_| true,
+                            body,
+                        )
+                    } else {
+                        write_jsonl_atomic(&staging_dir, &out_path, write_buf, body)
+                    };
+                    let report = match written {
+                        Ok(report) => report,
+                        Err(e) if plan.etl.opts.allow_partial && is_partial_scan_error(&e) => {
+                            tracing::warn!(path=%job.path.display(), output=%out_path.display(), error=%e, "Skipping month after zstd decode error; staged attach output was discarded and resume will retry it");
+                            return Ok(());
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    crate::parents::write_direct_attach_fingerprint(
+                        &staging_dir,
+                        &out_path,
+                        &job.path,
+                        parents,
+                        start,
+                        end,
+                        &scan,
+                        write_buf,
+                    )?;
+                    outputs.lock().unwrap()[*idx] = Some((out_path, report));
+                    Ok(())
+                },
+            )?;
+
+            // Dropping the sidecars is enough to stop a resumed run from
+            // keeping parts written under a failed strict whitelist.
+            finalize_whitelist_strict(whitelist_tracker.as_deref(), out_dir, || {
+                remove_matching_files(out_dir, |name| {
+                    name.starts_with("part_R") && name.ends_with(".parents-attach.json")
+                })
+            })?;
+            if let Some(pb) = pb {
+                pb.finish_with_message("done");
+            }
+
+            let outputs: Vec<_> = outputs.into_inner().unwrap().into_iter().flatten().collect();
+            let records_written: u64 = outputs
+                .iter()
+                .map(|(_, report)| report.records_written())
+                .sum();
+            let stats = crate::parents::finish_parent_attach_reports(
+                outputs.iter().map(|(_, report)| *report),
+            );
+            let mut paths: Vec<PathBuf> = outputs.into_iter().map(|(path, _)| path).collect();
+            paths.sort();
+
+            let manifest = scan_manifest_input(
+                manifest_start,
+                "scan.attach_parents_zst",
+                if compressed {
+                    "parent-attached-zst-directory"
+                } else {
+                    "parent-attached-jsonl-directory"
+                },
+                &plan.etl,
+                &plan.query,
+                &files,
+                plan.limit,
+                manifest_counts(&[
+                    ("records_written_this_run", records_written),
+                    ("attached_files", paths.len() as u64),
+                    ("parents_resolved", stats.resolved),
+                    ("parents_unresolved", stats.unresolved),
+                ]),
+                None,
+                None,
+                serde_json::json!({
+                    "resume": resume,
+                    "zst_level": compressed.then_some(zst_level),
+                    "annotate_depth_max": plan.reply_depth_cap,
+                }),
+            );
+            maybe_write_run_manifest(
+                plan.etl.opts.emit_manifest,
+                manifest,
+                ManifestDestination::Directory(out_dir.to_path_buf()),
+            )?;
+            Ok((paths, stats))
+        })
+    }
+
+    /// Run the whole spooled parents flow in one call: spool matching
+    /// records ([`extract_spool_monthly`](Self::extract_spool_monthly)),
+    /// collect the parent ids they reference
+    /// ([`RedditETL::collect_parent_ids_from_jsonls`]), resolve those parents
+    /// ([`RedditETL::resolve_parent_maps`]) and attach them
+    /// ([`RedditETL::attach_parents_jsonls_parallel_with_stats`]), writing
+    /// one attached JSONL file per spooled month into `out_dir`.
+    ///
+    /// Parents are resolved over the plan's date range widened by
+    /// `pad_months` on each side, since a reply can land months after its
+    /// parent; without a date range the whole corpus is searched. The spool
+    /// and parent cache live in a scratch dir under the work dir that is
+    /// removed when the call returns, so no stage is resumed. Use the stage
+    /// methods directly to keep the cache or resume a run.
+    ///
+    /// Returns the attached file paths (sorted) and the attach stats.
+    pub fn extract_with_parents(
+        self,
+        out_dir: &Path,
+        pad_months: u32,
+    ) -> Result<(Vec<PathBuf>, crate::parents::ParentAttachStats), RetlError> {
+        let etl = self.etl.clone();
+        let work_dir = etl.ensure_work_dir()?;
+        let scratch = crate::util::unique_scratch_dir(&work_dir, "extract_with_parents", "stages");
+        crate::util::create_dir_all_with_default_backoff(&scratch).with_context(|| {
+            format!("creating parents pipeline work dir {}", scratch.display())
+        })?;
+
+        let result = (|| {
+            let (spool_parts, _written) = self.extract_spool_monthly(&scratch.join("spool"))?;
+            let ids = etl.collect_parent_ids_from_jsonls(spool_parts.clone())?;
+
+            let pad = |ym: YearMonth, step: fn(YearMonth) -> Option<YearMonth>| {
+                (0..pad_months).fold(ym, |ym, _| step(ym).unwrap_or(ym))
+            };
+            let (start, end) = etl.planned_date_range()?;
+            let start = start.map(|ym| pad(ym, YearMonth::prev));
+            let end = end.map(|ym| pad(ym, YearMonth::next));
+            let parents = etl.clone().date_range(start, end).resolve_parent_maps(
+                &ids,
+                &scratch.join("parents_cache"),
+                /*resume=*/ false,
+            )?;
+
+            etl.attach_parents_jsonls_parallel_with_stats(
+                spool_parts,
+                out_dir,
+                &parents,
+                /*resume=*/ false,
+            )
+        })();
+
+        cleanup_scratch_dir(&scratch, "extract_with_parents");
+        result
+    }
+}
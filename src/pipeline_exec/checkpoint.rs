@@ -79,7 +79,6 @@ fn materialize_scan_checkpoint(
     let initial_months =
         load_and_validate_scan_checkpoint_manifest(&checkpoint_dir, &fingerprint)?;
     crate::progress_manifest::save(&checkpoint_dir, &initial_months, Some(&fingerprint))?;
-    let resumed_lines = committed_line_count(&initial_months);
     let accumulator = ManifestAccumulator::new(
         &checkpoint_dir,
         initial_months.clone(),
@@ -88,9 +87,10 @@ fn materialize_scan_checkpoint(
 
     let total_bytes = total_compressed_size(&files);
     let pb = if show_progress && etl.opts.progress {
-        Some(make_progress_bar_labeled(
+        Some(make_stage_progress_bar(
             total_bytes,
             etl.opts.progress_label.as_deref(),
+            etl.opts.progress_stage.as_ref(),
         ))
     } else {
         None
@@ -106,12 +106,19 @@ fn materialize_scan_checkpoint(
     }));
 
     let targets = resolve_target_subs_from(&etl.opts.subreddit, &query.subreddits);
-    let targets_ref = targets.as_ref();
-    let bounds = bounds_tuple(etl.opts.start, etl.opts.end);
-    let read_buf = etl.opts.read_buffer_bytes;
+    let record_limit = record_limit_from_with_claimed(limit, None, &initial_months);
+    let stream_ctx = StreamJobCtx {
+        targets: targets.as_ref(),
+        pb: pb.as_ref(),
+        bounds: bounds_tuple(etl.opts.start, etl.opts.end),
+        read_buf_bytes: etl.opts.read_buffer_bytes,
+        record_limit: record_limit.as_deref(),
+        allow_partial: etl.opts.allow_partial,
+        partial_reporter: Some(&etl.opts.partial_read_reporter),
+        reject_sink: etl.opts.reject_sink.as_ref(),
+        ..StreamJobCtx::new(query)
+    };
     let write_buf = etl.opts.write_buffer_bytes;
-    let record_limit = record_limit_from_with_claimed(limit, resumed_lines);
-    let no_whitelist: Option<Vec<String>> = None;
 
     crate::concurrency::for_each_file_limited(
         &files,
@@ -120,21 +127,11 @@ fn materialize_scan_checkpoint(
             let ctx = MonthJobCtx {
                 out_dir: &checkpoint_dir,
                 staging_dir: &staging_dir,
-                targets: targets_ref,
-                query,
-                whitelist: &no_whitelist,
-                pb: pb.as_ref(),
-                bounds,
-                read_buf,
                 write_buf,
-                human_ts: false,
-                whitelist_tracker: None,
-                record_limit: record_limit.as_deref(),
+                stream: stream_ctx,
                 resume: true,
                 completed_months: &initial_months,
                 accumulator: Some(&accumulator),
-                allow_partial: etl.opts.allow_partial,
-                partial_reporter: Some(&etl.opts.partial_read_reporter),
             };
             let outcome = process_month(job, &ctx)?;
             if let Some(month) = outcome {
@@ -228,6 +225,39 @@ where
     })
 }
 
+impl ScanPlan {
+    /// Invoke `on_record` with every matched record and its raw line: replayed
+    /// from the scan checkpoint (materialized first) when `resume` is set,
+    /// scanned straight from the corpus otherwise. Called on a built plan
+    /// inside its thread pool.
+    fn for_each_matched<F>(&self, on_record: F) -> Result<()>
+    where
+        F: Sync + Send + Fn(&MinimalRecord, &str) -> Result<()>,
+    {
+        if self.etl.opts.resume {
+            let checkpoint = materialize_scan_checkpoint(
+                &self.etl,
+                &self.query,
+                /*show_progress=*/ true,
+                self.limit,
+            )?;
+            for_each_checkpoint_record(
+                &checkpoint.parts,
+                self.etl.opts.read_buffer_bytes,
+                on_record,
+            )
+        } else {
+            scan_records(
+                &self.etl,
+                &self.query,
+                /*show_progress=*/ true,
+                self.limit,
+                |min, _kind, line| on_record(min, line),
+            )
+        }
+    }
+}
+
 fn copy_checkpoint_parts_to_jsonl(
     parts: &[PathBuf],
     out_path: &Path,
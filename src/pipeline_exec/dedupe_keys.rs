@@ -84,7 +84,7 @@ impl ScanPlan {
     /// only for key extraction. Matching records whose key extractor returns
     /// `Ok(None)` are omitted from the output; use
     /// [`ScanPlan::dedupe_keys_to_lines_with_stats`] to inspect that count.
-    pub fn dedupe_keys_to_lines(self, key: &KeyExtractor, out_path: &Path) -> Result<u64> {
+    pub fn dedupe_keys_to_lines(self, key: &KeyExtractor, out_path: &Path) -> Result<u64, RetlError> {
         Ok(self
             .dedupe_keys_to_lines_with_stats(key, out_path)?
             .unique_keys)
@@ -96,10 +96,10 @@ impl ScanPlan {
         self,
         key: &KeyExtractor,
         out_path: &Path,
-    ) -> Result<DedupeKeySummary> {
-        let plan = self.build()?;
+    ) -> Result<DedupeKeySummary, RetlError> {
+        let plan = self.build_for_scan()?;
         let parallelism = plan.etl.opts.parallelism;
-        with_thread_pool(parallelism, || {
+        Ok(with_thread_pool(parallelism, || {
             let manifest_start = RunManifestStart::now();
             let files = plan_pipeline_files(&plan.etl, Some(&plan.query))?;
             let work_dir = plan.etl.ensure_work_dir()?;
@@ -241,7 +241,7 @@ impl ScanPlan {
 
             let _ = crate::util::remove_dir_all_with_short_backoff(&tmp_dir);
             result
-        })
+        })?)
     }
 }
 
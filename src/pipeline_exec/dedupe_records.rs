@@ -0,0 +1,56 @@
+// Record dedupe: the collect pass behind `ScanPlan::dedupe_records_by`. The
+// emit pass is the export's own `stream_job_with_partial_policy` call with
+// `RecordDedupe::Drop`.
+
+/// Run the collect pass of [`ScanPlan::dedupe_records_by`] over `files` and
+/// reduce it to the records the emit pass must drop. `None` when the query
+/// does not dedupe records.
+///
+/// The pass applies the same filters as the export but writes nothing, so
+/// every planned file is read twice. The earliest copy of a key wins, by
+/// month, then comments before submissions, then line, which keeps the
+/// output independent of `file_concurrency`.
+fn collect_duplicate_records(
+    etl: &RedditETL,
+    query: &QuerySpec,
+    targets: Option<&Vec<String>>,
+    files: &[FileJob],
+) -> Result<Option<DuplicateRecords>> {
+    let Some(key) = query.dedupe_records.clone() else {
+        return Ok(None);
+    };
+    let work_dir = etl.ensure_work_dir()?;
+    let scratch_root = IdScratchRoot::create(&work_dir)?;
+    let shard_count = etl
+        .opts
+        .shard_count
+        .unwrap_or_else(|| parent_id_shard_count(total_compressed_size(files)));
+    let collector = RecordKeyCollector::create(key.clone(), scratch_root, shard_count)?;
+    // Nothing is written, so only the filters and the malformed-line
+    // handling carry over from the export.
+    let stream_ctx = StreamJobCtx {
+        targets,
+        bounds: bounds_tuple(etl.opts.start, etl.opts.end),
+        read_buf_bytes: etl.opts.read_buffer_bytes,
+        strict_json: etl.opts.strict_json,
+        allow_partial: etl.opts.allow_partial,
+        reject_sink: etl.opts.reject_sink.as_ref(),
+        record_dedupe: Some(RecordDedupe::Collect(&collector)),
+        ..StreamJobCtx::new(query)
+    };
+
+    crate::concurrency::for_each_file_limited(files, etl.opts.file_concurrency, |job| {
+        stream_job_with_partial_policy(job, &mut std::io::sink(), &stream_ctx)
+            .with_context(|| format!("collect dedupe keys from {}", job.path.display()))?;
+        Ok(())
+    })?;
+
+    let duplicates = collector.finish()?;
+    tracing::info!(
+        key = %dedupe_key_label(&key),
+        duplicates = duplicates.dropped(),
+        "dedupe_records_by will drop {} duplicate record(s)",
+        duplicates.dropped(),
+    );
+    Ok(Some(duplicates))
+}
@@ -0,0 +1,224 @@
+// Dry-run planning: `RedditETL::plan` / `ScanPlan::plan` report which corpus
+// files a run would open, and how large they are, without decoding any;
+// `RedditETL::plan_stats` / `ScanPlan::plan_stats` sum that up and report a
+// range that selects no file as `matched_files == 0` instead of an error;
+// `RedditETL::corpus_bounds` / `RedditETL::find_missing_months` report which
+// months exist at all, and `RedditETL::last_n_months` turns that into a range.
+
+/// One corpus file a run would read, as reported by [`RedditETL::plan`] and
+/// [`ScanPlan::plan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedFile {
+    pub path: PathBuf,
+    pub kind: FileKind,
+    pub ym: YearMonth,
+    /// Compressed on-disk size in bytes.
+    pub size_bytes: u64,
+}
+
+/// Totals over the files a run would read, as reported by
+/// [`RedditETL::plan_stats`] and [`ScanPlan::plan_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlanStats {
+    /// Planned corpus files; `0` when the source directories exist but no
+    /// monthly file falls in the date range, so "found nothing" can be told
+    /// apart from a run that read files and filtered every record out.
+    pub matched_files: usize,
+    /// Compressed on-disk size of the planned files in bytes.
+    pub compressed_bytes: u64,
+}
+
+impl PlanStats {
+    fn from_planned(planned: &[PlannedFile]) -> Self {
+        Self {
+            matched_files: planned.len(),
+            compressed_bytes: planned.iter().map(|file| file.size_bytes).sum(),
+        }
+    }
+}
+
+/// Months discovered for one source, as reported by
+/// [`RedditETL::corpus_bounds`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceBounds {
+    /// Earliest month present.
+    pub min: YearMonth,
+    /// Latest month present (inclusive).
+    pub max: YearMonth,
+    /// Months present between `min` and `max`; less than the span when the
+    /// corpus has holes.
+    pub months: usize,
+}
+
+impl SourceBounds {
+    fn from_months(months: &BTreeMap<YearMonth, PathBuf>) -> Option<Self> {
+        Some(Self {
+            min: *months.keys().next()?,
+            max: *months.keys().next_back()?,
+            months: months.len(),
+        })
+    }
+}
+
+/// Corpus date bounds per source; `None` for a source with no monthly files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CorpusBounds {
+    pub comments: Option<SourceBounds>,
+    pub submissions: Option<SourceBounds>,
+}
+
+impl RedditETL {
+    /// Discover which months exist for comments and submissions, e.g. to
+    /// populate a date picker before choosing a `date_range`.
+    ///
+    /// Reads directory listings only. Both sources are reported whatever
+    /// `sources` is set to, and the configured date range is ignored; extra
+    /// corpus roots are merged under the `duplicate_months` policy.
+    pub fn corpus_bounds(&self) -> Result<CorpusBounds, RetlError> {
+        let discovered = discover_roots_checked(
+            &self.opts.corpus_roots(),
+            Sources::Both,
+            self.opts.duplicate_months,
+        )?;
+        Ok(CorpusBounds {
+            comments: SourceBounds::from_months(&discovered.comments),
+            submissions: SourceBounds::from_months(&discovered.submissions),
+        })
+    }
+
+    /// Process the latest `n` months present for each selected source,
+    /// e.g. `last_n_months(3)` to follow the newest dumps without naming a
+    /// range. Under [`Sources::Both`] comments and submissions each get their
+    /// own newest `n` months, so a source whose dumps lag the other is not
+    /// cut short. Months are counted over the files that exist (the explicit
+    /// [`files`](Self::files) when set), so a hole in the corpus does not use
+    /// up part of `n`; a corpus with fewer than `n` months is processed
+    /// whole, and `n == 0` is treated as 1.
+    ///
+    /// The months are resolved when a run plans its files, so builder order
+    /// does not matter and a discovery failure surfaces from the run. A later
+    /// `date_range`, `since` or `until` replaces this setting.
+    pub fn last_n_months(mut self, n: usize) -> Self {
+        self.opts = self.opts.with_last_n_months(n);
+        self
+    }
+
+    /// The `(start, end)` month range a run plans over: the configured
+    /// `date_range`, or the months [`last_n_months`](Self::last_n_months)
+    /// resolves to on disk.
+    pub(crate) fn planned_date_range(&self) -> Result<(Option<YearMonth>, Option<YearMonth>)> {
+        if self.opts.last_n_months.is_none() {
+            return Ok((self.opts.start, self.opts.end));
+        }
+        let (discovered, sources) = match &self.opts.files {
+            Some(files) => (
+                discover_files_checked(files, self.opts.duplicate_months)?,
+                Sources::Both,
+            ),
+            None => (
+                discover_roots_checked(
+                    &self.opts.corpus_roots(),
+                    self.opts.sources,
+                    self.opts.duplicate_months,
+                )?,
+                self.opts.sources,
+            ),
+        };
+        Ok(self.opts.planned_range(&discovered, sources))
+    }
+
+    /// Months absent from the corpus between each selected source's earliest
+    /// and latest discovered month, as `(source, month)` pairs in source then
+    /// month order.
+    ///
+    /// A configured `date_range` narrows the check; months outside the
+    /// discovered span are never reported, so only holes inside the corpus
+    /// show up. Runs skip these months silently, which reads as a month with
+    /// zero records.
+    pub fn find_missing_months(&self) -> Result<Vec<(FileKind, YearMonth)>, RetlError> {
+        self.opts.check_config()?;
+        let discovered = discover_roots_checked(
+            &self.opts.corpus_roots(),
+            self.opts.sources,
+            self.opts.duplicate_months,
+        )?;
+        let (start, end) = self.opts.planned_range(&discovered, self.opts.sources);
+        Ok(
+            missing_month_diagnostics(&discovered, self.opts.sources, start, end)
+                .into_iter()
+                .flat_map(|diag| diag.months.into_iter().map(move |ym| (diag.kind, ym)))
+                .collect(),
+        )
+    }
+
+    /// List the files a run with these options would process, in processing
+    /// order, without reading them. Uses the same discovery and date-range
+    /// planning as a real run, so a wrong `base_dir` or an out-of-corpus range
+    /// fails here with the same [`PlanningError`](crate::PlanningError).
+    pub fn plan(&self) -> Result<Vec<PlannedFile>, RetlError> {
+        self.opts.check_config()?;
+        Ok(planned_files(&plan_files_for(self, None)?)?)
+    }
+
+    /// Totals for [`plan`](Self::plan). Source directories that exist but
+    /// hold no monthly file in the date range give `Ok` with
+    /// `matched_files == 0` where `plan` and the terminal operations fail
+    /// with a [`PlanningError`](crate::PlanningError); a selection whose
+    /// directories are all missing is still that error.
+    pub fn plan_stats(&self) -> Result<PlanStats, RetlError> {
+        self.opts.check_config()?;
+        plan_stats(plan_files_for(self, None))
+    }
+}
+
+impl ScanPlan {
+    /// Query-aware mirror of [`RedditETL::plan`]: validates the query and
+    /// narrows the month range by any `created_utc` bounds exactly as the
+    /// terminal operations do. Record-level filters do not skip files.
+    pub fn plan(self) -> Result<Vec<PlannedFile>, RetlError> {
+        let plan = self.build()?;
+        plan.etl.opts.check_config()?;
+        Ok(planned_files(&plan_files_for(&plan.etl, Some(&plan.query))?)?)
+    }
+
+    /// Query-aware mirror of [`RedditETL::plan_stats`].
+    pub fn plan_stats(self) -> Result<PlanStats, RetlError> {
+        let plan = self.build()?;
+        plan.etl.opts.check_config()?;
+        plan_stats(plan_files_for(&plan.etl, Some(&plan.query)))
+    }
+}
+
+/// Sums up a planning result, turning "no file in range" into an empty
+/// [`PlanStats`]; see [`RedditETL::plan_stats`].
+fn plan_stats(jobs: Result<Vec<FileJob>>) -> Result<PlanStats, RetlError> {
+    let jobs = match jobs {
+        Ok(jobs) => jobs,
+        Err(err) => match err.downcast_ref::<PlanningError>() {
+            Some(PlanningError::DateRangeNoFiles { .. }) => return Ok(PlanStats::default()),
+            Some(PlanningError::NoSourceFiles { statuses, .. })
+                if statuses.iter().any(|status| status.exists) =>
+            {
+                return Ok(PlanStats::default())
+            }
+            _ => return Err(err.into()),
+        },
+    };
+    Ok(PlanStats::from_planned(&planned_files(&jobs)?))
+}
+
+fn planned_files(jobs: &[FileJob]) -> Result<Vec<PlannedFile>> {
+    jobs.iter()
+        .map(|job| {
+            let size_bytes = fs::metadata(&job.path)
+                .with_context(|| format!("failed to stat planned file {}", job.path.display()))?
+                .len();
+            Ok(PlannedFile {
+                path: job.path.clone(),
+                kind: job.kind,
+                ym: job.ym,
+                size_bytes,
+            })
+        })
+        .collect()
+}
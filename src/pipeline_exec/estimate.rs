@@ -0,0 +1,203 @@
+// Output-size projection: `ScanPlan::estimate_output` decodes a bounded
+// prefix of every planned file, measures the match rate and matched-record
+// size there, and scales both to each file's compressed size.
+
+/// Compressed bytes sampled from the start of each file by
+/// [`ScanPlan::estimate_output`], in multiples of `read_buffer_bytes`.
+const ESTIMATE_SAMPLE_READ_BUFFERS: u64 = 32;
+
+/// Projected size of a [`ScanPlan`]'s output, from
+/// [`ScanPlan::estimate_output`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutputEstimate {
+    /// Estimated matching records, capped by `limit`.
+    pub records: u64,
+    /// Estimated uncompressed JSONL bytes of those records, capped by
+    /// `max_output_bytes`.
+    pub bytes: u64,
+    /// Matching records actually seen in the sample. Estimates built on a
+    /// few dozen matches are rough; on zero they only say "little or none".
+    pub sampled_records: u64,
+    /// Share of the planned compressed bytes the sample decoded, `0.0..=1.0`.
+    /// `1.0` means every file was read to the end and the counts are exact.
+    pub sampled_fraction: f64,
+}
+
+/// What one file's sample saw.
+#[derive(Clone, Copy, Default)]
+struct FileSample {
+    compressed_bytes: u64,
+    matched: u64,
+    matched_bytes: u64,
+    complete: bool,
+}
+
+impl ScanPlan {
+    /// Estimate how many records and bytes this scan would write, e.g. to
+    /// provision disk before a large `extract_to_jsonl`.
+    ///
+    /// Decodes the first `32 * read_buffer_bytes` of compressed input from
+    /// every planned file (8 MiB at the default buffer size), applies the
+    /// query's filters there, and scales each file's matched records and
+    /// bytes by its compressed size over the compressed bytes sampled. The
+    /// compression ratio is therefore measured per file rather than assumed.
+    /// Files smaller than the sample are counted exactly.
+    ///
+    /// Bytes are the matched lines as stored, projected through
+    /// `whitelist_fields` (JSON-pointer entries included) when set; timestamp rewriting and provenance are
+    /// not applied. A filter whose matches cluster late in a month (for
+    /// example a `created_utc` bound inside it) is under-sampled, so treat
+    /// the result as a provisioning estimate. `source_ratio` and
+    /// `dedupe_records` are not applied.
+    pub fn estimate_output(self) -> Result<OutputEstimate, RetlError> {
+        let plan = self.build_for_run()?;
+        plan.etl.opts.check_config()?;
+        let etl = &plan.etl;
+        let query = &plan.query;
+        let files = plan_pipeline_files(etl, Some(query))?;
+        let targets = resolve_target_subs_from(&etl.opts.subreddit, &query.subreddits);
+        let bounds = bounds_tuple(etl.opts.start, etl.opts.end);
+        let budget = etl.opts.read_buffer_bytes as u64 * ESTIMATE_SAMPLE_READ_BUFFERS;
+        let samples = Mutex::new(Vec::<(u64, FileSample)>::with_capacity(files.len()));
+
+        with_thread_pool(etl.opts.parallelism, || {
+            crate::concurrency::for_each_file_limited(
+                &files,
+                etl.opts.file_concurrency,
+                |job| -> Result<()> {
+                    let size = fs::metadata(&job.path)
+                        .with_context(|| format!("stat {}", job.path.display()))?
+                        .len();
+                    let sample = sample_file(job, query, targets.as_ref(), bounds, etl, budget)?;
+                    samples.lock().unwrap().push((size, sample));
+                    Ok(())
+                },
+            )
+        })?;
+
+        let (mut records, mut bytes) = (0.0_f64, 0.0_f64);
+        let (mut sampled_records, mut sampled_compressed, mut total_compressed) = (0, 0, 0);
+        for (size, sample) in samples.into_inner().unwrap() {
+            let scale = if sample.complete || sample.compressed_bytes == 0 {
+                1.0
+            } else {
+                size as f64 / sample.compressed_bytes as f64
+            };
+            records += sample.matched as f64 * scale;
+            bytes += sample.matched_bytes as f64 * scale;
+            sampled_records += sample.matched;
+            sampled_compressed += if sample.complete {
+                size
+            } else {
+                sample.compressed_bytes.min(size)
+            };
+            total_compressed += size;
+        }
+        if let Some(limit) = plan
+            .limit
+            .map(|limit| limit as f64)
+            .filter(|&l| l < records)
+        {
+            bytes *= limit / records;
+            records = limit;
+        }
+        if let Some(max) = etl.opts.max_output_bytes {
+            bytes = bytes.min(max as f64);
+        }
+        Ok(OutputEstimate {
+            records: records.round() as u64,
+            bytes: bytes.round() as u64,
+            sampled_records,
+            sampled_fraction: if total_compressed == 0 {
+                1.0
+            } else {
+                sampled_compressed as f64 / total_compressed as f64
+            },
+        })
+    }
+}
+
+/// Decode `job` until `budget` compressed bytes have been read, counting the
+/// records `query` matches and their output bytes.
+///
+/// The decoder pulls compressed input a buffer at a time, so the byte count
+/// runs ahead of the lines decoded. Whenever it grows, everything read
+/// before the jump has been decoded into the lines seen so far; the sample
+/// is cut at such a jump so its records and compressed bytes line up.
+fn sample_file(
+    job: &FileJob,
+    query: &QuerySpec,
+    targets: Option<&Vec<String>>,
+    bounds: Option<DateBounds>,
+    etl: &RedditETL,
+    budget: u64,
+) -> Result<FileSample> {
+    let consumed = std::cell::Cell::new(0_u64);
+    let mut sample = FileSample::default();
+    let mut cut = FileSample::default();
+    let mut projector = etl
+        .opts
+        .whitelist_fields
+        .as_deref()
+        .map(WhitelistProjector::new);
+    let mut line_number = 0_u64;
+    let mut on_progress = |delta: u64| consumed.set(consumed.get() + delta);
+    let run_settings = RunSettings::from_options(&etl.opts);
+    let status = for_each_line_with_opts_status(
+        &job.path,
+        LineStreamOpts {
+            read_buf_bytes: Some(etl.opts.read_buffer_bytes),
+            progress: Some(&mut on_progress),
+            partial_read_policy: if etl.opts.allow_partial {
+                PartialReadPolicy::AllowPartial
+            } else {
+                PartialReadPolicy::Strict
+            },
+            max_line_bytes: run_settings.max_line_bytes,
+            io_retries: run_settings.io_retries,
+            zstd_dictionary: run_settings.zstd_dictionary.as_ref(),
+            ..Default::default()
+        },
+        |line| {
+            line_number += 1;
+            let now = consumed.get();
+            if now != sample.compressed_bytes {
+                cut = sample;
+                sample.compressed_bytes = now;
+                if cut.compressed_bytes >= budget {
+                    return Err(record_limit_reached_error());
+                }
+            }
+            let Ok(min) = parse_minimal(line) else {
+                return Ok(());
+            };
+            if !matches_minimal(&min, targets, query, job.kind)
+                || !within_bounds_on(&min, bounds, query.time_field)
+            {
+                return Ok(());
+            }
+            if query.requires_full_parse() {
+                let val: Value = serde_json::from_str(line)
+                    .map_err(|e| malformed_json_error(&job.path, line_number, e))?;
+                if !matches_full(&val, job.kind, query) {
+                    return Ok(());
+                }
+            }
+            let len = match projector.as_mut() {
+                Some(projector) => projector.project(line, &job.path, line_number)?.len(),
+                None => line.len() + 1,
+            };
+            sample.matched += 1;
+            sample.matched_bytes += len as u64;
+            Ok(())
+        },
+    );
+    match status {
+        Ok(_) => Ok(FileSample {
+            complete: true,
+            ..sample
+        }),
+        Err(e) if is_record_limit_reached(&e) => Ok(cut),
+        Err(e) => Err(e),
+    }
+}
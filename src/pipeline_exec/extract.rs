@@ -31,15 +31,16 @@ fn validate_jsonl_part(path: &Path) -> Result<MonthEntry> {
         size,
         lines,
         sha256: None,
+        output_bytes: None,
     })
 }
 
 impl ScanPlan {
-    pub fn extract_to_jsonl(self, out_path: &Path) -> Result<()> {
-        let plan = self.build()?;
+    pub fn extract_to_jsonl(self, out_path: &Path) -> Result<(), RetlError> {
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let targets = resolve_target_subs_from(&plan.etl.opts.subreddit, &plan.query.subreddits);
-        extract_common(
+        Ok(extract_common(
             &plan.etl,
             &plan.query,
             targets.as_ref(),
@@ -49,14 +50,14 @@ impl ScanPlan {
             "jsonl",
             "scan.extract_to_jsonl",
             plan.limit,
-        )
+        )?)
     }
 
-    pub fn extract_to_json(self, out_path: &Path, pretty: bool) -> Result<()> {
-        let plan = self.build()?;
+    pub fn extract_to_json(self, out_path: &Path, pretty: bool) -> Result<(), RetlError> {
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let targets = resolve_target_subs_from(&plan.etl.opts.subreddit, &plan.query.subreddits);
-        extract_common(
+        Ok(extract_common(
             &plan.etl,
             &plan.query,
             targets.as_ref(),
@@ -66,7 +67,7 @@ impl ScanPlan {
             "json",
             "scan.extract_to_json",
             plan.limit,
-        )
+        )?)
     }
 
     /// Write a single Apache Parquet file at `out_path`. Internally extracts
@@ -77,7 +78,7 @@ impl ScanPlan {
     ///
     /// Requires the `parquet` cargo feature; without it the conversion step
     /// returns a build-flag error from [`crate::parquet_writer::write_parquet_atomic_if`].
-    pub fn extract_to_parquet(self, out_path: &Path) -> Result<()> {
+    pub fn extract_to_parquet(self, out_path: &Path) -> Result<(), RetlError> {
         let plan = self.clone_for_parquet_intermediate();
         let intermediate = parquet_intermediate_path(out_path)?;
         // Run extract → stitched JSONL at the intermediate path.
@@ -95,7 +96,7 @@ impl ScanPlan {
         if let Err(e) = crate::util::remove_with_short_backoff(&intermediate) {
             tracing::warn!(path=%intermediate.display(), error=%e, "failed to remove parquet intermediate JSONL");
         }
-        result
+        Ok(result?)
     }
 
     /// Snapshot a tiny view of the [`RedditETL`] config the parquet two-pass
@@ -1,4 +1,3 @@
-
 /// Per-entry validator for the extract scratch dir: re-reads the cached part
 /// file and returns a freshly computed [`MonthEntry`] (size+line count). The
 /// returned entry replaces the manifest's recorded line count, matching the
@@ -98,23 +97,31 @@ fn extract_common(
                 fields.iter().cloned(),
             ))
         });
-        let record_limit = record_limit_from_with_claimed(limit, resumed_lines);
+        let record_limit =
+            record_limit_from_with_claimed(limit, etl.opts.max_output_bytes, &initial_months);
+        let duplicates = collect_duplicate_records(etl, query, targets, &files)?;
 
         let total_bytes = total_compressed_size(&files);
         let pb = if etl.opts.progress {
-            Some(make_progress_bar_labeled(
+            Some(make_stage_progress_bar(
                 total_bytes,
                 etl.opts.progress_label.as_deref(),
+                etl.opts.progress_stage.as_ref(),
             ))
         } else {
             None
         };
 
-        let targets_ref = targets;
-        let bounds = bounds_tuple(etl.opts.start, etl.opts.end);
-        let read_buf = etl.opts.read_buffer_bytes;
+        let stream_ctx = StreamJobCtx {
+            targets,
+            whitelist: &whitelist,
+            pb: pb.as_ref(),
+            whitelist_tracker: whitelist_tracker.as_deref(),
+            record_limit: record_limit.as_deref(),
+            record_dedupe: duplicates.as_ref().map(RecordDedupe::Drop),
+            ..StreamJobCtx::from_options(&etl.opts, query)
+        };
         let write_buf = etl.opts.write_buffer_bytes;
-        let human_ts = etl.opts.human_readable_timestamps;
 
         crate::concurrency::for_each_file_limited(
             &files,
@@ -162,25 +169,11 @@ fn extract_common(
                     }
                 }
 
-                let lines = match write_jsonl_atomic(&staging_dir, &tmp_file, write_buf, |w| {
-                    let result = stream_job_with_partial_policy(
-                        job,
-                        w,
-                        targets_ref,
-                        query,
-                        &whitelist,
-                        pb.clone(),
-                        bounds,
-                        read_buf,
-                        human_ts,
-                        whitelist_tracker.as_deref(),
-                        etl.opts.allow_partial,
-                        Some(&etl.opts.partial_read_reporter),
-                        record_limit.as_deref(),
-                    )?;
-                    complete_stream_job(job, result)
+                let result = match write_jsonl_atomic(&staging_dir, &tmp_file, write_buf, |w| {
+                    let result = stream_job_with_partial_policy(job, w, &stream_ctx)?;
+                    checked_stream_job(job, result)
                 }) {
-                    Ok(lines) => lines,
+                    Ok(result) => result,
                     Err(e) if etl.opts.allow_partial && is_partial_scan_error(&e) => {
                         tracing::warn!(path=%job.path.display(), part=%tmp_file.display(), error=%e, "Skipping extract month after zstd decode error; staged part was discarded and resume will retry it");
                         return Ok(());
@@ -188,13 +181,14 @@ fn extract_common(
                     Err(e) => return Err(e),
                 };
 
-                output_records.fetch_add(lines, Ordering::Relaxed);
+                output_records.fetch_add(result.written, Ordering::Relaxed);
                 if let Some(acc) = &accumulator {
                     let size = fs::metadata(&tmp_file).map(|m| m.len()).unwrap_or(0);
                     let entry = MonthEntry {
                         size,
-                        lines,
+                        lines: result.written,
                         sha256: None,
+                        output_bytes: Some(result.output_bytes),
                     };
                     acc.commit(key, entry).context(
                         "failed to durably update extract progress manifest after publishing resume part",
@@ -219,10 +213,11 @@ fn extract_common(
         }
         ensure_resume_manifest_durable(accumulator.as_ref(), "extract")?;
 
+        let month_major = etl.opts.stitch_month_major();
         match finalize {
-            Finalize::Jsonl => stitch_tmp_parts(&tmp_dir, out_path, write_buf)?,
+            Finalize::Jsonl => stitch_tmp_parts(&tmp_dir, out_path, month_major, write_buf)?,
             Finalize::JsonArray { pretty } => {
-                stitch_tmp_parts_to_json_array(&tmp_dir, out_path, pretty, write_buf)?
+                stitch_tmp_parts_to_json_array(&tmp_dir, out_path, pretty, month_major, write_buf)?
             }
         }
         let manifest = scan_manifest_input(
@@ -0,0 +1,200 @@
+// Streaming extraction to a caller-supplied sink: `ScanPlan::extract_to_writer`
+// sends matched records through the extract write path straight into any
+// `Write` (a socket, a pipe, an in-memory buffer), batched by
+// `ETLOptions::write_batch_size`; `ScanPlan::extract_framed_to_writer` sends
+// the same records as length-prefixed frames.
+
+impl ScanPlan {
+    /// Write matching records as JSONL to `writer`, with the same
+    /// filter/whitelist/timestamp handling as
+    /// [`extract_to_jsonl`](Self::extract_to_jsonl) but no temp parts and no
+    /// stitching, so records reach the sink while the scan is running.
+    ///
+    /// Each file gathers [`write_batch_size`](crate::ETLOptions::write_batch_size)
+    /// serialized records into one buffer and hands the sink a single
+    /// `write_all` per batch, which matters when every write is a syscall
+    /// or takes a lock (sockets, pipes, shared writers). Files run
+    /// concurrently, so batches from different months can interleave; a
+    /// record is never split. Set `ordered_output(true)` for chronological
+    /// output. The sink is flushed once at the end.
+    ///
+    /// This method does not consult `resume` (there is no output to
+    /// checkpoint) and writes no run manifest. Returns the number of records
+    /// written.
+    pub fn extract_to_writer<W: Write + Send>(self, writer: W) -> Result<u64, RetlError> {
+        Ok(self.extract_to_sink(writer, SinkFraming::Lines)?)
+    }
+
+    /// Like [`extract_to_writer`](Self::extract_to_writer), but each record
+    /// goes out as a 4-byte big-endian length followed by its compact JSON
+    /// bytes, with no newline, for length-prefixed (gRPC-style) protocols
+    /// whose consumers do not split on newlines. Read the stream back with
+    /// [`read_framed`](crate::read_framed). Batching by
+    /// [`write_batch_size`](crate::ETLOptions::write_batch_size) applies as
+    /// usual. A record above [`DEFAULT_MAX_LINE_BYTES`](crate::DEFAULT_MAX_LINE_BYTES),
+    /// which `read_framed` would refuse, fails the run even when
+    /// [`max_line_bytes`](crate::ETLOptions::max_line_bytes) is raised.
+    pub fn extract_framed_to_writer<W: Write + Send>(self, writer: W) -> Result<u64, RetlError> {
+        Ok(self.extract_to_sink(writer, SinkFraming::LengthPrefixed)?)
+    }
+
+    fn extract_to_sink<W: Write + Send>(self, writer: W, framing: SinkFraming) -> Result<u64> {
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        validate_export_whitelist(&plan.etl)?;
+        let etl = &plan.etl;
+        with_thread_pool(etl.opts.parallelism, || {
+            let targets = resolve_target_subs_from(&etl.opts.subreddit, &plan.query.subreddits);
+            let mut files = plan_pipeline_files(etl, Some(&plan.query))?;
+            warn_if_unfiltered_undated_query(etl, &plan.query, &files);
+            let file_concurrency = if etl.opts.ordered_output {
+                sort_jobs_chronologically(&mut files);
+                1
+            } else {
+                etl.opts.file_concurrency
+            };
+
+            let whitelist = etl.opts.whitelist_fields.clone();
+            let whitelist_tracker = whitelist.as_ref().map(|fields| {
+                Arc::new(WhitelistMatchTracker::new(
+                    etl.opts.strict_whitelist,
+                    fields.iter().cloned(),
+                ))
+            });
+            let record_limit = record_limit_from(plan.limit, etl.opts.max_output_bytes);
+            let duplicates = collect_duplicate_records(etl, &plan.query, targets.as_ref(), &files)?;
+            let pb = if etl.opts.progress {
+                Some(make_stage_progress_bar(
+                    total_compressed_size(&files),
+                    etl.opts.progress_label.as_deref(),
+                    etl.opts.progress_stage.as_ref(),
+                ))
+            } else {
+                None
+            };
+            let stream_ctx = StreamJobCtx {
+                targets: targets.as_ref(),
+                whitelist: &whitelist,
+                pb: pb.as_ref(),
+                whitelist_tracker: whitelist_tracker.as_deref(),
+                record_limit: record_limit.as_deref(),
+                record_dedupe: duplicates.as_ref().map(RecordDedupe::Drop),
+                ..StreamJobCtx::from_options(&etl.opts, &plan.query)
+            };
+            let sink = Mutex::new(writer);
+            let written = AtomicU64::new(0);
+
+            crate::concurrency::for_each_file_limited(
+                &files,
+                file_concurrency,
+                |job| -> Result<()> {
+                    if record_limit
+                        .as_ref()
+                        .is_some_and(|limit| limit.is_exhausted())
+                    {
+                        return Ok(());
+                    }
+                    let mut batch =
+                        RecordBatchWriter::new(&sink, etl.opts.write_batch_size, framing);
+                    let result = stream_job_with_partial_policy(job, &mut batch, &stream_ctx)?;
+                    batch
+                        .write_pending()
+                        .with_context(|| format!("write records from {}", job.path.display()))?;
+                    // Records already handed to the sink cannot be taken
+                    // back, so a partial month is reported, not retried.
+                    if !result.complete {
+                        tracing::warn!(path=%job.path.display(), written=result.written, "Partial month written to sink after zstd decode error");
+                    }
+                    written.fetch_add(result.written, Ordering::Relaxed);
+                    Ok(())
+                },
+            )?;
+
+            if let Some(tracker) = &whitelist_tracker {
+                tracker.finalize()?;
+            }
+            if let Some(pb) = pb {
+                pb.finish_with_message("done");
+            }
+            sink.into_inner()
+                .map_err(|_| anyhow!("extract_to_writer sink lock poisoned"))?
+                .flush()
+                .context("flush extract_to_writer sink")?;
+            Ok(written.into_inner())
+        })
+    }
+}
+
+/// How [`RecordBatchWriter`] hands records to the sink.
+#[derive(Clone, Copy)]
+enum SinkFraming {
+    /// JSONL, as the write path produces it.
+    Lines,
+    /// Big-endian `u32` length, then the record without its newline.
+    LengthPrefixed,
+}
+
+/// Per-file staging buffer in front of a shared sink: collects serialized
+/// records and passes every `batch_size` complete lines to the sink in one
+/// `write_all` under its lock.
+struct RecordBatchWriter<'a, W: Write> {
+    sink: &'a Mutex<W>,
+    buf: Vec<u8>,
+    framed: Vec<u8>,
+    pending: usize,
+    batch_size: usize,
+    framing: SinkFraming,
+}
+
+impl<'a, W: Write> RecordBatchWriter<'a, W> {
+    fn new(sink: &'a Mutex<W>, batch_size: usize, framing: SinkFraming) -> Self {
+        Self {
+            sink,
+            buf: Vec::new(),
+            framed: Vec::new(),
+            pending: 0,
+            batch_size: batch_size.max(1),
+            framing,
+        }
+    }
+
+    /// Hand every complete buffered line to the sink; a trailing partial
+    /// record stays buffered until its newline arrives.
+    fn write_pending(&mut self) -> std::io::Result<()> {
+        let Some(end) = self.buf.iter().rposition(|&b| b == b'\n') else {
+            return Ok(());
+        };
+        let out = match self.framing {
+            SinkFraming::Lines => &self.buf[..=end],
+            SinkFraming::LengthPrefixed => {
+                self.framed.clear();
+                for record in self.buf[..end].split(|&b| b == b'\n') {
+                    crate::frames::write_be_frame(&mut self.framed, record)?;
+                }
+                &self.framed
+            }
+        };
+        self.sink
+            .lock()
+            .map_err(|_| std::io::Error::other("extract_to_writer sink lock poisoned"))?
+            .write_all(out)?;
+        self.buf.drain(..=end);
+        self.pending = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for RecordBatchWriter<'_, W> {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(bytes);
+        self.pending += bytes.iter().filter(|&&b| b == b'\n').count();
+        if self.pending >= self.batch_size {
+            self.write_pending()?;
+        }
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
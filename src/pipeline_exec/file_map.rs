@@ -0,0 +1,108 @@
+// Per-file extension point: `RedditETL::for_each_planned_file` hands each
+// planned corpus file to caller code under the same concurrency limits as
+// the built-in scans; `RedditETL::for_each_raw_line` builds on it to hand out
+// undecoded line bytes.
+
+impl RedditETL {
+    /// Run `f` once per corpus file this configuration would read, with the
+    /// crate's own concurrency discipline: at most `file_concurrency` files in
+    /// flight (further capped by the open-file budget) on a pool sized by
+    /// `parallelism`.
+    ///
+    /// Files are planned exactly as for a real run ([`RedditETL::plan`]
+    /// lists the same set); `ordered_output` processes them one at a time in
+    /// `(month, RC before RS)` order. `f` receives the [`FileJob`] only and
+    /// opens the file itself, e.g. with
+    /// [`for_each_line_cfg`](crate::for_each_line_cfg). The first error
+    /// returned by `f` aborts the run. No query filters apply here; use
+    /// [`ScanPlan::for_each_record`] for filtered records.
+    pub fn for_each_planned_file<F>(&self, f: F) -> Result<(), RetlError>
+    where
+        F: Sync + Send + Fn(&FileJob) -> Result<()>,
+    {
+        let _run_settings = self.opts.enter_run_settings();
+        let mut files = plan_pipeline_files(self, None)?;
+        let file_concurrency = if self.opts.ordered_output {
+            sort_jobs_chronologically(&mut files);
+            1
+        } else {
+            self.opts.file_concurrency
+        };
+        Ok(with_thread_pool(self.opts.parallelism, || {
+            crate::concurrency::for_each_file_limited(&files, file_concurrency, &f)
+        })?)
+    }
+
+    /// Call `f` with every non-empty line of every planned corpus file as raw
+    /// bytes, for the fastest byte-level passes such as counting lines that
+    /// contain a pattern. Lines are only decompressed: there is no UTF-8
+    /// validation, no JSON parse, no query filtering and no progress bar, and
+    /// a line that fits in the read buffer is handed over without copying.
+    /// The trailing `\n` / `\r\n` is stripped.
+    ///
+    /// Bytes come straight from the dump, so `f` must cope with invalid
+    /// UTF-8 and with lines that are not JSON (use `str::from_utf8` or
+    /// `String::from_utf8_lossy` when it needs text). Files run concurrently
+    /// as in [`RedditETL::for_each_planned_file`], so `f` must be
+    /// `Sync + Send` and use atomics or locks for accumulators. A decode
+    /// error, or a line longer than [`max_line_bytes`](crate::max_line_bytes),
+    /// aborts the run.
+    pub fn for_each_raw_line<F>(&self, f: F) -> Result<(), RetlError>
+    where
+        F: Sync + Send + Fn(&[u8]),
+    {
+        let read_buf = self.opts.read_buffer_bytes;
+        let max_line = self.opts.max_line_bytes;
+        self.for_each_planned_file(|job| {
+            let mut reader = crate::util::open_maybe_compressed(&job.path, read_buf)
+                .with_context(|| format!("open {}", job.path.display()))?;
+            for_each_raw_line_in(&mut *reader, max_line, &f)
+                .with_context(|| format!("read raw lines from {}", job.path.display()))
+        })
+    }
+}
+
+/// Split `reader` on `\n` and hand each non-empty line (minus a trailing
+/// `\r`) to `f`, borrowing from the reader's buffer when the whole line is
+/// in it and from one reused spill buffer otherwise.
+fn for_each_raw_line_in(
+    reader: &mut dyn std::io::BufRead,
+    max_line: usize,
+    f: &dyn Fn(&[u8]),
+) -> Result<()> {
+    let emit = |line: &[u8]| {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if !line.is_empty() {
+            f(line);
+        }
+    };
+    let mut spill: Vec<u8> = Vec::new();
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        let used = match available.iter().position(|&b| b == b'\n') {
+            Some(i) if spill.is_empty() => {
+                emit(&available[..i]);
+                i + 1
+            }
+            Some(i) => {
+                spill.extend_from_slice(&available[..i]);
+                emit(&spill);
+                spill.clear();
+                i + 1
+            }
+            None => {
+                spill.extend_from_slice(available);
+                available.len()
+            }
+        };
+        reader.consume(used);
+        if spill.len() > max_line {
+            anyhow::bail!("line exceeds the {max_line}-byte line cap");
+        }
+    }
+    emit(&spill);
+    Ok(())
+}
@@ -105,6 +105,53 @@ fn build_resume_fingerprint(
     files: &[FileJob],
 ) -> Result<String> {
     let zst_level = (operation == "partitioned-zst").then_some(etl.opts.zst_level);
+    let zstd_dictionary_id = (operation == "partitioned-zst")
+        .then(|| etl.opts.zstd_dictionary.as_ref().map(|d| d.id()))
+        .flatten();
+    // The query block is built on its own to keep `json!` under the macro
+    // recursion limit.
+    let query_fingerprint = serde_json::json!({
+        "subreddits": query.subreddits.as_ref(),
+        "subreddits_out": query.subreddits_out.as_ref(),
+        "ids_in": query.ids_in.as_ref(),
+        "comment_ids_in": query.comment_ids_in.as_ref(),
+        "submission_ids_in": query.submission_ids_in.as_ref(),
+        "authors_in": query.authors_in.as_ref(),
+        "authors_out": query.authors_out.as_ref(),
+        "exclude_common_bots": query.exclude_common_bots,
+        "author_regex": query.author_regex.as_ref().map(|re| re.as_str()),
+        "author_regex_pattern": query.author_regex_pattern.as_ref(),
+        "min_score": query.min_score,
+        "max_score": query.max_score,
+        "timestamp_bounds": {
+            "created_utc_gte": query.timestamp_bounds.created_utc_gte,
+            "created_utc_lt": query.timestamp_bounds.created_utc_lt,
+        },
+        "time_field": query.time_field.as_str(),
+        "keywords_any": query.keywords_any.as_ref(),
+        "keywords_all": query.keywords_all.as_ref(),
+        "keywords_exclude": query.keywords_exclude.as_ref(),
+        "text_regex": query.text_regex.as_ref().map(|re| re.as_str()),
+        "text_regex_pattern": query.text_regex_pattern.as_ref(),
+        "exclude_bot_bodies": query.exclude_bot_bodies,
+        "body_regex_exclude": query.body_regex_exclude.iter().map(|re| re.as_str()).collect::<Vec<_>>(),
+        "domains_in": query.domains_in.as_ref(),
+        "contains_url": query.contains_url,
+        "no_url": query.no_url,
+        "json_predicates": query.json_predicates_fingerprint(),
+        "filter_pseudo_users": query.filter_pseudo_users,
+        "top_level_comments_only": query.top_level_comments_only,
+        "subreddit_sample": query.subreddit_sample.map(|s| serde_json::json!({
+            "fraction": s.fraction,
+            "seed": s.seed,
+        })),
+        "dedupe_records_by": query.dedupe_records.as_deref().map(dedupe_key_label),
+        "filter": query.filter.is_some(),
+        "transform": query.transform.is_some(),
+        "normalize_edited": query.normalize_edited.as_str(),
+        "canonicalize_subreddit_case": query.canonicalize_subreddit_case,
+        "source_ratio": query.source_ratio.map(|r| [r.comments, r.submissions]),
+    });
     let input = serde_json::json!({
         "operation": operation,
         "corpus_paths": {
@@ -119,36 +166,17 @@ fn build_resume_fingerprint(
         "legacy_subreddit": etl.opts.subreddit.as_ref(),
         "whitelist_fields": etl.opts.whitelist_fields.as_ref(),
         "strict_whitelist": etl.opts.strict_whitelist,
+        "whitelist_source_order": etl.opts.whitelist_source_order,
         "human_readable_timestamps": etl.opts.human_readable_timestamps,
+        "canonicalize_json": etl.opts.canonicalize_json,
+        "add_provenance": etl.opts.add_provenance,
+        "strict_json": etl.opts.strict_json,
+        "max_output_bytes": etl.opts.max_output_bytes,
+        "keep_empty_outputs": etl.opts.keep_empty_outputs,
         "zst_level": zst_level,
+        "zstd_dictionary_id": zstd_dictionary_id,
         "limit": limit,
-        "query": {
-            "subreddits": query.subreddits.as_ref(),
-            "ids_in": query.ids_in.as_ref(),
-            "comment_ids_in": query.comment_ids_in.as_ref(),
-            "submission_ids_in": query.submission_ids_in.as_ref(),
-            "authors_in": query.authors_in.as_ref(),
-            "authors_out": query.authors_out.as_ref(),
-            "exclude_common_bots": query.exclude_common_bots,
-            "author_regex": query.author_regex.as_ref().map(|re| re.as_str()),
-            "author_regex_pattern": query.author_regex_pattern.as_ref(),
-            "min_score": query.min_score,
-            "max_score": query.max_score,
-            "timestamp_bounds": {
-                "created_utc_gte": query.timestamp_bounds.created_utc_gte,
-                "created_utc_lt": query.timestamp_bounds.created_utc_lt,
-            },
-            "keywords_any": query.keywords_any.as_ref(),
-            "keywords_all": query.keywords_all.as_ref(),
-            "keywords_exclude": query.keywords_exclude.as_ref(),
-            "text_regex": query.text_regex.as_ref().map(|re| re.as_str()),
-            "text_regex_pattern": query.text_regex_pattern.as_ref(),
-            "domains_in": query.domains_in.as_ref(),
-            "contains_url": query.contains_url,
-            "no_url": query.no_url,
-            "json_predicates": query.json_predicates_fingerprint(),
-            "filter_pseudo_users": query.filter_pseudo_users,
-        }
+        "query": query_fingerprint,
     });
     let bytes = serde_json::to_vec(&input).context("serialize resume fingerprint input")?;
     Ok(stable_fnv1a_hex(&bytes))
@@ -0,0 +1,276 @@
+// Corpus group-by: `ScanPlan::for_each_group` routes matching records to
+// on-disk shards by key, then groups each shard in memory with the stage-3
+// micro-bucketing so the caller receives `(key, records)` groups.
+// `ScanPlan::author_sessions` builds on the same shards to split each
+// author's records into activity sessions.
+
+/// Micro-buckets per shard handed to [`process_bucket_streaming`] by
+/// [`ScanPlan::for_each_group`].
+const GROUP_MICRO_BUCKETS: usize = 64;
+
+/// Shard file name under the group-by scratch dir.
+fn group_shard_file_name(idx: usize) -> String {
+    format!("group_{idx:04}.jsonl")
+}
+
+/// Routing key for one matched record. The built-in author/subreddit keys
+/// reuse the fast-path parse the filter already did.
+fn group_key(key: &KeyExtractor, min: &MinimalRecord, line: &str) -> Result<Option<String>> {
+    match key {
+        KeyExtractor::AuthorLowerFast => Ok(min.author.as_deref().map(normalize_author)),
+        KeyExtractor::SubredditLowerFast => Ok(min.subreddit.as_deref().map(str::to_lowercase)),
+        _ => key.key_from_line(line),
+    }
+}
+
+impl ScanPlan {
+    /// Group matching records by `key` and call `on_group(key, records)` for
+    /// each group, without driving the bucketing stages by hand.
+    ///
+    /// Matching records are first routed by key into `shard_count` scratch
+    /// shards under `work_dir` (the stage 1/2 shuffle, done during the scan),
+    /// then each shard is grouped with [`process_bucket_streaming`] under
+    /// `cfg`. Records are the raw corpus lines; as with
+    /// [`ScanPlan::for_each_record`], whitelist and timestamp options do not
+    /// apply. Records whose key is missing are dropped with a warning.
+    ///
+    /// Same contract as `process_bucket_streaming`: when memory is tight or
+    /// the inflight caps are reached, one key may be delivered in **several
+    /// partial groups**, so merge by key downstream if you need whole groups.
+    /// A key's groups all come from one shard and are delivered before the
+    /// next shard starts; key order is otherwise unspecified. `on_group` is
+    /// called from one thread at a time and an error from it aborts the run.
+    /// This method does not consult `resume`.
+    pub fn for_each_group<F>(self, key: KeyExtractor, cfg: &BucketingCfg, on_group: F) -> Result<(), RetlError>
+    where
+        F: FnMut(&str, Vec<String>) -> Result<()> + Send,
+    {
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        Ok(with_thread_pool(plan.etl.opts.parallelism, || {
+            plan.group_by_shard(key, cfg, on_group, || Ok(()))
+        })?)
+    }
+
+    /// [`ScanPlan::for_each_group`] on an already built plan, calling
+    /// `on_shard_done` after the last group of each shard (every group of a
+    /// key has been delivered by then). Callers enter the thread pool.
+    fn group_by_shard<F, D>(
+        &self,
+        key: KeyExtractor,
+        cfg: &BucketingCfg,
+        mut on_group: F,
+        mut on_shard_done: D,
+    ) -> Result<()>
+    where
+        F: FnMut(&str, Vec<String>) -> Result<()> + Send,
+        D: FnMut() -> Result<()>,
+    {
+        let plan = self;
+        let work_dir = plan.etl.ensure_work_dir()?;
+        let scratch = crate::util::unique_scratch_dir(&work_dir, "group_by", "shards");
+        crate::util::create_dir_all_with_default_backoff(&scratch)
+            .with_context(|| format!("creating group-by work dir {}", scratch.display()))?;
+
+        let result = (|| -> Result<()> {
+            let shard_count = clamp_shard_count(
+                plan.etl.opts.shard_count.unwrap_or(MAX_SHARDS),
+                "ScanPlan::for_each_group",
+            );
+            let shards = create_line_shard_writers(
+                &scratch,
+                shard_count,
+                group_shard_file_name,
+                "group-by shard",
+            )?;
+            let state = seeded_state("group_by");
+            let dropped = AtomicU64::new(0);
+            scan_records(
+                &plan.etl,
+                &plan.query,
+                /*show_progress=*/ true,
+                plan.limit,
+                |min, _kind, line| match group_key(&key, min, line)? {
+                    Some(k) => shards.write_parts(
+                        shard_index(&state, &k, shard_count),
+                        &[line.as_bytes(), b"\n"],
+                    ),
+                    None => {
+                        dropped.fetch_add(1, Ordering::Relaxed);
+                        Ok(())
+                    }
+                },
+            )?;
+            shards.flush_all()?;
+            drop(shards);
+
+            let dropped = dropped.into_inner();
+            if dropped > 0 {
+                tracing::warn!(
+                    key = %dedupe_key_label(&key),
+                    dropped_records = dropped,
+                    "for_each_group dropped {} matching record(s) without an extractable key",
+                    dropped,
+                );
+            }
+
+            for idx in 0..shard_count {
+                process_bucket_streaming(
+                    &scratch.join(group_shard_file_name(idx)),
+                    GROUP_MICRO_BUCKETS,
+                    cfg,
+                    &mut on_group,
+                    &key,
+                    #[cfg(feature = "test-utils")]
+                    None,
+                )?;
+                on_shard_done()?;
+            }
+            Ok(())
+        })();
+
+        cleanup_scratch_dir(&scratch, "group-by");
+        result
+    }
+}
+
+/// One run of an author's activity: first and last `created_utc` and the
+/// records in between.
+#[derive(Clone, Copy)]
+struct AuthorSession {
+    start: i64,
+    end: i64,
+    records: u64,
+}
+
+/// Split sorted timestamps into sessions wherever consecutive records are
+/// more than `gap_secs` apart.
+fn sessions_from_sorted(times: &[i64], gap_secs: i64, out: &mut Vec<AuthorSession>) {
+    let mut iter = times.iter().copied();
+    let Some(first) = iter.next() else {
+        return;
+    };
+    let mut cur = AuthorSession {
+        start: first,
+        end: first,
+        records: 1,
+    };
+    for t in iter {
+        if t.saturating_sub(cur.end) > gap_secs {
+            out.push(cur);
+            cur = AuthorSession {
+                start: t,
+                end: t,
+                records: 0,
+            };
+        }
+        cur.end = t;
+        cur.records += 1;
+    }
+    out.push(cur);
+}
+
+/// Merge sessions built from separate partial groups of one author. Sorted
+/// by start, a session joins the previous one when it overlaps it or starts
+/// within `gap_secs` of its end, which is exactly how the union of the
+/// groups' records would have split.
+fn merge_sessions(sessions: &mut Vec<AuthorSession>, gap_secs: i64) {
+    sessions.sort_unstable_by_key(|s| (s.start, s.end));
+    let mut merged: Vec<AuthorSession> = Vec::with_capacity(sessions.len());
+    for s in sessions.drain(..) {
+        match merged.last_mut() {
+            Some(cur) if s.start.saturating_sub(cur.end) <= gap_secs => {
+                cur.end = cur.end.max(s.end);
+                cur.records += s.records;
+            }
+            _ => merged.push(s),
+        }
+    }
+    *sessions = merged;
+}
+
+impl ScanPlan {
+    /// Split each author's matching records into activity sessions and write
+    /// one `author\tstart\tend\trecords` row per session to `out_path`: a
+    /// new session starts whenever an author's next record (by
+    /// `created_utc`) is more than `gap_secs` seconds after the previous one.
+    /// `start`/`end` are the first and last record's `created_utc`.
+    ///
+    /// Records are grouped by normalized author through the same sharded
+    /// bucketing as [`ScanPlan::for_each_group`] (configured from these
+    /// options), then sorted by time per group. A key split into several
+    /// partial groups under memory pressure still yields the sessions of its
+    /// whole record set. Rows are grouped by shard; within a shard they are
+    /// sorted by author, then start. Records without an author or a numeric
+    /// `created_utc` are skipped. Authors are escaped like the other author
+    /// TSVs (see [`unescape_kv_key`](crate::unescape_kv_key)). A negative
+    /// `gap_secs` is treated as `0`.
+    /// This method does not consult `resume`.
+    pub fn author_sessions(self, gap_secs: i64, out_path: &Path) -> Result<(), RetlError> {
+        let gap_secs = gap_secs.max(0);
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let manifest_start = RunManifestStart::now();
+        let files = plan_pipeline_files(&plan.etl, Some(&plan.query))?;
+        let cfg = BucketingCfg::from(&plan.etl.opts);
+        let pending = Mutex::new(HashMap::<String, Vec<AuthorSession>>::new());
+        let mut matched_records = 0_u64;
+        let mut sessions_written = 0_u64;
+        with_thread_pool(plan.etl.opts.parallelism, || {
+            write_at_path_atomic(out_path, plan.etl.opts.write_buffer_bytes, |out| {
+                plan.group_by_shard(
+                    KeyExtractor::AuthorLowerFast,
+                    &cfg,
+                    |author, records| {
+                        let mut times: Vec<i64> = records
+                            .iter()
+                            .filter_map(|line| parse_minimal(line).ok()?.created_utc)
+                            .collect();
+                        times.sort_unstable();
+                        let mut pending = pending.lock().unwrap();
+                        let sessions = pending.entry(author.to_string()).or_default();
+                        sessions_from_sorted(&times, gap_secs, sessions);
+                        Ok(())
+                    },
+                    || {
+                        let mut shard: Vec<_> = pending.lock().unwrap().drain().collect();
+                        shard.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+                        for (author, mut sessions) in shard {
+                            merge_sessions(&mut sessions, gap_secs);
+                            let author = crate::kv_shard::escape_kv_key(&author);
+                            for s in sessions {
+                                writeln!(out, "{author}\t{}\t{}\t{}", s.start, s.end, s.records)?;
+                                matched_records += s.records;
+                                sessions_written += 1;
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+            })
+        })?;
+
+        let manifest = scan_manifest_input(
+            manifest_start,
+            "scan.author_sessions",
+            "tsv",
+            &plan.etl,
+            &plan.query,
+            &files,
+            plan.limit,
+            manifest_counts(&[
+                ("matched_records", matched_records),
+                ("output_rows", sessions_written),
+            ]),
+            None,
+            None,
+            serde_json::json!({ "gap_secs": gap_secs }),
+        );
+        maybe_write_run_manifest(
+            plan.etl.opts.emit_manifest,
+            manifest,
+            ManifestDestination::File(out_path.to_path_buf()),
+        )?;
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 
 fn complete_stream_job(job: &FileJob, result: StreamJobResult) -> Result<u64> {
+    checked_stream_job(job, result).map(|r| r.written)
+}
+
+/// [`complete_stream_job`] for callers that also want the record stats.
+fn checked_stream_job(job: &FileJob, result: StreamJobResult) -> Result<StreamJobResult> {
     if result.complete {
-        Ok(result.written)
+        Ok(result)
     } else {
         Err(PartialScanError {
             path: job.path.clone(),
@@ -11,18 +16,40 @@ fn complete_stream_job(job: &FileJob, result: StreamJobResult) -> Result<u64> {
     }
 }
 
-fn record_limit_from(limit: Option<u64>) -> Option<Arc<RecordLimit>> {
-    limit.map(|n| Arc::new(RecordLimit::new(n)))
+fn record_limit_from(limit: Option<u64>, max_bytes: Option<u64>) -> Option<Arc<RecordLimit>> {
+    record_limit_from_with_claimed(limit, max_bytes, &HashMap::new())
 }
 
-fn record_limit_from_with_claimed(limit: Option<u64>, claimed: u64) -> Option<Arc<RecordLimit>> {
-    limit.map(|n| Arc::new(RecordLimit::new_with_claimed(n, claimed)))
+/// Shared stop condition for a run with a record `limit` and/or an output
+/// byte budget. On resume the records and bytes of the months already
+/// committed in `completed` count towards both.
+fn record_limit_from_with_claimed(
+    limit: Option<u64>,
+    max_bytes: Option<u64>,
+    completed: &HashMap<String, MonthEntry>,
+) -> Option<Arc<RecordLimit>> {
+    if limit.is_none() && max_bytes.is_none() {
+        return None;
+    }
+    let claimed_lines = committed_line_count(completed);
+    let claimed_bytes = committed_output_bytes(completed);
+    let limit = RecordLimit::new_with_claimed(limit.unwrap_or(u64::MAX), claimed_lines);
+    Some(Arc::new(limit.with_byte_budget(max_bytes, claimed_bytes)))
 }
 
 fn committed_line_count(months: &HashMap<String, MonthEntry>) -> u64 {
     months.values().map(|entry| entry.lines).sum()
 }
 
+/// Uncompressed output bytes of committed months. Entries written before
+/// `output_bytes` was recorded fall back to their on-disk size.
+fn committed_output_bytes(months: &HashMap<String, MonthEntry>) -> u64 {
+    months
+        .values()
+        .map(|entry| entry.output_bytes.unwrap_or(entry.size))
+        .sum()
+}
+
 fn is_partial_scan_error(e: &anyhow::Error) -> bool {
     e.chain()
         .any(|cause| cause.downcast_ref::<PartialScanError>().is_some())
@@ -4,12 +4,16 @@ include!("scan.rs");
 include!("fingerprint.rs");
 include!("checkpoint.rs");
 include!("scan_records.rs");
+include!("source_ratio.rs");
 include!("resume_run.rs");
 include!("extract_common.rs");
 include!("spool.rs");
 include!("partitioned.rs");
+include!("partitioned_by_key.rs");
 include!("extract.rs");
+include!("extract_writer.rs");
 include!("dedupe_keys.rs");
+include!("dedupe_records.rs");
 include!("tabular_format/selector.rs");
 include!("tabular_format/writer.rs");
 include!("tabular/parts.rs");
@@ -18,6 +22,12 @@ include!("tabular/extract.rs");
 include!("tabular/convert.rs");
 include!("analytics.rs");
 include!("usernames.rs");
+include!("records.rs");
+include!("group_by.rs");
+include!("dry_run.rs");
+include!("estimate.rs");
+include!("file_map.rs");
+include!("attach_parents.rs");
 
 #[cfg(test)]
 mod tests;
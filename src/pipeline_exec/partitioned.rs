@@ -17,6 +17,7 @@ fn partitioned_resume_operation(format: ExportFormat) -> &'static str {
         ExportFormat::Jsonl => "partitioned-jsonl",
         ExportFormat::Zst => "partitioned-zst",
         ExportFormat::Parquet => "partitioned-parquet",
+        ExportFormat::Bzip2 { .. } => "partitioned-bzip2",
     }
 }
 
@@ -25,6 +26,7 @@ fn partitioned_ext(format: ExportFormat) -> &'static str {
         ExportFormat::Jsonl => "jsonl",
         ExportFormat::Zst => "zst",
         ExportFormat::Parquet => "parquet",
+        ExportFormat::Bzip2 { .. } => "bz2",
     }
 }
 
@@ -147,6 +149,11 @@ fn validate_partitioned_resume_output(
             // before the rename), so a size match is sufficient evidence that
             // the same publish recorded by `_progress.json` is still on disk.
         }
+        ExportFormat::Bzip2 { .. } => {
+            // Size-only for the same reason as Parquet: the bzip2 stream was
+            // finished before the atomic rename, so a size match identifies
+            // the publish recorded by `_progress.json`.
+        }
     }
     Ok(())
 }
@@ -170,39 +177,39 @@ struct PartitionedJobCtx<'a> {
     out_base_dir: &'a Path,
     staging_dir: &'a Path,
     format: ExportFormat,
-    targets: Option<&'a Vec<String>>,
-    query: &'a QuerySpec,
-    whitelist: &'a Option<Vec<String>>,
-    pb: Option<&'a ProgressBar>,
-    bounds: Option<DateBounds>,
-    read_buf: usize,
+    stream: StreamJobCtx<'a>,
     write_buf: usize,
-    human_ts: bool,
     zst_level: i32,
+    zstd_dictionary: Option<&'a ZstdDictionary>,
     parquet_row_group_size: usize,
     parquet_compression: &'a str,
-    whitelist_tracker: Option<&'a WhitelistMatchTracker>,
-    record_limit: Option<&'a RecordLimit>,
-    allow_partial: bool,
-    partial_reporter: Option<&'a crate::config::PartialReadReporter>,
+    keep_empty_outputs: bool,
     resume: bool,
     completed_keys: &'a HashSet<String>,
     accumulator: Option<&'a ManifestAccumulator>,
     output_records: &'a AtomicU64,
     output_files: &'a AtomicU64,
+    /// Record count and `created_utc` range per partition published by this
+    /// run, for the run manifest's `output_files`.
+    produced: &'a Mutex<ProducedFiles>,
 }
 
 /// Per-file body for partitioned exports. Returns `Ok(())` on resume-skip,
 /// abort-flag skip, a successful publish, a zero-record month (staged then
 /// discarded, never published), or a tolerated zstd partial-scan skip (already
 /// logged). Single `stream_job_with_partial_policy` call site dispatched by
-/// `format` — the writer wrapper (`write_jsonl_atomic_if` vs
-/// `write_zst_atomic_if`) is the only thing that differs between branches.
+/// `format` — the writer wrapper (`write_jsonl_atomic_if`,
+/// `write_zst_atomic_if`, `write_bz2_atomic_if`, or the parquet writer) is the
+/// only thing that differs between branches.
 fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result<()> {
     let key = export_part_key(job);
     let out_path = partitioned_output_path(ctx.out_base_dir, job, ctx.format);
 
-    if ctx.record_limit.is_some_and(|limit| limit.is_exhausted()) {
+    if ctx
+        .stream
+        .record_limit
+        .is_some_and(|limit| limit.is_exhausted())
+    {
         return Ok(());
     }
 
@@ -215,29 +222,19 @@ fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result
     }
 
     if ctx.resume && ctx.completed_keys.contains(&key) {
-        if let Some(pb) = ctx.pb {
+        if let Some(pb) = ctx.stream.pb {
             let sz = fs::metadata(&job.path).map(|m| m.len()).unwrap_or(0);
             pb.inc(sz);
         }
         return Ok(());
     }
 
+    // The parquet writer's body must return a plain count, so the created_utc
+    // range and output byte count travel out through this cell instead.
+    let stream_stats = std::cell::Cell::new((None, 0));
     let stream = |w: &mut dyn Write| -> Result<u64> {
-        let result = stream_job_with_partial_policy(
-            job,
-            w,
-            ctx.targets,
-            ctx.query,
-            ctx.whitelist,
-            ctx.pb.cloned(),
-            ctx.bounds,
-            ctx.read_buf,
-            ctx.human_ts,
-            ctx.whitelist_tracker,
-            ctx.allow_partial,
-            ctx.partial_reporter,
-            ctx.record_limit,
-        )?;
+        let result = stream_job_with_partial_policy(job, w, &ctx.stream)?;
+        stream_stats.set((result.created_utc_range, result.output_bytes));
         complete_stream_job(job, result)
     };
 
@@ -247,8 +244,9 @@ fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result
     // never renamed onto the published path. This avoids the publish-then-
     // delete race where a best-effort `remove` failing after the rename would
     // strand a non-empty zero-record file at the published path while the
-    // manifest records `size: 0`.
-    let should_publish = |&n: &u64| n > 0;
+    // manifest records `size: 0`. `keep_empty_outputs` publishes the staged
+    // empty output as well, so every planned month has a file.
+    let should_publish = |&n: &u64| n > 0 || ctx.keep_empty_outputs;
     let written_result = match ctx.format {
         ExportFormat::Jsonl => write_jsonl_atomic_if(
             ctx.staging_dir,
@@ -261,6 +259,7 @@ fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result
             ctx.staging_dir,
             &out_path,
             ctx.zst_level,
+            ctx.zstd_dictionary,
             ctx.write_buf,
             should_publish,
             stream,
@@ -274,26 +273,40 @@ fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result
             should_publish,
             stream,
         ),
+        ExportFormat::Bzip2 { level } => write_bz2_atomic_if(
+            ctx.staging_dir,
+            &out_path,
+            level,
+            ctx.write_buf,
+            should_publish,
+            stream,
+        ),
     };
 
     let written = match written_result {
         Ok(n) => n,
-        Err(e) if ctx.allow_partial && is_partial_scan_error(&e) => {
+        Err(e) if ctx.stream.allow_partial && is_partial_scan_error(&e) => {
             tracing::warn!(path=%job.path.display(), error=%e, "Skipping partitioned export month after zstd decode error; staged output was discarded");
             return Ok(());
         }
         Err(e) => return Err(e),
     };
 
+    let (created_utc_range, output_bytes) = stream_stats.get();
     ctx.output_records.fetch_add(written, Ordering::Relaxed);
-    if written > 0 {
+    let published = written > 0 || ctx.keep_empty_outputs;
+    if published {
         ctx.output_files.fetch_add(1, Ordering::Relaxed);
+        ctx.produced
+            .lock()
+            .unwrap()
+            .insert(out_path.clone(), (written, created_utc_range));
     }
     if let Some(acc) = ctx.accumulator {
-        let size = if written == 0 {
-            0
-        } else {
+        let size = if published {
             fs::metadata(&out_path).map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
         };
         acc.commit(
             key,
@@ -301,6 +314,7 @@ fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result
                 size,
                 lines: written,
                 sha256: None,
+                output_bytes: Some(output_bytes),
             },
         )
         .context(
@@ -310,19 +324,26 @@ fn process_partitioned_job(job: &FileJob, ctx: &PartitionedJobCtx<'_>) -> Result
     Ok(())
 }
 
-/// Build + persist the run-manifest tail for `export_partitioned`.
+/// Build + persist the run-manifest tail for `export_partitioned`. Counts
+/// and published partitions come from the finished run's `ctx`; `resumed`
+/// lists the partitions a resumed run kept, with their line counts.
 fn emit_partitioned_resume_manifest(
     start: RunManifestStart,
     plan: &PreparedScan<'_>,
     files: &[FileJob],
-    format: ExportFormat,
-    out_base_dir: &Path,
-    resume: bool,
+    ctx: &PartitionedJobCtx<'_>,
     resume_fingerprint: &str,
-    output_records: u64,
-    output_files: u64,
+    resumed: impl IntoIterator<Item = (PathBuf, u64)>,
 ) -> Result<()> {
-    let manifest = scan_manifest_input(
+    let PartitionedJobCtx {
+        out_base_dir,
+        format,
+        resume,
+        ..
+    } = *ctx;
+    let output_records = ctx.output_records.load(Ordering::Relaxed);
+    let output_files = ctx.output_files.load(Ordering::Relaxed);
+    let mut manifest = scan_manifest_input(
         start,
         "scan.export_partitioned",
         partitioned_resume_operation(format),
@@ -339,10 +360,19 @@ fn emit_partitioned_resume_manifest(
         serde_json::json!({
             "partition_format": partitioned_ext(format),
             "zst_level": (format == ExportFormat::Zst).then_some(plan.etl.opts.zst_level),
+            "zstd_dictionary_id": (format == ExportFormat::Zst)
+                .then(|| plan.etl.opts.zstd_dictionary.as_ref().map(|d| d.id()))
+                .flatten(),
             "parquet_row_group_size": (format == ExportFormat::Parquet).then_some(plan.etl.opts.parquet_row_group_size),
             "parquet_compression": (format == ExportFormat::Parquet).then_some(plan.etl.opts.parquet_compression.clone()),
+            "bzip2_level": match format {
+                ExportFormat::Bzip2 { level } => Some(level),
+                _ => None,
+            },
         }),
     );
+    let produced = std::mem::take(&mut *ctx.produced.lock().unwrap());
+    manifest.output_files = month_output_files(out_base_dir, produced, resumed);
     maybe_write_run_manifest(
         plan.etl.opts.emit_manifest,
         manifest,
@@ -359,7 +389,8 @@ struct PreparedScan<'a> {
 }
 
 impl ScanPlan {
-    /// Export corpus back to partitioned JSONL or ZST by month/kinds with query filters.
+    /// Export corpus back to partitioned JSONL, ZST, Parquet, or bzip2 by
+    /// month/kinds with query filters.
     /// This lives on ScanPlan (advanced query mode).
     ///
     /// Each output is staged as a unique
@@ -367,8 +398,13 @@ impl ScanPlan {
     /// closed with checksum, or buffer flushed) and atomically renamed onto its
     /// final path. Stale `*.inprogress` from a crashed prior run are swept on
     /// entry only when their owner PID is no longer live.
-    pub fn export_partitioned(self, out_base_dir: &Path, format: ExportFormat) -> Result<()> {
-        let plan = self.build()?;
+    pub fn export_partitioned(self, out_base_dir: &Path, format: ExportFormat) -> Result<(), RetlError> {
+        if let ExportFormat::Bzip2 { level } = format {
+            if !(1..=9).contains(&level) {
+                return Err(anyhow!("bzip2 export level must be between 1 and 9, got {level}").into());
+            }
+        }
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         validate_export_whitelist(&plan.etl)?;
         let targets = resolve_target_subs_from(&plan.etl.opts.subreddit, &plan.query.subreddits);
@@ -379,7 +415,7 @@ impl ScanPlan {
             limit: plan.limit,
         };
 
-        with_thread_pool(parallelism, || {
+        Ok(with_thread_pool(parallelism, || {
             let manifest_start = RunManifestStart::now();
             let files = plan_pipeline_files(prepared.etl, Some(prepared.query))?;
             warn_if_unfiltered_undated_query(prepared.etl, prepared.query, &files);
@@ -442,10 +478,16 @@ impl ScanPlan {
             // `validate_partitioned_resume_output` when `lines == 0 &&
             // !path.exists()`), which never incremented `output_files` on the
             // fresh run — counting them here would make a resumed run's
-            // `partition_files` total over-count versus a fresh run.
-            let resumed_output_files =
-                initial_months.values().filter(|e| e.lines > 0).count() as u64;
+            // `partition_files` total over-count versus a fresh run. With
+            // `keep_empty_outputs` (part of the fingerprint) every committed
+            // month published one.
+            let keep_empty_outputs = prepared.etl.opts.keep_empty_outputs;
+            let resumed_output_files = initial_months
+                .values()
+                .filter(|e| keep_empty_outputs || e.lines > 0)
+                .count() as u64;
             let output_files = AtomicU64::new(resumed_output_files);
+            let produced = Mutex::new(HashMap::new());
 
             let whitelist = prepared.etl.opts.whitelist_fields.clone();
             let whitelist_tracker = whitelist.as_ref().map(|fields| {
@@ -454,12 +496,23 @@ impl ScanPlan {
                     fields.iter().cloned(),
                 ))
             });
-            let record_limit = record_limit_from_with_claimed(prepared.limit, resumed_lines);
+            let record_limit = record_limit_from_with_claimed(
+                prepared.limit,
+                prepared.etl.opts.max_output_bytes,
+                &initial_months,
+            );
+            let duplicates = collect_duplicate_records(
+                prepared.etl,
+                prepared.query,
+                targets.as_ref(),
+                &files,
+            )?;
             let total_bytes = total_compressed_size(&files);
             let pb = if prepared.etl.opts.progress {
-                Some(make_progress_bar_labeled(
+                Some(make_stage_progress_bar(
                     total_bytes,
                     prepared.etl.opts.progress_label.as_deref(),
+                    prepared.etl.opts.progress_stage.as_ref(),
                 ))
             } else {
                 None
@@ -469,26 +522,27 @@ impl ScanPlan {
                 out_base_dir,
                 staging_dir: &staging_dir,
                 format,
-                targets: targets.as_ref(),
-                query: prepared.query,
-                whitelist: &whitelist,
-                pb: pb.as_ref(),
-                bounds: bounds_tuple(prepared.etl.opts.start, prepared.etl.opts.end),
-                read_buf: prepared.etl.opts.read_buffer_bytes,
+                stream: StreamJobCtx {
+                    targets: targets.as_ref(),
+                    whitelist: &whitelist,
+                    pb: pb.as_ref(),
+                    whitelist_tracker: whitelist_tracker.as_deref(),
+                    record_limit: record_limit.as_deref(),
+                    record_dedupe: duplicates.as_ref().map(RecordDedupe::Drop),
+                    ..StreamJobCtx::from_options(&prepared.etl.opts, prepared.query)
+                },
                 write_buf: prepared.etl.opts.write_buffer_bytes,
-                human_ts: prepared.etl.opts.human_readable_timestamps,
                 zst_level: prepared.etl.opts.zst_level,
+                zstd_dictionary: prepared.etl.opts.zstd_dictionary.as_ref(),
                 parquet_row_group_size: prepared.etl.opts.parquet_row_group_size,
                 parquet_compression: &prepared.etl.opts.parquet_compression,
-                whitelist_tracker: whitelist_tracker.as_deref(),
-                record_limit: record_limit.as_deref(),
-                allow_partial: prepared.etl.opts.allow_partial,
-                partial_reporter: Some(&prepared.etl.opts.partial_read_reporter),
+                keep_empty_outputs: prepared.etl.opts.keep_empty_outputs,
                 resume,
                 completed_keys: &completed_keys,
                 accumulator: accumulator.as_ref(),
                 output_records: &output_records,
                 output_files: &output_files,
+                produced: &produced,
             };
 
             crate::concurrency::for_each_file_limited(
@@ -500,7 +554,7 @@ impl ScanPlan {
             finalize_whitelist_strict(whitelist_tracker.as_deref(), out_base_dir, || {
                 clear_partitioned_resume_outputs(out_base_dir, format)
             })?;
-            if let Some(pb) = pb {
+            if let Some(pb) = &pb {
                 pb.finish_with_message("done");
             }
             ensure_resume_manifest_durable(accumulator.as_ref(), "partitioned export")?;
@@ -508,13 +562,13 @@ impl ScanPlan {
                 manifest_start,
                 &prepared,
                 &files,
-                format,
-                out_base_dir,
-                resume,
+                &ctx,
                 &resume_fingerprint,
-                output_records.load(Ordering::Relaxed),
-                output_files.load(Ordering::Relaxed),
+                initial_months.iter().filter_map(|(key, entry)| {
+                    let path = partitioned_output_path_for_key(out_base_dir, key, format)?;
+                    Some((path, entry.lines))
+                }),
             )
-        })
+        })?)
     }
 }
@@ -0,0 +1,525 @@
+// Key-partitioned export: `ScanPlan::export_partitioned_by` routes each
+// matching record to `<out>/<key>/data.<ext>` instead of one file per
+// (kind, month).
+
+/// Most per-key staging files [`ScanPlan::export_partitioned_by`] keeps open
+/// at once. When more keys are live, the least recently written one is
+/// flushed and closed, and reopened for append on its next record. The
+/// effective cap is also held to a quarter of the open-file budget (see
+/// [`set_open_file_budget`](crate::set_open_file_budget)).
+pub const MAX_OPEN_KEY_PARTITIONS: usize = 128;
+
+/// Partition directory for records whose key is missing, null, or empty.
+/// Real keys never start with `_` once escaped, so this cannot collide.
+const UNKEYED_PARTITION_DIR: &str = "_unkeyed";
+/// File name (before the format extension) inside each partition directory.
+const KEYED_PARTITION_STEM: &str = "data";
+/// Escaped keys longer than this are truncated and suffixed with a hash of
+/// the full key so the directory name fits common filesystem limits.
+const MAX_PARTITION_DIR_NAME: usize = 160;
+/// Routed lines a file worker buffers before handing them to the staging
+/// writers, so the shared lock is taken per batch rather than per record.
+const ROUTE_BATCH_BYTES: usize = 1024 * 1024;
+
+/// Where a key-routed export publishes each key's records.
+#[derive(Clone, Copy)]
+enum KeyedLayout {
+    /// `<out>/<key>/data.<ext>`, for [`ScanPlan::export_partitioned_by`].
+    PartitionDirs,
+    /// `<out>/<key>.<ext>`, for [`ScanPlan::extract_split_by_subreddit`].
+    Files,
+}
+
+impl KeyedLayout {
+    fn name(self) -> &'static str {
+        match self {
+            KeyedLayout::PartitionDirs => "export_partitioned_by",
+            KeyedLayout::Files => "extract_split_by_subreddit",
+        }
+    }
+
+    fn operation(self) -> &'static str {
+        match self {
+            KeyedLayout::PartitionDirs => "scan.export_partitioned_by",
+            KeyedLayout::Files => "scan.extract_split_by_subreddit",
+        }
+    }
+
+    /// Output path for the partition named `name`, creating its directory.
+    fn out_path(self, out_base_dir: &Path, name: &str, ext: &str) -> Result<PathBuf> {
+        match self {
+            KeyedLayout::PartitionDirs => {
+                let dir = out_base_dir.join(name);
+                crate::util::create_dir_all_with_default_backoff(&dir)
+                    .with_context(|| format!("creating partition dir {}", dir.display()))?;
+                Ok(dir.join(format!("{KEYED_PARTITION_STEM}.{ext}")))
+            }
+            KeyedLayout::Files => Ok(out_base_dir.join(format!("{name}.{ext}"))),
+        }
+    }
+}
+
+/// Directory name for one partition key.
+///
+/// ASCII alphanumerics pass through, as do `-`, `.` and `_` after the first
+/// byte; every other byte is `%XX`-escaped. A key therefore can never name
+/// `..`, the `_staging` dir, or [`UNKEYED_PARTITION_DIR`].
+fn partition_key_dir_name(key: Option<&str>) -> String {
+    let Some(key) = key.filter(|k| !k.is_empty()) else {
+        return UNKEYED_PARTITION_DIR.to_string();
+    };
+    let mut out = String::with_capacity(key.len());
+    // Longest prefix of `out` that fits and ends on a whole key character,
+    // so truncation never splits a `%XX` triple or a multi-byte escape run.
+    let mut cut = 0;
+    for (i, b) in key.bytes().enumerate() {
+        if key.is_char_boundary(i) && out.len() <= MAX_PARTITION_DIR_NAME {
+            cut = out.len();
+        }
+        if b.is_ascii_alphanumeric() || (i > 0 && matches!(b, b'-' | b'.' | b'_')) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    if out.len() > MAX_PARTITION_DIR_NAME {
+        out.truncate(cut);
+        out.push('~');
+        out.push_str(&stable_hash_component(key.as_bytes()));
+    }
+    out
+}
+
+/// One key's staged JSONL file and the number of lines routed to it.
+struct StagedKeyPartition {
+    path: PathBuf,
+    lines: u64,
+}
+
+struct KeyedStagingState {
+    partitions: HashMap<String, StagedKeyPartition>,
+    /// Open writers keyed by partition dir name, with the tick of their last
+    /// write for least-recently-used eviction.
+    open: HashMap<String, (BufWriter<fs::File>, u64)>,
+    tick: u64,
+    evictions: u64,
+}
+
+/// Per-key JSONL staging files under a scratch dir, with at most `max_open`
+/// of them open at a time.
+struct KeyedStaging {
+    dir: PathBuf,
+    write_buf: usize,
+    max_open: usize,
+    state: Mutex<KeyedStagingState>,
+}
+
+impl KeyedStaging {
+    fn new(dir: PathBuf, write_buf: usize, max_open: usize) -> Self {
+        Self {
+            dir,
+            write_buf,
+            max_open: max_open.max(1),
+            state: Mutex::new(KeyedStagingState {
+                partitions: HashMap::new(),
+                open: HashMap::new(),
+                tick: 0,
+                evictions: 0,
+            }),
+        }
+    }
+
+    /// Append each `(dir name, lines, line count)` batch entry to its key's
+    /// staging file.
+    fn append(&self, batch: &mut HashMap<String, (Vec<u8>, u64)>) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow!("keyed export staging lock poisoned"))?;
+        let state = &mut *state;
+        for (name, (bytes, lines)) in batch.drain() {
+            state.tick += 1;
+            let tick = state.tick;
+            if !state.open.contains_key(&name) {
+                if state.open.len() >= self.max_open {
+                    let lru = state
+                        .open
+                        .iter()
+                        .min_by_key(|(_, (_, last))| *last)
+                        .map(|(k, _)| k.clone())
+                        .expect("open writer set is non-empty at the cap");
+                    let (mut w, _) = state.open.remove(&lru).expect("lru key is open");
+                    w.flush()
+                        .with_context(|| format!("flush staged partition for key dir {lru}"))?;
+                    state.evictions += 1;
+                }
+                let next_index = state.partitions.len();
+                let entry =
+                    state
+                        .partitions
+                        .entry(name.clone())
+                        .or_insert_with(|| StagedKeyPartition {
+                            path: self.dir.join(format!("part_{next_index:08}.jsonl")),
+                            lines: 0,
+                        });
+                let f = crate::util::open_append_with_default_backoff(&entry.path)
+                    .with_context(|| format!("open staged partition {}", entry.path.display()))?;
+                state.open.insert(
+                    name.clone(),
+                    (BufWriter::with_capacity(self.write_buf, f), tick),
+                );
+            }
+            let (w, last) = state.open.get_mut(&name).expect("writer was just opened");
+            *last = tick;
+            w.write_all(&bytes)
+                .with_context(|| format!("write staged partition for key dir {name}"))?;
+            if let Some(p) = state.partitions.get_mut(&name) {
+                p.lines += lines;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flush and close every open writer; returns the staged partitions
+    /// sorted by dir name plus the number of LRU evictions.
+    fn finish(self) -> Result<(Vec<(String, StagedKeyPartition)>, u64)> {
+        let state = self
+            .state
+            .into_inner()
+            .map_err(|_| anyhow!("keyed export staging lock poisoned"))?;
+        for (name, (mut w, _)) in state.open {
+            w.flush()
+                .with_context(|| format!("flush staged partition for key dir {name}"))?;
+        }
+        let mut partitions: Vec<_> = state.partitions.into_iter().collect();
+        partitions.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        Ok((partitions, state.evictions))
+    }
+}
+
+/// `Write` sink for one corpus file: splits the exported lines, extracts
+/// each line's key, and hands them to [`KeyedStaging`] in batches.
+struct KeyRouter<'a> {
+    staging: &'a KeyedStaging,
+    key: &'a KeyExtractor,
+    pending: Vec<u8>,
+    batch: HashMap<String, (Vec<u8>, u64)>,
+}
+
+impl<'a> KeyRouter<'a> {
+    fn new(staging: &'a KeyedStaging, key: &'a KeyExtractor) -> Self {
+        Self {
+            staging,
+            key,
+            pending: Vec::new(),
+            batch: HashMap::new(),
+        }
+    }
+
+    /// Route every complete line in `pending` and push the batch to staging.
+    fn route(&mut self) -> Result<()> {
+        let Some(end) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return Ok(());
+        };
+        let tail = self.pending.split_off(end + 1);
+        let lines = std::mem::replace(&mut self.pending, tail);
+        for line in lines.split(|&b| b == b'\n').filter(|l| !l.is_empty()) {
+            let text = std::str::from_utf8(line).context("exported record is not valid UTF-8")?;
+            let key = self
+                .key
+                .key_from_line(text)
+                .context("extract partition key from exported record")?;
+            let (bytes, count) = self
+                .batch
+                .entry(partition_key_dir_name(key.as_deref()))
+                .or_default();
+            bytes.extend_from_slice(line);
+            bytes.push(b'\n');
+            *count += 1;
+        }
+        self.staging.append(&mut self.batch)
+    }
+
+    /// Route a trailing line without `\n` (none is expected from the stream
+    /// writers) and push everything still buffered.
+    fn finish(mut self) -> Result<()> {
+        if !self.pending.is_empty() && !self.pending.ends_with(b"\n") {
+            self.pending.push(b'\n');
+        }
+        self.route()
+    }
+}
+
+impl Write for KeyRouter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        if self.pending.len() >= ROUTE_BATCH_BYTES {
+            self.route().map_err(std::io::Error::other)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Publish one staged key partition to `out_path` in `format`.
+fn publish_key_partition(
+    etl: &RedditETL,
+    staging_dir: &Path,
+    out_path: &Path,
+    format: ExportFormat,
+    staged: &StagedKeyPartition,
+) -> Result<u64> {
+    let opts = &etl.opts;
+    let body = |w: &mut dyn Write| -> Result<u64> {
+        let mut r = BufReader::with_capacity(
+            opts.read_buffer_bytes,
+            crate::util::open_with_default_backoff(&staged.path)
+                .with_context(|| format!("open staged partition {}", staged.path.display()))?,
+        );
+        std::io::copy(&mut r, w)
+            .with_context(|| format!("copy staged partition {}", staged.path.display()))?;
+        Ok(staged.lines)
+    };
+    let should_publish = |&n: &u64| n > 0;
+    match format {
+        ExportFormat::Jsonl => write_jsonl_atomic_if(
+            staging_dir,
+            out_path,
+            opts.write_buffer_bytes,
+            should_publish,
+            body,
+        ),
+        ExportFormat::Zst => write_zst_atomic_if(
+            staging_dir,
+            out_path,
+            opts.zst_level,
+            opts.zstd_dictionary.as_ref(),
+            opts.write_buffer_bytes,
+            should_publish,
+            body,
+        ),
+        ExportFormat::Parquet => crate::parquet_writer::write_parquet_atomic_if(
+            staging_dir,
+            out_path,
+            opts.parquet_row_group_size,
+            &opts.parquet_compression,
+            opts.write_buffer_bytes,
+            should_publish,
+            body,
+        ),
+        ExportFormat::Bzip2 { level } => write_bz2_atomic_if(
+            staging_dir,
+            out_path,
+            level,
+            opts.write_buffer_bytes,
+            should_publish,
+            body,
+        ),
+    }
+}
+
+impl ScanPlan {
+    /// Export matching records partitioned by `key` rather than by month:
+    /// every record goes to `<out_base_dir>/<key>/data.<ext>` (`jsonl`, `zst`,
+    /// `parquet`, or `bz2`). Records whose key is missing or null land in
+    /// `<out_base_dir>/_unkeyed/`.
+    ///
+    /// The key is read from the record as exported, so a `--whitelist` must
+    /// keep the key field. Key directory names keep ASCII letters, digits,
+    /// `-`, `.` and `_`; other bytes are `%XX`-escaped (`r/c++` becomes
+    /// `r%2Fc%2B%2B`), and very long keys are truncated with a hash suffix.
+    ///
+    /// Records are first appended to per-key JSONL staging files under
+    /// `work_dir`. At most [`MAX_OPEN_KEY_PARTITIONS`] of them are open at
+    /// once; beyond that the least recently written is closed and later
+    /// reopened for append, so high-cardinality keys (authors) cost extra
+    /// opens rather than descriptors. After the scan each partition is
+    /// written in `format` and atomically published, so a failed run leaves
+    /// no half-written `data.*` file. Existing partitions for keys this run
+    /// does not produce are left alone. `resume` is not supported here.
+    pub fn export_partitioned_by(
+        self,
+        out_base_dir: &Path,
+        key: KeyExtractor,
+        format: ExportFormat,
+    ) -> Result<(), RetlError> {
+        Ok(self.export_keyed(out_base_dir, key, format, KeyedLayout::PartitionDirs)?)
+    }
+
+    /// Extract matching records into one file per subreddit in a single
+    /// scan: `<out_dir>/<subreddit>.<ext>`, named by the lowercased
+    /// subreddit and escaped like [`ScanPlan::export_partitioned_by`] keys.
+    /// Records without a subreddit land in `<out_dir>/_unkeyed.<ext>`.
+    ///
+    /// Staging, the open-file cap, and atomic publishing work as in
+    /// [`ScanPlan::export_partitioned_by`], and a whitelist must keep
+    /// `subreddit`. Files for subreddits this run does not produce are left
+    /// alone. `resume` is not supported here.
+    pub fn extract_split_by_subreddit(self, out_dir: &Path, format: ExportFormat) -> Result<(), RetlError> {
+        Ok(self.export_keyed(
+            out_dir,
+            KeyExtractor::SubredditLowerFast,
+            format,
+            KeyedLayout::Files,
+        )?)
+    }
+
+    fn export_keyed(
+        self,
+        out_base_dir: &Path,
+        key: KeyExtractor,
+        format: ExportFormat,
+        layout: KeyedLayout,
+    ) -> Result<()> {
+        if let ExportFormat::Bzip2 { level } = format {
+            if !(1..=9).contains(&level) {
+                anyhow::bail!("bzip2 export level must be between 1 and 9, got {level}");
+            }
+        }
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        validate_export_whitelist(&plan.etl)?;
+        if plan.etl.opts.resume {
+            tracing::warn!(
+                "{} does not support resume; every partition is rebuilt",
+                layout.name()
+            );
+        }
+        let targets = resolve_target_subs_from(&plan.etl.opts.subreddit, &plan.query.subreddits);
+        let parallelism = plan.etl.opts.parallelism;
+
+        with_thread_pool(parallelism, || {
+            let manifest_start = RunManifestStart::now();
+            let files = plan_pipeline_files(&plan.etl, Some(&plan.query))?;
+            warn_if_unfiltered_undated_query(&plan.etl, &plan.query, &files);
+
+            crate::util::create_dir_all_with_default_backoff(out_base_dir)
+                .with_context(|| format!("creating export dir {}", out_base_dir.display()))?;
+            let staging_dir = ensure_staging_dir(out_base_dir)?;
+            sweep_stale_inprogress(out_base_dir, true)?;
+
+            let work_dir = plan.etl.ensure_work_dir()?;
+            let tmp_dir = crate::util::unique_scratch_dir(&work_dir, "keyed_export", "partitions");
+            crate::util::create_dir_all_with_default_backoff(&tmp_dir)
+                .with_context(|| format!("creating keyed export work dir {}", tmp_dir.display()))?;
+
+            let result = (|| -> Result<()> {
+                let max_open = crate::concurrency::open_file_budget()
+                    .map_or(MAX_OPEN_KEY_PARTITIONS, |budget| {
+                        MAX_OPEN_KEY_PARTITIONS.min(budget / 4)
+                    });
+                let staging =
+                    KeyedStaging::new(tmp_dir.clone(), plan.etl.opts.write_buffer_bytes, max_open);
+
+                let whitelist = plan.etl.opts.whitelist_fields.clone();
+                let whitelist_tracker = whitelist.as_ref().map(|fields| {
+                    WhitelistMatchTracker::new(
+                        plan.etl.opts.strict_whitelist,
+                        fields.iter().cloned(),
+                    )
+                });
+                let record_limit = record_limit_from(plan.limit, plan.etl.opts.max_output_bytes);
+                let duplicates =
+                    collect_duplicate_records(&plan.etl, &plan.query, targets.as_ref(), &files)?;
+                let pb = plan.etl.opts.progress.then(|| {
+                    make_stage_progress_bar(
+                        total_compressed_size(&files),
+                        plan.etl.opts.progress_label.as_deref(),
+                        plan.etl.opts.progress_stage.as_ref(),
+                    )
+                });
+                let stream_ctx = StreamJobCtx {
+                    targets: targets.as_ref(),
+                    whitelist: &whitelist,
+                    pb: pb.as_ref(),
+                    whitelist_tracker: whitelist_tracker.as_ref(),
+                    record_limit: record_limit.as_deref(),
+                    record_dedupe: duplicates.as_ref().map(RecordDedupe::Drop),
+                    ..StreamJobCtx::from_options(&plan.etl.opts, &plan.query)
+                };
+
+                crate::concurrency::for_each_file_limited(
+                    &files,
+                    plan.etl.opts.file_concurrency,
+                    |job| -> Result<()> {
+                        if record_limit
+                            .as_ref()
+                            .is_some_and(|limit| limit.is_exhausted())
+                        {
+                            return Ok(());
+                        }
+                        let mut router = KeyRouter::new(&staging, &key);
+                        let result = stream_job_with_partial_policy(job, &mut router, &stream_ctx)
+                            .with_context(|| format!("keyed export of {}", job.path.display()))?;
+                        router.finish()?;
+                        if !result.complete {
+                            // Lines already routed from this file stay in
+                            // their partitions; there is no per-month output
+                            // to discard.
+                            tracing::warn!(
+                                path = %job.path.display(),
+                                records = result.written,
+                                "keyed export kept records decoded before a zstd decode error"
+                            );
+                        }
+                        Ok(())
+                    },
+                )?;
+
+                let (partitions, evictions) = staging.finish()?;
+                if evictions > 0 {
+                    tracing::info!(
+                        partitions = partitions.len(),
+                        max_open,
+                        evictions,
+                        "keyed export closed least-recently-used partition writers to stay under the open-file cap"
+                    );
+                }
+                finalize_whitelist_strict(whitelist_tracker.as_ref(), out_base_dir, || Ok(()))?;
+
+                let ext = partitioned_ext(format);
+                let records_written = partitions
+                    .par_iter()
+                    .map(|(name, staged)| -> Result<u64> {
+                        let out_path = layout.out_path(out_base_dir, name, ext)?;
+                        publish_key_partition(&plan.etl, &staging_dir, &out_path, format, staged)
+                    })
+                    .collect::<Result<Vec<u64>>>()?
+                    .into_iter()
+                    .sum::<u64>();
+                if let Some(pb) = pb {
+                    pb.finish_with_message("done");
+                }
+
+                let manifest = scan_manifest_input(
+                    manifest_start,
+                    layout.operation(),
+                    ext,
+                    &plan.etl,
+                    &plan.query,
+                    &files,
+                    plan.limit,
+                    manifest_counts(&[
+                        ("records_written", records_written),
+                        ("partition_files", partitions.len() as u64),
+                    ]),
+                    None,
+                    None,
+                    serde_json::json!({ "partition_key": dedupe_key_label(&key) }),
+                );
+                maybe_write_run_manifest(
+                    plan.etl.opts.emit_manifest,
+                    manifest,
+                    ManifestDestination::Directory(out_base_dir.to_path_buf()),
+                )?;
+                Ok(())
+            })();
+
+            cleanup_scratch_dir(&tmp_dir, "keyed export");
+            result
+        })
+    }
+}
@@ -0,0 +1,129 @@
+// Callback-style record iteration: `ScanPlan::for_each_record` hands each
+// matched line to the caller as a lazily-parsed `Record`;
+// `ScanPlan::records_channel` streams parsed records to another thread;
+// `ScanPlan::for_each_batch` groups those records into fixed-size batches.
+
+impl ScanPlan {
+    /// Invoke `f` for every record that passes the query, without writing any
+    /// output. Common fields on the [`Record`] come from the fast-path parse
+    /// the filter already did; [`Record::get`] parses the full line only on
+    /// first arbitrary-field access.
+    ///
+    /// Files are scanned concurrently (bounded by `file_concurrency`), so `f`
+    /// must be `Sync + Send` and use interior mutability for accumulators.
+    /// An error returned by `f` aborts the scan. This method does not consult
+    /// `resume`: there is no output to checkpoint.
+    pub fn for_each_record<F>(self, f: F) -> Result<(), RetlError>
+    where
+        F: Sync + Send + Fn(&Record<'_>) -> Result<()>,
+    {
+        let plan = self.build_for_scan()?;
+        log_pseudo_user_filter(&plan.query);
+        let parallelism = plan.etl.opts.parallelism;
+        Ok(with_thread_pool(parallelism, || {
+            scan_records(
+                &plan.etl,
+                &plan.query,
+                /*show_progress=*/ true,
+                plan.limit,
+                |min, kind, line| f(&Record::with_minimal(line, kind, min)),
+            )
+        })?)
+    }
+}
+
+/// Internal stop signal: the consumer dropped the `Receiver`, which ends a
+/// [`ScanPlan::records_channel`] scan early without it counting as a failure.
+#[derive(Debug)]
+struct RecordsReceiverDropped;
+
+impl fmt::Display for RecordsReceiverDropped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("records_channel receiver was dropped")
+    }
+}
+
+impl std::error::Error for RecordsReceiverDropped {}
+
+/// Scan thread handle and record receiver returned by
+/// [`ScanPlan::records_channel`].
+type RecordsChannel = (
+    std::thread::JoinHandle<Result<(), RetlError>>,
+    crossbeam_channel::Receiver<Value>,
+);
+
+impl ScanPlan {
+    /// Run the scan on a background thread and send each matching record,
+    /// fully parsed, through a bounded channel of capacity `bound`.
+    ///
+    /// The scan blocks while the channel is full, so a slow consumer applies
+    /// backpressure instead of buffering the corpus in memory (`bound == 0`
+    /// makes every send a rendezvous). Query validation errors are returned
+    /// immediately; scan errors are returned by joining the handle. Dropping
+    /// the `Receiver` stops the scan early and the handle then returns `Ok`.
+    /// The join handle should be joined only after the receiver is drained or
+    /// dropped, otherwise a full channel blocks the scan forever.
+    pub fn records_channel(self, bound: usize) -> Result<RecordsChannel, RetlError> {
+        let plan = self.build()?;
+        let (tx, rx) = crossbeam_channel::bounded::<Value>(bound);
+        let handle = std::thread::Builder::new()
+            .name("retl-records-channel".to_string())
+            .spawn(move || {
+                let result = plan.for_each_record(|record| {
+                    let value = match record.value() {
+                        Some(v) => v.clone(),
+                        None => serde_json::from_str(record.line())
+                            .context("matched record is not valid JSON")?,
+                    };
+                    tx.send(value)
+                        .map_err(|_| anyhow::Error::new(RecordsReceiverDropped))
+                });
+                match result {
+                    Err(e) if e.inner().chain().any(|c| c.is::<RecordsReceiverDropped>()) => {
+                        Ok(())
+                    }
+                    other => other,
+                }
+            })
+            .context("failed to spawn records_channel scan thread")?;
+        Ok((handle, rx))
+    }
+}
+
+impl ScanPlan {
+    /// Invoke `f` with batches of up to `n` fully parsed matching records,
+    /// flushing a final partial batch once the scan ends.
+    ///
+    /// Records arrive through [`Self::records_channel`] with capacity `n`, so
+    /// at most two batches' worth of records are in memory at once and `f`
+    /// runs on the calling thread (it only needs `FnMut`). Batch order
+    /// follows delivery order; set `ordered_output(true)` for chronological
+    /// batches. A scan error — including a panic on the scan thread, e.g. from
+    /// a `transform` closure — is returned after the batches already
+    /// delivered, and the trailing partial batch is then not flushed. `n` must
+    /// be nonzero.
+    pub fn for_each_batch<F>(self, n: usize, mut f: F) -> Result<(), RetlError>
+    where
+        F: FnMut(&[Value]),
+    {
+        if n == 0 {
+            return Err(anyhow!("for_each_batch: batch size must be at least 1").into());
+        }
+        let (handle, rx) = self.records_channel(n)?;
+        let mut batch = Vec::with_capacity(n);
+        for value in rx {
+            batch.push(value);
+            if batch.len() == n {
+                f(&batch);
+                batch.clear();
+            }
+        }
+        handle
+            .join()
+            .map_err(|_| anyhow!("for_each_batch scan thread panicked"))??;
+        if !batch.is_empty() {
+            f(&batch);
+        }
+        Ok(())
+    }
+}
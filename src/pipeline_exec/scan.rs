@@ -14,6 +14,8 @@ struct MonthResult {
     out_path: PathBuf,
     key: String,
     lines: u64,
+    created_utc_range: Option<(i64, i64)>,
+    output_bytes: u64,
 }
 
 struct ScanCheckpoint {
@@ -22,13 +24,19 @@ struct ScanCheckpoint {
 }
 
 fn effective_plan_range(
-    etl: &RedditETL,
+    (mut start, mut end): (Option<YearMonth>, Option<YearMonth>),
     query: Option<&QuerySpec>,
 ) -> (Option<YearMonth>, Option<YearMonth>) {
-    let mut start = etl.opts.start;
-    let mut end = etl.opts.end;
-
-    if let Some(bounds) = query.map(|q| q.timestamp_bounds) {
+    if let Some(q) = query {
+        let bounds = q.timestamp_bounds;
+        // Dump files are partitioned by creation month. Edit and retrieval
+        // times never precede creation, so an upper bound on either still
+        // caps the creation month, but a lower bound says nothing about it:
+        // only derive the start month when the bound is on `created_utc`.
+        let derived_start = match q.time_field {
+            TimeField::Created => bounds.derived_start_month(),
+            TimeField::Edited | TimeField::RetrievedOn => None,
+        };
         // A `created_utc` timestamp bound (`--after`/`--before`) is strictly
         // more precise than the month-granularity `--start`/`--end` range. When
         // both are present we INTERSECT them for file planning —
@@ -44,7 +52,7 @@ fn effective_plan_range(
         // the bound as before. Log a narrowing so a user wondering why an
         // `--after` scan read fewer months than `--start` implied can see what
         // drove file selection.
-        match (start, bounds.derived_start_month()) {
+        match (start, derived_start) {
             (None, derived) => start = derived,
             (Some(explicit), Some(derived)) => {
                 let planned = explicit.max(derived);
@@ -69,7 +77,8 @@ fn effective_plan_range(
                         explicit_end = %explicit,
                         timestamp_end = %derived,
                         planned_end = %planned,
-                        "file planning end month narrowed from the explicit date range ({explicit}) to {planned}: the created_utc timestamp bound (<= {derived}) is tighter"
+                        "file planning end month narrowed from the explicit date range ({explicit}) to {planned}: the {} timestamp bound (<= {derived}) is tighter",
+                        q.time_field.as_str()
                     );
                 }
                 end = Some(planned);
@@ -89,13 +98,33 @@ fn plan_pipeline_files(etl: &RedditETL, query: Option<&QuerySpec>) -> Result<Vec
     // operation on a reused builder would report the first run's skipped
     // files too and inflate `skipped_file_count` in its run manifest.
     etl.opts.partial_read_reporter.clear();
-    let discovered = discover_sources_checked(
-        &etl.opts.comments_dir,
-        &etl.opts.submissions_dir,
+    plan_files_for(etl, query)
+}
+
+/// `(month, RC before RS)` order used by `ETLOptions::ordered_output` and
+/// `ETLOptions::interleaved_sources`.
+fn sort_jobs_chronologically(files: &mut [FileJob]) {
+    files.sort_by_key(|job| (job.ym, job.kind == FileKind::Submission));
+}
+
+/// Discovery + date-range planning shared by real runs and the dry-run
+/// [`RedditETL::plan`]; has no side effects on the builder.
+fn plan_files_for(etl: &RedditETL, query: Option<&QuerySpec>) -> Result<Vec<FileJob>> {
+    if let Some(mut jobs) = etl.opts.explicit_file_jobs()? {
+        if etl.opts.interleaved_sources {
+            sort_jobs_chronologically(&mut jobs);
+        }
+        return Ok(jobs);
+    }
+    let discovered = discover_roots_checked(
+        &etl.opts.corpus_roots(),
         etl.opts.sources,
+        etl.opts.duplicate_months,
     )?;
-    let (start, end) = effective_plan_range(etl, query);
-    let jobs = plan_files_checked(
+    let (start, end) =
+        effective_plan_range(etl.opts.planned_range(&discovered, etl.opts.sources), query);
+    warn_if_resolved_range_empty(&discovered, etl.opts.sources, start, end);
+    let mut jobs = plan_files_checked(
         &discovered,
         &etl.opts.comments_dir,
         &etl.opts.submissions_dir,
@@ -104,12 +133,59 @@ fn plan_pipeline_files(etl: &RedditETL, query: Option<&QuerySpec>) -> Result<Vec
         end,
     )?;
     log_missing_month_warnings(&discovered, etl.opts.sources, start, end);
+    etl.opts.retain_last_n_months(&mut jobs);
+    if etl.opts.interleaved_sources {
+        sort_jobs_chronologically(&mut jobs);
+    }
     Ok(jobs)
 }
 
+/// Warn for each selected source whose months, clamped to the requested
+/// range, leave nothing to scan. An open-ended `since`/`until` past the corpus
+/// edge, or a timestamp bound that excludes the explicit range, otherwise
+/// shows up only as a missing source or a `DateRangeNoFiles` error.
+fn warn_if_resolved_range_empty(
+    discovered: &Discovered,
+    sources: Sources,
+    start: Option<YearMonth>,
+    end: Option<YearMonth>,
+) {
+    if start.is_none() && end.is_none() {
+        return;
+    }
+    let selected: &[(FileKind, &BTreeMap<YearMonth, PathBuf>)] = match sources {
+        Sources::Comments => &[(FileKind::Comment, &discovered.comments)],
+        Sources::Submissions => &[(FileKind::Submission, &discovered.submissions)],
+        Sources::Both => &[
+            (FileKind::Comment, &discovered.comments),
+            (FileKind::Submission, &discovered.submissions),
+        ],
+    };
+    for &(kind, months) in selected {
+        let (Some(&first), Some(&last)) = (months.keys().next(), months.keys().next_back()) else {
+            continue;
+        };
+        let lo = start.map_or(first, |s| s.max(first));
+        let hi = end.map_or(last, |e| e.min(last));
+        if lo > hi {
+            let source = kind.long_label();
+            tracing::warn!(
+                source,
+                requested_start = ?start,
+                requested_end = ?end,
+                available_start = %first,
+                available_end = %last,
+                "resolved date range is empty after clamping to the {source} months on disk ({first}..={last}); no {source} files will be read"
+            );
+        }
+    }
+}
+
 fn warn_if_unfiltered_undated_query(etl: &RedditETL, query: &QuerySpec, files: &[FileJob]) {
     if etl.opts.start.is_some()
         || etl.opts.end.is_some()
+        || etl.opts.last_n_months.is_some()
+        || etl.opts.files.is_some()
         || etl.opts.subreddit.is_some()
         || query.has_selective_filters()
     {
@@ -132,6 +208,33 @@ fn manifest_counts(entries: &[(&str, u64)]) -> BTreeMap<String, u64> {
         .collect()
 }
 
+/// Record count and `created_utc` range of each output file a run published,
+/// keyed by path.
+type ProducedFiles = HashMap<PathBuf, (u64, Option<(i64, i64)>)>;
+
+/// `output_files` for a directory output published one file per month.
+/// Files this run published (`produced`) carry their stream stats; files
+/// kept from a resumed run (`resumed`: path plus `_progress.json` line count)
+/// have no `created_utc` range. Resumed files that are gone, or that this run
+/// re-produced, are skipped.
+fn month_output_files(
+    root: &Path,
+    produced: ProducedFiles,
+    resumed: impl IntoIterator<Item = (PathBuf, u64)>,
+) -> Vec<OutputFileEntry> {
+    let mut out: Vec<OutputFileEntry> = resumed
+        .into_iter()
+        .filter(|(path, _)| !produced.contains_key(path) && path.exists())
+        .map(|(path, lines)| OutputFileEntry::new(root, &path, lines, None))
+        .collect();
+    out.extend(
+        produced
+            .iter()
+            .map(|(path, (lines, range))| OutputFileEntry::new(root, path, *lines, *range)),
+    );
+    out
+}
+
 fn scan_manifest_input(
     start: RunManifestStart,
     operation: &str,
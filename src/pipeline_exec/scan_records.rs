@@ -3,7 +3,7 @@
 ///
 /// Encapsulates file discovery/planning, optional progress-bar setup, the
 /// `for_each_file_limited` per-file fan-out, and the line-level
-/// `parse_minimal` → `matches_minimal` → `within_bounds` →
+/// `parse_minimal` → `matches_minimal` → `within_bounds_on` →
 /// (optional) `matches_full` filtering ladder. The caller owns whatever
 /// accumulator the matched records feed into and supplies it through
 /// interior mutability inside `on_record`.
@@ -28,19 +28,29 @@ where
     let targets_ref = targets.as_ref();
     let bounds = bounds_tuple(etl.opts.start, etl.opts.end);
     let read_buf = etl.opts.read_buffer_bytes;
-    let record_limit = record_limit_from(limit);
+    // Passed per file: without `with_thread_pool` the fan-out runs on the
+    // global pool, whose workers do not carry this run's settings.
+    let run_settings = RunSettings::from_options(&etl.opts);
+    let record_limit = record_limit_from(limit, None);
     if record_limit.as_ref().is_some_and(|limit| limit.is_zero()) {
         return Ok(());
     }
 
-    let files = plan_pipeline_files(etl, Some(query))?;
+    let mut files = plan_pipeline_files(etl, Some(query))?;
     warn_if_unfiltered_undated_query(etl, query, &files);
+    let file_concurrency = if etl.opts.ordered_output {
+        sort_jobs_chronologically(&mut files);
+        1
+    } else {
+        etl.opts.file_concurrency
+    };
 
     let pb = if show_progress && etl.opts.progress {
         let total_bytes = total_compressed_size(&files);
-        Some(make_progress_bar_labeled(
+        Some(make_stage_progress_bar(
             total_bytes,
             etl.opts.progress_label.as_deref(),
+            etl.opts.progress_stage.as_ref(),
         ))
     } else {
         None
@@ -48,7 +58,7 @@ where
 
     let fanout = crate::concurrency::for_each_file_limited(
         &files,
-        etl.opts.file_concurrency,
+        file_concurrency,
         |job| -> Result<()> {
             if record_limit
                 .as_ref()
@@ -58,6 +68,7 @@ where
             }
             let kind = job.kind;
             let mut line_number: u64 = 0;
+            let mut delivered: u64 = 0;
             let line_cb = |line: &str| -> Result<()> {
                 line_number += 1;
                 let min = match parse_minimal(line) {
@@ -70,7 +81,7 @@ where
                 if !matches_minimal(&min, targets_ref, query, kind) {
                     return Ok(());
                 }
-                if !within_bounds(&min, bounds) {
+                if !within_bounds_on(&min, bounds, query.time_field) {
                     return Ok(());
                 }
                 if query.requires_full_parse() {
@@ -82,6 +93,7 @@ where
                 }
                 claim_record_or_stop(record_limit.as_deref())?;
                 on_record(&min, kind, line)?;
+                delivered += 1;
                 Ok(())
             };
             let partial_read_policy = if etl.opts.allow_partial {
@@ -93,20 +105,27 @@ where
             let mut skip_cb = |path: &Path, err: &anyhow::Error| {
                 etl.opts.partial_read_reporter.record(path, err);
             };
-            for_each_line_with_opts_status(
-                &job.path,
-                LineStreamOpts {
-                    read_buf_bytes: Some(read_buf),
-                    progress: progress_cb.as_mut().map(|cb| cb as &mut dyn FnMut(u64)),
-                    on_skip: etl
-                        .opts
-                        .allow_partial
-                        .then_some(&mut skip_cb as &mut dyn FnMut(&Path, &anyhow::Error)),
-                    partial_read_policy,
-                    ..Default::default()
-                },
-                line_cb,
-            )?;
+            let trace = FileTrace::start(&job.path);
+            trace.in_scope(|| {
+                for_each_line_with_opts_status(
+                    &job.path,
+                    LineStreamOpts {
+                        read_buf_bytes: Some(read_buf),
+                        progress: progress_cb.as_mut().map(|cb| cb as &mut dyn FnMut(u64)),
+                        on_skip: etl
+                            .opts
+                            .allow_partial
+                            .then_some(&mut skip_cb as &mut dyn FnMut(&Path, &anyhow::Error)),
+                        partial_read_policy,
+                        max_line_bytes: run_settings.max_line_bytes,
+                        io_retries: run_settings.io_retries,
+                        zstd_dictionary: run_settings.zstd_dictionary.as_ref(),
+                        ..Default::default()
+                    },
+                    line_cb,
+                )
+            })?;
+            trace.finish(delivered);
             Ok(())
         },
     );
@@ -0,0 +1,95 @@
+// Source-mix sampling: `ScanPlan::build_for_scan` resolves a
+// `ScanPlan::source_ratio` target into per-kind keep fractions with one
+// counting pass before the real run; `matches_minimal` then samples by id.
+
+/// A built plan that is about to execute, with its IO settings current on
+/// this thread until it drops. Derefs to the plan.
+struct ScanRun {
+    plan: ScanPlan,
+    _run_settings: crate::config::RunSettingsScope,
+}
+
+impl std::ops::Deref for ScanRun {
+    type Target = ScanPlan;
+
+    fn deref(&self) -> &ScanPlan {
+        &self.plan
+    }
+}
+
+impl ScanPlan {
+    /// [`ScanPlan::build`] with the run's IO settings entered first, so
+    /// planning already reads with them. Used directly only by estimates,
+    /// which do not pre-count for `source_ratio`.
+    fn build_for_run(self) -> Result<ScanRun> {
+        let run_settings = self.etl.opts.enter_run_settings();
+        Ok(ScanRun {
+            plan: self.build()?,
+            _run_settings: run_settings,
+        })
+    }
+
+    /// [`ScanPlan::build_for_run`] plus the matched-record pre-count that
+    /// `source_ratio` needs. Execution methods start here; the dry-run plan
+    /// uses `build` and never scans. A plan whose fractions are already
+    /// resolved is not counted again.
+    ///
+    /// Also starts the run's reject file, once and before any pass (the
+    /// count here, a dedupe collect pass) can reject a line.
+    fn build_for_scan(self) -> Result<ScanRun> {
+        let mut run = self.build_for_run()?;
+        let plan = &mut run.plan;
+        if let Some(sink) = &plan.etl.opts.reject_sink {
+            sink.begin_run(plan.etl.opts.resume)?;
+        }
+        if let Some(ratio) = plan.query.source_ratio.filter(|r| r.keep.is_none()) {
+            let (comments, submissions) = count_matched_by_kind(&plan.etl, &plan.query)?;
+            if comments == 0 || submissions == 0 {
+                tracing::warn!(
+                    comments,
+                    submissions,
+                    "source_ratio: one source matched nothing; the other is kept whole"
+                );
+            }
+            let keep = ratio.resolve(comments, submissions);
+            tracing::info!(
+                comments,
+                submissions,
+                keep_comments = keep.comments,
+                keep_submissions = keep.submissions,
+                "source_ratio resolved keep fractions"
+            );
+            plan.query.source_ratio = Some(SourceRatio {
+                keep: Some(keep),
+                ..ratio
+            });
+        }
+        Ok(run)
+    }
+}
+
+/// Records `query` matches per kind, ignoring its `source_ratio`.
+fn count_matched_by_kind(etl: &RedditETL, query: &QuerySpec) -> Result<(u64, u64)> {
+    let mut unsampled = query.clone();
+    unsampled.source_ratio = None;
+    let comments = AtomicU64::new(0);
+    let submissions = AtomicU64::new(0);
+    with_thread_pool(etl.opts.parallelism, || {
+        scan_records(
+            etl,
+            &unsampled,
+            /*show_progress=*/ true,
+            None,
+            |_min, kind, _line| {
+                match kind {
+                    FileKind::Comment => &comments,
+                    FileKind::Submission => &submissions,
+                }
+                .fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+    })
+    .context("source_ratio: counting matched records per source")?;
+    Ok((comments.into_inner(), submissions.into_inner()))
+}
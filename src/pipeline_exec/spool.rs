@@ -36,24 +36,14 @@ fn clear_extract_resume_parts(tmp_dir: &Path) -> Result<()> {
 struct MonthJobCtx<'a> {
     out_dir: &'a Path,
     staging_dir: &'a Path,
-    targets: Option<&'a Vec<String>>,
-    query: &'a QuerySpec,
-    whitelist: &'a Option<Vec<String>>,
-    pb: Option<&'a ProgressBar>,
-    bounds: Option<DateBounds>,
-    read_buf: usize,
     write_buf: usize,
-    human_ts: bool,
-    whitelist_tracker: Option<&'a WhitelistMatchTracker>,
-    record_limit: Option<&'a RecordLimit>,
+    stream: StreamJobCtx<'a>,
     resume: bool,
     /// Months already published by a prior run (the validated `_progress.json`
     /// entries). The recorded `MonthEntry.size` lets the resume fast-path
     /// re-stat each published output before trusting it.
     completed_months: &'a HashMap<String, MonthEntry>,
     accumulator: Option<&'a ManifestAccumulator>,
-    allow_partial: bool,
-    partial_reporter: Option<&'a crate::config::PartialReadReporter>,
 }
 
 /// Per-month closure body: skip if the month is already published (resume
@@ -72,7 +62,11 @@ fn process_month(job: &FileJob, ctx: &MonthJobCtx<'_>) -> Result<Option<MonthRes
         .join(format!("{}_{}.jsonl", file_prefix, job.ym));
     let key = crate::progress_manifest::month_key(key_prefix, job.ym);
 
-    if ctx.record_limit.is_some_and(|limit| limit.is_exhausted()) {
+    if ctx
+        .stream
+        .record_limit
+        .is_some_and(|limit| limit.is_exhausted())
+    {
         return Ok(None);
     }
 
@@ -97,7 +91,7 @@ fn process_month(job: &FileJob, ctx: &MonthJobCtx<'_>) -> Result<Option<MonthRes
         if let Some(entry) = ctx.completed_months.get(&key) {
             match fs::metadata(&out_path) {
                 Ok(meta) if meta.len() == entry.size => {
-                    if let Some(pb) = ctx.pb {
+                    if let Some(pb) = ctx.stream.pb {
                         let sz = fs::metadata(&job.path).map(|m| m.len()).unwrap_or(0);
                         pb.inc(sz);
                     }
@@ -124,26 +118,12 @@ fn process_month(job: &FileJob, ctx: &MonthJobCtx<'_>) -> Result<Option<MonthRes
         }
     }
 
-    let n = match write_jsonl_atomic(ctx.staging_dir, &out_path, ctx.write_buf, |w| {
-        let result = stream_job_with_partial_policy(
-            job,
-            w,
-            ctx.targets,
-            ctx.query,
-            ctx.whitelist,
-            ctx.pb.cloned(),
-            ctx.bounds,
-            ctx.read_buf,
-            ctx.human_ts,
-            ctx.whitelist_tracker,
-            ctx.allow_partial,
-            ctx.partial_reporter,
-            ctx.record_limit,
-        )?;
-        complete_stream_job(job, result)
+    let result = match write_jsonl_atomic(ctx.staging_dir, &out_path, ctx.write_buf, |w| {
+        let result = stream_job_with_partial_policy(job, w, &ctx.stream)?;
+        checked_stream_job(job, result)
     }) {
-        Ok(n) => n,
-        Err(e) if ctx.allow_partial && is_partial_scan_error(&e) => {
+        Ok(result) => result,
+        Err(e) if ctx.stream.allow_partial && is_partial_scan_error(&e) => {
             tracing::warn!(path=%job.path.display(), output=%out_path.display(), error=%e, "Skipping month after zstd decode error; staged spool output was discarded and resume will retry it");
             return Ok(None);
         }
@@ -153,7 +133,9 @@ fn process_month(job: &FileJob, ctx: &MonthJobCtx<'_>) -> Result<Option<MonthRes
     Ok(Some(MonthResult {
         out_path,
         key,
-        lines: n,
+        lines: result.written,
+        created_utc_range: result.created_utc_range,
+        output_bytes: result.output_bytes,
     }))
 }
 
@@ -168,6 +150,7 @@ fn commit_entry_to_manifest(acc: &ManifestAccumulator, result: MonthResult) -> R
         size,
         lines: result.lines,
         sha256: None,
+        output_bytes: Some(result.output_bytes),
     };
     acc.commit(result.key, entry)
 }
@@ -201,8 +184,8 @@ impl ScanPlan {
     /// a prior crashed run are swept only when their owner PID is no longer live.
     ///
     /// Returns `(vector_of_paths, total_records_written)`.
-    pub fn extract_spool_monthly(self, out_dir: &Path) -> Result<(Vec<PathBuf>, u64)> {
-        let plan = self.build()?;
+    pub fn extract_spool_monthly(self, out_dir: &Path) -> Result<(Vec<PathBuf>, u64), RetlError> {
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         validate_export_whitelist(&plan.etl)?;
         let parallelism = plan.etl.opts.parallelism;
@@ -245,11 +228,15 @@ impl ScanPlan {
                 |key, entry| validate_spool_entry(out_dir, key, entry),
             )?;
 
+            let duplicates =
+                collect_duplicate_records(&plan.etl, &plan.query, targets.as_ref(), &files)?;
+
             let total_bytes = total_compressed_size(&files);
             let pb = if plan.etl.opts.progress {
-                Some(make_progress_bar_labeled(
+                Some(make_stage_progress_bar(
                     total_bytes,
                     plan.etl.opts.progress_label.as_deref(),
+                    plan.etl.opts.progress_stage.as_ref(),
                 ))
             } else {
                 None
@@ -257,6 +244,7 @@ impl ScanPlan {
 
             let total_written = AtomicU64::new(0);
             let parts = Mutex::new(Vec::<PathBuf>::new());
+            let produced = Mutex::new(HashMap::new());
 
             // Pre-seed `parts` with already-completed months so the returned
             // list reflects the full set of published outputs (resumed + new),
@@ -274,8 +262,6 @@ impl ScanPlan {
                 }
             }
 
-            let resumed_lines = committed_line_count(&initial_months);
-
             let whitelist = plan.etl.opts.whitelist_fields.clone();
             let whitelist_tracker = whitelist.as_ref().map(|fields| {
                 Arc::new(WhitelistMatchTracker::new(
@@ -283,12 +269,21 @@ impl ScanPlan {
                     fields.iter().cloned(),
                 ))
             });
-            let record_limit = record_limit_from_with_claimed(plan.limit, resumed_lines);
-            let targets_ref = targets.as_ref();
-            let bounds = bounds_tuple(plan.etl.opts.start, plan.etl.opts.end);
-            let read_buf = plan.etl.opts.read_buffer_bytes;
+            let record_limit = record_limit_from_with_claimed(
+                plan.limit,
+                plan.etl.opts.max_output_bytes,
+                &initial_months,
+            );
+            let stream_ctx = StreamJobCtx {
+                targets: targets.as_ref(),
+                whitelist: &whitelist,
+                pb: pb.as_ref(),
+                whitelist_tracker: whitelist_tracker.as_deref(),
+                record_limit: record_limit.as_deref(),
+                record_dedupe: duplicates.as_ref().map(RecordDedupe::Drop),
+                ..StreamJobCtx::from_options(&plan.etl.opts, &plan.query)
+            };
             let write_buf = plan.etl.opts.write_buffer_bytes;
-            let human_ts = plan.etl.opts.human_readable_timestamps;
 
             crate::concurrency::for_each_file_limited(
                 &files,
@@ -297,27 +292,21 @@ impl ScanPlan {
                     let ctx = MonthJobCtx {
                         out_dir,
                         staging_dir: &staging_dir,
-                        targets: targets_ref,
-                        query: &plan.query,
-                        whitelist: &whitelist,
-                        pb: pb.as_ref(),
-                        bounds,
-                        read_buf,
                         write_buf,
-                        human_ts,
-                        whitelist_tracker: whitelist_tracker.as_deref(),
-                        record_limit: record_limit.as_deref(),
+                        stream: stream_ctx,
                         resume,
                         completed_months: &initial_months,
                         accumulator: accumulator.as_ref(),
-                        allow_partial: plan.etl.opts.allow_partial,
-                        partial_reporter: Some(&plan.etl.opts.partial_read_reporter),
                     };
                     let outcome = process_month(job, &ctx)?;
 
                     if let Some(month) = outcome {
                         total_written.fetch_add(month.lines, Ordering::Relaxed);
                         parts.lock().unwrap().push(month.out_path.clone());
+                        produced.lock().unwrap().insert(
+                            month.out_path.clone(),
+                            (month.lines, month.created_utc_range),
+                        );
                         if let Some(acc) = &accumulator {
                             commit_entry_to_manifest(acc, month).context(
                                 "failed to durably update resume progress manifest after publishing spool output",
@@ -350,7 +339,7 @@ impl ScanPlan {
             let mut list = parts.into_inner().unwrap();
             list.sort();
             list.dedup();
-            let manifest = scan_manifest_input(
+            let mut manifest = scan_manifest_input(
                 manifest_start,
                 "scan.extract_spool_monthly",
                 "spool-jsonl-directory",
@@ -370,6 +359,13 @@ impl ScanPlan {
                 resume.then(|| crate::progress_manifest::manifest_path(out_dir)),
                 serde_json::json!({}),
             );
+            manifest.output_files = month_output_files(
+                out_dir,
+                produced.into_inner().unwrap(),
+                initial_months
+                    .iter()
+                    .map(|(key, entry)| (out_dir.join(format!("part_{key}.jsonl")), entry.lines)),
+            );
             maybe_write_run_manifest(
                 plan.etl.opts.emit_manifest,
                 manifest,
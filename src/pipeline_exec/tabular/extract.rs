@@ -41,12 +41,13 @@ fn extract_tabular_common(
             etl.opts.strict_whitelist,
             fields.iter().cloned(),
         )));
-        let record_limit = record_limit_from(limit);
+        let record_limit = record_limit_from(limit, etl.opts.max_output_bytes);
         let total_bytes = total_compressed_size(&files);
         let pb = if etl.opts.progress {
-            Some(make_progress_bar_labeled(
+            Some(make_stage_progress_bar(
                 total_bytes,
                 etl.opts.progress_label.as_deref(),
+                etl.opts.progress_stage.as_ref(),
             ))
         } else {
             None
@@ -109,7 +110,16 @@ fn extract_tabular_common(
         if let Some(pb) = pb {
             pb.finish_with_message("done");
         }
-        stitch_tabular_parts(&tmp_dir, out_path, fields, opts, format, write_buf)?;
+        let month_major = etl.opts.stitch_month_major();
+        stitch_tabular_parts(
+            &tmp_dir,
+            out_path,
+            fields,
+            opts,
+            format,
+            month_major,
+            write_buf,
+        )?;
         let manifest = scan_manifest_input(
             manifest_start,
             &format!("scan.extract_to_{}", format.label()),
@@ -174,12 +184,12 @@ impl ScanPlan {
         out_path: &Path,
         fields: I,
         opts: TabularExportOptions,
-    ) -> Result<()>
+    ) -> Result<(), RetlError>
     where
         I: IntoIterator<Item = S>,
         S: Into<String>,
     {
-        self.extract_to_tabular(out_path, fields, opts, TabularFormat::Csv)
+        Ok(self.extract_to_tabular(out_path, fields, opts, TabularFormat::Csv)?)
     }
 
     /// Export matching records as TSV. `fields` is the fixed top-level schema;
@@ -195,12 +205,12 @@ impl ScanPlan {
         out_path: &Path,
         fields: I,
         opts: TabularExportOptions,
-    ) -> Result<()>
+    ) -> Result<(), RetlError>
     where
         I: IntoIterator<Item = S>,
         S: Into<String>,
     {
-        self.extract_to_tabular(out_path, fields, opts, TabularFormat::Tsv)
+        Ok(self.extract_to_tabular(out_path, fields, opts, TabularFormat::Tsv)?)
     }
 
     fn extract_to_tabular<I, S>(
@@ -230,7 +240,7 @@ impl ScanPlan {
             warn_tabular_whitelist_override(preset, &fields, format);
         }
         scan.etl.opts.whitelist_fields = Some(fields.clone());
-        let plan = scan.build()?;
+        let plan = scan.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let targets = resolve_target_subs_from(&plan.etl.opts.subreddit, &plan.query.subreddits);
         extract_tabular_common(
@@ -5,7 +5,11 @@ fn tabular_part_path(tmp_dir: &Path, key: &str, format: TabularFormat) -> PathBu
     tmp_dir.join(format!(".part_{}{}", key, format.row_suffix()))
 }
 
-fn tabular_part_paths(tmp_dir: &Path, format: TabularFormat) -> Result<Vec<PathBuf>> {
+fn tabular_part_paths(
+    tmp_dir: &Path,
+    format: TabularFormat,
+    month_major: bool,
+) -> Result<Vec<PathBuf>> {
     let suffix = format.row_suffix();
     let mut paths = Vec::new();
     for entry in crate::util::read_dir_with_default_backoff(tmp_dir)
@@ -23,6 +27,9 @@ fn tabular_part_paths(tmp_dir: &Path, format: TabularFormat) -> Result<Vec<PathB
         }
     }
     paths.sort();
+    if month_major {
+        sort_parts_month_major(&mut paths);
+    }
     Ok(paths)
 }
 
@@ -32,9 +39,10 @@ fn stitch_tabular_parts(
     fields: &[String],
     opts: TabularExportOptions,
     format: TabularFormat,
+    month_major: bool,
     write_buf: usize,
 ) -> Result<()> {
-    let parts = tabular_part_paths(tmp_dir, format)?;
+    let parts = tabular_part_paths(tmp_dir, format, month_major)?;
     write_at_path_atomic(out_path, write_buf, |out| {
         if opts.header {
             write_tabular_header(out, fields, format)?;
@@ -19,6 +19,9 @@ fn stream_tabular_job<W: Write + ?Sized>(
     record_limit: Option<&RecordLimit>,
 ) -> Result<StreamJobResult> {
     let mut written = 0_u64;
+    let mut output_bytes = 0_u64;
+    let mut writer = ByteCountingWriter::new(writer);
+    let mut created_utc_range = None;
     let mut line_number = 0_u64;
     let mut on_line = |line: &str| -> Result<()> {
         line_number += 1;
@@ -29,7 +32,9 @@ fn stream_tabular_job<W: Write + ?Sized>(
                 Err(e) => return Err(malformed_json_error(&job.path, line_number, e)),
             },
         };
-        if !matches_minimal(&min, targets, query, job.kind) || !within_bounds(&min, bounds) {
+        if !matches_minimal(&min, targets, query, job.kind)
+            || !within_bounds_on(&min, bounds, query.time_field)
+        {
             return Ok(());
         }
         // Parse the full JSON `Value` at most once per surviving record.
@@ -47,13 +52,24 @@ fn stream_tabular_job<W: Write + ?Sized>(
             None
         };
         claim_record_or_stop(record_limit)?;
-        let val: Value = match prevalidated {
+        widen_created_utc_range(&mut created_utc_range, min.created_utc);
+        let mut val: Value = match prevalidated {
             Some(val) => val,
             None => serde_json::from_str(line)
                 .map_err(|e| malformed_json_error(&job.path, line_number, e))?,
         };
+        // Like the JSONL writer, rewrite `edited` and `subreddit` and run the
+        // caller's transform before the row's cells are selected so derived
+        // or redacted fields reach the output.
+        apply_edited_mode(&mut val, query.normalize_edited);
+        if query.canonicalize_subreddit_case {
+            lowercase_subreddit_field(&mut val);
+        }
+        if let Some(RecordTransform(f)) = &query.transform {
+            f(&mut val);
+        }
         let (cells, matched_indices) = tabular_cells_from_value(&val, selectors)?;
-        write_tabular_row(writer, fields, &cells, format).with_context(|| {
+        write_tabular_row(&mut writer, fields, &cells, format).with_context(|| {
             format!(
                 "writing {} row for {} line {}",
                 format.label(),
@@ -62,6 +78,11 @@ fn stream_tabular_job<W: Write + ?Sized>(
             )
         })?;
         written += 1;
+        let bytes = writer.take_bytes();
+        output_bytes += bytes;
+        if let Some(limit) = record_limit {
+            limit.add_bytes(bytes);
+        }
         if let Some(tracker) = whitelist_tracker {
             tracker.observe(crate::streaming::WhitelistEmission {
                 matched_fields: &matched_indices,
@@ -82,21 +103,31 @@ fn stream_tabular_job<W: Write + ?Sized>(
             reporter.record(path, err);
         }
     };
-    let stream_result = for_each_line_with_opts_status(
-        &job.path,
-        LineStreamOpts {
-            read_buf_bytes: Some(read_buf_bytes),
-            progress: progress_cb.as_mut().map(|cb| cb as &mut dyn FnMut(u64)),
-            on_skip: allow_partial.then_some(&mut skip_cb as &mut dyn FnMut(&Path, &anyhow::Error)),
-            partial_read_policy,
-            ..Default::default()
-        },
-        |s| on_line(s),
-    );
+    let trace = FileTrace::start(&job.path);
+    let stream_result = trace.in_scope(|| {
+        for_each_line_with_opts_status(
+            &job.path,
+            LineStreamOpts {
+                read_buf_bytes: Some(read_buf_bytes),
+                progress: progress_cb.as_mut().map(|cb| cb as &mut dyn FnMut(u64)),
+                on_skip: allow_partial
+                    .then_some(&mut skip_cb as &mut dyn FnMut(&Path, &anyhow::Error)),
+                partial_read_policy,
+                ..Default::default()
+            },
+            |s| on_line(s),
+        )
+    });
     let complete = match stream_result {
         Ok(complete) => complete,
         Err(e) if is_record_limit_reached(&e) => true,
         Err(e) => return Err(e),
     };
-    Ok(StreamJobResult { written, complete })
+    trace.finish(written);
+    Ok(StreamJobResult {
+        written,
+        complete,
+        created_utc_range,
+        output_bytes,
+    })
 }
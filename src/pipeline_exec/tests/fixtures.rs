@@ -128,6 +128,7 @@ pub(super) fn seed_scan_checkpoint_part(
             size: fs::metadata(&part).unwrap().len(),
             lines,
             sha256: None,
+            output_bytes: None,
         },
     );
     crate::progress_manifest::save(&checkpoint_dir, &months, Some(&fingerprint)).unwrap();
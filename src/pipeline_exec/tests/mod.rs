@@ -10,5 +10,6 @@ mod checkpoint_reuse;
 mod fingerprint;
 mod fixtures;
 mod manifest_commit;
+mod partitioned_by_key;
 mod partitioned_zero_record;
 mod resume_robustness;
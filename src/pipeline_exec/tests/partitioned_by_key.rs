@@ -0,0 +1,48 @@
+//! Unit tests for the key-partitioned export's directory naming and its
+//! LRU-capped staging writers.
+
+use super::*;
+
+#[test]
+fn partition_key_dir_names_escape_unsafe_bytes() {
+    assert_eq!(partition_key_dir_name(Some("AskScience")), "AskScience");
+    assert_eq!(partition_key_dir_name(Some("a-b.c_d")), "a-b.c_d");
+    assert_eq!(partition_key_dir_name(Some("../etc")), "%2E.%2Fetc");
+    assert_eq!(partition_key_dir_name(Some("_staging")), "%5Fstaging");
+    assert_eq!(partition_key_dir_name(Some("c++")), "c%2B%2B");
+    assert_eq!(partition_key_dir_name(Some("")), UNKEYED_PARTITION_DIR);
+    assert_eq!(partition_key_dir_name(None), UNKEYED_PARTITION_DIR);
+
+    let long = "é".repeat(200);
+    let name = partition_key_dir_name(Some(&long));
+    assert!(name.len() <= MAX_PARTITION_DIR_NAME + 1 + 24, "{name}");
+    let (prefix, _hash) = name.split_once('~').unwrap();
+    assert!(
+        prefix.ends_with("%A9"),
+        "escape triples must not be split: {prefix}"
+    );
+    assert_ne!(name, partition_key_dir_name(Some(&"é".repeat(201))));
+}
+
+#[test]
+fn keyed_staging_reopens_evicted_writers_for_append() {
+    let dir = tempfile::tempdir().unwrap();
+    let staging = KeyedStaging::new(dir.path().to_path_buf(), 64, 2);
+    for round in 0..3 {
+        for key in ["a", "b", "c"] {
+            let mut batch = HashMap::new();
+            batch.insert(key.to_string(), (format!("{key}{round}\n").into_bytes(), 1));
+            staging.append(&mut batch).unwrap();
+        }
+    }
+
+    let (partitions, evictions) = staging.finish().unwrap();
+    assert!(evictions > 0, "three keys over two open writers must evict");
+    let names: Vec<&str> = partitions.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+    for (name, staged) in &partitions {
+        assert_eq!(staged.lines, 3);
+        let body = fs::read_to_string(&staged.path).unwrap();
+        assert_eq!(body, format!("{name}0\n{name}1\n{name}2\n"));
+    }
+}
@@ -5,6 +5,7 @@ fn entry(size: u64, lines: u64) -> MonthEntry {
         size,
         lines,
         sha256: None,
+        output_bytes: None,
     }
 }
 
@@ -43,3 +44,28 @@ fn surviving_resumed_parts_is_empty_for_empty_manifest() {
         surviving_resumed_parts(&months, |key| tmp.path().join(format!("part_{key}.jsonl")));
     assert!(got.is_empty());
 }
+
+/// A resumed run's byte budget starts from the bytes its committed months
+/// wrote, falling back to the on-disk size for entries that predate
+/// `output_bytes`.
+#[test]
+fn resumed_byte_budget_counts_committed_output_bytes() {
+    let mut months = std::collections::HashMap::new();
+    months.insert(
+        "RS_2006-01".to_string(),
+        MonthEntry {
+            output_bytes: Some(60),
+            ..entry(20, 3)
+        },
+    );
+    months.insert("RC_2006-01".to_string(), entry(30, 2));
+
+    let limit = record_limit_from_with_claimed(None, Some(100), &months).unwrap();
+    assert!(!limit.is_exhausted());
+    limit.add_bytes(10);
+    assert!(limit.is_exhausted());
+
+    let fresh = record_limit_from(None, Some(100)).unwrap();
+    fresh.add_bytes(10);
+    assert!(!fresh.is_exhausted());
+}
@@ -1,45 +1,63 @@
+use crate::agg_expr::HllState;
 use crate::atomic_write::{
     ensure_staging_dir, sweep_stale_atomic_replace_tmp, sweep_stale_inprogress,
-    write_at_path_atomic, write_jsonl_atomic, write_jsonl_atomic_if, write_zst_atomic_if,
+    write_at_path_atomic, write_bz2_atomic_if, write_jsonl_atomic, write_jsonl_atomic_if,
+    write_zst_atomic_if,
 };
-use crate::config::ETLOptions;
+use crate::bucketing::{process_bucket_streaming, BucketingCfg};
+use crate::config::{clamp_shard_count, ETLOptions, RunSettings, Sources, MAX_SHARDS};
 use crate::date::YearMonth;
 use crate::dedupe::{
     build_runs_sorted_with_key_stats, merge_runs_sorted_with_key_stats, DedupeCfg,
 };
+use crate::error::RetlError;
 use crate::filters::{
-    bounds_tuple, matches_full, matches_minimal, resolve_target_subs_from, within_bounds,
+    bounds_tuple, matches_full, matches_minimal, resolve_target_subs_from, within_bounds_on,
     ym_from_epoch, DateBounds,
 };
 use crate::key_extractor::KeyExtractor;
 use crate::kv_shard::ShardedKVWriter;
 use crate::paths::{
-    discover_sources_checked, log_missing_month_warnings, plan_files_checked, FileJob, FileKind,
+    discover_files_checked, discover_roots_checked, log_missing_month_warnings,
+    missing_month_diagnostics, plan_files_checked, Discovered, FileJob, FileKind, PlanningError,
 };
 use crate::pipeline::{RedditETL, ScanPlan};
-use crate::progress::{make_progress_bar_labeled, total_compressed_size};
+use crate::progress::{make_stage_progress_bar, total_compressed_size, ProgressBar};
 use crate::progress_manifest::{ManifestAccumulator, MonthEntry};
-use crate::query::QuerySpec;
+use crate::quantile::{TDigest, DEFAULT_COMPRESSION as QUANTILE_COMPRESSION};
+use crate::query::{
+    normalize_author, QuerySpec, RecordTransform, SourceRatio, TimeField,
+};
+use crate::record::Record;
 use crate::run_manifest::{
     corpus_snapshot_from_etl, etl_options_value, maybe_write_run_manifest, scan_query_value,
-    ManifestDestination, RunManifestInput, RunManifestStart,
+    ManifestDestination, OutputFileEntry, RunManifestInput, RunManifestStart,
 };
+use crate::parents_ids::{parent_id_shard_count, IdScratchRoot};
 use crate::shard::{ShardedWriter, UsernameStream};
-use crate::stitch::{concat_tsvs, stitch_tmp_parts, stitch_tmp_parts_to_json_array};
+use crate::shard_common::{
+    create_line_shard_writers, create_memory_line_shards, seeded_state, shard_index,
+    unique_shard_line_vec,
+};
+use crate::stitch::{
+    concat_tsvs, sort_parts_month_major, stitch_tmp_parts, stitch_tmp_parts_to_json_array,
+};
 use crate::streaming::{
-    claim_record_or_stop, is_record_limit_reached, process_file_for_usernames_with_skip,
-    stream_job_with_partial_policy, RecordLimit, StreamJobResult, WhitelistMatchTracker,
+    apply_edited_mode, claim_record_or_stop, is_record_limit_reached, lowercase_subreddit_field,
+    process_file_for_usernames_with_skip, record_limit_reached_error,
+    stream_job_with_partial_policy, widen_created_utc_range, ByteCountingWriter, DuplicateRecords,
+    RecordDedupe, RecordKeyCollector, RecordLimit, StreamJobCtx, StreamJobResult,
+    WhitelistMatchTracker, WhitelistProjector,
 };
 use crate::util::{
     stable_fnv1a_hex, system_time_parts,
-    with_thread_pool,
+    with_thread_pool, FileTrace,
 };
 use crate::zstd_jsonl::{
     for_each_line_with_opts_status, malformed_json_error, parse_minimal, LineStreamOpts,
-    MinimalRecord, PartialReadPolicy,
+    MinimalRecord, PartialReadPolicy, ZstdDictionary,
 };
 use anyhow::{anyhow, Context, Result};
-use indicatif::ProgressBar;
 use rayon::prelude::*;
 use serde_json::Value;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -55,12 +73,14 @@ use std::sync::{Arc, Mutex};
 /// `Parquet` is always present in the type, but the actual write path is
 /// compiled in only when the `parquet` cargo feature is enabled. Without the
 /// feature, attempting to write `Parquet` returns a clear build-flag error
-/// instead of a confusing runtime panic.
+/// instead of a confusing runtime panic. `Bzip2` follows the same rule with
+/// the `bzip2` feature; `level` is the bzip2 block size (`1..=9`).
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ExportFormat {
     Jsonl,
     Zst,
     Parquet,
+    Bzip2 { level: u32 },
 }
 
 /// Options for [`ScanPlan::extract_to_csv`] and [`ScanPlan::extract_to_tsv`].
@@ -142,8 +162,8 @@ const DEDUPE_KEY_DROP_WARN_RATE: f64 = 0.01;
 static EXTRACT_SCRATCH_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug)]
-struct PartialScanError {
-    path: PathBuf,
+pub(crate) struct PartialScanError {
+    pub(crate) path: PathBuf,
     written: u64,
 }
 
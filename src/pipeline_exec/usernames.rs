@@ -1,13 +1,14 @@
 // Usernames collection paths: the deprecated `RedditETL::usernames` shim and
-// the query-aware `ScanPlan::usernames` (plus the JS-like `for_each_username`
-// / `try_for_each_username` convenience wrappers).
+// the query-aware `ScanPlan::usernames` / `usernames_limit` (plus the JS-like
+// `for_each_username` / `try_for_each_username` convenience wrappers).
 
 impl RedditETL {
     #[deprecated(
         note = "use RedditETL::scan().usernames(); this shim ignores ETLOptions::resume \
                 (it always performs a full re-scan) and query filters"
     )]
-    pub fn usernames(self) -> Result<UsernameStream> {
+    pub fn usernames(self) -> Result<UsernameStream, RetlError> {
+        let _run_settings = self.opts.enter_run_settings();
         // The deprecated shim has no resume support: unlike `ScanPlan::usernames`
         // it never materializes a scan checkpoint. Warn loudly rather than
         // silently performing a full re-scan when the caller asked to resume.
@@ -24,22 +25,22 @@ impl RedditETL {
             .ok_or_else(|| anyhow!("subreddit is required"))?;
         let parallelism = self.opts.parallelism;
 
-        with_thread_pool(parallelism, || {
+        Ok(with_thread_pool(parallelism, || {
             let work_dir = self.ensure_work_dir()?;
             let files = plan_pipeline_files(&self, None)?;
             tracing::info!("Planned {} files for processing.", files.len());
 
-            let shard_writer =
-                ShardedWriter::create(&work_dir, "usernames", self.opts.shard_count)?;
+            let shard_writer = name_shard_writer(&self.opts, &work_dir, "usernames")?;
             let scratch_root = shard_writer.scratch_root().to_path_buf();
             let result = (|| -> Result<UsernameStream> {
                 let read_buf = self.opts.read_buffer_bytes;
 
                 let total_bytes = total_compressed_size(&files);
                 let pb = if self.opts.progress {
-                    Some(make_progress_bar_labeled(
+                    Some(make_stage_progress_bar(
                         total_bytes,
                         self.opts.progress_label.as_deref(),
+                        self.opts.progress_stage.as_ref(),
                     ))
                 } else {
                     None
@@ -89,60 +90,82 @@ impl RedditETL {
                 cleanup_scratch_dir(&scratch_root, "usernames");
             }
             result
-        })
+        })?)
     }
 }
 
 impl ScanPlan {
-    pub fn usernames(self) -> Result<UsernameStream> {
-        let plan = self.build()?;
+    pub fn usernames(self) -> Result<UsernameStream, RetlError> {
+        Ok(self.usernames_capped(None)?)
+    }
+
+    /// Like [`ScanPlan::usernames`], but stop scanning once `n` distinct
+    /// usernames have been seen, for quick samples that should not read the
+    /// whole corpus.
+    ///
+    /// The first `n` distinct names are tracked in a shared in-memory set, so
+    /// memory grows with `n` and the stream yields exactly `min(n, distinct
+    /// matches)` names. *Which* names those are depends on file order and, with
+    /// `file_concurrency > 1`, on worker timing, so they are an arbitrary
+    /// rather than a chronological sample; use `ordered_output` for the
+    /// earliest names. Unlike [`ScanPlan::limit`], which caps matched
+    /// *records*, this caps distinct authors.
+    ///
+    /// `resume` is ignored here: materializing the scan checkpoint would read
+    /// the whole corpus before the cap could apply, so a capped scan always
+    /// streams the input and stops as soon as the cap is reached.
+    pub fn usernames_limit(self, n: usize) -> Result<UsernameStream, RetlError> {
+        Ok(self.usernames_capped(Some(n))?)
+    }
+
+    fn usernames_capped(self, cap: Option<usize>) -> Result<UsernameStream> {
+        let plan = self.build_for_scan()?;
         log_pseudo_user_filter(&plan.query);
         let parallelism = plan.etl.opts.parallelism;
         with_thread_pool(parallelism, || {
             let work_dir = plan.etl.ensure_work_dir()?;
-            let shard_writer =
-                ShardedWriter::create(&work_dir, "usernames_q", plan.etl.opts.shard_count)?;
+            let shard_writer = name_shard_writer(&plan.etl.opts, &work_dir, "usernames_q")?;
             let scratch_root = shard_writer.scratch_root().to_path_buf();
 
             let result = (|| -> Result<UsernameStream> {
-                if plan.etl.opts.resume {
-                    let checkpoint = materialize_scan_checkpoint(
-                        &plan.etl,
-                        &plan.query,
-                        /*show_progress=*/ true,
-                        plan.limit,
-                    )?;
-                    for_each_checkpoint_record(
-                        &checkpoint.parts,
-                        plan.etl.opts.read_buffer_bytes,
-                        |min, _line| {
-                            if let Some(a) = min.author.as_deref() {
-                                let a = a.trim();
-                                if a.is_empty() {
-                                    return Ok(());
-                                }
-                                shard_writer.write(a)?;
+                // Names admitted so far under `cap`; unused without one.
+                let seen = Mutex::new(HashSet::<String>::new());
+                let observe = |min: &MinimalRecord| -> Result<()> {
+                    let Some(a) = min.author.as_deref() else {
+                        return Ok(());
+                    };
+                    let a = a.trim();
+                    if a.is_empty() {
+                        return Ok(());
+                    }
+                    if let Some(cap) = cap {
+                        let mut seen = seen.lock().unwrap();
+                        if !seen.contains(a) {
+                            if seen.len() >= cap {
+                                return Err(record_limit_reached_error());
                             }
-                            Ok(())
-                        },
-                    )?;
+                            seen.insert(a.to_string());
+                        }
+                    }
+                    shard_writer.write(a)
+                };
+                // A checkpoint covers the whole corpus, so only uncapped scans
+                // replay one; a capped scan must be able to stop early.
+                let scanned = if cap.is_none() {
+                    plan.for_each_matched(|min, _line| observe(min))
                 } else {
                     scan_records(
                         &plan.etl,
                         &plan.query,
                         /*show_progress=*/ true,
                         plan.limit,
-                        |min, _kind, _line| {
-                            if let Some(a) = min.author.as_deref() {
-                                let a = a.trim();
-                                if a.is_empty() {
-                                    return Ok(());
-                                }
-                                shard_writer.write(a)?;
-                            }
-                            Ok(())
-                        },
-                    )?;
+                        |min, _kind, _line| observe(min),
+                    )
+                };
+                match scanned {
+                    Ok(()) => {}
+                    Err(e) if is_record_limit_reached(&e) => {}
+                    Err(e) => return Err(e),
                 }
 
                 let (deduped, scratch_root) = shard_writer.dedup_with_scratch("usernames_q")?;
@@ -158,7 +181,7 @@ impl ScanPlan {
     /// JS-like lambda: stream **deduped** usernames and invoke a callback for each one.
     /// Example:
     ///   etl.scan().subreddit("programming").for_each_username(|name| authors.push(name.to_string()))?;
-    pub fn for_each_username<F>(self, mut f: F) -> Result<()>
+    pub fn for_each_username<F>(self, mut f: F) -> Result<(), RetlError>
     where
         F: FnMut(&str),
     {
@@ -172,7 +195,7 @@ impl ScanPlan {
     /// Fallible variant of [`ScanPlan::for_each_username`]. Callback errors and
     /// stream open/read errors are returned to the caller instead of being
     /// ignored or logged-and-skipped.
-    pub fn try_for_each_username<F>(self, mut f: F) -> Result<()>
+    pub fn try_for_each_username<F>(self, mut f: F) -> Result<(), RetlError>
     where
         F: FnMut(&str) -> Result<()>,
     {
@@ -184,3 +207,19 @@ impl ScanPlan {
         Ok(())
     }
 }
+
+/// The username-shard writer `opts` asks for: scratch files, or in-memory
+/// shards with spill under [`ETLOptions::in_memory_shards`].
+fn name_shard_writer(opts: &ETLOptions, work_dir: &Path, prefix: &str) -> Result<ShardedWriter> {
+    let writer = if opts.in_memory_shards {
+        ShardedWriter::create_in_memory(
+            work_dir,
+            prefix,
+            opts.resolved_shard_count(),
+            opts.in_memory_shard_spill_bytes,
+        )?
+    } else {
+        ShardedWriter::create(work_dir, prefix, opts.resolved_shard_count())?
+    };
+    Ok(writer.with_flush_every_bytes(opts.shard_flush_bytes))
+}
@@ -1,10 +1,28 @@
-//! Progress reporting utilities: global byte-based progress bar and total size helper.
+//! Progress reporting utilities: global byte-based progress bar, stacked
+//! pipeline-stage bars, and total size helper.
+//!
+//! Bars are `indicatif` bars when the default `progress` feature is on.
+//! Without it the same names resolve to the draw-free shims in `progress/noop.rs`, so
+//! every helper here still works and `progress(true)` simply draws nothing.
 
+use crate::error::RetlError;
 use crate::paths::FileJob;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+#[cfg(feature = "progress")]
+pub use indicatif::{MultiProgress, ProgressBar};
+#[cfg(feature = "progress")]
+use indicatif::ProgressStyle;
 use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 
+#[cfg(not(feature = "progress"))]
+mod noop;
+#[cfg(not(feature = "progress"))]
+pub use noop::{MultiProgress, ProgressBar};
+#[cfg(not(feature = "progress"))]
+use noop::ProgressStyle;
+
 /// Optional global MultiProgress that allows multiple bars to render concurrently.
 /// If unset, progress bars draw to the default terminal target.
 static GLOBAL_MP: OnceLock<Arc<MultiProgress>> = OnceLock::new();
@@ -15,21 +33,164 @@ pub fn set_global_multiprogress(mp: Arc<MultiProgress>) {
     let _ = GLOBAL_MP.set(mp);
 }
 
-fn new_bar(total: u64) -> ProgressBar {
-    if let Some(mp) = GLOBAL_MP.get() {
+fn new_bar(total: u64, stage: Option<&ProgressStage>) -> ProgressBar {
+    if let Some(stage) = stage {
+        stage.add_bar(total)
+    } else if let Some(mp) = GLOBAL_MP.get() {
         mp.add(ProgressBar::new(total))
     } else {
         ProgressBar::new(total)
     }
 }
 
+/// Stacked progress for a multi-stage pipeline (spool → collect ids →
+/// resolve → attach, say): each stage's bar stacks above one overall bar that
+/// counts finished stages, so the stages share the terminal instead of
+/// redrawing over each other and the overall bar carries the combined ETA.
+///
+/// Hand a [`ProgressStage`] to each pipeline call with
+/// [`RedditETL::progress_stage`](crate::RedditETL::progress_stage);
+/// [`run_stage`](Self::run_stage) does that bookkeeping around a closure.
+#[derive(Clone)]
+pub struct PipelineProgress {
+    mp: Arc<MultiProgress>,
+    overall: ProgressBar,
+    stages: Arc<[String]>,
+}
+
+impl PipelineProgress {
+    /// Stack on the [`set_global_multiprogress`] target when one is
+    /// installed, otherwise on a new terminal `MultiProgress`. `stages` names
+    /// the stages in run order and sizes the overall bar.
+    pub fn new<I, S>(stages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mp = GLOBAL_MP
+            .get()
+            .cloned()
+            .unwrap_or_else(|| Arc::new(MultiProgress::new()));
+        Self::with_multiprogress(mp, stages)
+    }
+
+    /// Stack on a caller-owned `MultiProgress`.
+    pub fn with_multiprogress<I, S>(mp: Arc<MultiProgress>, stages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let stages: Arc<[String]> = stages.into_iter().map(Into::into).collect();
+        let overall = mp.add(ProgressBar::new(stages.len() as u64));
+        let style = ProgressStyle::with_template(
+            "{spinner:.green} {msg} {pos}/{len} stages [{bar:.cyan/blue}] {percent:>3}%  \
+             elapsed: {elapsed_precise}  eta: {eta_precise}"
+        )
+        .unwrap()
+        .progress_chars("█▉▊▋▌▍▎▏  ");
+        overall.set_style(style);
+        overall.set_message("Pipeline");
+        overall.enable_steady_tick(std::time::Duration::from_millis(100));
+        Self { mp, overall, stages }
+    }
+
+    /// Handle for stage `name`. A name not passed to the constructor extends
+    /// the overall bar by one stage.
+    pub fn stage(&self, name: impl Into<String>) -> ProgressStage {
+        let name = name.into();
+        if !self.stages.contains(&name) {
+            self.overall.inc_length(1);
+        }
+        self.overall.set_message(format!("Pipeline: {name}"));
+        ProgressStage {
+            pipeline: self.clone(),
+            name: name.into(),
+        }
+    }
+
+    /// Run one stage: `f` receives the stage handle to pass to its pipeline
+    /// call, and the overall bar advances when `f` succeeds.
+    pub fn run_stage<T, F>(&self, name: impl Into<String>, f: F) -> Result<T, RetlError>
+    where
+        F: FnOnce(ProgressStage) -> Result<T, RetlError>,
+    {
+        let stage = self.stage(name);
+        let out = f(stage.clone())?;
+        stage.finish();
+        Ok(out)
+    }
+
+    /// Finalize the overall bar once every stage has run.
+    pub fn finish(&self) {
+        self.overall.finish_with_message("Pipeline: done");
+    }
+
+    /// The `MultiProgress` the stage bars are drawn on.
+    pub fn multi_progress(&self) -> Arc<MultiProgress> {
+        self.mp.clone()
+    }
+}
+
+impl std::fmt::Debug for PipelineProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipelineProgress")
+            .field("stages", &self.stages)
+            .field("finished", &self.overall.position())
+            .finish()
+    }
+}
+
+/// One stage of a [`PipelineProgress`]. A pipeline call configured with it
+/// draws its bar above the overall bar, labeled with the stage name unless
+/// `progress_label` is set.
+#[derive(Clone)]
+pub struct ProgressStage {
+    pipeline: PipelineProgress,
+    name: Arc<str>,
+}
+
+impl ProgressStage {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Count this stage as done on the overall bar. [`PipelineProgress::run_stage`]
+    /// calls this for you.
+    pub fn finish(&self) {
+        self.pipeline.overall.inc(1);
+    }
+
+    fn add_bar(&self, total: u64) -> ProgressBar {
+        self.pipeline
+            .mp
+            .insert_before(&self.pipeline.overall, ProgressBar::new(total))
+    }
+}
+
+impl std::fmt::Debug for ProgressStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProgressStage").field(&self.name).finish()
+    }
+}
+
 #[allow(dead_code)]
 pub fn make_progress_bar(total_bytes: u64) -> ProgressBar {
     make_progress_bar_labeled(total_bytes, None)
 }
 
 pub fn make_progress_bar_labeled(total_bytes: u64, label: Option<&str>) -> ProgressBar {
-    let pb = new_bar(total_bytes);
+    make_stage_progress_bar(total_bytes, label, None)
+}
+
+/// [`make_progress_bar_labeled`] drawn as `stage`'s bar when one is set;
+/// `label` falls back to the stage name.
+pub(crate) fn make_stage_progress_bar(
+    total_bytes: u64,
+    label: Option<&str>,
+    stage: Option<&ProgressStage>,
+) -> ProgressBar {
+    let pb = new_bar(total_bytes, stage);
+    let label = label.or(stage.map(ProgressStage::name));
     let style = ProgressStyle::with_template(
         "{spinner:.green} {msg} {bytes:>10}/{total_bytes:<10} [{bar:.cyan/blue}] {percent:>3}%  \
          {bytes_per_sec}  elapsed: {elapsed_precise}  eta: {eta_precise}"
@@ -46,7 +207,16 @@ pub fn make_progress_bar_labeled(total_bytes: u64, label: Option<&str>) -> Progr
 
 /// Count-style progress bar (items processed out of total), with an optional label.
 pub fn make_count_progress(total: u64, label: &str) -> ProgressBar {
-    let pb = new_bar(total);
+    make_stage_count_progress(total, label, None)
+}
+
+/// [`make_count_progress`] drawn as `stage`'s bar when one is set.
+pub(crate) fn make_stage_count_progress(
+    total: u64,
+    label: &str,
+    stage: Option<&ProgressStage>,
+) -> ProgressBar {
+    let pb = new_bar(total, stage);
     let style = ProgressStyle::with_template(
         "{spinner:.green} {msg} {pos}/{len} [{bar:.cyan/blue}] {percent:>3}%  \
          it/s: {per_sec}  elapsed: {elapsed_precise}  eta: {eta_precise}"
@@ -61,6 +231,35 @@ pub fn make_count_progress(total: u64, label: &str) -> ProgressBar {
     pb
 }
 
+/// Count-style bar for operations that finish whole files at a time, weighted
+/// by input size: the total is the sum of the sizes in `files_with_sizes`
+/// and each finished file should advance it by its own size, so a few huge
+/// inputs no longer make the percentage and ETA jump. See [`file_sizes`].
+pub fn make_weighted_count_progress(
+    files_with_sizes: &[(PathBuf, u64)],
+    label: &str,
+) -> ProgressBar {
+    make_stage_weighted_count_progress(files_with_sizes, label, None)
+}
+
+/// [`make_weighted_count_progress`] drawn as `stage`'s bar when one is set.
+pub(crate) fn make_stage_weighted_count_progress(
+    files_with_sizes: &[(PathBuf, u64)],
+    label: &str,
+    stage: Option<&ProgressStage>,
+) -> ProgressBar {
+    let total = files_with_sizes.iter().map(|(_, size)| size).sum();
+    make_stage_progress_bar(total, Some(label).filter(|l| !l.is_empty()), stage)
+}
+
+/// Pair each path with its size on disk; an unreadable file weighs 0.
+pub fn file_sizes(paths: &[PathBuf]) -> Vec<(PathBuf, u64)> {
+    paths
+        .iter()
+        .map(|p| (p.clone(), fs::metadata(p).map(|m| m.len()).unwrap_or(0)))
+        .collect()
+}
+
 pub fn total_compressed_size(files: &[FileJob]) -> u64 {
     files
         .iter()
@@ -72,9 +271,13 @@ pub fn total_compressed_size(files: &[FileJob]) -> u64 {
 /// Use either `ProgressScope::bytes(..)` or `ProgressScope::count(..)`.
 /// - `inc_bytes(delta)` / `inc_items(delta)` increments progress
 /// - `finish(msg)` finalizes the bar with a message
+///
+/// A scope dropped without `finish` (an early `?` return, say) finishes and
+/// clears its bar, so error paths don't leave a spinner ticking.
 pub struct ProgressScope {
     pb: ProgressBar,
     mode: Mode,
+    finished: AtomicBool,
 }
 
 enum Mode { Bytes, Count }
@@ -82,11 +285,11 @@ enum Mode { Bytes, Count }
 impl ProgressScope {
     pub fn bytes<T: Into<String>>(label: T, total_bytes: u64) -> Self {
         let pb = make_progress_bar_labeled(total_bytes, Some(&label.into()));
-        Self { pb, mode: Mode::Bytes }
+        Self { pb, mode: Mode::Bytes, finished: AtomicBool::new(false) }
     }
     pub fn count<T: Into<String>>(label: T, total: u64) -> Self {
         let pb = {
-            let pb = new_bar(total);
+            let pb = new_bar(total, None);
             let style = ProgressStyle::with_template(
                 "{spinner:.green} {msg} {pos}/{len} [{bar:.cyan/blue}] {percent:>3}%  \
                  it/s: {per_sec}  elapsed: {elapsed_precise}  eta: {eta_precise}"
@@ -101,9 +304,55 @@ impl ProgressScope {
         if !label_str.is_empty() {
             pb.set_message(label_str);
         }
-        Self { pb, mode: Mode::Count }
+        Self { pb, mode: Mode::Count, finished: AtomicBool::new(false) }
     }
     #[inline] pub fn inc_bytes(&self, delta: u64) { let _ = &self.mode; self.pb.inc(delta); }
     #[inline] pub fn inc_items(&self, delta: u64) { let _ = &self.mode; self.pb.inc(delta); }
-    pub fn finish<T: Into<String>>(&self, msg: T) { self.pb.finish_with_message(msg.into()); }
+    pub fn finish<T: Into<String>>(&self, msg: T) {
+        self.finished.store(true, Ordering::Relaxed);
+        self.pb.finish_with_message(msg.into());
+    }
+}
+
+impl Drop for ProgressScope {
+    fn drop(&mut self) {
+        if !self.finished.load(Ordering::Relaxed) {
+            self.pb.finish_and_clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_scope_drop_without_finish_finishes_bar() {
+        let scope = ProgressScope::count("unfinished", 10);
+        scope.inc_items(3);
+        let bar = scope.pb.clone();
+        assert!(!bar.is_finished());
+        drop(scope);
+        assert!(bar.is_finished());
+    }
+
+    #[test]
+    fn weighted_count_progress_totals_input_sizes() {
+        let files = vec![(PathBuf::from("a"), 10), (PathBuf::from("b"), 90)];
+        let bar = make_weighted_count_progress(&files, "weighted");
+        assert_eq!(bar.length(), Some(100));
+        bar.inc(files[1].1);
+        assert_eq!(bar.position(), 90);
+        bar.finish();
+    }
+
+    #[test]
+    fn progress_scope_drop_after_finish_keeps_message() {
+        let scope = ProgressScope::bytes("done", 10);
+        let bar = scope.pb.clone();
+        scope.finish("all done");
+        drop(scope);
+        assert!(bar.is_finished());
+        assert_eq!(bar.message(), "all done");
+    }
 }
@@ -0,0 +1,131 @@
+//! Draw-free stand-ins for the `indicatif` types RETL uses, compiled when the
+//! `progress` feature is off. They keep the same method names and shapes so
+//! call sites don't change, and they still track position, length, message
+//! and finished state so callers that read those back keep working; nothing
+//! is ever drawn.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+#[derive(Debug, Default)]
+struct BarState {
+    pos: u64,
+    len: u64,
+    message: String,
+    finished: bool,
+}
+
+/// Headless counterpart of `indicatif::ProgressBar`. Clones share state.
+#[derive(Clone, Debug, Default)]
+pub struct ProgressBar {
+    state: Arc<Mutex<BarState>>,
+}
+
+impl ProgressBar {
+    pub fn new(len: u64) -> Self {
+        let bar = Self::default();
+        bar.state.lock().len = len;
+        bar
+    }
+
+    pub fn hidden() -> Self {
+        Self::default()
+    }
+
+    pub fn set_style(&self, _style: ProgressStyle) {}
+
+    pub fn enable_steady_tick(&self, _interval: Duration) {}
+
+    pub fn inc(&self, delta: u64) {
+        let mut state = self.state.lock();
+        state.pos = state.pos.saturating_add(delta);
+    }
+
+    pub fn inc_length(&self, delta: u64) {
+        let mut state = self.state.lock();
+        state.len = state.len.saturating_add(delta);
+    }
+
+    pub fn set_position(&self, pos: u64) {
+        self.state.lock().pos = pos;
+    }
+
+    pub fn set_length(&self, len: u64) {
+        self.state.lock().len = len;
+    }
+
+    pub fn position(&self) -> u64 {
+        self.state.lock().pos
+    }
+
+    pub fn length(&self) -> Option<u64> {
+        Some(self.state.lock().len)
+    }
+
+    pub fn set_message(&self, msg: impl Into<Cow<'static, str>>) {
+        self.state.lock().message = msg.into().into_owned();
+    }
+
+    pub fn message(&self) -> String {
+        self.state.lock().message.clone()
+    }
+
+    pub fn finish(&self) {
+        self.state.lock().finished = true;
+    }
+
+    pub fn finish_with_message(&self, msg: impl Into<Cow<'static, str>>) {
+        let mut state = self.state.lock();
+        state.message = msg.into().into_owned();
+        state.finished = true;
+    }
+
+    pub fn finish_and_clear(&self) {
+        self.finish();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().finished
+    }
+
+    /// Runs `f` directly; there is no bar to hide while it prints.
+    pub fn suspend<F: FnOnce() -> R, R>(&self, f: F) -> R {
+        f()
+    }
+}
+
+/// Headless counterpart of `indicatif::ProgressStyle`; templates are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct ProgressStyle;
+
+impl ProgressStyle {
+    pub fn with_template(_template: &str) -> Result<Self, std::convert::Infallible> {
+        Ok(Self)
+    }
+
+    pub fn progress_chars(self, _chars: &str) -> Self {
+        self
+    }
+}
+
+/// Headless counterpart of `indicatif::MultiProgress`: bars are handed back
+/// unchanged.
+#[derive(Debug, Default)]
+pub struct MultiProgress;
+
+impl MultiProgress {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn add(&self, pb: ProgressBar) -> ProgressBar {
+        pb
+    }
+
+    pub fn insert_before(&self, _before: &ProgressBar, pb: ProgressBar) -> ProgressBar {
+        pb
+    }
+}
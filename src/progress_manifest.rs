@@ -41,6 +41,11 @@ pub struct MonthEntry {
     pub lines: u64,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sha256: Option<String>,
+    /// Uncompressed bytes the month wrote, charged to `max_output_bytes`
+    /// when a run resumes. `None` in manifests that predate it, in which
+    /// case `size` stands in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_bytes: Option<u64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -278,6 +283,7 @@ mod tests {
                 size: 42,
                 lines: 7,
                 sha256: None,
+                output_bytes: None,
             },
         );
         save(tmp.path(), &months, Some("fp-1")).unwrap();
@@ -324,6 +330,7 @@ mod tests {
             size: 100,
             lines: 50,
             sha256: None,
+            output_bytes: None,
         };
         let result = acc.commit("RC_2024-01".to_string(), entry);
 
@@ -357,6 +364,7 @@ mod tests {
             size: 10,
             lines: 1,
             sha256: None,
+            output_bytes: None,
         };
         let _ = acc.commit("RC_2024-01".to_string(), entry_a);
         assert!(acc.last_save_error().is_some());
@@ -368,6 +376,7 @@ mod tests {
             size: 20,
             lines: 2,
             sha256: None,
+            output_bytes: None,
         };
         acc.commit("RC_2024-02".to_string(), entry_b)
             .expect("commit should succeed");
@@ -390,6 +399,7 @@ mod tests {
             size: 30,
             lines: 3,
             sha256: None,
+            output_bytes: None,
         };
         acc2.commit("RC_2024-03".to_string(), entry_c)
             .expect("commit should succeed");
@@ -0,0 +1,82 @@
+/// `authors_in` lists at least this long get a bloom prefilter; shorter lists
+/// stay on the plain linear scan, which is already cheap.
+pub(crate) const AUTHORS_IN_BLOOM_MIN_LEN: usize = 32;
+
+const BLOOM_BITS_PER_ENTRY: usize = 10;
+const BLOOM_HASHES: u64 = 4;
+
+/// Case-insensitive `authors_in` membership for large allow-lists.
+///
+/// ASCII authors (the overwhelming majority) are hashed with on-the-fly
+/// lowercasing and checked against a bloom filter; most non-members are
+/// rejected there without touching the list. A bloom hit is confirmed with a
+/// binary search over the ASCII entries. Non-ASCII authors skip the bloom and
+/// use the same linear Unicode-lowercase comparison as short lists.
+#[derive(Debug)]
+pub(crate) struct AuthorSet {
+    bits: Vec<u64>,
+    bit_count: u64,
+    ascii_sorted: Vec<String>,
+    all: Vec<String>,
+}
+
+impl AuthorSet {
+    pub(crate) fn new(list: &[String]) -> Self {
+        let bit_count = (list.len() * BLOOM_BITS_PER_ENTRY).max(64) as u64;
+        let mut set = Self {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            ascii_sorted: Vec::new(),
+            all: list.to_vec(),
+        };
+        for entry in list.iter().filter(|s| s.is_ascii()) {
+            let (h1, h2) = ascii_ci_hashes(entry);
+            for i in 0..BLOOM_HASHES {
+                let bit = h1.wrapping_add(i.wrapping_mul(h2)) % set.bit_count;
+                set.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+            set.ascii_sorted.push(entry.to_ascii_lowercase());
+        }
+        set.ascii_sorted.sort();
+        set.ascii_sorted.dedup();
+        set
+    }
+
+    pub(crate) fn contains(&self, needle: &str) -> bool {
+        if !needle.is_ascii() {
+            let lower = needle.to_lowercase();
+            return self.all.iter().any(|s| s == &lower);
+        }
+        self.bloom_may_contain(needle)
+            && self
+                .ascii_sorted
+                .binary_search_by(|entry| {
+                    entry
+                        .bytes()
+                        .cmp(needle.bytes().map(|b| b.to_ascii_lowercase()))
+                })
+                .is_ok()
+    }
+
+    fn bloom_may_contain(&self, needle: &str) -> bool {
+        let (h1, h2) = ascii_ci_hashes(needle);
+        (0..BLOOM_HASHES).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.bit_count;
+            self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+}
+
+/// Two independent 64-bit FNV-1a hashes of the ASCII-lowercased bytes, for
+/// Kirsch–Mitzenmacher double hashing.
+fn ascii_ci_hashes(s: &str) -> (u64, u64) {
+    let mut h1: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut h2: u64 = 0x8422_2325_cbf2_9ce4;
+    for b in s.bytes().map(|b| b.to_ascii_lowercase()) {
+        h1 = (h1 ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3);
+        h2 = (h2 ^ u64::from(b))
+            .wrapping_mul(0x0000_0100_0000_01b3)
+            .rotate_left(17);
+    }
+    (h1, h2 | 1)
+}
@@ -0,0 +1,361 @@
+/// Input accepted by [`QuerySpec::author_regex`] and
+/// [`ScanPlan::author_regex`](crate::ScanPlan::author_regex). Passing a raw
+/// pattern defers compilation until [`ScanPlan::build`](crate::ScanPlan::build),
+/// so malformed regexes return a structured [`QueryBuildError`] instead of
+/// panicking during builder construction.
+#[doc(hidden)]
+pub enum AuthorRegexInput {
+    Compiled(Regex),
+    Pattern(String),
+}
+
+#[doc(hidden)]
+pub trait IntoAuthorRegex {
+    fn into_author_regex(self) -> AuthorRegexInput;
+}
+
+impl IntoAuthorRegex for Regex {
+    fn into_author_regex(self) -> AuthorRegexInput {
+        AuthorRegexInput::Compiled(self)
+    }
+}
+
+impl IntoAuthorRegex for &str {
+    fn into_author_regex(self) -> AuthorRegexInput {
+        AuthorRegexInput::Pattern(self.to_string())
+    }
+}
+
+impl IntoAuthorRegex for String {
+    fn into_author_regex(self) -> AuthorRegexInput {
+        AuthorRegexInput::Pattern(self)
+    }
+}
+
+impl IntoAuthorRegex for &String {
+    fn into_author_regex(self) -> AuthorRegexInput {
+        AuthorRegexInput::Pattern(self.clone())
+    }
+}
+
+#[inline]
+fn lowercase_str(s: &str) -> String {
+    s.to_lowercase()
+}
+
+// Builder methods mirror the `ScanPlan` filter builders one-for-one (those
+// delegate here), so a query can be assembled once without a `RedditETL` and
+// handed to any number of plans via `ScanPlan::with_query`. See the `ScanPlan`
+// method of the same name for the full matching semantics.
+impl QuerySpec {
+    /// Empty query with the same defaults as
+    /// [`RedditETL::scan`](crate::RedditETL::scan): no filters, pseudo-users
+    /// (`[deleted]`, `[removed]`, empty author) excluded.
+    /// `QuerySpec::default()` keeps pseudo-users.
+    pub fn new() -> Self {
+        QuerySpec {
+            filter_pseudo_users: true,
+            ..Default::default()
+        }
+        .normalize()
+    }
+
+    /// Map an iterator of strings through `norm`, store it with `set_field`,
+    /// then renormalize.
+    fn set_string_list<I, S>(
+        mut self,
+        set_field: impl FnOnce(&mut QuerySpec, Vec<String>),
+        iter: I,
+        norm: fn(&str) -> String,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let v: Vec<String> = iter.into_iter().map(|s| norm(s.as_ref())).collect();
+        set_field(&mut self, v);
+        self.normalize()
+    }
+
+    pub fn subreddit(mut self, s: impl AsRef<str>) -> Self {
+        self.subreddits = Some(vec![normalize_str(s.as_ref())]);
+        self
+    }
+    pub fn subreddits<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.subreddits = Some(v), iter, normalize_str)
+    }
+    /// Reject records from these subreddits; a subreddit named in both the
+    /// allow- and deny-list is excluded.
+    pub fn subreddits_out<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.subreddits_out = Some(v), iter, normalize_str)
+    }
+    /// Alias for [`QuerySpec::subreddits_out`].
+    pub fn exclude_subreddits<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.subreddits_out(iter)
+    }
+    /// Keep every record from a deterministic `fraction` of subreddits. See
+    /// [`SubredditSample`].
+    pub fn sample_subreddits(mut self, fraction: f64, seed: u64) -> Self {
+        self.subreddit_sample = Some(SubredditSample::new(fraction, seed));
+        self
+    }
+    pub fn author(mut self, author: impl AsRef<str>) -> Self {
+        self.authors_in = Some(vec![normalize_author(author.as_ref())]);
+        self.normalize()
+    }
+    pub fn authors<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.authors_in(iter)
+    }
+    pub fn authors_in<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.authors_in = Some(v), iter, normalize_author)
+    }
+    pub fn authors_out<I, S>(mut self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self = self.set_string_list(|q, v| q.authors_out = Some(v), iter, normalize_author);
+        self.authors_out_explicit = true;
+        self
+    }
+    /// Alias for [`QuerySpec::authors_out`].
+    pub fn exclude_authors<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.authors_out(iter)
+    }
+    /// Merge the default bot/service deny-list into `authors_out` when the
+    /// owning plan is built (see the `exclude_common_bots` field).
+    pub fn exclude_common_bots(mut self) -> Self {
+        self.exclude_common_bots = true;
+        self
+    }
+    pub fn author_regex<R: IntoAuthorRegex>(mut self, re: R) -> Self {
+        match re.into_author_regex() {
+            AuthorRegexInput::Compiled(re) => {
+                self.author_regex_pattern = Some(re.as_str().to_string());
+                self.author_regex = Some(re);
+            }
+            AuthorRegexInput::Pattern(pattern) => {
+                self.author_regex_pattern = Some(pattern);
+                self.author_regex = None;
+            }
+        }
+        self
+    }
+    pub fn include_pseudo_users(mut self) -> Self {
+        self.filter_pseudo_users = false;
+        self
+    }
+
+    /// Add bare or `t1_`/`t3_`-prefixed record ID selectors. Repeated calls
+    /// accumulate.
+    pub fn ids<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.ids_in(iter)
+    }
+    /// Alias for [`QuerySpec::ids`]; like `ids`, repeated calls accumulate.
+    pub fn ids_in<I, S>(mut self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        // Append onto the unqualified bucket and re-normalize: `normalize`
+        // re-buckets every ID list, so previously supplied `t1_`/`t3_`
+        // selectors (already split into `comment_ids_in`/`submission_ids_in`)
+        // keep their source constraint instead of being wiped.
+        let new = iter.into_iter().map(|s| s.as_ref().to_string());
+        self.ids_in.get_or_insert_with(Vec::new).extend(new);
+        self.normalize()
+    }
+    /// Load record ID selectors from a newline-delimited file (see
+    /// [`read_record_ids_file`]) and add them as an [`ids`](QuerySpec::ids) filter.
+    pub fn ids_file(self, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let ids = read_record_ids_file(path.as_ref())?;
+        Ok(self.ids_in(ids))
+    }
+    pub fn top_level_comments_only(mut self, yes: bool) -> Self {
+        self.top_level_comments_only = yes;
+        self
+    }
+
+    pub fn keywords_any<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.keywords_any = Some(v), iter, lowercase_str)
+    }
+    pub fn keywords_all<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.keywords_all = Some(v), iter, lowercase_str)
+    }
+    pub fn exclude_keywords<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.keywords_exclude = Some(v), iter, lowercase_str)
+    }
+    /// Set a text regex; the pattern is compiled when the owning plan is
+    /// built, so a malformed one surfaces as [`QueryBuildError`].
+    pub fn text_regex(mut self, pattern: impl Into<String>) -> Self {
+        self.text_regex_pattern = Some(pattern.into());
+        self.text_regex = None;
+        self
+    }
+    /// Reject records whose `body`/`selftext` carries a common bot footer
+    /// (see the `exclude_bot_bodies` field).
+    pub fn exclude_bot_bodies(mut self) -> Self {
+        self.exclude_bot_bodies = true;
+        self
+    }
+    /// Reject records whose `body` or `selftext` matches `re`. Repeated calls
+    /// add patterns; a record matching any of them is rejected.
+    pub fn exclude_body_regex(mut self, re: Regex) -> Self {
+        self.body_regex_exclude.push(re);
+        self
+    }
+    pub fn domains_in<I, S>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.set_string_list(|q, v| q.domains_in = Some(v), iter, lowercase_str)
+    }
+    /// `true` keeps only records with an HTTP(S) URL; `false` clears the filter.
+    pub fn contains_url(mut self, yes: bool) -> Self {
+        self.contains_url = yes.then_some(true);
+        self
+    }
+    pub fn no_url(mut self) -> Self {
+        self.no_url = true;
+        self
+    }
+    /// Alias for [`QuerySpec::no_url`].
+    pub fn without_url(self) -> Self {
+        self.no_url()
+    }
+
+    pub fn min_score(mut self, v: i64) -> Self {
+        self.min_score = Some(v);
+        self
+    }
+    pub fn max_score(mut self, v: i64) -> Self {
+        self.max_score = Some(v);
+        self
+    }
+    pub fn created_utc_gte(mut self, ts: i64) -> Self {
+        self.timestamp_bounds.created_utc_gte = Some(ts);
+        self
+    }
+    pub fn created_utc_lt(mut self, ts: i64) -> Self {
+        self.timestamp_bounds.created_utc_lt = Some(ts);
+        self
+    }
+    pub fn timestamp_bounds(
+        mut self,
+        created_utc_gte: Option<i64>,
+        created_utc_lt: Option<i64>,
+    ) -> Self {
+        self.timestamp_bounds = TimestampBounds::new(created_utc_gte, created_utc_lt);
+        self
+    }
+    /// Alias for [`QuerySpec::created_utc_gte`].
+    pub fn after(self, ts: i64) -> Self {
+        self.created_utc_gte(ts)
+    }
+    /// Alias for [`QuerySpec::created_utc_lt`].
+    pub fn before(self, ts: i64) -> Self {
+        self.created_utc_lt(ts)
+    }
+    pub fn time_field(mut self, field: TimeField) -> Self {
+        self.time_field = field;
+        self
+    }
+
+    pub fn json_predicate(mut self, predicate: JsonPointerPredicate) -> Self {
+        self.json_predicates.push(predicate);
+        self
+    }
+    pub fn json_predicates<I>(mut self, predicates: I) -> Self
+    where
+        I: IntoIterator<Item = JsonPointerPredicate>,
+    {
+        self.json_predicates.extend(predicates);
+        self
+    }
+    /// Keep only records for which `f` returns true, checked on the parsed
+    /// record after every built-in filter. Replaces any earlier closure.
+    pub fn filter(mut self, f: Arc<dyn Fn(&Value) -> bool + Send + Sync>) -> Self {
+        self.filter = Some(RecordFilter(f));
+        self
+    }
+    pub fn json_exists(self, pointer: impl Into<String>) -> Self {
+        self.json_predicate(JsonPointerPredicate::exists(pointer))
+    }
+    /// Alias for [`QuerySpec::json_exists`].
+    pub fn has_field(self, pointer: impl Into<String>) -> Self {
+        self.json_exists(pointer)
+    }
+    pub fn missing_field(self, pointer: impl Into<String>) -> Self {
+        self.json_predicate(JsonPointerPredicate::missing(pointer))
+    }
+    pub fn json_eq(self, pointer: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.json_predicate(JsonPointerPredicate::equals(pointer, value))
+    }
+    pub fn json_ne(self, pointer: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.json_predicate(JsonPointerPredicate::not_equals(pointer, value))
+    }
+    pub fn json_number_cmp(
+        self,
+        pointer: impl Into<String>,
+        op: NumericComparison,
+        value: f64,
+    ) -> Self {
+        self.json_predicate(JsonPointerPredicate::number(pointer, op, value))
+    }
+    pub fn json_number_gt(self, pointer: impl Into<String>, value: f64) -> Self {
+        self.json_number_cmp(pointer, NumericComparison::GreaterThan, value)
+    }
+    pub fn json_number_gte(self, pointer: impl Into<String>, value: f64) -> Self {
+        self.json_number_cmp(pointer, NumericComparison::GreaterThanOrEqual, value)
+    }
+    pub fn json_number_lt(self, pointer: impl Into<String>, value: f64) -> Self {
+        self.json_number_cmp(pointer, NumericComparison::LessThan, value)
+    }
+    pub fn json_number_lte(self, pointer: impl Into<String>, value: f64) -> Self {
+        self.json_number_cmp(pointer, NumericComparison::LessThanOrEqual, value)
+    }
+    pub fn json_regex(self, pointer: impl Into<String>, pattern: impl Into<String>) -> Self {
+        self.json_predicate(JsonPointerPredicate::regex(pointer, pattern))
+    }
+}
@@ -4,7 +4,11 @@ include!("validation.rs");
 include!("record_ids.rs");
 include!("predicates.rs");
 include!("timestamps.rs");
+include!("subreddit_sample.rs");
+include!("source_ratio.rs");
 include!("spec.rs");
+include!("builders.rs");
 include!("normalize.rs");
+include!("author_set.rs");
 include!("validation_tail.rs");
 include!("tests.rs");
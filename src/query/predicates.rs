@@ -32,6 +32,7 @@ impl NumericComparison {
 #[derive(Debug, Clone)]
 enum JsonPredicateKind {
     Exists,
+    Missing,
     Equals(Value),
     NotEquals(Value),
     Number {
@@ -70,6 +71,15 @@ impl JsonPointerPredicate {
         }
     }
 
+    /// Match when the pointer does not resolve. A present JSON `null` counts
+    /// as present, so this is the exact complement of [`JsonPointerPredicate::exists`].
+    pub fn missing(pointer: impl Into<String>) -> Self {
+        Self {
+            pointer: pointer.into(),
+            kind: JsonPredicateKind::Missing,
+        }
+    }
+
     /// Match when the pointer resolves to the provided scalar JSON value.
     pub fn equals(pointer: impl Into<String>, value: impl Into<Value>) -> Self {
         Self {
@@ -145,7 +155,7 @@ impl JsonPointerPredicate {
                     ))
                 })?;
             }
-            JsonPredicateKind::Exists => {}
+            JsonPredicateKind::Exists | JsonPredicateKind::Missing => {}
         }
         Ok(())
     }
@@ -168,6 +178,7 @@ impl JsonPointerPredicate {
     pub(crate) fn matches(&self, record: &Value) -> bool {
         match &self.kind {
             JsonPredicateKind::Exists => record.pointer(&self.pointer).is_some(),
+            JsonPredicateKind::Missing => record.pointer(&self.pointer).is_none(),
             JsonPredicateKind::Equals(expected) => record
                 .pointer(&self.pointer)
                 .is_some_and(|actual| scalar_values_equal(actual, expected)),
@@ -208,6 +219,10 @@ impl JsonPointerPredicate {
                 "pointer": self.pointer.as_str(),
                 "op": "exists",
             }),
+            JsonPredicateKind::Missing => serde_json::json!({
+                "pointer": self.pointer.as_str(),
+                "op": "missing",
+            }),
             JsonPredicateKind::Equals(value) => serde_json::json!({
                 "pointer": self.pointer.as_str(),
                 "op": "=",
@@ -0,0 +1,88 @@
+/// Target comment:submission mix set by
+/// [`ScanPlan::source_ratio`](crate::ScanPlan::source_ratio).
+///
+/// `keep` holds the per-kind keep fractions once a pre-count of the matched
+/// records has resolved them; until then every record is kept. A record is
+/// kept when the seeded hash of its `id` falls in the lowest fraction of the
+/// hash range, so the same corpus and query keep the same records on every
+/// run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SourceRatio {
+    pub(crate) comments: u32,
+    pub(crate) submissions: u32,
+    pub(crate) keep: Option<SourceKeep>,
+}
+
+/// Resolved keep fractions for a [`SourceRatio`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SourceKeep {
+    pub(crate) comments: f64,
+    pub(crate) submissions: f64,
+}
+
+impl SourceRatio {
+    pub(crate) const fn new(comments: u32, submissions: u32) -> Self {
+        Self {
+            comments,
+            submissions,
+            keep: None,
+        }
+    }
+
+    /// Fractions that bring `matched_comments : matched_submissions` to the
+    /// target ratio while keeping every record of the scarcer kind. A kind
+    /// with nothing matched cannot be balanced against, so the other is kept
+    /// whole.
+    pub(crate) fn resolve(self, matched_comments: u64, matched_submissions: u64) -> SourceKeep {
+        let (c, s) = (f64::from(self.comments), f64::from(self.submissions));
+        let (mc, ms) = (matched_comments as f64, matched_submissions as f64);
+        let (comments, submissions) = if self.comments == 0 {
+            (0.0, 1.0)
+        } else if self.submissions == 0 {
+            (1.0, 0.0)
+        } else if matched_comments == 0 || matched_submissions == 0 {
+            (1.0, 1.0)
+        } else if mc > ms * c / s {
+            // More comments than the submissions on hand call for: thin
+            // comments, otherwise thin submissions.
+            (ms * c / s / mc, 1.0)
+        } else {
+            (1.0, mc * s / c / ms)
+        };
+        SourceKeep {
+            comments,
+            submissions,
+        }
+    }
+
+    /// Whether a record of `kind` with `id` falls inside the resolved
+    /// sample. Records without an `id` are dropped from a thinned kind.
+    #[inline]
+    pub(crate) fn keeps(self, kind: RecordIdKind, id: Option<&str>) -> bool {
+        let Some(keep) = self.keep else {
+            return true;
+        };
+        let fraction = match kind {
+            RecordIdKind::Comment => keep.comments,
+            RecordIdKind::Submission => keep.submissions,
+        };
+        if fraction >= 1.0 {
+            return true;
+        }
+        let Some(id) = id else {
+            return false;
+        };
+        // `as` saturates, so a fraction just below 1.0 cannot wrap to 0.
+        let threshold = (fraction * u64::MAX as f64) as u64;
+        crate::util::seeded_fraction_hash(b"source_ratio", id.as_bytes()) < threshold
+    }
+
+    pub(crate) fn validate(self) -> Result<(), QueryBuildError> {
+        if self.comments == 0 && self.submissions == 0 {
+            return Err(QueryBuildError::new(
+                "source_ratio needs a nonzero comments or submissions share",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,37 @@
 
+/// Closure set by [`ScanPlan::transform`](crate::ScanPlan::transform), run on
+/// each record just before it is written.
+#[derive(Clone)]
+pub(crate) struct RecordTransform(pub(crate) Arc<dyn Fn(&mut Value) + Send + Sync>);
+
+impl fmt::Debug for RecordTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecordTransform(<custom>)")
+    }
+}
+
+/// Predicate set by [`QuerySpec::filter`], run on the parsed record after the
+/// built-in filters.
+#[derive(Clone)]
+pub(crate) struct RecordFilter(pub(crate) Arc<dyn Fn(&Value) -> bool + Send + Sync>);
+
+impl fmt::Debug for RecordFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecordFilter(<custom>)")
+    }
+}
+
 /// High-level query/filter spec for advanced scans.
 /// All string lists are matched case-insensitively (Unicode-aware for keywords;
 /// subreddit/author/domain matching normalizes non-ASCII values via lowercase).
 #[derive(Debug, Default)]
 pub struct QuerySpec {
     pub subreddits: Option<Vec<String>>,
+    /// Subreddit deny-list. A record whose subreddit is listed is rejected even
+    /// when `subreddits` is `None` (all subreddits); when both lists name the
+    /// same subreddit, the deny-list wins. Record-level only: monthly files
+    /// cannot be pruned by subreddit.
+    pub subreddits_out: Option<Vec<String>>,
     /// Bare Reddit record IDs (without `t1_` / `t3_`) that match either source.
     /// Prefer [`ScanPlan::ids`](crate::ScanPlan::ids) /
     /// [`ScanPlan::ids_in`](crate::ScanPlan::ids_in) when accepting user input;
@@ -32,6 +59,10 @@ pub struct QuerySpec {
     /// Exact Unix timestamp bounds for the top-level `created_utc` field.
     /// Lower bound is inclusive; upper bound is exclusive.
     pub timestamp_bounds: TimestampBounds,
+    /// Record timestamp read by `timestamp_bounds`, the record-level month
+    /// bounds, and time-derived operations. Defaults to `created_utc`; the
+    /// bound field names keep their historical `created_utc_*` spelling.
+    pub time_field: TimeField,
     /// Keep records where at least one keyword is present in `body`, `selftext`, or `title`.
     pub keywords_any: Option<Vec<String>>, // substring in body/selftext/title (case-insensitive)
     /// Keep records only when every keyword is present across `body`, `selftext`, and `title`.
@@ -41,6 +72,12 @@ pub struct QuerySpec {
     /// Regex matched against `body`, `selftext`, or `title` on the MinimalRecord fast path.
     pub text_regex: Option<Regex>,
     pub(crate) text_regex_pattern: Option<String>,
+    /// Reject records whose `body` or `selftext` carries a common bot footer
+    /// (e.g. AutoModerator's "I am a bot, and this action was performed
+    /// automatically"), catching bots whatever their username.
+    pub exclude_bot_bodies: bool,
+    /// Reject records whose `body` or `selftext` matches any of these.
+    pub body_regex_exclude: Vec<Regex>,
     /// Submissions only: matches the top-level `domain` field. Comments do not
     /// have this field and are rejected when the filter is active.
     pub domains_in: Option<Vec<String>>,
@@ -63,13 +100,41 @@ pub struct QuerySpec {
     /// invalid pattern as a [`QueryBuildError`] up front, whereas the lazy
     /// path *panics* mid-scan on a pattern that never passed validation.
     pub json_predicates: Vec<JsonPointerPredicate>,
+    /// Caller-supplied full-record predicate, ANDed with every other filter.
+    /// Set by [`QuerySpec::filter`].
+    pub(crate) filter: Option<RecordFilter>,
     pub filter_pseudo_users: bool, // exclude [deleted]/[removed]/empty author; default true
+    /// Comments only: keep a comment only when its `parent_id` names a
+    /// submission (`t3_`), i.e. a direct reply to the post. Submissions are
+    /// unaffected.
+    pub top_level_comments_only: bool,
+    /// Keep only a deterministic fraction of subreddits, each with all of its
+    /// records. See [`SubredditSample`].
+    pub subreddit_sample: Option<SubredditSample>,
+    /// Drop every record whose key an earlier record already had. Set by
+    /// [`ScanPlan::dedupe_records_by`](crate::ScanPlan::dedupe_records_by).
+    pub(crate) dedupe_records: Option<Arc<KeyExtractor>>,
+    /// Mutate every written record. Set by
+    /// [`ScanPlan::transform`](crate::ScanPlan::transform).
+    pub(crate) transform: Option<RecordTransform>,
+    /// Coerce the written `edited` field. Set by
+    /// [`ScanPlan::normalize_edited`](crate::ScanPlan::normalize_edited).
+    pub(crate) normalize_edited: EditedMode,
+    /// Lowercase the written `subreddit` field. Set by
+    /// [`ScanPlan::canonicalize_subreddit_case`](crate::ScanPlan::canonicalize_subreddit_case).
+    pub(crate) canonicalize_subreddit_case: bool,
+    /// Thin comments or submissions toward a target mix. Set by
+    /// [`ScanPlan::source_ratio`](crate::ScanPlan::source_ratio).
+    pub(crate) source_ratio: Option<SourceRatio>,
 
     // Lazily-built case-insensitive automatons over keyword families.
     // Built once per QuerySpec on first call to the corresponding accessor.
     pub(crate) compiled_keywords_any: OnceLock<Arc<AhoCorasick>>,
     pub(crate) compiled_keywords_all: OnceLock<Arc<AhoCorasick>>,
     pub(crate) compiled_keywords_exclude: OnceLock<Arc<AhoCorasick>>,
+    // Bloom-prefiltered `authors_in` lookup, built on first use when the list
+    // reaches `AUTHORS_IN_BLOOM_MIN_LEN`.
+    pub(crate) compiled_authors_in: OnceLock<Arc<AuthorSet>>,
 }
 
 fn clone_keyword_cache(cache: &OnceLock<Arc<AhoCorasick>>) -> OnceLock<Arc<AhoCorasick>> {
@@ -84,6 +149,7 @@ impl Clone for QuerySpec {
     fn clone(&self) -> Self {
         Self {
             subreddits: self.subreddits.clone(),
+            subreddits_out: self.subreddits_out.clone(),
             ids_in: self.ids_in.clone(),
             comment_ids_in: self.comment_ids_in.clone(),
             submission_ids_in: self.submission_ids_in.clone(),
@@ -96,19 +162,31 @@ impl Clone for QuerySpec {
             min_score: self.min_score,
             max_score: self.max_score,
             timestamp_bounds: self.timestamp_bounds,
+            time_field: self.time_field,
             keywords_any: self.keywords_any.clone(),
             keywords_all: self.keywords_all.clone(),
             keywords_exclude: self.keywords_exclude.clone(),
             text_regex: self.text_regex.clone(),
             text_regex_pattern: self.text_regex_pattern.clone(),
+            exclude_bot_bodies: self.exclude_bot_bodies,
+            body_regex_exclude: self.body_regex_exclude.clone(),
             domains_in: self.domains_in.clone(),
             contains_url: self.contains_url,
             no_url: self.no_url,
             json_predicates: self.json_predicates.clone(),
+            filter: self.filter.clone(),
             filter_pseudo_users: self.filter_pseudo_users,
+            top_level_comments_only: self.top_level_comments_only,
+            subreddit_sample: self.subreddit_sample,
+            dedupe_records: self.dedupe_records.clone(),
+            transform: self.transform.clone(),
+            normalize_edited: self.normalize_edited,
+            canonicalize_subreddit_case: self.canonicalize_subreddit_case,
+            source_ratio: self.source_ratio,
             compiled_keywords_any: clone_keyword_cache(&self.compiled_keywords_any),
             compiled_keywords_all: clone_keyword_cache(&self.compiled_keywords_all),
             compiled_keywords_exclude: clone_keyword_cache(&self.compiled_keywords_exclude),
+            compiled_authors_in: self.compiled_authors_in.clone(),
         }
     }
 }
@@ -128,10 +206,21 @@ impl QuerySpec {
             }
         };
 
+        let author_sort_dedup = |v: &mut Option<Vec<String>>| {
+            if let Some(list) = v.as_mut() {
+                for s in list.iter_mut() {
+                    *s = normalize_author(s);
+                }
+                list.sort();
+                list.dedup();
+            }
+        };
+
         lower_sort_dedup(&mut self.subreddits);
+        lower_sort_dedup(&mut self.subreddits_out);
         normalize_id_filters(&mut self);
-        lower_sort_dedup(&mut self.authors_in);
-        lower_sort_dedup(&mut self.authors_out);
+        author_sort_dedup(&mut self.authors_in);
+        author_sort_dedup(&mut self.authors_out);
 
         normalize_trim_lower_list(&mut self.keywords_any);
         normalize_trim_lower_list(&mut self.keywords_all);
@@ -149,6 +238,7 @@ impl QuerySpec {
         self.compiled_keywords_any = OnceLock::new();
         self.compiled_keywords_all = OnceLock::new();
         self.compiled_keywords_exclude = OnceLock::new();
+        self.compiled_authors_in = OnceLock::new();
 
         self
     }
@@ -167,6 +257,7 @@ impl QuerySpec {
         self.timestamp_bounds.validate()?;
 
         validate_string_list_filter("subreddits", &self.subreddits)?;
+        validate_string_list_filter("subreddits_out", &self.subreddits_out)?;
         validate_id_list_filter("ids_in", &self.ids_in)?;
         validate_id_list_filter("comment_ids_in", &self.comment_ids_in)?;
         validate_id_list_filter("submission_ids_in", &self.submission_ids_in)?;
@@ -185,6 +276,12 @@ impl QuerySpec {
         }
 
         validate_text_regex_filter(&self.text_regex_pattern, &self.text_regex)?;
+        if let Some(sample) = self.subreddit_sample {
+            sample.validate()?;
+        }
+        if let Some(ratio) = self.source_ratio {
+            ratio.validate()?;
+        }
 
         if let (Some(allow), Some(deny)) = (&self.authors_in, &self.authors_out) {
             if let Some(author) = allow.iter().find(|a| deny.iter().any(|d| d == *a)) {
@@ -236,11 +333,12 @@ impl QuerySpec {
         Ok(self)
     }
 
-    /// Only JSON-pointer predicates require full-record parsing; all fixed
-    /// filters, including `created_utc` timestamp bounds, are handled on the
-    /// MinimalRecord fast path.
+    /// Only JSON-pointer predicates and a [`QuerySpec::filter`] closure
+    /// require full-record parsing; all fixed filters, including
+    /// `created_utc` timestamp bounds, are handled on the MinimalRecord fast
+    /// path.
     pub fn requires_full_parse(&self) -> bool {
-        !self.json_predicates.is_empty()
+        !self.json_predicates.is_empty() || self.filter.is_some()
     }
 
     /// Returns a lazily-built case-insensitive Aho-Corasick automaton over
@@ -250,6 +348,20 @@ impl QuerySpec {
         self.keywords_any_automaton()
     }
 
+    /// Bloom-prefiltered set for a large `authors_in` list; `None` for short
+    /// or absent lists, which the filter scans linearly.
+    pub(crate) fn authors_in_set(&self) -> Option<&AuthorSet> {
+        let list = self.authors_in.as_ref()?;
+        if list.len() < AUTHORS_IN_BLOOM_MIN_LEN {
+            return None;
+        }
+        Some(
+            self.compiled_authors_in
+                .get_or_init(|| Arc::new(AuthorSet::new(list)))
+                .as_ref(),
+        )
+    }
+
     pub fn keywords_any_automaton(&self) -> Option<&AhoCorasick> {
         keyword_automaton_for(
             &self.keywords_any,
@@ -351,6 +463,7 @@ impl QuerySpec {
 
     pub(crate) fn has_selective_filters(&self) -> bool {
         self.subreddits.as_ref().is_some_and(|v| !v.is_empty())
+            || self.subreddits_out.as_ref().is_some_and(|v| !v.is_empty())
             || self.has_id_filters()
             || self.authors_in.as_ref().is_some_and(|v| !v.is_empty())
             || self.author_regex.is_some()
@@ -368,9 +481,14 @@ impl QuerySpec {
                 .is_some_and(|v| !v.is_empty())
             || self.text_regex.is_some()
             || self.text_regex_pattern.is_some()
+            || !self.body_regex_exclude.is_empty()
             || self.domains_in.as_ref().is_some_and(|v| !v.is_empty())
             || self.contains_url == Some(true)
             || self.no_url
             || !self.json_predicates.is_empty()
+            || self.filter.is_some()
+            || self.top_level_comments_only
+            || self.subreddit_sample.is_some()
+            || self.source_ratio.is_some()
     }
 }
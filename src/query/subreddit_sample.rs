@@ -0,0 +1,51 @@
+/// Deterministic whole-subreddit sample: a subreddit is either kept with all
+/// of its records or dropped entirely.
+///
+/// Each subreddit name (compared case-insensitively) is hashed together with
+/// `seed`; the subreddit is kept when its hash falls in the lowest `fraction`
+/// of the hash range. The same `(fraction, seed)` keeps the same subreddits on
+/// every run and machine, and a larger fraction with the same seed keeps a
+/// superset of a smaller one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubredditSample {
+    pub fraction: f64,
+    pub seed: u64,
+}
+
+impl SubredditSample {
+    pub const fn new(fraction: f64, seed: u64) -> Self {
+        Self { fraction, seed }
+    }
+
+    /// Whether `subreddit` falls inside the sample.
+    #[inline]
+    pub fn keeps(self, subreddit: &str) -> bool {
+        if self.fraction >= 1.0 {
+            return true;
+        }
+        // `as` saturates, so a fraction just below 1.0 cannot wrap to 0.
+        let threshold = (self.fraction * u64::MAX as f64) as u64;
+        subreddit_sample_hash(self.seed, subreddit) < threshold
+    }
+
+    pub(crate) fn validate(self) -> Result<(), QueryBuildError> {
+        if !(0.0..=1.0).contains(&self.fraction) {
+            return Err(QueryBuildError::new(format!(
+                "sample_subreddits fraction ({}) must be between 0.0 and 1.0",
+                self.fraction
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Seeded, platform-stable hash of a lowercased subreddit name. Lowercase
+/// ASCII names, the common case, are hashed without allocating.
+fn subreddit_sample_hash(seed: u64, subreddit: &str) -> u64 {
+    let lowered = if subreddit.is_ascii() && !subreddit.bytes().any(|b| b.is_ascii_uppercase()) {
+        std::borrow::Cow::Borrowed(subreddit)
+    } else {
+        std::borrow::Cow::Owned(subreddit.to_lowercase())
+    };
+    crate::util::seeded_fraction_hash(&seed.to_le_bytes(), lowered.as_bytes())
+}
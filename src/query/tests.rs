@@ -125,4 +125,65 @@ mod tests {
         // reject it as a blank entry rather than letting it through.
         assert_eq!(normalize_str("r/   "), "");
     }
+
+    #[test]
+    fn normalize_author_strips_user_prefixes() {
+        assert_eq!(normalize_author("u/Alice"), "alice");
+        assert_eq!(normalize_author(" /u/Alice "), "alice");
+        assert_eq!(normalize_author("U/ alice"), "alice");
+        assert_eq!(normalize_author("@ALICE"), "alice");
+        assert_eq!(normalize_author("alice"), "alice");
+        assert_eq!(strip_author_prefix("u/Alice"), "Alice");
+        // Author normalization leaves `r/` alone and subreddit normalization
+        // leaves `u/` alone.
+        assert_eq!(normalize_author("r/alice"), "r/alice");
+        assert_eq!(normalize_str("u/alice"), "u/alice");
+        assert_eq!(normalize_author("u/   "), "");
+
+        let q = QuerySpec {
+            authors_in: Some(vec!["u/Alice".into(), "alice".into()]),
+            ..Default::default()
+        }
+        .normalize();
+        assert_eq!(q.authors_in, Some(vec!["alice".to_string()]));
+    }
+
+    #[test]
+    fn authors_in_bloom_prefilter_agrees_with_linear_scan() {
+        fn linear(list: &[String], needle: &str) -> bool {
+            if needle.is_ascii() {
+                list.iter().any(|s| s.eq_ignore_ascii_case(needle))
+            } else {
+                let lower = needle.to_lowercase();
+                list.iter().any(|s| s == &lower)
+            }
+        }
+
+        let mut authors: Vec<String> = (0..200).map(|i| format!("User_{i}")).collect();
+        authors.push("Émile".to_string());
+        let query = QuerySpec {
+            authors_in: Some(authors),
+            ..Default::default()
+        }
+        .normalize();
+        let list = query.authors_in.clone().unwrap();
+        let set = query.authors_in_set().expect("list is above the bloom threshold");
+
+        let mut candidates: Vec<String> = Vec::new();
+        for i in 0..2_000 {
+            candidates.push(format!("user_{i}"));
+            candidates.push(format!("USER_{i}"));
+            candidates.push(format!("someone_else_{i}"));
+        }
+        candidates.extend(["émile", "ÉMILE", "emile", "", "[deleted]"].map(String::from));
+        for c in &candidates {
+            assert_eq!(set.contains(c), linear(&list, c), "author {c:?}");
+        }
+
+        let short = QuerySpec {
+            authors_in: Some(vec!["alice".to_string()]),
+            ..Default::default()
+        };
+        assert!(short.authors_in_set().is_none());
+    }
 }
@@ -76,6 +76,67 @@ impl TimestampBounds {
     }
 }
 
+/// Which record timestamp the query's time filters read.
+///
+/// Applies to [`TimestampBounds`], the record-level `start`/`end` month gate,
+/// and time-derived outputs such as `count_by_month`. Monthly dump files are
+/// partitioned by creation month regardless, so `start`/`end` still select
+/// files by `created_utc`; because edits and retrieval happen after creation,
+/// only the upper end of a timestamp bound narrows file planning for
+/// [`TimeField::Edited`] and [`TimeField::RetrievedOn`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeField {
+    /// Top-level `created_utc` (the default).
+    #[default]
+    Created,
+    /// Top-level `edited`, when it holds a timestamp. Records whose `edited`
+    /// is `false` (never edited) or a bare `true` have no value and are
+    /// rejected by active bounds.
+    Edited,
+    /// Top-level `retrieved_on`: when the archiver fetched the record.
+    RetrievedOn,
+}
+
+impl TimeField {
+    /// JSON field name this variant reads.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created_utc",
+            Self::Edited => "edited",
+            Self::RetrievedOn => "retrieved_on",
+        }
+    }
+}
+
+/// How written records carry the top-level `edited` field, which Reddit
+/// stores as `false` for never-edited records and as a Unix timestamp
+/// otherwise. Set by
+/// [`ScanPlan::normalize_edited`](crate::ScanPlan::normalize_edited).
+///
+/// Records without an `edited` field are written without one in every mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EditedMode {
+    /// Write `edited` as found (the default).
+    #[default]
+    Leave,
+    /// Always a boolean: `false`, `0` and `null` become `false`, anything
+    /// else (a timestamp, a bare `true`) becomes `true`.
+    AsBool,
+    /// Always a timestamp or `null`: a numeric `edited` becomes an RFC3339
+    /// string, and `false`, `true` or `0` become `null`.
+    AsTimestamp,
+}
+
+impl EditedMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Leave => "leave",
+            Self::AsBool => "as_bool",
+            Self::AsTimestamp => "as_timestamp",
+        }
+    }
+}
+
 fn unix_timestamp_to_year_month(ts: i64) -> Option<YearMonth> {
     let dt = OffsetDateTime::from_unix_timestamp(ts).ok()?;
     let year = dt.year();
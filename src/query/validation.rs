@@ -1,4 +1,5 @@
 use crate::date::YearMonth;
+use crate::key_extractor::KeyExtractor;
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
 use anyhow::Context;
 use regex::Regex;
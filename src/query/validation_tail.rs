@@ -169,6 +169,15 @@ fn validate_string_list_filter(
     Ok(())
 }
 
+/// Canonical form of a subreddit name, as the subreddit filters store it:
+/// trim, lowercase, then strip one leading `r/` (any case) and trim again.
+/// A `u/` prefix is kept; use [`normalize_author`] for author names, or
+/// [`normalize_all`] to canonicalize a whole list.
+///
+/// ```
+/// assert_eq!(retl::normalize_str("R/Rust"), "rust");
+/// assert_eq!(retl::normalize_str("  r/ AskScience "), "askscience");
+/// ```
 pub fn normalize_str(s: &str) -> String {
     let s = s.trim().to_lowercase();
     if let Some(rest) = s.strip_prefix("r/") {
@@ -180,3 +189,49 @@ pub fn normalize_str(s: &str) -> String {
         s
     }
 }
+
+/// Trim an author name and strip one leading `u/`, `/u/` or `@` (the `u` in
+/// any case), keeping the name's case. Reddit usernames cannot contain `/` or
+/// `@`, so the strip never changes a real name.
+pub fn strip_author_prefix(s: &str) -> &str {
+    let s = s.trim();
+    let rest = ["/u/", "u/", "@"].iter().find_map(|prefix| {
+        let head = s.get(..prefix.len())?;
+        head.eq_ignore_ascii_case(prefix)
+            .then(|| &s[prefix.len()..])
+    });
+    // Re-trim for the same reason as `normalize_str`: "u/  alice" must not
+    // keep its padding.
+    rest.unwrap_or(s).trim()
+}
+
+/// Author counterpart of [`normalize_str`]: strip a `u/`, `/u/` or `@` prefix, then
+/// lowercase, so `u/Alice`, `/u/alice` and `@ALICE` all become `alice`.
+/// Subreddit names keep using `normalize_str`.
+pub fn normalize_author(s: &str) -> String {
+    strip_author_prefix(s).to_lowercase()
+}
+
+/// Canonicalize a list of subreddit or author names the way the query
+/// builders do before matching: each entry goes through [`normalize_str`]
+/// (trim, lowercase, strip `r/`) and then [`normalize_author`] (strip `u/`,
+/// `/u/` or `@`), and the result is sorted and deduplicated. Entries that
+/// are blank after stripping stay as `""`; the query builders reject those.
+///
+/// ```
+/// assert_eq!(retl::normalize_all(["u/Bob", "bob"]), ["bob"]);
+/// assert_eq!(retl::normalize_all(["r/Rust", "rust", "AskScience"]), ["askscience", "rust"]);
+/// ```
+pub fn normalize_all<I, S>(iter: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut names: Vec<String> = iter
+        .into_iter()
+        .map(|s| normalize_author(&normalize_str(s.as_ref())))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
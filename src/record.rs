@@ -0,0 +1,129 @@
+//! Typed view over one raw JSONL record with lazily-parsed fields.
+//!
+//! [`Record`] sits between the two representations the rest of the crate
+//! uses: common fields (`author`, `subreddit`, `score`, `created_utc`, `id`)
+//! come from the cheap [`MinimalRecord`] fast path, and any other field is
+//! served from a full `serde_json::Value` parse that runs only on the first
+//! arbitrary-field access.
+
+use serde_json::Value;
+use std::cell::OnceCell;
+
+use crate::date::YearMonth;
+use crate::filters::ym_from_epoch;
+use crate::paths::FileKind;
+use crate::zstd_jsonl::{parse_minimal, MinimalRecord};
+
+/// One matched corpus record, handed to [`ScanPlan::for_each_record`](crate::ScanPlan::for_each_record).
+///
+/// Borrows the raw line; nothing is parsed beyond what the accessors you call
+/// require. Field accessors return `None` for missing fields or unexpected
+/// JSON types, mirroring [`MinimalRecord`]'s lossy decoding.
+pub struct Record<'a> {
+    line: &'a str,
+    kind: FileKind,
+    borrowed_minimal: Option<&'a MinimalRecord>,
+    minimal: OnceCell<Option<MinimalRecord>>,
+    full: OnceCell<Option<Value>>,
+}
+
+impl<'a> Record<'a> {
+    /// Wrap a raw JSONL line from a file of the given `kind`. No parsing
+    /// happens until an accessor is called.
+    pub fn new(line: &'a str, kind: FileKind) -> Self {
+        Self {
+            line,
+            kind,
+            borrowed_minimal: None,
+            minimal: OnceCell::new(),
+            full: OnceCell::new(),
+        }
+    }
+
+    /// Reuse a `MinimalRecord` the scan loop already parsed for filtering.
+    pub(crate) fn with_minimal(line: &'a str, kind: FileKind, minimal: &'a MinimalRecord) -> Self {
+        Self {
+            borrowed_minimal: Some(minimal),
+            ..Self::new(line, kind)
+        }
+    }
+
+    /// The raw JSONL line, byte-for-byte as read from the corpus.
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// Whether the record came from an RC (comment) or RS (submission) file.
+    pub fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    /// Fast-path fields; `None` only when the line is not a JSON object.
+    pub fn minimal(&self) -> Option<&MinimalRecord> {
+        if let Some(min) = self.borrowed_minimal {
+            return Some(min);
+        }
+        self.minimal
+            .get_or_init(|| parse_minimal(self.line).ok())
+            .as_ref()
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.minimal()?.author.as_deref()
+    }
+
+    pub fn subreddit(&self) -> Option<&str> {
+        self.minimal()?.subreddit.as_deref()
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.minimal()?.id.as_deref()
+    }
+
+    pub fn score(&self) -> Option<i64> {
+        self.minimal()?.score
+    }
+
+    pub fn created_utc(&self) -> Option<i64> {
+        self.minimal()?.created_utc
+    }
+
+    /// Calendar month of `created_utc` (UTC).
+    pub fn created_ym(&self) -> Option<YearMonth> {
+        self.created_utc().map(ym_from_epoch)
+    }
+
+    /// Look up an arbitrary field. `field` is either a top-level key
+    /// (`"distinguished"`) or an RFC 6901 JSON Pointer (`"/media/oembed/type"`).
+    /// The first call parses the full line; later calls reuse that parse.
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        let full = self.value()?;
+        if field.starts_with('/') {
+            full.pointer(field)
+        } else {
+            full.get(field)
+        }
+    }
+
+    /// The fully-parsed record, parsing on first use. `None` when the line is
+    /// not valid JSON.
+    pub fn value(&self) -> Option<&Value> {
+        self.full
+            .get_or_init(|| serde_json::from_str(self.line).ok())
+            .as_ref()
+    }
+
+    /// `true` once an accessor has triggered the full `Value` parse.
+    pub fn is_fully_parsed(&self) -> bool {
+        self.full.get().is_some()
+    }
+}
+
+impl std::fmt::Debug for Record<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Record")
+            .field("kind", &self.kind)
+            .field("line", &self.line)
+            .finish()
+    }
+}
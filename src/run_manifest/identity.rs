@@ -15,6 +15,7 @@ pub fn file_identity(path: &Path) -> FileIdentity {
 pub(crate) fn scan_query_value(query: &QuerySpec, limit: Option<u64>) -> Value {
     json!({
         "subreddits": query.subreddits.as_ref(),
+        "subreddits_out": query.subreddits_out.as_ref(),
         "authors_in": query.authors_in.as_ref(),
         "authors_out": query.authors_out.as_ref(),
         "exclude_common_bots": query.exclude_common_bots,
@@ -23,10 +24,22 @@ pub(crate) fn scan_query_value(query: &QuerySpec, limit: Option<u64>) -> Value {
         "min_score": query.min_score,
         "max_score": query.max_score,
         "keywords_any": query.keywords_any.as_ref(),
+        "exclude_bot_bodies": query.exclude_bot_bodies,
+        "body_regex_exclude": query.body_regex_exclude.iter().map(|re| re.as_str()).collect::<Vec<_>>(),
         "domains_in": query.domains_in.as_ref(),
         "contains_url": query.contains_url,
         "json_predicates": query.json_predicates_fingerprint(),
         "filter_pseudo_users": query.filter_pseudo_users,
+        "top_level_comments_only": query.top_level_comments_only,
+        "subreddit_sample": query.subreddit_sample.map(|s| json!({
+            "fraction": s.fraction,
+            "seed": s.seed,
+        })),
+        "filter": query.filter.is_some(),
+        "transform": query.transform.is_some(),
+        "normalize_edited": query.normalize_edited.as_str(),
+        "canonicalize_subreddit_case": query.canonicalize_subreddit_case,
+        "source_ratio": query.source_ratio.map(|r| [r.comments, r.submissions]),
         "limit": limit,
     })
 }
@@ -34,20 +47,39 @@ pub(crate) fn scan_query_value(query: &QuerySpec, limit: Option<u64>) -> Value {
 pub(crate) fn etl_options_value(etl: &ETLOptions, limit: Option<u64>, extra: Value) -> Value {
     json!({
         "sources": sources_label(etl.sources),
+        "extra_base_dirs": etl
+            .extra_base_dirs
+            .iter()
+            .map(|p| path_to_stable_string(p))
+            .collect::<Vec<_>>(),
+        "files": etl
+            .files
+            .as_ref()
+            .map(|files| files.iter().map(|p| path_to_stable_string(p)).collect::<Vec<_>>()),
         "start": etl.opts_start_string(),
         "end": etl.opts_end_string(),
         "legacy_subreddit": etl.subreddit.as_ref(),
         "whitelist_fields": etl.whitelist_fields.as_ref(),
         "strict_whitelist": etl.strict_whitelist,
+        "whitelist_source_order": etl.whitelist_source_order,
         "strict_key": etl.strict_key,
         "parallelism": etl.parallelism,
         "file_concurrency": etl.file_concurrency,
-        "shard_count": etl.shard_count,
+        "ordered_output": etl.ordered_output,
+        "interleaved_sources": etl.interleaved_sources,
+        "shard_count": etl.resolved_shard_count(),
         "work_dir": etl.work_dir.as_ref().map(|p| path_to_stable_string(p)),
         "read_buffer_bytes": etl.read_buffer_bytes,
         "write_buffer_bytes": etl.write_buffer_bytes,
+        "max_line_bytes": etl.max_line_bytes,
         "human_readable_timestamps": etl.human_readable_timestamps,
+        "canonicalize_json": etl.canonicalize_json,
+        "add_provenance": etl.add_provenance,
+        "strict_json": etl.strict_json,
+        "max_output_bytes": etl.max_output_bytes,
+        "keep_empty_outputs": etl.keep_empty_outputs,
         "zst_level": etl.zst_level,
+        "zstd_dictionary_id": etl.zstd_dictionary.as_ref().map(|d| d.id()),
         "inflight_bytes": etl.inflight_bytes,
         "inflight_groups": etl.inflight_groups,
         "resume": etl.resume,
@@ -81,6 +81,48 @@ pub struct OutputSnapshot {
     pub manifest_path: String,
 }
 
+/// One published file of a directory output, as listed under
+/// `output_files`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OutputFileEntry {
+    /// Path relative to the output directory, `/`-separated.
+    pub path: String,
+    pub records: u64,
+    pub size_bytes: u64,
+    /// Smallest and largest `created_utc` among the file's records. Absent
+    /// when no record carried one, or when the file was published by an
+    /// earlier run that this run resumed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_created_utc: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_created_utc: Option<i64>,
+}
+
+impl OutputFileEntry {
+    /// Describe `path` (inside `root`) from its on-disk size and the record
+    /// stats the writer collected.
+    pub fn new(
+        root: &Path,
+        path: &Path,
+        records: u64,
+        created_utc_range: Option<(i64, i64)>,
+    ) -> Self {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let rel = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Self {
+            path: rel,
+            records,
+            size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            min_created_utc: created_utc_range.map(|(min, _)| min),
+            max_created_utc: created_utc_range.map(|(_, max)| max),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct ResumeSnapshot {
     pub enabled: bool,
@@ -118,6 +160,10 @@ pub struct RunManifest {
     pub corpus: CorpusSnapshot,
     pub inputs: Vec<FileIdentity>,
     pub output: OutputSnapshot,
+    /// Per-file listing for directory outputs that publish one file per
+    /// month (partitioned export, monthly spool); omitted otherwise.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub output_files: Vec<OutputFileEntry>,
     pub counts: BTreeMap<String, u64>,
     pub partial_read: PartialReadReport,
     pub resume: ResumeSnapshot,
@@ -142,6 +188,7 @@ pub struct RunManifestInput {
     pub corpus: CorpusSnapshot,
     pub inputs: Vec<FileIdentity>,
     pub output_format: String,
+    pub output_files: Vec<OutputFileEntry>,
     pub counts: BTreeMap<String, u64>,
     pub partial_read: PartialReadReport,
     pub resume_enabled: bool,
@@ -163,6 +210,7 @@ impl RunManifestInput {
             corpus: CorpusSnapshot::empty(),
             inputs: Vec::new(),
             output_format: "unknown".to_string(),
+            output_files: Vec::new(),
             counts: BTreeMap::new(),
             partial_read: PartialReadReport::default(),
             resume_enabled: false,
@@ -70,6 +70,11 @@ pub fn write_run_manifest(
         corpus: input.corpus,
         inputs: input.inputs,
         output,
+        output_files: {
+            let mut files = input.output_files;
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            files
+        },
         counts: input.counts,
         partial_read: input.partial_read,
         resume,
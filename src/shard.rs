@@ -2,17 +2,22 @@ pub use crate::username_stream::UsernameStream;
 
 use crate::config::clamp_shard_count;
 use crate::shard_common;
+use crate::username_stream::SortedMerge;
 use crate::util::unique_scratch_dir;
 use ahash::RandomState;
 use anyhow::{Context, Result};
 use rayon::prelude::*;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 
-/// Disk-backed sharded dedup writer: concurrent-safe.
+fn shard_file_name(idx: usize) -> String {
+    format!("shard_{idx:04}.tmp")
+}
+
+/// Sharded dedup writer: concurrent-safe. Shards are scratch files, or
+/// in-memory buffers that spill to those files with
+/// [`create_in_memory`](Self::create_in_memory).
 pub struct ShardedWriter {
     run_root: PathBuf,
-    base_dir: PathBuf,
     shards: shard_common::LineShardWriters,
     count: usize,
     state: RandomState, // seeded for deterministic sharding
@@ -29,7 +34,7 @@ impl ShardedWriter {
         let shards = shard_common::create_line_shard_writers(
             &shards_dir,
             count,
-            |i| format!("shard_{i:04}.tmp"),
+            shard_file_name,
             "shard scratch",
         )?;
 
@@ -37,13 +42,53 @@ impl ShardedWriter {
 
         Ok(Self {
             run_root,
-            base_dir: shards_dir,
             shards,
             count,
             state,
         })
     }
 
+    /// Like [`create`](Self::create), but each shard is a `Vec<u8>` in
+    /// memory. Once the shards together hold more than `spill_bytes` — or
+    /// memory runs low — the largest are appended to their scratch files
+    /// and released; dedup then reads each shard's file and buffer together,
+    /// so results match the disk-backed writer.
+    pub fn create_in_memory(
+        work_dir: &Path,
+        prefix: &str,
+        count: usize,
+        spill_bytes: u64,
+    ) -> Result<Self> {
+        let count = clamp_shard_count(count, "ShardedWriter::create_in_memory");
+        let run_root = unique_scratch_dir(work_dir, prefix, "shards");
+        let shards_dir = run_root.join("shards");
+        crate::util::create_dir_all_with_default_backoff(&shards_dir)
+            .with_context(|| format!("create shard scratch dir {}", shards_dir.display()))?;
+
+        let shards = shard_common::create_memory_line_shards(
+            &shards_dir,
+            count,
+            shard_file_name,
+            spill_bytes,
+        );
+
+        Ok(Self {
+            run_root,
+            shards,
+            count,
+            state: shard_common::seeded_state("usernames"),
+        })
+    }
+
+    /// Flush every shard once `bytes` have been appended across them since
+    /// the last flush (`0` flushes only when buffers fill, under memory
+    /// pressure, and at the end). Defaults to
+    /// [`DEFAULT_SHARD_FLUSH_BYTES`](crate::DEFAULT_SHARD_FLUSH_BYTES).
+    pub fn with_flush_every_bytes(mut self, bytes: u64) -> Self {
+        self.shards.set_flush_every_bytes(bytes);
+        self
+    }
+
     pub fn scratch_root(&self) -> &Path {
         &self.run_root
     }
@@ -55,10 +100,7 @@ impl ShardedWriter {
 
     pub fn write(&self, key: &str) -> Result<()> {
         let idx = self.shard_index(key);
-        let mut guard = self.shards[idx].lock();
-        guard.write_all(key.as_bytes())?;
-        guard.write_all(b"\n")?;
-        Ok(())
+        self.shards.write_parts(idx, &[key.as_bytes(), b"\n"])
     }
 
     pub fn flush_all(&self) -> Result<()> {
@@ -75,45 +117,104 @@ impl ShardedWriter {
     /// per-run scratch root that owns them. Callers that hand the files to a
     /// lazy consumer should keep this root alive until that consumer is done.
     pub fn dedup_with_scratch(self, prefix: &str) -> Result<(Vec<PathBuf>, PathBuf)> {
-        self.flush_all()?;
         let ShardedWriter {
-            run_root,
-            base_dir,
-            shards,
-            count,
-            state: _,
+            run_root, shards, ..
         } = self;
-        drop(shards); // ensure writers are closed
+        let contents = shards.finish()?; // ensure writers are closed
 
         let dedup_dir = run_root.join(format!("{prefix}_dedup"));
         crate::util::create_dir_all_with_default_backoff(&dedup_dir)
             .with_context(|| format!("create dedup scratch dir {}", dedup_dir.display()))?;
 
-        let shard_paths: Vec<PathBuf> = (0..count)
-            .map(|i| base_dir.join(format!("shard_{:04}.tmp", i)))
-            .collect();
-
-        let deduped: Vec<PathBuf> = shard_paths
+        let deduped: Vec<PathBuf> = contents
             .par_iter()
-            .map(|shard_path| -> Result<PathBuf> {
-                let out_path = dedup_dir.join(
-                    shard_path
-                        .file_name()
-                        .unwrap()
-                        .to_string_lossy()
-                        .replace(".tmp", ".txt"),
-                );
+            .enumerate()
+            .map(|(i, shard)| -> Result<PathBuf> {
+                let out_path = dedup_dir.join(shard_file_name(i).replace(".tmp", ".txt"));
                 // `sort = true`: `ShardedWriter` shards are consumed by
                 // sorted-merge downstream, so the deduped output is sorted.
-                // Shares `shard_common::dedup_line_shard` with the parent-id
+                // Shares the `shard_common` dedup loop with the parent-id
                 // dedupe stage (which passes `sort = false`).
-                shard_common::dedup_line_shard(shard_path, &out_path, true)?;
+                shard_common::dedup_shard_contents(shard, &out_path, true)?;
                 Ok(out_path)
             })
             .collect::<Result<Vec<_>>>()?;
 
         Ok((deduped, run_root))
     }
+
+    /// Deduplicate every shard in parallel and sort the union into one
+    /// sorted, deduped `Vec`.
+    ///
+    /// A throughput alternative to [`dedup`](Self::dedup) plus
+    /// [`UsernameStream::collect_sorted`] for callers that want the whole set
+    /// at once: shards are read straight into memory instead of being written
+    /// back out and then read one at a time. Holds every distinct name in
+    /// memory. The scratch directory is removed before returning.
+    pub fn into_sorted_vec_parallel(self) -> Result<Vec<String>> {
+        let ShardedWriter {
+            run_root, shards, ..
+        } = self;
+        let sorted = shards
+            .finish()
+            .and_then(|contents| {
+                contents
+                    .par_iter()
+                    .map(shard_common::unique_shard_line_vec)
+                    .collect::<Result<Vec<_>>>()
+            })
+            .map(|shards| {
+                // A name lives in exactly one shard, so the deduped shards are
+                // disjoint and one sort of their concatenation orders them.
+                let mut names = shards.concat();
+                names.par_sort_unstable();
+                names
+            });
+        if let Err(e) = crate::util::remove_dir_all_with_short_backoff(&run_root) {
+            tracing::warn!(path=%run_root.display(), error=%e, "ShardedWriter: failed to remove scratch dir");
+        }
+        sorted
+    }
+
+    /// Deduplicate and sort every shard in parallel, then k-way merge the
+    /// sorted shard files into one sorted, deduped, newline-terminated file
+    /// at `out_path`, published atomically. Returns the number of names
+    /// written.
+    ///
+    /// The bounded-memory counterpart of
+    /// [`into_sorted_vec_parallel`](Self::into_sorted_vec_parallel): only
+    /// the shards being sorted and one pending name per shard are in memory,
+    /// never the whole set. The scratch directory is removed before
+    /// returning.
+    pub fn merge_sorted_to(self, out_path: &Path) -> Result<u64> {
+        let merged = self
+            .dedup_with_scratch("merge")
+            .and_then(|(sorted, run_root)| {
+                let written = merge_sorted_files_to(&sorted, out_path);
+                if let Err(e) = crate::util::remove_dir_all_with_short_backoff(&run_root) {
+                    tracing::warn!(path=%run_root.display(), error=%e, "ShardedWriter: failed to remove scratch dir");
+                }
+                written
+            });
+        merged.with_context(|| format!("merge sorted shards into {}", out_path.display()))
+    }
+}
+
+fn merge_sorted_files_to(sorted: &[PathBuf], out_path: &Path) -> Result<u64> {
+    let mut merge = SortedMerge::open(sorted)?;
+    crate::atomic_write::write_at_path_atomic(
+        out_path,
+        crate::config::DEFAULT_WRITE_BUFFER_BYTES,
+        |w| {
+            let mut written = 0u64;
+            while let Some(name) = merge.next_name()? {
+                w.write_all(name.as_bytes())?;
+                w.write_all(b"\n")?;
+                written += 1;
+            }
+            Ok(written)
+        },
+    )
 }
 
 #[cfg(test)]
@@ -151,4 +252,146 @@ mod tests {
         assert_eq!(writer.count, crate::config::MAX_SHARDS);
         assert_eq!(writer.shards.len(), crate::config::MAX_SHARDS);
     }
+
+    #[test]
+    fn tiny_flush_interval_flushes_while_writing_and_dedups_correctly() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let writer = ShardedWriter::create(tmp.path(), "flush", 4)
+            .expect("create sharded writer")
+            .with_flush_every_bytes(1);
+        for i in 0..5_000 {
+            writer.write(&format!("user_{:04}", i % 1_000)).expect("write key");
+        }
+        // Every write crossed the 1-byte mark, so the shards are on disk
+        // before the final flush.
+        let on_disk: u64 = (0..4)
+            .map(|i| {
+                std::fs::metadata(
+                    writer
+                        .scratch_root()
+                        .join("shards")
+                        .join(format!("shard_{i:04}.tmp")),
+                )
+                .expect("shard metadata")
+                .len()
+            })
+            .sum();
+        assert_eq!(on_disk, 5_000 * "user_0000\n".len() as u64);
+
+        let (deduped, _scratch_root) = writer.dedup_with_scratch("out").expect("dedup shards");
+        let mut keys = Vec::new();
+        for path in deduped {
+            let mut contents = String::new();
+            open_with_backoff(&path, 2, 0)
+                .expect("open deduped shard")
+                .read_to_string(&mut contents)
+                .expect("read deduped shard");
+            keys.extend(contents.lines().map(str::to_string));
+        }
+        keys.sort();
+        let expected: Vec<String> = (0..1_000).map(|i| format!("user_{i:04}")).collect();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn in_memory_shards_spill_past_a_tiny_limit_and_dedup_across_both_parts() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let shard_file = |writer: &ShardedWriter, i: usize| {
+            writer
+                .scratch_root()
+                .join("shards")
+                .join(format!("shard_{i:04}.tmp"))
+        };
+
+        let roomy = ShardedWriter::create_in_memory(tmp.path(), "roomy", 4, u64::MAX)
+            .expect("create in-memory writer");
+        roomy.write("alice").expect("write key");
+        assert!((0..4).all(|i| !shard_file(&roomy, i).exists()));
+
+        let writer = ShardedWriter::create_in_memory(tmp.path(), "spill", 4, 64)
+            .expect("create in-memory writer");
+        for i in 0..5_000 {
+            writer
+                .write(&format!("user_{:04}", i % 1_000))
+                .expect("write key");
+        }
+        let spilled: u64 = (0..4)
+            .filter_map(|i| std::fs::metadata(shard_file(&writer, i)).ok())
+            .map(|m| m.len())
+            .sum();
+        assert!(spilled > 0, "a 64-byte limit should have spilled shards");
+        assert!(spilled < 5_000 * "user_0000\n".len() as u64);
+
+        let (deduped, _scratch_root) = writer.dedup_with_scratch("out").expect("dedup shards");
+        let mut keys = Vec::new();
+        for path in deduped {
+            let mut contents = String::new();
+            open_with_backoff(&path, 2, 0)
+                .expect("open deduped shard")
+                .read_to_string(&mut contents)
+                .expect("read deduped shard");
+            keys.extend(contents.lines().map(str::to_string));
+        }
+        keys.sort();
+        let expected: Vec<String> = (0..1_000).map(|i| format!("user_{i:04}")).collect();
+        assert_eq!(keys, expected);
+        assert_eq!(
+            roomy.into_sorted_vec_parallel().expect("collect"),
+            ["alice"]
+        );
+    }
+
+    #[test]
+    fn parallel_sorted_vec_matches_streamed_and_sorted_names() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let fill = |prefix: &str| {
+            let writer = ShardedWriter::create(tmp.path(), prefix, 8).expect("create writer");
+            for i in 0..3_000 {
+                writer
+                    .write(&format!("user_{}", (i * 7) % 1_234))
+                    .expect("write key");
+            }
+            writer
+        };
+
+        let (deduped, scratch_root) = fill("stream").dedup_with_scratch("out").expect("dedup");
+        let streamed = UsernameStream::from_deduped_files_with_cleanup(deduped, vec![scratch_root])
+            .expect("stream")
+            .collect_sorted();
+
+        let writer = fill("parallel");
+        let scratch_root = writer.scratch_root().to_path_buf();
+        let parallel = writer.into_sorted_vec_parallel().expect("parallel collect");
+
+        assert_eq!(parallel.len(), 1_234);
+        assert_eq!(parallel, streamed);
+        assert!(!scratch_root.exists(), "scratch dir should be removed");
+    }
+
+    #[test]
+    fn merge_sorted_to_writes_one_strictly_sorted_file_from_many_shards() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let writer = ShardedWriter::create(tmp.path(), "merge", 8).expect("create writer");
+        for i in (0..3_000).rev() {
+            writer
+                .write(&format!("user_{}", (i * 7) % 1_234))
+                .expect("write key");
+        }
+        let scratch_root = writer.scratch_root().to_path_buf();
+        let out = tmp.path().join("out").join("names.txt");
+
+        let written = writer.merge_sorted_to(&out).expect("merge shards");
+
+        let mut contents = String::new();
+        open_with_backoff(&out, 2, 0)
+            .expect("open merged file")
+            .read_to_string(&mut contents)
+            .expect("read merged file");
+        let names: Vec<&str> = contents.lines().collect();
+        assert_eq!(written, 1_234);
+        assert_eq!(names.len(), 1_234);
+        assert!(names.windows(2).all(|w| w[0] < w[1]), "not strictly sorted");
+        assert!(contents.ends_with('\n'));
+        assert!(!scratch_root.exists(), "scratch dir should be removed");
+    }
 }
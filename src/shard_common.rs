@@ -15,8 +15,9 @@ use anyhow::{Context, Result};
 use parking_lot::Mutex;
 use std::fs::File;
 use std::hash::{BuildHasher, Hash, Hasher};
-use std::io::{BufReader, BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Construct a deterministic [`RandomState`] for a given module label.
 ///
@@ -24,6 +25,9 @@ use std::path::{Path, PathBuf};
 /// - `"usernames"` — `shard::ShardedWriter`
 /// - `"kv"` — `kv_shard::ShardedKVWriter`
 /// - `"parent_ids"` — `parents::IdShardWriter` / `parents::IdShards`
+/// - `"group_by"` — `ScanPlan::for_each_group` key shards
+/// - `"record_dedupe"` — `ScanPlan::dedupe_records_by` key/position shards
+/// - `"distinct_authors"` — `ScanPlan::distinct_authors_by_month` pair shards
 pub(crate) fn seeded_state(label: &str) -> RandomState {
     let (k0, k1, k2, k3) = match label {
         "usernames" => (
@@ -44,6 +48,24 @@ pub(crate) fn seeded_state(label: &str) -> RandomState {
             0x9900_aa00_bb00_cc00,
             0xdd00_ee00_ff00_0123,
         ),
+        "group_by" => (
+            0x6a09_e667_f3bc_c908,
+            0xbb67_ae85_84ca_a73b,
+            0x3c6e_f372_fe94_f82b,
+            0xa54f_f53a_5f1d_36f1,
+        ),
+        "record_dedupe" => (
+            0x510e_527f_ade6_82d1,
+            0x9b05_688c_2b3e_6c1f,
+            0x1f83_d9ab_fb41_bd6b,
+            0x5be0_cd19_137e_2179,
+        ),
+        "distinct_authors" => (
+            0xcbbb_9d5d_c105_9ed8,
+            0x629a_292a_367c_d507,
+            0x9159_015a_3070_dd17,
+            0x152f_ecd8_f70e_5939,
+        ),
         other => panic!("seeded_state: unknown label {other:?}"),
     };
     RandomState::with_seeds(k0, k1, k2, k3)
@@ -57,7 +79,221 @@ pub(crate) fn shard_index(state: &RandomState, key: &str, count: usize) -> usize
     (hasher.finish() as usize) % count
 }
 
-pub(crate) type LineShardWriters = Vec<Mutex<BufWriter<File>>>;
+/// How often (in appended bytes) the writers sample memory pressure.
+const LOW_MEMORY_CHECK_BYTES: u64 = 1024 * 1024;
+
+/// Where one shard's lines go: a buffered scratch file, or an in-memory
+/// buffer that is written to the end of a lazily created scratch file each
+/// time it is spilled.
+enum ShardSink {
+    Disk(BufWriter<File>),
+    Memory { buf: Vec<u8>, spill: Option<File> },
+}
+
+impl ShardSink {
+    fn write_all(&mut self, part: &[u8]) -> std::io::Result<()> {
+        match self {
+            Self::Disk(w) => w.write_all(part),
+            Self::Memory { buf, .. } => {
+                buf.extend_from_slice(part);
+                Ok(())
+            }
+        }
+    }
+
+    fn held_bytes(&self) -> u64 {
+        match self {
+            Self::Disk(_) => 0,
+            Self::Memory { buf, .. } => buf.len() as u64,
+        }
+    }
+
+    /// Move an in-memory buffer to the end of its scratch file and release
+    /// it. Returns the bytes moved.
+    fn spill(&mut self, path: &Path) -> Result<u64> {
+        let Self::Memory { buf, spill } = self else {
+            return Ok(0);
+        };
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let w = match spill {
+            Some(w) => w,
+            None => spill.insert(
+                crate::util::create_with_default_backoff(path)
+                    .with_context(|| format!("create shard spill file {}", path.display()))?,
+            ),
+        };
+        w.write_all(buf)?;
+        let moved = buf.len() as u64;
+        *buf = Vec::new();
+        Ok(moved)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Disk(w) => w.flush(),
+            // Spills go straight to the file in one write.
+            Self::Memory { .. } => Ok(()),
+        }
+    }
+}
+
+/// Spill policy for in-memory shards.
+struct MemoryShards {
+    /// Bytes currently held in shard buffers.
+    held: AtomicU64,
+    /// Spill once `held` exceeds this.
+    spill_bytes: u64,
+}
+
+/// One buffered writer per shard plus a shared appended-bytes counter.
+///
+/// Every `flush_every_bytes` appended bytes (`0` disables this) — and at any
+/// 1 MiB mark where [`is_low_memory`](crate::mem::is_low_memory) reports
+/// pressure — all shard buffers are flushed. This bounds how much a crash
+/// loses and returns buffer memory under pressure instead of only at the end.
+///
+/// Writers from [`create_memory_line_shards`] keep each shard in a `Vec<u8>`
+/// instead. When the buffers together exceed the spill limit, or at a 1 MiB
+/// mark under memory pressure, the largest buffers are appended to their
+/// scratch files until at most half the limit (or half the held bytes under
+/// pressure) stays in memory. [`finish`](Self::finish) hands back both parts.
+pub(crate) struct LineShardWriters {
+    sinks: Vec<Mutex<ShardSink>>,
+    paths: Vec<PathBuf>,
+    unflushed: AtomicU64,
+    flush_every_bytes: u64,
+    memory: Option<MemoryShards>,
+}
+
+impl LineShardWriters {
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    pub(crate) fn set_flush_every_bytes(&mut self, bytes: u64) {
+        self.flush_every_bytes = bytes;
+    }
+
+    /// Append `parts` back to back to shard `idx` as one locked write.
+    pub(crate) fn write_parts(&self, idx: usize, parts: &[&[u8]]) -> Result<()> {
+        let mut written = 0u64;
+        {
+            let mut w = self.sinks[idx].lock();
+            for part in parts {
+                w.write_all(part)?;
+                written += part.len() as u64;
+            }
+            // Counted under the shard lock so a concurrent spill of this
+            // shard never subtracts bytes that were not yet added.
+            if let Some(memory) = &self.memory {
+                memory.held.fetch_add(written, Ordering::Relaxed);
+            }
+        }
+        let before = self.unflushed.fetch_add(written, Ordering::Relaxed);
+        let after = before + written;
+        let pressure = before / LOW_MEMORY_CHECK_BYTES != after / LOW_MEMORY_CHECK_BYTES
+            && crate::mem::is_low_memory(crate::mem::AdaptiveMemCfg::default().soft_low_frac);
+        if let Some(memory) = &self.memory {
+            if pressure || memory.held.load(Ordering::Relaxed) > memory.spill_bytes {
+                self.spill_largest(memory, pressure)?;
+            }
+            return Ok(());
+        }
+        let periodic = self.flush_every_bytes > 0 && after >= self.flush_every_bytes;
+        if periodic || pressure {
+            // Two writers crossing the mark together both flush; the second
+            // pass finds empty buffers.
+            self.unflushed.store(0, Ordering::Relaxed);
+            self.flush_all()?;
+        }
+        Ok(())
+    }
+
+    /// Spill the largest in-memory shards, biggest first, until the held
+    /// bytes drop to the target. Racing spillers skip shards another one
+    /// already emptied.
+    fn spill_largest(&self, memory: &MemoryShards, pressure: bool) -> Result<()> {
+        let held = memory.held.load(Ordering::Relaxed);
+        let target = if pressure {
+            held / 2
+        } else {
+            memory.spill_bytes / 2
+        };
+        let mut sizes: Vec<(u64, usize)> = self
+            .sinks
+            .iter()
+            .enumerate()
+            .map(|(idx, sink)| (sink.lock().held_bytes(), idx))
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        let mut spilled = 0u64;
+        for (len, idx) in sizes {
+            if len == 0 || memory.held.load(Ordering::Relaxed) <= target {
+                break;
+            }
+            let mut sink = self.sinks[idx].lock();
+            let moved = sink.spill(&self.paths[idx])?;
+            memory.held.fetch_sub(moved, Ordering::Relaxed);
+            spilled += moved;
+        }
+        tracing::debug!(
+            spilled_bytes = spilled,
+            held_bytes = held,
+            pressure,
+            "spilled in-memory shards to disk"
+        );
+        Ok(())
+    }
+
+    pub(crate) fn flush_all(&self) -> Result<()> {
+        for sink in &self.sinks {
+            sink.lock().flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flush and close every shard, returning what each one holds.
+    pub(crate) fn finish(self) -> Result<Vec<ShardContents>> {
+        self.flush_all()?;
+        Ok(self
+            .sinks
+            .into_iter()
+            .zip(self.paths)
+            .map(|(sink, path)| match sink.into_inner() {
+                ShardSink::Disk(_) => ShardContents::on_disk(path),
+                ShardSink::Memory { buf, spill } => ShardContents {
+                    file: spill.map(|_| path),
+                    memory: buf,
+                },
+            })
+            .collect())
+    }
+}
+
+/// One shard's lines once writing is done: the scratch file (if it has one)
+/// followed by the bytes still held in memory.
+pub(crate) struct ShardContents {
+    file: Option<PathBuf>,
+    memory: Vec<u8>,
+}
+
+impl ShardContents {
+    pub(crate) fn on_disk(path: PathBuf) -> Self {
+        Self {
+            file: Some(path),
+            memory: Vec::new(),
+        }
+    }
+
+    fn label(&self) -> &Path {
+        self.file
+            .as_deref()
+            .unwrap_or_else(|| Path::new("<in-memory shard>"))
+    }
+}
 
 /// Create deterministic line-oriented shard scratch files under `dir`.
 ///
@@ -70,21 +306,100 @@ pub(crate) fn create_line_shard_writers(
     mut file_name: impl FnMut(usize) -> String,
     context_label: &str,
 ) -> Result<LineShardWriters> {
-    let mut writers = Vec::with_capacity(count);
+    let mut sinks = Vec::with_capacity(count);
+    let mut paths = Vec::with_capacity(count);
     for i in 0..count {
         let path: PathBuf = dir.join(file_name(i));
         let file = crate::util::create_with_default_backoff(&path)
             .with_context(|| format!("create {context_label} {}", path.display()))?;
-        writers.push(Mutex::new(BufWriter::new(file)));
+        sinks.push(Mutex::new(ShardSink::Disk(BufWriter::new(file))));
+        paths.push(path);
+    }
+    Ok(LineShardWriters {
+        sinks,
+        paths,
+        unflushed: AtomicU64::new(0),
+        flush_every_bytes: crate::config::DEFAULT_SHARD_FLUSH_BYTES,
+        memory: None,
+    })
+}
+
+/// In-memory counterpart of [`create_line_shard_writers`]: shards start as
+/// empty buffers, and a shard's file under `dir` is only created when it is
+/// first spilled, once the buffers together hold more than `spill_bytes`.
+pub(crate) fn create_memory_line_shards(
+    dir: &Path,
+    count: usize,
+    mut file_name: impl FnMut(usize) -> String,
+    spill_bytes: u64,
+) -> LineShardWriters {
+    LineShardWriters {
+        sinks: (0..count)
+            .map(|_| {
+                Mutex::new(ShardSink::Memory {
+                    buf: Vec::new(),
+                    spill: None,
+                })
+            })
+            .collect(),
+        paths: (0..count).map(|i| dir.join(file_name(i))).collect(),
+        unflushed: AtomicU64::new(0),
+        flush_every_bytes: crate::config::DEFAULT_SHARD_FLUSH_BYTES,
+        memory: Some(MemoryShards {
+            held: AtomicU64::new(0),
+            spill_bytes,
+        }),
     }
-    Ok(writers)
 }
 
 pub(crate) fn flush_line_shard_writers(writers: &LineShardWriters) -> Result<()> {
-    for writer in writers {
-        writer.lock().flush()?;
+    writers.flush_all()
+}
+
+/// Preallocation for a shard's dedup set: one slot per ~8 input bytes (a
+/// short ID plus newline), clamped so tiny shards don't reserve a large table
+/// and huge shards grow on demand rather than reserving up front.
+fn dedup_capacity_hint(input_bytes: u64) -> usize {
+    const BYTES_PER_LINE_ESTIMATE: u64 = 8;
+    const MIN_CAPACITY: u64 = 1024;
+    const MAX_CAPACITY: u64 = 1 << 20;
+    (input_bytes / BYTES_PER_LINE_ESTIMATE).clamp(MIN_CAPACITY, MAX_CAPACITY) as usize
+}
+
+/// Read a shard's distinct non-empty lines into a set, file part first.
+fn unique_shard_lines(contents: &ShardContents) -> Result<AHashSet<String>> {
+    let label = contents.label();
+    let (file_part, file_bytes): (Box<dyn BufRead>, u64) = match &contents.file {
+        Some(path) => {
+            let in_file = crate::util::open_with_default_backoff(path)
+                .with_context(|| format!("open shard for dedup: {}", path.display()))?;
+            let bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            (Box::new(BufReader::new(in_file)), bytes)
+        }
+        None => (Box::new(std::io::empty()), 0),
+    };
+    let mut reader = file_part.chain(&contents.memory[..]);
+
+    let input_bytes = file_bytes + contents.memory.len() as u64;
+    let mut seen: AHashSet<String> = AHashSet::with_capacity(dedup_capacity_hint(input_bytes));
+    let mut buf = String::with_capacity(16 * 1024);
+    loop {
+        let n = read_line_capped(&mut reader, &mut buf, DEFAULT_MAX_LINE_BYTES, label)
+            .with_context(|| format!("read shard for dedup: {}", label.display()))?;
+        if n == 0 {
+            break;
+        }
+        if !buf.is_empty() {
+            seen.insert(buf.clone());
+        }
     }
-    Ok(())
+    Ok(seen)
+}
+
+/// The distinct lines of a shard in unspecified order, kept in memory
+/// instead of written back out like [`dedup_shard_contents`] does.
+pub(crate) fn unique_shard_line_vec(contents: &ShardContents) -> Result<Vec<String>> {
+    Ok(unique_shard_lines(contents)?.into_iter().collect())
 }
 
 /// Read a line-oriented shard file, collapse duplicate lines into a hash set,
@@ -101,23 +416,16 @@ pub(crate) fn flush_line_shard_writers(writers: &LineShardWriters) -> Result<()>
 /// did not); both now route through this single helper so they cannot diverge
 /// again.
 pub(crate) fn dedup_line_shard(input: &Path, output: &Path, sort: bool) -> Result<usize> {
-    let in_file = crate::util::open_with_default_backoff(input)
-        .with_context(|| format!("open shard for dedup: {}", input.display()))?;
-    let mut reader = BufReader::new(in_file);
-
-    let mut seen: AHashSet<String> = AHashSet::with_capacity(64_000);
-    let mut buf = String::with_capacity(16 * 1024);
-    loop {
-        let n = read_line_capped(&mut reader, &mut buf, DEFAULT_MAX_LINE_BYTES, input)
-            .with_context(|| format!("read shard for dedup: {}", input.display()))?;
-        if n == 0 {
-            break;
-        }
-        if !buf.is_empty() {
-            seen.insert(buf.clone());
-        }
-    }
+    dedup_shard_contents(&ShardContents::on_disk(input.to_path_buf()), output, sort)
+}
 
+/// [`dedup_line_shard`] over a shard that may be partly or wholly in memory.
+pub(crate) fn dedup_shard_contents(
+    contents: &ShardContents,
+    output: &Path,
+    sort: bool,
+) -> Result<usize> {
+    let seen = unique_shard_lines(contents)?;
     let unique_count = seen.len();
     let out_file = crate::util::create_with_default_backoff(output)
         .with_context(|| format!("create dedup output: {}", output.display()))?;
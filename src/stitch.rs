@@ -15,12 +15,40 @@ use std::path::{Path, PathBuf};
 
 const STITCH_BUF_BYTES: usize = 16 * 1024;
 
-fn list_tmp_parts(dir: &Path) -> Result<Vec<PathBuf>> {
-    jsonl_part_paths(dir)
+fn list_tmp_parts(dir: &Path, month_major: bool) -> Result<Vec<PathBuf>> {
+    let mut paths = jsonl_part_paths(dir)?;
+    if month_major {
+        sort_parts_month_major(&mut paths);
+    }
+    Ok(paths)
+}
+
+/// Reorder name-sorted parts so each month's RC part is followed by its RS
+/// part (`ETLOptions::interleaved_sources` or `ordered_output`). Part names carry the source and
+/// month as `RC_2006-01` / `RS_2006-01`, optionally behind a `.part_` prefix.
+pub(crate) fn sort_parts_month_major(paths: &mut [PathBuf]) {
+    paths.sort_by_cached_key(|path| {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let key = name.strip_prefix(".part_").unwrap_or(name);
+        match key.split_once('_') {
+            Some((source, month)) => (month.to_string(), source.to_string()),
+            None => (key.to_string(), String::new()),
+        }
+    });
 }
 
-pub fn stitch_tmp_parts(tmp_dir: &Path, out_path: &Path, write_buf: usize) -> Result<()> {
-    let parts = list_tmp_parts(tmp_dir)?;
+/// Concatenate the JSONL parts in `tmp_dir` into `out_path`, by name or,
+/// with `month_major`, month by month (see [`sort_parts_month_major`]).
+pub fn stitch_tmp_parts(
+    tmp_dir: &Path,
+    out_path: &Path,
+    month_major: bool,
+    write_buf: usize,
+) -> Result<()> {
+    let parts = list_tmp_parts(tmp_dir, month_major)?;
     write_at_path_atomic(out_path, write_buf, |out| {
         for path in &parts {
             let mut r = BufReader::new(crate::util::open_with_default_backoff(path)?);
@@ -44,13 +72,14 @@ pub fn stitch_tmp_parts_to_json_array(
     tmp_dir: &Path,
     out_path: &Path,
     pretty: bool,
+    month_major: bool,
     write_buf: usize,
 ) -> Result<()> {
     if pretty {
-        return stitch_tmp_parts_to_json_array_pretty(tmp_dir, out_path, write_buf);
+        return stitch_tmp_parts_to_json_array_pretty(tmp_dir, out_path, month_major, write_buf);
     }
 
-    let parts = list_tmp_parts(tmp_dir)?;
+    let parts = list_tmp_parts(tmp_dir, month_major)?;
     write_at_path_atomic(out_path, write_buf, |out| {
         let mut first = true;
 
@@ -83,9 +112,10 @@ pub fn stitch_tmp_parts_to_json_array(
 fn stitch_tmp_parts_to_json_array_pretty(
     tmp_dir: &Path,
     out_path: &Path,
+    month_major: bool,
     write_buf: usize,
 ) -> Result<()> {
-    let parts = list_tmp_parts(tmp_dir)?;
+    let parts = list_tmp_parts(tmp_dir, month_major)?;
     write_at_path_atomic(out_path, write_buf, |out| {
         let mut first = true;
 
@@ -194,7 +224,7 @@ mod tests {
         }
 
         let out = dir.path().join("out.json");
-        let res = stitch_tmp_parts_to_json_array(&tmp_dir, &out, false, 64 * 1024);
+        let res = stitch_tmp_parts_to_json_array(&tmp_dir, &out, false, false, 64 * 1024);
         assert!(
             res.is_err(),
             "stitching a corrupt temp part must surface an Err, got Ok with file at {}",
@@ -225,7 +255,7 @@ mod tests {
         }
 
         let out = dir.path().join("out.json");
-        stitch_tmp_parts_to_json_array(&tmp_dir, &out, false, 64 * 1024).unwrap();
+        stitch_tmp_parts_to_json_array(&tmp_dir, &out, false, false, 64 * 1024).unwrap();
         let got = fs::read_to_string(&out).unwrap();
         assert_eq!(got, "[{\"id\":\"r1\"},{\"id\":\"r2\"}]");
         // Round-trip through serde to confirm it parses as an array of two objects.
@@ -260,7 +290,7 @@ mod tests {
 
         // JSONL stitch: records appear in sorted-by-filename order.
         let jsonl_out = dir.path().join("out.jsonl");
-        stitch_tmp_parts(&tmp_dir, &jsonl_out, 64 * 1024).unwrap();
+        stitch_tmp_parts(&tmp_dir, &jsonl_out, false, 64 * 1024).unwrap();
         let got = fs::read_to_string(&jsonl_out).unwrap();
         assert_eq!(
             got,
@@ -271,7 +301,7 @@ mod tests {
 
         // Compact JSON array: same ordering invariant.
         let json_out = dir.path().join("out.json");
-        stitch_tmp_parts_to_json_array(&tmp_dir, &json_out, false, 64 * 1024).unwrap();
+        stitch_tmp_parts_to_json_array(&tmp_dir, &json_out, false, false, 64 * 1024).unwrap();
         let got = fs::read_to_string(&json_out).unwrap();
         assert_eq!(
             got,
@@ -294,7 +324,7 @@ mod tests {
         }
 
         let out = dir.path().join("out.json");
-        stitch_tmp_parts_to_json_array(&tmp_dir, &out, true, 64 * 1024).unwrap();
+        stitch_tmp_parts_to_json_array(&tmp_dir, &out, true, false, 64 * 1024).unwrap();
         let got = fs::read_to_string(&out).unwrap();
         assert_eq!(
             got,
@@ -15,18 +15,83 @@ fn write_with_timestamps<W: Write + ?Sized>(
     Ok(())
 }
 
+/// A whitelist entry starting with `/` is a JSON pointer into nested data.
+/// Returns the pointer and its output key: the alias after ` as `, or else
+/// the pointer's unescaped segments joined with `.` (`/media/oembed/title`
+/// is written as `media.oembed.title`).
+fn whitelist_pointer_entry(field: &str) -> Option<(&str, String)> {
+    if !field.starts_with('/') {
+        return None;
+    }
+    let (pointer, alias) = match field.split_once(" as ") {
+        Some((pointer, alias)) => (pointer.trim_end(), alias.trim()),
+        None => (field, ""),
+    };
+    let key = if alias.is_empty() {
+        pointer[1..]
+            .split('/')
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect::<Vec<_>>()
+            .join(".")
+    } else {
+        alias.to_string()
+    };
+    Some((pointer, key))
+}
+
+/// Whether any whitelist entry is a JSON pointer. The streaming tokenizer
+/// only sees top-level keys, so such whitelists always take the Value path.
+fn whitelist_has_pointers(fields: &[String]) -> bool {
+    fields.iter().any(|field| field.starts_with('/'))
+}
+
+/// What [`write_with_whitelist`] projects a line with: the whitelist and its
+/// tokenizer, the tokenizer's scratch buffers, and the line's position, which
+/// a malformed line on the `Value` path reports.
+struct WhitelistWrite<'a> {
+    fields: &'a [String],
+    tokenizer: &'a WhitelistTokenizer,
+    tokenizer_buf: &'a mut String,
+    matched_indices: &'a mut Vec<usize>,
+    human_timestamps: bool,
+    source_order: bool,
+    has_pointers: bool,
+    path: &'a std::path::Path,
+    line_number: u64,
+}
+
+/// Project `line` onto the whitelist and write it; returns whether the slow
+/// `Value` path was taken.
 fn write_with_whitelist<W: Write + ?Sized>(
     writer: &mut W,
     line: &str,
-    fields: &[String],
-    tokenizer: &WhitelistTokenizer,
-    tokenizer_buf: &mut String,
-    matched_indices: &mut Vec<usize>,
-    human_timestamps: bool,
+    whitelist: WhitelistWrite<'_>,
     written: &mut u64,
-    path: &std::path::Path,
-    line_number: u64,
 ) -> Result<bool> {
+    let WhitelistWrite {
+        fields,
+        tokenizer,
+        tokenizer_buf,
+        matched_indices,
+        human_timestamps,
+        source_order,
+        has_pointers,
+        path,
+        line_number,
+    } = whitelist;
+    if has_pointers {
+        write_via_value(
+            writer,
+            line,
+            &ValueRewrite::projection(fields, source_order, human_timestamps),
+            Some(matched_indices),
+            written,
+            path,
+            line_number,
+        )?;
+        return Ok(true);
+    }
+
     // Preferred path: the streaming tokenizer copies raw value bytes verbatim
     // and never builds a `serde_json::Value`. If it rejects a structurally
     // surprising line, fall back to the slow Value path so correctness on odd
@@ -53,9 +118,8 @@ fn write_with_whitelist<W: Write + ?Sized>(
     write_via_value(
         writer,
         line,
-        Some(fields),
+        &ValueRewrite::projection(fields, source_order, human_timestamps),
         Some(matched_indices),
-        human_timestamps,
         written,
         path,
         line_number,
@@ -63,27 +127,259 @@ fn write_with_whitelist<W: Write + ?Sized>(
     Ok(true)
 }
 
+/// Top-level fields of a JSON line in source order (a repeated key keeps its
+/// first position and its last value, matching `Value` parsing). Non-object
+/// lines yield no fields, so whitelisting them writes `{}` like the `Value`
+/// path does.
+struct SourceOrderedFields(Vec<(String, Value)>);
+
+impl<'de> serde::Deserialize<'de> for SourceOrderedFields {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for FieldsVisitor {
+            type Value = SourceOrderedFields;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a JSON value")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut fields: Vec<(String, Value)> = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, Value>()? {
+                    match fields.iter_mut().find(|(seen, _)| *seen == key) {
+                        Some(slot) => slot.1 = value,
+                        None => fields.push((key, value)),
+                    }
+                }
+                Ok(SourceOrderedFields(fields))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+
+            fn visit_bool<E>(self, _: bool) -> std::result::Result<Self::Value, E> {
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+
+            fn visit_i64<E>(self, _: i64) -> std::result::Result<Self::Value, E> {
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+
+            fn visit_u64<E>(self, _: u64) -> std::result::Result<Self::Value, E> {
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+
+            fn visit_f64<E>(self, _: f64) -> std::result::Result<Self::Value, E> {
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+
+            fn visit_str<E>(self, _: &str) -> std::result::Result<Self::Value, E> {
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+
+            fn visit_unit<E>(self) -> std::result::Result<Self::Value, E> {
+                Ok(SourceOrderedFields(Vec::new()))
+            }
+        }
+
+        d.deserialize_any(FieldsVisitor)
+    }
+}
+
+/// Whitelist projection of `line` that keeps the record's key order: `Map`
+/// sorts keys, so the projected object comes back with its keys in the order
+/// they appear in `line`. JSON-pointer entries have no source position and
+/// follow the top-level keys in whitelist order.
+fn project_source_order(
+    line: &str,
+    whitelist: &[String],
+    mut matched_indices: Option<&mut Vec<usize>>,
+    path: &std::path::Path,
+    line_number: u64,
+) -> Result<(Value, Vec<String>)> {
+    let SourceOrderedFields(fields) =
+        serde_json::from_str(line).map_err(|e| malformed_json_error(path, line_number, e))?;
+    let mut matched = |idx: usize| {
+        if let Some(indices) = matched_indices.as_mut() {
+            indices.push(idx);
+        }
+    };
+    let mut pointer_values = Vec::new();
+    for (idx, entry) in whitelist.iter().enumerate() {
+        let Some((pointer, key)) = whitelist_pointer_entry(entry) else {
+            continue;
+        };
+        let (head, rest) = match pointer[1..].find('/') {
+            Some(pos) => pointer[1..].split_at(pos),
+            None => (&pointer[1..], ""),
+        };
+        let head = head.replace("~1", "/").replace("~0", "~");
+        let found = fields
+            .iter()
+            .find(|(field, _)| *field == head)
+            .and_then(|(_, value)| value.pointer(rest));
+        if let Some(value) = found {
+            matched(idx);
+            pointer_values.push((key, value.clone()));
+        }
+    }
+    let mut keys = Vec::new();
+    let mut obj = Map::new();
+    for (key, value) in fields {
+        if let Some(idx) = whitelist.iter().position(|f| *f == key) {
+            matched(idx);
+            keys.push(key.clone());
+            obj.insert(key, value);
+        }
+    }
+    for (key, value) in pointer_values {
+        if !obj.contains_key(&key) {
+            keys.push(key.clone());
+        }
+        obj.insert(key, value);
+    }
+    if let Some(indices) = matched_indices {
+        indices.sort_unstable();
+    }
+    Ok((Value::Object(obj), keys))
+}
+
+/// Write `val` with its top-level keys in `keys` order. Keys added after the
+/// projection (`_src_file`/`_src_line`, a transform's own) follow in map
+/// order; keys a transform removed are skipped.
+fn write_in_key_order<W: Write + ?Sized>(
+    writer: &mut W,
+    keys: &[String],
+    val: &Value,
+) -> Result<()> {
+    let Value::Object(obj) = val else {
+        serde_json::to_writer(&mut *writer, val)?;
+        return Ok(());
+    };
+    let ordered = keys.iter().filter(|key| obj.contains_key(key.as_str()));
+    let rest = obj.keys().filter(|key| !keys.contains(key));
+    writer.write_all(b"{")?;
+    for (i, key) in ordered.chain(rest).enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut *writer, key)?;
+        writer.write_all(b":")?;
+        serde_json::to_writer(&mut *writer, &obj[key.as_str()])?;
+    }
+    writer.write_all(b"}")?;
+    Ok(())
+}
+
+/// Serializes a `Value` with object keys sorted at every depth. `Map` only
+/// sorts while serde_json's `preserve_order` feature is off, and a dependency
+/// can turn that on, so canonical output re-collects each object into a
+/// `BTreeMap` instead of relying on it.
+struct CanonicalJson<'a>(&'a Value);
+
+impl serde::Serialize for CanonicalJson<'_> {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        match self.0 {
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| (k.as_str(), CanonicalJson(v)))
+                .collect::<std::collections::BTreeMap<_, _>>()
+                .serialize(s),
+            Value::Array(items) => {
+                let mut seq = s.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&CanonicalJson(item))?;
+                }
+                seq.end()
+            }
+            other => other.serialize(s),
+        }
+    }
+}
+
+/// What [`write_via_value`] does to a parsed record: project `whitelist` (in
+/// the record's key order with `source_order`, unless `canonical` sorts the
+/// keys), coerce `edited`, lowercase `subreddit`, rewrite timestamps, append
+/// `_src_file`/`_src_line` when `provenance` names the dump, then run the
+/// caller's `transform`.
+#[derive(Clone, Copy)]
+struct ValueRewrite<'a> {
+    whitelist: Option<&'a [String]>,
+    source_order: bool,
+    human_timestamps: bool,
+    canonical: bool,
+    provenance: Option<&'a str>,
+    transform: Option<&'a RecordTransform>,
+    edited: EditedMode,
+    lowercase_subreddit: bool,
+}
+
+impl<'a> ValueRewrite<'a> {
+    /// Whitelist projection only: the slow path of the streaming tokenizer.
+    fn projection(fields: &'a [String], source_order: bool, human_timestamps: bool) -> Self {
+        Self {
+            whitelist: Some(fields),
+            source_order,
+            human_timestamps,
+            canonical: false,
+            provenance: None,
+            transform: None,
+            edited: EditedMode::Leave,
+            lowercase_subreddit: false,
+        }
+    }
+}
+
+/// Parse `line`, apply `rewrite`, and write the result.
 fn write_via_value<W: Write + ?Sized>(
     writer: &mut W,
     line: &str,
-    whitelist: Option<&[String]>,
+    rewrite: &ValueRewrite<'_>,
     mut matched_indices: Option<&mut Vec<usize>>,
-    human_timestamps: bool,
     written: &mut u64,
     path: &std::path::Path,
     line_number: u64,
 ) -> Result<()> {
+    let ValueRewrite {
+        whitelist,
+        source_order,
+        human_timestamps,
+        canonical,
+        provenance,
+        transform,
+        edited,
+        lowercase_subreddit,
+    } = *rewrite;
     if let Some(indices) = matched_indices.as_mut() {
         indices.clear();
     }
-    let val: Value =
-        serde_json::from_str(line).map_err(|e| malformed_json_error(path, line_number, e))?;
-    let mut out_val = if let Some(fields) = whitelist {
+    let mut key_order = None;
+    let mut out_val = if let (Some(fields), true) = (whitelist, source_order) {
+        let (val, keys) = project_source_order(line, fields, matched_indices, path, line_number)?;
+        key_order = Some(keys);
+        val
+    } else if let Some(fields) = whitelist {
+        let val: Value =
+            serde_json::from_str(line).map_err(|e| malformed_json_error(path, line_number, e))?;
         let mut obj = Map::new();
         if let Some(map) = val.as_object() {
             for (idx, k) in fields.iter().enumerate() {
-                if let Some(v) = map.get(k) {
-                    obj.insert(k.clone(), v.clone());
+                let (key, found) = match whitelist_pointer_entry(k) {
+                    Some((pointer, key)) => (key, val.pointer(pointer)),
+                    None => (k.clone(), map.get(k)),
+                };
+                if let Some(v) = found {
+                    obj.insert(key, v.clone());
                     if let Some(indices) = matched_indices.as_mut() {
                         indices.push(idx);
                     }
@@ -92,19 +388,97 @@ fn write_via_value<W: Write + ?Sized>(
         }
         Value::Object(obj)
     } else {
-        val
+        serde_json::from_str(line).map_err(|e| malformed_json_error(path, line_number, e))?
     };
 
+    apply_edited_mode(&mut out_val, edited);
+    if lowercase_subreddit {
+        lowercase_subreddit_field(&mut out_val);
+    }
     if human_timestamps {
         apply_human_timestamps(&mut out_val);
     }
+    if let (Some(src_file), Value::Object(obj)) = (provenance, &mut out_val) {
+        obj.insert("_src_file".to_string(), Value::from(src_file));
+        obj.insert("_src_line".to_string(), Value::from(line_number));
+    }
+    if let Some(RecordTransform(f)) = transform {
+        f(&mut out_val);
+    }
 
-    serde_json::to_writer(&mut *writer, &out_val)?;
+    if canonical {
+        serde_json::to_writer(&mut *writer, &CanonicalJson(&out_val))?;
+    } else if let Some(keys) = &key_order {
+        write_in_key_order(writer, keys, &out_val)?;
+    } else {
+        serde_json::to_writer(&mut *writer, &out_val)?;
+    }
     writer.write_all(b"\n")?;
     *written += 1;
     Ok(())
 }
 
+/// Lowercase a top-level string `subreddit` in place.
+pub(crate) fn lowercase_subreddit_field(val: &mut Value) {
+    if let Some(Value::String(sub)) = val.as_object_mut().and_then(|obj| obj.get_mut("subreddit")) {
+        if sub.chars().any(char::is_uppercase) {
+            *sub = sub.to_lowercase();
+        }
+    }
+}
+
+/// Projects single lines through a whitelist the way the JSONL write path
+/// does, pointer entries included, without timestamp rewriting.
+pub(crate) struct WhitelistProjector<'a> {
+    fields: &'a [String],
+    tokenizer: WhitelistTokenizer,
+    has_pointers: bool,
+    tokenizer_buf: String,
+    matched_indices: Vec<usize>,
+    out: Vec<u8>,
+}
+
+impl<'a> WhitelistProjector<'a> {
+    pub(crate) fn new(fields: &'a [String]) -> Self {
+        Self {
+            fields,
+            tokenizer: WhitelistTokenizer::new(fields.iter().map(|s| s.as_str())),
+            has_pointers: whitelist_has_pointers(fields),
+            tokenizer_buf: String::new(),
+            matched_indices: Vec::new(),
+            out: Vec::new(),
+        }
+    }
+
+    /// The projected line, newline included.
+    pub(crate) fn project(
+        &mut self,
+        line: &str,
+        path: &std::path::Path,
+        line_number: u64,
+    ) -> Result<&[u8]> {
+        self.out.clear();
+        let mut written = 0_u64;
+        let _used_slow_path = write_with_whitelist(
+            &mut self.out,
+            line,
+            WhitelistWrite {
+                fields: self.fields,
+                tokenizer: &self.tokenizer,
+                tokenizer_buf: &mut self.tokenizer_buf,
+                matched_indices: &mut self.matched_indices,
+                human_timestamps: false,
+                source_order: false,
+                has_pointers: self.has_pointers,
+                path,
+                line_number,
+            },
+            &mut written,
+        )?;
+        Ok(&self.out)
+    }
+}
+
 #[doc(hidden)]
 pub fn project_whitelist_line_for_tests(
     line: &str,
@@ -112,33 +486,57 @@ pub fn project_whitelist_line_for_tests(
     path: &std::path::Path,
     line_number: u64,
 ) -> Result<String> {
-    let tokenizer = WhitelistTokenizer::new(fields.iter().map(|s| s.as_str()));
-    let mut tokenizer_buf = String::new();
-    let mut matched_indices = Vec::new();
-    let mut out = Vec::new();
-    let mut written = 0_u64;
-    let _used_slow_path = write_with_whitelist(
-        &mut out,
-        line,
-        fields,
-        &tokenizer,
-        &mut tokenizer_buf,
-        &mut matched_indices,
-        false,
-        &mut written,
-        path,
-        line_number,
-    )?;
-    Ok(String::from_utf8(out)?.trim_end_matches('\n').to_string())
+    let mut projector = WhitelistProjector::new(fields);
+    let out = projector.project(line, path, line_number)?;
+    Ok(std::str::from_utf8(out)?.trim_end_matches('\n').to_string())
+}
+
+/// Counts bytes passed to the wrapped writer so each record's output size
+/// can be charged against a [`RecordLimit`] byte budget.
+pub(crate) struct ByteCountingWriter<'a, W: Write + ?Sized> {
+    inner: &'a mut W,
+    bytes: u64,
+}
+
+impl<'a, W: Write + ?Sized> ByteCountingWriter<'a, W> {
+    pub(crate) fn new(inner: &'a mut W) -> Self {
+        Self { inner, bytes: 0 }
+    }
+
+    /// Bytes written since the last call.
+    pub(crate) fn take_bytes(&mut self) -> u64 {
+        std::mem::take(&mut self.bytes)
+    }
+}
+
+impl<W: Write + ?Sized> Write for ByteCountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 #[derive(Clone, Copy)]
 enum StreamWritePath<'a> {
     Raw,
     Timestamps,
+    /// Full parse and re-serialization (`canonicalize_json` sorts keys,
+    /// `add_provenance` appends `_src_file`/`_src_line`, `transform` runs the
+    /// caller's closure, `edited` coerces that field's type,
+    /// `lowercase_subreddit` lowercases `subreddit`), with the whitelist, if
+    /// any, projected on the parsed `Value` (in source key order with
+    /// `source_order`).
+    Parsed(ValueRewrite<'a>),
     Whitelist {
         fields: &'a [String],
         tokenizer: &'a WhitelistTokenizer,
+        source_order: bool,
+        has_pointers: bool,
     },
 }
 
@@ -149,6 +547,21 @@ pub struct StreamJobResult {
     /// or more lines. Callers that publish resumable outputs must not commit
     /// such files as complete.
     pub complete: bool,
+    /// Smallest and largest `created_utc` among the written records, or
+    /// `None` when none carried one.
+    pub created_utc_range: Option<(i64, i64)>,
+    /// Bytes written for those records, before any output compression.
+    pub output_bytes: u64,
+}
+
+/// Widen `range` to include `ts`.
+pub(crate) fn widen_created_utc_range(range: &mut Option<(i64, i64)>, ts: Option<i64>) {
+    if let Some(ts) = ts {
+        *range = Some(match *range {
+            Some((lo, hi)) => (lo.min(ts), hi.max(ts)),
+            None => (ts, ts),
+        });
+    }
 }
 
 #[allow(dead_code)]
@@ -167,6 +580,122 @@ pub fn stream_job<W: Write + ?Sized>(
     stream_job_with_partial_policy(
         job,
         writer,
+        &StreamJobCtx {
+            targets,
+            whitelist,
+            pb: pb.as_ref(),
+            bounds,
+            read_buf_bytes,
+            human_timestamps,
+            whitelist_tracker,
+            ..StreamJobCtx::new(query)
+        },
+    )
+}
+
+/// Send a line that failed to parse to `reject_sink` and continue, or fail
+/// the file with the usual malformed-JSON error when no sink is configured.
+fn reject_malformed_line(
+    reject_sink: Option<&RejectSink>,
+    path: &std::path::Path,
+    line_number: u64,
+    line: &str,
+    error: impl std::fmt::Display,
+) -> Result<()> {
+    match reject_sink {
+        Some(sink) => sink.record(path, line_number, line, error),
+        None => Err(malformed_json_error(path, line_number, error)),
+    }
+}
+
+/// Per-run settings for [`stream_job_with_partial_policy`], shared by every
+/// file of a run. [`StreamJobCtx::from_options`] fills in what comes from
+/// [`ETLOptions`]; callers add the rest with struct update syntax.
+#[derive(Clone, Copy)]
+pub(crate) struct StreamJobCtx<'a> {
+    pub(crate) targets: Option<&'a Vec<String>>,
+    pub(crate) query: &'a QuerySpec,
+    pub(crate) whitelist: &'a Option<Vec<String>>,
+    pub(crate) pb: Option<&'a ProgressBar>,
+    pub(crate) bounds: Option<DateBounds>,
+    pub(crate) read_buf_bytes: usize,
+    pub(crate) human_timestamps: bool,
+    pub(crate) whitelist_source_order: bool,
+    pub(crate) canonicalize_json: bool,
+    pub(crate) add_provenance: bool,
+    pub(crate) strict_json: bool,
+    pub(crate) whitelist_tracker: Option<&'a WhitelistMatchTracker>,
+    pub(crate) allow_partial: bool,
+    pub(crate) partial_reporter: Option<&'a PartialReadReporter>,
+    pub(crate) record_limit: Option<&'a RecordLimit>,
+    pub(crate) reject_sink: Option<&'a RejectSink>,
+    pub(crate) record_dedupe: Option<RecordDedupe<'a>>,
+    /// The run's IO overrides; `None` reads the thread's run settings (see
+    /// [`crate::config::RunSettings`]) or the process-wide default.
+    pub(crate) io_retries: Option<(usize, u64)>,
+    pub(crate) max_line_bytes: Option<usize>,
+    pub(crate) fsync: Option<bool>,
+    pub(crate) zstd_dictionary: Option<&'a ZstdDictionary>,
+}
+
+impl<'a> StreamJobCtx<'a> {
+    /// Every record written raw: no whitelist, rewrites, limit or sinks.
+    pub(crate) fn new(query: &'a QuerySpec) -> Self {
+        Self {
+            targets: None,
+            query,
+            whitelist: &None,
+            pb: None,
+            bounds: None,
+            read_buf_bytes: DEFAULT_READ_BUFFER_BYTES,
+            human_timestamps: false,
+            whitelist_source_order: false,
+            canonicalize_json: false,
+            add_provenance: false,
+            strict_json: false,
+            whitelist_tracker: None,
+            allow_partial: false,
+            partial_reporter: None,
+            record_limit: None,
+            reject_sink: None,
+            record_dedupe: None,
+            io_retries: None,
+            max_line_bytes: None,
+            fsync: None,
+            zstd_dictionary: None,
+        }
+    }
+
+    /// Date bounds, buffer size, output rewrites, partial-read/reject
+    /// handling and IO settings as `opts` configures them.
+    pub(crate) fn from_options(opts: &'a ETLOptions, query: &'a QuerySpec) -> Self {
+        let run = RunSettings::from_options(opts);
+        Self {
+            bounds: bounds_tuple(opts.start, opts.end),
+            read_buf_bytes: opts.read_buffer_bytes,
+            human_timestamps: opts.human_readable_timestamps,
+            whitelist_source_order: opts.whitelist_source_order,
+            canonicalize_json: opts.canonicalize_json,
+            add_provenance: opts.add_provenance,
+            strict_json: opts.strict_json,
+            allow_partial: opts.allow_partial,
+            partial_reporter: Some(&opts.partial_read_reporter),
+            reject_sink: opts.reject_sink.as_ref(),
+            io_retries: run.io_retries,
+            max_line_bytes: run.max_line_bytes,
+            fsync: run.fsync,
+            zstd_dictionary: opts.zstd_dictionary.as_ref(),
+            ..Self::new(query)
+        }
+    }
+}
+
+pub(crate) fn stream_job_with_partial_policy<W: Write + ?Sized>(
+    job: &FileJob,
+    writer: &mut W,
+    ctx: &StreamJobCtx<'_>,
+) -> Result<StreamJobResult> {
+    let StreamJobCtx {
         targets,
         query,
         whitelist,
@@ -174,29 +703,25 @@ pub fn stream_job<W: Write + ?Sized>(
         bounds,
         read_buf_bytes,
         human_timestamps,
+        whitelist_source_order,
+        canonicalize_json,
+        add_provenance,
+        strict_json,
         whitelist_tracker,
-        false,
-        None,
-        None,
-    )
-}
-
-pub(crate) fn stream_job_with_partial_policy<W: Write + ?Sized>(
-    job: &FileJob,
-    writer: &mut W,
-    targets: Option<&Vec<String>>,
-    query: &QuerySpec,
-    whitelist: &Option<Vec<String>>,
-    pb: Option<ProgressBar>,
-    bounds: Option<DateBounds>,
-    read_buf_bytes: usize,
-    human_timestamps: bool,
-    whitelist_tracker: Option<&WhitelistMatchTracker>,
-    allow_partial: bool,
-    partial_reporter: Option<&crate::config::PartialReadReporter>,
-    record_limit: Option<&RecordLimit>,
-) -> Result<StreamJobResult> {
+        allow_partial,
+        partial_reporter,
+        record_limit,
+        reject_sink,
+        record_dedupe,
+        io_retries,
+        max_line_bytes,
+        fsync,
+        zstd_dictionary,
+    } = *ctx;
     let mut written: u64 = 0;
+    let mut output_bytes: u64 = 0;
+    let mut writer = ByteCountingWriter::new(writer);
+    let mut created_utc_range = None;
     let mut ts_buf = String::new();
     let mut tok_buf = String::new();
     let mut matched_indices = Vec::new();
@@ -207,7 +732,35 @@ pub(crate) fn stream_job_with_partial_policy<W: Write + ?Sized>(
         .as_ref()
         .map(|fields| WhitelistTokenizer::new(fields.iter().map(|s| s.as_str())));
 
+    // `_src_file` names the monthly dump (`RC_2006-01`) rather than echoing
+    // `job.path`, so the value does not depend on where the corpus is mounted.
+    let src_file = add_provenance.then(|| {
+        let prefix = match job.kind {
+            FileKind::Comment => "RC",
+            FileKind::Submission => "RS",
+        };
+        format!("{prefix}_{}", job.ym)
+    });
+
     let write_path = match whitelist.as_deref() {
+        fields
+            if canonicalize_json
+                || add_provenance
+                || query.transform.is_some()
+                || query.normalize_edited != EditedMode::Leave
+                || query.canonicalize_subreddit_case =>
+        {
+            StreamWritePath::Parsed(ValueRewrite {
+                whitelist: fields,
+                source_order: whitelist_source_order,
+                human_timestamps,
+                canonical: canonicalize_json,
+                provenance: src_file.as_deref(),
+                transform: query.transform.as_ref(),
+                edited: query.normalize_edited,
+                lowercase_subreddit: query.canonicalize_subreddit_case,
+            })
+        }
         None if human_timestamps => StreamWritePath::Timestamps,
         None => StreamWritePath::Raw,
         Some(fields) => StreamWritePath::Whitelist {
@@ -215,9 +768,19 @@ pub(crate) fn stream_job_with_partial_policy<W: Write + ?Sized>(
             tokenizer: tokenizer
                 .as_ref()
                 .expect("whitelist tokenizer is built when fields are present"),
+            source_order: whitelist_source_order,
+            has_pointers: whitelist_has_pointers(fields),
         },
     };
 
+    // The collect pass of a record dedupe reads the same lines the emit pass
+    // will; leave malformed ones for the emit pass to send to `reject_sink`
+    // so each is recorded once.
+    let reject = |line_number: u64, line: &str, e: &dyn std::fmt::Display| match record_dedupe {
+        Some(RecordDedupe::Collect(_)) if reject_sink.is_some() => Ok(()),
+        _ => reject_malformed_line(reject_sink, &job.path, line_number, line, e),
+    };
+
     let mut line_number: u64 = 0;
     let mut on_line = |line: &str| -> Result<()> {
         line_number += 1;
@@ -232,51 +795,117 @@ pub(crate) fn stream_job_with_partial_policy<W: Write + ?Sized>(
             Ok(min) => min,
             Err(_) => match serde_json::from_str::<Value>(line) {
                 Ok(_) => return Ok(()),
-                Err(e) => return Err(malformed_json_error(&job.path, line_number, e)),
+                Err(e) => return reject(line_number, line, &e),
             },
         };
         if !matches_minimal(&min, targets, query, job.kind) {
             return Ok(());
         }
-        if !within_bounds(&min, bounds) {
+        if !within_bounds_on(&min, bounds, query.time_field) {
             return Ok(());
         }
         if query.requires_full_parse() {
-            let val: Value = serde_json::from_str(line)
-                .map_err(|e| malformed_json_error(&job.path, line_number, e))?;
+            let val: Value = match serde_json::from_str(line) {
+                Ok(val) => val,
+                Err(e) => return reject(line_number, line, &e),
+            };
             if !matches_full(&val, job.kind, query) {
                 return Ok(());
             }
+        } else if strict_json {
+            // `MinimalRecord` skips the fields it does not name without fully
+            // validating them; parse the whole line before writing it.
+            if let Err(e) = serde_json::from_str::<Value>(line) {
+                return reject(line_number, line, &e);
+            }
+        }
+        match record_dedupe {
+            Some(RecordDedupe::Collect(collector)) => {
+                return collector.observe(job, line_number, line);
+            }
+            Some(RecordDedupe::Drop(duplicates))
+                if duplicates.is_duplicate(job, line_number, line)? =>
+            {
+                return Ok(());
+            }
+            _ => {}
         }
 
         claim_record_or_stop(record_limit)?;
 
-        match write_path {
+        let writer = &mut writer;
+        let wrote = match write_path {
             StreamWritePath::Raw => write_raw_line(writer, line, &mut written),
             StreamWritePath::Timestamps => {
                 write_with_timestamps(writer, line, &mut ts_buf, &mut written)
             }
-            StreamWritePath::Whitelist { fields, tokenizer } => {
-                let used_slow_path = write_with_whitelist(
-                    writer,
-                    line,
+            StreamWritePath::Parsed(rewrite) => write_via_value(
+                writer,
+                line,
+                &rewrite,
+                Some(&mut matched_indices),
+                &mut written,
+                &job.path,
+                line_number,
+            )
+            .and_then(|()| match (whitelist_tracker, rewrite.whitelist) {
+                (Some(tracker), Some(_)) => tracker.observe(WhitelistEmission {
+                    matched_fields: &matched_indices,
+                    used_slow_path: true,
+                }),
+                _ => Ok(()),
+            }),
+            StreamWritePath::Whitelist {
+                fields,
+                tokenizer,
+                source_order,
+                has_pointers,
+            } => write_with_whitelist(
+                writer,
+                line,
+                WhitelistWrite {
                     fields,
                     tokenizer,
-                    &mut tok_buf,
-                    &mut matched_indices,
+                    tokenizer_buf: &mut tok_buf,
+                    matched_indices: &mut matched_indices,
                     human_timestamps,
-                    &mut written,
-                    &job.path,
+                    source_order,
+                    has_pointers,
+                    path: &job.path,
                     line_number,
-                )?;
-                if let Some(tracker) = whitelist_tracker {
-                    tracker.observe(WhitelistEmission {
-                        matched_fields: &matched_indices,
-                        used_slow_path,
-                    })?;
-                }
+                },
+                &mut written,
+            )
+            .and_then(|used_slow_path| match whitelist_tracker {
+                Some(tracker) => tracker.observe(WhitelistEmission {
+                    matched_fields: &matched_indices,
+                    used_slow_path,
+                }),
+                None => Ok(()),
+            }),
+        };
+        let bytes = writer.take_bytes();
+        output_bytes += bytes;
+        if let Some(limit) = record_limit {
+            limit.add_bytes(bytes);
+        }
+        match wrote {
+            Ok(()) => {
+                widen_created_utc_range(&mut created_utc_range, min.created_utc);
                 Ok(())
             }
+            // The Parsed and whitelist paths fully parse lines that
+            // `parse_minimal` let through; one that fails there wrote nothing
+            // and is rejected like any other malformed line.
+            Err(e) => match e.downcast_ref::<MalformedJsonError>() {
+                Some(malformed) if reject_sink.is_some() => {
+                    if let Some(limit) = record_limit {
+                        limit.release();
+                    }
+                    reject(line_number, line, &malformed.message)
+                }
+                _ => Err(e),
+            },
         }
     };
 
@@ -291,23 +920,45 @@ pub(crate) fn stream_job_with_partial_policy<W: Write + ?Sized>(
             reporter.record(path, err);
         }
     };
-    let stream_result = for_each_line_with_opts_status(
-        &job.path,
-        LineStreamOpts {
-            read_buf_bytes: Some(read_buf_bytes),
-            progress: progress_cb.as_mut().map(|cb| cb as &mut dyn FnMut(u64)),
-            on_skip: allow_partial
-                .then_some(&mut skip_cb as &mut dyn FnMut(&std::path::Path, &anyhow::Error)),
-            partial_read_policy,
-            ..Default::default()
-        },
-        |s| on_line(s),
-    );
+    let trace = FileTrace::start(&job.path);
+    let stream_result = trace.in_scope(|| {
+        for_each_line_with_opts_status(
+            &job.path,
+            LineStreamOpts {
+                read_buf_bytes: Some(read_buf_bytes),
+                progress: progress_cb.as_mut().map(|cb| cb as &mut dyn FnMut(u64)),
+                on_skip: allow_partial
+                    .then_some(&mut skip_cb as &mut dyn FnMut(&std::path::Path, &anyhow::Error)),
+                partial_read_policy,
+                max_line_bytes,
+                io_retries,
+                zstd_dictionary,
+                ..Default::default()
+            },
+            |s| on_line(s),
+        )
+    });
     let complete = match stream_result {
         Ok(complete) => complete,
         Err(e) if is_record_limit_reached(&e) => true,
         Err(e) => return Err(e),
     };
+    trace.finish(written);
+    // Publish this file's rejects, replacing any an earlier run recorded for
+    // it. The collect pass stages none, so it leaves them to the emit pass.
+    if let Some(sink) = reject_sink {
+        if !matches!(record_dedupe, Some(RecordDedupe::Collect(_))) {
+            sink.finish_source(
+                &job.path,
+                fsync.unwrap_or_else(crate::util::run_fsync_outputs),
+            )?;
+        }
+    }
 
-    Ok(StreamJobResult { written, complete })
+    Ok(StreamJobResult {
+        written,
+        complete,
+        created_utc_range,
+        output_bytes,
+    })
 }
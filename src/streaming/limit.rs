@@ -1,16 +1,21 @@
+use crate::config::{
+    ETLOptions, PartialReadReporter, RejectSink, RunSettings, DEFAULT_READ_BUFFER_BYTES,
+};
 use crate::filters::{
-    matches_full, matches_minimal, matches_subreddit_basic, within_bounds, DateBounds,
+    bounds_tuple, matches_full, matches_minimal, matches_subreddit_basic, within_bounds_on,
+    DateBounds,
 };
 use crate::json_whitelist::WhitelistTokenizer;
-use crate::paths::FileJob;
-use crate::query::QuerySpec;
+use crate::paths::{FileJob, FileKind};
+use crate::progress::ProgressBar;
+use crate::query::{EditedMode, QuerySpec, RecordTransform};
 use crate::shard::ShardedWriter;
+use crate::util::FileTrace;
 use crate::zstd_jsonl::{
     for_each_line_with_opts_status, malformed_json_error, parse_minimal, LineStreamOpts,
-    PartialReadPolicy,
+    MalformedJsonError, PartialReadPolicy, ZstdDictionary,
 };
 use anyhow::{anyhow, Result};
-use indicatif::ProgressBar;
 use serde_json::{Map, Value};
 use std::io::{self, Write};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -49,33 +54,60 @@ pub(crate) fn is_record_limit_reached(err: &anyhow::Error) -> bool {
         .any(|cause| cause.downcast_ref::<RecordLimitReached>().is_some())
 }
 
+/// Shared stop condition for one run's writers: a cap on records claimed
+/// and, optionally, a soft cap on uncompressed output bytes. The byte budget
+/// is checked before each record is claimed and charged after it is written,
+/// so every concurrent worker may overshoot it by the record in flight.
 #[derive(Debug)]
 pub(crate) struct RecordLimit {
     max: u64,
     claimed: AtomicU64,
+    max_bytes: Option<u64>,
+    bytes: AtomicU64,
 }
 
 impl RecordLimit {
-    pub(crate) fn new(max: u64) -> Self {
-        Self::new_with_claimed(max, 0)
-    }
-
     pub(crate) fn new_with_claimed(max: u64, claimed: u64) -> Self {
         Self {
             max,
             claimed: AtomicU64::new(claimed.min(max)),
+            max_bytes: None,
+            bytes: AtomicU64::new(0),
         }
     }
 
+    /// Adds a soft cap on output bytes written through this limit, of which
+    /// `claimed_bytes` were already written by an earlier run.
+    pub(crate) fn with_byte_budget(mut self, max_bytes: Option<u64>, claimed_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self.bytes = AtomicU64::new(claimed_bytes);
+        self
+    }
+
     pub(crate) fn is_zero(&self) -> bool {
-        self.max == 0
+        self.max == 0 || self.max_bytes == Some(0)
     }
 
     pub(crate) fn is_exhausted(&self) -> bool {
-        self.claimed.load(Ordering::Relaxed) >= self.max
+        self.claimed.load(Ordering::Relaxed) >= self.max || self.bytes_exhausted()
+    }
+
+    fn bytes_exhausted(&self) -> bool {
+        self.max_bytes
+            .is_some_and(|max| self.bytes.load(Ordering::Relaxed) >= max)
+    }
+
+    /// Charges bytes written for a claimed record against the byte budget.
+    pub(crate) fn add_bytes(&self, n: u64) {
+        if self.max_bytes.is_some() {
+            self.bytes.fetch_add(n, Ordering::Relaxed);
+        }
     }
 
     pub(crate) fn try_claim(&self) -> bool {
+        if self.bytes_exhausted() {
+            return false;
+        }
         let mut cur = self.claimed.load(Ordering::Relaxed);
         loop {
             if cur >= self.max {
@@ -92,6 +124,11 @@ impl RecordLimit {
             }
         }
     }
+
+    /// Gives back a claim whose record was not written after all.
+    pub(crate) fn release(&self) {
+        self.claimed.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 #[inline]
@@ -4,6 +4,7 @@
 include!("limit.rs");
 include!("whitelist_tracker.rs");
 include!("timestamps.rs");
+include!("record_dedupe.rs");
 include!("job.rs");
 include!("usernames.rs");
 include!("tests.rs");
@@ -0,0 +1,213 @@
+// Record dedupe for `ScanPlan::dedupe_records_by`. Run as two passes over the
+// same planned files: the collect pass shards every matching record's key and
+// position to disk, the reduction keeps the earliest position per key, and the
+// emit pass drops the positions that lost.
+
+use crate::key_extractor::KeyExtractor;
+use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
+use crate::parents_ids::{IdScratchRoot, IdShardWriter, IdShards, SharedIdsetCache};
+use crate::shard_common::{self, LineShardWriters};
+use ahash::{AHashMap, RandomState};
+use anyhow::Context;
+use rayon::prelude::*;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Loser-shard files [`DuplicateRecords`] keeps parsed at once.
+const DUPLICATE_RECORDS_CACHE_CAP: usize = 64;
+
+/// Which pass of a record-dedupe run
+/// [`stream_job_with_partial_policy`] is driving.
+#[derive(Clone, Copy)]
+pub(crate) enum RecordDedupe<'a> {
+    /// Record each matching record's key and position; write nothing.
+    Collect(&'a RecordKeyCollector),
+    /// Skip records that lost to an earlier copy of their key.
+    Drop(&'a DuplicateRecords),
+}
+
+/// Where a record sits in the planned corpus. Comparing two positions as
+/// strings orders them by month, then comments before submissions, then line.
+fn record_position(job: &FileJob, line_number: u64) -> String {
+    let prefix = match job.kind {
+        FileKind::Comment => "RC",
+        FileKind::Submission => "RS",
+    };
+    format!("{}:{prefix}:{line_number:020}", job.ym)
+}
+
+/// One shard line: the key as a JSON string (so tabs and newlines in it are
+/// escaped), a tab, and the record position.
+fn record_dedupe_line(key: &str, position: &str) -> String {
+    format!("{}\t{position}", Value::from(key))
+}
+
+fn record_dedupe_key(
+    key: &KeyExtractor,
+    job: &FileJob,
+    line_number: u64,
+    line: &str,
+) -> Result<Option<String>> {
+    key.key_from_line(line).with_context(|| {
+        format!(
+            "extract dedupe key from {} line {line_number}",
+            job.path.display()
+        )
+    })
+}
+
+/// Collect-pass sink: routes `key\tposition` lines to shards by key, so every
+/// copy of a key lands in the same shard.
+pub(crate) struct RecordKeyCollector {
+    key: Arc<KeyExtractor>,
+    scratch_root: Arc<IdScratchRoot>,
+    dir: PathBuf,
+    count: usize,
+    rs: RandomState,
+    writers: LineShardWriters,
+}
+
+impl RecordKeyCollector {
+    pub(crate) fn create(
+        key: Arc<KeyExtractor>,
+        scratch_root: Arc<IdScratchRoot>,
+        count: usize,
+    ) -> Result<Self> {
+        let count = crate::config::clamp_shard_count(count, "RecordKeyCollector::create");
+        let dir = scratch_root.path().join("record_keys");
+        crate::util::create_dir_all_with_default_backoff(&dir)
+            .with_context(|| format!("create record dedupe shard dir {}", dir.display()))?;
+        let writers = shard_common::create_line_shard_writers(
+            &dir,
+            count,
+            record_key_shard_name,
+            "record dedupe shard",
+        )?;
+        Ok(Self {
+            key,
+            scratch_root,
+            dir,
+            count,
+            rs: shard_common::seeded_state("record_dedupe"),
+            writers,
+        })
+    }
+
+    /// Record one matching line. Records without a key are never duplicates,
+    /// so they are not recorded.
+    fn observe(&self, job: &FileJob, line_number: u64, line: &str) -> Result<()> {
+        let Some(key) = record_dedupe_key(&self.key, job, line_number, line)? else {
+            return Ok(());
+        };
+        let entry = record_dedupe_line(&key, &record_position(job, line_number));
+        let idx = shard_common::shard_index(&self.rs, &key, self.count);
+        self.writers.write_parts(idx, &[entry.as_bytes(), b"\n"])
+    }
+
+    /// Reduce the collected shards to the set of losing positions: every copy
+    /// of a key except its earliest. Each shard is reduced in memory on its
+    /// own, so peak memory is one shard's distinct keys.
+    pub(crate) fn finish(self) -> Result<DuplicateRecords> {
+        self.writers.flush_all()?;
+        let RecordKeyCollector {
+            key,
+            scratch_root,
+            dir,
+            count,
+            writers,
+            ..
+        } = self;
+        drop(writers);
+
+        let losers = IdShardWriter::create(scratch_root, "dup_records", count)?;
+        (0..count)
+            .into_par_iter()
+            .try_for_each(|i| write_shard_losers(&dir.join(record_key_shard_name(i)), &losers))?;
+        let losers = losers.dedup()?;
+        if let Err(e) = crate::util::remove_dir_all_with_short_backoff(&dir) {
+            tracing::debug!(
+                path = %dir.display(),
+                error = %e,
+                "failed to remove record dedupe key shard scratch"
+            );
+        }
+        Ok(DuplicateRecords {
+            key,
+            losers,
+            cache: SharedIdsetCache::new(DUPLICATE_RECORDS_CACHE_CAP),
+        })
+    }
+}
+
+fn record_key_shard_name(idx: usize) -> String {
+    format!("record_keys_{idx:04}.tmp")
+}
+
+/// Read one key shard twice: first to find each key's earliest position, then
+/// to hand every other position to `losers`.
+fn write_shard_losers(path: &Path, losers: &IdShardWriter) -> Result<()> {
+    let mut earliest: AHashMap<String, String> = AHashMap::new();
+    for_each_shard_line(path, |key, position| {
+        match earliest.get_mut(key) {
+            Some(best) if position < best.as_str() => *best = position.to_string(),
+            Some(_) => {}
+            None => {
+                earliest.insert(key.to_string(), position.to_string());
+            }
+        }
+        Ok(())
+    })?;
+    for_each_shard_line(path, |key, position| {
+        if earliest.get(key).is_some_and(|best| best.as_str() != position) {
+            losers.write(&format!("{key}\t{position}"))?;
+        }
+        Ok(())
+    })
+}
+
+fn for_each_shard_line(path: &Path, mut f: impl FnMut(&str, &str) -> Result<()>) -> Result<()> {
+    let file = crate::util::open_with_default_backoff(path)
+        .with_context(|| format!("open record dedupe shard {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut buf = String::new();
+    loop {
+        let n = read_line_capped(&mut reader, &mut buf, DEFAULT_MAX_LINE_BYTES, path)
+            .with_context(|| format!("read record dedupe shard {}", path.display()))?;
+        if n == 0 {
+            return Ok(());
+        }
+        // Positions never contain a tab and the JSON-quoted key never holds
+        // a raw one, so the last tab is the separator.
+        if let Some((key, position)) = buf.rsplit_once('\t') {
+            f(key, position)?;
+        }
+    }
+}
+
+/// Emit-pass filter: the positions that lost to an earlier copy of their key,
+/// kept as on-disk [`IdShards`] and loaded shard by shard on lookup.
+pub(crate) struct DuplicateRecords {
+    key: Arc<KeyExtractor>,
+    losers: IdShards,
+    cache: SharedIdsetCache,
+}
+
+impl DuplicateRecords {
+    /// Number of records the emit pass will drop.
+    pub(crate) fn dropped(&self) -> usize {
+        self.losers.total_ids
+    }
+
+    fn is_duplicate(&self, job: &FileJob, line_number: u64, line: &str) -> Result<bool> {
+        if self.losers.total_ids == 0 {
+            return Ok(false);
+        }
+        let Some(key) = record_dedupe_key(&self.key, job, line_number, line)? else {
+            return Ok(false);
+        };
+        let entry = record_dedupe_line(&key, &record_position(job, line_number));
+        let shard = self.losers.path_for(self.losers.idx(&entry));
+        Ok(self.cache.get_or_load(&shard)?.contains(&entry))
+    }
+}
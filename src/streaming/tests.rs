@@ -134,14 +134,18 @@ mod tests {
         let fast_used_slow_path = write_with_whitelist(
             &mut out,
             r#"{"id":"kept","subreddit":"programming","author":"a"}"#,
-            &fields,
-            &tokenizer,
-            &mut tokenizer_buf,
-            &mut matched_indices,
-            false,
+            WhitelistWrite {
+                fields: &fields,
+                tokenizer: &tokenizer,
+                tokenizer_buf: &mut tokenizer_buf,
+                matched_indices: &mut matched_indices,
+                human_timestamps: false,
+                source_order: false,
+                has_pointers: false,
+                path: std::path::Path::new("test.jsonl"),
+                line_number: 1,
+            },
             &mut written,
-            std::path::Path::new("test.jsonl"),
-            1,
         )
         .unwrap();
         assert_eq!(matched_indices, vec![0], "sanity: projection contains id");
@@ -154,14 +158,18 @@ mod tests {
         let slow_used_slow_path = write_with_whitelist(
             &mut out,
             r#"[{"id":"not-a-top-level-object"}]"#,
-            &fields,
-            &tokenizer,
-            &mut tokenizer_buf,
-            &mut matched_indices,
-            false,
+            WhitelistWrite {
+                fields: &fields,
+                tokenizer: &tokenizer,
+                tokenizer_buf: &mut tokenizer_buf,
+                matched_indices: &mut matched_indices,
+                human_timestamps: false,
+                source_order: false,
+                has_pointers: false,
+                path: std::path::Path::new("test.jsonl"),
+                line_number: 2,
+            },
             &mut written,
-            std::path::Path::new("test.jsonl"),
-            2,
         )
         .unwrap();
         assert!(
@@ -195,14 +203,18 @@ mod tests {
             let used_slow_path = write_with_whitelist(
                 &mut out,
                 r#"[{"id":"slow"}]"#,
-                &fields,
-                &tokenizer,
-                &mut tokenizer_buf,
-                &mut matched_indices,
-                false,
+                WhitelistWrite {
+                    fields: &fields,
+                    tokenizer: &tokenizer,
+                    tokenizer_buf: &mut tokenizer_buf,
+                    matched_indices: &mut matched_indices,
+                    human_timestamps: false,
+                    source_order: false,
+                    has_pointers: false,
+                    path: std::path::Path::new("test.jsonl"),
+                    line_number: i + 1,
+                },
                 &mut written,
-                std::path::Path::new("test.jsonl"),
-                i + 1,
             )
             .unwrap();
             assert!(used_slow_path);
@@ -247,14 +259,18 @@ mod tests {
             let used_slow_path = write_with_whitelist(
                 &mut out,
                 r#"{"id":"x","permalink":"/a","permalink":"/b"}"#,
-                &fields,
-                &tokenizer,
-                &mut tokenizer_buf,
-                &mut matched_indices,
-                false,
+                WhitelistWrite {
+                    fields: &fields,
+                    tokenizer: &tokenizer,
+                    tokenizer_buf: &mut tokenizer_buf,
+                    matched_indices: &mut matched_indices,
+                    human_timestamps: false,
+                    source_order: false,
+                    has_pointers: false,
+                    path: std::path::Path::new("test.jsonl"),
+                    line_number: i + 1,
+                },
                 &mut written,
-                std::path::Path::new("test.jsonl"),
-                i + 1,
             )
             .unwrap();
             assert!(used_slow_path, "a duplicate whitelisted key forces the slow path");
@@ -271,6 +287,45 @@ mod tests {
             .expect("a field matched on the slow path is cleared when there is no fast path");
     }
 
+    #[test]
+    fn whitelist_source_order_keeps_key_order_on_slow_path() {
+        // A duplicate whitelisted key forces the `Value` fallback, whose
+        // `Map` sorts keys; source order must keep `permalink` before `id`.
+        let fields = vec!["id".to_string(), "permalink".to_string()];
+        let tokenizer = WhitelistTokenizer::new(fields.iter().map(|s| s.as_str()));
+        let line = r#"{"permalink":"/a","author":"x","id":"p1","permalink":"/b"}"#;
+        let project = |source_order: bool| {
+            let mut tokenizer_buf = String::new();
+            let mut matched_indices = Vec::new();
+            let mut out = Vec::new();
+            let mut written = 0_u64;
+            let used_slow_path = write_with_whitelist(
+                &mut out,
+                line,
+                WhitelistWrite {
+                    fields: &fields,
+                    tokenizer: &tokenizer,
+                    tokenizer_buf: &mut tokenizer_buf,
+                    matched_indices: &mut matched_indices,
+                    human_timestamps: false,
+                    source_order,
+                    has_pointers: false,
+                    path: std::path::Path::new("test.jsonl"),
+                    line_number: 1,
+                },
+                &mut written,
+            )
+            .unwrap();
+            assert!(used_slow_path, "a duplicate whitelisted key forces the slow path");
+            assert_eq!(matched_indices, [0, 1]);
+            assert_eq!(written, 1);
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(project(false), "{\"id\":\"p1\",\"permalink\":\"/b\"}\n");
+        assert_eq!(project(true), "{\"permalink\":\"/b\",\"id\":\"p1\"}\n");
+    }
+
     #[test]
     fn strict_whitelist_fires_on_fast_path_empty_projection_at_finalize() {
         let fields = vec!["not_present".to_string()];
@@ -285,14 +340,18 @@ mod tests {
             let used_slow_path = write_with_whitelist(
                 &mut out,
                 r#"{"id":"fast","subreddit":"programming","author":"a"}"#,
-                &fields,
-                &tokenizer,
-                &mut tokenizer_buf,
-                &mut matched_indices,
-                false,
+                WhitelistWrite {
+                    fields: &fields,
+                    tokenizer: &tokenizer,
+                    tokenizer_buf: &mut tokenizer_buf,
+                    matched_indices: &mut matched_indices,
+                    human_timestamps: false,
+                    source_order: false,
+                    has_pointers: false,
+                    path: std::path::Path::new("test.jsonl"),
+                    line_number: i + 1,
+                },
                 &mut written,
-                std::path::Path::new("test.jsonl"),
-                i + 1,
             )
             .unwrap();
             assert!(!used_slow_path);
@@ -337,14 +396,18 @@ mod tests {
             let used_slow_path = write_with_whitelist(
                 &mut out,
                 line,
-                &fields,
-                &tokenizer,
-                &mut tokenizer_buf,
-                &mut matched_indices,
-                false,
+                WhitelistWrite {
+                    fields: &fields,
+                    tokenizer: &tokenizer,
+                    tokenizer_buf: &mut tokenizer_buf,
+                    matched_indices: &mut matched_indices,
+                    human_timestamps: false,
+                    source_order: false,
+                    has_pointers: false,
+                    path: std::path::Path::new("test.jsonl"),
+                    line_number: i + 1,
+                },
                 &mut written,
-                std::path::Path::new("test.jsonl"),
-                i + 1,
             )
             .unwrap();
             tracker
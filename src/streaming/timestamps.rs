@@ -16,6 +16,42 @@ pub fn apply_human_timestamps(val: &mut Value) {
     }
 }
 
+/// Coerce a top-level `edited` to the type `mode` asks for. Runs before
+/// `apply_human_timestamps`, so `AsTimestamp` formats the value itself and a
+/// missing `edited` stays missing.
+pub(crate) fn apply_edited_mode(val: &mut Value, mode: EditedMode) {
+    let Some(v) = val.as_object_mut().and_then(|obj| obj.get_mut("edited")) else {
+        return;
+    };
+    match mode {
+        EditedMode::Leave => {}
+        EditedMode::AsBool => {
+            let edited = match v {
+                Value::Null => false,
+                Value::Bool(b) => *b,
+                Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+                _ => true,
+            };
+            *v = Value::Bool(edited);
+        }
+        EditedMode::AsTimestamp => {
+            // An already formatted string is kept as is.
+            if v.is_string() {
+                return;
+            }
+            // Float-encoded timestamps (`1609459200.0`) occur in older dumps.
+            let ts = v
+                .as_i64()
+                .or_else(|| v.as_f64().map(|f| f as i64))
+                .filter(|&n| n != 0);
+            *v = ts
+                .and_then(|n| OffsetDateTime::from_unix_timestamp(n).ok())
+                .and_then(|dt| dt.format(&Rfc3339).ok())
+                .map_or(Value::Null, Value::String);
+        }
+    }
+}
+
 /// Test whether the JSON string token at `bytes[key_start]` (which must be a
 /// `"`) begins one of `"created_utc":`, `"retrieved_on":`, `"edited":` with an
 /// integer-literal value, and if so return `(value_start, value_end)`:
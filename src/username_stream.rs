@@ -1,5 +1,7 @@
 use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
@@ -19,6 +21,11 @@ enum ReadStep {
 }
 
 /// A streaming merger that yields deduped usernames from deduped shards.
+///
+/// A plain stream walks its shards one after another, so names come out
+/// grouped by shard rather than globally sorted. [`union`](Self::union),
+/// [`intersection`](Self::intersection) and [`difference`](Self::difference)
+/// combine two streams into one that yields names in ascending order.
 pub struct UsernameStream {
     files: Vec<PathBuf>,
     cleanup_roots: Vec<PathBuf>,
@@ -26,6 +33,8 @@ pub struct UsernameStream {
     reader: Option<BufReader<File>>,
     buf: String,
     current_file_errors: usize,
+    /// Set for streams built by the set operations; replaces the shard walk.
+    set_op: Option<Box<SetOp>>,
 }
 
 impl UsernameStream {
@@ -45,9 +54,81 @@ impl UsernameStream {
             reader: None,
             buf: String::with_capacity(8 * 1024),
             current_file_errors: 0,
+            set_op: None,
         })
     }
 
+    /// Drain the stream and return how many usernames were left in it.
+    ///
+    /// Consumes the stream (scratch directories are removed afterwards).
+    /// Like the `Iterator` impl, shards that fail to open are logged and
+    /// skipped rather than counted.
+    pub fn count_remaining(self) -> usize {
+        self.count()
+    }
+
+    /// Drain the stream into a globally sorted, deduped `Vec`.
+    ///
+    /// Each deduped shard holds a name at most once and a name maps to a
+    /// single shard, so the only work left is sorting the concatenation;
+    /// the `dedup` is a cheap safeguard. Errors are handled as in the
+    /// `Iterator` impl.
+    pub fn collect_sorted(self) -> Vec<String> {
+        let mut names: Vec<String> = self.collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Names in either stream, sorted and deduped.
+    ///
+    /// Like the other set operations this is a streaming merge: each input's
+    /// shards are k-way merged (every deduped shard is already sorted, and a
+    /// name lives in exactly one shard), so memory stays at one pending name
+    /// per shard. All shards of both inputs are open at once. Each input is
+    /// read from its first shard, regardless of names already taken from it,
+    /// and its scratch directories are kept until the result is dropped. A
+    /// shard that turns out not to be sorted is reported as an error.
+    pub fn union(self, other: UsernameStream) -> Result<UsernameStream> {
+        self.combine(other, SetOpKind::Union)
+    }
+
+    /// Names in both streams, sorted. See [`union`](Self::union).
+    pub fn intersection(self, other: UsernameStream) -> Result<UsernameStream> {
+        self.combine(other, SetOpKind::Intersection)
+    }
+
+    /// Names in `self` but not in `other`, sorted. See [`union`](Self::union).
+    pub fn difference(self, other: UsernameStream) -> Result<UsernameStream> {
+        self.combine(other, SetOpKind::Difference)
+    }
+
+    fn combine(mut self, mut other: UsernameStream, kind: SetOpKind) -> Result<UsernameStream> {
+        let left = self.take_sorted_source()?;
+        let right = other.take_sorted_source()?;
+        let mut cleanup_roots = std::mem::take(&mut self.cleanup_roots);
+        cleanup_roots.append(&mut other.cleanup_roots);
+        let mut out = Self::from_deduped_files_with_cleanup(Vec::new(), cleanup_roots)?;
+        out.set_op = Some(Box::new(SetOp {
+            kind,
+            left,
+            right,
+            heads: (None, None),
+        }));
+        Ok(out)
+    }
+
+    /// This stream's names as an ascending source, leaving `self` empty
+    /// apart from its cleanup roots.
+    fn take_sorted_source(&mut self) -> Result<SortedSource> {
+        self.reader = None;
+        if let Some(op) = self.set_op.take() {
+            return Ok(SortedSource::SetOp(op));
+        }
+        let files = std::mem::take(&mut self.files);
+        Ok(SortedSource::Shards(SortedMerge::open(&files)?))
+    }
+
     fn cleanup_scratch(&mut self) {
         self.reader = None;
         for root in std::mem::take(&mut self.cleanup_roots) {
@@ -81,9 +162,24 @@ impl UsernameStream {
     ///
     /// `None` is a clean end-of-stream. `Some(Err(_))` means opening the next
     /// shard failed; the stream has already advanced past it, so calling
-    /// `try_next` again attempts the file after it. The lossy
-    /// `Iterator::next` impl wraps this method and logs+continues on errors.
+    /// `try_next` again attempts the file after it. For streams built by the
+    /// set operations, `Some(Err(_))` is a failed merge and ends the stream.
+    /// The lossy `Iterator::next` impl wraps this method and logs+continues
+    /// on errors.
     pub fn try_next(&mut self) -> Option<Result<String>> {
+        if let Some(op) = &mut self.set_op {
+            match op.next_name() {
+                Ok(Some(name)) => return Some(Ok(name)),
+                Ok(None) => {}
+                // A failed merge cannot resume mid-shard, so it ends the
+                // stream after reporting the error.
+                Err(e) => {
+                    self.set_op = None;
+                    return Some(Err(e));
+                }
+            }
+            self.set_op = None;
+        }
         loop {
             if self.reader.is_none() {
                 match self.open_next() {
@@ -152,6 +248,147 @@ impl UsernameStream {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SetOpKind {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// Ascending, deduped names: a shard merge or a nested set operation.
+enum SortedSource {
+    Shards(SortedMerge),
+    SetOp(Box<SetOp>),
+}
+
+impl SortedSource {
+    fn next_name(&mut self) -> Result<Option<String>> {
+        match self {
+            Self::Shards(merge) => merge.next_name(),
+            Self::SetOp(op) => op.next_name(),
+        }
+    }
+}
+
+/// K-way merge over sorted shard files, yielding each name once in
+/// ascending order.
+pub(crate) struct SortedMerge {
+    readers: Vec<(PathBuf, BufReader<File>)>,
+    heap: BinaryHeap<Reverse<(String, usize)>>,
+    last: Option<String>,
+    buf: String,
+}
+
+impl SortedMerge {
+    pub(crate) fn open(files: &[PathBuf]) -> Result<Self> {
+        let mut merge = Self {
+            readers: Vec::with_capacity(files.len()),
+            heap: BinaryHeap::with_capacity(files.len()),
+            last: None,
+            buf: String::new(),
+        };
+        for path in files {
+            let f = crate::util::open_with_default_backoff(path)
+                .with_context(|| format!("open shard for merging: {}", path.display()))?;
+            merge.readers.push((path.clone(), BufReader::new(f)));
+        }
+        for idx in 0..merge.readers.len() {
+            merge.refill(idx)?;
+        }
+        Ok(merge)
+    }
+
+    /// Push the next non-empty line of shard `idx`, if any, onto the heap.
+    fn refill(&mut self, idx: usize) -> Result<()> {
+        let (path, reader) = &mut self.readers[idx];
+        loop {
+            let n = read_line_capped(reader, &mut self.buf, DEFAULT_MAX_LINE_BYTES, path)
+                .with_context(|| format!("read shard for merging: {}", path.display()))?;
+            if n == 0 {
+                return Ok(());
+            }
+            if !self.buf.is_empty() {
+                self.heap.push(Reverse((self.buf.clone(), idx)));
+                return Ok(());
+            }
+        }
+    }
+
+    pub(crate) fn next_name(&mut self) -> Result<Option<String>> {
+        while let Some(Reverse((name, idx))) = self.heap.pop() {
+            self.refill(idx)?;
+            match self.last.as_deref().map(|last| name.as_str().cmp(last)) {
+                Some(Ordering::Equal) => continue,
+                Some(Ordering::Less) => anyhow::bail!(
+                    "username shard {} is not sorted: {name:?} follows {:?}",
+                    self.readers[idx].0.display(),
+                    self.last.as_deref().unwrap_or_default()
+                ),
+                _ => {}
+            }
+            self.last = Some(name.clone());
+            return Ok(Some(name));
+        }
+        Ok(None)
+    }
+}
+
+/// Merge-join of two ascending sources.
+struct SetOp {
+    kind: SetOpKind,
+    left: SortedSource,
+    right: SortedSource,
+    /// Current `(left, right)` heads. The outer `None` marks a side whose
+    /// head was consumed and must be pulled before the next comparison, so
+    /// a side is only advanced when its next name is actually needed and a
+    /// merge error surfaces on the call after the last good name.
+    heads: (Option<Option<String>>, Option<Option<String>>),
+}
+
+impl SetOp {
+    fn next_name(&mut self) -> Result<Option<String>> {
+        loop {
+            if self.heads.0.is_none() {
+                self.heads.0 = Some(self.left.next_name()?);
+            }
+            if self.heads.1.is_none() {
+                self.heads.1 = Some(self.right.next_name()?);
+            }
+            let l = self.heads.0.as_ref().and_then(Option::as_ref);
+            let r = self.heads.1.as_ref().and_then(Option::as_ref);
+            let ord = match (l, r) {
+                (None, None) => return Ok(None),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(a), Some(b)) => a.cmp(b),
+            };
+            match (self.kind, ord) {
+                (SetOpKind::Intersection, _) if l.is_none() || r.is_none() => return Ok(None),
+                (SetOpKind::Difference, _) if l.is_none() => return Ok(None),
+                (_, Ordering::Less) => {
+                    let name = self.heads.0.take().flatten();
+                    if self.kind != SetOpKind::Intersection {
+                        return Ok(name);
+                    }
+                }
+                (_, Ordering::Greater) => {
+                    let name = self.heads.1.take().flatten();
+                    if self.kind == SetOpKind::Union {
+                        return Ok(name);
+                    }
+                }
+                (_, Ordering::Equal) => {
+                    self.heads.1 = None;
+                    let name = self.heads.0.take().flatten();
+                    if self.kind != SetOpKind::Difference {
+                        return Ok(name);
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl Drop for UsernameStream {
     fn drop(&mut self) {
         self.cleanup_scratch();
@@ -331,4 +568,90 @@ mod tests {
         }
         assert_eq!(errors, MAX_READ_RETRIES_PER_FILE);
     }
+
+    fn set_op_inputs(dir: &Path) -> (UsernameStream, UsernameStream) {
+        let a = dir.join("a");
+        let b = dir.join("b");
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("shard_0000.txt"), "alice\ncarol\n").unwrap();
+        fs::write(a.join("shard_0001.txt"), "bob\ndave\n").unwrap();
+        fs::write(b.join("shard_0000.txt"), "bob\nerin\n").unwrap();
+        fs::write(b.join("shard_0001.txt"), "\ncarol\r\n").unwrap();
+        let files = |d: &Path| vec![d.join("shard_0000.txt"), d.join("shard_0001.txt")];
+        (
+            UsernameStream::from_deduped_files(files(&a)).unwrap(),
+            UsernameStream::from_deduped_files(files(&b)).unwrap(),
+        )
+    }
+
+    #[test]
+    fn set_operations_merge_shards_in_sorted_order() {
+        let dir = tempdir().unwrap();
+
+        let (a, b) = set_op_inputs(dir.path());
+        let got: Vec<String> = a.union(b).unwrap().collect();
+        assert_eq!(got, vec!["alice", "bob", "carol", "dave", "erin"]);
+
+        let (a, b) = set_op_inputs(dir.path());
+        let got: Vec<String> = a.intersection(b).unwrap().collect();
+        assert_eq!(got, vec!["bob", "carol"]);
+
+        let (a, b) = set_op_inputs(dir.path());
+        let got: Vec<String> = a.difference(b).unwrap().collect();
+        assert_eq!(got, vec!["alice", "dave"]);
+
+        let (a, b) = set_op_inputs(dir.path());
+        let got: Vec<String> = b.difference(a).unwrap().collect();
+        assert_eq!(got, vec!["erin"]);
+    }
+
+    #[test]
+    fn set_operations_chain() {
+        let dir = tempdir().unwrap();
+        let (a, b) = set_op_inputs(dir.path());
+        let c_path = dir.path().join("c.txt");
+        fs::write(&c_path, "carol\nerin\nzoe\n").unwrap();
+        let c = UsernameStream::from_deduped_files(vec![c_path]).unwrap();
+
+        // (A ∪ B) ∩ C
+        let got: Vec<String> = a.union(b).unwrap().intersection(c).unwrap().collect();
+        assert_eq!(got, vec!["carol", "erin"]);
+    }
+
+    #[test]
+    fn set_operations_report_unsorted_shards() {
+        let dir = tempdir().unwrap();
+        let a_path = dir.path().join("a.txt");
+        let b_path = dir.path().join("b.txt");
+        fs::write(&a_path, "mallory\nalice\n").unwrap();
+        fs::write(&b_path, "").unwrap();
+        let a = UsernameStream::from_deduped_files(vec![a_path]).unwrap();
+        let b = UsernameStream::from_deduped_files(vec![b_path]).unwrap();
+
+        let mut merged = a.union(b).unwrap();
+        assert_eq!(merged.try_next().unwrap().unwrap(), "mallory");
+        let err = merged.try_next().unwrap().unwrap_err();
+        assert!(format!("{err:#}").contains("not sorted"), "{err:#}");
+        assert!(merged.try_next().is_none());
+    }
+
+    #[test]
+    fn count_remaining_and_collect_sorted_drain_the_stream() {
+        let dir = tempdir().unwrap();
+        let p1 = dir.path().join("shard_0000.txt");
+        let p2 = dir.path().join("shard_0001.txt");
+        fs::write(&p1, "dave\nalice\n").unwrap();
+        fs::write(&p2, "carol\n\nbob\n").unwrap();
+
+        let mut stream = UsernameStream::from_deduped_files(vec![p1.clone(), p2.clone()]).unwrap();
+        assert_eq!(stream.next().as_deref(), Some("dave"));
+        assert_eq!(stream.count_remaining(), 3);
+
+        let stream = UsernameStream::from_deduped_files(vec![p2, p1]).unwrap();
+        assert_eq!(
+            stream.collect_sorted(),
+            vec!["alice", "bob", "carol", "dave"]
+        );
+    }
 }
@@ -4,66 +4,86 @@ use std::fs::File;
 use std::io;
 use std::path::Path;
 
-use super::retry::{
-    with_backoff, DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES, SHORT_BACKOFF_TRIES,
-};
+use super::long_path::long_path;
+use super::retry::{run_io_retries, short_io_retries, with_backoff};
 #[cfg(test)]
 use super::testing::{maybe_inject_retriable_io_error_for_tests, TestIoOp};
 
 /// Open a file with retries/backoff for transient errors.
 /// Prefer [`open_with_default_backoff`] unless a caller needs a custom budget.
 pub fn open_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> io::Result<File> {
+    let os_path = long_path(path);
     with_backoff(tries, delay_ms, || {
         #[cfg(test)]
         maybe_inject_retriable_io_error_for_tests(TestIoOp::Open, path)?;
-        File::open(path)
+        File::open(&os_path)
     })
 }
 
 pub fn open_with_default_backoff(path: &Path) -> io::Result<File> {
-    open_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    open_with_backoff(path, tries, delay_ms)
 }
 
 /// Create or truncate a file with retries/backoff for transient errors.
 /// Prefer [`create_with_default_backoff`] unless a caller needs a custom budget.
 pub fn create_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> io::Result<File> {
+    let os_path = long_path(path);
     with_backoff(tries, delay_ms, || {
         #[cfg(test)]
         maybe_inject_retriable_io_error_for_tests(TestIoOp::Create, path)?;
-        File::create(path)
+        File::create(&os_path)
     })
 }
 
 pub fn create_with_default_backoff(path: &Path) -> io::Result<File> {
-    create_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    create_with_backoff(path, tries, delay_ms)
+}
+
+/// Open a file for appending, creating it if missing, with retries/backoff
+/// for transient errors (including `EMFILE` while other writers close).
+pub(crate) fn open_append_with_default_backoff(path: &Path) -> io::Result<File> {
+    let os_path = long_path(path);
+    let (tries, delay_ms) = run_io_retries();
+    with_backoff(tries, delay_ms, || {
+        fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&os_path)
+    })
 }
 
 /// Create a single directory with retries/backoff for transient errors.
 /// Prefer [`create_dir_with_default_backoff`] unless a caller needs a custom budget.
 pub(crate) fn create_dir_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> io::Result<()> {
+    let os_path = long_path(path);
     with_backoff(tries, delay_ms, || {
         #[cfg(test)]
         maybe_inject_retriable_io_error_for_tests(TestIoOp::CreateDir, path)?;
-        fs::create_dir(path)
+        fs::create_dir(&os_path)
     })
 }
 
 pub(crate) fn create_dir_with_default_backoff(path: &Path) -> io::Result<()> {
-    create_dir_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    create_dir_with_backoff(path, tries, delay_ms)
 }
 
 /// Recursively create directories with retries/backoff for transient errors.
 /// Prefer [`create_dir_all_with_default_backoff`] unless a caller needs a custom budget.
 pub fn create_dir_all_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> io::Result<()> {
+    let os_path = long_path(path);
     with_backoff(tries, delay_ms, || {
         #[cfg(test)]
         maybe_inject_retriable_io_error_for_tests(TestIoOp::CreateDirAll, path)?;
-        fs::create_dir_all(path)
+        fs::create_dir_all(&os_path)
     })
 }
 
 pub fn create_dir_all_with_default_backoff(path: &Path) -> io::Result<()> {
-    create_dir_all_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    create_dir_all_with_backoff(path, tries, delay_ms)
 }
 
 /// Read a directory with retries/backoff for transient errors.
@@ -75,15 +95,17 @@ pub fn read_dir_with_backoff(
     tries: usize,
     delay_ms: u64,
 ) -> io::Result<Vec<fs::DirEntry>> {
+    let os_path = long_path(path);
     with_backoff(tries, delay_ms, || {
         #[cfg(test)]
         maybe_inject_retriable_io_error_for_tests(TestIoOp::ReadDir, path)?;
-        fs::read_dir(path)?.collect()
+        fs::read_dir(&os_path)?.collect()
     })
 }
 
 pub fn read_dir_with_default_backoff(path: &Path) -> io::Result<Vec<fs::DirEntry>> {
-    read_dir_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    read_dir_with_backoff(path, tries, delay_ms)
 }
 
 /// Create a brand-new file with retries/backoff for transient errors.
@@ -93,23 +115,26 @@ pub fn read_dir_with_default_backoff(path: &Path) -> io::Result<Vec<fs::DirEntry
 /// existing staged file.
 /// Prefer [`create_new_with_default_backoff`] unless a caller needs a custom budget.
 pub fn create_new_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> io::Result<File> {
+    let os_path = long_path(path);
     with_backoff(tries, delay_ms, || {
         fs::OpenOptions::new()
             .write(true)
             .create_new(true)
-            .open(path)
+            .open(&os_path)
     })
 }
 
 pub fn create_new_with_default_backoff(path: &Path) -> io::Result<File> {
-    create_new_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    create_new_with_backoff(path, tries, delay_ms)
 }
 
 /// Remove a file with retries/backoff for transient errors.
 /// Succeeds if the file doesn't exist. Prefer [`remove_with_default_backoff`]
 /// or [`remove_with_short_backoff`] unless a caller needs a custom budget.
 pub fn remove_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> Result<()> {
-    with_backoff(tries, delay_ms, || match fs::remove_file(path) {
+    let os_path = long_path(path);
+    with_backoff(tries, delay_ms, || match fs::remove_file(&os_path) {
         Ok(()) => Ok(()),
         Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
         Err(e) => Err(e),
@@ -118,11 +143,13 @@ pub fn remove_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> Result<(
 }
 
 pub fn remove_with_default_backoff(path: &Path) -> Result<()> {
-    remove_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    remove_with_backoff(path, tries, delay_ms)
 }
 
 pub fn remove_with_short_backoff(path: &Path) -> Result<()> {
-    remove_with_backoff(path, SHORT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = short_io_retries();
+    remove_with_backoff(path, tries, delay_ms)
 }
 
 /// Recursively remove a scratch directory with retries/backoff.
@@ -130,7 +157,8 @@ pub fn remove_with_short_backoff(path: &Path) -> Result<()> {
 /// [`remove_dir_all_with_default_backoff`] or [`remove_dir_all_with_short_backoff`]
 /// unless a caller needs a custom budget.
 pub fn remove_dir_all_with_backoff(path: &Path, tries: usize, delay_ms: u64) -> Result<()> {
-    with_backoff(tries, delay_ms, || match fs::remove_dir_all(path) {
+    let os_path = long_path(path);
+    with_backoff(tries, delay_ms, || match fs::remove_dir_all(&os_path) {
         Ok(()) => Ok(()),
         Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
         Err(e) => Err(e),
@@ -139,9 +167,11 @@ pub fn remove_dir_all_with_backoff(path: &Path, tries: usize, delay_ms: u64) ->
 }
 
 pub fn remove_dir_all_with_default_backoff(path: &Path) -> Result<()> {
-    remove_dir_all_with_backoff(path, DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = run_io_retries();
+    remove_dir_all_with_backoff(path, tries, delay_ms)
 }
 
 pub fn remove_dir_all_with_short_backoff(path: &Path) -> Result<()> {
-    remove_dir_all_with_backoff(path, SHORT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)
+    let (tries, delay_ms) = short_io_retries();
+    remove_dir_all_with_backoff(path, tries, delay_ms)
 }
@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Longest plain Win32 path that every file API accepts. `CreateDirectoryW`
+/// reserves room for an 8.3 file name, so its limit is `MAX_PATH - 12` = 248
+/// rather than 260; staying under that keeps short paths untouched.
+#[cfg(windows)]
+const WIN_PLAIN_PATH_LIMIT: usize = 248;
+
+/// Rewrite `path` so Windows file APIs accept it past the 260-char `MAX_PATH`
+/// limit.
+///
+/// Long paths are made absolute, normalized (`.`/`..` resolved, `/` turned
+/// into `\`, which verbatim paths no longer do for us) and given the `\\?\`
+/// prefix; UNC paths `\\server\share\...` become `\\?\UNC\server\share\...`.
+/// Short paths, already-verbatim paths, and device paths are returned as-is.
+/// A no-op on other platforms.
+#[cfg(windows)]
+pub(crate) fn long_path(path: &Path) -> Cow<'_, Path> {
+    use std::ffi::{OsStr, OsString};
+    use std::path::{Component, PathBuf, Prefix};
+
+    let joined;
+    let abs = if path.is_absolute() {
+        path
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) => {
+                joined = cwd.join(path);
+                joined.as_path()
+            }
+            Err(_) => return Cow::Borrowed(path),
+        }
+    };
+    if abs.as_os_str().len() < WIN_PLAIN_PATH_LIMIT {
+        return Cow::Borrowed(path);
+    }
+
+    let mut out = OsString::new();
+    let mut parts: Vec<&OsStr> = Vec::new();
+    for component in abs.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::Disk(drive) => {
+                    out.push(r"\\?\");
+                    out.push(format!("{}:", drive as char));
+                }
+                Prefix::UNC(server, share) => {
+                    out.push(r"\\?\UNC\");
+                    out.push(server);
+                    out.push(r"\");
+                    out.push(share);
+                }
+                Prefix::Verbatim(_)
+                | Prefix::VerbatimUNC(..)
+                | Prefix::VerbatimDisk(_)
+                | Prefix::DeviceNS(_) => return Cow::Borrowed(path),
+            },
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop();
+            }
+            Component::Normal(part) => parts.push(part),
+        }
+    }
+    if out.is_empty() {
+        return Cow::Borrowed(path);
+    }
+    for part in parts {
+        out.push(r"\");
+        out.push(part);
+    }
+    Cow::Owned(PathBuf::from(out))
+}
+
+#[cfg(not(windows))]
+pub(crate) fn long_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
@@ -2,9 +2,11 @@
 //!
 //! This module keeps the `crate::util::*` surface stable while isolating the
 //! retry classifier/budget (`retry`), filesystem wrappers (`fs_ops`), atomic
-//! replace helper (`replace`), and unit-test failure injection (`testing`).
+//! replace helper (`replace`), Windows long-path rewriting (`long_path`), and
+//! unit-test failure injection (`testing`).
 
 mod fs_ops;
+mod long_path;
 mod replace;
 mod retry;
 #[cfg(test)]
@@ -18,12 +20,18 @@ pub use fs_ops::{
     remove_dir_all_with_default_backoff, remove_dir_all_with_short_backoff, remove_with_backoff,
     remove_with_default_backoff, remove_with_short_backoff,
 };
-pub(crate) use fs_ops::{create_dir_with_backoff, create_dir_with_default_backoff};
-pub use replace::replace_file_atomic_backoff;
-pub(crate) use replace::ATOMIC_REPLACE_TMP_EXT;
+pub(crate) use fs_ops::{
+    create_dir_with_backoff, create_dir_with_default_backoff, open_append_with_default_backoff,
+};
+pub use replace::{fsync_outputs, replace_file_atomic_backoff, set_fsync_outputs};
+pub(crate) use replace::{run_fsync_outputs, ATOMIC_REPLACE_TMP_EXT};
+pub(crate) use retry::run_io_retries;
 #[cfg(any(test, feature = "test-utils"))]
 pub use retry::{cap_backoff_budget_for_test, TestBackoffBudgetGuard};
-pub use retry::{DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES, SHORT_BACKOFF_TRIES};
+pub use retry::{
+    io_retries, is_retriable_io_error, set_io_retries, DEFAULT_BACKOFF_DELAY_MS,
+    DEFAULT_BACKOFF_TRIES, SHORT_BACKOFF_TRIES,
+};
 #[cfg(test)]
 #[allow(unused_imports)]
 pub(crate) use testing::TestIoFailureGuard;
@@ -66,4 +74,94 @@ mod tests {
 
         assert_eq!(names, vec!["entry.txt"]);
     }
+
+    #[test]
+    fn is_retriable_io_error_classifies_transient_codes() {
+        use std::io;
+
+        #[cfg(windows)]
+        {
+            let sharing = io::Error::from_raw_os_error(super::retry::WIN_ERR_SHARING_VIOLATION);
+            assert!(is_retriable_io_error(&sharing));
+        }
+        assert!(is_retriable_io_error(&io::Error::from(io::ErrorKind::Interrupted)));
+        assert!(is_retriable_io_error(&io::Error::from(io::ErrorKind::WouldBlock)));
+        assert!(!is_retriable_io_error(&io::Error::from(io::ErrorKind::NotFound)));
+        assert!(!is_retriable_io_error(&io::Error::new(io::ErrorKind::Other, "x")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_retriable_io_error_retries_descriptor_exhaustion() {
+        use std::io;
+
+        // EMFILE / ENFILE: too many open files, process-wide / system-wide.
+        assert!(is_retriable_io_error(&io::Error::from_raw_os_error(24)));
+        assert!(is_retriable_io_error(&io::Error::from_raw_os_error(23)));
+        // ENOENT / EEXIST stay fatal.
+        assert!(!is_retriable_io_error(&io::Error::from_raw_os_error(2)));
+        assert!(!is_retriable_io_error(&io::Error::from_raw_os_error(17)));
+    }
+
+    /// Win32 codes are not retried on Unix, where the same numbers mean
+    /// something else: EISDIR (21) and EPIPE (32) fail at once.
+    #[cfg(unix)]
+    #[test]
+    fn is_retriable_io_error_ignores_win32_codes_on_unix() {
+        use std::io;
+
+        assert!(!is_retriable_io_error(&io::Error::from_raw_os_error(21)));
+        assert!(!is_retriable_io_error(&io::Error::from_raw_os_error(32)));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn create_with_backoff_handles_paths_past_max_path() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let mut dir = tmp.path().to_path_buf();
+        for i in 0..12 {
+            dir.push(format!("deliberately_long_segment_{i:02}_xxxxxxxxxxxxxxxx"));
+        }
+        let file = dir.join("leaf.txt");
+        assert!(file.as_os_str().len() > 260, "{}", file.display());
+
+        create_dir_all_with_default_backoff(&dir).expect("create long dir");
+        {
+            use std::io::Write;
+            let mut f = create_with_default_backoff(&file).expect("create long file");
+            f.write_all(b"x").expect("write");
+        }
+        let mut s = String::new();
+        {
+            use std::io::Read;
+            open_with_default_backoff(&file)
+                .expect("open long file")
+                .read_to_string(&mut s)
+                .expect("read");
+        }
+        assert_eq!(s, "x");
+
+        let tmp_file = dir.join("leaf.txt.tmp");
+        drop(create_with_default_backoff(&tmp_file).expect("create tmp"));
+        replace_file_atomic_backoff(&tmp_file, &file).expect("replace long file");
+        remove_dir_all_with_default_backoff(tmp.path()).expect("remove long tree");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn long_path_rewrites_unc_and_leaves_short_paths() {
+        use super::long_path::long_path;
+        use std::path::Path;
+
+        let short = Path::new(r"C:\data\RC_2006-01.zst");
+        assert_eq!(long_path(short).as_ref(), short);
+
+        let segment = "s".repeat(80);
+        let unc = format!(r"\\server\share\{segment}\{segment}\..\{segment}\out.jsonl");
+        let rewritten = long_path(Path::new(&unc));
+        assert_eq!(
+            rewritten.as_os_str().to_string_lossy(),
+            format!(r"\\?\UNC\server\share\{segment}\{segment}\out.jsonl")
+        );
+    }
 }
@@ -2,15 +2,72 @@ use anyhow::{Context, Result};
 use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::fs_ops::remove_with_backoff;
-use super::retry::{with_backoff, DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES};
+use super::long_path::long_path;
+use super::retry::{run_io_retries, with_backoff};
 
 /// Per-process nonce so two concurrent fallbacks never collide on a temp name.
 static FALLBACK_NONCE: AtomicU64 = AtomicU64::new(0);
 
+/// Whether [`replace_file_atomic_backoff`] syncs before publishing.
+static FSYNC_OUTPUTS: AtomicBool = AtomicBool::new(false);
+
+/// Make [`replace_file_atomic_backoff`] `sync_all` the staged file before
+/// the rename and, on Unix, the destination directory after it, so a
+/// published output survives a crash or power loss right after the call
+/// returns. Every atomic output RETL writes is published through it.
+///
+/// Process-wide, so the last setting wins; a run with
+/// [`ETLOptions::with_fsync`](crate::ETLOptions::with_fsync) set uses its
+/// own setting instead. Default `false`: the page cache
+/// flushes on its own schedule and most runs prefer the speed.
+pub fn set_fsync_outputs(yes: bool) {
+    FSYNC_OUTPUTS.store(yes, Ordering::Relaxed);
+}
+
+/// The setting installed by [`set_fsync_outputs`].
+pub fn fsync_outputs() -> bool {
+    FSYNC_OUTPUTS.load(Ordering::Relaxed)
+}
+
+/// The setting in effect on this thread: the executing run's
+/// [`ETLOptions::with_fsync`](crate::ETLOptions::with_fsync) if it set one,
+/// else [`fsync_outputs`].
+pub(crate) fn run_fsync_outputs() -> bool {
+    crate::config::RunSettings::read(|run| run.fsync).unwrap_or_else(fsync_outputs)
+}
+
+/// Flush `path`'s data and metadata to disk. Opened for writing because
+/// Windows' `FlushFileBuffers` needs write access; nothing is truncated.
+fn sync_file(path: &Path) -> Result<()> {
+    fs::OpenOptions::new()
+        .write(true)
+        .open(long_path(path))
+        .and_then(|f| f.sync_all())
+        .with_context(|| format!("sync {}", path.display()))
+}
+
+/// Persist the directory entry a rename just changed. Unix only: Windows
+/// cannot open a directory as a file, and NTFS journals the rename itself.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    fs::File::open(parent)
+        .and_then(|dir| dir.sync_all())
+        .with_context(|| format!("sync directory {}", parent.display()))
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
 /// Extension on the copy+rename fallback's private sibling temp file.
 ///
 /// The fallback in [`replace_file_atomic_backoff`] streams bytes into a
@@ -25,13 +82,15 @@ pub(crate) const ATOMIC_REPLACE_TMP_EXT: &str = ".atomic-replace-tmp";
 
 /// Rename a file with retries/backoff for transient errors.
 fn rename_with_backoff(src: &Path, dest: &Path, tries: usize, delay_ms: u64) -> Result<()> {
-    with_backoff(tries, delay_ms, || fs::rename(src, dest))
+    let (os_src, os_dest) = (long_path(src), long_path(dest));
+    with_backoff(tries, delay_ms, || fs::rename(&os_src, &os_dest))
         .with_context(|| format!("rename {} -> {}", src.display(), dest.display()))
 }
 
 /// Copy a file with retries/backoff for transient errors.
 fn copy_with_backoff(src: &Path, dest: &Path, tries: usize, delay_ms: u64) -> Result<()> {
-    with_backoff(tries, delay_ms, || fs::copy(src, dest))
+    let (os_src, os_dest) = (long_path(src), long_path(dest));
+    with_backoff(tries, delay_ms, || fs::copy(&os_src, &os_dest))
         .map(|_| ())
         .with_context(|| format!("copy {} -> {}", src.display(), dest.display()))
 }
@@ -93,16 +152,40 @@ fn unique_sibling_tmp(dest: &Path) -> Result<PathBuf> {
 /// reader may briefly observe a zero-length or partially written `dest`. This
 /// last-resort path is therefore **not** atomic, and emits a `tracing::warn!`
 /// recording that atomicity was lost.
+///
+/// ## Long and UNC paths
+///
+/// Both paths go through the same `\\?\` long-path rewrite as the other
+/// backoff helpers, so deep `work_dir` trees and `\\server\share` outputs
+/// publish past `MAX_PATH`. A rename from a local `tmp` onto a share fails
+/// fast with `ERROR_NOT_SAME_DEVICE` and lands in the copy+rename fallback,
+/// whose sibling temp lives on the share itself; transient SMB drops
+/// (`ERROR_UNEXP_NET_ERR`, `ERROR_NETNAME_DELETED`) are retried.
+///
+/// ## Durability
+///
+/// With [`set_fsync_outputs`] (or the executing run's
+/// [`ETLOptions::with_fsync`](crate::ETLOptions::with_fsync)) on, the bytes
+/// being published are synced before each rename and `dest`'s directory
+/// after it; otherwise a returned `Ok` only means the rename reached the
+/// page cache.
 pub fn replace_file_atomic_backoff(tmp: &Path, dest: &Path) -> Result<()> {
     // The atomic publish step is the most critical I/O path in the toolkit;
     // it shares the crate-wide backoff budget so it can never silently
     // diverge from the other `*_with_backoff` call sites, and a future tune
-    // of `DEFAULT_BACKOFF_TRIES` reaches it for free.
-    let tries = DEFAULT_BACKOFF_TRIES;
-    let delay_ms = DEFAULT_BACKOFF_DELAY_MS;
+    // of `DEFAULT_BACKOFF_TRIES` or a `set_io_retries` override reaches it
+    // for free.
+    let (tries, delay_ms) = run_io_retries();
+    let fsync = run_fsync_outputs();
+    if fsync {
+        sync_file(tmp)?;
+    }
 
     // Primary path: a single atomic MoveFileExW(REPLACE_EXISTING).
     if rename_with_backoff(tmp, dest, tries, delay_ms).is_ok() {
+        if fsync {
+            sync_parent_dir(dest)?;
+        }
         return Ok(());
     }
 
@@ -110,8 +193,14 @@ pub fn replace_file_atomic_backoff(tmp: &Path, dest: &Path) -> Result<()> {
     // The rename keeps the swap atomic; the copy never touches `dest`.
     let staged = unique_sibling_tmp(dest)?;
     copy_with_backoff(tmp, &staged, tries, delay_ms)?;
+    if fsync {
+        sync_file(&staged)?;
+    }
     match rename_with_backoff(&staged, dest, tries, delay_ms) {
         Ok(_) => {
+            if fsync {
+                sync_parent_dir(dest)?;
+            }
             remove_with_backoff(tmp, tries, delay_ms)?;
             Ok(())
         }
@@ -135,6 +224,9 @@ pub fn replace_file_atomic_backoff(tmp: &Path, dest: &Path) -> Result<()> {
             // no longer live (it is no longer leaked permanently).
             let _ = remove_with_backoff(&staged, tries, delay_ms);
             copy_with_backoff(tmp, dest, tries, delay_ms)?;
+            if fsync {
+                sync_file(dest)?;
+            }
             remove_with_backoff(tmp, tries, delay_ms)?;
             Ok(())
         }
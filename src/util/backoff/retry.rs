@@ -1,12 +1,14 @@
 use std::io;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::thread::sleep;
 use std::time::Duration;
 
 /// Default retry count for Windows-friendly file operations.
 ///
 /// These retry budgets cover transient filter-driver, antivirus, sharing, USB,
-/// and removable-volume failures matched by the retry classifier (Win32 error
-/// codes 5, 21, 32, 33, 225, 433, 1006, 1117, and 1224). Keep the values stable
+/// removable-volume, and SMB-share failures matched by the retry classifier
+/// (Win32 error codes 5, 21, 32, 33, 59, 64, 225, 433, 1006, 1117, and 1224),
+/// plus POSIX `EAGAIN`, `EINTR`, `EMFILE`, and `ENFILE`. Keep the values stable
 /// unless all atomic-write/backoff call sites are intentionally retuned.
 pub const DEFAULT_BACKOFF_TRIES: usize = 16;
 
@@ -16,35 +18,117 @@ pub const SHORT_BACKOFF_TRIES: usize = 8;
 /// Default linear backoff delay, in milliseconds, between retry attempts.
 pub const DEFAULT_BACKOFF_DELAY_MS: u64 = 50;
 
+/// Process-wide budget behind the `*_with_default_backoff` and
+/// `*_with_short_backoff` wrappers; see [`set_io_retries`].
+static IO_RETRY_TRIES: AtomicUsize = AtomicUsize::new(DEFAULT_BACKOFF_TRIES);
+static IO_RETRY_DELAY_MS: AtomicU64 = AtomicU64::new(DEFAULT_BACKOFF_DELAY_MS);
+
+/// Set the retry budget used by every `*_with_default_backoff` helper (and
+/// by [`replace_file_atomic_backoff`](crate::replace_file_atomic_backoff)):
+/// up to `tries` attempts, sleeping `base_delay_ms * attempt` between them.
+/// The `*_with_short_backoff` cleanup helpers use half as many tries.
+///
+/// Process-wide, so the last setting wins; raise it for a flaky NAS, or set
+/// `tries = 1` to fail fast in CI. A run with a non-default
+/// [`ETLOptions::with_io_retries`](crate::ETLOptions::with_io_retries)
+/// uses its own budget instead and leaves this one alone. Defaults to
+/// `(DEFAULT_BACKOFF_TRIES, DEFAULT_BACKOFF_DELAY_MS)` = `(16, 50)`. A
+/// `tries` of `0` is treated as one attempt. Explicit-budget callers
+/// (`open_with_backoff(path, tries, delay)`) are unaffected.
+pub fn set_io_retries(tries: usize, base_delay_ms: u64) {
+    IO_RETRY_TRIES.store(tries.max(1), Ordering::Relaxed);
+    IO_RETRY_DELAY_MS.store(base_delay_ms, Ordering::Relaxed);
+}
+
+/// The `(tries, base_delay_ms)` budget set by [`set_io_retries`].
+pub fn io_retries() -> (usize, u64) {
+    (
+        IO_RETRY_TRIES.load(Ordering::Relaxed),
+        IO_RETRY_DELAY_MS.load(Ordering::Relaxed),
+    )
+}
+
+/// The budget in effect on this thread: the executing run's
+/// [`ETLOptions::with_io_retries`](crate::ETLOptions::with_io_retries) if it
+/// set one, else [`io_retries`].
+pub(crate) fn run_io_retries() -> (usize, u64) {
+    crate::config::RunSettings::read(|run| run.io_retries).unwrap_or_else(io_retries)
+}
+
+/// Budget for best-effort cleanup: half the configured tries, at least one.
+pub(super) fn short_io_retries() -> (usize, u64) {
+    let (tries, delay_ms) = run_io_retries();
+    ((tries / 2).max(1), delay_ms)
+}
+
 // Named Windows error codes commonly seen on filter-driver / sharing /
 // removable-volume hiccups. Used by `is_retriable_io_error` below; named so
 // the retry classification reads as documentation rather than a wall of magic
-// integers. Values match the Win32 `ERROR_*` constants of the same name.
+// integers. Values match the Win32 `ERROR_*` constants of the same name. They
+// are Windows-only: the same numbers are unrelated errno values elsewhere
+// (32 is `EPIPE`, 21 `EISDIR`).
+#[cfg(windows)]
 const WIN_ERR_ACCESS_DENIED: i32 = 5; // ERROR_ACCESS_DENIED — often AV/share
+#[cfg(windows)]
 const WIN_ERR_NOT_READY: i32 = 21; // ERROR_NOT_READY — device not ready
+#[cfg(windows)]
 pub(super) const WIN_ERR_SHARING_VIOLATION: i32 = 32; // ERROR_SHARING_VIOLATION
+#[cfg(windows)]
 const WIN_ERR_LOCK_VIOLATION: i32 = 33; // ERROR_LOCK_VIOLATION
+#[cfg(windows)]
+const WIN_ERR_UNEXP_NET_ERR: i32 = 59; // ERROR_UNEXP_NET_ERR — SMB hiccup
+#[cfg(windows)]
+const WIN_ERR_NETNAME_DELETED: i32 = 64; // ERROR_NETNAME_DELETED — share dropped
+#[cfg(windows)]
 const WIN_ERR_VIRUS_INFECTED: i32 = 225; // ERROR_VIRUS_INFECTED — AV/PUA blocked
+#[cfg(windows)]
 const WIN_ERR_NO_SUCH_DEVICE: i32 = 433; // ERROR_NO_SUCH_DEVICE
+#[cfg(windows)]
 const WIN_ERR_FILE_INVALID: i32 = 1006; // ERROR_FILE_INVALID — volume changed
+#[cfg(windows)]
 const WIN_ERR_IO_DEVICE: i32 = 1117; // ERROR_IO_DEVICE
+#[cfg(windows)]
 const WIN_ERR_USER_MAPPED_FILE: i32 = 1224; // ERROR_USER_MAPPED_FILE
 
-/// Return true for transient/retriable I/O errors often seen on Windows when
-/// filter drivers (AV/backup), USB/NAS volumes, or sharing violations occur.
-fn is_retriable_io_error(e: &io::Error) -> bool {
-    matches!(
+// POSIX descriptor exhaustion, hit when high `file_concurrency` runs many
+// decoders and writers at once. The values are the same on Linux, macOS and
+// the BSDs; `EAGAIN`/`EINTR` differ, so they are matched by `ErrorKind`.
+#[cfg(unix)]
+const POSIX_ENFILE: i32 = 23; // system-wide file table full
+#[cfg(unix)]
+pub(super) const POSIX_EMFILE: i32 = 24; // per-process descriptor limit
+
+/// Return true for transient I/O errors worth retrying: on Windows the Win32
+/// codes often seen when filter drivers (AV/backup), USB/NAS volumes, SMB
+/// shares, or sharing violations interfere, and on Unix `EAGAIN`, `EINTR`,
+/// `EMFILE` and `ENFILE`. The `*_with_backoff` helpers retry exactly these; custom file
+/// loops can use it to match their behavior.
+pub fn is_retriable_io_error(e: &io::Error) -> bool {
+    if matches!(e.kind(), io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock) {
+        return true;
+    }
+    #[cfg(unix)]
+    if matches!(e.raw_os_error(), Some(POSIX_EMFILE) | Some(POSIX_ENFILE)) {
+        return true;
+    }
+    #[cfg(windows)]
+    if matches!(
         e.raw_os_error(),
         Some(WIN_ERR_ACCESS_DENIED)
             | Some(WIN_ERR_NOT_READY)
             | Some(WIN_ERR_SHARING_VIOLATION)
             | Some(WIN_ERR_LOCK_VIOLATION)
+            | Some(WIN_ERR_UNEXP_NET_ERR)
+            | Some(WIN_ERR_NETNAME_DELETED)
             | Some(WIN_ERR_VIRUS_INFECTED)
             | Some(WIN_ERR_NO_SUCH_DEVICE)
             | Some(WIN_ERR_FILE_INVALID)
             | Some(WIN_ERR_IO_DEVICE)
             | Some(WIN_ERR_USER_MAPPED_FILE)
-    )
+    ) {
+        return true;
+    }
+    false
 }
 
 /// Generic retry-with-backoff loop used by the `*_with_backoff` family.
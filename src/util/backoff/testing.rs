@@ -2,7 +2,11 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-use super::retry::WIN_ERR_SHARING_VIOLATION;
+/// A transient error `is_retriable_io_error` retries on this platform.
+#[cfg(windows)]
+const INJECTED_RAW_OS_ERROR: i32 = super::retry::WIN_ERR_SHARING_VIOLATION;
+#[cfg(unix)]
+const INJECTED_RAW_OS_ERROR: i32 = super::retry::POSIX_EMFILE;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum TestIoOp {
@@ -91,7 +95,7 @@ fn inject_retriable_io_errors_for_match_tests(
             op,
             path_match,
             remaining: failures,
-            raw_os_error: WIN_ERR_SHARING_VIOLATION,
+            raw_os_error: INJECTED_RAW_OS_ERROR,
         });
     guard
 }
@@ -0,0 +1,88 @@
+//! Extension-driven decompression for JSONL intermediates.
+
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Compression of a JSONL intermediate, chosen by its final extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InputCompression {
+    Plain,
+    Gzip,
+    Zstd,
+}
+
+impl InputCompression {
+    pub(crate) fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("gz") => Self::Gzip,
+            Some(ext) if ext.eq_ignore_ascii_case("zst") => Self::Zstd,
+            _ => Self::Plain,
+        }
+    }
+}
+
+/// Open `path` for line reading, decompressing `.gz` (gzip, including
+/// concatenated members) and `.zst` (zstd, honoring RETL's dictionary
+/// frames) transparently; any other extension is read as plain text.
+/// `buf_bytes` sizes the outer line buffer.
+pub(crate) fn open_maybe_compressed(
+    path: &Path,
+    buf_bytes: usize,
+) -> io::Result<Box<dyn BufRead + Send>> {
+    let f = super::open_with_default_backoff(path)?;
+    Ok(match InputCompression::of(path) {
+        InputCompression::Plain => Box::new(BufReader::with_capacity(buf_bytes, f)),
+        InputCompression::Gzip => Box::new(BufReader::with_capacity(
+            buf_bytes,
+            flate2::read::MultiGzDecoder::new(BufReader::new(f)),
+        )),
+        InputCompression::Zstd => Box::new(BufReader::with_capacity(
+            buf_bytes,
+            crate::zstd_jsonl::open_zstd_decoder(f, path).map_err(io::Error::other)?,
+        )),
+    })
+}
+
+/// `name` without a trailing `.gz` / `.zst`, e.g. `part_RC_2006-01.jsonl`
+/// for `part_RC_2006-01.jsonl.gz`: the name a stage writes its plain JSONL
+/// output under.
+pub(crate) fn strip_compression_suffix(name: &str) -> &str {
+    match InputCompression::of(Path::new(name)) {
+        InputCompression::Plain => name,
+        _ => name.rsplit_once('.').map_or(name, |(stem, _)| stem),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn reads_plain_gzip_and_zstd_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = "{\"id\":\"a\"}\n{\"id\":\"b\"}\n";
+
+        let plain = dir.path().join("x.jsonl");
+        std::fs::write(&plain, body).unwrap();
+        let gz = dir.path().join("x.jsonl.gz");
+        let mut enc =
+            flate2::write::GzEncoder::new(std::fs::File::create(&gz).unwrap(), Default::default());
+        enc.write_all(body.as_bytes()).unwrap();
+        enc.finish().unwrap();
+        let zst = dir.path().join("x.jsonl.zst");
+        std::fs::write(&zst, zstd::encode_all(body.as_bytes(), 3).unwrap()).unwrap();
+
+        for path in [&plain, &gz, &zst] {
+            let mut out = String::new();
+            open_maybe_compressed(path, 8 * 1024)
+                .unwrap()
+                .read_to_string(&mut out)
+                .unwrap();
+            assert_eq!(out, body, "{}", path.display());
+        }
+        assert_eq!(strip_compression_suffix("x.jsonl.gz"), "x.jsonl");
+        assert_eq!(strip_compression_suffix("x.jsonl.zst"), "x.jsonl");
+        assert_eq!(strip_compression_suffix("x.jsonl"), "x.jsonl");
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::backoff::open_with_default_backoff;
+use super::output_parent;
+use crate::atomic_write::{ensure_staging_dir, write_zst_atomic_if};
+use crate::config::DEFAULT_WRITE_BUFFER_BYTES;
+use crate::ndjson::read_line_capped;
+
+/// Concatenate plain JSONL `inputs`, in order, into one checksummed `.zst`
+/// file at `out_path` and return the number of lines written.
+///
+/// Each input is streamed line by line into a single zstd encoder, so memory
+/// stays at one line plus the encoder window however large the inputs are.
+/// Every line is written with a `\n` terminator (an input whose last line has
+/// none still joins cleanly) and blank lines are dropped. Lines longer than
+/// the corpus cap ([`max_line_bytes`](crate::max_line_bytes)) fail the run.
+///
+/// The output is staged and atomically renamed like the crate's other `.zst`
+/// writers, so a failed run never leaves a truncated archive at `out_path`
+/// and `out_path` may safely be one of the inputs. `level` is clamped to
+/// zstd's `1..=22`.
+pub fn concat_jsonl_to_zst(inputs: &[PathBuf], out_path: &Path, level: i32) -> Result<u64> {
+    let staging_dir = ensure_staging_dir(output_parent(out_path))?;
+    let max_line_bytes = crate::zstd_jsonl::max_line_bytes();
+    write_zst_atomic_if(
+        &staging_dir,
+        out_path,
+        level.clamp(1, 22),
+        None,
+        DEFAULT_WRITE_BUFFER_BYTES,
+        |_| true,
+        |w| {
+            let mut lines: u64 = 0;
+            let mut line = String::new();
+            for input in inputs {
+                let file = open_with_default_backoff(input)
+                    .with_context(|| format!("open concat input {}", input.display()))?;
+                let mut reader = BufReader::new(file);
+                loop {
+                    let n = read_line_capped(&mut reader, &mut line, max_line_bytes, input)
+                        .with_context(|| format!("read concat input {}", input.display()))?;
+                    if n == 0 {
+                        break;
+                    }
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    w.write_all(line.as_bytes())?;
+                    w.write_all(b"\n")?;
+                    lines += 1;
+                }
+            }
+            Ok(lines)
+        },
+    )
+    .with_context(|| format!("concatenate JSONL into {}", out_path.display()))
+}
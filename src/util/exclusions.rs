@@ -1,6 +1,6 @@
 use super::backoff::open_with_default_backoff;
 use crate::ndjson::{read_line_capped, DEFAULT_MAX_LINE_BYTES};
-use crate::query::{normalize_str, QueryBuildError};
+use crate::query::{normalize_author, QueryBuildError};
 
 /// Returns a normalized (lowercase) default list of bot/service authors to exclude.
 /// This is a conservative set focused on high-volume/systemic accounts.
@@ -21,12 +21,23 @@ pub fn default_bot_authors() -> Vec<String> {
         "slackbot",
         "discordbot",
     ];
-    let mut v: Vec<String> = defaults.iter().map(|s| normalize_str(s)).collect();
+    let mut v: Vec<String> = defaults.iter().map(|s| normalize_author(s)).collect();
     v.sort();
     v.dedup();
     v
 }
 
+/// Regexes for boilerplate footers that bots append to their comments and
+/// posts, used by [`ScanPlan::exclude_bot_bodies`](crate::ScanPlan::exclude_bot_bodies).
+/// Kept conservative: each pattern is a phrase people rarely type themselves.
+pub fn default_bot_body_patterns() -> Vec<&'static str> {
+    vec![
+        r"(?i)I am a bot,? and this action was performed automatically",
+        r"(?i)\^\(?I(?:'m| am) a bot\b",
+        r"(?i)\bbeep,? boop[.!,]*\s+I(?:'m| am) a bot\b",
+    ]
+}
+
 /// Merge extra exclusions from env/file into the provided vector (in-place).
 /// - ETL_EXCLUDE_AUTHORS: comma/semicolon/space separated names
 /// - ETL_EXCLUDE_AUTHORS_FILE: path to newline-separated file of names
@@ -45,7 +56,7 @@ pub(crate) fn try_merge_extra_exclusions(
 
     if let Ok(s) = std::env::var("ETL_EXCLUDE_AUTHORS") {
         for raw in s.split(|c: char| c == ',' || c == ';' || c.is_whitespace()) {
-            let n = normalize_str(raw);
+            let n = normalize_author(raw);
             if !n.is_empty() {
                 extras.push(n);
             }
@@ -69,7 +80,7 @@ pub(crate) fn try_merge_extra_exclusions(
                 match read_line_capped(&mut r, &mut line, DEFAULT_MAX_LINE_BYTES, &path) {
                     Ok(0) => break,
                     Ok(_) => {
-                        let n = normalize_str(&line);
+                        let n = normalize_author(&line);
                         if !n.is_empty() {
                             extras.push(n);
                         }
@@ -89,7 +100,7 @@ pub(crate) fn try_merge_extra_exclusions(
 
     // normalize + sort + dedup
     for s in target.iter_mut() {
-        *s = normalize_str(s);
+        *s = normalize_author(s);
     }
     target.sort();
     target.dedup();
@@ -6,13 +6,21 @@
 //! Submodule map:
 //! - [`backoff`] — Windows-friendly retry policy, `*_with_backoff` I/O
 //!   wrappers, and `replace_file_atomic_backoff`.
-//! - [`exclusions`] — default bot author list and env/file merging.
+//! - [`compressed`] — `open_maybe_compressed`, reading `.gz`/`.zst`/plain
+//!   JSONL intermediates by extension.
+//! - [`concat`] — `concat_jsonl_to_zst`, streaming JSONL files into one
+//!   atomically published `.zst`.
+//! - [`exclusions`] — default bot author list and body patterns, and env/file
+//!   merging.
 //! - [`scratch`] — process-unique scratch directory naming and the
 //!   `ScratchGuard` RAII cleanup guard.
 //! - [`thread_pool`] — `with_thread_pool` scoped Rayon helper.
-//! - [`tracing`] — binary-only tracing subscriber init.
+//! - [`tracing`] — binary-only tracing subscriber init and the per-file
+//!   `FileTrace` debug span.
 
 mod backoff;
+mod compressed;
+mod concat;
 mod exclusions;
 mod scratch;
 mod thread_pool;
@@ -21,11 +29,12 @@ mod tracing;
 pub use backoff::{
     create_dir_all_with_backoff, create_dir_all_with_default_backoff, create_new_with_backoff,
     create_new_with_default_backoff, create_with_backoff, create_with_default_backoff,
-    open_with_backoff, open_with_default_backoff, read_dir_with_backoff,
-    read_dir_with_default_backoff, remove_dir_all_with_backoff,
+    fsync_outputs, io_retries, is_retriable_io_error, open_with_backoff, open_with_default_backoff,
+    read_dir_with_backoff, read_dir_with_default_backoff, remove_dir_all_with_backoff,
     remove_dir_all_with_default_backoff, remove_dir_all_with_short_backoff, remove_with_backoff,
     remove_with_default_backoff, remove_with_short_backoff, replace_file_atomic_backoff,
-    DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES, SHORT_BACKOFF_TRIES,
+    set_fsync_outputs, set_io_retries, DEFAULT_BACKOFF_DELAY_MS, DEFAULT_BACKOFF_TRIES,
+    SHORT_BACKOFF_TRIES,
 };
 // `create_dir_with_backoff` has no current caller, but the original `util.rs`
 // exposed it as `pub(crate)` alongside its `_with_default_backoff` wrapper;
@@ -33,6 +42,9 @@ pub use backoff::{
 #[allow(unused_imports)]
 pub(crate) use backoff::create_dir_with_backoff;
 pub(crate) use backoff::create_dir_with_default_backoff;
+pub(crate) use backoff::open_append_with_default_backoff;
+// The backoff budget and fsync switch as the executing run overrides them.
+pub(crate) use backoff::{run_fsync_outputs, run_io_retries};
 // Suffix on `replace_file_atomic_backoff`'s copy+rename fallback siblings;
 // `atomic_write`'s stale sweep matches it to reclaim orphaned leftovers.
 pub(crate) use backoff::ATOMIC_REPLACE_TMP_EXT;
@@ -49,7 +61,10 @@ pub(crate) use backoff::{
     inject_retriable_io_errors_for_file_name_tests, inject_retriable_io_errors_for_tests, TestIoOp,
 };
 
-pub use exclusions::default_bot_authors;
+pub(crate) use compressed::{open_maybe_compressed, strip_compression_suffix};
+pub use concat::concat_jsonl_to_zst;
+
+pub use exclusions::{default_bot_authors, default_bot_body_patterns};
 pub(crate) use exclusions::try_merge_extra_exclusions;
 
 pub(crate) use scratch::{unique_scratch_dir, ScratchGuard};
@@ -57,6 +72,7 @@ pub(crate) use scratch::{unique_scratch_dir, ScratchGuard};
 pub use thread_pool::with_thread_pool;
 
 pub use tracing::init_tracing_for_binary;
+pub(crate) use tracing::FileTrace;
 
 // -------- Grab-bag helpers with no clearer home --------
 
@@ -83,6 +99,20 @@ pub(crate) fn stable_fnv1a_hex(bytes: &[u8]) -> String {
     format!("fnv1a64:{hash:016x}")
 }
 
+/// Platform-stable hash of `label` then `bytes` for threshold sampling
+/// (`hash < fraction * u64::MAX`). FNV-1a keeps it stable across releases; the
+/// splitmix64 finalizer spreads FNV's weak high bits so the comparison
+/// samples evenly. `label` keys independent samplers; an empty one hashes
+/// `bytes` alone.
+pub(crate) fn seeded_fraction_hash(label: &[u8], bytes: &[u8]) -> u64 {
+    let mut hash = FNV1A_OFFSET_BASIS;
+    fnv1a_update(&mut hash, label);
+    fnv1a_update(&mut hash, bytes);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
 pub(crate) fn system_time_parts(t: SystemTime) -> (i64, u32) {
     match t.duration_since(UNIX_EPOCH) {
         Ok(d) => (
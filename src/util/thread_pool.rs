@@ -1,10 +1,12 @@
-use crate::config::clamp_parallelism_threads;
+use crate::config::{clamp_parallelism_threads, RunSettings};
 
 /// Run `f` inside a scoped Rayon thread pool sized to `n` threads. If `n` is
-/// `None` (or `Some(0)`), run on the global default pool. Positive values are
-/// clamped through [`crate::config::max_parallelism_limit`]; if Rayon still
-/// rejects the pool, RETL logs a warning and safely falls back to the global
-/// pool instead of panicking.
+/// `None` (or `Some(0)`), run on the global default pool, unless the calling
+/// thread is executing a run that overrides IO settings: its workers then get
+/// a pool of their own, of Rayon's default size, that carries them. Positive
+/// values are clamped through [`crate::config::max_parallelism_limit`]; if
+/// Rayon still rejects the pool, RETL logs a warning and safely falls back to
+/// the global pool instead of panicking.
 ///
 /// The fallback does **not** honor the requested thread count: the global pool
 /// is sized by whatever the first builder / `RAYON_NUM_THREADS` / CPU-count
@@ -23,31 +25,51 @@ where
     F: FnOnce() -> R + Send,
     R: Send,
 {
-    match n {
-        Some(k) if k > 0 => {
-            let clamped = clamp_parallelism_threads(k, "with_thread_pool");
-            match rayon::ThreadPoolBuilder::new().num_threads(clamped).build() {
-                Ok(pool) => pool.install(f),
-                Err(e) => {
-                    // The fallback runs on the global pool, which is *not*
-                    // sized to `clamped`. Surface its actual thread count so
-                    // the operator sees the requested limit was not honored
-                    // rather than silently over-subscribing the machine.
+    // Workers read the IO settings of the run that built their pool, so a
+    // run with overrides gets its own pool even when `n` leaves the size to
+    // Rayon; the global pool's threads are shared with other runs.
+    let run_settings = RunSettings::current();
+    let requested = n.filter(|&k| k > 0);
+    if requested.is_none() && run_settings.is_none() {
+        return f();
+    }
+    let threads = match requested {
+        Some(k) => clamp_parallelism_threads(k, "with_thread_pool"),
+        None => rayon::current_num_threads(),
+    };
+    let mut builder = rayon::ThreadPoolBuilder::new().num_threads(threads);
+    if let Some(settings) = run_settings {
+        builder = builder.start_handler(move |_| RunSettings::adopt(settings.clone()));
+    }
+    match builder.build() {
+        Ok(pool) => pool.install(f),
+        Err(e) => {
+            match requested {
+                // The fallback runs on the global pool, which is *not* sized
+                // to `threads`. Surface its actual thread count so the
+                // operator sees the requested limit was not honored rather
+                // than silently over-subscribing the machine.
+                Some(k) => {
                     let effective_threads = rayon::current_num_threads();
                     tracing::warn!(
                         requested = k,
-                        clamped,
+                        clamped = threads,
                         effective_threads,
                         error = %e,
                         "failed to build scoped Rayon thread pool; falling back to the \
-                         global pool — the requested parallelism ({clamped}) was NOT \
+                         global pool — the requested parallelism ({threads}) was NOT \
                          applied and this run will use {effective_threads} threads, \
                          which may over-subscribe a memory-tight host"
                     );
-                    f()
                 }
+                None => tracing::warn!(
+                    error = %e,
+                    "failed to build scoped Rayon thread pool; falling back to the \
+                     global pool, whose workers use the process-wide IO settings \
+                     instead of this run's"
+                ),
             }
+            f()
         }
-        _ => f(),
     }
 }
@@ -16,3 +16,47 @@ pub fn init_tracing_for_binary() {
             .try_init();
     });
 }
+
+/// Debug-level `file` span around one monthly file, closed by a `file done`
+/// event carrying the file's record count and elapsed time. Enable with
+/// `RUST_LOG=retl=debug` for a per-file timing breakdown.
+///
+/// When debug is filtered out the span is disabled at creation, so the file
+/// size is never stat'ed and no clock is read.
+pub(crate) struct FileTrace {
+    span: tracing::Span,
+    start: Option<std::time::Instant>,
+}
+
+impl FileTrace {
+    pub(crate) fn start(path: &std::path::Path) -> Self {
+        let span = tracing::debug_span!(
+            "file",
+            path = %path.display(),
+            size = tracing::field::Empty
+        );
+        if span.is_disabled() {
+            return Self { span, start: None };
+        }
+        if let Ok(meta) = std::fs::metadata(path) {
+            span.record("size", meta.len());
+        }
+        Self {
+            span,
+            start: Some(std::time::Instant::now()),
+        }
+    }
+
+    /// Run `f` inside the span, so events it emits carry the file's fields.
+    pub(crate) fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.span.in_scope(f)
+    }
+
+    pub(crate) fn finish(self, records: u64) {
+        if let Some(start) = self.start {
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            self.span
+                .in_scope(|| tracing::debug!(records, elapsed_ms, "file done"));
+        }
+    }
+}
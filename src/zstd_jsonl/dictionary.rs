@@ -0,0 +1,295 @@
+use anyhow::{bail, Context};
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Default maximum size of a dictionary produced by [`train_zstd_dictionary`].
+///
+/// 112 KiB matches the `zstd --train` CLI default; larger dictionaries rarely
+/// help line-shaped Reddit records and cost that much extra memory per open
+/// encoder/decoder.
+pub const DEFAULT_ZSTD_DICTIONARY_BYTES: usize = 112 * 1024;
+
+/// Total sample bytes fed to the dictionary trainer. zstd recommends roughly
+/// 100x the target dictionary size; more mostly slows training down.
+const TRAIN_SAMPLE_BUDGET_BYTES: usize = 100 * DEFAULT_ZSTD_DICTIONARY_BYTES;
+
+/// Records longer than this are skipped as training samples. The trainer
+/// learns from repeated short substrings; one huge selftext would eat the
+/// budget without teaching it anything the smaller records do not.
+const MAX_TRAIN_SAMPLE_BYTES: usize = 64 * 1024;
+
+/// `BufWriter` capacity for the trained dictionary; it is written in one go.
+const DICTIONARY_WRITE_BUF_BYTES: usize = 64 * 1024;
+
+/// Dictionary consulted by every zstd reader for frames that carry a
+/// dictionary id; see [`set_zstd_dictionary`].
+static ACTIVE_ZSTD_DICTIONARY: RwLock<Option<ZstdDictionary>> = RwLock::new(None);
+
+/// A trained zstd dictionary loaded from disk.
+///
+/// Cheap to clone (the bytes are shared). Only trained dictionaries are
+/// accepted: their non-zero dictionary id is recorded in every frame written
+/// with them, which is how readers decide whether a file needs the dictionary
+/// at all. Raw-content dictionaries carry no id and are rejected by
+/// [`ZstdDictionary::load`].
+#[derive(Clone)]
+pub struct ZstdDictionary {
+    path: PathBuf,
+    id: u32,
+    bytes: Arc<[u8]>,
+}
+
+impl ZstdDictionary {
+    /// Read and validate the dictionary at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = crate::util::open_with_default_backoff(path)
+            .with_context(|| format!("open zstd dictionary {}", path.display()))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .with_context(|| format!("read zstd dictionary {}", path.display()))?;
+        Self::from_bytes(path, bytes)
+    }
+
+    fn from_bytes(path: &Path, bytes: Vec<u8>) -> Result<Self> {
+        let Some(id) = zstd::zstd_safe::get_dict_id_from_dict(&bytes) else {
+            bail!(
+                "{} is not a trained zstd dictionary (no dictionary id); \
+                 build one with train_zstd_dictionary or `zstd --train`",
+                path.display()
+            );
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            id: id.get(),
+            bytes: bytes.into(),
+        })
+    }
+
+    /// File the dictionary was loaded from (or written to, when trained).
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Dictionary id recorded in the header of every frame compressed with it.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Raw dictionary bytes, as passed to zstd.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl std::fmt::Debug for ZstdDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZstdDictionary")
+            .field("path", &self.path)
+            .field("id", &self.id)
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+impl PartialEq for ZstdDictionary {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.bytes == other.bytes
+    }
+}
+
+impl Eq for ZstdDictionary {}
+
+/// Install the dictionary used to read dictionary-compressed zstd inputs, or
+/// clear it with `None`.
+///
+/// Process-wide, so the last setting wins. A run with
+/// [`ETLOptions::with_zstd_dictionary`](crate::ETLOptions::with_zstd_dictionary)
+/// set reads with its own dictionary instead. Readers only apply it to
+/// frames whose header names its dictionary id, so plain inputs decode
+/// exactly as before.
+pub fn set_zstd_dictionary(dictionary: Option<ZstdDictionary>) {
+    *ACTIVE_ZSTD_DICTIONARY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = dictionary;
+}
+
+/// The dictionary installed by [`set_zstd_dictionary`], if any.
+pub fn zstd_dictionary() -> Option<ZstdDictionary> {
+    ACTIVE_ZSTD_DICTIONARY
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// The dictionary in effect on this thread: the executing run's
+/// [`ETLOptions::with_zstd_dictionary`](crate::ETLOptions::with_zstd_dictionary)
+/// if it loaded one, else [`zstd_dictionary`].
+pub(crate) fn run_zstd_dictionary() -> Option<ZstdDictionary> {
+    crate::config::RunSettings::read(|run| run.zstd_dictionary.clone()).or_else(zstd_dictionary)
+}
+
+/// Sentinel returned from the sampling callback once a file's share of the
+/// training budget is used up; stops the decode early without an error.
+#[derive(Debug)]
+struct SampleBudgetReached;
+
+impl std::fmt::Display for SampleBudgetReached {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("zstd dictionary sample budget reached")
+    }
+}
+
+impl std::error::Error for SampleBudgetReached {}
+
+/// Train a zstd dictionary from records sampled out of `samples` and write it
+/// to `out_path`.
+///
+/// Each sample file is a monthly `.zst` dump (anything else is read as plain
+/// JSONL). Every record is one training sample; the byte budget is split
+/// evenly across files so many tiny months contribute alongside a few large
+/// ones, and each file is only decoded until its share is used. The
+/// dictionary is at most [`DEFAULT_ZSTD_DICTIONARY_BYTES`] and is written
+/// atomically. Fails if the samples are too few or too small for zstd to
+/// train on.
+pub fn train_zstd_dictionary<P: AsRef<Path>>(
+    samples: &[P],
+    out_path: impl AsRef<Path>,
+) -> Result<ZstdDictionary> {
+    let out_path = out_path.as_ref();
+    if samples.is_empty() {
+        bail!("train_zstd_dictionary needs at least one sample file");
+    }
+    let per_file_budget = (TRAIN_SAMPLE_BUDGET_BYTES / samples.len()).max(1);
+    let mut data = Vec::new();
+    let mut sizes = Vec::new();
+    for sample in samples {
+        let path = sample.as_ref();
+        let start = data.len();
+        let mut take = |line: &str| -> Result<()> {
+            if line.is_empty() || line.len() > MAX_TRAIN_SAMPLE_BYTES {
+                return Ok(());
+            }
+            data.extend_from_slice(line.as_bytes());
+            sizes.push(line.len());
+            if data.len() - start >= per_file_budget {
+                return Err(anyhow::Error::new(SampleBudgetReached));
+            }
+            Ok(())
+        };
+        let read = if path.extension().is_some_and(|ext| ext == "zst") {
+            for_each_line_with_opts(path, LineStreamOpts::default(), &mut take)
+        } else {
+            read_plain_sample_lines(path, &mut take)
+        };
+        match read {
+            Ok(()) => {}
+            Err(e) if e.is::<SampleBudgetReached>() => {}
+            Err(e) => {
+                return Err(e.context(format!(
+                    "sample zstd dictionary records from {}",
+                    path.display()
+                )))
+            }
+        }
+    }
+    if sizes.is_empty() {
+        bail!(
+            "train_zstd_dictionary found no records in {} sample file(s)",
+            samples.len()
+        );
+    }
+
+    let bytes = zstd::dict::from_continuous(&data, &sizes, DEFAULT_ZSTD_DICTIONARY_BYTES)
+        .with_context(|| {
+            format!(
+                "train zstd dictionary from {} records ({} bytes); \
+                 zstd needs more or larger samples",
+                sizes.len(),
+                data.len()
+            )
+        })?;
+    crate::atomic_write::write_at_path_atomic(out_path, DICTIONARY_WRITE_BUF_BYTES, |w| {
+        w.write_all(&bytes)?;
+        Ok(())
+    })
+    .with_context(|| format!("write zstd dictionary {}", out_path.display()))?;
+    ZstdDictionary::from_bytes(out_path, bytes)
+}
+
+fn read_plain_sample_lines(
+    path: &Path,
+    on_line: &mut impl FnMut(&str) -> Result<()>,
+) -> Result<()> {
+    let file = crate::util::open_with_default_backoff(path)
+        .with_context(|| format!("open sample {}", path.display()))?;
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("read sample {}", path.display()))?;
+        on_line(line.trim_end_matches('\r'))?;
+    }
+    Ok(())
+}
+
+/// Input buffer capacity zstd recommends for streaming decompression; the
+/// same size `Decoder::new` would pick internally.
+fn zstd_decode_in_bytes() -> usize {
+    zstd::zstd_safe::DCtx::in_size()
+}
+
+/// Dictionary needed to decode the frame starting at `head`, if it names one.
+///
+/// Frames without a dictionary id decode with no dictionary, even when one is
+/// installed: zstd would otherwise seed them with the dictionary's history
+/// and repeat offsets and corrupt the output. A frame whose id does not match
+/// the installed dictionary is a configuration error, not corruption, so it
+/// is reported as such. `dictionary` overrides [`run_zstd_dictionary`].
+fn frame_dictionary(
+    head: &[u8],
+    path: &Path,
+    dictionary: Option<&ZstdDictionary>,
+) -> Result<Option<ZstdDictionary>> {
+    let Some(want) = zstd::zstd_safe::get_dict_id_from_frame(head) else {
+        return Ok(None);
+    };
+    let want = want.get();
+    match dictionary.cloned().or_else(run_zstd_dictionary) {
+        Some(dict) if dict.id() == want => Ok(Some(dict)),
+        Some(dict) => bail!(
+            "{} was compressed with zstd dictionary id {want}, but the loaded dictionary {} has id {}",
+            path.display(),
+            dict.path().display(),
+            dict.id()
+        ),
+        None => bail!(
+            "{} was compressed with zstd dictionary id {want}; load that dictionary with \
+             ETLOptions::with_zstd_dictionary",
+            path.display()
+        ),
+    }
+}
+
+/// Build a decoder over an already-buffered input, using `dictionary` when
+/// the frame needs one, and raise the window limit for large Reddit frames.
+fn new_zstd_decoder<R: BufRead>(
+    input: R,
+    dictionary: Option<&ZstdDictionary>,
+) -> io::Result<Decoder<'static, R>> {
+    let mut decoder = match dictionary {
+        Some(dict) => Decoder::with_dictionary(input, dict.as_bytes())?,
+        None => Decoder::with_buffer(input)?,
+    };
+    decoder.window_log_max(ZSTD_WINDOW_LOG_MAX)?;
+    Ok(decoder)
+}
+
+/// Open a dictionary-aware zstd decoder over `reader` (see
+/// [`frame_dictionary`]). `path` is only used in error messages.
+pub(crate) fn open_zstd_decoder<R: Read>(
+    reader: R,
+    path: &Path,
+) -> Result<Decoder<'static, BufReader<R>>> {
+    let mut input = BufReader::with_capacity(zstd_decode_in_bytes(), reader);
+    let dictionary = frame_dictionary(input.fill_buf()?, path, None)?;
+    Ok(new_zstd_decoder(input, dictionary.as_ref())?)
+}
@@ -37,22 +37,56 @@ pub fn malformed_json_error(
     line_number: u64,
     source: impl std::fmt::Display,
 ) -> anyhow::Error {
-    anyhow!(
-        "malformed JSON in {} at line {}: {}",
-        path.display(),
+    anyhow::Error::new(MalformedJsonError {
+        path: path.to_path_buf(),
         line_number,
-        source
-    )
+        message: source.to_string(),
+    })
 }
 
 pub fn zstd_decode_error(path: &Path, source: anyhow::Error) -> anyhow::Error {
     // Do not interpolate the source message into the context: `source` stays
     // in the error chain, so `{:#}` rendering already appends it once. Adding
     // it here would render the underlying zstd message twice.
-    source.context(format!(
-        "zstd decode error while streaming {}",
-        path.display()
-    ))
+    source.context(ZstdDecodeContext {
+        path: path.to_path_buf(),
+    })
+}
+
+/// Typed form of [`malformed_json_error`] so [`crate::RetlError`] can recover
+/// the path, and the reject sink the parser's message, without parsing the
+/// rendered error.
+#[derive(Debug)]
+pub(crate) struct MalformedJsonError {
+    pub(crate) path: std::path::PathBuf,
+    line_number: u64,
+    pub(crate) message: String,
+}
+
+impl std::fmt::Display for MalformedJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "malformed JSON in {} at line {}: {}",
+            self.path.display(),
+            self.line_number,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for MalformedJsonError {}
+
+/// Context attached by [`zstd_decode_error`].
+#[derive(Debug)]
+pub(crate) struct ZstdDecodeContext {
+    pub(crate) path: std::path::PathBuf,
+}
+
+impl std::fmt::Display for ZstdDecodeContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "zstd decode error while streaming {}", self.path.display())
+    }
 }
 
 // ----------------------------- Streaming ----------------------------------
@@ -30,6 +30,20 @@ pub struct LineStreamOpts<'a> {
     /// the caller is deliberately accepting lossy results and records skipped
     /// paths somewhere machine-readable.
     pub partial_read_policy: PartialReadPolicy,
+    /// Longest decoded line accepted, in bytes. `None` → the executing run's
+    /// [`ETLOptions::with_max_line_bytes`](crate::ETLOptions::with_max_line_bytes),
+    /// else the process-wide [`max_line_bytes`]. A longer line is treated as
+    /// stream corruption and handled by `partial_read_policy`, so a corrupt
+    /// newline-free run cannot grow the line buffer without bound.
+    pub max_line_bytes: Option<usize>,
+    /// `(tries, base_delay_ms)` for opening the file. `None` → the executing
+    /// run's [`ETLOptions::with_io_retries`](crate::ETLOptions::with_io_retries),
+    /// else the process-wide [`io_retries`](crate::io_retries).
+    pub io_retries: Option<(usize, u64)>,
+    /// Dictionary for frames that name one. `None` → the executing run's
+    /// [`ETLOptions::with_zstd_dictionary`](crate::ETLOptions::with_zstd_dictionary),
+    /// else the process-wide [`zstd_dictionary`].
+    pub zstd_dictionary: Option<&'a ZstdDictionary>,
     /// Sample [`maybe_throttle_low_memory`] every
     /// [`THROTTLE_SAMPLE_MASK`]+1 lines. Set `false` for stages that briefly
     /// allocate a lot (e.g., parent-cache builds) where the backoff would
@@ -44,18 +58,57 @@ impl<'a> Default for LineStreamOpts<'a> {
             progress: None,
             on_skip: None,
             partial_read_policy: PartialReadPolicy::Strict,
+            max_line_bytes: None,
+            io_retries: None,
+            zstd_dictionary: None,
             throttle: true,
         }
     }
 }
 
+/// Per-line cap used by corpus line streams that do not set
+/// [`LineStreamOpts::max_line_bytes`]; see [`set_max_line_bytes`].
+static CORPUS_MAX_LINE_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_LINE_BYTES);
+
+/// Set the longest corpus line, in bytes, that zstd line streams accept
+/// (minimum 1).
+///
+/// Process-wide, so the last setting wins. A run whose
+/// [`ETLOptions::with_max_line_bytes`](crate::ETLOptions::with_max_line_bytes)
+/// differs from the default uses its own cap instead. Defaults to
+/// [`DEFAULT_MAX_LINE_BYTES`].
+pub fn set_max_line_bytes(bytes: usize) {
+    CORPUS_MAX_LINE_BYTES.store(bytes.max(1), Ordering::Relaxed);
+}
+
+/// The corpus line cap installed by [`set_max_line_bytes`].
+pub fn max_line_bytes() -> usize {
+    CORPUS_MAX_LINE_BYTES.load(Ordering::Relaxed)
+}
+
+/// The line cap in effect on this thread: the executing run's
+/// [`ETLOptions::with_max_line_bytes`](crate::ETLOptions::with_max_line_bytes)
+/// if it set one, else [`max_line_bytes`].
+pub(crate) fn run_max_line_bytes() -> usize {
+    crate::config::RunSettings::read(|run| run.max_line_bytes).unwrap_or_else(max_line_bytes)
+}
+
+/// What decoding a line stream needs beyond its bytes: the line cap and the
+/// dictionary for frames that name one (`None` → [`run_zstd_dictionary`]).
+#[derive(Clone, Copy)]
+struct LineDecode<'d> {
+    max_line_bytes: usize,
+    dictionary: Option<&'d ZstdDictionary>,
+}
+
 /// Stream a zstd JSONL file line-by-line using `opts`, calling `on_line`
-/// with each raw `&str` (newline already stripped).
+/// with each raw `&str` (newline already stripped). A `.jsonl` path is read
+/// as plain text, so the crate's own JSONL exports can be scanned as a corpus.
 ///
 /// We request `window_log_max(ZSTD_WINDOW_LOG_MAX)` up front to avoid
 /// "Frame requires too much memory" on very large frames. If decoding
-/// still fails (e.g., checksum/corruption), strict mode returns a contextual
-/// error. In [`PartialReadPolicy::AllowPartial`] mode we log a single warning,
+/// still fails (e.g., checksum/corruption, or a "line" longer than the
+/// line cap), strict mode returns a contextual error. In [`PartialReadPolicy::AllowPartial`] mode we log a single warning,
 /// invoke `opts.on_skip` (if set), report only the remaining compressed-byte
 /// progress needed to reach the file's size (if a progress callback is set),
 /// and return `Ok(false)` so callers can keep resume manifests from marking
@@ -70,11 +123,19 @@ pub fn for_each_line_with_opts_status(
         mut progress,
         mut on_skip,
         partial_read_policy,
+        max_line_bytes: line_cap,
+        io_retries,
+        zstd_dictionary,
         throttle,
     } = opts;
     let result = for_each_line_attempt(
         path,
         read_buf_bytes,
+        io_retries.unwrap_or_else(crate::util::run_io_retries),
+        LineDecode {
+            max_line_bytes: line_cap.unwrap_or_else(run_max_line_bytes),
+            dictionary: zstd_dictionary,
+        },
         progress.as_deref_mut(),
         throttle,
         &mut on_line,
@@ -110,6 +171,46 @@ pub fn for_each_line_with_opts_status(
     }
 }
 
+/// Label standing in for a path in errors from [`for_each_line_reader`].
+const READER_LABEL: &str = "<reader>";
+
+/// Stream zstd JSONL from any `Read` (stdin, a socket, an in-memory buffer)
+/// instead of a file path, calling `on_line` with each raw `&str` (newline
+/// already stripped).
+///
+/// Uses the same decoder setup, line cap and dictionary handling as the
+/// path-based readers, which open their file and run this same loop. Decode
+/// errors are strict and name the input `<reader>`.
+pub fn for_each_line_reader<R: Read>(
+    reader: R,
+    read_buf_bytes: usize,
+    mut on_line: impl FnMut(&str) -> Result<()>,
+) -> Result<()> {
+    let label = Path::new(READER_LABEL);
+    let result = for_each_line_read_attempt(
+        reader,
+        label,
+        false,
+        Some(read_buf_bytes),
+        LineDecode {
+            max_line_bytes: run_max_line_bytes(),
+            dictionary: None,
+        },
+        None,
+        true,
+        &mut on_line,
+    );
+    match result {
+        Ok(()) => Ok(()),
+        Err(LineStreamAttemptError::Decode { source, .. }) => Err(zstd_decode_error(label, source)),
+        Err(
+            LineStreamAttemptError::Open(e)
+            | LineStreamAttemptError::InvalidLine(e)
+            | LineStreamAttemptError::Callback(e),
+        ) => Err(e),
+    }
+}
+
 /// Wrapper for callers that do not need the complete/incomplete boolean.
 /// Decode-error behavior is controlled by [`LineStreamOpts::partial_read_policy`]
 /// and is strict by default.
@@ -164,6 +265,60 @@ pub fn for_each_line_with_progress_cfg_no_throttle_status(
     )
 }
 
+thread_local! {
+    /// Line buffer reused across files read on the same thread, so scans over
+    /// thousands of monthly files don't re-allocate it per file.
+    static LINE_BUF: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+#[cfg(test)]
+thread_local! {
+    /// `BufReader` capacity used by the most recent read on this thread.
+    static LAST_READ_BUF_CAP: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+#[cfg(test)]
+fn last_read_buf_cap_for_tests() -> usize {
+    LAST_READ_BUF_CAP.with(|c| c.get())
+}
+
+/// Owns the pooled line buffer for one read and hands it back on drop (on
+/// every exit path, including errors). The buffer is *taken* out of the pool
+/// rather than borrowed, so a nested scan started from inside `on_line` just
+/// gets a fresh buffer instead of a `RefCell` double-borrow panic.
+struct PooledLineBuf {
+    buf: String,
+}
+
+impl PooledLineBuf {
+    fn take() -> Self {
+        let mut buf = LINE_BUF
+            .try_with(|slot| std::mem::take(&mut *slot.borrow_mut()))
+            .unwrap_or_default();
+        if buf.capacity() == 0 {
+            buf.reserve(DEFAULT_READ_BUF_BYTES);
+        }
+        Self { buf }
+    }
+}
+
+impl Drop for PooledLineBuf {
+    fn drop(&mut self) {
+        let mut buf = std::mem::take(&mut self.buf);
+        buf.clear();
+        if buf.capacity() > MAX_RETAINED_LINE_BUF_BYTES {
+            buf.shrink_to(DEFAULT_READ_BUF_BYTES);
+        }
+        // `try_with` fails only during thread teardown; dropping is fine then.
+        let _ = LINE_BUF.try_with(|slot| {
+            let mut slot = slot.borrow_mut();
+            if slot.capacity() < buf.capacity() {
+                *slot = buf;
+            }
+        });
+    }
+}
+
 /// A `Read` wrapper that counts compressed bytes read.
 struct CountingReader<R: Read> {
     inner: R,
@@ -177,6 +332,27 @@ impl<R: Read> Read for CountingReader<R> {
     }
 }
 
+/// Whether `path` names an uncompressed JSONL corpus file (`RC_YYYY-MM.jsonl`,
+/// as written by a JSONL partitioned export) rather than a `.zst` dump.
+pub(crate) fn is_plain_jsonl(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jsonl")
+}
+
+/// The decoded byte stream of one input: zstd frames, or plain JSONL passed
+/// through as-is.
+enum DecodedInput<R: Read> {
+    Zstd(Decoder<'static, BufReader<CountingReader<R>>>),
+    Plain(CountingReader<R>),
+}
+impl<R: Read> Read for DecodedInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            DecodedInput::Zstd(decoder) => decoder.read(buf),
+            DecodedInput::Plain(reader) => reader.read(buf),
+        }
+    }
+}
+
 enum LineStreamAttemptError {
     Open(anyhow::Error),
     Decode {
@@ -186,9 +362,9 @@ enum LineStreamAttemptError {
         /// metadata bytes so progress never exceeds 100%.
         bytes_reported: u64,
     },
-    /// A decoded JSONL line exceeded the configured cap or was not valid UTF-8.
-    /// This is record-level invalid data, not a zstd-frame skip, so it remains
-    /// fatal even when `PartialReadPolicy::AllowPartial` is set.
+    /// A decoded JSONL line was not valid UTF-8. This is record-level invalid
+    /// data, not a zstd-frame skip, so it remains fatal even when
+    /// `PartialReadPolicy::AllowPartial` is set.
     InvalidLine(anyhow::Error),
     Callback(anyhow::Error),
 }
@@ -207,36 +383,78 @@ enum LineStreamAttemptError {
 fn for_each_line_attempt<'borrow, 'cb: 'borrow>(
     path: &Path,
     read_buf_bytes: Option<usize>,
-    mut on_progress: Option<&'borrow mut (dyn FnMut(u64) + 'cb)>,
+    (tries, delay_ms): (usize, u64),
+    decode: LineDecode<'_>,
+    on_progress: Option<&'borrow mut (dyn FnMut(u64) + 'cb)>,
     throttle: bool,
     on_line: &mut impl FnMut(&str) -> Result<()>,
 ) -> std::result::Result<(), LineStreamAttemptError> {
-    let file = crate::util::open_with_default_backoff(path).map_err(|e| {
+    let file = crate::util::open_with_backoff(path, tries, delay_ms).map_err(|e| {
         LineStreamAttemptError::Open(
             anyhow::Error::new(e).context(format!("open zstd input {}", path.display())),
         )
     })?;
+    for_each_line_read_attempt(
+        file,
+        path,
+        is_plain_jsonl(path),
+        read_buf_bytes,
+        decode,
+        on_progress,
+        throttle,
+        on_line,
+    )
+}
+
+/// The decode loop behind every reader: `source` is the compressed stream
+/// (or plain JSONL when `plain` is set) and `path` only labels errors.
+#[allow(clippy::too_many_arguments)]
+fn for_each_line_read_attempt<'borrow, 'cb: 'borrow, R: Read>(
+    source: R,
+    path: &Path,
+    plain: bool,
+    read_buf_bytes: Option<usize>,
+    decode: LineDecode<'_>,
+    mut on_progress: Option<&'borrow mut (dyn FnMut(u64) + 'cb)>,
+    throttle: bool,
+    on_line: &mut impl FnMut(&str) -> Result<()>,
+) -> std::result::Result<(), LineStreamAttemptError> {
     let counter = Arc::new(AtomicU64::new(0));
     let cnt = CountingReader {
-        inner: file,
+        inner: source,
         counter: counter.clone(),
     };
 
-    let mut decoder = Decoder::new(cnt).map_err(|e| LineStreamAttemptError::Decode {
-        source: e.into(),
-        bytes_reported: 0,
-    })?;
-    decoder
-        .window_log_max(ZSTD_WINDOW_LOG_MAX)
-        .map_err(|e| LineStreamAttemptError::Decode {
-            source: e.into(),
-            bytes_reported: 0,
+    let decoded = if plain {
+        DecodedInput::Plain(cnt)
+    } else {
+        let mut input = BufReader::with_capacity(zstd_decode_in_bytes(), cnt);
+        let head = input
+            .fill_buf()
+            .map_err(|e| LineStreamAttemptError::Decode {
+                source: e.into(),
+                bytes_reported: 0,
+            })?;
+        // A missing or mismatched dictionary is a configuration error, not
+        // frame corruption, so it stays fatal even under `AllowPartial`.
+        let dictionary = frame_dictionary(head, path, decode.dictionary)
+            .map_err(LineStreamAttemptError::Open)?;
+        let decoder = new_zstd_decoder(input, dictionary.as_ref()).map_err(|e| {
+            LineStreamAttemptError::Decode {
+                source: e.into(),
+                bytes_reported: 0,
+            }
         })?;
+        DecodedInput::Zstd(decoder)
+    };
 
     let cap = read_buf_bytes.unwrap_or(DEFAULT_READ_BUF_BYTES);
-    let mut reader = BufReader::with_capacity(cap, decoder);
+    let mut reader = BufReader::with_capacity(cap, decoded);
+    #[cfg(test)]
+    LAST_READ_BUF_CAP.with(|c| c.set(reader.capacity()));
 
-    let mut buf = String::with_capacity(DEFAULT_READ_BUF_BYTES);
+    let mut pooled = PooledLineBuf::take();
+    let buf = &mut pooled.buf;
     let mut last: u64 = 0;
     // Sampled cooperative memory backoff: maybe_throttle_low_memory acquires a
     // global Mutex (see src/mem.rs). Now that bucketing/dedupe carry their own
@@ -244,34 +462,50 @@ fn for_each_line_attempt<'borrow, 'cb: 'borrow>(
     // sample every THROTTLE_SAMPLE_MASK+1 lines to keep mutex contention out
     // of the hot read loop.
     let mut tick: u32 = 0;
+    let max_line_bytes = decode.max_line_bytes;
     loop {
-        let n = match read_line_capped(&mut reader, &mut buf, DEFAULT_MAX_LINE_BYTES, path) {
+        let n = match read_line_capped(&mut reader, buf, max_line_bytes, path) {
             Ok(n) => n,
             Err(e) => {
                 // `read_line_capped` marks *both* record-level faults — the
                 // line-length cap and an invalid-UTF-8 line — with an
-                // `InvalidLineError` payload. Classify either as `InvalidLine`
-                // (fatal even under `AllowPartial`) by downcasting the marker
-                // rather than string-matching the message: a UTF-8 error's
-                // message does not mention `max_line_bytes`, so the old
-                // substring check let it fall through to `Decode` and be
+                // `InvalidLineError` payload. Classify by downcasting the
+                // marker rather than string-matching the message: a UTF-8
+                // error's message does not mention `max_line_bytes`, so the
+                // old substring check let it fall through to `Decode` and be
                 // mistaken for a tolerated zstd-frame skip.
-                let is_invalid_line = e
+                let over_cap = e
                     .get_ref()
-                    .map_or(false, |inner| inner.is::<InvalidLineError>());
-                if is_invalid_line {
-                    return Err(LineStreamAttemptError::InvalidLine(
-                        anyhow::Error::new(e).context(format!(
-                            "read zstd JSONL line from {} (max_line_bytes={})",
-                            path.display(),
-                            DEFAULT_MAX_LINE_BYTES
-                        )),
-                    ));
+                    .and_then(|inner| inner.downcast_ref::<InvalidLineError>())
+                    .map(InvalidLineError::is_over_cap);
+                match over_cap {
+                    // A newline-free run past the cap is a corrupt stream, not
+                    // a record: let the partial-read policy decide, exactly
+                    // like a frame decode error.
+                    Some(true) => {
+                        return Err(LineStreamAttemptError::Decode {
+                            source: anyhow::Error::new(e).context(format!(
+                                "read zstd JSONL line from {} (max_line_bytes={}; raise it \
+                                 with ETLOptions::with_max_line_bytes)",
+                                path.display(),
+                                max_line_bytes
+                            )),
+                            bytes_reported: last,
+                        });
+                    }
+                    Some(false) => {
+                        return Err(LineStreamAttemptError::InvalidLine(
+                            anyhow::Error::new(e)
+                                .context(format!("read zstd JSONL line from {}", path.display())),
+                        ));
+                    }
+                    None => {
+                        return Err(LineStreamAttemptError::Decode {
+                            source: e.into(),
+                            bytes_reported: last,
+                        });
+                    }
                 }
-                return Err(LineStreamAttemptError::Decode {
-                    source: e.into(),
-                    bytes_reported: last,
-                });
             }
         };
         if n == 0 {
@@ -294,7 +528,7 @@ fn for_each_line_attempt<'borrow, 'cb: 'borrow>(
                 last = cur;
             }
         }
-        on_line(&buf).map_err(LineStreamAttemptError::Callback)?;
+        on_line(buf.as_str()).map_err(LineStreamAttemptError::Callback)?;
         if throttle {
             tick = tick.wrapping_add(1);
             if tick & THROTTLE_SAMPLE_MASK == 0 {
@@ -305,4 +539,6 @@ fn for_each_line_attempt<'borrow, 'cb: 'borrow>(
     Ok(())
 }
 
-pub use crate::integrity::{quick_validate_zst, validate_zst_full, QuickOutcome};
+pub use crate::integrity::{
+    quick_validate_zst, validate_zst_full, validate_zst_full_stats, QuickOutcome,
+};
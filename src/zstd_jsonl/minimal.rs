@@ -1,10 +1,11 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use serde::Deserialize;
+use std::cell::RefCell;
 use std::fs;
 use std::io::{self, BufReader, Read};
 use std::path::Path;
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
+    atomic::{AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
 use zstd::stream::read::Decoder;
@@ -30,6 +31,12 @@ const THROTTLE_SAMPLE_MASK: u32 = 0xFFFF;
 /// Default `BufReader` capacity when callers do not specify one.
 const DEFAULT_READ_BUF_BYTES: usize = 16 * 1024;
 
+/// Largest line-buffer capacity kept in the per-thread pool between files.
+/// A single pathological line can grow the buffer toward
+/// `DEFAULT_MAX_LINE_BYTES`; shrink it back instead of pinning that much RAM
+/// on every worker thread for the rest of the run.
+const MAX_RETAINED_LINE_BUF_BYTES: usize = 1024 * 1024;
+
 fn de_opt_string_lossy<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -96,6 +103,20 @@ fn integral_f64_to_i64(n: f64) -> Option<i64> {
     (i as f64 == n).then_some(i)
 }
 
+/// `edited` is `false` on never-edited records and an edit timestamp
+/// otherwise (a bare `true` on some old dumps). Only a timestamp is kept;
+/// booleans and other shapes become `None`.
+fn de_opt_edited_lossy<'de, D>(deserializer: D) -> std::result::Result<Option<i64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|v| match v {
+        serde_json::Value::Bool(_) => None,
+        other => coerce_json_i64(&other),
+    }))
+}
+
 fn de_opt_bool_lossy<'de, D>(deserializer: D) -> std::result::Result<Option<bool>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -112,9 +133,10 @@ where
 /// (submissions) and `id` (both kinds). Optional fields are lossy: unexpected
 /// JSON types become `None` instead of making the entire hot-path parse fail,
 /// so schema drift in unused fields does not silently drop otherwise valid
-/// records. The numeric fields `created_utc` and `score` additionally coerce
-/// string- and float-encoded numbers (`"100"`, `100.0`) to `i64` so the fast
-/// path filters them the same way the `--json` slow path does.
+/// records. The numeric fields `created_utc`, `retrieved_on`, `edited` and
+/// `score` additionally coerce string- and float-encoded numbers (`"100"`,
+/// `100.0`) to `i64` so the fast path filters them the same way the `--json`
+/// slow path does; a boolean `edited` reads as `None`.
 #[derive(Debug, Deserialize)]
 pub struct MinimalRecord {
     #[serde(default, deserialize_with = "de_opt_string_lossy")]
@@ -124,6 +146,11 @@ pub struct MinimalRecord {
     #[serde(default, deserialize_with = "de_opt_i64_lossy")]
     pub created_utc: Option<i64>,
     #[serde(default, deserialize_with = "de_opt_i64_lossy")]
+    pub retrieved_on: Option<i64>,
+    /// Edit timestamp; `None` for never-edited records (`"edited": false`).
+    #[serde(default, deserialize_with = "de_opt_edited_lossy")]
+    pub edited: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_lossy")]
     pub score: Option<i64>,
 
     // ID of the record (present on both RC and RS)
@@ -150,4 +177,16 @@ pub struct MinimalRecord {
     pub domain: Option<String>, // submissions (used by domains_in)
 }
 
+impl MinimalRecord {
+    /// The timestamp selected by `field`, or `None` when the record lacks it.
+    #[inline]
+    pub fn timestamp(&self, field: crate::query::TimeField) -> Option<i64> {
+        match field {
+            crate::query::TimeField::Created => self.created_utc,
+            crate::query::TimeField::Edited => self.edited,
+            crate::query::TimeField::RetrievedOn => self.retrieved_on,
+        }
+    }
+}
+
 // ----------------------------- Helpers for full-error logging ------------------------------------
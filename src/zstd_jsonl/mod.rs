@@ -1,4 +1,5 @@
 include!("minimal.rs");
 include!("errors.rs");
+include!("dictionary.rs");
 include!("line_stream.rs");
 include!("tests.rs");
@@ -350,6 +350,61 @@ mod tests {
         );
     }
 
+    /// A corrupt stream that never emits a newline must hit the line cap long
+    /// before it can exhaust memory, and is then handled like a decode error:
+    /// fatal when strict, a skipped (incomplete) file under `AllowPartial`.
+    #[test]
+    fn newline_free_run_past_line_cap_follows_partial_read_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runaway-line.zst");
+        let cap = 64 * 1024;
+
+        let mut payload = Vec::new();
+        payload.extend_from_slice(b"{\"id\":\"r0\"}\n");
+        payload.extend_from_slice(b"{\"id\":\"r1\"}\n");
+        // 64x the cap with no newline anywhere; zstd squeezes it to a few KiB.
+        payload.extend(std::iter::repeat(b'x').take(cap * 64));
+        write_zst_with_checksum(&path, &payload);
+
+        let opts = |policy| LineStreamOpts {
+            read_buf_bytes: Some(16 * 1024),
+            partial_read_policy: policy,
+            max_line_bytes: Some(cap),
+            ..Default::default()
+        };
+
+        let mut seen = Vec::new();
+        let err = for_each_line_with_opts_status(&path, opts(PartialReadPolicy::Strict), |line| {
+            assert!(line.len() <= cap);
+            seen.push(line.to_string());
+            Ok(())
+        })
+        .expect_err("a line past the cap must be fatal in strict mode");
+        let msg = format!("{err:#}");
+        assert!(msg.contains("max_line_bytes=65536"), "{msg}");
+        assert!(msg.contains(&path.display().to_string()), "{msg}");
+        assert_eq!(seen, ["{\"id\":\"r0\"}", "{\"id\":\"r1\"}"]);
+
+        let mut skip_count = 0usize;
+        let mut on_skip = |_: &Path, _: &anyhow::Error| skip_count += 1;
+        let mut lines = 0usize;
+        let complete = for_each_line_with_opts_status(
+            &path,
+            LineStreamOpts {
+                on_skip: Some(&mut on_skip),
+                ..opts(PartialReadPolicy::AllowPartial)
+            },
+            |_line| {
+                lines += 1;
+                Ok(())
+            },
+        )
+        .expect("allow_partial should skip the runaway line like a corrupt frame");
+        assert!(!complete, "the skipped file must be reported incomplete");
+        assert_eq!(skip_count, 1);
+        assert_eq!(lines, 2);
+    }
+
     /// Reddit dumps occasionally store `score` / `created_utc` as JSON strings
     /// or floats. `parse_minimal` must coerce those encodings to `i64` so the
     /// fast-path filters (`matches_minimal`, `within_bounds`) see the same
@@ -388,4 +443,142 @@ mod tests {
         assert_eq!(missing.score, None);
         assert_eq!(missing.created_utc, None);
     }
+
+    /// The configured `read_buffer_bytes` must reach the `BufReader` instead
+    /// of silently falling back to the 16 KiB default, and the per-thread line
+    /// buffer must survive across files (and across a nested scan).
+    #[test]
+    fn configured_read_buffer_is_honored_and_line_buffer_is_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("RC_2020-01.zst");
+        write_zst_with_checksum(&path, b"{\"id\":\"a\"}\n{\"id\":\"b\"}\n");
+
+        for_each_line_cfg(&path, 256 * 1024, |_| Ok(())).unwrap();
+        assert_eq!(last_read_buf_cap_for_tests(), 256 * 1024);
+
+        for_each_line_with_opts(&path, LineStreamOpts::default(), |_| Ok(())).unwrap();
+        assert_eq!(last_read_buf_cap_for_tests(), DEFAULT_READ_BUF_BYTES);
+
+        let pooled_cap = LINE_BUF.with(|b| b.borrow().capacity());
+        assert!(pooled_cap >= DEFAULT_READ_BUF_BYTES, "line buffer returned to pool");
+
+        let mut nested_lines = 0usize;
+        for_each_line_cfg(&path, 64 * 1024, |_| {
+            for_each_line_cfg(&path, 64 * 1024, |_| {
+                nested_lines += 1;
+                Ok(())
+            })
+        })
+        .unwrap();
+        assert_eq!(nested_lines, 4, "nested scans each get their own buffer");
+    }
+
+    /// A small month compressed against a trained dictionary must decode to
+    /// exactly the same lines as the plain frame, once the dictionary is
+    /// installed; without it the reader names the missing dictionary id.
+    #[test]
+    fn dictionary_compressed_month_decodes_like_plain_month() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = |i: usize| {
+            format!(
+                "{{\"id\":\"c{i}\",\"author\":\"user_{}\",\"subreddit\":\"{}\",\"score\":{},\
+                 \"created_utc\":{},\"body\":\"comment number {i} about thread {}\"}}",
+                i % 97,
+                ["askscience", "programming", "rust", "worldnews"][i % 4],
+                i % 50,
+                1_136_073_600 + i,
+                i % 13
+            )
+        };
+        let mut samples = String::new();
+        for i in 0..10_000 {
+            samples.push_str(&record(i));
+            samples.push('\n');
+        }
+        let samples_path = dir.path().join("RC_2006-01.zst");
+        write_zst_with_checksum(&samples_path, samples.as_bytes());
+        let dict_path = dir.path().join("reddit.dict");
+        let dict = train_zstd_dictionary(&[&samples_path], &dict_path).unwrap();
+        assert_eq!(ZstdDictionary::load(&dict_path).unwrap(), dict);
+
+        // Only a handful of records: a bigger month finds enough repeats in
+        // itself that the dictionary stops paying for its frame overhead.
+        let month: Vec<String> = (20_000..20_004).map(record).collect();
+        let staging = crate::atomic_write::ensure_staging_dir(dir.path()).unwrap();
+        let write = |name: &str, dictionary: Option<&ZstdDictionary>| {
+            let dest = dir.path().join(name);
+            crate::atomic_write::write_zst_atomic_if(
+                &staging,
+                &dest,
+                3,
+                dictionary,
+                64 * 1024,
+                |_| true,
+                |w| {
+                    for line in &month {
+                        writeln!(w, "{line}")?;
+                    }
+                    Ok(())
+                },
+            )
+            .unwrap();
+            dest
+        };
+        let plain = write("plain.zst", None);
+        let with_dict = write("with_dict.zst", Some(&dict));
+        assert!(
+            fs::metadata(&with_dict).unwrap().len() < fs::metadata(&plain).unwrap().len(),
+            "dictionary should shrink a small month"
+        );
+
+        let read = |path: &Path| {
+            let mut lines = Vec::new();
+            for_each_line_cfg(path, 16 * 1024, |line| {
+                lines.push(line.to_string());
+                Ok(())
+            })
+            .map(|_| lines)
+        };
+        set_zstd_dictionary(None);
+        let err = read(&with_dict).expect_err("dictionary frame needs the dictionary");
+        assert!(
+            format!("{err:#}").contains(&format!("dictionary id {}", dict.id())),
+            "unexpected error: {err:#}"
+        );
+
+        set_zstd_dictionary(Some(dict));
+        assert_eq!(read(&plain).unwrap(), month);
+        assert_eq!(read(&with_dict).unwrap(), month);
+        validate_zst_full(&with_dict).unwrap();
+        set_zstd_dictionary(None);
+    }
+
+    #[test]
+    fn for_each_line_reader_decodes_in_memory_buffer() {
+        let mut compressed = Vec::new();
+        {
+            let mut enc = zstd::stream::write::Encoder::new(&mut compressed, 3).unwrap();
+            enc.write_all(b"{\"id\":\"a\"}\n\n{\"id\":\"b\"}\n{\"id\":\"c\"}")
+                .unwrap();
+            enc.finish().unwrap();
+        }
+
+        let mut lines = Vec::new();
+        for_each_line_reader(compressed.as_slice(), 16 * 1024, |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            lines,
+            [r#"{"id":"a"}"#, "", r#"{"id":"b"}"#, r#"{"id":"c"}"#]
+        );
+
+        let err = for_each_line_reader(&b"not zstd"[..], 16 * 1024, |_| Ok(()))
+            .expect_err("garbage input must fail strictly");
+        assert!(
+            format!("{err:#}").contains("<reader>"),
+            "unexpected error: {err:#}"
+        );
+    }
 }
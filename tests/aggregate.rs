@@ -2,7 +2,9 @@
 mod common;
 
 use common::*;
-use retl::{AggregatePartialReadPolicy, Aggregator, RedditETL};
+use retl::{
+    AggregatePartialReadPolicy, Aggregator, CountByField, MinMaxField, RedditETL, SumField,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs::{self, File};
@@ -252,6 +254,147 @@ fn aggregate_over_jsonl_inputs() {
     assert_eq!(agg.count, 4);
 }
 
+/// The ready-made single-field aggregators over a spool with two
+/// subreddits: `CountByField("subreddit")` yields the per-subreddit counts,
+/// and `SumField` / `MinMaxField` fold `score`.
+#[test]
+fn common_field_aggregators_over_spool() {
+    let base = tempfile::tempdir().unwrap().keep();
+    let comment = |id: &str, sub: &str, score: i64| {
+        serde_json::json!({
+            "id": id, "author": format!("user_{id}"), "subreddit": sub, "score": score,
+            "body": "hi", "parent_id": "t3_s1", "link_id": "t3_s1", "created_utc": 1136074600,
+        })
+        .to_string()
+    };
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            comment("c1", "programming", 3),
+            comment("c2", "programming", -2),
+            comment("c3", "rust", 10),
+        ],
+    );
+    write_zst_lines(
+        &base.join("submissions").join("RS_2006-01.zst"),
+        &[serde_json::json!({
+            "id": "s1", "author": "bob", "subreddit": "rust", "score": 7,
+            "title": "t", "selftext": "", "created_utc": 1136073600,
+        })
+        .to_string()],
+    );
+    let (parts, n) = RedditETL::new()
+        .base_dir(&base)
+        .progress(false)
+        .scan()
+        .extract_spool_monthly(&base.join("spool"))
+        .unwrap();
+    assert_eq!(n, 4);
+
+    let etl = RedditETL::new().progress(false);
+    let (by_sub, report) = etl
+        .aggregate_jsonls_parallel_collect_with(parts.clone(), &base.join("count_shards"), || {
+            CountByField::new("subreddit")
+        })
+        .unwrap();
+    assert_eq!(report.problem_count(), 0);
+    assert_eq!(by_sub.field, "subreddit");
+    assert_eq!(by_sub.counts.get("programming").copied(), Some(2));
+    assert_eq!(by_sub.counts.get("rust").copied(), Some(2));
+    assert_eq!(by_sub.counts.len(), 2);
+    assert_eq!(by_sub.records_missing, 0);
+
+    let (sum, _) = etl
+        .aggregate_jsonls_parallel_collect_with(parts.clone(), &base.join("sum_shards"), || {
+            SumField::new("score")
+        })
+        .unwrap();
+    assert_eq!(sum.sum, 18.0);
+    assert_eq!(sum.records, 4);
+
+    let (range, _) = etl
+        .aggregate_jsonls_parallel_collect_with(parts, &base.join("minmax_shards"), || {
+            MinMaxField::new("score")
+        })
+        .unwrap();
+    assert_eq!(range.min, Some(-2.0));
+    assert_eq!(range.max, Some(10.0));
+}
+
+/// The fieldless `Default` of a common aggregator is not a usable shard
+/// state: the `Default`-built entry points fail instead of publishing an
+/// empty histogram.
+#[test]
+fn default_built_field_aggregators_fail_loudly() {
+    let base = make_corpus_basic();
+    let (parts, _n) = RedditETL::new()
+        .base_dir(&base)
+        .progress(false)
+        .scan()
+        .extract_spool_monthly(&base.join("spool"))
+        .unwrap();
+    let etl = RedditETL::new().progress(false);
+    let out = base.join("by_sub.json");
+
+    let err = etl
+        .aggregate_jsonls_parallel::<CountByField>(parts.clone(), &base.join("shards"), &out, false)
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("CountByField has no field"),
+        "unexpected error: {err:#}"
+    );
+    assert!(!out.exists(), "no empty aggregate may be published");
+
+    let err = etl
+        .aggregate_jsonls_parallel_collect::<SumField>(parts, &base.join("sum_shards"))
+        .unwrap_err();
+    assert!(err.to_string().contains("SumField has no field"));
+}
+
+/// Gzip-compressed spool parts (`.jsonl.gz`) aggregate to the same result
+/// as the plain parts they were compressed from.
+#[test]
+fn aggregate_over_gzip_spool_matches_plain() {
+    let base = make_corpus_basic();
+    let (parts, _n) = RedditETL::new()
+        .base_dir(&base)
+        .progress(false)
+        .scan()
+        .include_pseudo_users()
+        .extract_spool_monthly(&base.join("spool_plain"))
+        .unwrap();
+    let gz_dir = base.join("spool_gz");
+    fs::create_dir_all(&gz_dir).unwrap();
+    let gz_parts: Vec<PathBuf> = parts
+        .iter()
+        .map(|part| {
+            let name = format!("{}.gz", part.file_name().unwrap().to_string_lossy());
+            let gz = gz_dir.join(name);
+            let mut enc = flate2::write::GzEncoder::new(
+                File::create(&gz).unwrap(),
+                flate2::Compression::default(),
+            );
+            enc.write_all(&fs::read(part).unwrap()).unwrap();
+            enc.finish().unwrap();
+            gz
+        })
+        .collect();
+
+    let etl = RedditETL::new().progress(false);
+    let count = |inputs: Vec<PathBuf>, shards: &str| {
+        let (by_sub, report) = etl
+            .aggregate_jsonls_parallel_collect_with(inputs, &base.join(shards), || {
+                CountByField::new("author")
+            })
+            .unwrap();
+        assert_eq!(report.problem_count(), 0);
+        by_sub.counts
+    };
+    let plain = count(parts, "plain_shards");
+    assert_eq!(plain.values().sum::<u64>(), 5);
+    assert_eq!(count(gz_parts, "gz_shards"), plain);
+}
+
 #[test]
 fn aggregate_same_basename_inputs_get_distinct_shards() {
     let tmp = tempfile::tempdir().unwrap();
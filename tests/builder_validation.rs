@@ -387,3 +387,46 @@ fn include_pseudo_users_keeps_deleted_removed_and_empty_authors() {
         .unwrap();
     assert_eq!(included_counts.get(&YearMonth::new(2006, 1)), Some(&4));
 }
+
+#[test]
+fn one_query_spec_reused_across_date_ranges() {
+    let jan = YearMonth::new(2006, 1);
+    let feb = YearMonth::new(2006, 2);
+    let mar = YearMonth::new(2006, 3);
+    let base = common::make_corpus_multi_month(&[jan, feb, mar]);
+
+    // Built once, independently of any RedditETL: each month has one
+    // `user_*` submission (score 10) and one `user_*` comment (score 2).
+    let query = QuerySpec::new()
+        .subreddit("r/Programming")
+        .author_regex("^user_")
+        .min_score(2);
+    assert_eq!(
+        query.subreddits.as_deref(),
+        Some(&["programming".to_string()][..])
+    );
+
+    let run = |start: YearMonth, end: YearMonth| {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(start), Some(end))
+            .progress(false)
+            .scan()
+            .with_query(query.clone())
+            .count_by_month()
+            .unwrap()
+    };
+
+    let first = run(jan, jan);
+    assert_eq!(first.into_iter().collect::<Vec<_>>(), vec![(jan, 2)]);
+    let rest = run(feb, mar);
+    assert_eq!(
+        rest.into_iter().collect::<Vec<_>>(),
+        vec![(feb, 2), (mar, 2)]
+    );
+
+    // `QuerySpec::new` keeps the scan default of dropping pseudo-users.
+    assert!(query.filter_pseudo_users);
+    assert!(!QuerySpec::default().filter_pseudo_users);
+}
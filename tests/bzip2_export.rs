@@ -0,0 +1,70 @@
+#[path = "common/mod.rs"]
+mod common;
+
+use common::make_corpus_basic;
+use retl::{ExportFormat, RedditETL, Sources, YearMonth};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+fn decompress_bz2_lines(path: &Path) -> Vec<String> {
+    let f = std::fs::File::open(path).unwrap();
+    let dec = bzip2::read::BzDecoder::new(f);
+    BufReader::new(dec)
+        .lines()
+        .map(|l| l.unwrap())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[test]
+fn export_partitioned_bzip2_round_trips_line_counts() {
+    let base = make_corpus_basic();
+    let out_dir = base.join("export_bz2");
+
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .export_partitioned(&out_dir, ExportFormat::Bzip2 { level: 6 })
+        .unwrap();
+
+    let rc = out_dir.join("comments").join("RC_2006-01.bz2");
+    let rs = out_dir.join("submissions").join("RS_2006-01.bz2");
+    assert_eq!(decompress_bz2_lines(&rc).len(), 3);
+    assert_eq!(decompress_bz2_lines(&rs).len(), 2);
+}
+
+#[test]
+fn export_partitioned_bzip2_skips_zero_record_months() {
+    let base = make_corpus_basic();
+    let out_dir = base.join("export_bz2_empty");
+
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .author("nobody_matches_this")
+        .export_partitioned(&out_dir, ExportFormat::Bzip2 { level: 9 })
+        .unwrap();
+
+    assert!(!out_dir.join("comments").join("RC_2006-01.bz2").exists());
+    assert!(!out_dir.join("submissions").join("RS_2006-01.bz2").exists());
+}
+
+#[test]
+fn export_partitioned_bzip2_rejects_out_of_range_level() {
+    let base = make_corpus_basic();
+    let err = RedditETL::new()
+        .base_dir(&base)
+        .progress(false)
+        .scan()
+        .export_partitioned(&base.join("bad_level"), ExportFormat::Bzip2 { level: 0 })
+        .unwrap_err();
+    assert!(err.to_string().contains("bzip2 export level"), "{err}");
+}
@@ -39,6 +39,7 @@ pub fn empty_parent_maps() -> ParentMaps {
     ParentMaps {
         comments: HashMap::new(),
         submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
         comment_shards: Some(HashMap::new()),
         submission_shards: Some(HashMap::new()),
         payload_spec: Default::default(),
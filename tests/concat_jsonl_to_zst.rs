@@ -0,0 +1,40 @@
+//! `concat_jsonl_to_zst` streams several JSONL outputs into one `.zst`
+//! archive, in input order.
+
+use retl::concat_jsonl_to_zst;
+use std::fs;
+
+#[test]
+fn concat_jsonl_to_zst_keeps_every_line_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("part_a.jsonl");
+    let second = dir.path().join("part_b.jsonl");
+    fs::write(&first, "{\"id\":\"a1\"}\n{\"id\":\"a2\"}\n").unwrap();
+    // No trailing newline on the last line, plus a stray blank line.
+    fs::write(&second, "{\"id\":\"b1\"}\n\n{\"id\":\"b2\"}").unwrap();
+
+    let out = dir.path().join("merged").join("all.jsonl.zst");
+    fs::create_dir_all(out.parent().unwrap()).unwrap();
+    let written = concat_jsonl_to_zst(&[first, second], &out, 3).unwrap();
+    assert_eq!(written, 4);
+
+    retl::validate_zst_full(&out).unwrap();
+    let decoded = zstd::decode_all(fs::File::open(&out).unwrap()).unwrap();
+    assert_eq!(
+        String::from_utf8(decoded).unwrap(),
+        "{\"id\":\"a1\"}\n{\"id\":\"a2\"}\n{\"id\":\"b1\"}\n{\"id\":\"b2\"}\n"
+    );
+}
+
+#[test]
+fn concat_jsonl_to_zst_missing_input_publishes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let present = dir.path().join("present.jsonl");
+    fs::write(&present, "{\"id\":\"x\"}\n").unwrap();
+    let out = dir.path().join("all.zst");
+
+    let err = concat_jsonl_to_zst(&[present, dir.path().join("missing.jsonl")], &out, 3)
+        .expect_err("a missing input must fail the concat");
+    assert!(format!("{err:#}").contains("missing.jsonl"), "{err:#}");
+    assert!(!out.exists());
+}
@@ -3,6 +3,8 @@
 //!   expected unique-key set.
 //! - `for_each_file_limited`: drive at meaningful concurrency (>1) and verify the
 //!   callback fires once per file, in arbitrary order, with no lost work.
+//! - Open-file budget: a tiny budget serializes the fan-out, and a CLI scan
+//!   under a low `ulimit -n` still completes.
 
 #[path = "common/mod.rs"]
 mod common;
@@ -10,7 +12,7 @@ mod common;
 use common::*;
 
 use retl::{
-    for_each_file_limited, FileJob, FileKind, ShardedWriter, YearMonth,
+    for_each_file_limited, set_open_file_budget, FileJob, FileKind, ShardedWriter, YearMonth,
 };
 
 use std::collections::BTreeSet;
@@ -151,3 +153,57 @@ fn for_each_file_limited_with_limit_one_is_serial() {
     // Serial path preserves input order.
     assert_eq!(order.into_inner().unwrap(), vec![1, 2, 3]);
 }
+
+#[test]
+fn for_each_file_limited_respects_open_file_budget() {
+    let jobs: Vec<YearMonth> = (1..=6).map(|m| YearMonth::new(2006, m as u8)).collect();
+
+    // A one-descriptor budget leaves room for a single in-flight file.
+    set_open_file_budget(Some(1));
+    let in_flight = AtomicUsize::new(0);
+    let peak = AtomicUsize::new(0);
+    let res = for_each_file_limited(&jobs, 8, |_| {
+        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(())
+    });
+    set_open_file_budget(None);
+
+    res.unwrap();
+    assert_eq!(peak.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_completes_under_a_low_soft_fd_limit() {
+    let months: Vec<YearMonth> = (1..=12).map(|m| YearMonth::new(2006, m as u8)).collect();
+    let base = make_corpus_multi_month(&months);
+    let work = tempfile::tempdir().unwrap();
+    let out = work.path().join("users.txt");
+
+    // The scan keeps its 256 username shards open throughout; eight
+    // in-flight files budget 32 more descriptors on top of those, so the
+    // budget has to scale them down to fit a 290-descriptor soft limit.
+    assert_cmd::Command::new("sh")
+        .arg("-c")
+        .arg(r#"ulimit -Sn 290 && exec "$@""#)
+        .arg("sh")
+        .arg(assert_cmd::cargo::cargo_bin("retl"))
+        .arg("scan")
+        .arg("--data-dir")
+        .arg(&base)
+        .arg("--work-dir")
+        .arg(work.path().join("w"))
+        .args(["--start", "2006-01", "--end", "2006-12"])
+        .args(["--parallelism", "8", "--file-concurrency", "8", "--no-progress"])
+        .arg("--out")
+        .arg(&out)
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("lowering file concurrency"));
+
+    let lines = std::fs::read_to_string(&out).unwrap();
+    assert!(lines.lines().count() >= 12, "{lines}");
+}
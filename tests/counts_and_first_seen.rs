@@ -87,3 +87,429 @@ fn author_counts_and_first_seen_tsv() {
     assert_eq!(kv2.get("alice").copied(), Some(1136074600));   // comment c1
     assert_eq!(kv2.get("charlie").copied(), Some(1136074700)); // comment c2
 }
+
+/// Author filters strip a `u/` prefix and lowercase, so `u/Alice` selects the
+/// same records as `alice`.
+#[test]
+fn authors_in_accepts_user_prefixed_names() {
+    let base = make_corpus_basic();
+    let counts_tsv = base.join("prefixed_author_counts.tsv");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .scan()
+        .authors_in(["u/Alice"])
+        .author_counts_to_tsv(&counts_tsv)
+        .unwrap();
+    assert_eq!(read_lines(&counts_tsv), ["alice\t1"]);
+}
+
+/// A differently cased author is one author in the count metrics, while
+/// `usernames()` keeps the name as it appears in the corpus.
+#[test]
+fn author_counts_key_authors_by_normalized_name() {
+    let base = make_corpus_basic();
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-02.zst"),
+        &[serde_json::json!({
+            "id": "c9",
+            "author": "Alice",
+            "subreddit": "programming",
+            "created_utc": 1138752000,
+        })
+        .to_string()],
+    );
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+    };
+
+    let counts_tsv = base.join("normalized_counts.tsv");
+    etl()
+        .scan()
+        .authors_in(["alice"])
+        .author_counts_to_tsv(&counts_tsv)
+        .unwrap();
+    assert_eq!(read_lines(&counts_tsv), ["alice\t2"]);
+
+    let names = etl()
+        .scan()
+        .authors_in(["alice"])
+        .usernames()
+        .unwrap()
+        .collect_sorted();
+    assert_eq!(names, ["Alice", "alice"]);
+}
+
+/// `build_first_seen_detailed_to_tsv()` keeps the subreddit and id of each
+/// author's earliest record next to its timestamp.
+#[test]
+fn first_seen_detailed_tsv_records_subreddit_and_id() {
+    let base = make_corpus_basic();
+    let out = base.join("first_seen_detailed.tsv");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .build_first_seen_detailed_to_tsv(&out)
+        .unwrap();
+
+    let mut rows = read_lines(&out);
+    rows.sort();
+    assert!(rows.contains(&"alice\t1136074600\tprogramming\tc1".to_string()), "{rows:?}");
+    assert!(rows.contains(&"bob\t1136073600\tprogramming\ts1".to_string()), "{rows:?}");
+}
+
+/// `update_first_seen_index()` folds a new month into an existing index in
+/// place: an earlier timestamp lowers the author's entry, new authors are
+/// added, and untouched authors keep their prior value.
+#[test]
+fn update_first_seen_index_takes_the_earlier_timestamp() {
+    let base = make_corpus_basic();
+    let etl = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false);
+    let index = base.join("first_seen_incremental.tsv");
+    etl.clone()
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .scan()
+        .build_first_seen_index_to_tsv(&index)
+        .unwrap();
+
+    // A late-arriving dump can carry records older than the month it is
+    // filed under, so scan it from its own root without a month gate.
+    let new_root = base.join("new_months");
+    let comment = |author: &str, ts: i64| {
+        serde_json::json!({"author": author, "created_utc": ts, "subreddit": "programming"})
+            .to_string()
+    };
+    write_zst_lines(
+        &new_root.join("comments").join("RC_2006-02.zst"),
+        &[
+            comment("alice", 1136000000),
+            comment("bob", 1139000000),
+            comment("dana", 1139000100),
+        ],
+    );
+    etl.base_dir(&new_root)
+        .scan()
+        .update_first_seen_index(&index, &index)
+        .unwrap();
+
+    let mut rows = read_lines(&index);
+    rows.sort();
+    assert_eq!(
+        rows,
+        [
+            "AutoModerator\t1136073601",
+            "alice\t1136000000",
+            "bob\t1136073600",
+            "charlie\t1136074700",
+            "dana\t1139000100",
+        ]
+    );
+}
+
+/// `distinct_authors_by_month()` counts each author once per month: in the
+/// multi-month corpus `user_<month>` posts both the submission and a comment,
+/// so two matched records yield one distinct author. The approximate
+/// HyperLogLog mode agrees at this size.
+#[test]
+fn distinct_authors_by_month_counts_repeat_author_once() {
+    let months = [YearMonth::new(2006, 1), YearMonth::new(2006, 2)];
+    let base = make_corpus_multi_month(&months);
+    let scan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(months[0]), Some(months[1]))
+            .progress(false)
+            .scan()
+            .authors_in(["user_2006-01", "user_2006-02"])
+    };
+
+    let records = scan().count_by_month().unwrap();
+    assert_eq!(records.get(&months[0]).copied(), Some(2));
+    assert_eq!(records.get(&months[1]).copied(), Some(2));
+
+    let exact = scan().distinct_authors_by_month().unwrap();
+    assert_eq!(exact.len(), 2);
+    assert_eq!(exact.get(&months[0]).copied(), Some(1));
+    assert_eq!(exact.get(&months[1]).copied(), Some(1));
+
+    let approx = scan().distinct_authors_by_month_approx().unwrap();
+    assert_eq!(approx, exact);
+
+    let in_memory = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(months[0]), Some(months[1]))
+        .in_memory_shards(true)
+        .shard_count(2)
+        .progress(false)
+        .scan()
+        .authors_in(["user_2006-01", "user_2006-02"])
+        .distinct_authors_by_month()
+        .unwrap();
+    assert_eq!(in_memory, exact);
+
+    // Without the author filter each month also has `commenter_<month>` and
+    // AutoModerator: three distinct authors over four records.
+    let all = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(months[0]), Some(months[1]))
+        .progress(false)
+        .scan()
+        .distinct_authors_by_month()
+        .unwrap();
+    assert_eq!(all.get(&months[0]).copied(), Some(3));
+    assert_eq!(all.get(&months[1]).copied(), Some(3));
+}
+
+/// Distinct authors are keyed on the normalized name, so case and `u/` or
+/// `@` prefixes do not split one author into several.
+#[test]
+fn distinct_authors_by_month_normalizes_author_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let comment = |id: &str, author: &str| {
+        serde_json::json!({"id": id, "author": author, "subreddit": "rust", "created_utc": 1136074600})
+            .to_string()
+    };
+    write_zst_lines(
+        &dir.path().join("comments").join("RC_2006-01.zst"),
+        &[
+            comment("c1", "Alice"),
+            comment("c2", "u/alice"),
+            comment("c3", "@ALICE"),
+            comment("c4", "bob"),
+        ],
+    );
+    let scan = || {
+        RedditETL::new()
+            .base_dir(dir.path())
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+    };
+    let month = YearMonth::new(2006, 1);
+    let exact = scan().distinct_authors_by_month().unwrap();
+    assert_eq!(exact.get(&month).copied(), Some(2));
+    assert_eq!(scan().distinct_authors_by_month_approx().unwrap(), exact);
+}
+
+/// `distinct_subreddits()` lists each subreddit once, lowercased and sorted,
+/// whatever its casing in the dump or how many records it has.
+#[test]
+fn distinct_subreddits_lists_each_subreddit_once() {
+    let base = make_corpus_basic();
+    let subs = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .scan()
+        .distinct_subreddits()
+        .unwrap();
+    assert_eq!(subs, ["programming"]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let comment = |id: &str, sub: &str| {
+        serde_json::json!({"id": id, "author": "a", "subreddit": sub, "created_utc": 1136074600})
+            .to_string()
+    };
+    write_zst_lines(
+        &dir.path().join("comments").join("RC_2006-01.zst"),
+        &[comment("c1", "Rust"), comment("c2", "golang"), comment("c3", "rust")],
+    );
+    let subs = RedditETL::new()
+        .base_dir(dir.path())
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .distinct_subreddits()
+        .unwrap();
+    assert_eq!(subs, ["golang", "rust"]);
+}
+
+/// `score_quantiles()` estimates quantiles over a known score distribution:
+/// scores 0..=1000 spread over two months, in scrambled order.
+#[test]
+fn score_quantiles_estimate_a_known_distribution() {
+    let dir = tempfile::tempdir().unwrap();
+    let comment = |i: u64| {
+        let score = (i * 389) % 1001;
+        serde_json::json!({
+            "id": format!("c{i}"), "author": "a", "subreddit": "rust",
+            "score": score, "created_utc": 1136074600,
+        })
+        .to_string()
+    };
+    let lines: Vec<String> = (0..1001).map(comment).collect();
+    let (jan, feb) = lines.split_at(500);
+    write_zst_lines(&dir.path().join("comments").join("RC_2006-01.zst"), jan);
+    write_zst_lines(&dir.path().join("comments").join("RC_2006-02.zst"), feb);
+    let plan = || {
+        RedditETL::new()
+            .base_dir(dir.path())
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+    };
+
+    let qs = plan().score_quantiles(&[0.0, 0.5, 0.9, 1.0]).unwrap();
+    assert_eq!(qs[0], 0);
+    assert!((qs[1] - 500).abs() <= 5, "median estimate {}", qs[1]);
+    assert!((qs[2] - 900).abs() <= 5, "p90 estimate {}", qs[2]);
+    assert_eq!(qs[3], 1000);
+
+    assert!(plan().score_quantiles(&[1.5]).is_err());
+    assert!(plan().subreddit("golang").score_quantiles(&[0.5]).is_err());
+}
+
+/// `field_inventory()` counts records per top-level key across both sources:
+/// the basic corpus keeps two comments and two submissions after
+/// pseudo-user filtering.
+#[test]
+fn field_inventory_counts_top_level_keys_across_sources() {
+    let base = make_corpus_basic();
+    let plan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .progress(false)
+            .scan()
+    };
+
+    let inventory = plan().field_inventory().unwrap();
+    assert_eq!(inventory.get("author").copied(), Some(4));
+    assert_eq!(inventory.get("subreddit").copied(), Some(4));
+    assert_eq!(inventory.get("body").copied(), Some(2));
+    assert_eq!(inventory.get("parent_id").copied(), Some(2));
+    assert_eq!(inventory.get("title").copied(), Some(2));
+    assert_eq!(inventory.get("domain").copied(), Some(2));
+    assert_eq!(inventory.get("missing_everywhere"), None);
+
+    // Sampling hashes each line, so a rerun inventories the same records.
+    let sampled = plan().field_inventory_sampled(0.5).unwrap();
+    assert_eq!(sampled, plan().field_inventory_sampled(0.5).unwrap());
+    assert!(sampled.get("id").copied().unwrap_or(0) <= 4);
+    assert!(plan().field_inventory_sampled(0.0).is_err());
+}
+
+/// `author_karma_to_tsv()` sums `score` per author, negatives included;
+/// `author_max_score_to_tsv()` keeps each author's best single score.
+/// Records without a numeric score are skipped by both.
+#[test]
+fn author_karma_and_max_score_tsv() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().to_path_buf();
+    let lines: Vec<String> = [
+        r#"{"id":"c1","author":"alice","subreddit":"programming","created_utc":1136073600,"score":5,"body":"a"}"#,
+        r#"{"id":"c2","author":"alice","subreddit":"programming","created_utc":1136073601,"score":-2,"body":"b"}"#,
+        r#"{"id":"c3","author":"bob","subreddit":"programming","created_utc":1136073602,"score":-7,"body":"c"}"#,
+        r#"{"id":"c4","author":"carol","subreddit":"programming","created_utc":1136073603,"body":"no score"}"#,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &lines);
+    let plan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+    };
+
+    let karma_tsv = base.join("author_karma.tsv");
+    plan().author_karma_to_tsv(&karma_tsv).unwrap();
+    let mut karma = read_lines(&karma_tsv);
+    karma.sort();
+    assert_eq!(karma, ["alice\t3", "bob\t-7"]);
+
+    let max_tsv = base.join("author_max_score.tsv");
+    plan().author_max_score_to_tsv(&max_tsv).unwrap();
+    let mut max = read_lines(&max_tsv);
+    max.sort();
+    assert_eq!(max, ["alice\t5", "bob\t-7"]);
+}
+
+/// An author name containing a tab is escaped in the KV shards and the
+/// reduced TSV, so its rows neither split nor merge with other authors.
+#[test]
+fn author_counts_escape_tab_in_author_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().to_path_buf();
+    let lines: Vec<String> = [
+        r#"{"id":"c1","author":"tab\tuser","subreddit":"programming","created_utc":1136073600,"body":"a"}"#,
+        r#"{"id":"c2","author":"tab\tuser","subreddit":"programming","created_utc":1136073601,"body":"b"}"#,
+        r#"{"id":"c3","author":"tab","subreddit":"programming","created_utc":1136073602,"body":"c"}"#,
+        r#"{"id":"c4","author":"user","subreddit":"programming","created_utc":1136073603,"body":"d"}"#,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &lines);
+
+    let counts_tsv = base.join("author_counts.tsv");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .author_counts_to_tsv(&counts_tsv)
+        .unwrap();
+
+    let mut rows = read_lines(&counts_tsv);
+    rows.sort();
+    assert_eq!(rows, ["tab\t1", "tab\\tuser\t2", "user\t1"]);
+    let (key, count) = rows[1].split_once('\t').unwrap();
+    assert_eq!(retl::unescape_kv_key(key), "tab\tuser");
+    assert_eq!(count, "2");
+}
+
+/// `author_sessions` splits an author's records where the gap to the
+/// previous record exceeds `gap_secs`: two records 10 s apart form one
+/// session, a third an hour later starts another.
+#[test]
+fn author_sessions_split_on_time_gap() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().to_path_buf();
+    let lines: Vec<String> = [
+        r#"{"id":"c3","author":"Alice","subreddit":"programming","created_utc":1136077210,"body":"c"}"#,
+        r#"{"id":"c1","author":"alice","subreddit":"programming","created_utc":1136073600,"body":"a"}"#,
+        r#"{"id":"c2","author":"alice","subreddit":"programming","created_utc":1136073610,"body":"b"}"#,
+        r#"{"id":"c4","author":"bob","subreddit":"programming","created_utc":1136073605,"body":"d"}"#,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &lines);
+
+    let sessions_tsv = base.join("sessions.tsv");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .author_sessions(60, &sessions_tsv)
+        .unwrap();
+
+    let mut rows = read_lines(&sessions_tsv);
+    rows.sort();
+    assert_eq!(
+        rows,
+        [
+            "alice\t1136073600\t1136073610\t2",
+            "alice\t1136077210\t1136077210\t1",
+            "bob\t1136073605\t1136073605\t1",
+        ]
+    );
+}
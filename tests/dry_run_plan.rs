@@ -0,0 +1,198 @@
+//! `RedditETL::plan` / `ScanPlan::plan` list the files a run would read, with
+//! sizes, without decoding anything.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use common::make_corpus_basic;
+use retl::{FileKind, RedditETL, Sources, YearMonth};
+use std::fs;
+
+#[test]
+fn plan_lists_both_corpus_files_with_sizes() {
+    let base = make_corpus_basic();
+    let etl = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false);
+
+    let planned = etl.plan().unwrap();
+    assert_eq!(planned.len(), 2, "{planned:?}");
+    let kinds: Vec<FileKind> = planned.iter().map(|p| p.kind).collect();
+    assert!(kinds.contains(&FileKind::Comment));
+    assert!(kinds.contains(&FileKind::Submission));
+    for file in &planned {
+        assert_eq!(file.ym, YearMonth::new(2006, 1));
+        assert_eq!(file.size_bytes, fs::metadata(&file.path).unwrap().len());
+        assert!(file.size_bytes > 0);
+    }
+
+    let via_scan = etl.scan().subreddit("programming").plan().unwrap();
+    assert_eq!(via_scan, planned);
+}
+
+#[test]
+fn scan_plan_honors_sources_and_rejects_invalid_queries() {
+    let base = make_corpus_basic();
+
+    let planned = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .plan()
+        .unwrap();
+    assert_eq!(planned.len(), 1);
+    assert_eq!(planned[0].kind, FileKind::Comment);
+    assert!(planned[0].path.ends_with("RC_2006-01.zst"));
+
+    let err = RedditETL::new()
+        .base_dir(&base)
+        .progress(false)
+        .scan()
+        .subreddits_out(["  "])
+        .plan()
+        .unwrap_err();
+    assert!(
+        err.inner()
+            .downcast_ref::<retl::QueryBuildError>()
+            .is_some(),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn interleaved_sources_plans_month_major() {
+    let base = common::make_corpus_multi_month(&[YearMonth::new(2006, 1), YearMonth::new(2006, 2)]);
+    let etl = RedditETL::new().base_dir(&base).sources(Sources::Both).progress(false);
+
+    let order = |etl: &RedditETL| -> Vec<(FileKind, YearMonth)> {
+        etl.plan().unwrap().into_iter().map(|p| (p.kind, p.ym)).collect()
+    };
+    let jan = YearMonth::new(2006, 1);
+    let feb = YearMonth::new(2006, 2);
+
+    assert_eq!(
+        order(&etl),
+        vec![
+            (FileKind::Comment, jan),
+            (FileKind::Comment, feb),
+            (FileKind::Submission, jan),
+            (FileKind::Submission, feb),
+        ]
+    );
+    assert_eq!(
+        order(&etl.interleaved_sources(true)),
+        vec![
+            (FileKind::Comment, jan),
+            (FileKind::Submission, jan),
+            (FileKind::Comment, feb),
+            (FileKind::Submission, feb),
+        ]
+    );
+}
+
+#[test]
+fn corpus_bounds_report_months_per_source() {
+    let base = make_corpus_basic();
+    // Bounds describe the corpus, not the configured sources or date range.
+    let bounds = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2010, 1)), Some(YearMonth::new(2010, 2)))
+        .progress(false)
+        .corpus_bounds()
+        .unwrap();
+
+    let jan = YearMonth::new(2006, 1);
+    for source in [bounds.comments, bounds.submissions] {
+        let source = source.expect("both sources have a month");
+        assert_eq!((source.min, source.max, source.months), (jan, jan, 1));
+    }
+
+    let empty = tempfile::tempdir().unwrap();
+    let bounds = RedditETL::new()
+        .base_dir(empty.path())
+        .corpus_bounds()
+        .unwrap();
+    assert_eq!(bounds.comments, None);
+    assert_eq!(bounds.submissions, None);
+}
+
+#[test]
+fn find_missing_months_reports_holes_inside_the_corpus() {
+    let jan = YearMonth::new(2006, 1);
+    let feb = YearMonth::new(2006, 2);
+    let mar = YearMonth::new(2006, 3);
+    let base = common::make_corpus_multi_month(&[jan, feb, YearMonth::new(2006, 4)]);
+    let etl = RedditETL::new().base_dir(&base).progress(false);
+
+    assert_eq!(
+        etl.find_missing_months().unwrap(),
+        vec![(FileKind::Comment, mar), (FileKind::Submission, mar)]
+    );
+    assert_eq!(
+        etl.clone()
+            .sources(Sources::Submissions)
+            .find_missing_months()
+            .unwrap(),
+        vec![(FileKind::Submission, mar)]
+    );
+    // The configured range bounds the check.
+    let before_hole = etl.date_range(Some(jan), Some(feb));
+    assert!(before_hole.find_missing_months().unwrap().is_empty());
+}
+
+/// The basic corpus files are far smaller than the per-file sample, so the
+/// estimate decodes them whole and matches what an extract writes.
+#[test]
+fn estimate_output_matches_extract_on_a_fully_sampled_corpus() {
+    let base = make_corpus_basic();
+    let scan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .progress(false)
+            .scan()
+            .subreddit("programming")
+    };
+
+    let estimate = scan().estimate_output().unwrap();
+    let mut out = Vec::new();
+    let written = scan().extract_to_writer(&mut out).unwrap();
+
+    assert!(estimate.records > 0 && estimate.bytes > 0, "{estimate:?}");
+    assert_eq!(estimate.records, written);
+    assert_eq!(estimate.bytes, out.len() as u64);
+    assert_eq!(estimate.sampled_records, written);
+    assert_eq!(estimate.sampled_fraction, 1.0);
+
+    let limited = scan().limit(2).estimate_output().unwrap();
+    assert_eq!(limited.records, 2);
+    assert!(limited.bytes < estimate.bytes, "{limited:?}");
+}
+
+/// A JSON-pointer whitelist entry is projected for the estimate the same way
+/// the extract writes it.
+#[test]
+fn estimate_output_projects_pointer_whitelist_entries_like_extract() {
+    let base = make_corpus_basic();
+    let scan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .progress(false)
+            .whitelist_fields(["id", "/author"])
+            .scan()
+            .subreddit("programming")
+    };
+
+    let estimate = scan().estimate_output().unwrap();
+    let mut out = Vec::new();
+    let written = scan().extract_to_writer(&mut out).unwrap();
+
+    assert!(written > 0);
+    assert!(String::from_utf8_lossy(&out).contains("\"author\""));
+    assert_eq!(estimate.records, written);
+    assert_eq!(estimate.bytes, out.len() as u64);
+}
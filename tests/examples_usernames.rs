@@ -107,3 +107,87 @@ fn usernames_exclude_common_bots() {
     got.sort();
     assert_eq!(got, vec!["alice", "bob", "charlie"]);
 }
+
+/// Demonstrates the `collect_sorted` / `count_remaining` conveniences in place
+/// of the usual `collect` + `sort` + `dedup` dance.
+/// Outcome: with common bots excluded the basic corpus has three authors.
+#[test]
+fn usernames_collect_sorted_and_count_remaining() {
+    let base = make_corpus_basic();
+    let run = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+            .scan()
+            .subreddit("programming")
+            .exclude_common_bots()
+            .usernames()
+            .unwrap()
+    };
+
+    assert_eq!(run().collect_sorted(), vec!["alice", "bob", "charlie"]);
+    assert_eq!(run().count_remaining(), 3);
+}
+
+/// `usernames_limit(n)` stops the scan once `n` distinct authors are seen and
+/// yields exactly that many distinct names; a limit above the corpus's
+/// distinct count returns every author.
+#[test]
+fn usernames_limit_stops_at_n_distinct_authors() {
+    // 4000 records cycling through 1000 distinct authors.
+    let base = make_corpus_n_records(4000);
+    let collect = |n: usize| {
+        let mut it = RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .file_concurrency(2)
+            .progress(false)
+            .scan()
+            .usernames_limit(n)
+            .unwrap();
+        let mut got = Vec::<String>::new();
+        while let Some(u) = it.next() {
+            got.push(u);
+        }
+        got
+    };
+
+    let sample = collect(100);
+    assert_eq!(sample.len(), 100);
+    let mut distinct = sample.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 100, "the stream is deduped");
+    assert!(sample.iter().all(|u| u.starts_with("user_")));
+
+    assert_eq!(collect(5000).len(), 1000);
+    assert!(collect(0).is_empty());
+}
+
+/// Under `resume`, a capped scan still stops early instead of first
+/// materializing a whole-corpus scan checkpoint.
+#[test]
+fn usernames_limit_under_resume_skips_the_scan_checkpoint() {
+    let base = make_corpus_n_records(4000);
+    let work = tempfile::tempdir().unwrap();
+    let mut it = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .work_dir(work.path())
+        .resume(true)
+        .progress(false)
+        .scan()
+        .usernames_limit(100)
+        .unwrap();
+    let mut got = 0;
+    while it.next().is_some() {
+        got += 1;
+    }
+    assert_eq!(got, 100);
+    assert!(
+        !work.path().join("scan_checkpoints").exists(),
+        "a capped scan must not build a checkpoint"
+    );
+}
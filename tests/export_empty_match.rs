@@ -4,17 +4,26 @@
 //!   when `written == 0`, so an unmatched month must NOT leave an empty
 //!   `RC_/RS_` artifact at the destination (we'd otherwise pollute the
 //!   target corpus with empty files).
+//!   With `keep_empty_outputs(true)` every planned month is published
+//!   instead, empty when nothing matched.
 //! - `count_by_month`: returns an empty BTreeMap (no panic).
 //! - `extract_to_jsonl`: still creates the output file (an empty one) when
 //!   files were planned but no records survived filtering.
 //! - `extract_to_json`: errors when no input files exist for the date range,
 //!   but writes "[]" when files exist and filtering matches no records.
+//! - `usernames` / `count_by_month`: a wrong `base_dir` or an out-of-corpus
+//!   date range is the same `PlanningError` as for extraction, never an empty
+//!   stream or map that looks like "everything was filtered out".
+//! - `plan_stats`: the same out-of-corpus range is `Ok` with
+//!   `matched_files == 0`, while missing source dirs stay an error.
 
 #[path = "common/mod.rs"]
 mod common;
 
 use common::*;
-use retl::{ConfigBuildError, ExportFormat, PlanningError, RedditETL, Sources, YearMonth};
+use retl::{
+    ConfigBuildError, ExportFormat, PlanStats, PlanningError, RedditETL, Sources, YearMonth,
+};
 use std::fs;
 
 #[test]
@@ -31,7 +40,10 @@ fn extract_errors_when_selected_source_dirs_are_missing() {
         .extract_to_jsonl(&out)
         .unwrap_err();
 
-    let planning = err.downcast_ref::<PlanningError>().expect("PlanningError");
+    let planning = err
+        .inner()
+        .downcast_ref::<PlanningError>()
+        .expect("PlanningError");
     match planning {
         PlanningError::NoSourceFiles { statuses, .. } => {
             assert_eq!(statuses.len(), 2);
@@ -61,7 +73,10 @@ fn extract_errors_when_selected_source_dirs_exist_but_have_no_matching_files() {
         .extract_to_jsonl(&out)
         .unwrap_err();
 
-    let planning = err.downcast_ref::<PlanningError>().expect("PlanningError");
+    let planning = err
+        .inner()
+        .downcast_ref::<PlanningError>()
+        .expect("PlanningError");
     match planning {
         PlanningError::NoSourceFiles { statuses, .. } => {
             assert_eq!(statuses.len(), 2);
@@ -88,7 +103,10 @@ fn extract_errors_when_date_range_excludes_available_corpus() {
         .extract_to_jsonl(&out)
         .unwrap_err();
 
-    let planning = err.downcast_ref::<PlanningError>().expect("PlanningError");
+    let planning = err
+        .inner()
+        .downcast_ref::<PlanningError>()
+        .expect("PlanningError");
     match planning {
         PlanningError::DateRangeNoFiles { requested_start, requested_end, available_start, available_end, .. } => {
             assert_eq!(*requested_start, Some(YearMonth::new(1999, 1)));
@@ -102,6 +120,117 @@ fn extract_errors_when_date_range_excludes_available_corpus() {
     }
 }
 
+#[test]
+fn usernames_errors_instead_of_streaming_nothing_when_base_dir_is_wrong() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = RedditETL::new()
+        .base_dir(dir.path().join("no_such_corpus"))
+        .work_dir(dir.path().join("work"))
+        .sources(Sources::Both)
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .usernames()
+        .err()
+        .expect("missing corpus dirs must be an error");
+
+    let planning = err
+        .inner()
+        .downcast_ref::<PlanningError>()
+        .expect("PlanningError");
+    match planning {
+        PlanningError::NoSourceFiles { statuses, .. } => {
+            assert!(statuses.iter().all(|s| !s.exists));
+        }
+        other => panic!("unexpected planning error: {other:?}"),
+    }
+}
+
+#[test]
+fn count_by_month_distinguishes_out_of_range_from_filtered_out() {
+    let base = make_corpus_basic();
+
+    let err = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2010, 1)), Some(YearMonth::new(2010, 12)))
+        .progress(false)
+        .scan()
+        .count_by_month()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.inner().downcast_ref::<PlanningError>(),
+            Some(PlanningError::DateRangeNoFiles { .. })
+        ),
+        "{err:#}"
+    );
+
+    // Same corpus, in-range month, filter that matches nothing: Ok and empty.
+    let counts = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .authors_in(["nonexistent_user_abcxyz"])
+        .count_by_month()
+        .unwrap();
+    assert!(counts.is_empty());
+}
+
+#[test]
+fn plan_stats_reports_zero_matched_files_for_out_of_range_dates() {
+    let base = make_corpus_basic();
+    let etl = |start: YearMonth, end: YearMonth| {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(start), Some(end))
+            .progress(false)
+    };
+
+    let stats = etl(YearMonth::new(2010, 1), YearMonth::new(2010, 12))
+        .plan_stats()
+        .unwrap();
+    assert_eq!(stats, PlanStats::default());
+    assert_eq!(stats.matched_files, 0);
+
+    let stats = etl(YearMonth::new(2010, 1), YearMonth::new(2010, 12))
+        .scan()
+        .subreddit("programming")
+        .plan_stats()
+        .unwrap();
+    assert_eq!(stats.matched_files, 0);
+
+    let stats = etl(YearMonth::new(2006, 1), YearMonth::new(2006, 1))
+        .plan_stats()
+        .unwrap();
+    assert_eq!(stats.matched_files, 2);
+    assert!(stats.compressed_bytes > 0);
+}
+
+#[test]
+fn plan_stats_errors_when_selected_source_dirs_are_missing() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = RedditETL::new()
+        .base_dir(dir.path().join("no_such_corpus"))
+        .work_dir(dir.path().join("work"))
+        .sources(Sources::Both)
+        .progress(false)
+        .plan_stats()
+        .unwrap_err();
+
+    match err.inner().downcast_ref::<PlanningError>() {
+        Some(PlanningError::NoSourceFiles { statuses, .. }) => {
+            assert!(statuses.iter().all(|s| !s.exists));
+        }
+        other => panic!("unexpected error: {other:?} ({err:#})"),
+    }
+}
+
 #[test]
 fn invalid_date_range_surfaces_structured_build_error() {
     let base = make_corpus_basic();
@@ -116,7 +245,10 @@ fn invalid_date_range_surfaces_structured_build_error() {
         .extract_to_jsonl(&out)
         .unwrap_err();
 
-    let build = err.downcast_ref::<ConfigBuildError>().expect("ConfigBuildError");
+    let build = err
+        .inner()
+        .downcast_ref::<ConfigBuildError>()
+        .expect("ConfigBuildError");
     assert_eq!(build, &ConfigBuildError::InvalidDateRange { start: YearMonth::new(2006, 2), end: YearMonth::new(2006, 1) });
 }
 
@@ -182,6 +314,36 @@ fn export_partitioned_zst_unmatched_query_removes_output_files() {
     }
 }
 
+#[test]
+fn export_partitioned_keep_empty_outputs_publishes_empty_files() {
+    let base = make_corpus_basic();
+    let export = |keep: bool, out_dir: &std::path::Path| {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .keep_empty_outputs(keep)
+            .progress(false)
+            .scan()
+            .authors_in(["nonexistent_user_abcxyz"])
+            .export_partitioned(out_dir, ExportFormat::Jsonl)
+            .unwrap();
+        (
+            out_dir.join("comments").join("RC_2006-01.jsonl"),
+            out_dir.join("submissions").join("RS_2006-01.jsonl"),
+        )
+    };
+
+    let (rc, rs) = export(true, &base.join("export_keep_empty"));
+    for path in [&rc, &rs] {
+        assert!(path.exists(), "empty month should be kept: {}", path.display());
+        assert_eq!(fs::metadata(path).unwrap().len(), 0);
+    }
+
+    let (rc, rs) = export(false, &base.join("export_drop_empty"));
+    assert!(!rc.exists() && !rs.exists());
+}
+
 #[test]
 fn count_by_month_returns_empty_map_for_unmatched_query() {
     let base = make_corpus_basic();
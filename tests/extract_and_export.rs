@@ -2,7 +2,7 @@
 mod common;
 
 use common::*;
-use retl::{validate_zst_full, ExportFormat, RedditETL, Sources, YearMonth};
+use retl::{validate_zst_full, ExportFormat, KeyExtractor, RedditETL, Sources, YearMonth};
 
 /// Extract with a whitelist and human-readable timestamps:
 /// - Focus "programming" in Jan 2006
@@ -156,3 +156,479 @@ fn export_partitioned_zst() {
         );
     }
 }
+
+/// Partitioned export keyed by subreddit:
+/// - Two months of comments plus one submission file across three subreddits,
+///   one of them needing escaping (`c++`) and one record with an empty subreddit
+/// - Every subreddit's records from both months land in `<sub>/data.jsonl`
+/// - The keyless record lands in `_unkeyed/data.jsonl`
+#[test]
+fn export_partitioned_by_subreddit_groups_records_across_months() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("corpus");
+    let rec = |id: &str, sub: Option<&str>, ts: i64| {
+        let mut v = serde_json::json!({"id": id, "author": "a", "created_utc": ts});
+        if let Some(sub) = sub {
+            v["subreddit"] = sub.into();
+        }
+        v.to_string()
+    };
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            rec("c1", Some("rust"), 1136074600),
+            rec("c2", Some("golang"), 1136074700),
+            rec("c3", Some("c++"), 1136074800),
+            // Records without a subreddit never pass the scan filter; an
+            // empty one does, and has no usable key.
+            rec("c4", Some(""), 1136074900),
+        ],
+    );
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-02.zst"),
+        &[rec("c5", Some("Rust"), 1138752000), rec("c6", Some("golang"), 1138752100)],
+    );
+    write_zst_lines(
+        &base.join("submissions").join("RS_2006-01.zst"),
+        &[rec("s1", Some("rust"), 1136073600)],
+    );
+
+    let out_dir = dir.path().join("by_sub");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .work_dir(dir.path().join("work"))
+        .scan()
+        .include_pseudo_users()
+        .export_partitioned_by(
+            &out_dir,
+            KeyExtractor::subreddit_lowercase_fast(),
+            ExportFormat::Jsonl,
+        )
+        .unwrap();
+
+    let ids = |name: &str| {
+        let mut ids: Vec<String> = read_jsonl_values(&out_dir.join(name).join("data.jsonl"))
+            .iter()
+            .map(|v| v["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids("rust"), ["c1", "c5", "s1"]);
+    assert_eq!(ids("golang"), ["c2", "c6"]);
+    assert_eq!(ids("c%2B%2B"), ["c3"]);
+    assert_eq!(ids("_unkeyed"), ["c4"]);
+
+    let mut dirs: Vec<String> = std::fs::read_dir(&out_dir)
+        .unwrap()
+        .map(|e| e.unwrap())
+        .filter(|e| e.file_type().unwrap().is_dir())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|n| n != "_staging")
+        .collect();
+    dirs.sort();
+    assert_eq!(dirs, ["_unkeyed", "c%2B%2B", "golang", "rust"]);
+}
+
+/// `extract_split_by_subreddit` writes one `<subreddit>.jsonl` per subreddit
+/// in a single scan, each holding exactly what a per-subreddit extract would.
+#[test]
+fn extract_split_by_subreddit_writes_one_file_per_subreddit() {
+    let base = make_corpus_basic();
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+    };
+    let sorted_ids = |path: &std::path::Path| {
+        let mut ids: Vec<String> = read_jsonl_values(path)
+            .iter()
+            .map(|v| v["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    let out_dir = base.join("split_by_sub");
+    etl()
+        .scan()
+        .extract_split_by_subreddit(&out_dir, ExportFormat::Jsonl)
+        .unwrap();
+
+    let single = base.join("programming_only.jsonl");
+    etl()
+        .scan()
+        .subreddit("programming")
+        .extract_to_jsonl(&single)
+        .unwrap();
+    let programming = sorted_ids(&out_dir.join("programming.jsonl"));
+    assert!(!programming.is_empty());
+    assert_eq!(programming, sorted_ids(&single));
+    assert!(!out_dir.join("programming").exists());
+}
+
+/// `canonicalize_json` sorts object keys at every depth, so the same record
+/// written with different key orders comes out byte-identical; the default
+/// raw path keeps each line as it was.
+#[test]
+fn canonicalize_json_sorts_keys_recursively() {
+    let base = tempfile::tempdir().unwrap().keep();
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            r#"{"id":"c1","author":"alice","subreddit":"rust","created_utc":1136074600,"gildings":{"gid_1":1,"gid_2":0}}"#.to_string(),
+            r#"{"gildings":{"gid_2":0,"gid_1":1},"created_utc":1136074600,"subreddit":"rust","author":"alice","id":"c1"}"#.to_string(),
+        ],
+    );
+    let extract = |canonical: bool, out: &std::path::Path| {
+        let plan = RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan();
+        let plan = if canonical {
+            plan.canonicalize_json()
+        } else {
+            plan
+        };
+        plan.extract_to_jsonl(out).unwrap();
+        read_lines(out)
+    };
+
+    let canonical = extract(true, &base.join("canonical.jsonl"));
+    assert_eq!(canonical.len(), 2);
+    assert_eq!(canonical[0], canonical[1]);
+    assert_eq!(
+        canonical[0],
+        r#"{"author":"alice","created_utc":1136074600,"gildings":{"gid_1":1,"gid_2":0},"id":"c1","subreddit":"rust"}"#
+    );
+
+    let raw = extract(false, &base.join("raw.jsonl"));
+    assert_ne!(raw[0], raw[1]);
+}
+
+/// `add_provenance` tags each record with the monthly file it came from and
+/// its 1-based line there. Blank and non-matching lines still count, and the
+/// tags survive a whitelist that does not name them.
+#[test]
+fn add_provenance_tags_source_file_and_line() {
+    let base = tempfile::tempdir().unwrap().keep();
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            r#"{"id":"c1","author":"ann","subreddit":"rust","created_utc":1136074600}"#.to_string(),
+            r#"{"id":"c2","author":"bob","subreddit":"rust","created_utc":1136074601}"#.to_string(),
+            String::new(),
+            r#"{"id":"c3","author":"ann","subreddit":"rust","created_utc":1136074602}"#.to_string(),
+        ],
+    );
+    write_zst_lines(
+        &base.join("submissions").join("RS_2006-01.zst"),
+        &[
+            r#"{"id":"s1","author":"bob","subreddit":"rust","created_utc":1136074500}"#.to_string(),
+            r#"{"id":"s2","author":"ann","subreddit":"rust","created_utc":1136074501}"#.to_string(),
+        ],
+    );
+    let out = base.join("provenance.jsonl");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .whitelist_fields(["id"])
+        .progress(false)
+        .scan()
+        .author("ann")
+        .add_provenance()
+        .extract_to_jsonl(&out)
+        .unwrap();
+
+    let mut tagged: Vec<(String, u64, String)> = read_jsonl_values(&out)
+        .into_iter()
+        .map(|v| {
+            (
+                v["_src_file"].as_str().unwrap().to_string(),
+                v["_src_line"].as_u64().unwrap(),
+                v["id"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    tagged.sort();
+    let expected = [
+        ("RC_2006-01", 1, "c1"),
+        ("RC_2006-01", 4, "c3"),
+        ("RS_2006-01", 2, "s2"),
+    ];
+    assert_eq!(
+        tagged,
+        expected.map(|(file, line, id)| (file.to_string(), line, id.to_string()))
+    );
+}
+
+/// `whitelist_source_order` still holds when `add_provenance` forces the
+/// parsed write path: whitelisted keys keep the record's order and the tags
+/// follow them.
+#[test]
+fn add_provenance_keeps_whitelist_source_order() {
+    let base = tempfile::tempdir().unwrap().keep();
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[r#"{"subreddit":"rust","id":"c1","author":"ann","created_utc":1136074600}"#.to_string()],
+    );
+    let out = base.join("provenance_order.jsonl");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .whitelist_fields(["id", "author", "subreddit"])
+        .whitelist_source_order(true)
+        .progress(false)
+        .scan()
+        .add_provenance()
+        .extract_to_jsonl(&out)
+        .unwrap();
+
+    assert_eq!(
+        read_lines(&out),
+        [r#"{"subreddit":"rust","id":"c1","author":"ann","_src_file":"RC_2006-01","_src_line":1}"#]
+    );
+}
+
+/// `transform` runs on every written record, after the whitelist.
+#[test]
+fn transform_adds_a_field_to_every_extracted_record() {
+    let base = make_corpus_basic();
+    let out = base.join("transformed.jsonl");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .whitelist_fields(["id"])
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .transform(std::sync::Arc::new(|record: &mut serde_json::Value| {
+            record["batch"] = serde_json::json!("2006-01");
+        }))
+        .extract_to_jsonl(&out)
+        .unwrap();
+
+    let records = read_jsonl_values(&out);
+    assert_eq!(records.len(), 4);
+    for record in &records {
+        let obj = record.as_object().unwrap();
+        assert_eq!(obj.len(), 2, "{record}");
+        assert!(obj["id"].is_string());
+        assert_eq!(obj["batch"], "2006-01");
+    }
+}
+
+/// `canonicalize_subreddit_case(true)` writes every spelling of a subreddit
+/// in the lowercase form the filter matched on; without it the dump's casing
+/// is kept.
+#[test]
+fn canonicalize_subreddit_case_lowercases_extracted_subreddits() {
+    let base = tempfile::tempdir().unwrap().keep();
+    let lines: Vec<String> = ["AskScience", "askscience", "ASKSCIENCE"]
+        .iter()
+        .enumerate()
+        .map(|(i, sub)| {
+            serde_json::json!({"id": format!("c{i}"), "author": "alice", "subreddit": sub,
+                "created_utc": 1136074600_i64})
+            .to_string()
+        })
+        .collect();
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &lines);
+
+    let subreddits = |canonical: bool, whitelist: &[&str]| -> Vec<String> {
+        let out = base.join(format!("case_{canonical}_{}.jsonl", whitelist.len()));
+        let mut etl = RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false);
+        if !whitelist.is_empty() {
+            etl = etl.whitelist_fields(whitelist.iter().copied());
+        }
+        etl.scan()
+            .subreddit("AskScience")
+            .canonicalize_subreddit_case(canonical)
+            .extract_to_jsonl(&out)
+            .unwrap();
+        let mut records = read_jsonl_values(&out);
+        records.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+        records
+            .iter()
+            .map(|r| r["subreddit"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        subreddits(false, &[]),
+        ["AskScience", "askscience", "ASKSCIENCE"]
+    );
+    assert_eq!(subreddits(true, &[]), ["askscience"; 3]);
+    assert_eq!(subreddits(true, &["id", "subreddit"]), ["askscience"; 3]);
+}
+
+/// `dedupe_records_by` keeps one copy of a record that two overlapping
+/// slices both carry: the earliest by month, then line. Records whose key is
+/// unique pass through untouched.
+#[test]
+fn dedupe_records_by_id_exports_one_copy_of_a_duplicated_record() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("corpus");
+    let rec = |id: &str, body: &str, ts: i64| {
+        serde_json::json!({"id": id, "author": "a", "subreddit": "rust", "body": body, "created_utc": ts})
+            .to_string()
+    };
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            rec("c1", "first", 1136074600),
+            rec("c2", "only", 1136074700),
+            rec("c1", "second", 1136074600),
+        ],
+    );
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-02.zst"),
+        &[rec("c1", "third", 1136074600), rec("c3", "only", 1138752000)],
+    );
+
+    let out_dir = dir.path().join("deduped");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .work_dir(dir.path().join("work"))
+        .scan()
+        .dedupe_records_by(KeyExtractor::json_pointer("/id"))
+        .export_partitioned(&out_dir, ExportFormat::Jsonl)
+        .unwrap();
+
+    let records = |month: &str| -> Vec<(String, String)> {
+        read_jsonl_values(&out_dir.join("comments").join(format!("RC_{month}.jsonl")))
+            .iter()
+            .map(|v| {
+                (
+                    v["id"].as_str().unwrap().to_string(),
+                    v["body"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    };
+    let pair = |id: &str, body: &str| (id.to_string(), body.to_string());
+    assert_eq!(records("2006-01"), [pair("c1", "first"), pair("c2", "only")]);
+    assert_eq!(records("2006-02"), [pair("c3", "only")]);
+}
+
+/// `max_output_bytes` stops writing once the budget is spent: the output
+/// holds the records written before the cap plus at most the one that
+/// crossed it, and the extract still succeeds.
+#[test]
+fn max_output_bytes_caps_extract_size_near_the_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("corpus");
+    let lines: Vec<String> = (0..50)
+        .map(|i| {
+            serde_json::json!({
+                "id": format!("c{i:02}"),
+                "author": "a",
+                "subreddit": "rust",
+                "body": "x".repeat(40),
+                "created_utc": 1136074600 + i,
+            })
+            .to_string()
+        })
+        .collect();
+    let line_len = lines[0].len() as u64 + 1;
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &lines);
+
+    let budget = 5 * line_len - 10;
+    let out = dir.path().join("capped.jsonl");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .max_output_bytes(budget)
+        .extract_to_jsonl(&out)
+        .unwrap();
+
+    let size = std::fs::metadata(&out).unwrap().len();
+    assert!(size >= budget, "stopped early: {size} < {budget}");
+    assert!(
+        size <= budget + line_len,
+        "overshot: {size} > {budget} + {line_len}"
+    );
+    assert_eq!(read_jsonl_values(&out).len(), 5);
+}
+
+/// A JSONL partitioned export is itself a corpus: pointing `base_dir` at it
+/// discovers the `RC_`/`RS_` `.jsonl` partitions and reads them uncompressed,
+/// yielding the same usernames as the original `.zst` corpus.
+#[test]
+fn jsonl_export_can_be_requeried_as_a_corpus() {
+    let base = make_corpus_basic();
+    let out_dir = base.join("export_jsonl");
+    let scan = |base: &std::path::Path| {
+        RedditETL::new()
+            .base_dir(base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+            .scan()
+            .subreddit("programming")
+    };
+
+    scan(&base)
+        .export_partitioned(&out_dir, ExportFormat::Jsonl)
+        .unwrap();
+    assert!(out_dir.join("comments").join("RC_2006-01.jsonl").exists());
+
+    let usernames = |base: &std::path::Path| {
+        let mut names: Vec<String> = scan(base).usernames().unwrap().collect();
+        names.sort();
+        names
+    };
+    let from_export = usernames(&out_dir);
+    assert!(!from_export.is_empty());
+    assert_eq!(from_export, usernames(&base));
+}
+
+#[test]
+fn framed_extraction_round_trips_the_same_records() {
+    let base = make_corpus_n_records(5);
+    let scan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .progress(false)
+            .write_batch_size(2)
+            .scan()
+    };
+    let mut framed = Vec::new();
+    assert_eq!(scan().extract_framed_to_writer(&mut framed).unwrap(), 5);
+    let mut lines = Vec::new();
+    scan().extract_to_writer(&mut lines).unwrap();
+
+    let mut reader = framed.as_slice();
+    let mut records = Vec::new();
+    while let Some(record) = retl::read_framed(&mut reader).unwrap() {
+        records.push(record);
+    }
+    let expected: Vec<serde_json::Value> = String::from_utf8(lines)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(records.len(), 5);
+    assert_eq!(records, expected);
+
+    let truncated = &framed[..framed.len() - 1];
+    let mut reader = truncated;
+    let err = std::iter::from_fn(|| retl::read_framed(&mut reader).transpose())
+        .find_map(Result::err)
+        .expect("a cut-off frame should fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
@@ -94,7 +94,7 @@ fn extract_to_json_array_errors_when_no_input_files_exist() {
         .extract_to_json(&out, false)
         .unwrap_err();
 
-    assert!(err.downcast_ref::<PlanningError>().is_some());
+    assert!(err.inner().downcast_ref::<PlanningError>().is_some());
     assert!(
         !out.exists(),
         "no empty JSON array should be published without candidate input files"
@@ -0,0 +1,57 @@
+//! Per-file `file` spans: with debug logging on, every planned monthly file
+//! closes with a `file done` event carrying its record count and elapsed
+//! time. Lives in its own test binary because it installs the process-wide
+//! subscriber (rayon workers do not see a thread-local default).
+
+#[path = "common/mod.rs"]
+mod common;
+
+use common::*;
+use retl::{RedditETL, Sources};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn extract_emits_per_file_debug_event() {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+
+    let base = make_corpus_basic();
+    let out = base.join("traced.jsonl");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .extract_to_jsonl(&out)
+        .unwrap();
+    let written = read_lines(&out).len();
+
+    let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    let events: Vec<&str> = logs.lines().filter(|l| l.contains("file done")).collect();
+    assert_eq!(events.len(), 1, "{logs}");
+    assert!(events[0].contains("RC_2006-01.zst"), "{}", events[0]);
+    let records = format!("records={written}");
+    assert!(events[0].contains(&records), "{}", events[0]);
+    assert!(events[0].contains("elapsed_ms="), "{}", events[0]);
+}
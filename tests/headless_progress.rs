@@ -0,0 +1,114 @@
+//! Progress-enabled runs must behave the same with and without the `progress`
+//! feature. CI runs this file in the headless configuration too:
+//!
+//!     cargo test --no-default-features --test headless_progress
+//!
+//! Without the feature `progress(true)` draws nothing, but the operations
+//! still run and the progress helpers keep their bookkeeping.
+
+mod common;
+
+use common::{make_corpus_basic, read_jsonl_values};
+use retl::{
+    file_sizes, make_weighted_count_progress, CountByField, PipelineProgress, ProgressScope,
+    RedditETL, Sources, YearMonth,
+};
+
+#[test]
+fn progress_enabled_extract_and_count_produce_correct_outputs() {
+    let base = make_corpus_basic();
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(true)
+    };
+
+    let out = base.join("headless.jsonl");
+    etl()
+        .scan()
+        .subreddit("programming")
+        .extract_to_jsonl(&out)
+        .unwrap();
+    let mut ids: Vec<String> = read_jsonl_values(&out)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, ["c1", "c2", "s1", "s2"]);
+
+    let counts = etl().scan().count_by_month().unwrap();
+    assert_eq!(counts.get(&YearMonth::new(2006, 1)).copied(), Some(4));
+
+    let authors = etl().scan().usernames().unwrap().collect_sorted();
+    assert_eq!(authors, ["AutoModerator", "alice", "bob", "charlie"]);
+}
+
+/// Aggregate and parent attach tick a byte-weighted bar per finished input.
+#[test]
+fn progress_enabled_aggregate_and_attach_complete_with_weighted_bars() {
+    let base = make_corpus_basic();
+    let work = base.join("headless_weighted");
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&work)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(true)
+    };
+    let (parts, written) = etl()
+        .scan()
+        .subreddit("programming")
+        .extract_spool_monthly(&work.join("spool"))
+        .unwrap();
+
+    let (by_sub, report) = etl()
+        .aggregate_jsonls_parallel_collect_with(parts.clone(), &work.join("shards"), || {
+            CountByField::new("subreddit")
+        })
+        .unwrap();
+    assert_eq!(report.problem_count(), 0);
+    assert_eq!(by_sub.counts.get("programming").copied(), Some(written));
+
+    let ids = etl().collect_parent_ids_from_jsonls(parts.clone()).unwrap();
+    let parents = etl()
+        .resolve_parent_maps(&ids, &work.join("parents"), false)
+        .unwrap();
+    let attached = etl()
+        .attach_parents_jsonls_parallel(parts.clone(), &work.join("attached"), &parents, false)
+        .unwrap();
+    assert_eq!(attached.len(), parts.len());
+
+    let weighted = file_sizes(&parts);
+    let bar = make_weighted_count_progress(&weighted, "weighted");
+    for (_, size) in &weighted {
+        bar.inc(*size);
+    }
+    bar.finish();
+    assert_eq!(bar.position(), weighted.iter().map(|(_, size)| size).sum::<u64>());
+}
+
+#[test]
+fn progress_helpers_run_without_a_terminal() {
+    let scope = ProgressScope::count("items", 3);
+    scope.inc_items(3);
+    scope.finish("done");
+    drop(ProgressScope::bytes("dropped unfinished", 10));
+
+    let pipeline = PipelineProgress::new(["one", "two"]);
+    let seen = pipeline
+        .run_stage("one", |stage| Ok(stage.name().to_string()))
+        .unwrap();
+    assert_eq!(seen, "one");
+    pipeline.run_stage("two", |_stage| Ok(())).unwrap();
+    pipeline.finish();
+
+    let mp = pipeline.multi_progress();
+    let bar = mp.add(retl::ProgressBar::new(2));
+    bar.inc(2);
+    bar.finish_with_message("bar done");
+    assert!(bar.is_finished());
+    assert_eq!(bar.position(), 2);
+}
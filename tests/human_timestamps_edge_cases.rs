@@ -21,7 +21,7 @@
 mod common;
 
 use common::*;
-use retl::{RedditETL, Sources};
+use retl::{EditedMode, RedditETL, Sources};
 use serde_json::{json, Value};
 use std::path::PathBuf;
 
@@ -168,3 +168,53 @@ fn non_target_keys_not_misidentified_when_string_contains_keyword_substring() {
     let cu = out[0].get("created_utc").and_then(|v| v.as_str()).unwrap();
     assert!(cu.contains('T'), "real created_utc must still be RFC3339");
 }
+
+/// `normalize_edited` gives `edited` one type per mode; a record without
+/// `edited` is written without one in every mode.
+#[test]
+fn normalize_edited_coerces_false_timestamp_and_missing_per_mode() {
+    let rec = |id: &str, edited: Option<Value>| {
+        let mut v = json!({
+            "subreddit":"programming","author":"alice","id":id,
+            "body":"x","parent_id":"t3_s1","created_utc": 1136074600_i64
+        });
+        if let Some(e) = edited {
+            v["edited"] = e;
+        }
+        v.to_string()
+    };
+    let base = build_corpus_with_lines(vec![
+        rec("c1", Some(json!(false))),
+        rec("c2", Some(json!(1609459200_i64))),
+        rec("c3", None),
+    ]);
+
+    let edited_by_mode = |mode: EditedMode, name: &str| -> Vec<Option<Value>> {
+        let out = base.join(name);
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+            .subreddit("programming")
+            .normalize_edited(mode)
+            .extract_to_jsonl(&out)
+            .unwrap();
+        let mut records = read_jsonl_values(&out);
+        records.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+        records.iter().map(|r| r.get("edited").cloned()).collect()
+    };
+
+    assert_eq!(
+        edited_by_mode(EditedMode::Leave, "leave.jsonl"),
+        vec![Some(json!(false)), Some(json!(1609459200_i64)), None]
+    );
+    assert_eq!(
+        edited_by_mode(EditedMode::AsBool, "as_bool.jsonl"),
+        vec![Some(json!(false)), Some(json!(true)), None]
+    );
+    assert_eq!(
+        edited_by_mode(EditedMode::AsTimestamp, "as_timestamp.jsonl"),
+        vec![Some(Value::Null), Some(json!("2021-01-01T00:00:00Z")), None]
+    );
+}
@@ -134,3 +134,22 @@ fn large_record_id_sets_match_without_full_parse_filters() {
 
     assert_eq!(counts.get(&YearMonth::new(2006, 1)), Some(&1));
 }
+
+#[test]
+fn top_level_comments_only_drops_replies_and_keeps_submissions() {
+    let base = common::make_corpus_basic();
+    let out = base.join("top_level.jsonl");
+
+    jan_2006_scan(&base)
+        .top_level_comments_only(true)
+        .extract_to_jsonl(&out)
+        .unwrap();
+
+    let mut ids: Vec<String> = read_jsonl_values(&out)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    // c2 replies to c1 (`t1_` parent); c3 is dropped by the default pseudo-user filter.
+    assert_eq!(ids, ["c1", "s1", "s2"]);
+}
@@ -6,9 +6,10 @@ use common::*;
 use predicates::prelude::*;
 use predicates::str::contains;
 use retl::{
-    quick_validate_zst, ConfigBuildError, IntegrityMode, QuickOutcome, RedditETL, Sources,
-    YearMonth,
+    quick_validate_zst, validate_zst_full_stats, ConfigBuildError, CorruptAction, CorruptOutcome,
+    IntegrityMode, QuickOutcome, RedditETL, Sources, YearMonth, INTEGRITY_TIMEOUT_ERROR,
 };
+use std::time::Duration;
 
 fn retl_cmd() -> Command {
     Command::cargo_bin("retl").expect("retl binary should be built")
@@ -55,6 +56,63 @@ fn integrity_check_detects_corrupt_month() {
     );
 }
 
+/// `CorruptAction::Quarantine` moves the corrupt month out of the corpus in
+/// the same pass that finds it, leaves the good month alone, and reports the
+/// new location.
+#[test]
+fn integrity_quarantines_corrupt_month() {
+    let base = make_corpus_basic();
+    add_corrupt_month(&base);
+    let corrupt = base.join("comments").join("RC_2006-02.zst");
+    let quarantine = base.join("quarantine");
+
+    let report = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 2)))
+        .progress(false)
+        .integrity_on_corrupt(CorruptAction::Quarantine(quarantine.clone()))
+        .check_corpus_integrity(IntegrityMode::Full)
+        .unwrap();
+
+    let moved = quarantine.join("RC_2006-02.zst");
+    assert_eq!(report.failure_count(), 1);
+    assert_eq!(
+        report.corrupt_actions,
+        vec![(corrupt.clone(), CorruptOutcome::Quarantined(moved.clone()))]
+    );
+    assert!(!corrupt.exists(), "corrupt month should leave the corpus");
+    assert!(moved.is_file(), "corrupt month should land in quarantine");
+    assert!(base.join("comments").join("RC_2006-01.zst").is_file());
+}
+
+/// `CorruptAction::Delete` removes a month that fails to decode but leaves a
+/// healthy plain `.jsonl` month, which is not a zstd stream, in place.
+#[test]
+fn integrity_delete_leaves_healthy_plain_jsonl_month() {
+    let base = make_corpus_basic();
+    add_corrupt_month(&base);
+    let corrupt = base.join("comments").join("RC_2006-02.zst");
+    let plain = base.join("comments").join("RC_2006-03.jsonl");
+    std::fs::write(&plain, "{\"id\":\"p\"}\n").unwrap();
+
+    let report = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .integrity_on_corrupt(CorruptAction::Delete)
+        .check_corpus_integrity(IntegrityMode::Full)
+        .unwrap();
+
+    assert_eq!(report.failure_count(), 1);
+    assert_eq!(
+        report.corrupt_actions,
+        vec![(corrupt.clone(), CorruptOutcome::Deleted)]
+    );
+    assert!(!corrupt.exists());
+    assert!(plain.is_file());
+}
+
 /// A backwards date range from `with_date_range` is stored as a deferred
 /// `ConfigBuildError`. The integrity runner must surface it before planning so
 /// the user gets the purpose-built "invalid date range" message instead of a
@@ -72,6 +130,7 @@ fn integrity_rejects_backwards_date_range_with_invalid_date_range_error() {
         .unwrap_err();
 
     let build = err
+        .inner()
         .downcast_ref::<ConfigBuildError>()
         .expect("backwards date range should surface a ConfigBuildError");
     assert_eq!(
@@ -121,6 +180,72 @@ fn quick_validate_reports_prefix_only_when_budget_exhausted() {
     );
 }
 
+/// Full mode inventories every passing file: the basic corpus comment month
+/// decodes to its three records, and Quick mode leaves `file_stats` empty.
+#[test]
+fn full_integrity_reports_decoded_line_counts() {
+    let base = make_corpus_basic();
+    let path = base.join("comments").join("RC_2006-01.zst");
+
+    let (decoded_bytes, lines) = validate_zst_full_stats(&path).unwrap();
+    assert_eq!(lines, 3);
+    assert!(decoded_bytes > 0);
+
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+    };
+    let full = etl().check_corpus_integrity(IntegrityMode::Full).unwrap();
+    assert!(full.is_ok());
+    assert_eq!(full.file_stats.len(), 1);
+    let (stats_path, stats) = &full.file_stats[0];
+    assert_eq!(stats_path.file_name(), path.file_name());
+    assert_eq!(stats.lines, 3);
+    assert_eq!(stats.decoded_bytes, decoded_bytes);
+
+    let quick = etl()
+        .check_corpus_integrity(IntegrityMode::Quick {
+            sample_bytes: 64 * 1024,
+        })
+        .unwrap();
+    assert!(quick.file_stats.is_empty());
+}
+
+/// Plain `.jsonl` months are validated as lines rather than zstd frames: a
+/// healthy one passes both modes, one holding invalid UTF-8 fails.
+#[test]
+fn integrity_validates_plain_jsonl_months_as_lines() {
+    let base = tempfile::tempdir().unwrap().keep();
+    let dir = base.join("comments");
+    std::fs::create_dir_all(&dir).unwrap();
+    let good = dir.join("RC_2006-01.jsonl");
+    std::fs::write(&good, "{\"id\":\"a\"}\n{\"id\":\"b\"}\n").unwrap();
+    let bad = dir.join("RC_2006-02.jsonl");
+    std::fs::write(&bad, b"{\"id\":\"c\"}\n\xff\xfe\n").unwrap();
+
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+    };
+    let full = etl().check_corpus_integrity(IntegrityMode::Full).unwrap();
+    assert_eq!(full.failure_count(), 1);
+    assert_eq!(full.failures[0].0, bad);
+    assert_eq!(full.file_stats.len(), 1);
+    assert_eq!(full.file_stats[0].0, good);
+    assert_eq!(full.file_stats[0].1.lines, 2);
+
+    let quick = etl()
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .check_corpus_integrity(IntegrityMode::Quick { sample_bytes: 4 })
+        .unwrap();
+    assert!(quick.is_ok());
+}
+
 #[test]
 fn quick_validate_rejects_zero_sample_bytes() {
     let base = make_corpus_basic();
@@ -224,3 +349,44 @@ fn integrity_errors_when_date_range_matches_zero_files() {
         "unexpected error: {err}"
     );
 }
+
+/// A file that decodes to far more data than the per-file timeout allows is
+/// flagged as `"timeout"` instead of blocking the run; without the timeout
+/// the same file passes.
+#[test]
+fn integrity_file_timeout_flags_slow_to_decode_file() {
+    let base = tempfile::tempdir().unwrap().keep();
+    let dir = base.join("comments");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("RC_2006-01.zst");
+    // 256 MiB of repetitive records compresses to a tiny file but still takes
+    // many milliseconds to decode.
+    let line = r#"{"id":"c1","author":"alice","subreddit":"rust","created_utc":1136074600}"#;
+    let chunk = format!("{line}\n").repeat((1 << 20) / (line.len() + 1));
+    let mut enc =
+        zstd::stream::write::Encoder::new(std::fs::File::create(&path).unwrap(), 1).unwrap();
+    enc.include_checksum(true).unwrap();
+    for _ in 0..256 {
+        std::io::Write::write_all(&mut enc, chunk.as_bytes()).unwrap();
+    }
+    enc.finish().unwrap();
+
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+    };
+    let report = etl()
+        .integrity_file_timeout(Duration::from_millis(1))
+        .check_corpus_integrity(IntegrityMode::Full)
+        .unwrap();
+    assert_eq!(
+        report.failures,
+        vec![(path.clone(), INTEGRITY_TIMEOUT_ERROR.to_string())]
+    );
+    assert!(report.file_stats.is_empty());
+
+    let report = etl().check_corpus_integrity(IntegrityMode::Full).unwrap();
+    assert!(report.is_ok());
+}
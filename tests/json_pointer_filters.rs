@@ -2,9 +2,10 @@
 mod common;
 
 use assert_cmd::Command;
-use common::{make_corpus_basic, read_jsonl_values};
+use common::{make_corpus_basic, read_jsonl_values, write_zst_lines};
 use predicates::prelude::*;
-use retl::{RedditETL, Sources, YearMonth};
+use retl::{RedditETL, ScanPlan, Sources, YearMonth};
+use std::sync::Arc;
 
 fn retl() -> Command {
     Command::cargo_bin("retl").expect("retl binary should be built")
@@ -107,3 +108,149 @@ fn cli_json_pointer_validation_errors_name_bad_parts() {
         .failure()
         .stderr(predicate::str::contains("regex").and(predicate::str::contains("/title")));
 }
+
+#[test]
+fn library_has_field_and_missing_field_agree_between_extract_and_count() {
+    let base = make_corpus_basic();
+    let cwd = tempfile::tempdir().unwrap();
+    let plan = |pointer_present: bool| {
+        let scan = RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+            .scan();
+        if pointer_present {
+            scan.has_field("/distinguished")
+        } else {
+            scan.missing_field("/distinguished")
+        }
+    };
+
+    let present = cwd.path().join("present.jsonl");
+    plan(true).extract_to_jsonl(&present).unwrap();
+    let mut ids: Vec<String> = read_jsonl_values(&present)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    // Only comments carry `distinguished`; c3 is dropped by the pseudo-user filter.
+    assert_eq!(ids, ["c1", "c2"]);
+    let counted = plan(true).count_by_month().unwrap();
+    assert_eq!(counted.get(&YearMonth::new(2006, 1)).copied(), Some(2));
+
+    let absent = cwd.path().join("absent.jsonl");
+    plan(false).extract_to_jsonl(&absent).unwrap();
+    let mut ids: Vec<String> = read_jsonl_values(&absent)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, ["s1", "s2"]);
+}
+
+/// A `filter` closure is ANDed with the built-in filters on both the count
+/// and the export path.
+#[test]
+fn filter_closure_agrees_between_extract_and_count() {
+    let base = tempfile::tempdir().unwrap().keep();
+    let rec = |id: &str, author: &str, sub: &str| {
+        serde_json::json!({"id": id, "author": author, "subreddit": sub, "created_utc": 1136074600})
+            .to_string()
+    };
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            rec("c1", "ann", "rust"),
+            rec("c2", "cara", "rust"),
+            rec("c3", "eve", "golang"),
+            rec("c4", "bob", "rust"),
+            rec("c5", "dave", "rust"),
+        ],
+    );
+    let plan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+            .subreddit("rust")
+            .filter(Arc::new(|record: &serde_json::Value| {
+                record["author"].as_str().is_some_and(|a| a.len() % 2 == 1)
+            }))
+    };
+
+    let out = base.join("odd_authors.jsonl");
+    plan().extract_to_jsonl(&out).unwrap();
+    let ids: Vec<String> = read_jsonl_values(&out)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["c1", "c4"]);
+    let counted = plan().count_by_month().unwrap();
+    assert_eq!(counted.get(&YearMonth::new(2006, 1)).copied(), Some(2));
+}
+
+#[test]
+fn cli_missing_field_predicate_exports_records_without_the_field() {
+    let base = make_corpus_basic();
+    let cwd = tempfile::tempdir().unwrap();
+    let out = cwd.path().join("missing.jsonl");
+
+    retl()
+        .arg("export")
+        .arg("--data-dir")
+        .arg(&base)
+        .args([
+            "--source",
+            "both",
+            "--start",
+            "2006-01",
+            "--end",
+            "2006-01",
+            "--json",
+            "missing:/parent_id",
+            "--format",
+            "jsonl",
+            "--no-progress",
+            "--out",
+        ])
+        .arg(&out)
+        .assert()
+        .success();
+
+    let values = read_jsonl_values(&out);
+    assert!(!values.is_empty());
+    assert!(values.iter().all(|v| v.get("parent_id").is_none()));
+}
+
+#[test]
+fn full_parse_queries_agree_between_extract_and_count() {
+    let base = make_corpus_basic();
+    let cwd = tempfile::tempdir().unwrap();
+    let scan = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+            .scan()
+    };
+    let cases: [(&str, fn(ScanPlan) -> ScanPlan); 4] = [
+        ("eq", |p| p.json_eq("/edited", false)),
+        ("num", |p| p.json_number_gte("/ups", 3.0)),
+        ("regex", |p| p.json_regex("/domain", r"\.com$")),
+        ("missing", |p| p.missing_field("/num_comments")),
+    ];
+
+    for (name, refine) in cases {
+        let out = cwd.path().join(format!("{name}.jsonl"));
+        refine(scan()).extract_to_jsonl(&out).unwrap();
+        let extracted = read_jsonl_values(&out).len() as u64;
+        let counted: u64 = refine(scan()).count_by_month().unwrap().values().sum();
+        assert_eq!(
+            extracted, counted,
+            "extract and count disagree for full-parse query {name}"
+        );
+    }
+}
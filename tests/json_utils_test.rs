@@ -1,8 +1,8 @@
 //! Direct tests for the small `json_utils` helpers: `author_lower`,
-//! `subreddit_lower`, `is_comment_record`. These are publicly re-exported but
-//! until now had no direct coverage.
+//! `subreddit_lower`, `is_comment_record`, `record_kind`. These are publicly
+//! re-exported but until now had no direct coverage.
 
-use retl::{author_lower, is_comment_record, subreddit_lower};
+use retl::{author_lower, is_comment_record, record_kind, subreddit_lower, FileKind};
 use serde_json::json;
 
 #[test]
@@ -53,35 +53,66 @@ fn subreddit_lower_returns_none_when_missing_or_non_string() {
 }
 
 #[test]
-fn is_comment_record_requires_both_body_and_parent_id() {
-    // Comment-shaped record (RC): has both
+fn record_kind_classifies_canonical_records() {
     let comment = json!({
-        "body": "hi", "parent_id": "t3_abc", "author": "alice"
+        "body": "hi", "parent_id": "t3_abc", "link_id": "t3_abc", "author": "alice"
     });
+    assert_eq!(record_kind(&comment), Some(FileKind::Comment));
     assert!(is_comment_record(&comment));
 
-    // Submission-shaped record (RS): has neither
     let submission = json!({
         "title": "hi", "selftext": "", "author": "bob", "domain": "x.com"
     });
+    assert_eq!(record_kind(&submission), Some(FileKind::Submission));
     assert!(!is_comment_record(&submission));
 
-    // Has body but no parent_id (e.g., a self-post might have body in some schemas)
-    let body_only = json!({"body": "x", "author": "c"});
-    assert!(!is_comment_record(&body_only));
+    assert_eq!(record_kind(&json!({})), None);
+    assert_eq!(record_kind(&json!("not an object")), None);
+}
+
+#[test]
+fn record_kind_ignores_a_stray_body_on_submissions() {
+    let submission = json!({
+        "name": "t3_abc", "title": "hi", "body": "mirrored selftext", "author": "bob"
+    });
+    assert_eq!(record_kind(&submission), Some(FileKind::Submission));
+    assert!(!is_comment_record(&submission));
+
+    // Without a fullname, submission-only fields still win over `body`.
+    let unnamed = json!({"title": "hi", "body": "x", "num_comments": 3});
+    assert_eq!(record_kind(&unnamed), Some(FileKind::Submission));
+
+    // `body` alone is not evidence of either kind.
+    assert_eq!(record_kind(&json!({"body": "x", "author": "c"})), None);
+}
+
+#[test]
+fn record_kind_recognizes_comments_missing_body_or_parent_id() {
+    // Whitelisted down to `link_id`: still a comment.
+    let no_parent = json!({"link_id": "t3_abc", "author": "c"});
+    assert_eq!(record_kind(&no_parent), Some(FileKind::Comment));
 
-    // Has parent_id but no body
+    // Prefixed `parent_id` without `body`.
     let parent_only = json!({"parent_id": "t1_abc", "author": "c"});
-    assert!(!is_comment_record(&parent_only));
+    assert_eq!(record_kind(&parent_only), Some(FileKind::Comment));
 
-    // Empty object
-    assert!(!is_comment_record(&json!({})));
+    // `name` is authoritative even when other fields are stripped.
+    assert_eq!(
+        record_kind(&json!({"name": "t1_xyz"})),
+        Some(FileKind::Comment)
+    );
 }
 
 #[test]
-fn is_comment_record_does_not_inspect_field_values() {
-    // Helper only checks presence — values can be null and it still matches.
-    // This is documented behavior in the helper.
-    let v = json!({"body": null, "parent_id": null});
-    assert!(is_comment_record(&v));
+fn record_kind_inspects_values_not_just_presence() {
+    // Null or unprefixed ids carry no signal.
+    assert_eq!(
+        record_kind(&json!({"body": null, "parent_id": null})),
+        None
+    );
+    assert_eq!(
+        record_kind(&json!({"body": "x", "parent_id": "abc"})),
+        None
+    );
+    assert!(!is_comment_record(&json!({"body": null, "parent_id": null})));
 }
@@ -97,3 +97,253 @@ fn usernames_lambda_callback() {
     authors.sort();
     assert_eq!(authors, vec!["alice", "bob", "charlie"]);
 }
+
+/// `for_each_record` hands each matched line over as a lazily-parsed `Record`:
+/// fast-path accessors (`author`, `created_ym`) must not trigger a full parse,
+/// while an arbitrary-field `get` parses once and serves later lookups.
+#[test]
+fn for_each_record_serves_fast_path_and_full_parse_fields() {
+    let base = common::make_corpus_basic();
+    let seen = std::sync::Mutex::new(Vec::new());
+
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .for_each_record(|rec| {
+            let author = rec.author().unwrap_or_default().to_string();
+            assert_eq!(rec.created_ym(), Some(YearMonth::new(2006, 1)));
+            assert!(!rec.is_fully_parsed(), "fast-path access must not parse the full line");
+
+            let link_id = rec.get("link_id").and_then(|v| v.as_str()).map(str::to_string);
+            assert!(rec.is_fully_parsed());
+            assert!(rec.get("/retrieved_on").and_then(|v| v.as_i64()).is_some());
+
+            seen.lock().unwrap().push((author, link_id));
+            Ok(())
+        })
+        .unwrap();
+
+    let mut seen = seen.into_inner().unwrap();
+    seen.sort();
+    assert_eq!(
+        seen,
+        vec![
+            ("alice".to_string(), Some("t3_s1".to_string())),
+            ("charlie".to_string(), Some("t3_s1".to_string())),
+        ]
+    );
+}
+
+/// `records_channel` runs the scan on a background thread; draining the
+/// receiver yields every matched record and the join reports success.
+#[test]
+fn records_channel_streams_all_matches_to_the_receiver() {
+    let base = common::make_corpus_basic();
+
+    let (handle, rx) = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .records_channel(1)
+        .unwrap();
+
+    let records: Vec<serde_json::Value> = rx.iter().collect();
+    handle.join().expect("scan thread panicked").unwrap();
+
+    assert_eq!(records.len(), 5);
+    let mut ids: Vec<&str> = records.iter().filter_map(|v| v["id"].as_str()).collect();
+    ids.sort();
+    assert_eq!(ids, vec!["c1", "c2", "c3", "s1", "s2"]);
+}
+
+/// `for_each_batch(2)` over the five basic-corpus records delivers two full
+/// batches and a final partial one.
+#[test]
+fn for_each_batch_flushes_final_partial_batch() {
+    let base = common::make_corpus_basic();
+    let mut sizes = Vec::new();
+    let mut ids = Vec::new();
+
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .scan()
+        .include_pseudo_users()
+        .for_each_batch(2, |batch| {
+            sizes.push(batch.len());
+            for v in batch {
+                ids.push(v["id"].as_str().unwrap().to_string());
+            }
+        })
+        .unwrap();
+
+    assert_eq!(sizes, vec![2, 2, 1]);
+    ids.sort();
+    assert_eq!(ids, vec!["c1", "c2", "c3", "s1", "s2"]);
+}
+
+/// A panic on the scan thread comes back as an error, not a caller panic.
+#[test]
+fn for_each_batch_returns_scan_thread_panic_as_error() {
+    let base = common::make_corpus_basic();
+
+    let err = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .scan()
+        .include_pseudo_users()
+        .filter(std::sync::Arc::new(|_: &serde_json::Value| -> bool {
+            panic!("filter panicked")
+        }))
+        .for_each_batch(2, |_| {})
+        .unwrap_err();
+
+    assert!(err.to_string().contains("scan thread panicked"), "{err:#}");
+}
+
+/// Dropping the receiver early stops the scan without surfacing an error.
+#[test]
+fn records_channel_stops_cleanly_when_receiver_is_dropped() {
+    let base = common::make_corpus_basic();
+
+    let (handle, rx) = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .scan()
+        .records_channel(0)
+        .unwrap();
+
+    drop(rx);
+    handle.join().expect("scan thread panicked").unwrap();
+}
+
+/// With `ordered_output(true)` callback delivery follows `(month, RC before
+/// RS)` order even when `file_concurrency` would otherwise race files.
+#[test]
+fn ordered_output_delivers_records_in_year_month_order() {
+    let months = [
+        YearMonth::new(2006, 1),
+        YearMonth::new(2006, 2),
+        YearMonth::new(2006, 3),
+    ];
+    let base = common::make_corpus_multi_month(&months);
+    let seen = std::sync::Mutex::new(Vec::new());
+
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .file_concurrency(4)
+        .ordered_output(true)
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .for_each_record(|rec| {
+            seen.lock().unwrap().push((rec.created_ym().unwrap(), rec.kind()));
+            Ok(())
+        })
+        .unwrap();
+
+    let seen = seen.into_inner().unwrap();
+    assert_eq!(seen.len(), 12);
+    let month_order: Vec<YearMonth> = seen.iter().map(|(ym, _)| *ym).collect();
+    let mut sorted = month_order.clone();
+    sorted.sort();
+    assert_eq!(month_order, sorted);
+    for pair in seen.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            assert!(
+                !(pair[0].1 == retl::FileKind::Submission && pair[1].1 == retl::FileKind::Comment),
+                "RS record delivered before RC within {}",
+                pair[0].0
+            );
+        }
+    }
+}
+
+/// `for_each_group` scans the corpus, groups matching records by author, and
+/// the groups (possibly partial, so merged here by key) rebuild exactly the
+/// per-author record sets; records without an author are dropped.
+#[test]
+fn for_each_group_reconstructs_per_author_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("corpus");
+    let rec = |id: &str, author: Option<&str>, ts: i64| {
+        let mut v = serde_json::json!({"id": id, "subreddit": "rust", "created_utc": ts});
+        if let Some(author) = author {
+            v["author"] = author.into();
+        }
+        v.to_string()
+    };
+    common::write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            rec("c1", Some("alice"), 1136074600),
+            rec("c2", Some("Bob"), 1136074700),
+            rec("c3", Some("carol"), 1136074800),
+            rec("c4", None, 1136074900),
+        ],
+    );
+    common::write_zst_lines(
+        &base.join("comments").join("RC_2006-02.zst"),
+        &[rec("c5", Some("ALICE"), 1138752000), rec("c6", Some("bob"), 1138752100)],
+    );
+    common::write_zst_lines(
+        &base.join("submissions").join("RS_2006-01.zst"),
+        &[rec("s1", Some("alice"), 1136073600)],
+    );
+
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    let mut calls = 0usize;
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .progress(false)
+        .shard_count(4)
+        .work_dir(dir.path().join("work"))
+        .scan()
+        .include_pseudo_users()
+        .for_each_group(
+            retl::KeyExtractor::author_lowercase_fast(),
+            &retl::BucketingCfg::default(),
+            |key, records| {
+                calls += 1;
+                let ids = groups.entry(key.to_string()).or_default();
+                for line in records {
+                    let v: serde_json::Value = serde_json::from_str(&line)?;
+                    ids.push(v["id"].as_str().unwrap().to_string());
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    for ids in groups.values_mut() {
+        ids.sort();
+    }
+    let expected: HashMap<String, Vec<String>> = [
+        ("alice", vec!["c1", "c5", "s1"]),
+        ("bob", vec!["c2", "c6"]),
+        ("carol", vec!["c3"]),
+    ]
+    .into_iter()
+    .map(|(k, ids)| (k.to_string(), ids.into_iter().map(String::from).collect()))
+    .collect();
+    assert_eq!(groups, expected);
+    assert!(calls >= expected.len());
+    let leftover: Vec<_> = fs::read_dir(dir.path().join("work"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert!(leftover.is_empty(), "scratch shards should be removed: {leftover:?}");
+}
@@ -16,7 +16,9 @@
 use std::time::{Duration, Instant};
 
 use retl::{
-    is_low_memory, maybe_throttle_low_memory, set_available_memory_fraction_for_tests,
+    available_memory_bytes, is_low_memory, maybe_throttle_low_memory, memory_budget_bytes,
+    set_available_memory_fraction_for_tests, set_memory_budget_bytes, sysinfo_ok,
+    total_memory_bytes,
 };
 use serial_test::serial;
 
@@ -66,3 +68,29 @@ fn maybe_throttle_low_memory_actually_sleeps_when_low() {
     // Reset so the global cache doesn't leak into other tests/binaries.
     set_available_memory_fraction_for_tests(1.0);
 }
+
+#[test]
+#[serial]
+fn memory_budget_below_process_rss_reports_low_memory() {
+    // Any test binary's resident set dwarfs a 1 MiB budget, so the budgeted
+    // reading must have (almost) nothing available.
+    set_memory_budget_bytes(Some(1024 * 1024));
+    assert_eq!(memory_budget_bytes(), Some(1024 * 1024));
+    let low = is_low_memory(0.10);
+    let total = total_memory_bytes();
+    let available = available_memory_bytes();
+    set_memory_budget_bytes(None);
+    assert_eq!(memory_budget_bytes(), None);
+
+    if !sysinfo_ok() {
+        // No process readings in this sandbox; the budget falls back to
+        // the (unusable) system reading.
+        return;
+    }
+    assert!(low, "a budget below the process RSS must report low memory");
+    assert_eq!(total, 1024 * 1024, "total reports the budget");
+    assert!(available < 1024 * 1024 / 10);
+
+    // Back on system RAM, nothing is below a zero threshold.
+    assert!(!is_low_memory(0.0));
+}
@@ -193,3 +193,329 @@ fn bounds_tuple_represents_independent_endpoints() {
     assert_eq!(bounded.start, Some(YearMonth::new(2006, 1)));
     assert_eq!(bounded.end, Some(YearMonth::new(2006, 3)));
 }
+
+#[test]
+fn for_each_planned_file_counts_lines_per_file() {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    let (jan, feb) = (YearMonth::new(2006, 1), YearMonth::new(2006, 2));
+    let base = make_corpus_multi_month(&[jan, feb, YearMonth::new(2006, 3)]);
+    let etl = retl::RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(jan), Some(feb))
+        .file_concurrency(2)
+        .progress(false);
+
+    let counts = Mutex::new(BTreeMap::new());
+    etl.for_each_planned_file(|job| {
+        let mut lines = 0u64;
+        retl::for_each_line_reader(std::fs::File::open(&job.path)?, 1 << 16, |_| {
+            lines += 1;
+            Ok(())
+        })?;
+        counts
+            .lock()
+            .unwrap()
+            .insert((job.ym, job.kind.long_label()), lines);
+        Ok(())
+    })
+    .unwrap();
+
+    // Two records per file; 2006-03 is outside the configured range.
+    let expected: BTreeMap<_, _> = [jan, feb]
+        .into_iter()
+        .flat_map(|ym| [((ym, "comments"), 2), ((ym, "submissions"), 2)])
+        .collect();
+    assert_eq!(counts.into_inner().unwrap(), expected);
+}
+
+/// `for_each_raw_line` hands every non-empty line to the callback as bytes,
+/// across months and sources, including a line that is not valid UTF-8.
+#[test]
+fn for_each_raw_line_counts_lines_containing_a_byte_pattern() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    let dir = tempfile::tempdir().unwrap();
+    let write = |rel: &str, body: &[u8]| {
+        let path = dir.path().join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, zstd::encode_all(body, 3).unwrap()).unwrap();
+    };
+    write(
+        "comments/RC_2006-01.zst",
+        b"{\"subreddit\":\"rust\"}\n{\"subreddit\":\"golang\"}\n\n{\"body\":\"rust \xff\xfe\"}\r\n",
+    );
+    write(
+        "comments/RC_2006-02.zst",
+        b"{\"subreddit\":\"rust\"}\n{\"subreddit\":\"python\"}",
+    );
+    write("submissions/RS_2006-01.zst", b"{\"title\":\"rustacean\"}\n");
+
+    let (lines, rust) = (AtomicU64::new(0), AtomicU64::new(0));
+    retl::RedditETL::new()
+        .base_dir(dir.path())
+        .sources(Sources::Both)
+        .file_concurrency(2)
+        .progress(false)
+        .for_each_raw_line(|line| {
+            assert!(!line.is_empty() && !line.ends_with(b"\r"));
+            lines.fetch_add(1, Ordering::Relaxed);
+            if line.windows(4).any(|w| w == b"rust") {
+                rust.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .unwrap();
+    assert_eq!(lines.into_inner(), 6);
+    assert_eq!(rust.into_inner(), 4);
+}
+
+/// `last_n_months` resolves the newest months on disk when the run plans its
+/// files; a corpus shorter than `n` is processed whole.
+#[test]
+fn last_n_months_processes_only_latest_months() {
+    let months: Vec<YearMonth> = (1..=4).map(|m| YearMonth::new(2006, m)).collect();
+    let base = make_corpus_multi_month(&months);
+    let counted_months = |n: usize| {
+        retl::RedditETL::new()
+            .last_n_months(n)
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+            .count_by_month()
+            .unwrap()
+            .into_keys()
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(counted_months(2), months[2..]);
+    assert_eq!(counted_months(10), months);
+
+    // With explicit `files`, the newest months are picked among those files.
+    let listed = retl::RedditETL::new()
+        .files(
+            months[1..]
+                .iter()
+                .map(|ym| base.join("comments").join(format!("RC_{ym}.zst")))
+                .collect(),
+        )
+        .last_n_months(1)
+        .plan()
+        .unwrap();
+    assert_eq!(listed.iter().map(|f| f.ym).collect::<Vec<_>>(), [months[3]]);
+
+    // A discovery failure surfaces from the run instead of being swallowed.
+    let missing = base.join("no_such_corpus");
+    let err = retl::RedditETL::new()
+        .base_dir(&missing)
+        .last_n_months(2)
+        .plan()
+        .unwrap_err();
+    assert!(
+        err.inner().downcast_ref::<retl::PlanningError>().is_some(),
+        "{err:#}"
+    );
+}
+
+/// Under `Sources::Both`, `last_n_months` counts each source's own newest
+/// months: with RS dumps lagging RC by two months, `last_n_months(2)` keeps
+/// the two newest RS months instead of the RC window, where RS has nothing.
+#[test]
+fn last_n_months_windows_each_source_separately() {
+    let months: Vec<YearMonth> = (1..=4).map(|m| YearMonth::new(2006, m)).collect();
+    let base = make_corpus_multi_month(&months);
+    for ym in &months[2..] {
+        std::fs::remove_file(base.join("submissions").join(format!("RS_{ym}.zst"))).unwrap();
+    }
+
+    let planned = retl::RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .last_n_months(2)
+        .plan()
+        .unwrap();
+    let mut got: Vec<(FileKind, YearMonth)> = planned.iter().map(|f| (f.kind, f.ym)).collect();
+    got.sort_by_key(|&(kind, ym)| (kind == FileKind::Submission, ym));
+    assert_eq!(
+        got,
+        [
+            (FileKind::Comment, months[2]),
+            (FileKind::Comment, months[3]),
+            (FileKind::Submission, months[0]),
+            (FileKind::Submission, months[1]),
+        ]
+    );
+
+    // Explicit `files` are windowed per source the same way.
+    let listed = retl::RedditETL::new()
+        .files(
+            months
+                .iter()
+                .map(|ym| base.join("comments").join(format!("RC_{ym}.zst")))
+                .chain(
+                    months[..2]
+                        .iter()
+                        .map(|ym| base.join("submissions").join(format!("RS_{ym}.zst"))),
+                )
+                .collect(),
+        )
+        .last_n_months(1)
+        .plan()
+        .unwrap();
+    let mut got: Vec<(FileKind, YearMonth)> = listed.iter().map(|f| (f.kind, f.ym)).collect();
+    got.sort_by_key(|&(kind, ym)| (kind == FileKind::Submission, ym));
+    assert_eq!(
+        got,
+        [
+            (FileKind::Comment, months[3]),
+            (FileKind::Submission, months[1]),
+        ]
+    );
+}
+
+/// `year` and `years` are shorthand for a January–December `date_range`.
+#[test]
+fn year_plans_the_same_files_as_an_explicit_jan_dec_range() {
+    let months: Vec<YearMonth> = (1..=12).map(|m| YearMonth::new(2006, m)).collect();
+    let base = make_corpus_multi_month(&months);
+    let etl = || {
+        retl::RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Both)
+            .progress(false)
+    };
+
+    let explicit = etl()
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 12)))
+        .plan()
+        .unwrap();
+    assert_eq!(explicit.len(), 24);
+    assert_eq!(etl().year(2006).plan().unwrap(), explicit);
+    assert_eq!(etl().years(2006..=2006).plan().unwrap(), explicit);
+    assert_eq!(etl().scan().year(2006).plan().unwrap(), explicit);
+}
+
+/// `files` processes exactly the listed months, whatever the date range and
+/// `sources` would otherwise select.
+#[test]
+fn explicit_files_are_processed_instead_of_the_discovered_range() {
+    let months: Vec<YearMonth> = (1..=3).map(|m| YearMonth::new(2006, m)).collect();
+    let base = make_corpus_multi_month(&months);
+    let files = vec![
+        base.join("submissions").join("RS_2006-03.zst"),
+        base.join("comments").join("RC_2006-01.zst"),
+    ];
+    let etl = retl::RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 3)))
+        .files(files.clone())
+        .progress(false);
+
+    let planned: Vec<_> = etl
+        .plan()
+        .unwrap()
+        .into_iter()
+        .map(|f| (f.kind, f.ym, f.path))
+        .collect();
+    assert_eq!(
+        planned,
+        [
+            (FileKind::Comment, YearMonth::new(2006, 1), files[1].clone()),
+            (FileKind::Submission, YearMonth::new(2006, 3), files[0].clone()),
+        ]
+    );
+
+    let out = base.join("explicit.jsonl");
+    etl.scan().extract_to_jsonl(&out).unwrap();
+    let records = read_jsonl_values(&out);
+    assert!(!records.is_empty());
+    for record in &records {
+        let id = record["id"].as_str().unwrap();
+        assert!(
+            (id.starts_with('c') && id.ends_with("2006-01"))
+                || (id.starts_with('s') && id.ends_with("2006-03")),
+            "unexpected record {id}"
+        );
+    }
+}
+
+/// `since` and `until` set one bound and leave the other open (or as an
+/// earlier call set it).
+#[test]
+fn since_and_until_set_one_bound_of_the_scan_range() {
+    let months: Vec<YearMonth> = (1..=4).map(|m| YearMonth::new(2006, m)).collect();
+    let base = make_corpus_multi_month(&months);
+    let counted_months = |scan: retl::ScanPlan| {
+        scan.count_by_month()
+            .unwrap()
+            .into_keys()
+            .collect::<Vec<_>>()
+    };
+    let scan = || {
+        retl::RedditETL::new()
+            .base_dir(&base)
+            .sources(Sources::Comments)
+            .progress(false)
+            .scan()
+    };
+
+    assert_eq!(
+        counted_months(scan().since(YearMonth::new(2006, 2))),
+        months[1..]
+    );
+    assert_eq!(
+        counted_months(scan().until(YearMonth::new(2006, 2))),
+        months[..2]
+    );
+    assert_eq!(
+        counted_months(
+            scan()
+                .since(YearMonth::new(2006, 2))
+                .until(YearMonth::new(2006, 3))
+        ),
+        months[1..3]
+    );
+}
+
+#[test]
+fn interleaved_sources_stitches_file_outputs_month_by_month() {
+    let base = make_corpus_multi_month(&[YearMonth::new(2006, 1), YearMonth::new(2006, 2)]);
+    let out = base.join("stitched.jsonl");
+    let file_order = |etl: retl::RedditETL| {
+        etl.base_dir(&base)
+            .sources(Sources::Both)
+            .progress(false)
+            .scan()
+            .extract_to_jsonl(&out)
+            .unwrap();
+        let mut order: Vec<String> = Vec::new();
+        for record in read_jsonl_values(&out) {
+            let id = record["id"].as_str().unwrap();
+            let (prefix, month) = id.split_once('_').unwrap();
+            let source = if prefix.starts_with('c') { "RC" } else { "RS" };
+            let part = format!("{source}_{month}");
+            if order.last() != Some(&part) {
+                order.push(part);
+            }
+        }
+        order
+    };
+
+    // By default parts are stitched by name, all RC months first.
+    assert_eq!(
+        file_order(retl::RedditETL::new()),
+        ["RC_2006-01", "RC_2006-02", "RS_2006-01", "RS_2006-02"]
+    );
+    // `ordered_output` stitches chronologically, like its callback delivery.
+    assert_eq!(
+        file_order(retl::RedditETL::new().ordered_output(true)),
+        ["RC_2006-01", "RS_2006-01", "RC_2006-02", "RS_2006-02"]
+    );
+    assert_eq!(
+        file_order(retl::RedditETL::new().interleaved_sources(true)),
+        ["RC_2006-01", "RS_2006-01", "RC_2006-02", "RS_2006-02"]
+    );
+}
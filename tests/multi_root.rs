@@ -0,0 +1,82 @@
+//! A corpus split across several roots (`base_dir` + `add_base_dir`) is
+//! discovered and processed as one run; overlapping months follow the
+//! configured `DuplicateMonthPolicy`.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use common::make_corpus_multi_month;
+use retl::{DuplicateMonthPolicy, ParentIds, PlanningError, RedditETL, Sources, YearMonth};
+use std::collections::HashMap;
+
+#[test]
+fn months_from_every_root_are_processed_in_one_run() {
+    let early = make_corpus_multi_month(&[YearMonth::new(2006, 1)]);
+    let late = make_corpus_multi_month(&[YearMonth::new(2006, 2)]);
+
+    let etl = RedditETL::new()
+        .base_dir(&early)
+        .add_base_dir(&late)
+        .sources(Sources::Both)
+        .progress(false);
+
+    let planned = etl.plan().unwrap();
+    assert_eq!(planned.len(), 4, "{planned:?}");
+
+    let counts = etl.scan().subreddit("programming").count_by_month().unwrap();
+    assert_eq!(counts.get(&YearMonth::new(2006, 1)).copied(), Some(4));
+    assert_eq!(counts.get(&YearMonth::new(2006, 2)).copied(), Some(4));
+}
+
+#[test]
+fn duplicate_months_error_by_default_and_later_root_wins_when_asked() {
+    let first = make_corpus_multi_month(&[YearMonth::new(2006, 1)]);
+    let second = make_corpus_multi_month(&[YearMonth::new(2006, 1)]);
+
+    let err = RedditETL::new()
+        .base_dir(&first)
+        .add_base_dir(&second)
+        .progress(false)
+        .plan()
+        .unwrap_err();
+    match err.inner().downcast_ref::<PlanningError>() {
+        Some(PlanningError::DuplicateMonth { ym, .. }) => assert_eq!(*ym, YearMonth::new(2006, 1)),
+        other => panic!("expected DuplicateMonth, got {other:?}"),
+    }
+
+    let planned = RedditETL::new()
+        .base_dir(&first)
+        .add_base_dir(&second)
+        .duplicate_month_policy(DuplicateMonthPolicy::LaterWins)
+        .progress(false)
+        .plan()
+        .unwrap();
+    assert_eq!(planned.len(), 2);
+    assert!(planned.iter().all(|p| p.path.starts_with(&second)));
+}
+
+/// Parent resolution plans through every corpus root, so a parent stored in
+/// an added root is found.
+#[test]
+fn parent_resolution_reads_months_from_every_root() {
+    let early = make_corpus_multi_month(&[YearMonth::new(2006, 1)]);
+    let late = make_corpus_multi_month(&[YearMonth::new(2006, 2)]);
+    let cache = tempfile::tempdir().unwrap();
+    let mut ids = ParentIds::new();
+    ids.extend_prefixed(["t3_s_2006-01", "t3_s_2006-02"]);
+
+    let parents = RedditETL::new()
+        .base_dir(&early)
+        .add_base_dir(&late)
+        .progress(false)
+        .resolve_parent_maps(&ids, cache.path(), false)
+        .unwrap();
+
+    let shards = parents.submission_shards.unwrap();
+    let mut months: Vec<_> = shards.keys().copied().collect();
+    months.sort();
+    assert_eq!(months, [YearMonth::new(2006, 1), YearMonth::new(2006, 2)]);
+    let late_shard: HashMap<String, (String, String)> =
+        serde_json::from_slice(&std::fs::read(&shards[&YearMonth::new(2006, 2)]).unwrap()).unwrap();
+    assert!(late_shard.contains_key("s_2006-02"), "{late_shard:?}");
+}
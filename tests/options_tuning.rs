@@ -1,4 +1,12 @@
-use retl::{AdaptiveMemCfg, BucketingCfg, DedupeCfg, ETLOptions};
+#[path = "common/mod.rs"]
+mod common;
+
+use common::*;
+use retl::{
+    fsync_outputs, io_retries, AdaptiveMemCfg, BucketingCfg, DedupeCfg, ETLOptions, ExportFormat,
+    RedditETL, YearMonth,
+};
+use std::fs;
 use std::io;
 use std::sync::{Arc, Mutex};
 use tracing_subscriber::fmt::MakeWriter;
@@ -147,3 +155,202 @@ fn inflight_groups_builder_clamps_zero_to_one() {
     assert_eq!(opts.inflight_groups, 1);
     assert_eq!(BucketingCfg::from(&opts).inflight_groups, 1);
 }
+
+/// Serializes the tests that run with non-default process-wide IO settings,
+/// so one test's run cannot show up in another's observations.
+static IO_SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn single_try_io_retries_leave_normal_operations_working() {
+    let _serial = IO_SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    // The retry budget only matters when an op fails transiently; with one
+    // try and no delay, an ordinary extract must still succeed.
+    let base = make_corpus_basic();
+    let out = base.join("out.jsonl");
+    let etl = RedditETL::new()
+        .base_dir(&base)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .io_retries(1, 0);
+    let result = etl.scan().subreddit("programming").extract_to_jsonl(&out);
+
+    result.expect("extract with a single-try budget");
+    assert!(!read_lines(&out).is_empty());
+    assert_eq!(
+        ETLOptions::default().with_io_retries(0, 5).io_retries,
+        (1, 5),
+        "zero tries means one attempt"
+    );
+}
+
+#[test]
+fn runs_never_change_the_process_wide_io_settings() {
+    let _serial = IO_SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let base = make_corpus_basic();
+    let before = (io_retries(), fsync_outputs());
+    let etl = RedditETL::new()
+        .base_dir(&base)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .io_retries(3, 7)
+        .fsync(true);
+    assert_eq!(
+        (io_retries(), fsync_outputs()),
+        before,
+        "builders must not touch the process-wide settings"
+    );
+
+    let during = Mutex::new(Vec::new());
+    etl.for_each_planned_file(|_job| {
+        during.lock().unwrap().push((io_retries(), fsync_outputs()));
+        Ok(())
+    })
+    .expect("walk planned files");
+    etl.scan()
+        .for_each_record(|_record| {
+            during.lock().unwrap().push((io_retries(), fsync_outputs()));
+            Ok(())
+        })
+        .expect("scan records");
+
+    let during = during.into_inner().unwrap();
+    assert!(!during.is_empty());
+    assert!(during.iter().all(|seen| *seen == before), "{during:?}");
+    assert_eq!((io_retries(), fsync_outputs()), before);
+}
+
+#[test]
+fn overlapping_runs_keep_their_own_line_caps() {
+    let base = make_corpus_basic();
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+    };
+    let capped = || etl().max_line_bytes(8);
+    let err = capped()
+        .scan()
+        .count_by_month()
+        .expect_err("an 8-byte cap rejects every record");
+    assert!(format!("{err:#}").contains("max_line_bytes=8"), "{err:#}");
+
+    // While the capped run executes, a run at the default cap on another
+    // thread must not pick its cap up.
+    let seen = Mutex::new(Vec::new());
+    capped()
+        .for_each_planned_file(|_job| {
+            let counts =
+                std::thread::scope(|s| s.spawn(|| etl().scan().count_by_month()).join().unwrap());
+            seen.lock().unwrap().push(
+                counts
+                    .map(|c| c.values().sum::<u64>())
+                    .map_err(|e| format!("{e:#}")),
+            );
+            Ok(())
+        })
+        .expect("walk planned files");
+
+    let seen = seen.into_inner().unwrap();
+    assert!(!seen.is_empty());
+    assert!(
+        seen.iter().all(|total| matches!(total, Ok(n) if *n > 0)),
+        "{seen:?}"
+    );
+}
+
+#[test]
+fn fsync_outputs_leave_extract_and_export_working() {
+    // Durability itself cannot be observed here; what must hold is that the
+    // extra syncs never change what gets published.
+    let _serial = IO_SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let base = make_corpus_basic();
+    let jsonl = base.join("fsync.jsonl");
+    let parts = base.join("fsync_parts");
+    let etl = RedditETL::new()
+        .base_dir(&base)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .fsync(true);
+    let extracted = etl
+        .clone()
+        .scan()
+        .subreddit("programming")
+        .extract_to_jsonl(&jsonl);
+    let partitioned = etl
+        .scan()
+        .subreddit("programming")
+        .export_partitioned(&parts, ExportFormat::Zst);
+
+    extracted.expect("extract with fsync on");
+    partitioned.expect("partitioned extract with fsync on");
+    assert!(!read_lines(&jsonl).is_empty());
+    assert!(fs::read_dir(&parts).unwrap().next().is_some());
+    assert!(!ETLOptions::default().fsync);
+}
+
+#[test]
+fn in_memory_shards_collect_the_same_usernames_while_spilling() {
+    let base = make_corpus_basic();
+    let usernames = |etl: RedditETL| {
+        etl.progress(false)
+            .scan()
+            .usernames()
+            .unwrap()
+            .collect_sorted()
+    };
+    let on_disk = usernames(RedditETL::new().base_dir(&base));
+    let in_memory = usernames(
+        RedditETL::new()
+            .base_dir(&base)
+            .in_memory_shards(true)
+            .in_memory_shard_spill_bytes(1),
+    );
+    assert!(!on_disk.is_empty());
+    assert_eq!(in_memory, on_disk);
+}
+
+/// Sink that counts `write` calls and sleeps on each one, like a socket
+/// paying a syscall per write.
+#[derive(Default)]
+struct SlowCountingSink {
+    writes: usize,
+    bytes: Vec<u8>,
+}
+
+impl io::Write for SlowCountingSink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        self.writes += 1;
+        self.bytes.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_batch_size_groups_records_into_fewer_sink_writes() {
+    let base = make_corpus_n_records(5);
+    let extract = |batch: usize| {
+        let mut sink = SlowCountingSink::default();
+        let written = RedditETL::new()
+            .base_dir(&base)
+            .progress(false)
+            .write_batch_size(batch)
+            .scan()
+            .extract_to_writer(&mut sink)
+            .unwrap();
+        assert_eq!(written, 5);
+        sink
+    };
+    let unbatched = extract(1);
+    let batched = extract(2);
+    assert_eq!(unbatched.writes, 5);
+    assert_eq!(batched.writes, 3);
+    assert_eq!(batched.bytes, unbatched.bytes);
+    assert_eq!(String::from_utf8(batched.bytes).unwrap().lines().count(), 5);
+    assert_eq!(ETLOptions::default().write_batch_size, 1);
+}
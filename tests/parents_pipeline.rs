@@ -7,8 +7,12 @@ mod atomic_staging;
 mod attach_resume;
 #[path = "parents_pipeline/diagnostics.rs"]
 mod diagnostics;
+#[path = "parents_pipeline/direct_attach.rs"]
+mod direct_attach;
 #[path = "parents_pipeline/end_to_end.rs"]
 mod end_to_end;
+#[path = "parents_pipeline/id_collection.rs"]
+mod id_collection;
 #[path = "parents_pipeline/payload_fields.rs"]
 mod payload_fields;
 #[path = "parents_pipeline/resolve_resume.rs"]
@@ -43,6 +43,7 @@ fn unresolved_parent_is_absent_and_counted() {
     let parents = ParentMaps {
         comments: HashMap::new(),
         submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
         comment_shards: Some(HashMap::new()),
         submission_shards: Some(HashMap::new()),
         payload_spec: Default::default(),
@@ -94,6 +95,7 @@ fn attach_parents_rerun_prunes_stale_owned_parts_when_inputs_shrink() {
     let parents = ParentMaps {
         comments: HashMap::new(),
         submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
         comment_shards: Some(HashMap::new()),
         submission_shards: Some(HashMap::new()),
         payload_spec: Default::default(),
@@ -153,6 +155,7 @@ fn attach_parents_resume_rebuilds_corrupt_published_output() {
     let parents = ParentMaps {
         comments,
         submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
         comment_shards: Some(HashMap::new()),
         submission_shards: Some(HashMap::new()),
         payload_spec: Default::default(),
@@ -288,3 +291,134 @@ fn attach_resume_rebuilds_when_resolution_window_expands() {
         Some("December parent")
     );
 }
+
+#[test]
+fn attach_parents_resume_redoes_output_truncated_by_a_crash() {
+    let tmp = tempfile::tempdir().unwrap();
+    let input = tmp.path().join("part_RC_2006-01.jsonl");
+    let lines: Vec<String> = (0..3)
+        .map(|i| {
+            format!(
+                "{{\"id\":\"c{i}\",\"body\":\"child\",\"parent_id\":\"t1_p1\",\"created_utc\":{}}}",
+                1136073600 + i
+            )
+        })
+        .collect();
+    fs::write(&input, lines.join("\n") + "\n").unwrap();
+
+    let mut comments = HashMap::new();
+    comments.insert("p1".to_string(), "parent body".to_string());
+    let parents = ParentMaps {
+        comments,
+        submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
+        comment_shards: Some(HashMap::new()),
+        submission_shards: Some(HashMap::new()),
+        payload_spec: Default::default(),
+    };
+    let attach = || {
+        RedditETL::new()
+            .progress(false)
+            .attach_parents_jsonls_parallel_with_stats(
+                vec![input.clone()],
+                &tmp.path().join("attached"),
+                &parents,
+                true,
+            )
+            .unwrap()
+    };
+
+    let (attached_paths, first) = attach();
+    assert_eq!(first.resolved, 3);
+    let out_path = &attached_paths[0];
+    let full = fs::read_to_string(out_path).unwrap();
+
+    // A crash mid-rewrite: the staged `.inprogress` from a dead process holds
+    // one line, and the published file was cut back to a clean line boundary,
+    // so every remaining line still parses and the sidecar still matches.
+    let staging = tmp.path().join("attached").join("_staging");
+    fs::create_dir_all(&staging).unwrap();
+    let first_line = format!("{}\n", full.lines().next().unwrap());
+    fs::write(
+        staging.join(format!(
+            "part_RC_2006-01.jsonl.retl-{}-0-0.inprogress",
+            u32::MAX
+        )),
+        &first_line,
+    )
+    .unwrap();
+    fs::write(out_path, &first_line).unwrap();
+
+    let (_, resumed) = attach();
+    assert_eq!(
+        resumed.resolved, 3,
+        "truncated output must be redone, not skipped"
+    );
+    assert_eq!(fs::read_to_string(out_path).unwrap(), full);
+}
+
+#[test]
+fn split_unresolved_parents_routes_missing_parent_to_unresolved_dir() {
+    let tmp = tempfile::tempdir().unwrap();
+    let input = tmp.path().join("part_RC_2006-01.jsonl");
+    fs::write(
+        &input,
+        concat!(
+            "{\"id\":\"c1\",\"body\":\"child\",\"parent_id\":\"t1_p1\",\"created_utc\":1136073600}\n",
+            "{\"id\":\"c2\",\"body\":\"orphan\",\"parent_id\":\"t1_missing\",\"created_utc\":1136073601}\n",
+        ),
+    )
+    .unwrap();
+
+    let mut comments = HashMap::new();
+    comments.insert("p1".to_string(), "parent body".to_string());
+    let parents = ParentMaps {
+        comments,
+        submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
+        comment_shards: Some(HashMap::new()),
+        submission_shards: Some(HashMap::new()),
+        payload_spec: Default::default(),
+    };
+    let out_dir = tmp.path().join("attached");
+    let attach = |resume: bool| {
+        RedditETL::new()
+            .progress(false)
+            .split_unresolved_parents(true)
+            .attach_parents_jsonls_parallel_with_stats(
+                vec![input.clone()],
+                &out_dir,
+                &parents,
+                resume,
+            )
+            .unwrap()
+    };
+    let ids = |path: &std::path::Path| -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| {
+                let v: serde_json::Value = serde_json::from_str(l).unwrap();
+                v["id"].as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+
+    let (attached_paths, stats) = attach(false);
+    assert_eq!(
+        stats,
+        ParentAttachStats {
+            resolved: 1,
+            unresolved: 1,
+        }
+    );
+    let unresolved_path = out_dir.join("unresolved").join("part_RC_2006-01.jsonl");
+    assert_eq!(ids(&attached_paths[0]), ["c1"]);
+    assert_eq!(ids(&unresolved_path), ["c2"]);
+
+    // Resume reuses both halves of the split.
+    let (_, resumed) = attach(true);
+    assert_eq!(resumed, ParentAttachStats::default());
+    assert_eq!(ids(&attached_paths[0]), ["c1"]);
+    assert_eq!(ids(&unresolved_path), ["c2"]);
+}
@@ -284,6 +284,7 @@ fn attach_parents_reports_corrupt_cache_shard_with_recovery_hint() {
     let parents = ParentMaps {
         comments: HashMap::new(),
         submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
         comment_shards: Some(comment_shards),
         submission_shards: Some(HashMap::new()),
         payload_spec: Default::default(),
@@ -319,6 +320,7 @@ fn attach_parents_errors_on_malformed_spool_json_with_location() {
     let parents = ParentMaps {
         comments: HashMap::new(),
         submissions: HashMap::new(),
+        comment_parent_links: HashMap::new(),
         comment_shards: Some(HashMap::new()),
         submission_shards: Some(HashMap::new()),
         payload_spec: Default::default(),
@@ -0,0 +1,206 @@
+use crate::common::make_corpus_basic;
+use crate::common::parents::*;
+use retl::{ExportFormat, RedditETL, Sources, YearMonth};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn read_part(path: &Path) -> String {
+    if path.extension().is_some_and(|ext| ext == "zst") {
+        let bytes = zstd::decode_all(fs::File::open(path).unwrap()).unwrap();
+        String::from_utf8(bytes).unwrap()
+    } else {
+        fs::read_to_string(path).unwrap()
+    }
+}
+
+fn part_names(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+fn attach_parents_zst_matches_spool_then_attach() {
+    let base = make_corpus_basic();
+    let (work_dir, spool_dir, spool_with_parents_dir, parents_cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let ym = YearMonth::new(2006, 1);
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&lib_tmp)
+            .sources(Sources::Both)
+            .date_range(Some(ym), Some(ym))
+            .progress(false)
+    };
+
+    let (spool_parts, _) = etl()
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .extract_spool_monthly(&spool_dir)
+        .unwrap();
+    let ids = etl()
+        .collect_parent_ids_from_jsonls(spool_parts.clone())
+        .unwrap();
+    let parents = etl()
+        .resolve_parent_maps(&ids, &parents_cache_dir, false)
+        .unwrap();
+    let (spooled, spooled_stats) = etl()
+        .attach_parents_jsonls_parallel_with_stats(
+            spool_parts,
+            &spool_with_parents_dir,
+            &parents,
+            false,
+        )
+        .unwrap();
+    assert!(spooled_stats.resolved > 0, "fixture should resolve parents");
+
+    let direct_dir = work_dir.join("direct_with_parents");
+    let (direct, direct_stats) = etl()
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .attach_parents_zst(&direct_dir, &parents, ExportFormat::Jsonl, false)
+        .unwrap();
+    assert_eq!(direct_stats, spooled_stats);
+    assert_eq!(part_names(&direct), part_names(&spooled));
+    for (direct_part, spooled_part) in direct.iter().zip(&spooled) {
+        assert_eq!(
+            read_part(direct_part),
+            read_part(spooled_part),
+            "{} differs from spool-then-attach output",
+            direct_part.display()
+        );
+    }
+
+    let zst_dir = work_dir.join("direct_with_parents_zst");
+    let (zst_parts, zst_stats) = etl()
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .attach_parents_zst(&zst_dir, &parents, ExportFormat::Zst, false)
+        .unwrap();
+    assert_eq!(zst_stats, spooled_stats);
+    for (zst_part, spooled_part) in zst_parts.iter().zip(&spooled) {
+        assert!(zst_part.to_string_lossy().ends_with(".jsonl.zst"));
+        assert_eq!(read_part(zst_part), read_part(spooled_part));
+    }
+}
+
+#[test]
+fn attach_parents_zst_resume_keeps_current_parts() {
+    let base = make_corpus_basic();
+    let (work_dir, _spool_dir, _attached_dir, parents_cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let ym = YearMonth::new(2006, 1);
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&lib_tmp)
+            .sources(Sources::Comments)
+            .date_range(Some(ym), Some(ym))
+            .progress(false)
+    };
+    let mut ids = retl::ParentIds::new();
+    ids.extend_prefixed(["t3_s1", "t1_c1"]);
+    let parents = etl()
+        .sources(Sources::Both)
+        .resolve_parent_maps(&ids, &parents_cache_dir, false)
+        .unwrap();
+
+    let out_dir = work_dir.join("direct_resume");
+    let run = || {
+        etl()
+            .scan()
+            .include_pseudo_users()
+            .attach_parents_zst(&out_dir, &parents, ExportFormat::Jsonl, true)
+            .unwrap()
+    };
+    let (first, first_stats) = run();
+    assert_eq!(first.len(), 1);
+    let sidecar = PathBuf::from(format!("{}.parents-attach.json", first[0].display()));
+    assert!(sidecar.exists(), "direct attach should publish a resume sidecar");
+    let before = fs::read_to_string(&first[0]).unwrap();
+
+    let (second, second_stats) = run();
+    assert_eq!(second, first);
+    assert_eq!(fs::read_to_string(&second[0]).unwrap(), before);
+    assert!(first_stats.total() > 0);
+    assert_eq!(
+        second_stats.total(),
+        0,
+        "a resumed month is kept, not re-attached"
+    );
+}
+
+#[test]
+fn attach_parents_zst_annotates_reply_depth() {
+    let base = make_corpus_basic();
+    let (work_dir, _spool_dir, _attached_dir, parents_cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let ym = YearMonth::new(2006, 1);
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&lib_tmp)
+            .sources(Sources::Both)
+            .date_range(Some(ym), Some(ym))
+            .progress(false)
+    };
+    let mut ids = retl::ParentIds::new();
+    ids.extend_prefixed(["t3_s1", "t1_c1"]);
+    let links_file = parents_cache_dir
+        .join("comments")
+        .join("RC_2006-01.json.parent-links.json");
+
+    // Links are only recorded on request; depth refuses maps without them.
+    let plain = etl()
+        .resolve_parent_maps(&ids, &parents_cache_dir, false)
+        .unwrap();
+    assert!(!links_file.exists());
+    let err = etl()
+        .scan()
+        .annotate_depth()
+        .attach_parents_zst(
+            &work_dir.join("no_links"),
+            &plain,
+            ExportFormat::Jsonl,
+            false,
+        )
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("parent_reply_links"), "{err:#}");
+
+    let parents = etl()
+        .parent_reply_links(true)
+        .resolve_parent_maps(&ids, &parents_cache_dir, true)
+        .unwrap();
+    assert!(links_file.exists());
+
+    let depths = |out_dir: &Path, max_depth: usize| {
+        let (parts, _) = etl()
+            .scan()
+            .annotate_depth_with_max(max_depth)
+            .attach_parents_zst(out_dir, &parents, ExportFormat::Jsonl, false)
+            .unwrap();
+        let mut depths = std::collections::BTreeMap::new();
+        for part in &parts {
+            for line in read_part(part).lines() {
+                let v: serde_json::Value = serde_json::from_str(line).unwrap();
+                depths.insert(v["id"].as_str().unwrap().to_string(), v["depth"].clone());
+            }
+        }
+        depths
+    };
+
+    // Chain s1 <- c1 <- c2: c2's depth walks through c1's resolved parent link.
+    let walked = depths(&work_dir.join("depth"), retl::DEFAULT_MAX_REPLY_DEPTH);
+    assert_eq!(walked["s1"], 0);
+    assert_eq!(walked["c1"], 1);
+    assert_eq!(walked["c2"], 2);
+
+    let capped = depths(&work_dir.join("depth_capped"), 1);
+    assert_eq!(capped["c1"], 1);
+    assert!(capped["c2"].is_null(), "a walk past the cap has no depth");
+}
@@ -92,6 +92,39 @@ fn spool_resolve_attach_parents_end_to_end() {
     );
 }
 
+/// `extract_with_parents` runs spool, collect, resolve and attach in one
+/// call and matches the staged pipeline above.
+#[test]
+fn extract_with_parents_runs_the_whole_pipeline_in_one_call() {
+    let base = make_corpus_basic();
+    let (_work_dir, _spool_dir, attached_dir, _cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let ym = YearMonth::new(2006, 1);
+
+    let (attached_paths, stats) = RedditETL::new()
+        .base_dir(&base)
+        .work_dir(&lib_tmp)
+        .sources(Sources::Both)
+        .date_range(Some(ym), Some(ym))
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .extract_with_parents(&attached_dir, 1)
+        .unwrap();
+
+    assert!(attached_paths.iter().all(|p| p.starts_with(&attached_dir)));
+    assert_eq!(count_jsonl_lines(&attached_paths), 5);
+    assert!(stats.resolved > 0, "fixture comments reference parents");
+    let leftover: Vec<_> = std::fs::read_dir(&lib_tmp)
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("extract_with_parents"))
+        .collect();
+    assert!(leftover.is_empty(), "scratch not removed: {leftover:?}");
+}
+
 #[test]
 fn direct_parent_ids_construction_matches_spool_collection_resolution() {
     let base = make_corpus_basic();
@@ -222,6 +255,7 @@ fn resolve_parent_maps_rejects_backwards_date_range() {
     };
 
     let build = err
+        .inner()
         .downcast_ref::<retl::ConfigBuildError>()
         .expect("backwards date range should surface a ConfigBuildError");
     assert_eq!(
@@ -232,3 +266,148 @@ fn resolve_parent_maps_rejects_backwards_date_range() {
         }
     );
 }
+
+#[test]
+fn parent_cache_caps_do_not_change_attached_output() {
+    let base = make_corpus_basic();
+    let (work_dir, spool_dir, _attached_dir, _cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let ym = YearMonth::new(2006, 1);
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&lib_tmp)
+            .sources(Sources::Both)
+            .date_range(Some(ym), Some(ym))
+            .progress(false)
+    };
+
+    let (spool_parts, _n) = etl()
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .extract_spool_monthly(&spool_dir)
+        .unwrap();
+    let ids = etl()
+        .collect_parent_ids_from_jsonls(spool_parts.clone())
+        .unwrap();
+
+    let attach_with = |label: &str, caps: Option<(usize, usize)>| {
+        let configure = |etl: RedditETL| match caps {
+            Some((comments, submissions)) => etl.parent_cache_caps(comments, submissions),
+            None => etl,
+        };
+        let parents = configure(etl())
+            .resolve_parent_maps(&ids, &work_dir.join(format!("cache_{label}")), false)
+            .unwrap();
+        let (paths, stats) = configure(etl())
+            .attach_parents_jsonls_parallel_with_stats(
+                spool_parts.clone(),
+                &work_dir.join(format!("attached_{label}")),
+                &parents,
+                false,
+            )
+            .unwrap();
+        let contents: Vec<String> = paths
+            .iter()
+            .map(|p| std::fs::read_to_string(p).unwrap())
+            .collect();
+        (contents, stats)
+    };
+
+    let (default_out, default_stats) = attach_with("default", None);
+    let (large_out, large_stats) = attach_with("large", Some((4096, 4096)));
+    let (tiny_out, tiny_stats) = attach_with("tiny", Some((1, 1)));
+    assert!(default_stats.resolved > 0, "fixture should resolve parents");
+    assert_eq!(large_stats, default_stats);
+    assert_eq!(large_out, default_out);
+    assert_eq!(tiny_stats, default_stats);
+    assert_eq!(tiny_out, default_out);
+}
+
+// Draws onto a hidden `indicatif` target, so it needs the real bars.
+#[cfg(feature = "progress")]
+#[test]
+fn pipeline_progress_stacks_spool_and_collect_stages() {
+    use indicatif::{MultiProgress, ProgressDrawTarget};
+    use retl::PipelineProgress;
+    use std::sync::Arc;
+
+    let base = make_corpus_basic();
+    let (_work_dir, spool_dir, _attached_dir, _cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&lib_tmp)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(true)
+    };
+
+    let mp = Arc::new(MultiProgress::with_draw_target(ProgressDrawTarget::hidden()));
+    let pipeline = PipelineProgress::with_multiprogress(mp, ["spool", "collect ids"]);
+    let (spool_parts, written) = pipeline
+        .run_stage("spool", |stage| {
+            etl()
+                .progress_stage(stage)
+                .scan()
+                .include_pseudo_users()
+                .extract_spool_monthly(&spool_dir)
+        })
+        .unwrap();
+    let ids: ParentIds = pipeline
+        .run_stage("collect ids", |stage| {
+            etl()
+                .progress_stage(stage)
+                .collect_parent_ids_from_jsonls(spool_parts.clone())
+        })
+        .unwrap();
+    pipeline.finish();
+
+    assert_eq!(written, 5);
+    assert!(!ids.is_empty(), "fixture comments reference parents");
+}
+
+#[test]
+fn export_parent_maps_ndjson_writes_one_line_per_resolved_parent() {
+    let base = make_corpus_basic();
+    let (work_dir, spool_dir, _attached_dir, _cache_dir, lib_tmp) =
+        make_parent_pipeline_dirs(&base);
+    let etl = || {
+        RedditETL::new()
+            .base_dir(&base)
+            .work_dir(&lib_tmp)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+    };
+    let (spool_parts, _) = etl()
+        .scan()
+        .include_pseudo_users()
+        .extract_spool_monthly(&spool_dir)
+        .unwrap();
+    let ids = etl().collect_parent_ids_from_jsonls(spool_parts).unwrap();
+
+    let out = work_dir.join("parents.ndjson");
+    let written = etl().export_parent_maps_ndjson(&ids, &out).unwrap();
+
+    let mut rows: Vec<serde_json::Value> = std::fs::read_to_string(&out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    rows.sort_by_key(|row| row["id"].as_str().unwrap().to_string());
+    assert_eq!(written, 2, "t1_c1 and t3_s1 are the only referenced parents");
+    assert_eq!(
+        rows,
+        vec![
+            serde_json::json!({
+                "id": "c1", "kind": "comment", "author": "alice",
+                "body": "I love Rust http://rust-lang.org",
+            }),
+            serde_json::json!({
+                "id": "s1", "kind": "submission", "author": "bob",
+                "title": "Rust news", "selftext": "",
+            }),
+        ]
+    );
+}
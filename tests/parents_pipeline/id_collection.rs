@@ -0,0 +1,60 @@
+use ahash::AHashSet;
+use retl::{ParentIds, RedditETL, MAX_SHARDS};
+use std::fs;
+use std::path::Path;
+
+fn collect_with_shards(spool: &Path, work_dir: &Path, shards: usize) -> ParentIds {
+    RedditETL::new()
+        .work_dir(work_dir)
+        .shard_count(shards)
+        .progress(false)
+        .collect_parent_ids_from_jsonls(vec![spool.to_path_buf()])
+        .unwrap()
+}
+
+/// Shard loader for `contains_t1`/`contains_t3`. Each set is leaked so it
+/// can be handed out by reference; fine for a test over a few tiny shards.
+fn load_shard<'a>(p: &'a Path) -> anyhow::Result<&'a AHashSet<String>> {
+    let set: AHashSet<String> = fs::read_to_string(p)
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    Ok(Box::leak(Box::new(set)))
+}
+
+fn membership(ids: &ParentIds, t1: &[String], t3: &[String]) -> (Vec<bool>, Vec<bool>) {
+    let mut loader = load_shard;
+    let t1_hits = t1.iter().map(|id| ids.contains_t1(id, &mut loader).unwrap()).collect();
+    let t3_hits = t3.iter().map(|id| ids.contains_t3(id, &mut loader).unwrap()).collect();
+    (t1_hits, t3_hits)
+}
+
+/// Shard count is a memory/disk knob: one shard and many shards must yield
+/// the same deduped parent-id sets.
+#[test]
+fn parent_id_collection_is_independent_of_shard_count() {
+    let tmp = tempfile::tempdir().unwrap();
+    let spool = tmp.path().join("spool.jsonl");
+    let mut lines = String::new();
+    for i in 0..500 {
+        // Every parent appears twice so dedup has work to do.
+        lines.push_str(&format!(
+            "{{\"id\":\"c{i}\",\"parent_id\":\"t1_p{}\",\"link_id\":\"t3_s{}\"}}\n",
+            i / 2,
+            i % 7
+        ));
+    }
+    fs::write(&spool, lines).unwrap();
+
+    let t1: Vec<String> = (0..300).map(|i| format!("p{i}")).collect();
+    let t3: Vec<String> = (0..10).map(|i| format!("s{i}")).collect();
+
+    let few = collect_with_shards(&spool, &tmp.path().join("work_one"), 1);
+    let many = collect_with_shards(&spool, &tmp.path().join("work_many"), MAX_SHARDS);
+
+    let few_hits = membership(&few, &t1, &t3);
+    assert_eq!(few_hits, membership(&many, &t1, &t3));
+    assert_eq!(few_hits.0.iter().filter(|hit| **hit).count(), 250);
+    assert_eq!(few_hits.1.iter().filter(|hit| **hit).count(), 7);
+}
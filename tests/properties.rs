@@ -373,6 +373,7 @@ fn run_dedupe_pipeline(lines: &[String], dir: &Path, label: &str) -> Vec<String>
         read_buf_bytes: 8 * 1024,
         write_buf_bytes: 8 * 1024,
         inflight_bytes: 0, // disable cap; tight min/max_buf_mb drives flushes
+        run_format: retl::IntermediateFormat::Text,
     };
     let runs = build_runs_sorted(&in_path, &runs_dir, &key, &cfg).unwrap();
     let out_path = dir.join(format!("{}_out.ndjson", label));
@@ -0,0 +1,84 @@
+//! `RedditETL` / `ScanPlan` operations return `RetlError`, so callers can
+//! branch on the kind of failure without string matching.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use common::{add_corrupt_month, make_corpus_basic};
+use retl::{Cancelled, RedditETL, RetlError, Sources, YearMonth};
+
+#[test]
+fn backwards_date_range_is_config() {
+    let base = make_corpus_basic();
+    let err = RedditETL::new()
+        .base_dir(&base)
+        .date_range(Some(YearMonth::new(2006, 2)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .count_by_month()
+        .unwrap_err();
+
+    assert!(matches!(err, RetlError::Config(_)), "{err:?}");
+    assert!(err.to_string().contains("invalid date range"), "{err}");
+    assert!(err.path().is_none());
+}
+
+#[test]
+fn missing_corpus_and_bad_query_are_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = RedditETL::new()
+        .base_dir(dir.path())
+        .work_dir(dir.path().join("work"))
+        .progress(false)
+        .scan()
+        .count_by_month()
+        .unwrap_err();
+    assert!(matches!(err, RetlError::Config(_)), "{err:?}");
+
+    let err = RedditETL::new()
+        .base_dir(make_corpus_basic())
+        .progress(false)
+        .scan()
+        .subreddits_out(["  "])
+        .count_by_month()
+        .unwrap_err();
+    assert!(matches!(err, RetlError::Config(_)), "{err:?}");
+}
+
+#[test]
+fn corrupt_month_is_decode_with_path() {
+    let base = make_corpus_basic();
+    add_corrupt_month(&base);
+
+    let err = RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .count_by_month()
+        .unwrap_err();
+
+    match err {
+        RetlError::Decode { path, source } => {
+            assert!(path.ends_with("RC_2006-02.zst"), "{}", path.display());
+            assert!(
+                format!("{source:#}").contains("zstd decode error"),
+                "{source:#}"
+            );
+        }
+        other => panic!("expected RetlError::Decode, got {other:?}"),
+    }
+}
+
+#[test]
+fn callback_returning_cancelled_is_cancelled() {
+    let err = RedditETL::new()
+        .base_dir(make_corpus_basic())
+        .progress(false)
+        .scan()
+        .for_each_record(|_| Err(Cancelled.into()))
+        .unwrap_err();
+
+    assert!(matches!(err, RetlError::Cancelled(_)), "{err:?}");
+    assert!(err.to_string().contains("cancelled"), "{err:#}");
+}
@@ -1,10 +1,10 @@
 #[path = "common/mod.rs"]
 mod common;
 
-use common::make_corpus_multi_month;
+use common::{make_corpus_basic, make_corpus_multi_month};
 use retl::{
     discover_upstream_manifests_from_inputs, manifest_path_for_directory, manifest_path_for_file,
-    RedditETL, Sources, YearMonth,
+    ExportFormat, RedditETL, Sources, YearMonth,
 };
 use serde_json::Value;
 use std::fs;
@@ -120,6 +120,9 @@ fn directory_spool_manifest_is_discoverable_from_downstream_inputs() {
         manifest["counts"]["part_files"].as_u64(),
         Some(parts.len() as u64)
     );
+    let files = manifest["output_files"].as_array().expect("output_files");
+    assert_eq!(files.len(), parts.len());
+    assert!(files.iter().all(|f| f["records"].as_u64() == Some(2)));
 
     let upstream = discover_upstream_manifests_from_inputs(&parts);
     assert_eq!(
@@ -136,3 +139,44 @@ fn directory_spool_manifest_is_discoverable_from_downstream_inputs() {
         manifest["manifest_fingerprint"].as_str()
     );
 }
+
+#[test]
+fn partitioned_export_manifest_lists_each_output_file() {
+    let base = make_corpus_basic();
+    let out_dir = base.join("export_listed");
+
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+        .subreddit("programming")
+        .include_pseudo_users()
+        .export_partitioned(&out_dir, ExportFormat::Jsonl)
+        .unwrap();
+
+    let manifest = read_json(&manifest_path_for_directory(&out_dir));
+    let files = manifest["output_files"].as_array().expect("output_files");
+    assert_eq!(files.len(), 2, "{files:?}");
+
+    let rc = &files[0];
+    assert_eq!(rc["path"], "comments/RC_2006-01.jsonl");
+    assert_eq!(rc["records"].as_u64(), Some(3));
+    assert_eq!(rc["min_created_utc"].as_i64(), Some(1136074600));
+    assert_eq!(rc["max_created_utc"].as_i64(), Some(1136074800));
+
+    let rs = &files[1];
+    assert_eq!(rs["path"], "submissions/RS_2006-01.jsonl");
+    assert_eq!(rs["records"].as_u64(), Some(2));
+    assert_eq!(rs["min_created_utc"].as_i64(), Some(1136073600));
+    assert_eq!(rs["max_created_utc"].as_i64(), Some(1136073601));
+
+    for f in files {
+        let path = out_dir.join(f["path"].as_str().unwrap());
+        assert_eq!(
+            f["size_bytes"].as_u64(),
+            Some(fs::metadata(&path).unwrap().len())
+        );
+    }
+}
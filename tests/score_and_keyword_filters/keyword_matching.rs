@@ -241,3 +241,47 @@ fn text_regex_matches_body_selftext_or_title() {
 
     assert_eq!(counts.get(&YearMonth::new(2006, 1)).copied(), Some(4));
 }
+
+#[test]
+fn exclude_bot_bodies_drops_bot_footers_and_custom_body_patterns() {
+    let dir = tempfile::tempdir().unwrap().keep();
+    let rc = dir.join("comments").join("RC_2006-01.zst");
+    let comment = |id: &str, author: &str, body: &str| {
+        json!({"subreddit":"programming","author":author,"id":id,"body":body,"created_utc":1136074600_i64,"score":1_i64}).to_string()
+    };
+    write_zst_lines(
+        &rc,
+        &[
+            comment("c1", "renamed_bot", "Your post was removed.\n\n*I am a bot, and this action was performed automatically. Please contact the moderators of this subreddit if you have any questions.*"),
+            comment("c2", "alice", "I think the bot was wrong here."),
+            comment("c3", "bob", "[Mirror](https://example.com) | ^(I'm a bot) | ^(opt out)"),
+            comment("c4", "carol", "Sponsored: buy now"),
+        ],
+    );
+    let ids = |plan: retl::ScanPlan, name: &str| -> Vec<String> {
+        let out = dir.join(name);
+        plan.extract_to_jsonl(&out).unwrap();
+        read_jsonl_values(&out)
+            .iter()
+            .map(|v| v["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let scan = || {
+        RedditETL::new()
+            .base_dir(&dir)
+            .sources(Sources::Comments)
+            .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+            .progress(false)
+            .scan()
+    };
+
+    assert_eq!(ids(scan().exclude_bot_bodies(), "bots.jsonl"), ["c2", "c4"]);
+    let sponsored = regex::Regex::new(r"^Sponsored:").unwrap();
+    assert_eq!(
+        ids(
+            scan().exclude_bot_bodies().exclude_body_regex(sponsored),
+            "bots_and_custom.jsonl"
+        ),
+        ["c2"]
+    );
+}
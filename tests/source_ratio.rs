@@ -0,0 +1,103 @@
+#[path = "common/mod.rs"]
+mod common;
+
+use common::write_zst_lines;
+use retl::{RedditETL, ScanPlan, Sources, YearMonth};
+use serde_json::json;
+use std::path::Path;
+
+const COMMENTS: usize = 2_000;
+const SUBMISSIONS: usize = 100;
+
+fn make_comment_heavy_corpus(base: &Path) {
+    let rc: Vec<String> = (0..COMMENTS)
+        .map(|i| {
+            json!({
+                "id": format!("c{i}"), "author": format!("user{}", i % 50),
+                "subreddit": "programming", "body": "hi", "parent_id": "t3_s0",
+                "score": 1, "created_utc": 1136074600 + i as i64,
+            })
+            .to_string()
+        })
+        .collect();
+    let rs: Vec<String> = (0..SUBMISSIONS)
+        .map(|i| {
+            json!({
+                "id": format!("s{i}"), "author": format!("user{}", i % 50),
+                "subreddit": "programming", "title": "post", "selftext": "",
+                "score": 1, "created_utc": 1136074600 + i as i64,
+            })
+            .to_string()
+        })
+        .collect();
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &rc);
+    write_zst_lines(&base.join("submissions").join("RS_2006-01.zst"), &rs);
+}
+
+fn scan(base: &Path) -> ScanPlan {
+    RedditETL::new()
+        .base_dir(base)
+        .sources(Sources::Both)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+}
+
+/// `(comment ids, submission ids)` written by `plan`, sorted.
+fn kept_ids(plan: ScanPlan, out: &Path) -> (Vec<String>, Vec<String>) {
+    plan.extract_to_jsonl(out).unwrap();
+    let mut ids: Vec<String> = common::read_jsonl_values(out)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    let (comments, submissions) = ids.into_iter().partition(|id| id.starts_with('c'));
+    (comments, submissions)
+}
+
+#[test]
+fn source_ratio_thins_comments_toward_the_target_reproducibly() {
+    let tmp = tempfile::tempdir().unwrap();
+    make_comment_heavy_corpus(tmp.path());
+
+    let (comments, submissions) = kept_ids(
+        scan(tmp.path()).source_ratio(2, 1),
+        &tmp.path().join("first.jsonl"),
+    );
+    // Submissions are the scarce side and are kept whole; comments are
+    // sampled to about twice as many.
+    assert_eq!(submissions.len(), SUBMISSIONS);
+    let target = 2 * SUBMISSIONS;
+    assert!(
+        (target * 3 / 4..=target * 5 / 4).contains(&comments.len()),
+        "kept {} comments for a target of {target}",
+        comments.len()
+    );
+
+    let again = kept_ids(
+        scan(tmp.path()).source_ratio(2, 1),
+        &tmp.path().join("again.jsonl"),
+    );
+    assert_eq!(
+        again,
+        (comments, submissions),
+        "sampling must be keyed on id"
+    );
+
+    let (comments, submissions) = kept_ids(
+        scan(tmp.path()).source_ratio(1, 0),
+        &tmp.path().join("comments_only.jsonl"),
+    );
+    assert_eq!((comments.len(), submissions.len()), (COMMENTS, 0));
+}
+
+#[test]
+fn source_ratio_rejects_an_all_zero_ratio() {
+    let tmp = tempfile::tempdir().unwrap();
+    let err = scan(tmp.path())
+        .source_ratio(0, 0)
+        .build()
+        .err()
+        .expect("0:0 should fail the build");
+    assert!(err.to_string().contains("source_ratio"), "{err}");
+}
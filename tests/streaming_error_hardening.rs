@@ -52,7 +52,7 @@ fn etl_for_bad_corpus(base: &Path) -> RedditETL {
         .progress(false)
 }
 
-fn assert_malformed(err: anyhow::Error, path: &Path, line: u64) {
+fn assert_malformed(err: impl std::fmt::Display, path: &Path, line: u64) {
     let msg = format!("{err:#}");
     assert!(msg.contains("malformed JSON"), "unexpected error: {msg}");
     assert!(
@@ -94,6 +94,274 @@ fn extract_to_jsonl_fails_on_malformed_json_line_without_publishing() {
     );
 }
 
+#[test]
+fn extract_to_jsonl_routes_malformed_line_to_reject_sink() {
+    let (base, rc) = malformed_comment_corpus();
+    let out = base.join("out.jsonl");
+    let rejects = base.join("out.rejects.jsonl");
+
+    etl_for_bad_corpus(&base)
+        .reject_sink(&rejects)
+        .scan()
+        .extract_to_jsonl(&out)
+        .expect("a reject sink keeps malformed JSON from aborting the extract");
+
+    let ids: Vec<_> = read_jsonl_values(&out)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["c1", "c2"]);
+
+    let rejected = read_jsonl_values(&rejects);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0]["line"], "{\"id\":\"bad\",\"author\":");
+    assert_eq!(rejected[0]["line_number"], 2);
+    assert_eq!(rejected[0]["path"], rc.display().to_string());
+    assert!(!rejected[0]["error"].as_str().unwrap().is_empty());
+
+    // A clean rerun clears the previous run's reject file.
+    let clean = json!({
+        "id": "c3",
+        "author": "carol",
+        "subreddit": "programming",
+        "created_utc": 1136073602_i64,
+        "body": "fine"
+    });
+    write_zst_lines(&rc, &[clean.to_string()]);
+    etl_for_bad_corpus(&base)
+        .reject_sink(&rejects)
+        .scan()
+        .extract_to_jsonl(&out)
+        .unwrap();
+    assert!(!rejects.exists());
+}
+
+#[test]
+fn resumed_extract_keeps_rejects_from_months_it_skips() {
+    let (base, _rc) = malformed_comment_corpus();
+    let out = base.join("resumed.jsonl");
+    let rejects = base.join("resumed.rejects.jsonl");
+    let run = || {
+        etl_for_bad_corpus(&base)
+            .reject_sink(&rejects)
+            .resume(true)
+            .io_retries(1, 0)
+            .scan()
+            .extract_to_jsonl(&out)
+    };
+
+    // A directory in the way fails the final stitch after the month is
+    // committed to the resume manifest.
+    fs::create_dir_all(&out).unwrap();
+    run().expect_err("publishing onto a directory fails");
+    assert_eq!(read_jsonl_values(&rejects).len(), 1);
+
+    // The resumed run skips the committed month, so its reject has to
+    // survive from the first run.
+    fs::remove_dir(&out).unwrap();
+    run().unwrap();
+    assert_eq!(read_jsonl_values(&out).len(), 2);
+    let rejected = read_jsonl_values(&rejects);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0]["line_number"], 2);
+}
+
+/// A month a resumed run reads again replaces its reject rows instead of
+/// appending a second copy, and no staged reject file is left behind.
+#[test]
+fn resumed_extract_replaces_rejects_of_months_it_reads_again() {
+    let (base, rc) = malformed_comment_corpus();
+    let out = base.join("reread.jsonl");
+    let rejects = base.join("reread.rejects.jsonl");
+    let run = || {
+        etl_for_bad_corpus(&base)
+            .reject_sink(&rejects)
+            .resume(true)
+            .scan()
+            .extract_to_jsonl(&out)
+            .unwrap()
+    };
+    run();
+    assert_eq!(read_jsonl_values(&rejects).len(), 1);
+
+    // A changed corpus file invalidates the checkpoint, so the month is
+    // read again under the same reject file.
+    let ok = json!({
+        "id": "c0",
+        "author": "zed",
+        "subreddit": "programming",
+        "created_utc": 1136073599_i64
+    });
+    write_zst_lines(&rc, &[ok.to_string(), "{\"id\":\"bad\",\"author\":".to_string()]);
+    run();
+    let rejected = read_jsonl_values(&rejects);
+    assert_eq!(rejected.len(), 1, "{rejected:?}");
+    assert_eq!(rejected[0]["line_number"], 2);
+
+    let staging = base.join("_staging");
+    let leftovers: Vec<_> = fs::read_dir(&staging)
+        .map(|entries| entries.map(|e| e.unwrap().file_name()).collect())
+        .unwrap_or_default();
+    assert!(
+        !leftovers
+            .iter()
+            .any(|name| name.to_string_lossy().starts_with("reread.rejects")),
+        "staged reject files left behind: {leftovers:?}"
+    );
+}
+
+/// Each input's rejects are appended to the one reject file as the input
+/// finishes; rows of the inputs finished earlier stay, once each.
+#[test]
+fn rejects_from_each_input_are_recorded_once() {
+    let (base, rc) = malformed_comment_corpus();
+    let rs = base.join("submissions").join("RS_2006-01.zst");
+    let post = json!({
+        "id": "s1",
+        "author": "carol",
+        "subreddit": "programming",
+        "created_utc": 1136073600_i64,
+        "title": "post"
+    });
+    write_zst_lines(&rs, &[post.to_string(), "{\"id\":\"s_bad\"".to_string()]);
+    let rejects = base.join("both.rejects.jsonl");
+
+    etl_for_bad_corpus(&base)
+        .sources(Sources::Both)
+        .reject_sink(&rejects)
+        .scan()
+        .extract_to_jsonl(&base.join("both.jsonl"))
+        .unwrap();
+
+    let mut rejected: Vec<(String, u64)> = read_jsonl_values(&rejects)
+        .iter()
+        .map(|v| {
+            (
+                v["path"].as_str().unwrap().to_string(),
+                v["line_number"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    rejected.sort();
+    assert_eq!(
+        rejected,
+        [(rc.display().to_string(), 2), (rs.display().to_string(), 2)]
+    );
+}
+
+/// A torn last row, left by a run that died while appending, is trimmed when
+/// a resumed run starts instead of failing it.
+#[test]
+fn resumed_extract_trims_torn_reject_row() {
+    let (base, _rc) = malformed_comment_corpus();
+    let out = base.join("torn.jsonl");
+    let rejects = base.join("torn.rejects.jsonl");
+    let run = || {
+        etl_for_bad_corpus(&base)
+            .reject_sink(&rejects)
+            .resume(true)
+            .scan()
+            .extract_to_jsonl(&out)
+            .unwrap()
+    };
+    run();
+    let mut torn = fs::read(&rejects).unwrap();
+    torn.extend_from_slice(b"{\"path\":\"RC_2006-0");
+    fs::write(&rejects, torn).unwrap();
+
+    run();
+    let rejected = read_jsonl_values(&rejects);
+    assert_eq!(rejected.len(), 1, "{rejected:?}");
+    assert_eq!(rejected[0]["line_number"], 2);
+}
+
+/// A lone surrogate escape in a field the minimal parse skips slips through
+/// the raw fast path; `strict_json` fully parses the line and rejects it.
+#[test]
+fn strict_json_rejects_lines_the_fast_path_would_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    let rc = base.join("comments").join("RC_2006-01.zst");
+    let good = json!({
+        "id": "c1",
+        "author": "alice",
+        "subreddit": "programming",
+        "created_utc": 1136073600_i64,
+        "body": "ok"
+    })
+    .to_string();
+    let bad = r#"{"id":"c2","author":"bob","subreddit":"programming","created_utc":1136073601,"flair":"\ud800"}"#;
+    write_zst_lines(&rc, &[good, bad.to_string()]);
+
+    let out = base.join("lenient.jsonl");
+    etl_for_bad_corpus(base).scan().extract_to_jsonl(&out).unwrap();
+    let lenient = fs::read_to_string(&out).unwrap();
+    assert_eq!(lenient.lines().count(), 2);
+    assert!(lenient.contains(bad));
+
+    let out = base.join("strict.jsonl");
+    let rejects = base.join("strict.rejects.jsonl");
+    etl_for_bad_corpus(base)
+        .strict_json(true)
+        .reject_sink(&rejects)
+        .scan()
+        .extract_to_jsonl(&out)
+        .unwrap();
+    let ids: Vec<_> = read_jsonl_values(&out)
+        .iter()
+        .map(|v| v["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["c1"]);
+    let rejected = read_jsonl_values(&rejects);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0]["line"], bad);
+    assert_eq!(rejected[0]["line_number"], 2);
+}
+
+#[test]
+fn reject_sink_catches_lines_the_full_parse_paths_reject() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    let rc = base.join("comments").join("RC_2006-01.zst");
+    let good = json!({
+        "id": "c1",
+        "author": "alice",
+        "subreddit": "programming",
+        "created_utc": 1136073600_i64,
+        "body": "ok"
+    })
+    .to_string();
+    // `parse_minimal` accepts the lone surrogate, so without `strict_json`
+    // the line only fails once the Parsed or whitelist path parses it whole.
+    let bad = r#"{"id":"c2","author":"bob","subreddit":"programming","created_utc":1136073601,"flair":"\ud800"}"#;
+    write_zst_lines(&rc, &[good, bad.to_string()]);
+
+    let runs = [
+        ("canonical", etl_for_bad_corpus(base).canonicalize_json(true)),
+        (
+            "pointer",
+            etl_for_bad_corpus(base).whitelist_fields(["id", "/author as author"]),
+        ),
+    ];
+    for (name, etl) in runs {
+        let out = base.join(format!("{name}.jsonl"));
+        let rejects = base.join(format!("{name}.rejects.jsonl"));
+        etl.reject_sink(&rejects)
+            .scan()
+            .extract_to_jsonl(&out)
+            .unwrap_or_else(|e| panic!("{name}: {e:#}"));
+        let ids: Vec<_> = read_jsonl_values(&out)
+            .iter()
+            .map(|v| v["id"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(ids, ["c1"], "{name}");
+        let rejected = read_jsonl_values(&rejects);
+        assert_eq!(rejected.len(), 1, "{name}");
+        assert_eq!(rejected[0]["line"], bad, "{name}");
+        assert_eq!(rejected[0]["line_number"], 2, "{name}");
+    }
+}
+
 #[test]
 fn whitelist_slow_path_malformed_json_reports_path_and_line() {
     let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,73 @@
+#[path = "common/mod.rs"]
+mod common;
+
+use common::write_zst_lines;
+use retl::{RedditETL, ScanPlan, Sources, YearMonth};
+use serde_json::json;
+use std::path::Path;
+
+fn make_mixed_subreddit_corpus(base: &Path) {
+    let rc = ["programming", "reddit.com", "Reddit.com", "rust"]
+        .iter()
+        .enumerate()
+        .map(|(i, sub)| {
+            json!({
+                "id": format!("c{i}"), "author": format!("user{i}"), "subreddit": sub,
+                "body": "hi", "parent_id": "t3_s1", "score": 1,
+                "created_utc": 1136074600 + i as i64,
+            })
+            .to_string()
+        })
+        .collect::<Vec<_>>();
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &rc);
+}
+
+fn scan(base: &Path) -> ScanPlan {
+    RedditETL::new()
+        .base_dir(base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+}
+
+fn count(plan: ScanPlan) -> u64 {
+    plan.count_by_month().unwrap().values().sum()
+}
+
+#[test]
+fn subreddits_out_excludes_without_an_allow_list() {
+    let tmp = tempfile::tempdir().unwrap();
+    make_mixed_subreddit_corpus(tmp.path());
+
+    // Case-insensitive: both `reddit.com` spellings are dropped.
+    assert_eq!(count(scan(tmp.path()).subreddits_out(["reddit.com"])), 2);
+    assert_eq!(count(scan(tmp.path()).subreddits_out(["r/Reddit.com", "rust"])), 1);
+}
+
+#[test]
+fn subreddits_out_wins_over_allow_list() {
+    let tmp = tempfile::tempdir().unwrap();
+    make_mixed_subreddit_corpus(tmp.path());
+
+    let out = tmp.path().join("kept.jsonl");
+    scan(tmp.path())
+        .subreddits(["programming", "reddit.com"])
+        .subreddits_out(["reddit.com"])
+        .extract_to_jsonl(&out)
+        .unwrap();
+    let subs: Vec<String> = common::read_jsonl_values(&out)
+        .iter()
+        .map(|v| v["subreddit"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(subs, ["programming"]);
+}
+
+#[test]
+fn subreddits_out_rejects_blank_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+    make_mixed_subreddit_corpus(tmp.path());
+
+    let err = scan(tmp.path()).subreddits_out([" "]).build().err().expect("blank deny entry should fail the build");
+    assert!(err.to_string().contains("subreddits_out"), "{err}");
+}
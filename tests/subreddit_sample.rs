@@ -0,0 +1,130 @@
+#[path = "common/mod.rs"]
+mod common;
+
+use common::write_zst_lines;
+use retl::{RedditETL, ScanPlan, Sources, YearMonth};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+const SUBREDDITS: [&str; 12] = [
+    "askreddit",
+    "programming",
+    "rust",
+    "golang",
+    "python",
+    "science",
+    "history",
+    "books",
+    "movies",
+    "music",
+    "gaming",
+    "cooking",
+];
+const RECORDS_PER_SUBREDDIT: usize = 5;
+
+fn make_many_subreddit_corpus(base: &Path) {
+    let mut rc = Vec::new();
+    for (s, sub) in SUBREDDITS.iter().enumerate() {
+        for r in 0..RECORDS_PER_SUBREDDIT {
+            // Alternate the casing so sampling must treat names case-insensitively.
+            let name = if r % 2 == 0 {
+                sub.to_string()
+            } else {
+                sub.to_uppercase()
+            };
+            rc.push(
+                json!({
+                    "id": format!("c{s}_{r}"), "author": format!("user{r}"), "subreddit": name,
+                    "body": "hi", "parent_id": "t3_s1", "score": 1,
+                    "created_utc": 1136074600 + (s * RECORDS_PER_SUBREDDIT + r) as i64,
+                })
+                .to_string(),
+            );
+        }
+    }
+    write_zst_lines(&base.join("comments").join("RC_2006-01.zst"), &rc);
+}
+
+fn scan(base: &Path) -> ScanPlan {
+    RedditETL::new()
+        .base_dir(base)
+        .sources(Sources::Comments)
+        .date_range(Some(YearMonth::new(2006, 1)), Some(YearMonth::new(2006, 1)))
+        .progress(false)
+        .scan()
+}
+
+fn kept_per_subreddit(plan: ScanPlan, out: &Path) -> BTreeMap<String, usize> {
+    plan.extract_to_jsonl(out).unwrap();
+    let mut counts = BTreeMap::new();
+    for v in common::read_jsonl_values(out) {
+        let sub = v["subreddit"].as_str().unwrap().to_lowercase();
+        *counts.entry(sub).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[test]
+fn sample_subreddits_keeps_whole_subreddits_reproducibly() {
+    let tmp = tempfile::tempdir().unwrap();
+    make_many_subreddit_corpus(tmp.path());
+
+    let first = kept_per_subreddit(
+        scan(tmp.path()).sample_subreddits(0.5, 7),
+        &tmp.path().join("first.jsonl"),
+    );
+    for (sub, n) in &first {
+        assert_eq!(*n, RECORDS_PER_SUBREDDIT, "r/{sub} was split by the sample");
+    }
+    assert!(
+        !first.is_empty() && first.len() < SUBREDDITS.len(),
+        "a 50% sample of {} subreddits kept {:?}",
+        SUBREDDITS.len(),
+        first.keys()
+    );
+
+    let again = kept_per_subreddit(
+        scan(tmp.path()).sample_subreddits(0.5, 7),
+        &tmp.path().join("again.jsonl"),
+    );
+    assert_eq!(
+        first, again,
+        "same fraction and seed must pick the same subreddits"
+    );
+
+    // Raising the fraction under the same seed only adds subreddits.
+    let wider: BTreeSet<String> = kept_per_subreddit(
+        scan(tmp.path()).sample_subreddits(0.9, 7),
+        &tmp.path().join("wider.jsonl"),
+    )
+    .into_keys()
+    .collect();
+    assert!(first.keys().all(|sub| wider.contains(sub)));
+
+    let all = kept_per_subreddit(
+        scan(tmp.path()).sample_subreddits(1.0, 7),
+        &tmp.path().join("all.jsonl"),
+    );
+    assert_eq!(all.len(), SUBREDDITS.len());
+    let none = kept_per_subreddit(
+        scan(tmp.path()).sample_subreddits(0.0, 7),
+        &tmp.path().join("none.jsonl"),
+    );
+    assert!(none.is_empty());
+}
+
+#[test]
+fn sample_subreddits_rejects_fraction_outside_unit_range() {
+    let tmp = tempfile::tempdir().unwrap();
+    make_many_subreddit_corpus(tmp.path());
+
+    for fraction in [-0.1, 1.5, f64::NAN] {
+        let err = scan(tmp.path())
+            .sample_subreddits(fraction, 1)
+            .build()
+            .err()
+            .expect("out-of-range fraction should fail the build");
+        assert!(err.to_string().contains("sample_subreddits"), "{err}");
+    }
+}
@@ -2,7 +2,10 @@
 mod common;
 
 use common::write_zst_lines;
-use retl::{convert_jsonl_to_csv, convert_jsonl_to_tsv, RedditETL, Sources, TabularExportOptions};
+use retl::{
+    convert_jsonl_to_csv, convert_jsonl_to_tsv, EditedMode, RedditETL, Sources,
+    TabularExportOptions,
+};
 use serde_json::json;
 use std::fs;
 
@@ -60,6 +63,85 @@ fn tsv_rejects_line_breaks_without_publishing_final_output_and_csv_quotes_them()
     assert!(csv.contains("c1,\"hello\nworld\"\r\n"), "CSV was {csv:?}");
 }
 
+/// `transform` runs before the CSV columns are selected, so a derived field
+/// can be exported and a redacted one is written redacted.
+#[test]
+fn csv_extract_selects_columns_from_the_transformed_record() {
+    let base = make_multiline_comment_corpus();
+    let csv_out = base.join("transformed.csv");
+    RedditETL::new()
+        .base_dir(&base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .transform(std::sync::Arc::new(|record: &mut serde_json::Value| {
+            record["body"] = json!("[redacted]");
+            record["batch"] = json!("2006-01");
+        }))
+        .extract_to_csv(&csv_out, ["id", "body", "batch"], Default::default())
+        .unwrap();
+    let csv = fs::read_to_string(&csv_out).unwrap();
+    assert!(csv.contains("c1,[redacted],2006-01\r\n"), "CSV was {csv:?}");
+}
+
+/// CSV/TSV rows carry the same `edited` rewrite as the JSONL writer.
+#[test]
+fn csv_extract_writes_the_normalized_edited_value() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    fs::create_dir_all(base.join("submissions")).unwrap();
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            json!({"id":"c1","author":"alice","subreddit":"programming",
+                   "created_utc":1136073600_i64,"edited":1136073700_i64})
+            .to_string(),
+            json!({"id":"c2","author":"bob","subreddit":"programming",
+                   "created_utc":1136073601_i64,"edited":false})
+            .to_string(),
+        ],
+    );
+    let csv_out = base.join("edited.csv");
+    RedditETL::new()
+        .base_dir(base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .normalize_edited(EditedMode::AsBool)
+        .extract_to_csv(&csv_out, ["id", "edited"], Default::default())
+        .unwrap();
+    let csv = fs::read_to_string(&csv_out).unwrap();
+    assert!(csv.contains("c1,true\r\n"), "CSV was {csv:?}");
+    assert!(csv.contains("c2,false\r\n"), "CSV was {csv:?}");
+}
+
+/// `canonicalize_subreddit_case` lowercases the TSV `subreddit` column the
+/// way it does in JSONL output.
+#[test]
+fn tsv_extract_writes_the_canonical_subreddit_case() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    fs::create_dir_all(base.join("submissions")).unwrap();
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[json!({"id":"c1","author":"alice","subreddit":"AskScience",
+                 "created_utc":1136073600_i64})
+        .to_string()],
+    );
+    let tsv_out = base.join("subs.tsv");
+    RedditETL::new()
+        .base_dir(base)
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .canonicalize_subreddit_case(true)
+        .extract_to_tsv(&tsv_out, ["id", "subreddit"], Default::default())
+        .unwrap();
+    let tsv = fs::read_to_string(&tsv_out).unwrap();
+    assert!(tsv.contains("c1\taskscience\n"), "TSV was {tsv:?}");
+    assert!(!tsv.contains("AskScience"), "TSV was {tsv:?}");
+}
+
 #[test]
 fn library_tabular_exports_reject_unsupported_timestamp_and_resume_options() {
     let base = make_multiline_comment_corpus();
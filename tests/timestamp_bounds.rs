@@ -3,6 +3,7 @@ mod common;
 
 use assert_cmd::Command;
 use common::{read_jsonl_values, write_zst_lines};
+use retl::{RedditETL, Sources, TimeField};
 use serde_json::json;
 use std::fs;
 use std::io::Write;
@@ -193,3 +194,73 @@ fn cli_timestamp_bounds_narrow_explicit_date_range_for_planning() {
     ids.sort();
     assert_eq!(ids, vec!["kept_inside", "kept_lower"]);
 }
+
+/// `time_field(RetrievedOn)` moves the exact bounds onto `retrieved_on`.
+/// Records created long before the window are still found (a lower bound on
+/// retrieval says nothing about the creation month), while the upper bound
+/// still caps file planning because nothing is retrieved before it exists.
+#[test]
+fn library_timestamp_bounds_can_filter_on_retrieved_on() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    fs::create_dir_all(base.join("comments")).unwrap();
+
+    let lower = epoch(2015, 6, 1, 0, 0, 0);
+    let upper = epoch(2015, 7, 1, 0, 0, 0);
+
+    // Created after the upper bound, so it can never be retrieved inside the
+    // window; opening this corrupt month would fail the scan.
+    let mut corrupt = fs::File::create(base.join("comments").join("RC_2016-01.zst")).unwrap();
+    writeln!(corrupt, "not a zstd stream").unwrap();
+
+    let created = epoch(2006, 1, 2, 0, 0, 0);
+    write_zst_lines(
+        &base.join("comments").join("RC_2006-01.zst"),
+        &[
+            json!({
+                "id": "retrieved_early", "author": "alice", "subreddit": "programming",
+                "created_utc": created, "retrieved_on": lower - 1, "edited": false
+            })
+            .to_string(),
+            json!({
+                "id": "retrieved_inside", "author": "alice", "subreddit": "programming",
+                "created_utc": created, "retrieved_on": lower, "edited": lower + 10
+            })
+            .to_string(),
+            json!({
+                "id": "retrieved_string", "author": "bob", "subreddit": "programming",
+                "created_utc": created, "retrieved_on": (upper - 1).to_string()
+            })
+            .to_string(),
+            json!({
+                "id": "never_retrieved", "author": "bob", "subreddit": "programming",
+                "created_utc": created
+            })
+            .to_string(),
+            json!({
+                "id": "retrieved_late", "author": "carol", "subreddit": "programming",
+                "created_utc": created, "retrieved_on": upper
+            })
+            .to_string(),
+        ],
+    );
+
+    let out = base.join("retrieved.jsonl");
+    RedditETL::new()
+        .base_dir(base)
+        .work_dir(base.join("work"))
+        .sources(Sources::Comments)
+        .progress(false)
+        .scan()
+        .time_field(TimeField::RetrievedOn)
+        .timestamp_bounds(Some(lower), Some(upper))
+        .extract_to_jsonl(&out)
+        .unwrap();
+
+    let mut ids: Vec<String> = read_jsonl_values(&out)
+        .into_iter()
+        .map(|value| value["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["retrieved_inside", "retrieved_string"]);
+}
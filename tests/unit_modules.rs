@@ -405,6 +405,7 @@ fn assert_dedupe_groups_by_key(n_keys: usize, dupes_per_key: usize) {
         read_buf_bytes: 8 * 1024,
         write_buf_bytes: 8 * 1024,
         inflight_bytes: 0,
+        run_format: retl::IntermediateFormat::Text,
     };
     let runs_dir = dir.path().join("runs");
     let key = KeyExtractor::author_lowercase_fast();
@@ -487,6 +488,7 @@ fn dedupe_single_run_still_applies_merge_callback() {
         read_buf_bytes: 8 * 1024,
         write_buf_bytes: 8 * 1024,
         inflight_bytes: 0, // disable cap; the test wants a single big run
+        run_format: retl::IntermediateFormat::Text,
     };
     let runs_dir = dir.path().join("runs");
     let key = KeyExtractor::author_lowercase_fast();
@@ -504,6 +506,57 @@ fn dedupe_single_run_still_applies_merge_callback() {
     assert_eq!(merged, vec!["u00", "u01", "u02", "u03", "u04"]);
 }
 
+/// Binary runs carry the key in a frame instead of re-extracting it from
+/// JSON, so the merge must still see the same groups in the same order as
+/// text runs over the same input.
+#[test]
+fn dedupe_binary_runs_merge_like_text_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let in_path = dir.path().join("dedupe_in.ndjson");
+    let lines: Vec<String> = (0..60)
+        .map(|i| {
+            let author = format!("User_{:02}", i % 20);
+            serde_json::json!({"author": author, "body": "a\tb\nc", "id": i}).to_string()
+        })
+        .collect();
+    write_ndjson(&in_path, &lines);
+
+    let key = KeyExtractor::author_lowercase_fast();
+    let merge = |format: retl::IntermediateFormat, tag: &str| {
+        let cfg = DedupeCfg {
+            mem: retl::AdaptiveMemCfg { soft_low_frac: 0.999, high_frac: 1.0, adapt_cooldown_ms: 1 },
+            min_buf_mb: 0,
+            max_buf_mb: 0,
+            read_buf_bytes: 8 * 1024,
+            write_buf_bytes: 8 * 1024,
+            inflight_bytes: 0,
+            run_format: format,
+        };
+        let runs_dir = dir.path().join(format!("runs_{tag}"));
+        let runs = build_runs_sorted(&in_path, &runs_dir, &key, &cfg).unwrap();
+        assert!(runs.len() >= 2, "expected multiple runs, got {}", runs.len());
+        let out = dir.path().join(format!("merged_{tag}.ndjson"));
+        merge_runs_sorted(&runs, &out, &key, &cfg, |k, group, w| {
+            let mut ids: Vec<i64> = group
+                .iter()
+                .map(|l| serde_json::from_str::<Value>(l).unwrap()["id"].as_i64().unwrap())
+                .collect();
+            ids.sort_unstable();
+            writeln!(w, "{k}\t{ids:?}")?;
+            Ok(())
+        })
+        .unwrap();
+        (runs, common::read_lines(&out))
+    };
+
+    let (text_runs, text_out) = merge(retl::IntermediateFormat::Text, "text");
+    let (bin_runs, bin_out) = merge(retl::IntermediateFormat::Binary, "bin");
+    assert!(text_runs.iter().all(|p| p.extension().unwrap() == "ndjson"));
+    assert!(bin_runs.iter().all(|p| p.extension().unwrap() == "bin"));
+    assert_eq!(text_out.len(), 20);
+    assert_eq!(bin_out, text_out);
+}
+
 /// Regression: before the atomic-write fix, `merge_runs_sorted` staged its
 /// temp via `output.with_extension("ndjson.inprogress")`, which strips the
 /// final extension. Two concurrent merges with destinations `out/x.txt` and
@@ -553,6 +606,7 @@ fn merge_runs_sorted_concurrent_writes_share_dir_with_distinct_stems() {
         read_buf_bytes: 8 * 1024,
         write_buf_bytes: 8 * 1024,
         inflight_bytes: 0,
+        run_format: retl::IntermediateFormat::Text,
     };
 
     // Force both merges to be active inside the per-group callback at the
@@ -652,6 +706,7 @@ fn merge_runs_sorted_failure_removes_run_scratch() {
         read_buf_bytes: 8 * 1024,
         write_buf_bytes: 8 * 1024,
         inflight_bytes: 0,
+        run_format: retl::IntermediateFormat::Text,
     };
     let runs_dir = dir.path().join("runs");
     let key = KeyExtractor::author_lowercase_fast();
@@ -712,6 +767,35 @@ fn kv_shard_sum_reduces_per_key_totals() {
     assert_kv_shard_sum(60, 5, 4);
 }
 
+#[test]
+fn kv_shard_binary_format_reduces_like_text() {
+    let reduce = |format: retl::IntermediateFormat| {
+        let dir = tempfile::tempdir().unwrap();
+        let sum = ShardedKVWriter::create(dir.path(), "sum", 3)
+            .unwrap()
+            .with_intermediate_format(format);
+        let arg = ShardedKVWriter::create(dir.path(), "arg", 3)
+            .unwrap()
+            .with_intermediate_format(format);
+        for i in 0..40i64 {
+            let k = format!("k_{:02}", i % 10);
+            sum.write_kv(&k, i).unwrap();
+            arg.write_kv_payload(&k, i % 7, &format!("p{i}")).unwrap();
+        }
+        let mut rows = Vec::new();
+        let sums = sum.reduce_sum("sum").unwrap();
+        for p in sums.into_iter().chain(arg.reduce_argmin("arg").unwrap()) {
+            rows.extend(common::read_lines(&p));
+        }
+        rows.sort();
+        rows
+    };
+
+    let text = reduce(retl::IntermediateFormat::Text);
+    assert_eq!(text.len(), 20);
+    assert_eq!(reduce(retl::IntermediateFormat::Binary), text);
+}
+
 #[test]
 fn kv_shard_min_picks_smallest_value_per_key() {
     let dir = tempfile::tempdir().unwrap();
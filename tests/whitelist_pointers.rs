@@ -0,0 +1,60 @@
+mod common;
+
+use common::{read_jsonl_values, write_zst_lines};
+use retl::{RedditETL, Sources};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+fn make_corpus_with_media() -> PathBuf {
+    let base = tempfile::tempdir().unwrap().keep();
+    std::fs::create_dir_all(base.join("comments")).unwrap();
+    let record = json!({
+        "id": "s1", "author": "bob", "subreddit": "videos", "created_utc": 1136073600,
+        "title": "A video", "selftext": "",
+        "media": { "oembed": { "title": "Cat video", "author_name": "catfan" } },
+    });
+    write_zst_lines(
+        &base.join("submissions").join("RS_2006-01.zst"),
+        &[record.to_string()],
+    );
+    base
+}
+
+fn extract(base: &Path, out: &Path, source_order: bool) -> serde_json::Value {
+    RedditETL::new()
+        .base_dir(base)
+        .sources(Sources::Submissions)
+        .progress(false)
+        .whitelist_fields([
+            "id",
+            "/media/oembed/title",
+            "/media/oembed/author_name as oembed_author",
+        ])
+        .strict_whitelist(true)
+        .whitelist_source_order(source_order)
+        .scan()
+        .extract_to_jsonl(out)
+        .unwrap();
+    let mut values = read_jsonl_values(out);
+    assert_eq!(values.len(), 1);
+    values.remove(0)
+}
+
+#[test]
+fn json_pointer_whitelist_entries_extract_nested_fields() {
+    let base = make_corpus_with_media();
+
+    for source_order in [false, true] {
+        let out = base.join(format!("pointers_{source_order}.jsonl"));
+        let record = extract(&base, &out, source_order);
+        assert_eq!(
+            record,
+            json!({
+                "id": "s1",
+                "media.oembed.title": "Cat video",
+                "oembed_author": "catfan",
+            }),
+            "source_order={source_order}"
+        );
+    }
+}
@@ -1,10 +1,13 @@
 //! Direct tests for the publicly-exported helpers `with_thread_pool` and the
 //! memory introspection in `mem` (`available_memory_fraction` /
-//! `is_low_memory`). The pipeline uses these but never asserts their basic
+//! `is_low_memory` / `available_memory_bytes` / `total_memory_bytes`). The pipeline uses these but never asserts their basic
 //! invariants directly.
 
 use rayon::prelude::*;
-use retl::{available_memory_fraction, is_low_memory, with_thread_pool};
+use retl::{
+    available_memory_bytes, available_memory_fraction, is_low_memory, sysinfo_ok,
+    total_memory_bytes, with_thread_pool,
+};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[test]
@@ -59,6 +62,22 @@ fn available_memory_fraction_is_in_unit_interval() {
     );
 }
 
+#[test]
+fn memory_bytes_are_nonzero_and_available_fits_in_total() {
+    let total = total_memory_bytes();
+    let available = available_memory_bytes();
+    if !sysinfo_ok() {
+        // Sandboxes without a usable sysinfo report zero bytes.
+        return;
+    }
+    assert!(total > 0, "total_memory_bytes must be non-zero");
+    assert!(available > 0, "available_memory_bytes must be non-zero");
+    assert!(
+        available <= total,
+        "available ({available}) must not exceed total ({total})"
+    );
+}
+
 #[test]
 fn is_low_memory_is_false_at_threshold_zero() {
     // With threshold 0.0 nothing can be "below" it (since fraction >= 0.0